@@ -2,28 +2,89 @@ use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::{backend::Backend, Frame, Terminal};
 use std::cell::Cell;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::sync::mpsc;
-use std::time::Duration;
-
-use crate::config::Config;
+use std::time::{Duration, Instant};
+
+use crate::archive::{self, ArchiveEntry};
+use crate::branch_suggest;
+use crate::command_log;
+use crate::config::{Config, Task};
+use crate::disk_usage;
+use crate::events::EventSink;
+use crate::fs_watch::WorktreeWatcher;
+use crate::fuzzy;
 use crate::git;
+use crate::history;
+use crate::install;
+use crate::journal::{self, JournalEntry, JournalOp};
+use crate::metadata::{self, Metadata, ViewState};
+use crate::nested_repos;
+use crate::preview::{self, FilePreview};
+use crate::repo_registry;
+use crate::ship::{self, ShipStep};
 use crate::tmux;
 use crate::types::{
-    ActiveOp, AppMessage, AppState, ExitAction, GithubPrStatus, OpKind, OpResult, ScriptStatus,
-    SortMode, Worktree, WorktreeCreateRequest, WorktreeDetails, WorktreeStatus,
+    ActiveOp, AppMessage, AppState, BaseComparison, BaseUpdate, BulkRunStatus, ChangedFile,
+    CloneProgress, DiscardMode, ExitAction, GithubPrStatus, HookEnv, LogCommit, OpKind, OpResult,
+    ScriptStatus, SortMode, StatusFilter, Worktree, WorktreeCreateRequest, WorktreeDetails,
+    WorktreeStatus,
 };
+use crate::ui::spinner::Spinner;
 use crate::ui::theme::Theme;
-use crate::ui::{add_modal, config_modal, confirm_modal, help_modal, main_view};
+use crate::ui::{
+    add_modal, amend_modal, archive_modal, base_update_modal, branch_template_modal,
+    changed_files_modal, clone_modal, command_log_modal, config_modal, confirm_modal,
+    describe_modal, discard_modal, duplicate_modal, file_preview, force_push_modal, git_log_modal,
+    help_modal, install_modal, main_view, orphan_branches_modal, prune_modal,
+    remote_branches_modal, rename_modal, repo_switcher_modal, run_command_modal,
+    script_output_modal, task_menu_modal, typed_delete_modal, upstream_modal,
+};
+use crate::worktree_clean::{self, CleanDecision};
+use crate::worktree_exec;
+use crate::worktree_prune::paths_refer_to_same_location;
+use crate::worktree_repair;
+use crate::zellij;
+
+type PrStatusBatch = Vec<(PathBuf, Option<GithubPrStatus>)>;
+
+/// Recent branches the add modal learns naming patterns from.
+const RECENT_BRANCH_LIMIT: usize = 50;
 
 pub struct ScriptResult {
     pub success: bool,
     pub message: String,
 }
 
+/// Updates from a running post-add script: each output line as it is
+/// written, then the result. Runs across several worktrees also report each
+/// one as it finishes.
+pub enum ScriptEvent {
+    Output(String),
+    /// One worktree of a run across several finished
+    WorktreeDone(PathBuf, BulkRunStatus),
+    Done(ScriptResult),
+}
+
+/// Lines kept in the script output panel; older ones are dropped.
+const SCRIPT_OUTPUT_LIMIT: usize = 5000;
+
+/// Commits loaded into the log view
+const GIT_LOG_LIMIT: usize = 500;
+
+/// Minimum time between background list refreshes after leaving the editor
+const AUTO_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Updates from the clone started in the clone modal.
+pub enum CloneEvent {
+    Progress(CloneProgress),
+    /// Project directory and first worktree, or the error message
+    Done(std::result::Result<(PathBuf, PathBuf), String>),
+}
+
 pub struct App {
     pub worktrees: Vec<Worktree>,
     pub selected_index: usize,
@@ -41,22 +102,70 @@ pub struct App {
     pub merge_source_branch: Option<String>,    // Branch to merge from
     pub has_shell_integration: bool,            // Whether OWT_OUTPUT_FILE is set
     pub filter_text: String,                    // Search/filter text
+    pub status_filter: StatusFilter,            // Quick status filter, cycled with Tab
+    pub hide_unmatched: bool,                   // Filters hide non-matching rows, toggled with h
     pub is_filtering: bool,                     // Whether in filter mode
     pub last_key: Option<char>,                 // For gg detection
     pub sort_mode: SortMode,                    // Current sort mode
+    pub sort_reversed: bool,                    // Flip the sort mode's order, toggled with S
     pub verbose: bool,                          // Show detailed git command output
     pub last_command_detail: Option<String>,    // Last git command detail for verbose mode
     pub spinner_tick: usize,                    // Spinner animation tick
     pub theme: Theme,                           // Active color theme
-    pub viewport_height: Cell<u16>,             // Table viewport height (set during render)
-    pub help_scroll_offset: u16,                // Scroll offset for help modal
-    pub script_status: ScriptStatus,            // Background script status
-    pub script_receiver: Option<mpsc::Receiver<ScriptResult>>, // Channel for script completion
-    pub pr_status_receiver: Option<mpsc::Receiver<Vec<(PathBuf, Option<GithubPrStatus>)>>>,
+    pub spinner: Spinner,                       // Loading indicator style from config
+    pub needs_clear: bool, // The screen was reset behind ratatui's back; repaint all of it
+    pub viewport_height: Cell<u16>, // Table viewport height (set during render)
+    pub table_offset: Cell<usize>, // First row scrolled into view (set during render)
+    pub help_scroll_offset: u16, // Scroll offset for help modal
+    pub script_status: ScriptStatus, // Background script status
+    pub script_receiver: Option<mpsc::Receiver<ScriptEvent>>, // Script output and completion
+    pub script_output: Vec<String>, // Output of the last script run from the TUI
+    pub bulk_run: HashMap<PathBuf, BulkRunStatus>, // Per-worktree state of the last run across several
+    pub clone_receiver: Option<mpsc::Receiver<CloneEvent>>, // Clone started from the clone modal
+    pub clone_progress: Option<CloneProgress>,
+    pub issue_lookup_receiver: Option<mpsc::Receiver<(String, Result<String, String>)>>, // Input looked up, and the branch name built from its issue title
+    pub pr_status_receiver: Option<mpsc::Receiver<PrStatusBatch>>,
     pub active_op: Option<(OpKind, mpsc::Receiver<OpResult>)>,
     pub active_op_info: Option<ActiveOp>,
+    pub op_cancel: Option<git::CancelToken>, // Set while a fetch, pull, push, or merge can be canceled
     pub selected_details: Option<WorktreeDetails>,
     pub add_base_branch: String,
+    pub base_comparisons: HashMap<String, BaseComparison>, // Prefetched when the add modal opens
+    pub base_comparison_receiver: Option<mpsc::Receiver<(String, BaseComparison)>>,
+    pub base_update_receiver: Option<mpsc::Receiver<Vec<BaseUpdate>>>, // Sent before the UpdateBases OpResult
+    pub branch_suggestions: Vec<String>, // Learned from recent branch names when the add modal opens
+    pub add_path_override: Option<String>, // Worktree directory typed in the add modal (Ctrl+L)
+    pub add_editing_path: bool,
+    pub delete_nested_repos: Vec<String>, // Nested git repos in the delete targets, found when the modal opens
+    pub file_preview: Option<FilePreview>, // Loaded when a changed file is opened
+    pub file_preview_receiver: Option<mpsc::Receiver<(PathBuf, Result<FilePreview, String>)>>, // File being loaded, and the result
+    pub commit_detail: Vec<String>, // `git show --stat` of the commit opened in the log view
+    pub metadata: Metadata,         // Pins, notes, and flags from .owt/
+    pub usage_scores: HashMap<PathBuf, u64>, // Frecency per worktree path from .owt/history
+    pub switcher_repositories: Vec<PathBuf>, // Projects listed when the w switcher opened
+    pub branch_remotes: HashMap<String, String>, // branch.<name>.remote, for remote badges and grouping
+    pub branch_upstreams: HashMap<String, String>, // Configured upstream per branch, e.g. origin/main
+    pub remote_branches: Vec<String>, // Listed when the remote branch browser opens or fetches
+    pub remote_fetch_receiver: Option<mpsc::Receiver<Result<Vec<String>, String>>>,
+    pub fs_watcher: Option<WorktreeWatcher>, // Running while `live_status` is on
+    pub fs_watch_paths: Vec<PathBuf>,        // Worktrees the watcher was last started for
+    pub auto_fetch_receiver: Option<mpsc::Receiver<Result<(), String>>>,
+    pub last_auto_fetch: Option<Instant>,
+    pub show_upstream: bool, // Upstream column, toggled with b
+    pub branch_heads: HashMap<String, String>, // Short tip hash and subject per branch
+    pub show_head_commit: bool, // HEAD column, toggled with i
+    pub default_remote: Option<String>,
+    pub show_disk_usage: bool,             // Size column, toggled with z
+    pub disk_usage: HashMap<PathBuf, u64>, // Cached worktree sizes, cleared on refresh
+    pub disk_usage_receiver: Option<mpsc::Receiver<(PathBuf, u64)>>,
+    pub merged_branches: HashSet<String>, // Branches merged into the default branch
+    pub merged_receiver: Option<mpsc::Receiver<HashSet<String>>>,
+    pub refresh_receiver: Option<mpsc::Receiver<Vec<Worktree>>>, // Background full refresh after the editor returns
+    pub last_auto_refresh: Option<Instant>,
+    pub ship_steps: Vec<ShipStep>, // Plan of the running ship action; local steps run when it succeeds
+    pub ship_progress_receiver: Option<mpsc::Receiver<String>>,
+    pub ascii_badges: bool,        // Render badges without emoji
+    pub events: Option<EventSink>, // NDJSON state stream (--events)
 }
 
 impl App {
@@ -69,7 +178,31 @@ impl App {
     ) -> Result<Self> {
         let worktrees = git::list_worktrees(&bare_repo_path)?;
         // Load config with project-level override support
-        let config = Config::load_with_project(Some(&project_root_path)).unwrap_or_default();
+        let (config, config_error) = match Config::load_with_project(Some(&project_root_path)) {
+            Ok(config) => (config, None),
+            Err(error) => (Config::default(), Some(error)),
+        };
+        let metadata = Metadata::load(&project_root_path).unwrap_or_default();
+        let usage_scores = history::scores(&project_root_path);
+        let branch_remotes = git::branch_remotes(&bare_repo_path);
+        let branch_upstreams = git::branch_upstreams(&bare_repo_path);
+        let branch_heads = git::branch_heads(&bare_repo_path);
+        let default_remote = git::default_remote(&bare_repo_path);
+        let show_disk_usage = config.show_disk_usage.unwrap_or(false);
+        let show_upstream = config.show_upstream.unwrap_or(false);
+        let show_head_commit = config.show_head_commit.unwrap_or(false);
+        let hide_unmatched = config.hide_unmatched.unwrap_or(false);
+        let pending_operations = journal::pending(&project_root_path)
+            .map(|entries| entries.len())
+            .unwrap_or(0);
+        let theme = crate::ui::theme::resolve_theme(&config);
+        let spinner = Spinner::from_config(&config);
+        command_log::persist_to(
+            config
+                .command_log
+                .unwrap_or(false)
+                .then(|| Config::owt_dir(&project_root_path).join("commands.log")),
+        );
 
         // Determine current worktree from launch path
         let current_worktree_path = launch_path.and_then(|lp| {
@@ -98,8 +231,15 @@ impl App {
                 worktrees.iter().position(|wt| !wt.is_bare).unwrap_or(0)
             });
 
-        // Show initial message about shell integration if not set up
-        let initial_message = if !has_shell_integration {
+        // Show config errors first, then interrupted operations, then the shell integration tip if not set up
+        let initial_message = if let Some(error) = config_error {
+            Some(AppMessage::error(format!("Config error: {}", error)))
+        } else if pending_operations > 0 {
+            Some(AppMessage::error(format!(
+                "{} operation(s) were interrupted; run 'owt doctor' to finish or undo them",
+                pending_operations
+            )))
+        } else if !has_shell_integration {
             Some(AppMessage::info(
                 "Tip: Run 'owt setup' then reload shell for Enter key to change directory",
             ))
@@ -123,41 +263,227 @@ impl App {
             current_worktree_path,
             merge_source_branch: None,
             has_shell_integration,
-            filter_text: String::new(),
+            filter_text: metadata.view.filter.clone(),
+            status_filter: metadata
+                .view
+                .status_filter
+                .as_deref()
+                .and_then(StatusFilter::from_label)
+                .unwrap_or_default(),
+            hide_unmatched,
             is_filtering: false,
             last_key: None,
-            sort_mode: SortMode::default(),
-            verbose: false,
+            sort_mode: metadata
+                .view
+                .sort
+                .as_deref()
+                .and_then(SortMode::from_label)
+                .unwrap_or_default(),
+            sort_reversed: metadata.view.sort_reversed,
+            verbose: metadata.view.verbose,
             last_command_detail: None,
             spinner_tick: 0,
-            theme: crate::ui::theme::detect_theme(),
+            theme,
+            spinner,
+            needs_clear: false,
             viewport_height: Cell::new(0),
+            table_offset: Cell::new(0),
             help_scroll_offset: 0,
             script_status: ScriptStatus::Idle,
             script_receiver: None,
+            script_output: Vec::new(),
+            bulk_run: HashMap::new(),
+            clone_receiver: None,
+            clone_progress: None,
+            issue_lookup_receiver: None,
             pr_status_receiver: None,
             active_op: None,
             active_op_info: None,
+            op_cancel: None,
             selected_details: None,
             add_base_branch: "main".to_string(),
+            base_comparisons: HashMap::new(),
+            branch_suggestions: Vec::new(),
+            add_path_override: None,
+            add_editing_path: false,
+            delete_nested_repos: Vec::new(),
+            file_preview: None,
+            file_preview_receiver: None,
+            commit_detail: Vec::new(),
+            base_comparison_receiver: None,
+            base_update_receiver: None,
+            metadata,
+            usage_scores,
+            switcher_repositories: Vec::new(),
+            branch_remotes,
+            branch_upstreams,
+            remote_branches: Vec::new(),
+            remote_fetch_receiver: None,
+            fs_watcher: None,
+            fs_watch_paths: Vec::new(),
+            auto_fetch_receiver: None,
+            last_auto_fetch: None,
+            show_upstream,
+            branch_heads,
+            show_head_commit,
+            default_remote,
+            show_disk_usage,
+            disk_usage: HashMap::new(),
+            disk_usage_receiver: None,
+            merged_branches: HashSet::new(),
+            merged_receiver: None,
+            refresh_receiver: None,
+            last_auto_refresh: None,
+            ship_steps: Vec::new(),
+            ship_progress_receiver: None,
+            ascii_badges: metadata::ascii_badges(),
+            events: None,
         };
+        if app.sort_mode != SortMode::default() || app.sort_reversed || !app.filter_text.is_empty()
+        {
+            app.apply_sort();
+        }
+        app.select_first_filtered_worktree();
         app.update_selected_details();
         app.start_pr_status_refresh();
+        app.start_disk_usage_scan();
+        app.start_merged_check();
         Ok(app)
     }
 
+    /// App for a launch outside any repository: only the clone modal, which
+    /// loads the cloned repository once it is ready.
+    pub fn new_clone_prompt(target_dir: PathBuf, has_shell_integration: bool) -> Self {
+        let config = Config::load().unwrap_or_default();
+        let theme = crate::ui::theme::resolve_theme(&config);
+        let spinner = Spinner::from_config(&config);
+        Self {
+            worktrees: Vec::new(),
+            selected_index: 0,
+            selected_worktree_paths: HashSet::new(),
+            state: AppState::CloneModal {
+                target_dir: target_dir.clone(),
+            },
+            message: None,
+            bare_repo_path: target_dir.clone(),
+            project_root_path: target_dir,
+            repo_is_bare: false,
+            input_buffer: String::new(),
+            should_quit: false,
+            config,
+            exit_action: ExitAction::Quit,
+            current_worktree_path: None,
+            merge_source_branch: None,
+            has_shell_integration,
+            filter_text: String::new(),
+            status_filter: StatusFilter::All,
+            hide_unmatched: false,
+            is_filtering: false,
+            last_key: None,
+            sort_mode: SortMode::default(),
+            sort_reversed: false,
+            verbose: false,
+            last_command_detail: None,
+            spinner_tick: 0,
+            theme,
+            spinner,
+            needs_clear: false,
+            viewport_height: Cell::new(0),
+            table_offset: Cell::new(0),
+            help_scroll_offset: 0,
+            script_status: ScriptStatus::Idle,
+            script_receiver: None,
+            script_output: Vec::new(),
+            bulk_run: HashMap::new(),
+            clone_receiver: None,
+            clone_progress: None,
+            issue_lookup_receiver: None,
+            pr_status_receiver: None,
+            active_op: None,
+            active_op_info: None,
+            op_cancel: None,
+            selected_details: None,
+            add_base_branch: "main".to_string(),
+            base_comparisons: HashMap::new(),
+            branch_suggestions: Vec::new(),
+            add_path_override: None,
+            add_editing_path: false,
+            delete_nested_repos: Vec::new(),
+            file_preview: None,
+            file_preview_receiver: None,
+            commit_detail: Vec::new(),
+            base_comparison_receiver: None,
+            base_update_receiver: None,
+            metadata: Metadata::default(),
+            usage_scores: HashMap::new(),
+            switcher_repositories: Vec::new(),
+            branch_remotes: HashMap::new(),
+            branch_upstreams: HashMap::new(),
+            remote_branches: Vec::new(),
+            remote_fetch_receiver: None,
+            fs_watcher: None,
+            fs_watch_paths: Vec::new(),
+            auto_fetch_receiver: None,
+            last_auto_fetch: None,
+            show_upstream: false,
+            branch_heads: HashMap::new(),
+            show_head_commit: false,
+            default_remote: None,
+            show_disk_usage: false,
+            disk_usage: HashMap::new(),
+            disk_usage_receiver: None,
+            merged_branches: HashSet::new(),
+            merged_receiver: None,
+            refresh_receiver: None,
+            last_auto_refresh: None,
+            ship_steps: Vec::new(),
+            ship_progress_receiver: None,
+            ascii_badges: metadata::ascii_badges(),
+            events: None,
+        }
+    }
+
     pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
         while !self.should_quit {
+            self.emit_state_events();
+            if std::mem::take(&mut self.needs_clear) {
+                terminal.clear()?;
+            }
             terminal.draw(|frame| self.draw(frame))?;
             self.poll_script_status();
+            self.poll_clone();
+            self.poll_issue_lookup();
+            self.poll_file_preview();
             self.poll_pr_status();
+            self.poll_disk_usage();
+            self.poll_merged_check();
+            self.poll_auto_refresh();
+            self.poll_auto_fetch();
+            self.poll_fs_watch();
+            self.poll_background_refresh();
+            self.poll_base_comparisons();
+            self.poll_remote_fetch();
             self.poll_background_op();
 
             self.handle_events(terminal)?;
         }
+        self.save_view_state();
+        if let Some(events) = self.events.as_mut() {
+            events.emit("exit", serde_json::json!({}));
+        }
         Ok(())
     }
 
+    /// Report mode, selection, and operation start changes to the event stream.
+    fn emit_state_events(&mut self) {
+        let Some(events) = self.events.as_mut() else {
+            return;
+        };
+        events.observe_mode(self.state.label());
+        events.observe_selection(self.worktrees.get(self.selected_index));
+        events.observe_op(self.active_op_info.as_ref());
+    }
+
     fn start_pr_status_refresh(&mut self) {
         let bare_repo_path = self.bare_repo_path.clone();
         let worktrees: Vec<(PathBuf, String)> = self
@@ -186,6 +512,285 @@ impl App {
         self.pr_status_receiver = Some(rx);
     }
 
+    /// Measure worktrees whose size is not cached yet, one result per worktree
+    /// as it finishes. Does nothing while the Size column is hidden.
+    fn start_disk_usage_scan(&mut self) {
+        if !self.show_disk_usage || self.disk_usage_receiver.is_some() {
+            return;
+        }
+        let paths: Vec<PathBuf> = self
+            .worktrees
+            .iter()
+            .filter(|wt| !wt.is_bare && !self.disk_usage.contains_key(&wt.path))
+            .map(|wt| wt.path.clone())
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for path in paths {
+                let size = disk_usage::dir_size(&path);
+                // The receiver is dropped when the list is refreshed mid-scan
+                if tx.send((path, size)).is_err() {
+                    break;
+                }
+            }
+        });
+        self.disk_usage_receiver = Some(rx);
+    }
+
+    /// Find the branches merged into the default branch off the UI thread;
+    /// a newer check replaces one still running.
+    fn start_merged_check(&mut self) {
+        let repo_path = self.bare_repo_path.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            if let Ok(merged) = git::merged_into_default(&repo_path) {
+                let _ = tx.send(merged);
+            }
+        });
+        self.merged_receiver = Some(rx);
+    }
+
+    fn poll_merged_check(&mut self) {
+        let Some(rx) = self.merged_receiver.as_ref() else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(merged) => {
+                self.merged_branches = merged;
+                self.merged_receiver = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.merged_receiver = None,
+        }
+    }
+
+    /// What a forced delete of the targets would throw away: uncommitted
+    /// changes, and with the branch option, branches not merged into the
+    /// default branch (`git branch -D`). Empty unless `force` is on.
+    pub(crate) fn forced_delete_losses(&self, delete_branch: bool, force: bool) -> Vec<String> {
+        if !force {
+            return Vec::new();
+        }
+        let mut losses = Vec::new();
+        for worktree in self.action_worktrees() {
+            if worktree.status != WorktreeStatus::Clean {
+                losses.push(format!("{}: uncommitted changes", worktree.display_name()));
+            }
+            if let Some(branch) = worktree.branch.as_deref() {
+                if delete_branch && !self.is_merged(&worktree) {
+                    losses.push(format!("{}: unmerged branch", branch));
+                }
+            }
+        }
+        losses
+    }
+
+    /// Deletes only once the typed text matches `expected` exactly; `Esc`
+    /// goes back to the delete options
+    fn handle_confirm_delete_typed_input(
+        &mut self,
+        code: KeyCode,
+        [delete_branch, force, delete_remote, keep_nested]: [bool; 4],
+        expected: String,
+        at_risk: Vec<String>,
+    ) {
+        match code {
+            KeyCode::Esc => {
+                self.input_buffer.clear();
+                self.state = AppState::ConfirmDelete {
+                    delete_branch,
+                    force,
+                    delete_remote,
+                    keep_nested,
+                };
+            }
+            KeyCode::Enter => {
+                if self.input_buffer != expected {
+                    self.message = Some(AppMessage::error(format!(
+                        "Type {} to confirm, or Esc to go back",
+                        expected
+                    )));
+                    return;
+                }
+                self.input_buffer.clear();
+                self.delete_selected_worktree(delete_branch, force, delete_remote, keep_nested);
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
+        if matches!(code, KeyCode::Backspace | KeyCode::Char(_)) {
+            self.state = AppState::ConfirmDeleteTyped {
+                delete_branch,
+                force,
+                delete_remote,
+                keep_nested,
+                expected,
+                at_risk,
+            };
+        }
+    }
+
+    /// Whether the worktree's branch is already in the default branch
+    pub(crate) fn is_merged(&self, wt: &Worktree) -> bool {
+        !wt.is_bare
+            && wt
+                .branch
+                .as_ref()
+                .is_some_and(|branch| self.merged_branches.contains(branch))
+    }
+
+    fn poll_disk_usage(&mut self) {
+        while let Some(ref rx) = self.disk_usage_receiver {
+            match rx.try_recv() {
+                Ok((path, size)) => {
+                    self.disk_usage.insert(path, size);
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.disk_usage_receiver = None;
+                }
+            }
+        }
+    }
+
+    fn toggle_disk_usage(&mut self) {
+        self.show_disk_usage = !self.show_disk_usage;
+        if self.show_disk_usage {
+            self.start_disk_usage_scan();
+            self.message = Some(AppMessage::info("Size column shown"));
+        } else {
+            self.message = Some(AppMessage::info("Size column hidden"));
+        }
+    }
+
+    /// Pin or unpin the selected worktree. Pins are saved in `.owt/state.toml`
+    /// and keep the worktree above unpinned ones in every sort.
+    fn toggle_pin(&mut self) {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            self.message = Some(AppMessage::error("No worktree selected"));
+            return;
+        };
+        if wt.is_bare {
+            self.message = Some(AppMessage::error("Cannot pin bare repository"));
+            return;
+        }
+        let name = wt.display_name();
+        let pin = !self.metadata.pinned.contains(&name);
+        match Metadata::update(&self.project_root_path, |metadata| {
+            if pin {
+                metadata.pinned.insert(name.clone());
+            } else {
+                metadata.pinned.remove(&name);
+            }
+        }) {
+            Ok(metadata) => {
+                self.metadata = metadata;
+                self.apply_sort();
+                self.message = Some(AppMessage::info(format!(
+                    "{} {}",
+                    if pin { "Pinned" } else { "Unpinned" },
+                    name
+                )));
+            }
+            Err(e) => self.message = Some(AppMessage::error(format!("Failed to save pin: {}", e))),
+        }
+    }
+
+    fn toggle_upstream(&mut self) {
+        self.show_upstream = !self.show_upstream;
+        self.message = Some(AppMessage::info(if self.show_upstream {
+            "Upstream column shown"
+        } else {
+            "Upstream column hidden"
+        }));
+    }
+
+    fn toggle_head_commit(&mut self) {
+        self.show_head_commit = !self.show_head_commit;
+        self.message = Some(AppMessage::info(if self.show_head_commit {
+            "HEAD column shown"
+        } else {
+            "HEAD column hidden"
+        }));
+    }
+
+    /// Short tip hash and subject of the worktree's branch, or `-`
+    pub(crate) fn head_commit_display(&self, wt: &Worktree) -> String {
+        wt.branch
+            .as_ref()
+            .and_then(|branch| self.branch_heads.get(branch))
+            .cloned()
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Upstream the ahead/behind counts are relative to, or `-`
+    pub(crate) fn upstream_display(&self, wt: &Worktree) -> String {
+        wt.branch
+            .as_ref()
+            .and_then(|branch| self.branch_upstreams.get(branch))
+            .cloned()
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Fetch and compare every base the add modal may use, so the comparison
+    /// is ready by the time a branch type is picked.
+    fn start_base_comparison_prefetch(&mut self) {
+        if self.base_comparison_receiver.is_some() {
+            return;
+        }
+
+        let mut bases = vec![self.add_base_branch.clone()];
+        for base in self
+            .config
+            .branch_types
+            .iter()
+            .filter_map(|branch_type| branch_type.base.clone())
+        {
+            if !bases.contains(&base) {
+                bases.push(base);
+            }
+        }
+
+        let bare_repo_path = self.bare_repo_path.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for base in bases {
+                let comparison = git::compare_base_branch(&bare_repo_path, &base);
+                if tx.send((base, comparison)).is_err() {
+                    return;
+                }
+            }
+        });
+        self.base_comparison_receiver = Some(rx);
+    }
+
+    fn poll_base_comparisons(&mut self) {
+        let Some(rx) = self.base_comparison_receiver.as_ref() else {
+            return;
+        };
+        loop {
+            match rx.try_recv() {
+                Ok((base, comparison)) => {
+                    self.base_comparisons.insert(base, comparison);
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.base_comparison_receiver = None;
+                    return;
+                }
+            }
+        }
+    }
+
     fn poll_pr_status(&mut self) {
         let result = if let Some(rx) = self.pr_status_receiver.as_ref() {
             match rx.try_recv() {
@@ -214,13 +819,27 @@ impl App {
     }
 
     fn poll_script_status(&mut self) {
-        if let Some(ref rx) = self.script_receiver {
+        while let Some(ref rx) = self.script_receiver {
             match rx.try_recv() {
-                Ok(result) => {
+                Ok(ScriptEvent::Output(line)) => {
+                    self.script_output.push(line);
+                    if self.script_output.len() > SCRIPT_OUTPUT_LIMIT {
+                        let excess = self.script_output.len() - SCRIPT_OUTPUT_LIMIT;
+                        self.script_output.drain(..excess);
+                    }
+                }
+                Ok(ScriptEvent::WorktreeDone(path, status)) => {
+                    self.bulk_run.insert(path, status);
+                }
+                Ok(ScriptEvent::Done(result)) => {
+                    let label = match &self.script_status {
+                        ScriptStatus::Running { label, .. } => label.clone(),
+                        ScriptStatus::Idle => "Setup script".to_string(),
+                    };
                     let status_msg = if result.success {
-                        format!("Setup script {}", result.message)
+                        format!("{} {}", label, result.message)
                     } else {
-                        format!("Setup script failed: {}", result.message)
+                        format!("{} failed: {}", label, result.message)
                     };
                     self.message = Some(if result.success {
                         AppMessage::info(status_msg)
@@ -233,6 +852,7 @@ impl App {
                 Err(mpsc::TryRecvError::Empty) => {
                     // Still running, tick spinner
                     self.spinner_tick = self.spinner_tick.wrapping_add(1);
+                    break;
                 }
                 Err(mpsc::TryRecvError::Disconnected) => {
                     self.script_status = ScriptStatus::Idle;
@@ -242,7 +862,82 @@ impl App {
         }
     }
 
+    fn poll_clone(&mut self) {
+        let Some(rx) = self.clone_receiver.as_ref() else {
+            return;
+        };
+        let done = loop {
+            match rx.try_recv() {
+                Ok(CloneEvent::Progress(progress)) => self.clone_progress = Some(progress),
+                Ok(CloneEvent::Done(result)) => break result,
+                Err(mpsc::TryRecvError::Empty) => {
+                    self.spinner_tick = self.spinner_tick.wrapping_add(1);
+                    return;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    break Err("clone stopped unexpectedly".to_string())
+                }
+            }
+        };
+        self.clone_receiver = None;
+        self.clone_progress = None;
+
+        let loaded = done.and_then(|(project_dir, worktree_path)| {
+            App::new(
+                project_dir.join(".bare"),
+                project_dir,
+                true,
+                Some(worktree_path.clone()),
+                self.has_shell_integration,
+            )
+            .map(|app| (app, worktree_path))
+            .map_err(|error| error.to_string())
+        });
+        match loaded {
+            Ok((mut app, worktree_path)) => {
+                app.events = self.events.take();
+                app.message = Some(AppMessage::info(format!(
+                    "Cloned into {}",
+                    worktree_path.display()
+                )));
+                *self = app;
+            }
+            Err(error) => {
+                self.message = Some(AppMessage::error(format!("Clone failed: {}", error)));
+            }
+        }
+    }
+
+    /// Clone the URL in the input into `target_dir`, laid out like `owt clone`.
+    fn start_clone(&mut self, target_dir: &Path) {
+        let (url, resolved) = crate::resolve_clone_url(self.input_buffer.trim(), &self.config);
+        let project_dir = target_dir.join(crate::extract_repo_name(&resolved));
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let bare_repo_path = project_dir.join(".bare");
+            let progress_tx = tx.clone();
+            let result = git::clone_bare(&url, &bare_repo_path, None, None, |progress| {
+                let _ = progress_tx.send(CloneEvent::Progress(progress));
+            })
+            .and_then(|_| {
+                let config = Config::load().unwrap_or_default();
+                let (branch, worktree_path) =
+                    crate::first_clone_worktree(&config, &bare_repo_path, &project_dir, None);
+                git::add_worktree(&bare_repo_path, &branch, &worktree_path, None)?;
+                Ok((project_dir, worktree_path))
+            });
+            let _ = tx.send(CloneEvent::Done(result.map_err(|error| error.to_string())));
+        });
+        self.clone_receiver = Some(rx);
+    }
+
     fn poll_background_op(&mut self) {
+        if let Some(rx) = self.ship_progress_receiver.as_ref() {
+            while let Ok(progress) = rx.try_recv() {
+                self.message = Some(AppMessage::info(progress));
+            }
+        }
+
         let result = if let Some((_, rx)) = self.active_op.as_ref() {
             match rx.try_recv() {
                 Ok(result) => Some(Ok(result)),
@@ -257,22 +952,90 @@ impl App {
         };
 
         match result {
-            Some(Ok(result)) => {
-                self.handle_op_result(result);
+            Some(Ok(mut result)) => {
+                let prompted = self
+                    .op_cancel
+                    .take()
+                    .and_then(|cancel| cancel.take_prompted());
                 self.active_op = None;
                 self.active_op_info = None;
                 self.state = AppState::List;
+                if let Some(command) = prompted.filter(|_| !result.success) {
+                    self.rerun_in_terminal(&mut result, &command);
+                }
+                self.handle_op_result(result);
             }
             Some(Err(())) => {
+                if let (Some(events), Some((kind, _))) = (self.events.as_mut(), &self.active_op) {
+                    events.op_finished(kind, false, "Operation failed unexpectedly");
+                }
                 self.message = Some(AppMessage::error("Operation failed unexpectedly"));
                 self.active_op = None;
                 self.active_op_info = None;
+                self.op_cancel = None;
                 self.state = AppState::List;
             }
             Option::None => {}
         }
     }
 
+    /// Run a git command that failed for lack of a password or passphrase
+    /// prompt again with the terminal released, the way `open_editor` hands
+    /// it over, and report that run instead of the failure.
+    fn rerun_in_terminal(&mut self, result: &mut OpResult, command: &git::PromptedCommand) {
+        // The TUI draws on /dev/tty; stdout may be the cd handoff or `owt pick` pipe
+        match fs::File::options().read(true).write(true).open("/dev/tty") {
+            Ok(tty) => self.rerun_on_tty(result, command, tty),
+            Err(e) => {
+                result.message = format!("{} (no terminal to prompt on: {})", result.message, e)
+            }
+        }
+    }
+
+    fn rerun_on_tty(
+        &mut self,
+        result: &mut OpResult,
+        command: &git::PromptedCommand,
+        mut tty: fs::File,
+    ) {
+        use std::io::Write;
+
+        let _ = crossterm::terminal::disable_raw_mode();
+        let _ = crossterm::execute!(tty, crossterm::terminal::LeaveAlternateScreen);
+        let _ = writeln!(
+            tty,
+            "owt: {} needs your input\n$ {}",
+            result.display_name,
+            command.display()
+        );
+
+        let status = command.run_interactive(&tty);
+
+        let _ = crossterm::terminal::enable_raw_mode();
+        let _ = crossterm::execute!(tty, crossterm::terminal::EnterAlternateScreen);
+        self.needs_clear = true;
+
+        match status {
+            Ok(status) if status.success() => {
+                result.success = true;
+                result.message = format!(
+                    "{} completed in the terminal: {}",
+                    result.kind.label(),
+                    result.display_name
+                );
+            }
+            Ok(status) => {
+                result.message = format!(
+                    "{} failed in the terminal: {} ({})",
+                    result.kind.label(),
+                    result.display_name,
+                    status
+                );
+            }
+            Err(e) => result.message = format!("{} failed: {}", result.kind.label(), e),
+        }
+    }
+
     fn handle_op_result(&mut self, result: OpResult) {
         let OpResult {
             kind,
@@ -284,6 +1047,10 @@ impl App {
             ..
         } = result;
 
+        if let Some(events) = self.events.as_mut() {
+            events.op_finished(&kind, success, &message);
+        }
+
         if kind == OpKind::Merge {
             self.merge_source_branch = None;
         }
@@ -302,15 +1069,9 @@ impl App {
                 }
                 OpKind::Add => {
                     self.refresh_worktrees();
-                    if let Some(idx) = self
-                        .worktrees
-                        .iter()
-                        .position(|wt| paths_refer_to_same_location(&wt.path, &worktree_path))
-                    {
-                        self.selected_index = idx;
-                    }
-                    self.update_selected_details();
-                    self.run_post_add_script(&worktree_path);
+                    self.select_worktree_path(&worktree_path);
+                    let hook_env = self.hook_env(&worktree_path);
+                    self.run_post_add_script(&hook_env);
                     if self.config.tmux_worktree_mode {
                         let worktree_name = self
                             .selected_worktree()
@@ -325,11 +1086,39 @@ impl App {
                             }
                         }
                     }
+                    if let Some(command) = install::command_for(&self.config, &worktree_path) {
+                        self.state = AppState::ConfirmInstall {
+                            worktree_path: worktree_path.clone(),
+                            command,
+                        };
+                    }
                 }
                 OpKind::Fetch | OpKind::Pull | OpKind::Push | OpKind::Merge => {
                     self.refresh_worktrees();
                     self.update_selected_details();
                 }
+                OpKind::Rename => {
+                    self.refresh_worktrees();
+                    self.select_worktree_path(&worktree_path);
+                }
+                OpKind::Ship => {
+                    self.refresh_worktrees();
+                    self.select_worktree_path(&worktree_path);
+                    if let Err(failure) = self.finish_ship(&worktree_path) {
+                        self.message = Some(AppMessage::error(failure));
+                        return;
+                    }
+                }
+                OpKind::UpdateBases => {
+                    self.refresh_worktrees();
+                    self.update_selected_details();
+                    let updates = self
+                        .base_update_receiver
+                        .take()
+                        .and_then(|rx| rx.try_recv().ok())
+                        .unwrap_or_default();
+                    self.state = AppState::BaseUpdateSummary { updates };
+                }
             }
 
             if self.verbose {
@@ -338,6 +1127,21 @@ impl App {
             }
             self.message = Some(AppMessage::info(msg));
         } else {
+            if kind == OpKind::Rename {
+                // The local rename may have succeeded before a later step failed
+                self.refresh_worktrees();
+                self.select_worktree_path(&worktree_path);
+            }
+            if kind == OpKind::UpdateBases {
+                self.base_update_receiver = None;
+            }
+            if kind == OpKind::Ship {
+                self.ship_steps.clear();
+                self.ship_progress_receiver = None;
+                // A push may have landed before a later step failed
+                self.refresh_worktrees();
+                self.select_worktree_path(&worktree_path);
+            }
             if kind == OpKind::Delete {
                 for path in affected_paths {
                     self.worktrees.retain(|wt| wt.path != path);
@@ -362,6 +1166,10 @@ impl App {
                 main_view::render(frame, self);
                 add_modal::render(frame, self);
             }
+            AppState::AddTemplateFields { .. } => {
+                main_view::render(frame, self);
+                branch_template_modal::render(frame, self);
+            }
             AppState::ConfirmDelete { .. } => {
                 main_view::render(frame, self);
                 confirm_modal::render(frame, self);
@@ -378,11 +1186,103 @@ impl App {
                 main_view::render(frame, self);
                 crate::ui::merge_modal::render(frame, self);
             }
+            AppState::ConfirmForcePush => {
+                main_view::render(frame, self);
+                force_push_modal::render(frame, self);
+            }
+            AppState::ConfirmPrune { .. } => {
+                main_view::render(frame, self);
+                prune_modal::render(frame, self);
+            }
+            AppState::UpstreamSelect { .. } => {
+                main_view::render(frame, self);
+                upstream_modal::render(frame, self);
+            }
+            AppState::RemoteBranches { .. } => {
+                main_view::render(frame, self);
+                remote_branches_modal::render(frame, self);
+            }
+            AppState::OrphanBranches { .. } => {
+                main_view::render(frame, self);
+                orphan_branches_modal::render(frame, self);
+            }
+            AppState::ArchivedWorktrees { .. } => {
+                main_view::render(frame, self);
+                archive_modal::render(frame, self);
+            }
+            AppState::DuplicateWorktree { .. } => {
+                main_view::render(frame, self);
+                duplicate_modal::render(frame, self);
+            }
+            AppState::ConfirmInstall { .. } => {
+                main_view::render(frame, self);
+                install_modal::render(frame, self);
+            }
+            AppState::RenameModal { .. } => {
+                main_view::render(frame, self);
+                rename_modal::render(frame, self);
+            }
+            AppState::BaseUpdateSummary { .. } => {
+                main_view::render(frame, self);
+                base_update_modal::render(frame, self);
+            }
+            // No repository to list behind it yet
+            AppState::CloneModal { .. } => clone_modal::render(frame, self),
+            AppState::ChangedFiles { .. } => {
+                main_view::render(frame, self);
+                changed_files_modal::render(frame, self);
+            }
+            AppState::FilePreview { .. } => {
+                main_view::render(frame, self);
+                file_preview::render(frame, self);
+            }
+            // Full screen, nothing shows behind it
+            AppState::GitLog { .. } | AppState::CommitDetail { .. } => {
+                git_log_modal::render(frame, self)
+            }
+            AppState::ScriptOutput { .. } => {
+                main_view::render(frame, self);
+                script_output_modal::render(frame, self);
+            }
+            AppState::CommandLog { .. } => {
+                main_view::render(frame, self);
+                command_log_modal::render(frame, self);
+            }
+            AppState::TaskMenu { .. } => {
+                main_view::render(frame, self);
+                task_menu_modal::render(frame, self);
+            }
+            AppState::RunCommand => {
+                main_view::render(frame, self);
+                run_command_modal::render(frame, self);
+            }
+            AppState::RepoSwitcher { .. } => {
+                main_view::render(frame, self);
+                repo_switcher_modal::render(frame, self);
+            }
+            AppState::DescribeModal { .. } | AppState::NoteModal { .. } => {
+                main_view::render(frame, self);
+                describe_modal::render(frame, self);
+            }
+            AppState::AmendModal { .. } => {
+                main_view::render(frame, self);
+                amend_modal::render(frame, self);
+            }
+            AppState::DiscardModal { .. } => {
+                main_view::render(frame, self);
+                discard_modal::render(frame, self);
+            }
+            AppState::ConfirmDeleteTyped { .. } => {
+                main_view::render(frame, self);
+                typed_delete_modal::render(frame, self);
+            }
         }
     }
 
     fn handle_events<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
-        if event::poll(Duration::from_millis(100))? {
+        // Redraw at least as often as the spinner changes frames
+        let timeout = Duration::from_millis(100).min(self.spinner.interval());
+        if event::poll(timeout)? {
             match event::read()? {
                 Event::Key(key) => {
                     if key.kind != KeyEventKind::Press {
@@ -395,11 +1295,23 @@ impl App {
 
                     match self.state.clone() {
                         AppState::List => self.handle_list_input(key.code, key.modifiers),
-                        AppState::AddModal => self.handle_add_modal_input(key.code),
+                        AppState::AddModal => self.handle_add_modal_input(key.code, key.modifiers),
+                        AppState::AddTemplateFields {
+                            branch_type,
+                            values,
+                        } => self.handle_add_template_input(key.code, branch_type, values),
                         AppState::ConfirmDelete {
                             delete_branch,
                             force,
-                        } => self.handle_confirm_delete_input(key.code, delete_branch, force),
+                            delete_remote,
+                            keep_nested,
+                        } => self.handle_confirm_delete_input(
+                            key.code,
+                            delete_branch,
+                            force,
+                            delete_remote,
+                            keep_nested,
+                        ),
                         AppState::ConfigModal {
                             selected_index,
                             editing,
@@ -408,6 +1320,124 @@ impl App {
                         AppState::MergeBranchSelect { branches, selected } => {
                             self.handle_merge_branch_select_input(key.code, branches, selected)
                         }
+                        AppState::ConfirmForcePush => {
+                            self.handle_confirm_force_push_input(key.code)
+                        }
+                        AppState::ConfirmPrune { .. } => self.handle_confirm_prune_input(key.code),
+                        AppState::UpstreamSelect { branches, selected } => {
+                            self.handle_upstream_select_input(key.code, branches, selected)
+                        }
+                        AppState::RemoteBranches { filter, selected } => self
+                            .handle_remote_branches_input(
+                                key.code,
+                                key.modifiers,
+                                filter,
+                                selected,
+                            ),
+                        AppState::OrphanBranches { branches, selected } => {
+                            self.handle_orphan_branches_input(key.code, branches, selected)
+                        }
+                        AppState::ArchivedWorktrees { entries, selected } => {
+                            self.handle_archived_worktrees_input(key.code, entries, selected)
+                        }
+                        AppState::DuplicateWorktree { commit, new_branch } => {
+                            self.handle_duplicate_input(key.code, commit, new_branch)
+                        }
+                        AppState::ConfirmInstall {
+                            worktree_path,
+                            command,
+                        } => self.handle_confirm_install_input(key.code, worktree_path, command),
+                        AppState::RenameModal { propagate, remote } => {
+                            self.handle_rename_modal_input(key.code, propagate, remote)
+                        }
+                        AppState::BaseUpdateSummary { .. } => {
+                            // Any key dismisses the summary
+                            self.state = AppState::List;
+                        }
+                        AppState::CloneModal { target_dir } => {
+                            self.handle_clone_modal_input(key.code, key.modifiers, &target_dir)
+                        }
+                        AppState::ChangedFiles {
+                            worktree_path,
+                            files,
+                            selected,
+                        } => self.handle_changed_files_input(
+                            key.code,
+                            worktree_path,
+                            files,
+                            selected,
+                        ),
+                        AppState::FilePreview {
+                            worktree_path,
+                            files,
+                            selected,
+                            scroll,
+                        } => self.handle_file_preview_input(
+                            key.code,
+                            key.modifiers,
+                            worktree_path,
+                            files,
+                            selected,
+                            scroll,
+                        ),
+                        AppState::ScriptOutput { scroll, follow } => {
+                            self.handle_script_output_input(key.code, scroll, follow)
+                        }
+                        AppState::CommandLog { scroll, follow } => {
+                            self.handle_command_log_input(key.code, scroll, follow)
+                        }
+                        AppState::TaskMenu { selected } => {
+                            self.handle_task_menu_input(key.code, selected)
+                        }
+                        AppState::RunCommand => self.handle_run_command_input(key.code),
+                        AppState::RepoSwitcher { selected } => {
+                            self.handle_repo_switcher_input(key.code, selected)
+                        }
+                        AppState::DescribeModal { branch } => {
+                            self.handle_describe_modal_input(key.code, branch)
+                        }
+                        AppState::NoteModal { name } => {
+                            self.handle_note_modal_input(key.code, name)
+                        }
+                        AppState::AmendModal { worktree_path, .. } => {
+                            self.handle_amend_modal_input(key.code, worktree_path)
+                        }
+                        AppState::DiscardModal {
+                            worktree_path,
+                            name,
+                            ..
+                        } => self.handle_discard_modal_input(key.code, worktree_path, name),
+                        AppState::ConfirmDeleteTyped {
+                            delete_branch,
+                            force,
+                            delete_remote,
+                            keep_nested,
+                            expected,
+                            at_risk,
+                        } => self.handle_confirm_delete_typed_input(
+                            key.code,
+                            [delete_branch, force, delete_remote, keep_nested],
+                            expected,
+                            at_risk,
+                        ),
+                        AppState::GitLog {
+                            worktree_path,
+                            commits,
+                            selected,
+                        } => self.handle_git_log_input(key.code, worktree_path, commits, selected),
+                        AppState::CommitDetail {
+                            worktree_path,
+                            commits,
+                            selected,
+                            scroll,
+                        } => self.handle_commit_detail_input(
+                            key.code,
+                            key.modifiers,
+                            worktree_path,
+                            commits,
+                            selected,
+                            scroll,
+                        ),
                     }
                 }
                 Event::Resize(_, _) => {
@@ -442,6 +1472,10 @@ impl App {
                 self.move_selection_half_page_up();
                 self.last_key = None;
             }
+            KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.repair_worktrees();
+                self.last_key = None;
+            }
             KeyCode::Up | KeyCode::Char('k') => {
                 self.move_selection_up();
                 self.last_key = None;
@@ -482,15 +1516,27 @@ impl App {
                 self.enter_worktree();
                 self.last_key = None;
             }
+            KeyCode::Char(digit @ '1'..='9') => {
+                self.quick_jump(digit as usize - '0' as usize);
+                self.last_key = None;
+            }
             KeyCode::Char('/') => {
                 // Enter filter mode
                 self.is_filtering = true;
                 self.filter_text.clear();
+                self.filter_changed();
                 self.last_key = None;
             }
             KeyCode::Char('a') => {
                 self.state = AppState::AddModal;
                 self.input_buffer.clear();
+                self.add_path_override = None;
+                self.add_editing_path = false;
+                self.start_base_comparison_prefetch();
+                self.branch_suggestions = branch_suggest::candidates(&git::recent_local_branches(
+                    &self.bare_repo_path,
+                    RECENT_BRANCH_LIMIT,
+                ));
                 self.last_key = None;
             }
             KeyCode::Char('d') => {
@@ -500,9 +1546,12 @@ impl App {
                 } else if targets.iter().any(|wt| wt.is_bare) {
                     self.message = Some(AppMessage::error("Cannot delete bare repository"));
                 } else {
+                    self.delete_nested_repos = nested_repo_labels(&targets);
                     self.state = AppState::ConfirmDelete {
                         delete_branch: false,
                         force: false,
+                        delete_remote: false,
+                        keep_nested: false,
                     };
                 }
                 self.last_key = None;
@@ -511,10 +1560,54 @@ impl App {
                 self.toggle_selected_worktree();
                 self.last_key = None;
             }
+            KeyCode::Char('R') => {
+                self.open_rename_modal();
+                self.last_key = None;
+            }
+            KeyCode::Char('D') => {
+                self.open_describe_modal();
+                self.last_key = None;
+            }
+            KeyCode::Char('A') => {
+                self.open_amend_modal();
+                self.last_key = None;
+            }
+            KeyCode::Char('B') => {
+                self.open_upstream_select();
+                self.last_key = None;
+            }
+            KeyCode::Char('O') => {
+                self.open_orphan_branches();
+                self.last_key = None;
+            }
+            KeyCode::Char('Y') => {
+                self.open_duplicate_worktree();
+                self.last_key = None;
+            }
+            KeyCode::Char('K') => {
+                self.open_archived_worktrees();
+                self.last_key = None;
+            }
+            KeyCode::Char('E') => {
+                self.open_remote_branches();
+                self.last_key = None;
+            }
+            KeyCode::Char('X') => {
+                self.open_discard_modal();
+                self.last_key = None;
+            }
+            KeyCode::Char('n') => {
+                self.open_note_modal();
+                self.last_key = None;
+            }
             KeyCode::Char('o') => {
                 self.open_editor();
                 self.last_key = None;
             }
+            KeyCode::Char('l') => {
+                self.open_git_ui();
+                self.last_key = None;
+            }
             KeyCode::Char('t') => {
                 self.open_terminal();
                 self.last_key = None;
@@ -531,6 +1624,10 @@ impl App {
                 self.push_worktree();
                 self.last_key = None;
             }
+            KeyCode::Char('F') => {
+                self.open_force_push_confirm();
+                self.last_key = None;
+            }
             KeyCode::Char('m') => {
                 self.merge_upstream();
                 self.last_key = None;
@@ -539,18 +1636,94 @@ impl App {
                 self.open_merge_branch_select();
                 self.last_key = None;
             }
+            KeyCode::Char('U') => {
+                self.update_base_branches();
+                self.last_key = None;
+            }
+            KeyCode::Char('e') => {
+                self.open_changed_files();
+                self.last_key = None;
+            }
+            KeyCode::Char('V') => {
+                self.open_git_log();
+                self.last_key = None;
+            }
+            KeyCode::Char('Z') => {
+                self.ship_selected();
+                self.last_key = None;
+            }
+            KeyCode::Char('C') => {
+                self.select_merged_worktrees();
+                self.last_key = None;
+            }
+            KeyCode::Char('z') => {
+                self.toggle_disk_usage();
+                self.last_key = None;
+            }
+            KeyCode::Char('*') => {
+                self.toggle_pin();
+                self.last_key = None;
+            }
+            KeyCode::Char('b') => {
+                self.toggle_upstream();
+                self.last_key = None;
+            }
+            KeyCode::Char('i') => {
+                self.toggle_head_commit();
+                self.last_key = None;
+            }
+            KeyCode::Char('H') => {
+                self.run_setup_script_for_selected();
+                self.last_key = None;
+            }
+            KeyCode::Char('L') => {
+                self.open_script_output();
+                self.last_key = None;
+            }
+            KeyCode::Char('@') => {
+                self.open_command_log();
+                self.last_key = None;
+            }
+            KeyCode::Char('T') => {
+                self.open_task_menu();
+                self.last_key = None;
+            }
+            KeyCode::Char('!') => {
+                self.open_run_command();
+                self.last_key = None;
+            }
+            KeyCode::Char('w') => {
+                self.open_repo_switcher();
+                self.last_key = None;
+            }
+            KeyCode::Char('W') => {
+                self.open_in_tmux();
+                self.last_key = None;
+            }
             KeyCode::Char('r') => {
                 self.refresh_worktrees();
                 self.last_key = None;
             }
             KeyCode::Char('x') => {
-                self.prune_worktrees();
+                self.preview_prune();
                 self.last_key = None;
             }
             KeyCode::Char('s') => {
                 self.cycle_sort_mode();
                 self.last_key = None;
             }
+            KeyCode::Char('S') => {
+                self.toggle_sort_direction();
+                self.last_key = None;
+            }
+            KeyCode::Tab => {
+                self.cycle_status_filter();
+                self.last_key = None;
+            }
+            KeyCode::Char('h') => {
+                self.toggle_hide_unmatched();
+                self.last_key = None;
+            }
             KeyCode::Char('c') => {
                 self.state = AppState::ConfigModal {
                     selected_index: 0,
@@ -580,11 +1753,15 @@ impl App {
                 }
                 self.last_key = None;
             }
+            KeyCode::Esc if self.op_cancel.is_some() => {
+                self.cancel_active_op();
+                self.last_key = None;
+            }
             KeyCode::Esc => {
-                // Clear filter if any
-                if !self.filter_text.is_empty() {
-                    self.filter_text.clear();
-                }
+                // Clear the text and status filters if any
+                self.filter_text.clear();
+                self.status_filter = StatusFilter::All;
+                self.filter_changed();
                 self.last_key = None;
             }
             _ => {
@@ -603,6 +1780,7 @@ impl App {
                 // Cancel filter, show all worktrees
                 self.is_filtering = false;
                 self.filter_text.clear();
+                self.filter_changed();
             }
             KeyCode::Enter => {
                 // Exit filter mode and enter the selected worktree
@@ -616,93 +1794,522 @@ impl App {
             }
             KeyCode::Backspace => {
                 self.filter_text.pop();
+                self.filter_changed();
             }
             KeyCode::Char(c) => {
                 self.filter_text.push(c);
-                // Auto-select first matching worktree
-                self.select_first_filtered_worktree();
+                self.filter_changed();
             }
             _ => {}
         }
     }
 
+    /// Re-rank the list for the new filter text and select the best match
+    fn filter_changed(&mut self) {
+        self.apply_sort();
+        self.select_first_filtered_worktree();
+        self.update_selected_details();
+    }
+
+    /// Select the worktree whose branch, name, or path matches `target`.
+    /// Without an exact match, start with `target` as the list filter instead.
+    pub fn preselect(&mut self, target: &str) {
+        let target = target.trim();
+        if target.is_empty() {
+            return;
+        }
+
+        let target_path = Path::new(target);
+        let exact = self.worktrees.iter().position(|wt| {
+            wt.branch.as_deref() == Some(target)
+                || (!wt.is_bare && wt.display_name() == target)
+                || paths_refer_to_same_location(&wt.path, target_path)
+        });
+
+        if let Some(idx) = exact {
+            self.selected_index = idx;
+            // A remembered filter could hide the worktree asked for
+            self.filter_text.clear();
+        } else {
+            self.filter_text = target.to_string();
+            self.filter_changed();
+        }
+        self.update_selected_details();
+    }
+
     fn select_first_filtered_worktree(&mut self) {
-        if self.filter_text.is_empty() {
+        if !self.has_filter() {
             return;
         }
-        let filter_lower = self.filter_text.to_lowercase();
-        if let Some(idx) = self.worktrees.iter().position(|wt| {
-            wt.display_name().to_lowercase().contains(&filter_lower)
-                || wt.branch_display().to_lowercase().contains(&filter_lower)
-        }) {
+        if let Some(idx) = self.worktrees.iter().position(|wt| self.matches_filter(wt)) {
             self.selected_index = idx;
         }
     }
 
-    fn handle_add_modal_input(&mut self, code: KeyCode) {
+    /// Whether the text or status filter is narrowing the list
+    pub(crate) fn has_filter(&self) -> bool {
+        !self.filter_text.is_empty() || self.status_filter != StatusFilter::All
+    }
+
+    /// Whether a row passes both the `/` text filter and the status filter;
+    /// rows that do not are dimmed.
+    pub(crate) fn matches_filter(&self, wt: &Worktree) -> bool {
+        self.filter_score(wt).is_some() && self.status_filter.matches(wt)
+    }
+
+    /// Characters of `text` the `/` filter matched, empty without a filter
+    pub(crate) fn filter_positions(&self, text: &str) -> Vec<usize> {
+        if self.filter_text.is_empty() {
+            return Vec::new();
+        }
+        fuzzy::positions(&self.filter_text, text).unwrap_or_default()
+    }
+
+    /// Fuzzy score of the `/` filter against the worktree name or branch,
+    /// whichever is better; `Some(0)` without a filter.
+    fn filter_score(&self, wt: &Worktree) -> Option<i64> {
+        if self.filter_text.is_empty() {
+            return Some(0);
+        }
+        let name = fuzzy::score(&self.filter_text, &wt.display_name());
+        let branch = fuzzy::score(&self.filter_text, &wt.branch_display());
+        name.max(branch)
+    }
+
+    fn cycle_status_filter(&mut self) {
+        self.status_filter = self.status_filter.next();
+        self.select_first_filtered_worktree();
+        self.update_selected_details();
+        let matching = self
+            .worktrees
+            .iter()
+            .filter(|wt| !wt.is_bare && self.matches_filter(wt))
+            .count();
+        self.message = Some(AppMessage::info(format!(
+            "Show: {} ({} worktrees)",
+            self.status_filter.label(),
+            matching
+        )));
+    }
+
+    fn handle_add_modal_input(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if self.add_editing_path {
+            self.handle_add_path_input(code);
+            return;
+        }
         match code {
+            KeyCode::Char('l') if modifiers.contains(KeyModifiers::CONTROL) => {
+                if self.add_path_override.is_none() {
+                    let default_path = self.worktree_path_for_branch(self.input_buffer.trim());
+                    self.add_path_override = Some(default_path.display().to_string());
+                }
+                self.add_editing_path = true;
+            }
+            KeyCode::Char('t') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.start_issue_lookup();
+            }
+            // Pick an existing local branch instead of typing one
+            KeyCode::Char('o') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input_buffer.clear();
+                self.open_orphan_branches();
+            }
             KeyCode::Esc => {
                 self.state = AppState::List;
                 self.input_buffer.clear();
             }
-            KeyCode::Enter => {
-                if !self.input_buffer.trim().is_empty() {
-                    self.queue_worktree_create_after_exit();
-                }
-            }
-            KeyCode::Tab => {
-                self.cycle_add_base_branch();
+            KeyCode::Enter if !self.input_buffer.trim().is_empty() => {
+                self.queue_worktree_create_after_exit();
             }
+            KeyCode::Tab => match self.add_modal_suggestion() {
+                Some(suffix) => {
+                    let suffix = suffix.to_string();
+                    self.input_buffer.push_str(&suffix);
+                    self.apply_branch_type_base();
+                    self.start_branch_template();
+                }
+                None => self.cycle_add_base_branch(),
+            },
             KeyCode::Backspace => {
                 self.input_buffer.pop();
             }
             KeyCode::Char(c) => {
                 self.input_buffer.push(c);
+                self.apply_branch_type_base();
+                self.start_branch_template();
             }
             _ => {}
         }
     }
 
-    fn handle_confirm_delete_input(&mut self, code: KeyCode, delete_branch: bool, force: bool) {
+    /// Once the input is exactly the prefix of a branch type with a
+    /// `name_template`, ask for the template's variables instead
+    fn start_branch_template(&mut self) {
+        let branch_type = self.config.branch_types.iter().position(|branch_type| {
+            branch_type.prefix == self.input_buffer && !branch_type.template_fields().is_empty()
+        });
+        if let Some(branch_type) = branch_type {
+            self.input_buffer.clear();
+            self.state = AppState::AddTemplateFields {
+                branch_type,
+                values: Vec::new(),
+            };
+        }
+    }
+
+    /// One template variable per `Enter`; after the last one the add modal
+    /// comes back with the built name. `Esc` goes back with just the prefix.
+    fn handle_add_template_input(
+        &mut self,
+        code: KeyCode,
+        branch_type: usize,
+        mut values: Vec<String>,
+    ) {
+        let Some(fields) = self
+            .config
+            .branch_types
+            .get(branch_type)
+            .map(|branch_type| branch_type.template_fields())
+        else {
+            self.state = AppState::AddModal;
+            return;
+        };
         match code {
-            KeyCode::Esc | KeyCode::Char('n') => {
-                self.state = AppState::List;
+            KeyCode::Esc => {
+                self.input_buffer = self.config.branch_types[branch_type].prefix.clone();
+                self.state = AppState::AddModal;
+                return;
             }
-            KeyCode::Char('y') | KeyCode::Enter => {
-                // Require force for dirty worktrees
-                let dirty_targets: Vec<String> = self
-                    .action_worktrees()
-                    .into_iter()
-                    .filter(|wt| wt.status != WorktreeStatus::Clean)
-                    .map(|wt| wt.display_name())
-                    .collect();
-                if !dirty_targets.is_empty() && !force {
-                    let message = if dirty_targets.len() == 1 {
-                        "Worktree has uncommitted changes. Press 'f' to enable force delete."
-                            .to_string()
-                    } else {
-                        format!(
-                            "Worktree has uncommitted changes. Press 'f' to enable force delete: {}",
-                            dirty_targets.join(", ")
-                        )
-                    };
-                    self.message = Some(AppMessage::error(message));
+            KeyCode::Enter => {
+                let value = self.input_buffer.trim().to_string();
+                if value.is_empty() {
+                    self.message = Some(AppMessage::error(format!(
+                        "{} cannot be empty",
+                        fields[values.len()]
+                    )));
+                    return;
+                }
+                values.push(value);
+                self.input_buffer.clear();
+                if values.len() == fields.len() {
+                    self.input_buffer = self.config.branch_types[branch_type].render_name(|name| {
+                        fields
+                            .iter()
+                            .position(|field| field == name)
+                            .map(|index| values[index].clone())
+                    });
+                    self.message = None;
+                    self.state = AppState::AddModal;
                     return;
                 }
-                self.delete_selected_worktree(delete_branch, force);
             }
-            KeyCode::Char('b') => {
-                // Toggle delete branch option
-                self.state = AppState::ConfirmDelete {
-                    delete_branch: !delete_branch,
-                    force,
-                };
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
             }
-            KeyCode::Char('f') => {
-                // Toggle force delete option
-                self.state = AppState::ConfirmDelete {
-                    delete_branch,
-                    force: !force,
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
+        self.state = AppState::AddTemplateFields {
+            branch_type,
+            values,
+        };
+    }
+
+    /// Editing the worktree directory override: Enter keeps it, Esc drops it.
+    fn handle_add_path_input(&mut self, code: KeyCode) {
+        let Some(path) = self.add_path_override.as_mut() else {
+            self.add_editing_path = false;
+            return;
+        };
+        match code {
+            KeyCode::Esc => {
+                self.add_path_override = None;
+                self.add_editing_path = false;
+            }
+            KeyCode::Enter => {
+                if path.trim().is_empty() {
+                    self.add_path_override = None;
+                }
+                self.add_editing_path = false;
+            }
+            KeyCode::Tab => {
+                if let Some(completed) = complete_directory(path) {
+                    *path = completed;
+                }
+            }
+            KeyCode::Backspace => {
+                path.pop();
+            }
+            KeyCode::Char(c) => path.push(c),
+            _ => {}
+        }
+    }
+
+    /// Where the add modal will create the worktree for `branch`.
+    pub fn add_modal_worktree_path(&self, branch: &str) -> PathBuf {
+        match self.add_path_override.as_deref() {
+            Some(path) => {
+                let path = crate::config::expand_home_path(path.trim());
+                if path.is_relative() {
+                    self.project_root_path.join(path)
+                } else {
+                    path
+                }
+            }
+            None => self.worktree_path_for_branch(branch),
+        }
+    }
+
+    /// Switch to a branch type's base once its prefix has been typed.
+    /// Look up the title of the ticket typed in the add modal with
+    /// `issue_title_command`, in the background
+    fn start_issue_lookup(&mut self) {
+        let Some(command) = self.config.issue_title_command.clone() else {
+            self.message = Some(AppMessage::error(
+                "Set issue_title_command in config.toml to look up issue titles",
+            ));
+            return;
+        };
+        if self.issue_lookup_receiver.is_some() {
+            return;
+        }
+        let input = self.input_buffer.trim().to_string();
+        let Some((prefix, ticket)) = branch_suggest::issue_ticket(&input) else {
+            self.message = Some(AppMessage::error(
+                "Type a ticket ID first, e.g. feature/ABC-123 or #42",
+            ));
+            return;
+        };
+        let (prefix, ticket) = (prefix.to_string(), ticket.to_string());
+        let dir = self.project_root_path.clone();
+        self.message = Some(AppMessage::info(format!("Looking up {}...", ticket)));
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = branch_suggest::fetch_issue_title(&command, &ticket, &dir)
+                .map(|title| branch_suggest::issue_branch_name(&prefix, &ticket, &title))
+                .map_err(|error| error.to_string());
+            let _ = tx.send((input, result));
+        });
+        self.issue_lookup_receiver = Some(rx);
+    }
+
+    /// Fill in the branch name once the lookup finishes, unless the input
+    /// was edited or the modal closed in the meantime
+    fn poll_issue_lookup(&mut self) {
+        let Some(rx) = self.issue_lookup_receiver.as_ref() else {
+            return;
+        };
+        let (input, result) = match rx.try_recv() {
+            Ok(done) => done,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.issue_lookup_receiver = None;
+                return;
+            }
+        };
+        self.issue_lookup_receiver = None;
+        let still_wanted =
+            matches!(self.state, AppState::AddModal) && self.input_buffer.trim() == input;
+        match result {
+            Ok(branch) if still_wanted => {
+                self.input_buffer = branch;
+                self.message = None;
+            }
+            Ok(_) => {}
+            Err(error) => {
+                self.message = Some(AppMessage::error(format!("Issue lookup failed: {}", error)));
+            }
+        }
+    }
+
+    fn apply_branch_type_base(&mut self) {
+        let base = self
+            .config
+            .branch_types
+            .iter()
+            .find(|branch_type| branch_type.prefix == self.input_buffer)
+            .and_then(|branch_type| branch_type.base.clone());
+        if let Some(base) = base {
+            self.add_base_branch = base;
+        }
+    }
+
+    fn handle_clone_modal_input(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        target_dir: &Path,
+    ) {
+        // Keep the prompt open until the clone finishes so it is not left half done
+        if self.clone_receiver.is_some() {
+            return;
+        }
+        match code {
+            KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true
+            }
+            KeyCode::Enter if !self.input_buffer.trim().is_empty() => {
+                self.start_clone(target_dir);
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_rename_modal_input(
+        &mut self,
+        code: KeyCode,
+        propagate: bool,
+        remote: Option<String>,
+    ) {
+        match code {
+            KeyCode::Esc => {
+                self.state = AppState::List;
+                self.input_buffer.clear();
+            }
+            KeyCode::Enter if !self.input_buffer.trim().is_empty() => {
+                self.rename_selected_branch(propagate, remote);
+            }
+            KeyCode::Tab if remote.is_some() => {
+                self.state = AppState::RenameModal {
+                    propagate: !propagate,
+                    remote,
+                };
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_confirm_force_push_input(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') => {
+                self.state = AppState::List;
+            }
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.force_push_worktree();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_confirm_prune_input(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') => {
+                self.state = AppState::List;
+            }
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.state = AppState::List;
+                self.prune_worktrees();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_confirm_delete_input(
+        &mut self,
+        code: KeyCode,
+        delete_branch: bool,
+        force: bool,
+        delete_remote: bool,
+        keep_nested: bool,
+    ) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') => {
+                self.state = AppState::List;
+            }
+            KeyCode::Char('y') | KeyCode::Enter => {
+                // Require force for dirty worktrees
+                let dirty_targets: Vec<String> = self
+                    .action_worktrees()
+                    .into_iter()
+                    .filter(|wt| wt.status != WorktreeStatus::Clean)
+                    .map(|wt| wt.display_name())
+                    .collect();
+                if !dirty_targets.is_empty() && !force {
+                    let message = if dirty_targets.len() == 1 {
+                        "Worktree has uncommitted changes. Press 'f' to enable force delete."
+                            .to_string()
+                    } else {
+                        format!(
+                            "Worktree has uncommitted changes. Press 'f' to enable force delete: {}",
+                            dirty_targets.join(", ")
+                        )
+                    };
+                    self.message = Some(AppMessage::error(message));
+                    return;
+                }
+                let at_risk = self.forced_delete_losses(delete_branch, force);
+                if !at_risk.is_empty() {
+                    let targets = self.action_worktrees();
+                    let expected = match targets.as_slice() {
+                        [worktree] => worktree.display_name(),
+                        _ => format!("{} worktrees", targets.len()),
+                    };
+                    self.input_buffer.clear();
+                    self.state = AppState::ConfirmDeleteTyped {
+                        delete_branch,
+                        force,
+                        delete_remote,
+                        keep_nested,
+                        expected,
+                        at_risk,
+                    };
+                    return;
+                }
+                self.delete_selected_worktree(delete_branch, force, delete_remote, keep_nested);
+            }
+            KeyCode::Char('b') => {
+                // Toggle delete branch option
+                self.state = AppState::ConfirmDelete {
+                    delete_branch: !delete_branch,
+                    force,
+                    delete_remote,
+                    keep_nested,
+                };
+            }
+            KeyCode::Char('f') => {
+                // Toggle force delete option
+                self.state = AppState::ConfirmDelete {
+                    delete_branch,
+                    force: !force,
+                    delete_remote,
+                    keep_nested,
+                };
+            }
+            KeyCode::Char('r') => {
+                // Toggle remote branch delete option
+                self.state = AppState::ConfirmDelete {
+                    delete_branch,
+                    force,
+                    delete_remote: !delete_remote,
+                    keep_nested,
+                };
+            }
+            KeyCode::Char('a') => {
+                self.state = AppState::List;
+                self.archive_selected_worktrees();
+            }
+            KeyCode::Char('k') if !self.delete_nested_repos.is_empty() => {
+                // Toggle keeping nested repositories
+                self.state = AppState::ConfirmDelete {
+                    delete_branch,
+                    force,
+                    delete_remote,
+                    keep_nested: !keep_nested,
                 };
             }
             _ => {}
@@ -901,43 +2508,110 @@ impl App {
         }
     }
 
-    fn move_selection_up(&mut self) {
-        if self.selected_index > 0 {
-            self.selected_index -= 1;
+    /// Indices of the rows the table shows: every worktree, or only the
+    /// matching ones while a filter is active and `hide_unmatched` is on.
+    pub(crate) fn visible_indices(&self) -> Vec<usize> {
+        let hide = self.hide_unmatched && self.has_filter();
+        (0..self.worktrees.len())
+            .filter(|&i| !hide || self.matches_filter(&self.worktrees[i]))
+            .collect()
+    }
+
+    /// Move the selection among the visible rows; `step` maps the current
+    /// row position and the row count to the new position.
+    fn move_selection(&mut self, step: impl FnOnce(usize, usize) -> usize) {
+        let visible = self.visible_indices();
+        if visible.is_empty() {
+            return;
+        }
+        let position = visible
+            .iter()
+            .position(|&i| i == self.selected_index)
+            .unwrap_or(0);
+        let target = visible[step(position, visible.len()).min(visible.len() - 1)];
+        if target != self.selected_index {
+            self.selected_index = target;
             self.update_selected_details();
         }
     }
 
-    fn move_selection_down(&mut self) {
-        if self.selected_index < self.worktrees.len().saturating_sub(1) {
-            self.selected_index += 1;
-            self.update_selected_details();
+    fn half_page(&self) -> usize {
+        let vh = self.viewport_height.get();
+        if vh > 0 {
+            (vh / 2) as usize
+        } else {
+            10
         }
     }
 
+    fn move_selection_up(&mut self) {
+        self.move_selection(|position, _| position.saturating_sub(1));
+    }
+
+    fn move_selection_down(&mut self) {
+        self.move_selection(|position, _| position + 1);
+    }
+
     fn move_to_top(&mut self) {
-        self.selected_index = 0;
-        self.update_selected_details();
+        self.move_selection(|_, _| 0);
     }
 
     fn move_to_bottom(&mut self) {
-        self.selected_index = self.worktrees.len().saturating_sub(1);
-        self.update_selected_details();
+        self.move_selection(|_, len| len - 1);
     }
 
     fn move_selection_half_page_down(&mut self) {
-        let vh = self.viewport_height.get();
-        let half_page = if vh > 0 { (vh / 2) as usize } else { 10 };
-        let max_index = self.worktrees.len().saturating_sub(1);
-        self.selected_index = (self.selected_index + half_page).min(max_index);
-        self.update_selected_details();
+        let half_page = self.half_page();
+        self.move_selection(|position, _| position + half_page);
     }
 
     fn move_selection_half_page_up(&mut self) {
-        let vh = self.viewport_height.get();
-        let half_page = if vh > 0 { (vh / 2) as usize } else { 10 };
-        self.selected_index = self.selected_index.saturating_sub(half_page);
-        self.update_selected_details();
+        let half_page = self.half_page();
+        self.move_selection(|position, _| position.saturating_sub(half_page));
+    }
+
+    /// Worktree indices numbered 1-9 in the table: the first nine non-bare
+    /// rows scrolled into view.
+    pub(crate) fn quick_jump_targets(&self) -> Vec<usize> {
+        self.visible_indices()
+            .into_iter()
+            .skip(self.table_offset.get())
+            .filter(|&i| !self.worktrees[i].is_bare)
+            .take(9)
+            .collect()
+    }
+
+    /// Select the row numbered `number`; pressing the number of the row that
+    /// is already selected enters it.
+    fn quick_jump(&mut self, number: usize) {
+        let Some(&target) = self.quick_jump_targets().get(number - 1) else {
+            return;
+        };
+        if target != self.selected_index {
+            self.selected_index = target;
+            self.update_selected_details();
+            return;
+        }
+
+        self.poll_background_op();
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::info("Operation still in progress"));
+            return;
+        }
+        self.enter_worktree();
+    }
+
+    fn toggle_hide_unmatched(&mut self) {
+        self.hide_unmatched = !self.hide_unmatched;
+        if !self.visible_indices().contains(&self.selected_index) {
+            self.select_first_filtered_worktree();
+            self.update_selected_details();
+        }
+        self.message = Some(AppMessage::info(if self.hide_unmatched {
+            "Filtering hides non-matching worktrees"
+        } else {
+            "Filtering dims non-matching worktrees"
+        }));
     }
 
     fn jump_to_current_worktree(&mut self) {
@@ -956,6 +2630,17 @@ impl App {
         }
     }
 
+    fn select_worktree_path(&mut self, path: &Path) {
+        if let Some(idx) = self
+            .worktrees
+            .iter()
+            .position(|wt| paths_refer_to_same_location(&wt.path, path))
+        {
+            self.selected_index = idx;
+        }
+        self.update_selected_details();
+    }
+
     pub fn selected_worktree(&self) -> Option<&Worktree> {
         self.worktrees.get(self.selected_index)
     }
@@ -1001,18 +2686,25 @@ impl App {
     }
 
     fn worktree_path_for_branch(&self, branch: &str) -> PathBuf {
+        if let Some(path) = self
+            .config
+            .templated_worktree_path(&self.project_root_path, branch)
+        {
+            return path;
+        }
+        let folder = self.config.worktree_folder(branch);
         if self.repo_is_bare {
             return self
                 .bare_repo_path
                 .parent()
-                .map(|p| p.join(branch))
-                .unwrap_or_else(|| PathBuf::from(branch));
+                .map(|p| p.join(&folder))
+                .unwrap_or_else(|| PathBuf::from(&folder));
         }
 
         self.config
             .resolved_worktree_root()
             .join(self.repo_namespace())
-            .join(branch)
+            .join(folder)
     }
 
     fn repo_namespace(&self) -> String {
@@ -1035,8 +2727,23 @@ impl App {
         })
     }
 
+    /// Ghost text completing the branch name being typed; Tab accepts it.
+    pub fn add_modal_suggestion(&self) -> Option<&str> {
+        branch_suggest::completion(&self.branch_suggestions, &self.input_buffer)
+    }
+
     pub fn add_modal_base_label(&self) -> String {
-        format!("Base branch: {}", self.add_base_branch)
+        match self.base_comparisons.get(&self.add_base_branch) {
+            Some(comparison) => format!(
+                "Base branch: {} ({})",
+                self.add_base_branch,
+                comparison.summary()
+            ),
+            None if self.base_comparison_receiver.is_some() => {
+                format!("Base branch: {} (fetching...)", self.add_base_branch)
+            }
+            None => format!("Base branch: {}", self.add_base_branch),
+        }
     }
 
     fn cycle_add_base_branch(&mut self) {
@@ -1068,12 +2775,10 @@ impl App {
         match git::list_worktrees(&self.bare_repo_path) {
             Ok(worktrees) => {
                 self.worktrees = worktrees;
-                self.prune_missing_selected_paths();
-                self.apply_sort();
-                if self.selected_index >= self.worktrees.len() {
-                    self.selected_index = self.worktrees.len().saturating_sub(1);
-                }
-                self.update_selected_details();
+                self.reload_worktree_context();
+                self.disk_usage.clear();
+                self.disk_usage_receiver = None;
+                self.start_disk_usage_scan();
                 self.start_pr_status_refresh();
                 self.message = Some(AppMessage::info("Refreshed"));
             }
@@ -1083,16 +2788,254 @@ impl App {
         }
     }
 
-    fn cycle_sort_mode(&mut self) {
-        self.sort_mode = self.sort_mode.next();
+    /// Reload metadata and remotes for a new worktree list, then re-sort it
+    /// keeping the selected worktree selected.
+    fn reload_worktree_context(&mut self) {
+        if let Ok(metadata) = Metadata::load(&self.project_root_path) {
+            self.metadata = metadata;
+        }
+        self.branch_remotes = git::branch_remotes(&self.bare_repo_path);
+        self.branch_upstreams = git::branch_upstreams(&self.bare_repo_path);
+        self.branch_heads = git::branch_heads(&self.bare_repo_path);
+        self.default_remote = git::default_remote(&self.bare_repo_path);
+        self.start_merged_check();
+        self.prune_missing_selected_paths();
         self.apply_sort();
-        self.message = Some(AppMessage::info(format!(
-            "Sort: {}",
-            self.sort_mode.label()
-        )));
-    }
-
-    fn apply_sort(&mut self) {
+        if self.selected_index >= self.worktrees.len() {
+            self.selected_index = self.worktrees.len().saturating_sub(1);
+        }
+        self.update_selected_details();
+    }
+
+    /// Back from the editor or terminal: update the touched worktree right
+    /// away, then reload the whole list in the background at most once per
+    /// `AUTO_REFRESH_INTERVAL`.
+    fn resume_after_external(&mut self, path: &Path) {
+        if let Some(wt) = self.worktrees.iter_mut().find(|wt| wt.path == path) {
+            git::refresh_worktree_state(wt);
+        }
+        self.disk_usage.remove(path);
+        self.apply_sort();
+        self.start_disk_usage_scan();
+
+        let due = self
+            .last_auto_refresh
+            .is_none_or(|last| last.elapsed() >= AUTO_REFRESH_INTERVAL);
+        if due {
+            self.start_background_refresh();
+        }
+    }
+
+    /// Reload the list every `auto_refresh` seconds while the list view is
+    /// idle, so ahead/behind and statuses do not go stale when owt stays open.
+    fn poll_auto_refresh(&mut self) {
+        let Some(interval) = self.config.get_auto_refresh() else {
+            return;
+        };
+        if self.state != AppState::List || self.active_op.is_some() {
+            return;
+        }
+        match self.last_auto_refresh {
+            Some(last) if last.elapsed() >= interval => self.start_background_refresh(),
+            Some(_) => {}
+            // Count the first interval from launch
+            None => self.last_auto_refresh = Some(Instant::now()),
+        }
+    }
+
+    /// Fetch the default remote every `auto_fetch` minutes in the background,
+    /// then reload the list so behind counts pick up the new remote refs.
+    fn poll_auto_fetch(&mut self) {
+        if let Some(rx) = self.auto_fetch_receiver.as_ref() {
+            let result = match rx.try_recv() {
+                Ok(result) => result,
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => Ok(()),
+            };
+            self.auto_fetch_receiver = None;
+            match result {
+                Ok(()) => self.start_background_refresh(),
+                Err(e) => {
+                    self.message = Some(AppMessage::error(format!("Background fetch: {}", e)));
+                }
+            }
+            return;
+        }
+
+        let Some(interval) = self.config.get_auto_fetch() else {
+            return;
+        };
+        if self.active_op.is_some() {
+            return;
+        }
+        match self.last_auto_fetch {
+            Some(last) if last.elapsed() >= interval => {}
+            Some(_) => return,
+            // Count the first interval from launch
+            None => {
+                self.last_auto_fetch = Some(Instant::now());
+                return;
+            }
+        }
+
+        self.last_auto_fetch = Some(Instant::now());
+        let bare_repo_path = self.bare_repo_path.clone();
+        let cancel = git::CancelToken::with_timeout(self.config.get_git_timeout());
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = git::fetch_default_remote(&bare_repo_path, &cancel);
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+        self.auto_fetch_receiver = Some(rx);
+    }
+
+    /// With `live_status`, re-read the status of worktrees whose files or
+    /// git state changed on disk. The watcher restarts when the list changes.
+    fn poll_fs_watch(&mut self) {
+        if !self.config.live_status.unwrap_or(false) {
+            self.fs_watcher = None;
+            return;
+        }
+        let paths: Vec<PathBuf> = self
+            .worktrees
+            .iter()
+            .filter(|wt| !wt.is_bare)
+            .map(|wt| wt.path.clone())
+            .collect();
+        if paths != self.fs_watch_paths {
+            self.fs_watch_paths = paths;
+            self.fs_watcher = None;
+            let started = git::get_git_common_dir(&self.bare_repo_path)
+                .and_then(|common_dir| WorktreeWatcher::start(&common_dir, &self.fs_watch_paths));
+            match started {
+                Ok(watcher) => self.fs_watcher = Some(watcher),
+                Err(e) => {
+                    self.message =
+                        Some(AppMessage::error(format!("Live status disabled: {:#}", e)));
+                }
+            }
+        }
+
+        let Some(watcher) = self.fs_watcher.as_mut() else {
+            return;
+        };
+        let changed = watcher.settled_changes();
+        if changed.is_empty() {
+            return;
+        }
+        for wt in self
+            .worktrees
+            .iter_mut()
+            .filter(|wt| changed.contains(&wt.path))
+        {
+            git::refresh_worktree_state(wt);
+        }
+        self.apply_sort();
+        self.update_selected_details();
+    }
+
+    fn start_background_refresh(&mut self) {
+        if self.refresh_receiver.is_some() {
+            return;
+        }
+        self.last_auto_refresh = Some(Instant::now());
+        let bare_repo_path = self.bare_repo_path.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            if let Ok(worktrees) = git::list_worktrees(&bare_repo_path) {
+                let _ = tx.send(worktrees);
+            }
+        });
+        self.refresh_receiver = Some(rx);
+    }
+
+    fn poll_background_refresh(&mut self) {
+        let Some(rx) = self.refresh_receiver.as_ref() else {
+            return;
+        };
+        let mut worktrees = match rx.try_recv() {
+            Ok(worktrees) => worktrees,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.refresh_receiver = None;
+                return;
+            }
+        };
+        self.refresh_receiver = None;
+        // A running operation refreshes the list itself when it finishes
+        if self.active_op.is_some() {
+            return;
+        }
+
+        // Keep PR states and the selection so nothing visibly jumps
+        let selected_path = self.selected_worktree().map(|wt| wt.path.clone());
+        for wt in &mut worktrees {
+            if let Some(old) = self.worktrees.iter().find(|old| old.path == wt.path) {
+                wt.github_pr_status = old.github_pr_status;
+            }
+        }
+        self.worktrees = worktrees;
+        if let Some(selected_path) = selected_path {
+            self.select_worktree_path(&selected_path);
+        }
+        self.reload_worktree_context();
+        self.start_disk_usage_scan();
+    }
+
+    /// Remember the sort mode, filter, and verbose mode for the next launch.
+    /// Nothing is written while they match what was loaded.
+    pub(crate) fn save_view_state(&mut self) {
+        // The clone prompt was closed without loading a repository
+        if self.worktrees.is_empty() {
+            return;
+        }
+        let view = ViewState {
+            sort: (self.sort_mode != SortMode::default())
+                .then(|| self.sort_mode.label().to_string()),
+            sort_reversed: self.sort_reversed,
+            filter: self.filter_text.clone(),
+            status_filter: (self.status_filter != StatusFilter::All)
+                .then(|| self.status_filter.label().to_string()),
+            verbose: self.verbose,
+        };
+        if view == self.metadata.view {
+            return;
+        }
+        match Metadata::update(&self.project_root_path, |metadata| {
+            metadata.view = view.clone()
+        }) {
+            Ok(metadata) => self.metadata = metadata,
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!(
+                    "Failed to save view state: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    fn cycle_sort_mode(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+        self.apply_sort();
+        self.message = Some(AppMessage::info(format!("Sort: {}", self.sort_label())));
+    }
+
+    fn toggle_sort_direction(&mut self) {
+        self.sort_reversed = !self.sort_reversed;
+        self.apply_sort();
+        self.message = Some(AppMessage::info(format!("Sort: {}", self.sort_label())));
+    }
+
+    /// Sort mode for the footer and messages, e.g. `recent` or `recent (reversed)`
+    pub(crate) fn sort_label(&self) -> String {
+        if self.sort_reversed {
+            format!("{} (reversed)", self.sort_mode.label())
+        } else {
+            self.sort_mode.label().to_string()
+        }
+    }
+
+    fn apply_sort(&mut self) {
         // Remember currently selected worktree path
         let selected_path = self.selected_worktree().map(|wt| wt.path.clone());
 
@@ -1124,6 +3067,50 @@ impl App {
                     b.last_commit_time.cmp(&a.last_commit_time)
                 });
             }
+            SortMode::Frecency => {
+                let scores = &self.usage_scores;
+                self.worktrees.sort_by(|a, b| {
+                    // Bare repo first, then most used lately, then by name
+                    let score = |wt: &Worktree| scores.get(&wt.path).copied().unwrap_or(0);
+                    b.is_bare
+                        .cmp(&a.is_bare)
+                        .then_with(|| score(b).cmp(&score(a)))
+                        .then_with(|| {
+                            a.display_name()
+                                .to_lowercase()
+                                .cmp(&b.display_name().to_lowercase())
+                        })
+                });
+            }
+            SortMode::Remote => {
+                let remotes: Vec<Option<String>> = self
+                    .worktrees
+                    .iter()
+                    .map(|wt| self.worktree_remote(wt).map(str::to_string))
+                    .collect();
+                let mut keyed: Vec<(Option<String>, Worktree)> =
+                    remotes.into_iter().zip(self.worktrees.drain(..)).collect();
+                let default_remote = self.default_remote.clone();
+                keyed.sort_by(|(remote_a, a), (remote_b, b)| {
+                    // Bare repo first, then the default remote's group, then other
+                    // remotes by name, then branches without a remote
+                    let group = |remote: &Option<String>| match remote {
+                        Some(remote) if Some(remote) == default_remote.as_ref() => 0,
+                        Some(_) => 1,
+                        None => 2,
+                    };
+                    b.is_bare
+                        .cmp(&a.is_bare)
+                        .then_with(|| group(remote_a).cmp(&group(remote_b)))
+                        .then_with(|| remote_a.cmp(remote_b))
+                        .then_with(|| {
+                            a.display_name()
+                                .to_lowercase()
+                                .cmp(&b.display_name().to_lowercase())
+                        })
+                });
+                self.worktrees = keyed.into_iter().map(|(_, wt)| wt).collect();
+            }
             SortMode::Status => {
                 self.worktrees.sort_by(|a, b| {
                     // Bare repo always first
@@ -1145,6 +3132,25 @@ impl App {
                 });
             }
         }
+        if self.sort_reversed {
+            self.worktrees.reverse();
+        }
+        // While filtering, rank matches best first, keeping the sort for ties
+        if !self.filter_text.is_empty() {
+            let scores: Vec<Option<i64>> = self
+                .worktrees
+                .iter()
+                .map(|wt| self.filter_score(wt))
+                .collect();
+            let mut ranked: Vec<(Option<i64>, Worktree)> =
+                scores.into_iter().zip(self.worktrees.drain(..)).collect();
+            ranked.sort_by_key(|(score, wt)| (!wt.is_bare, std::cmp::Reverse(*score)));
+            self.worktrees = ranked.into_iter().map(|(_, wt)| wt).collect();
+        }
+        // The bare repo, then pinned worktrees, stay on top whatever the order
+        let pinned = &self.metadata.pinned;
+        self.worktrees
+            .sort_by_key(|wt| (!wt.is_bare, !pinned.contains(&wt.display_name())));
 
         // Restore selection to same worktree after sort
         if let Some(ref path) = selected_path {
@@ -1155,6 +3161,24 @@ impl App {
         self.update_selected_details();
     }
 
+    /// Remote a worktree's branch fetches from: `branch.<name>.remote`, else the default remote
+    pub(crate) fn worktree_remote(&self, wt: &Worktree) -> Option<&str> {
+        if wt.is_bare {
+            return None;
+        }
+        wt.branch
+            .as_ref()
+            .and_then(|branch| self.branch_remotes.get(branch))
+            .or(self.default_remote.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Remote shown next to the branch when it is not the default remote (a fork)
+    pub(crate) fn remote_badge(&self, wt: &Worktree) -> Option<&str> {
+        self.worktree_remote(wt)
+            .filter(|remote| Some(*remote) != self.default_remote.as_deref())
+    }
+
     fn prune_missing_selected_paths(&mut self) {
         let existing_paths: HashSet<PathBuf> =
             self.worktrees.iter().map(|wt| wt.path.clone()).collect();
@@ -1203,8 +3227,27 @@ impl App {
             self.message = Some(AppMessage::error("Branch name cannot be empty"));
             return;
         }
+        if let Some(problem) = git::branch_name_problem(&branch) {
+            self.message = Some(AppMessage::error(format!(
+                "Invalid branch name: {}",
+                problem
+            )));
+            return;
+        }
+        if let Some(branch_type) = self
+            .config
+            .branch_type_for(&branch)
+            .filter(|branch_type| !branch_type.matches_template(&branch))
+        {
+            self.message = Some(AppMessage::error(format!(
+                "{} branches must look like {}",
+                branch_type.prefix,
+                branch_type.name_template.as_deref().unwrap_or_default()
+            )));
+            return;
+        }
 
-        let worktree_path = self.worktree_path_for_branch(&branch);
+        let worktree_path = self.add_modal_worktree_path(&branch);
         if let Some(existing) = self.conflicting_worktree_for_branch(&branch, &worktree_path) {
             self.message = Some(AppMessage::error(format!(
                 "Branch '{}' is already checked out at {}. Remove or move that worktree first.",
@@ -1214,13 +3257,28 @@ impl App {
             self.state = AppState::List;
             return;
         }
+        if self.add_path_override.is_some() && worktree_path.exists() {
+            let is_empty_dir = fs::read_dir(&worktree_path)
+                .map(|mut entries| entries.next().is_none())
+                .unwrap_or(false);
+            if !is_empty_dir {
+                self.message = Some(AppMessage::error(format!(
+                    "{} already exists. Pick an empty or new directory.",
+                    worktree_path.display()
+                )));
+                return;
+            }
+        }
 
-        let source_path = self.current_worktree_path.clone().or_else(|| {
-            self.worktrees
-                .iter()
-                .find(|wt| !wt.is_bare)
-                .map(|wt| wt.path.clone())
-        });
+        let source_path = self.config.copy_source_path(
+            &self.worktrees,
+            self.current_worktree_path.clone().or_else(|| {
+                self.worktrees
+                    .iter()
+                    .find(|wt| !wt.is_bare)
+                    .map(|wt| wt.path.clone())
+            }),
+        );
 
         self.exit_action = ExitAction::CreateWorktree(WorktreeCreateRequest {
             bare_repo_path: self.bare_repo_path.clone(),
@@ -1229,6 +3287,11 @@ impl App {
             base_branch: self.add_base_branch.clone(),
             worktree_path,
             source_path,
+            base_fetched: self
+                .base_comparisons
+                .get(&self.add_base_branch)
+                .is_some_and(|comparison| comparison.fetched),
+            path_overridden: self.add_path_override.is_some(),
         });
         self.should_quit = true;
         self.state = AppState::List;
@@ -1261,12 +3324,23 @@ impl App {
         }
 
         let copy_files = self.config.copy_files.clone();
-        let source_path = self.current_worktree_path.clone().or_else(|| {
-            self.worktrees
-                .iter()
-                .find(|wt| !wt.is_bare)
-                .map(|wt| wt.path.clone())
-        });
+        let sparse_paths = self.config.sparse_paths_for_branch(&branch).to_vec();
+        let submodule_update = self.config.submodule_update.unwrap_or(false);
+        let lfs_cancel = self
+            .config
+            .lfs_pull
+            .unwrap_or(false)
+            .then(git::CancelToken::default);
+        let lfs_cancel_for_thread = lfs_cancel.clone();
+        let source_path = self.config.copy_source_path(
+            &self.worktrees,
+            self.current_worktree_path.clone().or_else(|| {
+                self.worktrees
+                    .iter()
+                    .find(|wt| !wt.is_bare)
+                    .map(|wt| wt.path.clone())
+            }),
+        );
 
         let bare_repo_path = self.bare_repo_path.clone();
         let worktree_path_for_thread = worktree_path.clone();
@@ -1286,13 +3360,15 @@ impl App {
                 &branch,
                 &worktree_path_for_thread,
                 base_branch_for_add,
+                &sparse_paths,
             );
 
-            let result = git::add_worktree(
+            let result = git::add_worktree_sparse(
                 &bare_repo_path,
                 &branch,
                 &worktree_path_for_thread,
                 base_branch_for_add,
+                &sparse_paths,
             );
 
             let copy_outcomes = if result.is_ok() {
@@ -1307,9 +3383,17 @@ impl App {
             };
 
             let message = match &result {
-                Ok(()) => append_copy_warnings(
-                    format!("Created worktree: {}", display_name_for_thread),
-                    &copy_outcomes,
+                Ok(()) => append_lfs_pull(
+                    append_submodule_update(
+                        append_copy_warnings(
+                            format!("Created worktree: {}", display_name_for_thread),
+                            &copy_outcomes,
+                        ),
+                        submodule_update,
+                        &worktree_path_for_thread,
+                    ),
+                    lfs_cancel_for_thread.as_ref(),
+                    &worktree_path_for_thread,
                 ),
                 Err(e) => format!("Failed to create: {}", e),
             };
@@ -1327,12 +3411,12 @@ impl App {
 
         self.state = AppState::List;
         self.message = Some(AppMessage::info(format!(
-            "Creating worktree: {}{}...",
-            display_name,
-            format!(" (base: {})", self.add_base_branch)
+            "Creating worktree: {} (base: {})...",
+            display_name, self.add_base_branch
         )));
         self.input_buffer.clear();
         self.active_op = Some((OpKind::Add, rx));
+        self.op_cancel = lfs_cancel;
         self.active_op_info = Some(ActiveOp {
             kind: OpKind::Add,
             worktree_path: worktree_path_for_state.clone(),
@@ -1341,7 +3425,29 @@ impl App {
         });
     }
 
-    fn run_post_add_script(&mut self, worktree_path: &PathBuf) {
+    /// Hook context for the worktree at `worktree_path`, created from the
+    /// add modal's base branch.
+    fn hook_env(&self, worktree_path: &Path) -> HookEnv {
+        let branch = self
+            .worktrees
+            .iter()
+            .find(|wt| wt.path == worktree_path)
+            .and_then(|wt| wt.branch.clone())
+            .unwrap_or_else(|| worktree_name_from_path(worktree_path));
+        HookEnv {
+            branch_type: self
+                .config
+                .branch_type_for(&branch)
+                .map(|branch_type| branch_type.name.clone()),
+            branch,
+            base_branch: self.add_base_branch.clone(),
+            worktree_path: worktree_path.to_path_buf(),
+            bare_path: self.bare_repo_path.clone(),
+        }
+    }
+
+    fn run_post_add_script(&mut self, hook_env: &HookEnv) {
+        let worktree_path = hook_env.worktree_path.as_path();
         let script_path = self
             .config
             .resolved_post_add_script_path(&self.project_root_path);
@@ -1361,8 +3467,9 @@ impl App {
 
         let session_name = format!("owt-post-add-{}-{}", std::process::id(), self.spinner_tick);
         let command = format!(
-            "cd {} && sh {}; status=$?; tmux kill-session -t {}; exit $status",
+            "cd {} && {} && sh {}; status=$?; tmux kill-session -t {}; exit $status",
             shell_quote(worktree_path),
+            hook_env.shell_exports(),
             shell_quote(&script_path),
             session_name
         );
@@ -1375,12 +3482,14 @@ impl App {
                 let (tx, rx) = mpsc::channel();
                 self.script_status = ScriptStatus::Running {
                     worktree_name: worktree_name.clone(),
+                    worktree_path: worktree_path.to_path_buf(),
+                    label: "Setup script".to_string(),
                 };
                 self.script_receiver = Some(rx);
-                let _ = tx.send(ScriptResult {
+                let _ = tx.send(ScriptEvent::Done(ScriptResult {
                     success: true,
                     message: format!("launched in tmux for {}", worktree_name),
-                });
+                }));
                 self.message = Some(AppMessage::info(format!(
                     "Setup script launched in tmux for {}",
                     worktree_name
@@ -1404,59 +3513,627 @@ impl App {
         }
     }
 
-    fn delete_selected_worktree(&mut self, delete_branch: bool, force: bool) {
-        if self.active_op.is_some() {
-            self.message = Some(AppMessage::error("Another operation is in progress"));
-            self.state = AppState::List;
+    /// Run the post-add script for the selected worktree in the background,
+    /// streaming its output into the script output panel.
+    fn run_setup_script_for_selected(&mut self) {
+        let Some(worktree_path) = self.script_target() else {
+            return;
+        };
+        let script_path = self
+            .config
+            .resolved_post_add_script_path(&self.project_root_path);
+        if !script_path.is_file() {
+            self.message = Some(AppMessage::error(format!(
+                "No setup script at {}",
+                script_path.display()
+            )));
             return;
         }
 
-        let worktrees = self.action_worktrees();
-        if worktrees.is_empty() {
+        let mut command = Command::new("sh");
+        command.arg(&script_path);
+        self.start_script(
+            "Setup script".to_string(),
+            &worktree_path,
+            command,
+            format!("$ sh {}", script_path.display()),
+            true,
+        );
+    }
+
+    fn open_task_menu(&mut self) {
+        if self.config.tasks.is_empty() {
+            self.message = Some(AppMessage::error(
+                "No tasks configured; add [[tasks]] to config.toml",
+            ));
             return;
         }
+        if self.selected_worktree().is_some_and(|wt| !wt.is_bare) {
+            self.state = AppState::TaskMenu { selected: 0 };
+        }
+    }
 
-        if worktrees.iter().any(|wt| wt.is_bare) {
-            self.message = Some(AppMessage::error("Cannot delete bare repository"));
-            self.state = AppState::List;
-            return;
+    fn handle_task_menu_input(&mut self, code: KeyCode, selected: usize) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = AppState::List;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.state = AppState::TaskMenu {
+                    selected: selected.saturating_sub(1),
+                };
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.state = AppState::TaskMenu {
+                    selected: (selected + 1).min(self.config.tasks.len().saturating_sub(1)),
+                };
+            }
+            KeyCode::Enter => {
+                self.state = AppState::List;
+                if let Some(task) = self.config.tasks.get(selected).cloned() {
+                    if self.selected_worktree_count() > 1 {
+                        let targets = self.action_worktrees();
+                        self.run_in_worktrees(
+                            format!("Task {}", task.name),
+                            &task.command,
+                            targets,
+                        );
+                    } else {
+                        self.run_task(&task);
+                    }
+                }
+            }
+            KeyCode::Char('a') => {
+                self.state = AppState::List;
+                if let Some(task) = self.config.tasks.get(selected).cloned() {
+                    let targets = self.worktrees.clone();
+                    self.run_in_worktrees(format!("Task {}", task.name), &task.command, targets);
+                }
+            }
+            _ => {}
         }
+    }
 
-        let dirty_worktrees: Vec<String> = worktrees
+    /// Projects the `w` switcher lists: the configured `repositories`, then
+    /// the registered projects not already listed, with the open project
+    /// first when it is not one of them.
+    fn collect_switcher_repositories(&self) -> Vec<PathBuf> {
+        let mut repositories: Vec<PathBuf> = self
+            .config
+            .repositories
             .iter()
-            .filter(|wt| wt.status != WorktreeStatus::Clean)
-            .map(Worktree::display_name)
+            .map(|path| crate::config::expand_home_path(path))
             .collect();
-        if !dirty_worktrees.is_empty() && !force {
-            let message = if worktrees.len() == 1 {
-                "Worktree has uncommitted changes. Press 'f' to enable force delete.".to_string()
-            } else {
-                format!(
-                    "Worktree has uncommitted changes. Press 'f' to enable force delete: {}",
-                    dirty_worktrees.join(", ")
-                )
-            };
-            self.message = Some(AppMessage::error(message));
-            return;
+        for root in repo_registry::load().unwrap_or_default().into_values() {
+            if !repositories.iter().any(|path| path_is_within(path, &root)) {
+                repositories.push(root);
+            }
+        }
+        if !repositories
+            .iter()
+            .any(|path| self.is_current_repository(path))
+        {
+            repositories.insert(0, self.project_root_path.clone());
         }
+        repositories
+    }
 
-        let display_name = batch_display_name(&worktrees, "worktree");
-        let display_name_for_thread = display_name.clone();
-        let display_name_for_state = display_name.clone();
-        let worktree_path_for_state = worktrees[0].path.clone();
-        let worktree_paths_for_state: Vec<PathBuf> =
-            worktrees.iter().map(|wt| wt.path.clone()).collect();
+    /// Whether `path` is, or is inside, the open project
+    pub(crate) fn is_current_repository(&self, path: &Path) -> bool {
+        path_is_within(path, &self.project_root_path)
+    }
 
-        let force_flag = if force { " --force" } else { "" };
-        let cmd_detail = worktrees
+    fn open_repo_switcher(&mut self) {
+        self.switcher_repositories = self.collect_switcher_repositories();
+        if self.switcher_repositories.len() < 2 {
+            self.message = Some(AppMessage::error(
+                "No other repositories; list them in repositories or open them once with owt",
+            ));
+            return;
+        }
+        let selected = self
+            .switcher_repositories
             .iter()
-            .map(|wt| {
-                format!(
-                    "git -C {} worktree remove{} {}",
-                    self.bare_repo_path.display(),
+            .position(|path| self.is_current_repository(path))
+            .unwrap_or(0);
+        self.state = AppState::RepoSwitcher { selected };
+    }
+
+    fn handle_repo_switcher_input(&mut self, code: KeyCode, selected: usize) {
+        let repositories = self.switcher_repositories.clone();
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = AppState::List;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.state = AppState::RepoSwitcher {
+                    selected: selected.saturating_sub(1),
+                };
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.state = AppState::RepoSwitcher {
+                    selected: (selected + 1).min(repositories.len().saturating_sub(1)),
+                };
+            }
+            KeyCode::Enter => {
+                self.state = AppState::List;
+                if let Some(path) = repositories.get(selected) {
+                    self.switch_repository(path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replace this session with one for the project at `path`, the way a
+    /// finished clone does. The current view state is saved first.
+    fn switch_repository(&mut self, path: &Path) {
+        if self.is_current_repository(path) {
+            return;
+        }
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+        let loaded = crate::resolve_repository_context(path).and_then(|context| {
+            App::new(
+                context.repo_path,
+                context.project_root_path,
+                context.repo_is_bare,
+                Some(path.to_path_buf()),
+                self.has_shell_integration,
+            )
+        });
+        match loaded {
+            Ok(mut app) => {
+                self.save_view_state();
+                app.events = self.events.take();
+                app.message = Some(AppMessage::info(format!(
+                    "Switched to {}",
+                    app.project_root_path.display()
+                )));
+                *self = app;
+            }
+            Err(error) => {
+                self.message = Some(AppMessage::error(format!(
+                    "Cannot open {}: {}",
+                    path.display(),
+                    error
+                )));
+            }
+        }
+    }
+
+    /// Run a configured task in the selected worktree through `sh -c`.
+    fn run_task(&mut self, task: &Task) {
+        let Some(worktree_path) = self.script_target() else {
+            return;
+        };
+        let mut command = Command::new("sh");
+        command.args(["-c", &task.command]);
+        self.start_script(
+            format!("Task {}", task.name),
+            &worktree_path,
+            command,
+            format!("$ {}", task.command),
+            true,
+        );
+    }
+
+    fn open_run_command(&mut self) {
+        if self.action_worktrees().iter().all(|wt| wt.is_bare) {
+            return;
+        }
+        self.input_buffer.clear();
+        self.state = AppState::RunCommand;
+    }
+
+    fn handle_run_command_input(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.state = AppState::List;
+                self.input_buffer.clear();
+            }
+            KeyCode::Enter => {
+                let command = std::mem::take(&mut self.input_buffer);
+                self.state = AppState::List;
+                if command.trim().is_empty() {
+                    return;
+                }
+                let targets = self.action_worktrees();
+                self.run_in_worktrees("Command".to_string(), &command, targets);
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Run `command` through `sh -c` in each of `targets`, several at once
+    /// like `owt exec`. Rows show each worktree's state until the next run,
+    /// and each worktree's output lands in the script output panel in one
+    /// piece, under a `==>` line, as soon as it finishes.
+    fn run_in_worktrees(&mut self, label: String, command: &str, targets: Vec<Worktree>) {
+        if let ScriptStatus::Running { label, .. } = &self.script_status {
+            self.message = Some(AppMessage::error(format!("{} is still running", label)));
+            return;
+        }
+        let targets: Vec<Worktree> = targets.into_iter().filter(|wt| !wt.is_bare).collect();
+        let shell = vec!["sh".to_string(), "-c".to_string(), command.to_string()];
+        let (tx, rx) = mpsc::channel();
+        let count = targets.len();
+        self.bulk_run = targets
+            .iter()
+            .map(|wt| (wt.path.clone(), BulkRunStatus::Running))
+            .collect();
+        std::thread::spawn(move || {
+            let targets: Vec<&Worktree> = targets.iter().collect();
+            let mut failed = Vec::new();
+            let jobs = worktree_exec::default_jobs();
+            worktree_exec::run_parallel(&targets, &shell, jobs, |index, result| {
+                let worktree = targets[index];
+                let mut lines = vec![format!("==> {}", worktree.display_name())];
+                let outcome = result.map(|output| {
+                    for stream in [&output.stdout, &output.stderr] {
+                        lines.extend(String::from_utf8_lossy(stream).lines().map(String::from));
+                    }
+                    output.status
+                });
+                let status = match outcome {
+                    Ok(status) if status.success() => BulkRunStatus::Succeeded,
+                    Ok(status) => BulkRunStatus::Failed(match status.code() {
+                        Some(code) => format!("exit {}", code),
+                        None => "terminated by signal".to_string(),
+                    }),
+                    Err(e) => BulkRunStatus::Failed(format!("{:#}", e)),
+                };
+                if let BulkRunStatus::Failed(reason) = &status {
+                    failed.push(worktree.display_name());
+                    lines.push(reason.clone());
+                }
+                for line in lines {
+                    let _ = tx.send(ScriptEvent::Output(line));
+                }
+                let _ = tx.send(ScriptEvent::WorktreeDone(worktree.path.clone(), status));
+            });
+            let result = if failed.is_empty() {
+                ScriptResult {
+                    success: true,
+                    message: format!("finished in {} worktrees", count),
+                }
+            } else {
+                ScriptResult {
+                    success: false,
+                    message: format!(
+                        "{} of {} worktrees ({})",
+                        failed.len(),
+                        count,
+                        failed.join(", ")
+                    ),
+                }
+            };
+            let _ = tx.send(ScriptEvent::Done(result));
+        });
+
+        self.script_output = vec![format!("$ {}", command)];
+        self.script_status = ScriptStatus::Running {
+            worktree_name: format!("{} worktrees", count),
+            worktree_path: self.project_root_path.clone(),
+            label,
+        };
+        self.script_receiver = Some(rx);
+        self.state = AppState::ScriptOutput {
+            scroll: 0,
+            follow: true,
+        };
+    }
+
+    fn handle_confirm_install_input(
+        &mut self,
+        code: KeyCode,
+        worktree_path: PathBuf,
+        command: String,
+    ) {
+        match code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.state = AppState::List;
+                if let ScriptStatus::Running { label, .. } = &self.script_status {
+                    self.message = Some(AppMessage::error(format!(
+                        "{} is still running; run {} yourself",
+                        label, command
+                    )));
+                    return;
+                }
+                let mut shell = Command::new("sh");
+                shell.args(["-c", &command]);
+                self.start_script(
+                    "Install".to_string(),
+                    &worktree_path,
+                    shell,
+                    format!("$ {}", command),
+                    false,
+                );
+            }
+            KeyCode::Char('n') | KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = AppState::List;
+            }
+            _ => {}
+        }
+    }
+
+    /// The selected worktree to run a script in, unless one is still running.
+    fn script_target(&mut self) -> Option<PathBuf> {
+        if let ScriptStatus::Running { label, .. } = &self.script_status {
+            self.message = Some(AppMessage::error(format!("{} is still running", label)));
+            return None;
+        }
+        self.selected_worktree()
+            .filter(|wt| !wt.is_bare)
+            .map(|wt| wt.path.clone())
+    }
+
+    /// Start `command` in `worktree_path` with the hook variables, forwarding
+    /// stdout and stderr line by line to the script output panel, which opens
+    /// right away with `open_output`.
+    fn start_script(
+        &mut self,
+        label: String,
+        worktree_path: &Path,
+        mut command: Command,
+        header: String,
+        open_output: bool,
+    ) {
+        let hook_env = self.hook_env(worktree_path);
+        let spawned = command
+            .current_dir(worktree_path)
+            .envs(hook_env.vars())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!(
+                    "{} could not start: {}",
+                    label, e
+                )));
+                return;
+            }
+        };
+
+        let worktree_name = worktree_name_from_path(worktree_path);
+        let (tx, rx) = mpsc::channel();
+        let readers = [
+            child.stdout.take().map(|out| stream_lines(out, tx.clone())),
+            child.stderr.take().map(|err| stream_lines(err, tx.clone())),
+        ];
+        let name_for_thread = worktree_name.clone();
+        std::thread::spawn(move || {
+            let status = child.wait();
+            for reader in readers.into_iter().flatten() {
+                let _ = reader.join();
+            }
+            let result = match status {
+                Ok(status) if status.success() => ScriptResult {
+                    success: true,
+                    message: format!("finished for {}", name_for_thread),
+                },
+                Ok(status) => ScriptResult {
+                    success: false,
+                    message: match status.code() {
+                        Some(code) => format!("exit {}", code),
+                        None => "terminated by signal".to_string(),
+                    },
+                },
+                Err(e) => ScriptResult {
+                    success: false,
+                    message: e.to_string(),
+                },
+            };
+            let _ = tx.send(ScriptEvent::Done(result));
+        });
+
+        self.script_output = vec![header];
+        self.bulk_run.clear();
+        self.script_status = ScriptStatus::Running {
+            worktree_name,
+            worktree_path: worktree_path.to_path_buf(),
+            label,
+        };
+        self.script_receiver = Some(rx);
+        if open_output {
+            self.state = AppState::ScriptOutput {
+                scroll: 0,
+                follow: true,
+            };
+        }
+    }
+
+    fn open_script_output(&mut self) {
+        if self.script_output.is_empty() {
+            self.message = Some(AppMessage::info("No script output yet"));
+            return;
+        }
+        self.state = AppState::ScriptOutput {
+            scroll: 0,
+            follow: true,
+        };
+    }
+
+    fn handle_script_output_input(&mut self, code: KeyCode, scroll: u16, follow: bool) {
+        let last_line =
+            u16::try_from(self.script_output.len().saturating_sub(1)).unwrap_or(u16::MAX);
+        // While following, the view starts at the tail
+        let scroll = if follow { last_line } else { scroll };
+        self.state = match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L') => AppState::List,
+            KeyCode::Down | KeyCode::Char('j') => AppState::ScriptOutput {
+                scroll: scroll.saturating_add(1).min(last_line),
+                follow: false,
+            },
+            KeyCode::Up | KeyCode::Char('k') => AppState::ScriptOutput {
+                scroll: scroll.saturating_sub(1),
+                follow: false,
+            },
+            KeyCode::Char('g') | KeyCode::Home => AppState::ScriptOutput {
+                scroll: 0,
+                follow: false,
+            },
+            KeyCode::Char('G') | KeyCode::End => AppState::ScriptOutput {
+                scroll: last_line,
+                follow: true,
+            },
+            _ => AppState::ScriptOutput { scroll, follow },
+        };
+    }
+
+    fn open_command_log(&mut self) {
+        if command_log::snapshot().is_empty() {
+            self.message = Some(AppMessage::info("No git commands run yet"));
+            return;
+        }
+        self.state = AppState::CommandLog {
+            scroll: 0,
+            follow: true,
+        };
+    }
+
+    fn handle_command_log_input(&mut self, code: KeyCode, scroll: u16, follow: bool) {
+        let last_line =
+            u16::try_from(command_log::lines().len().saturating_sub(1)).unwrap_or(u16::MAX);
+        // While following, the view starts at the tail
+        let scroll = if follow { last_line } else { scroll };
+        self.state = match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('@') => AppState::List,
+            KeyCode::Down | KeyCode::Char('j') => AppState::CommandLog {
+                scroll: scroll.saturating_add(1).min(last_line),
+                follow: false,
+            },
+            KeyCode::Up | KeyCode::Char('k') => AppState::CommandLog {
+                scroll: scroll.saturating_sub(1),
+                follow: false,
+            },
+            KeyCode::PageDown | KeyCode::Char('d') => AppState::CommandLog {
+                scroll: scroll.saturating_add(10).min(last_line),
+                follow: false,
+            },
+            KeyCode::PageUp | KeyCode::Char('u') => AppState::CommandLog {
+                scroll: scroll.saturating_sub(10),
+                follow: false,
+            },
+            KeyCode::Char('g') | KeyCode::Home => AppState::CommandLog {
+                scroll: 0,
+                follow: false,
+            },
+            KeyCode::Char('G') | KeyCode::End => AppState::CommandLog {
+                scroll: last_line,
+                follow: true,
+            },
+            _ => AppState::CommandLog { scroll, follow },
+        };
+    }
+
+    /// Branches of the delete targets that protected-branch rules keep on the remote
+    pub fn protected_delete_branches(&self) -> Vec<String> {
+        self.action_worktrees()
+            .iter()
+            .filter_map(|wt| wt.branch.clone())
+            .filter(|branch| self.config.is_protected_branch(branch))
+            .collect()
+    }
+
+    fn delete_selected_worktree(
+        &mut self,
+        delete_branch: bool,
+        force: bool,
+        delete_remote: bool,
+        keep_nested: bool,
+    ) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            self.state = AppState::List;
+            return;
+        }
+
+        let worktrees = self.action_worktrees();
+        if worktrees.is_empty() {
+            return;
+        }
+
+        if worktrees.iter().any(|wt| wt.is_bare) {
+            self.message = Some(AppMessage::error("Cannot delete bare repository"));
+            self.state = AppState::List;
+            return;
+        }
+
+        let dirty_worktrees: Vec<String> = worktrees
+            .iter()
+            .filter(|wt| wt.status != WorktreeStatus::Clean)
+            .map(Worktree::display_name)
+            .collect();
+        if !dirty_worktrees.is_empty() && !force {
+            let message = if worktrees.len() == 1 {
+                "Worktree has uncommitted changes. Press 'f' to enable force delete.".to_string()
+            } else {
+                format!(
+                    "Worktree has uncommitted changes. Press 'f' to enable force delete: {}",
+                    dirty_worktrees.join(", ")
+                )
+            };
+            self.message = Some(AppMessage::error(message));
+            return;
+        }
+
+        let display_name = batch_display_name(&worktrees, "worktree");
+        let display_name_for_thread = display_name.clone();
+        let display_name_for_state = display_name.clone();
+        let worktree_path_for_state = worktrees[0].path.clone();
+        let worktree_paths_for_state: Vec<PathBuf> =
+            worktrees.iter().map(|wt| wt.path.clone()).collect();
+
+        // Resolve remote targets before the local branch (and its tracking config) is gone
+        let remote_targets: Vec<Option<(String, String)>> = worktrees
+            .iter()
+            .map(|wt| {
+                let branch = wt.branch.as_deref().filter(|_| delete_remote)?;
+                if self.config.is_protected_branch(branch) {
+                    return None;
+                }
+                git::remote_branch_for(&self.bare_repo_path, branch)
+                    .filter(|(_, remote_branch)| !self.config.is_protected_branch(remote_branch))
+            })
+            .collect();
+        let skipped_protected = if delete_remote {
+            self.protected_delete_branches()
+        } else {
+            Vec::new()
+        };
+
+        let force_flag = if force { " --force" } else { "" };
+        let cmd_detail = worktrees
+            .iter()
+            .zip(&remote_targets)
+            .map(|(wt, remote_target)| {
+                let mut detail = format!(
+                    "git -C {} worktree remove{} {}",
+                    self.bare_repo_path.display(),
                     force_flag,
                     wt.path.display()
-                )
+                );
+                if let Some((remote, remote_branch)) = remote_target {
+                    detail.push_str(&format!(
+                        "\n$ git -C {} push {} --delete {}",
+                        self.bare_repo_path.display(),
+                        remote,
+                        remote_branch
+                    ));
+                }
+                detail
             })
             .collect::<Vec<_>>()
             .join("\n$ ");
@@ -1465,35 +4142,115 @@ impl App {
         self.message = Some(AppMessage::info(format!("Deleting: {}...", display_name)));
 
         let bare_repo_path = self.bare_repo_path.clone();
+        let project_root_path = self.project_root_path.clone();
         let (tx, rx) = mpsc::channel();
         std::thread::spawn(move || {
             let mut deleted = Vec::new();
             let mut failures = Vec::new();
+            let mut remote_deleted = Vec::new();
+            let mut kept_nested = Vec::new();
             let total = worktrees.len();
 
-            for wt in worktrees {
+            for (wt, remote_target) in worktrees.into_iter().zip(remote_targets) {
                 let name = wt.display_name();
+                let journal_id = match journal::begin(
+                    &project_root_path,
+                    journal::delete_entry(
+                        &bare_repo_path,
+                        &wt,
+                        force,
+                        delete_branch,
+                        remote_target.as_ref(),
+                    ),
+                ) {
+                    Ok(id) => id,
+                    Err(e) => {
+                        failures.push(format!("{}: not deleted, {}", name, e));
+                        continue;
+                    }
+                };
+                if keep_nested {
+                    let nested = nested_repos::find(&wt.path);
+                    match nested_repos::keep(&project_root_path, &wt.path, &name, &nested) {
+                        Ok(kept) => kept_nested.extend(kept),
+                        Err(e) => {
+                            failures.push(format!("{}: not deleted, {:#}", name, e));
+                            let _ = journal::finish(&project_root_path, &journal_id);
+                            continue;
+                        }
+                    }
+                }
                 match git::remove_worktree(&bare_repo_path, &wt.path, force) {
                     Ok(()) => {
+                        let _ = metadata::cleanup_location(&project_root_path, &wt.path);
                         deleted.push(wt.path.clone());
+                        let mut local_branch_kept = false;
                         if delete_branch {
                             if let Some(ref branch) = wt.branch {
                                 if let Err(e) = git::delete_branch(&bare_repo_path, branch, force) {
                                     failures.push(format!("{} branch delete failed: {}", name, e));
+                                    local_branch_kept = true;
+                                }
+                            }
+                        }
+                        match remote_target {
+                            // Never leave a local branch without its remote copy
+                            Some((remote, remote_branch)) if local_branch_kept => {
+                                failures.push(format!(
+                                "{} remote branch delete skipped: {}/{} kept with the local branch",
+                                name, remote, remote_branch
+                            ))
+                            }
+                            Some((remote, remote_branch)) => {
+                                match git::delete_remote_branch(
+                                    &bare_repo_path,
+                                    &remote,
+                                    &remote_branch,
+                                ) {
+                                    Ok(true) => {
+                                        remote_deleted.push(format!("{}/{}", remote, remote_branch))
+                                    }
+                                    Ok(false) => {}
+                                    Err(e) => failures.push(format!(
+                                        "{} remote branch delete failed: {}",
+                                        name, e
+                                    )),
                                 }
                             }
+                            None => {}
                         }
                     }
                     Err(e) => failures.push(format!("{}: {}", name, e)),
                 }
+                let _ = journal::finish(&project_root_path, &journal_id);
             }
 
             let message = if failures.is_empty() {
-                if total == 1 {
+                let mut message = if total == 1 {
                     format!("Deleted worktree: {}", display_name_for_thread)
                 } else {
                     format!("Deleted {} worktrees", deleted.len())
+                };
+                if !remote_deleted.is_empty() {
+                    message.push_str(&format!(" (remote: {})", remote_deleted.join(", ")));
                 }
+                if !kept_nested.is_empty() {
+                    message.push_str(&format!(
+                        "\nKept nested repositories: {}",
+                        kept_nested
+                            .iter()
+                            .map(|path| path.display().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                }
+                if !skipped_protected.is_empty() {
+                    message.push_str(&format!(
+                        "\nKept protected remote branch: {}",
+                        skipped_protected.join(", ")
+                    ));
+                }
+                message
             } else {
                 format!(
                     "Deleted {}/{} worktrees. Failed: {}",
@@ -1528,6 +4285,33 @@ impl App {
         });
     }
 
+    /// List what `git worktree prune` would remove and ask before pruning
+    fn preview_prune(&mut self) {
+        let cmd_detail = format!(
+            "git -C {} worktree prune --dry-run -v",
+            self.bare_repo_path.display()
+        );
+
+        match git::preview_prune_worktrees(&self.bare_repo_path) {
+            Ok(output) if output.is_empty() => {
+                self.message = Some(AppMessage::info("Nothing to prune"));
+            }
+            Ok(output) => {
+                self.state = AppState::ConfirmPrune {
+                    entries: output.lines().map(str::to_string).collect(),
+                };
+            }
+            Err(e) => {
+                let mut msg = format!("Prune failed: {}", e);
+                if self.verbose {
+                    msg = format!("{}\n$ {}", msg, cmd_detail);
+                    self.last_command_detail = Some(cmd_detail);
+                }
+                self.message = Some(AppMessage::error(msg));
+            }
+        }
+    }
+
     fn prune_worktrees(&mut self) {
         let cmd_detail = format!("git -C {} worktree prune -v", self.bare_repo_path.display());
 
@@ -1556,14 +4340,38 @@ impl App {
         }
     }
 
-    fn open_editor(&mut self) {
-        if let Some(wt) = self.selected_worktree() {
-            if wt.is_bare {
-                self.message = Some(AppMessage::error("Cannot open bare repository in editor"));
-                return;
+    /// `git worktree repair` for worktrees or a repository moved on disk
+    fn repair_worktrees(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+        match worktree_repair::repair(&self.bare_repo_path, &self.project_root_path) {
+            Ok(report) if report.is_empty() => {
+                self.message = Some(AppMessage::info("Repair completed: nothing to repair"));
             }
+            Ok(report) => {
+                self.message = Some(AppMessage::info(format!(
+                    "Repaired {} link(s): {}",
+                    report.len(),
+                    report.join("; ")
+                )));
+                self.refresh_worktrees();
+            }
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!("Repair failed: {}", e)));
+            }
+        }
+    }
 
-            let editor = self.config.get_editor();
+    fn open_editor(&mut self) {
+        if let Some(wt) = self.selected_worktree() {
+            if wt.is_bare {
+                self.message = Some(AppMessage::error("Cannot open bare repository in editor"));
+                return;
+            }
+
+            let editor = self.config.editor_for_branch(wt.branch.as_deref());
             let path = wt.path.clone();
 
             // We need to restore terminal before opening editor
@@ -1580,7 +4388,7 @@ impl App {
 
             match status {
                 Ok(s) if s.success() => {
-                    self.refresh_worktrees();
+                    self.resume_after_external(&path);
                 }
                 Ok(_) => {
                     self.message = Some(AppMessage::error("Editor exited with error"));
@@ -1592,6 +4400,45 @@ impl App {
         }
     }
 
+    /// Run the configured git UI (lazygit by default) in the selected worktree,
+    /// handing it the terminal until it exits.
+    fn open_git_ui(&mut self) {
+        if let Some(wt) = self.selected_worktree() {
+            if wt.is_bare {
+                self.message = Some(AppMessage::error("Cannot open bare repository in git UI"));
+                return;
+            }
+
+            let git_ui = self.config.get_git_ui();
+            let path = wt.path.clone();
+
+            let _ = crossterm::terminal::disable_raw_mode();
+            let _ =
+                crossterm::execute!(std::io::stdout(), crossterm::terminal::LeaveAlternateScreen);
+
+            let status = Command::new(&git_ui).current_dir(&path).status();
+
+            let _ = crossterm::terminal::enable_raw_mode();
+            let _ =
+                crossterm::execute!(std::io::stdout(), crossterm::terminal::EnterAlternateScreen);
+
+            match status {
+                Ok(s) if s.success() => {
+                    self.resume_after_external(&path);
+                }
+                Ok(_) => {
+                    self.message = Some(AppMessage::error(format!("{} exited with error", git_ui)));
+                }
+                Err(e) => {
+                    self.message = Some(AppMessage::error(format!(
+                        "Failed to open {}: {}",
+                        git_ui, e
+                    )));
+                }
+            }
+        }
+    }
+
     fn open_terminal(&mut self) {
         if let Some(wt) = self.selected_worktree() {
             if wt.is_bare {
@@ -1601,6 +4448,11 @@ impl App {
 
             let path = wt.path.clone();
             let terminal = self.config.get_terminal();
+            match terminal.as_deref() {
+                Some("tmux") => return self.open_in_tmux(),
+                Some("zellij") => return self.open_in_zellij(),
+                _ => {}
+            }
 
             #[cfg(target_os = "macos")]
             let result = {
@@ -1633,6 +4485,7 @@ impl App {
 
             match result {
                 Ok(s) if s.success() => {
+                    self.resume_after_external(&path);
                     self.message = Some(AppMessage::info("Opened terminal"));
                 }
                 Ok(_) => {
@@ -1646,6 +4499,61 @@ impl App {
         }
     }
 
+    /// Open the selected worktree in a tmux window (or, outside tmux, a
+    /// detached session) named after its branch.
+    fn open_in_tmux(&mut self) {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return;
+        };
+        if wt.is_bare {
+            self.message = Some(AppMessage::error("Cannot open bare repository in tmux"));
+            return;
+        }
+        let name = wt.branch.clone().unwrap_or_else(|| wt.display_name());
+        self.message = Some(match tmux::open_worktree_window(&wt.path, &name) {
+            Ok(tmux::TmuxTarget::Window(window)) => {
+                AppMessage::info(format!("Opened tmux window {}", window))
+            }
+            Ok(tmux::TmuxTarget::Session(session)) => AppMessage::info(format!(
+                "Opened tmux session {}; attach with: tmux attach -t '={}'",
+                session, session
+            )),
+            Err(e) => AppMessage::error(format!("Failed to open tmux: {}", e)),
+        });
+    }
+
+    /// Open the selected worktree in a new zellij tab named after its branch.
+    fn open_in_zellij(&mut self) {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return;
+        };
+        if wt.is_bare {
+            self.message = Some(AppMessage::error("Cannot open bare repository in zellij"));
+            return;
+        }
+        let name = wt.branch.clone().unwrap_or_else(|| wt.display_name());
+        self.message = Some(match zellij::open_worktree_tab(&wt.path, &name) {
+            Ok(()) => AppMessage::info(format!("Opened zellij tab {}", name)),
+            Err(e) => AppMessage::error(format!("Failed to open zellij: {}", e)),
+        });
+    }
+
+    /// Kill the git process of the running fetch, pull, push, or merge. The
+    /// operation then finishes as failed with "Canceled".
+    fn cancel_active_op(&mut self) {
+        let Some(cancel) = self.op_cancel.as_ref() else {
+            return;
+        };
+        cancel.cancel();
+        if let Some(info) = self.active_op_info.as_ref() {
+            self.message = Some(AppMessage::info(format!(
+                "Canceling {}: {}...",
+                info.kind.label(),
+                info.display_name
+            )));
+        }
+    }
+
     fn fetch_all(&mut self) {
         if self.active_op.is_some() {
             self.message = Some(AppMessage::error("Another operation is in progress"));
@@ -1667,15 +4575,21 @@ impl App {
         let worktree_path = wt.path.clone();
         let worktree_path_for_thread = worktree_path.clone();
         let worktree_path_for_state = worktree_path.clone();
-        let cmd_detail = format!("git -C {} fetch origin", worktree_path.display());
+        let cmd_detail = format!(
+            "git -C {} fetch {}",
+            worktree_path.display(),
+            self.worktree_remote(&wt).unwrap_or("origin")
+        );
         let cmd_detail_for_thread = cmd_detail.clone();
 
         self.state = AppState::List;
         self.message = Some(AppMessage::info(format!("Fetching: {}...", display_name)));
 
+        let cancel = git::CancelToken::with_timeout(self.config.get_git_timeout());
+        let cancel_for_thread = cancel.clone();
         let (tx, rx) = mpsc::channel();
         std::thread::spawn(move || {
-            let result = git::fetch_worktree(&worktree_path_for_thread);
+            let result = git::fetch_worktree(&worktree_path_for_thread, &cancel_for_thread);
             let _ = tx.send(OpResult {
                 kind: OpKind::Fetch,
                 success: result.is_ok(),
@@ -1691,6 +4605,7 @@ impl App {
         });
 
         self.active_op = Some((OpKind::Fetch, rx));
+        self.op_cancel = Some(cancel);
         self.active_op_info = Some(ActiveOp {
             kind: OpKind::Fetch,
             worktree_path: worktree_path_for_state.clone(),
@@ -1699,19 +4614,109 @@ impl App {
         });
     }
 
+    /// Fast-forward the default branch and every branch type base to their
+    /// remotes, then show what moved.
+    fn update_base_branches(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+
+        let default_branch =
+            git::get_default_branch(&self.bare_repo_path).unwrap_or_else(|_| "main".to_string());
+        let mut bases = vec![default_branch];
+        for base in self
+            .config
+            .branch_types
+            .iter()
+            .filter_map(|branch_type| branch_type.base.clone())
+        {
+            if !bases.contains(&base) {
+                bases.push(base);
+            }
+        }
+        let targets: Vec<(String, Option<PathBuf>)> = bases
+            .into_iter()
+            .map(|base| {
+                let worktree = self
+                    .worktrees
+                    .iter()
+                    .find(|wt| !wt.is_bare && wt.branch.as_deref() == Some(base.as_str()))
+                    .map(|wt| wt.path.clone());
+                (base, worktree)
+            })
+            .collect();
+
+        let display_name = targets
+            .iter()
+            .map(|(base, _)| base.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let worktree_paths: Vec<PathBuf> = targets
+            .iter()
+            .filter_map(|(_, worktree)| worktree.clone())
+            .collect();
+        let cmd_detail = format!(
+            "git -C {} fetch; git branch -f / git merge --ff-only for {}",
+            self.bare_repo_path.display(),
+            display_name
+        );
+
+        self.state = AppState::List;
+        self.message = Some(AppMessage::info(format!(
+            "Updating base branches: {}...",
+            display_name
+        )));
+
+        let bare_repo_path = self.bare_repo_path.clone();
+        let display_name_for_thread = display_name.clone();
+        let (updates_tx, updates_rx) = mpsc::channel();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let updates: Vec<BaseUpdate> = targets
+                .iter()
+                .map(|(base, worktree)| {
+                    git::update_base_branch(&bare_repo_path, base, worktree.as_deref())
+                })
+                .collect();
+            let moved = updates.iter().filter(|update| update.moved()).count();
+            let _ = updates_tx.send(updates);
+            let _ = tx.send(OpResult {
+                kind: OpKind::UpdateBases,
+                success: true,
+                message: format!("Updated {} base branch(es)", moved),
+                cmd_detail,
+                worktree_path: bare_repo_path,
+                affected_paths: Vec::new(),
+                display_name: display_name_for_thread,
+            });
+        });
+
+        self.base_update_receiver = Some(updates_rx);
+        self.active_op = Some((OpKind::UpdateBases, rx));
+        self.active_op_info = Some(ActiveOp {
+            kind: OpKind::UpdateBases,
+            worktree_path: self.bare_repo_path.clone(),
+            worktree_paths,
+            display_name,
+        });
+    }
+
     fn enter_worktree(&mut self) {
         if let Some(wt) = self.selected_worktree().cloned() {
             if wt.is_bare {
                 self.message = Some(AppMessage::error("Cannot enter bare repository"));
                 return;
             }
-            if self.config.tmux_worktree_mode {
-                if tmux::focus_pane_named(&wt.display_name()).unwrap_or(false) {
-                    self.exit_action = ExitAction::Quit;
-                    self.should_quit = true;
-                    return;
-                }
+            if self.config.tmux_worktree_mode
+                && tmux::focus_pane_named(&wt.display_name()).unwrap_or(false)
+            {
+                self.record_visit(&wt);
+                self.exit_action = ExitAction::Quit;
+                self.should_quit = true;
+                return;
             }
+            self.record_visit(&wt);
             // Always allow enter - even without shell integration, we print the path
             // The shell wrapper function (from `owt setup`) will handle the cd
             self.exit_action = ExitAction::ChangeDirectory(wt.path.clone());
@@ -1721,6 +4726,12 @@ impl App {
         }
     }
 
+    /// Add an enter to `.owt/history` for the frecency sort. A failed write
+    /// only loses the visit, so it does not stop the enter.
+    fn record_visit(&self, wt: &Worktree) {
+        let _ = history::record(&self.project_root_path, &wt.path);
+    }
+
     fn copy_path_to_clipboard(&mut self) {
         if let Some(wt) = self.selected_worktree() {
             let path_str = wt.path.to_string_lossy().to_string();
@@ -1815,13 +4826,20 @@ impl App {
             worktrees.iter().map(|wt| wt.path.clone()).collect();
         let cmd_detail = worktrees
             .iter()
-            .map(|wt| format!("git -C {} pull", wt.path.display()))
+            .map(|wt| match git::pull_target(&wt.path) {
+                Some((remote, branch)) => {
+                    format!("git -C {} pull {} {}", wt.path.display(), remote, branch)
+                }
+                None => format!("git -C {} pull", wt.path.display()),
+            })
             .collect::<Vec<_>>()
             .join("\n$ ");
 
         self.state = AppState::List;
         self.message = Some(AppMessage::info(format!("Pulling: {}...", display_name)));
 
+        let cancel = git::CancelToken::with_timeout(self.config.get_git_timeout());
+        let cancel_for_thread = cancel.clone();
         let (tx, rx) = mpsc::channel();
         std::thread::spawn(move || {
             let mut pulled = Vec::new();
@@ -1829,8 +4847,12 @@ impl App {
             let total = worktrees.len();
 
             for wt in worktrees {
+                if cancel_for_thread.is_canceled() {
+                    failures.push("Canceled".to_string());
+                    break;
+                }
                 let name = wt.display_name();
-                match git::pull_worktree(&wt.path) {
+                match git::pull_worktree(&wt.path, &cancel_for_thread) {
                     Ok(_) => pulled.push(wt.path.clone()),
                     Err(e) => failures.push(format!("{}: {}", name, e)),
                 }
@@ -1867,6 +4889,7 @@ impl App {
         });
 
         self.active_op = Some((OpKind::Pull, rx));
+        self.op_cancel = Some(cancel);
         self.active_op_info = Some(ActiveOp {
             kind: OpKind::Pull,
             worktree_path: worktree_path_for_state,
@@ -1875,175 +4898,505 @@ impl App {
         });
     }
 
-    fn push_worktree(&mut self) {
-        if self.active_op.is_some() {
-            self.message = Some(AppMessage::error("Another operation is in progress"));
-            return;
-        }
-
-        let wt = match self.selected_worktree().cloned() {
-            Some(wt) => wt,
-            Option::None => return,
+    fn open_describe_modal(&mut self) {
+        let branch = match self.selected_worktree() {
+            Some(wt) if wt.is_bare => return,
+            Some(wt) => match wt.branch.clone() {
+                Some(branch) => branch,
+                None => {
+                    self.message = Some(AppMessage::error("Cannot describe a detached HEAD"));
+                    return;
+                }
+            },
+            None => return,
         };
 
-        if wt.is_bare {
-            self.message = Some(AppMessage::error("Cannot push bare repository"));
-            return;
-        }
-
-        let display_name = wt.display_name();
-        let display_name_for_thread = display_name.clone();
-        let display_name_for_state = display_name.clone();
-        let worktree_path = wt.path.clone();
-        let worktree_path_for_thread = worktree_path.clone();
-        let worktree_path_for_state = worktree_path.clone();
-        let cmd_detail = format!("git -C {} push", worktree_path.display());
-        let cmd_detail_for_thread = cmd_detail.clone();
-
-        self.state = AppState::List;
-        self.message = Some(AppMessage::info(format!("Pushing: {}...", display_name)));
+        // The modal edits one line; git keeps whatever line breaks it had
+        self.input_buffer = git::branch_description(&self.bare_repo_path, &branch)
+            .map(|description| description.lines().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+        self.state = AppState::DescribeModal { branch };
+    }
 
-        let (tx, rx) = mpsc::channel();
-        std::thread::spawn(move || {
-            let result = git::push_worktree(&worktree_path_for_thread);
-            let message = match &result {
-                Ok(msg) => {
-                    if msg.is_empty() || msg.contains("Everything up-to-date") {
-                        "Push completed: Everything up-to-date".to_string()
-                    } else {
-                        format!("Push completed: {}", display_name_for_thread)
+    fn handle_describe_modal_input(&mut self, code: KeyCode, branch: String) {
+        match code {
+            KeyCode::Esc => {
+                self.state = AppState::List;
+                self.input_buffer.clear();
+            }
+            KeyCode::Enter => {
+                let description = std::mem::take(&mut self.input_buffer);
+                self.state = AppState::List;
+                match git::set_branch_description(&self.bare_repo_path, &branch, &description) {
+                    Ok(()) => {
+                        let action = if description.trim().is_empty() {
+                            "cleared"
+                        } else {
+                            "saved"
+                        };
+                        self.message = Some(AppMessage::info(format!(
+                            "Description {} for {}",
+                            action, branch
+                        )));
+                        self.update_selected_details();
+                    }
+                    Err(e) => {
+                        self.message = Some(AppMessage::error(format!("{}", e)));
                     }
                 }
-                Err(e) => format!("Push failed: {}", e),
-            };
-
-            let _ = tx.send(OpResult {
-                kind: OpKind::Push,
-                success: result.is_ok(),
-                message,
-                cmd_detail: cmd_detail_for_thread,
-                worktree_path: worktree_path_for_thread.clone(),
-                affected_paths: vec![worktree_path_for_thread.clone()],
-                display_name: display_name_for_thread,
-            });
-        });
-
-        self.active_op = Some((OpKind::Push, rx));
-        self.active_op_info = Some(ActiveOp {
-            kind: OpKind::Push,
-            worktree_path: worktree_path_for_state.clone(),
-            worktree_paths: vec![worktree_path_for_state.clone()],
-            display_name: display_name_for_state,
-        });
-    }
-
-    fn merge_upstream(&mut self) {
-        self.start_merge(None);
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
     }
 
-    fn open_merge_branch_select(&mut self) {
+    fn open_amend_modal(&mut self) {
         if self.active_op.is_some() {
             self.message = Some(AppMessage::error("Another operation is in progress"));
             return;
         }
-
-        let wt_info = self
-            .selected_worktree()
-            .map(|wt| (wt.is_bare, wt.status.clone()));
-
-        if let Some((is_bare, status)) = wt_info {
-            if is_bare {
-                self.message = Some(AppMessage::error("Cannot merge into bare repository"));
+        let worktree_path = match self.selected_worktree() {
+            Some(wt) if wt.is_bare => {
+                self.message = Some(AppMessage::error("Cannot amend in bare repository"));
                 return;
             }
-            if status != WorktreeStatus::Clean {
-                self.message = Some(AppMessage::error(
-                    "Cannot merge: worktree has uncommitted changes",
-                ));
+            Some(wt) => wt.path.clone(),
+            None => return,
+        };
+        let subject = match git::head_subject(&worktree_path) {
+            Ok(subject) => subject,
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!("{}", e)));
                 return;
             }
+        };
 
-            match git::list_local_branches(&self.bare_repo_path) {
-                Ok(branches) => {
-                    if branches.is_empty() {
-                        self.message = Some(AppMessage::error("No branches available to merge"));
-                        return;
+        self.input_buffer.clear();
+        self.state = AppState::AmendModal {
+            published: git::head_is_published(&worktree_path),
+            worktree_path,
+            subject,
+        };
+    }
+
+    fn handle_amend_modal_input(&mut self, code: KeyCode, worktree_path: PathBuf) {
+        match code {
+            KeyCode::Esc => {
+                self.state = AppState::List;
+                self.input_buffer.clear();
+            }
+            KeyCode::Enter => {
+                let message = std::mem::take(&mut self.input_buffer);
+                self.state = AppState::List;
+                match git::amend_head_commit(&worktree_path, Some(&message)) {
+                    Ok(()) => {
+                        let subject = git::head_subject(&worktree_path).unwrap_or_default();
+                        self.message = Some(AppMessage::info(format!("Amended HEAD: {}", subject)));
+                        if let Some(wt) = self
+                            .worktrees
+                            .iter_mut()
+                            .find(|wt| wt.path == worktree_path)
+                        {
+                            git::refresh_worktree_state(wt);
+                        }
+                        self.update_selected_details();
+                    }
+                    Err(e) => {
+                        self.message = Some(AppMessage::error(format!("{}", e)));
                     }
-                    self.state = AppState::MergeBranchSelect {
-                        branches,
-                        selected: 0,
-                    };
-                }
-                Err(e) => {
-                    self.message =
-                        Some(AppMessage::error(format!("Failed to list branches: {}", e)));
                 }
             }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
         }
     }
 
-    fn handle_merge_branch_select_input(
-        &mut self,
-        code: KeyCode,
-        branches: Vec<String>,
-        selected: usize,
-    ) {
+    fn open_discard_modal(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+        let (worktree_path, name) = match self.selected_worktree() {
+            Some(wt) if wt.is_bare => {
+                self.message = Some(AppMessage::error("Cannot discard in bare repository"));
+                return;
+            }
+            Some(wt) => (wt.path.clone(), wt.display_name()),
+            None => return,
+        };
+        let file_count = match git::list_changed_files(&worktree_path) {
+            Ok(files) => files.len(),
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!("{}", e)));
+                return;
+            }
+        };
+        if file_count == 0 && self.config.get_discard_mode() != DiscardMode::Ignored {
+            self.message = Some(AppMessage::info(format!("Nothing to discard in {}", name)));
+            return;
+        }
+
+        self.input_buffer.clear();
+        self.state = AppState::DiscardModal {
+            worktree_path,
+            name,
+            file_count,
+        };
+    }
+
+    /// Discards only once the typed text matches the worktree name exactly
+    fn handle_discard_modal_input(&mut self, code: KeyCode, worktree_path: PathBuf, name: String) {
         match code {
-            KeyCode::Esc | KeyCode::Char('q') => {
+            KeyCode::Esc => {
                 self.state = AppState::List;
+                self.input_buffer.clear();
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                let new_selected = if selected > 0 { selected - 1 } else { 0 };
-                self.state = AppState::MergeBranchSelect {
-                    branches,
-                    selected: new_selected,
-                };
+            KeyCode::Enter => {
+                if self.input_buffer != name {
+                    self.message = Some(AppMessage::error(format!(
+                        "Type {} to confirm, or Esc to cancel",
+                        name
+                    )));
+                    return;
+                }
+                self.input_buffer.clear();
+                self.state = AppState::List;
+                match git::discard_changes(&worktree_path, self.config.get_discard_mode()) {
+                    Ok(()) => {
+                        self.message =
+                            Some(AppMessage::info(format!("Discarded changes in {}", name)));
+                        self.resume_after_external(&worktree_path);
+                        self.update_selected_details();
+                    }
+                    Err(e) => {
+                        self.message = Some(AppMessage::error(format!("{}", e)));
+                    }
+                }
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                let new_selected = if selected < branches.len().saturating_sub(1) {
-                    selected + 1
-                } else {
-                    branches.len().saturating_sub(1)
-                };
-                self.state = AppState::MergeBranchSelect {
-                    branches,
-                    selected: new_selected,
-                };
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn open_note_modal(&mut self) {
+        let name = match self.selected_worktree() {
+            Some(wt) if wt.is_bare => return,
+            Some(wt) => wt.display_name(),
+            None => return,
+        };
+        self.input_buffer = self.metadata.notes.get(&name).cloned().unwrap_or_default();
+        self.state = AppState::NoteModal { name };
+    }
+
+    fn handle_note_modal_input(&mut self, code: KeyCode, name: String) {
+        match code {
+            KeyCode::Esc => {
+                self.state = AppState::List;
+                self.input_buffer.clear();
             }
             KeyCode::Enter => {
-                if let Some(branch) = branches.get(selected) {
-                    self.start_merge(Some(branch.clone()));
+                let note = std::mem::take(&mut self.input_buffer).trim().to_string();
+                self.state = AppState::List;
+                match Metadata::update(&self.project_root_path, |metadata| {
+                    if note.is_empty() {
+                        metadata.notes.remove(&name);
+                    } else {
+                        metadata.notes.insert(name.clone(), note.clone());
+                    }
+                }) {
+                    Ok(metadata) => {
+                        self.metadata = metadata;
+                        let action = if note.is_empty() { "cleared" } else { "saved" };
+                        self.message =
+                            Some(AppMessage::info(format!("Note {} for {}", action, name)));
+                    }
+                    Err(e) => {
+                        self.message =
+                            Some(AppMessage::error(format!("Failed to save note: {}", e)));
+                    }
                 }
             }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
             _ => {}
         }
     }
 
-    fn start_merge(&mut self, source_branch: Option<String>) {
+    fn open_rename_modal(&mut self) {
         if self.active_op.is_some() {
             self.message = Some(AppMessage::error("Another operation is in progress"));
-            self.state = AppState::List;
             return;
         }
 
-        let wt = match self.selected_worktree().cloned() {
-            Some(wt) => wt,
-            Option::None => {
-                self.state = AppState::List;
+        let branch = match self.selected_worktree() {
+            Some(wt) if wt.is_bare => {
+                self.message = Some(AppMessage::error("Cannot rename bare repository"));
                 return;
             }
+            Some(wt) => match wt.branch.clone() {
+                Some(branch) => branch,
+                None => {
+                    self.message = Some(AppMessage::error("Cannot rename a detached HEAD"));
+                    return;
+                }
+            },
+            None => return,
         };
 
-        if wt.is_bare {
-            self.message = Some(AppMessage::error("Cannot merge into bare repository"));
-            self.state = AppState::List;
+        // Only branches that were published get the push-the-rename option
+        let remote = git::branch_has_remote(&self.bare_repo_path, &branch)
+            .then(|| git::remote_branch_for(&self.bare_repo_path, &branch))
+            .flatten()
+            .map(|(remote, _)| remote);
+        self.input_buffer = branch;
+        self.state = AppState::RenameModal {
+            propagate: remote.is_some(),
+            remote,
+        };
+    }
+
+    /// Where the worktree folder moves on rename: only linked worktrees whose
+    /// path ends with the branch's folder name (the default layout, after
+    /// `worktree_naming`) follow the branch.
+    pub fn renamed_worktree_path(&self, wt: &Worktree, new_branch: &str) -> Option<PathBuf> {
+        let old_folder = self.config.worktree_folder(wt.branch.as_deref()?);
+        let is_main_worktree = !self.repo_is_bare && wt.path == self.bare_repo_path;
+        let is_launch_worktree = self.current_worktree_path.as_deref() == Some(wt.path.as_path());
+        if is_main_worktree || is_launch_worktree || !wt.path.ends_with(&old_folder) {
+            return None;
+        }
+
+        let depth = Path::new(&old_folder).components().count();
+        let root = wt.path.ancestors().nth(depth)?;
+        Some(root.join(self.config.worktree_folder(new_branch)))
+    }
+
+    fn rename_selected_branch(&mut self, propagate: bool, remote: Option<String>) {
+        self.state = AppState::List;
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
             return;
         }
-        if wt.status != WorktreeStatus::Clean {
-            self.message = Some(AppMessage::error(
-                "Cannot merge: worktree has uncommitted changes",
+
+        let new_branch = self.input_buffer.trim().to_string();
+        self.input_buffer.clear();
+        let wt = match self.selected_worktree().cloned() {
+            Some(wt) if !wt.is_bare => wt,
+            _ => return,
+        };
+        let Some(old_branch) = wt.branch.clone() else {
+            return;
+        };
+        if new_branch == old_branch {
+            self.message = Some(AppMessage::info("Branch name unchanged"));
+            return;
+        }
+
+        let new_path = self.renamed_worktree_path(&wt, &new_branch);
+        let remote_target = remote
+            .filter(|_| propagate)
+            .and_then(|_| git::remote_branch_for(&self.bare_repo_path, &old_branch));
+        // Deleting a PR head branch closes the PR, and protected names stay put
+        let keep_old_remote = match &remote_target {
+            Some((_, old_remote_branch)) => {
+                self.config.is_protected_branch(&old_branch)
+                    || self.config.is_protected_branch(old_remote_branch)
+                    || matches!(
+                        wt.github_pr_status,
+                        Some(GithubPrStatus::Open | GithubPrStatus::Draft)
+                    )
+            }
+            None => false,
+        };
+
+        let repo_path = self.bare_repo_path.clone();
+        let mut cmd_detail = format!(
+            "git -C {} branch -m {} {}",
+            repo_path.display(),
+            old_branch,
+            new_branch
+        );
+        if let Some(ref to) = new_path {
+            cmd_detail.push_str(&format!(
+                "\n$ git -C {} worktree move {} {}",
+                repo_path.display(),
+                wt.path.display(),
+                to.display()
             ));
-            self.state = AppState::List;
+        }
+        if let Some((ref remote, ref old_remote_branch)) = remote_target {
+            cmd_detail.push_str(&format!("\n$ git push -u {} {}", remote, new_branch));
+            cmd_detail.push_str(&format!(
+                "\n$ gh pr edit <open PRs based on {}> --base {}",
+                old_remote_branch, new_branch
+            ));
+            if !keep_old_remote {
+                cmd_detail.push_str(&format!(
+                    "\n$ git push {} --delete {}",
+                    remote, old_remote_branch
+                ));
+            }
+        }
+
+        let display_name = wt.display_name();
+        self.message = Some(AppMessage::info(format!(
+            "Renaming: {} -> {}...",
+            old_branch, new_branch
+        )));
+
+        let worktree_path = wt.path.clone();
+        let display_name_for_thread = display_name.clone();
+        let cmd_detail_for_thread = cmd_detail.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (success, message, final_path) = rename_branch_and_propagate(
+                &repo_path,
+                &worktree_path,
+                &old_branch,
+                &new_branch,
+                new_path,
+                remote_target,
+                keep_old_remote,
+            );
+            let _ = tx.send(OpResult {
+                kind: OpKind::Rename,
+                success,
+                message,
+                cmd_detail: cmd_detail_for_thread,
+                worktree_path: final_path.clone(),
+                affected_paths: vec![final_path],
+                display_name: display_name_for_thread,
+            });
+        });
+
+        self.active_op = Some((OpKind::Rename, rx));
+        self.active_op_info = Some(ActiveOp {
+            kind: OpKind::Rename,
+            worktree_path: wt.path.clone(),
+            worktree_paths: vec![wt.path],
+            display_name,
+        });
+    }
+
+    fn open_force_push_confirm(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+
+        match self.selected_worktree() {
+            Some(wt) if wt.is_bare => {
+                self.message = Some(AppMessage::error("Cannot push bare repository"));
+            }
+            Some(wt) if wt.branch.is_none() => {
+                self.message = Some(AppMessage::error("Cannot force push a detached HEAD"));
+            }
+            Some(_) => self.state = AppState::ConfirmForcePush,
+            None => {}
+        }
+    }
+
+    fn force_push_worktree(&mut self) {
+        self.state = AppState::List;
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+
+        let wt = match self.selected_worktree().cloned() {
+            Some(wt) if !wt.is_bare => wt,
+            _ => return,
+        };
+
+        let display_name = wt.display_name();
+        let display_name_for_thread = display_name.clone();
+        let worktree_path = wt.path.clone();
+        let worktree_path_for_thread = worktree_path.clone();
+        let cmd_detail = format!("git -C {} push --force-with-lease", worktree_path.display());
+        let cmd_detail_for_thread = cmd_detail.clone();
+
+        self.message = Some(AppMessage::info(format!(
+            "Force pushing: {}...",
+            display_name
+        )));
+
+        let journal_entry = JournalEntry::new(JournalOp::ForcePush, &worktree_path)
+            .branch(wt.branch.as_deref())
+            .previous_commit(git::resolve_commit(&worktree_path, "@{push}"))
+            .step(cmd_detail.clone());
+        let journal_id = match journal::begin(&self.project_root_path, journal_entry) {
+            Ok(id) => id,
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!("Force push not started: {}", e)));
+                return;
+            }
+        };
+        let project_root_path = self.project_root_path.clone();
+
+        let cancel = git::CancelToken::with_timeout(self.config.get_git_timeout());
+        let cancel_for_thread = cancel.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = git::force_push_worktree(&worktree_path_for_thread, &cancel_for_thread);
+            let _ = journal::finish(&project_root_path, &journal_id);
+            let message = match &result {
+                Ok(_) => format!("Force push completed: {}", display_name_for_thread),
+                Err(e) => format!("Force push failed: {}", e),
+            };
+
+            let _ = tx.send(OpResult {
+                kind: OpKind::Push,
+                success: result.is_ok(),
+                message,
+                cmd_detail: cmd_detail_for_thread,
+                worktree_path: worktree_path_for_thread.clone(),
+                affected_paths: vec![worktree_path_for_thread.clone()],
+                display_name: display_name_for_thread,
+            });
+        });
+
+        self.active_op = Some((OpKind::Push, rx));
+        self.op_cancel = Some(cancel);
+        self.active_op_info = Some(ActiveOp {
+            kind: OpKind::Push,
+            worktree_path: worktree_path.clone(),
+            worktree_paths: vec![worktree_path],
+            display_name,
+        });
+    }
+
+    fn push_worktree(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+
+        let wt = match self.selected_worktree().cloned() {
+            Some(wt) => wt,
+            Option::None => return,
+        };
+
+        if wt.is_bare {
+            self.message = Some(AppMessage::error("Cannot push bare repository"));
             return;
         }
 
@@ -2053,42 +5406,51 @@ impl App {
         let worktree_path = wt.path.clone();
         let worktree_path_for_thread = worktree_path.clone();
         let worktree_path_for_state = worktree_path.clone();
-        let source_branch_for_thread = source_branch.clone();
-
-        let cmd_detail = match source_branch.as_deref() {
-            Some(branch) => format!("git -C {} merge {}", worktree_path.display(), branch),
-            Option::None => format!("git -C {} merge @{{upstream}}", worktree_path.display()),
+        // New branches have no upstream yet; their first push sets it
+        let upstream_target = git::missing_upstream_push_target(&worktree_path);
+        let cmd_detail = match &upstream_target {
+            Some((remote, branch)) => format!(
+                "git -C {} push -u {} {}",
+                worktree_path.display(),
+                remote,
+                branch
+            ),
+            None => format!("git -C {} push", worktree_path.display()),
         };
         let cmd_detail_for_thread = cmd_detail.clone();
 
-        self.merge_source_branch = source_branch.clone();
         self.state = AppState::List;
-        self.message = Some(AppMessage::info(match source_branch.as_deref() {
-            Some(branch) => format!("Merging {}...", branch),
-            Option::None => format!("Merging upstream into {}...", display_name),
-        }));
+        self.message = Some(AppMessage::info(format!("Pushing: {}...", display_name)));
 
+        let cancel = git::CancelToken::with_timeout(self.config.get_git_timeout());
+        let cancel_for_thread = cancel.clone();
         let (tx, rx) = mpsc::channel();
         std::thread::spawn(move || {
-            let result = if let Some(source) = source_branch_for_thread {
-                git::merge_branch(&worktree_path_for_thread, &source)
-            } else {
-                git::merge_upstream(&worktree_path_for_thread)
+            let result = match &upstream_target {
+                Some((remote, branch)) => git::push_worktree_set_upstream(
+                    &worktree_path_for_thread,
+                    remote,
+                    branch,
+                    &cancel_for_thread,
+                ),
+                None => git::push_worktree(&worktree_path_for_thread, &cancel_for_thread),
             };
-
             let message = match &result {
+                Ok(msg) if upstream_target.is_some() => {
+                    format!("Push completed: {} ({})", display_name_for_thread, msg)
+                }
                 Ok(msg) => {
-                    if msg.is_empty() {
-                        "Merge completed".to_string()
+                    if msg.is_empty() || msg.contains("Everything up-to-date") {
+                        "Push completed: Everything up-to-date".to_string()
                     } else {
-                        format!("Merge completed: {}", msg)
+                        format!("Push completed: {}", display_name_for_thread)
                     }
                 }
-                Err(e) => format!("Merge failed: {}", e),
+                Err(e) => format!("Push failed: {}", e),
             };
 
             let _ = tx.send(OpResult {
-                kind: OpKind::Merge,
+                kind: OpKind::Push,
                 success: result.is_ok(),
                 message,
                 cmd_detail: cmd_detail_for_thread,
@@ -2098,1052 +5460,4891 @@ impl App {
             });
         });
 
-        self.active_op = Some((OpKind::Merge, rx));
+        self.active_op = Some((OpKind::Push, rx));
+        self.op_cancel = Some(cancel);
         self.active_op_info = Some(ActiveOp {
-            kind: OpKind::Merge,
+            kind: OpKind::Push,
             worktree_path: worktree_path_for_state.clone(),
             worktree_paths: vec![worktree_path_for_state.clone()],
             display_name: display_name_for_state,
         });
     }
-}
 
-#[cfg(test)]
-#[derive(Debug, PartialEq, Eq)]
-enum CopyFileOutcome {
-    Copied { file: String },
-    Warning { file: String, reason: String },
-}
-
-#[cfg(test)]
-fn copy_configured_files(
-    source: &Path,
-    destination: &Path,
-    files: &[String],
-) -> Vec<CopyFileOutcome> {
-    files
-        .iter()
-        .map(|file| copy_configured_file(source, destination, file))
-        .collect()
-}
-
-#[cfg(test)]
-fn copy_configured_file(source: &Path, destination: &Path, file: &str) -> CopyFileOutcome {
-    let src = source.join(file);
-    let dst = destination.join(file);
+    /// Run the configured ship steps on the selected worktree: push and PR in
+    /// the background, then the local steps in [`Self::finish_ship`].
+    /// Select every worktree `owt clean` would remove and open the delete
+    /// confirmation for them.
+    fn select_merged_worktrees(&mut self) {
+        let decisions = match worktree_clean::merged_worktrees(
+            &self.bare_repo_path,
+            &self.worktrees,
+            self.current_worktree_path.as_deref(),
+        ) {
+            Ok(decisions) => decisions,
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!(
+                    "Failed to find merged worktrees: {}",
+                    e
+                )));
+                return;
+            }
+        };
+        let targets: Vec<Worktree> = decisions
+            .into_iter()
+            .filter(|(_, decision)| *decision == CleanDecision::Merged)
+            .map(|(worktree, _)| worktree)
+            .collect();
+        if targets.is_empty() {
+            self.message = Some(AppMessage::error("No merged worktrees to clean"));
+            return;
+        }
 
-    if !src.exists() {
-        return CopyFileOutcome::Warning {
-            file: file.to_string(),
-            reason: format!("source file missing at {}", src.display()),
+        self.selected_worktree_paths = targets.iter().map(|wt| wt.path.clone()).collect();
+        self.delete_nested_repos = nested_repo_labels(&targets);
+        self.message = Some(AppMessage::info(format!(
+            "{} merged worktree(s) selected",
+            targets.len()
+        )));
+        self.state = AppState::ConfirmDelete {
+            delete_branch: false,
+            force: false,
+            delete_remote: false,
+            keep_nested: false,
         };
     }
 
-    if !src.is_file() {
-        return CopyFileOutcome::Warning {
-            file: file.to_string(),
-            reason: format!("source is not a file at {}", src.display()),
+    fn ship_selected(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return;
         };
+        if wt.is_bare {
+            self.message = Some(AppMessage::error("Cannot ship bare repository"));
+            return;
+        }
+        if wt.status != WorktreeStatus::Clean {
+            self.message = Some(AppMessage::error(
+                "Cannot ship: worktree has uncommitted changes",
+            ));
+            return;
+        }
+        let steps = ship::steps(&self.config);
+        if steps.is_empty() {
+            self.message = Some(AppMessage::error("No ship steps configured"));
+            return;
+        }
+
+        let display_name = wt.display_name();
+        let display_name_for_thread = display_name.clone();
+        let worktree_path = wt.path.clone();
+        let worktree_path_for_thread = worktree_path.clone();
+        let pr_exists = matches!(
+            wt.github_pr_status,
+            Some(GithubPrStatus::Open | GithubPrStatus::Draft)
+        );
+        let upstream_target = git::missing_upstream_push_target(&worktree_path);
+        let cmd_detail = steps
+            .iter()
+            .filter_map(|step| match step {
+                ShipStep::Push => Some(match &upstream_target {
+                    Some((remote, branch)) => format!(
+                        "git -C {} push -u {} {}",
+                        worktree_path.display(),
+                        remote,
+                        branch
+                    ),
+                    None => format!("git -C {} push", worktree_path.display()),
+                }),
+                ShipStep::Pr if !pr_exists => Some(format!(
+                    "cd {} && gh pr create --fill",
+                    worktree_path.display()
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n$ ");
+        let steps_for_thread = steps.clone();
+
+        self.state = AppState::List;
+        self.message = Some(AppMessage::info(format!("Shipping: {}...", display_name)));
+
+        let cancel = git::CancelToken::with_timeout(self.config.get_git_timeout());
+        let (tx, rx) = mpsc::channel();
+        let (progress_tx, progress_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let steps = steps_for_thread;
+            let mut completed = Vec::new();
+            let mut notes = Vec::new();
+            for (index, step) in steps.iter().enumerate() {
+                if !step.is_remote() {
+                    continue;
+                }
+                let _ = progress_tx.send(format!(
+                    "Ship {}/{}: {} {}...",
+                    index + 1,
+                    steps.len(),
+                    step.label(),
+                    display_name_for_thread
+                ));
+                let result = match step {
+                    ShipStep::Push => match &upstream_target {
+                        Some((remote, branch)) => git::push_worktree_set_upstream(
+                            &worktree_path_for_thread,
+                            remote,
+                            branch,
+                            &cancel,
+                        ),
+                        None => git::push_worktree(&worktree_path_for_thread, &cancel),
+                    }
+                    .map(|_| None),
+                    ShipStep::Pr if pr_exists => Ok(Some("PR already open".to_string())),
+                    ShipStep::Pr => git::create_github_pr(&worktree_path_for_thread).map(Some),
+                    ShipStep::Review | ShipStep::Main => Ok(None),
+                };
+                match result {
+                    Ok(note) => {
+                        completed.push(*step);
+                        notes.extend(note.filter(|note| !note.is_empty()));
+                    }
+                    Err(e) => {
+                        let _ = tx.send(OpResult {
+                            kind: OpKind::Ship,
+                            success: false,
+                            message: ship::failure_message(
+                                &steps,
+                                *step,
+                                &completed,
+                                &e.to_string(),
+                            ),
+                            cmd_detail,
+                            worktree_path: worktree_path_for_thread.clone(),
+                            affected_paths: vec![worktree_path_for_thread.clone()],
+                            display_name: display_name_for_thread,
+                        });
+                        return;
+                    }
+                }
+            }
+
+            let mut message = format!("Shipped: {}", display_name_for_thread);
+            for note in notes {
+                message.push_str(&format!("\n{}", note));
+            }
+            let _ = tx.send(OpResult {
+                kind: OpKind::Ship,
+                success: true,
+                message,
+                cmd_detail,
+                worktree_path: worktree_path_for_thread.clone(),
+                affected_paths: vec![worktree_path_for_thread],
+                display_name: display_name_for_thread,
+            });
+        });
+
+        self.ship_steps = steps;
+        self.ship_progress_receiver = Some(progress_rx);
+        self.active_op = Some((OpKind::Ship, rx));
+        self.active_op_info = Some(ActiveOp {
+            kind: OpKind::Ship,
+            worktree_path: worktree_path.clone(),
+            worktree_paths: vec![worktree_path],
+            display_name,
+        });
     }
 
-    if let Some(parent) = dst.parent() {
-        if let Err(error) = fs::create_dir_all(parent) {
-            return CopyFileOutcome::Warning {
-                file: file.to_string(),
-                reason: format!(
-                    "could not create destination parent {}: {}",
-                    parent.display(),
-                    error
-                ),
+    /// Local ship steps, run after push and PR succeeded. A failure names the
+    /// step that stopped and what was kept.
+    fn finish_ship(&mut self, worktree_path: &Path) -> Result<(), String> {
+        let steps = std::mem::take(&mut self.ship_steps);
+        self.ship_progress_receiver = None;
+        let mut completed: Vec<ShipStep> = steps
+            .iter()
+            .copied()
+            .filter(|step| step.is_remote())
+            .collect();
+        let name = worktree_name_from_path(worktree_path);
+
+        for step in steps.iter().copied().filter(|step| !step.is_remote()) {
+            let result = match step {
+                ShipStep::Review => Metadata::update(&self.project_root_path, |metadata| {
+                    metadata.review.insert(name.clone());
+                })
+                .map(|metadata| self.metadata = metadata),
+                ShipStep::Main => {
+                    let default_branch = git::get_default_branch(&self.bare_repo_path)
+                        .unwrap_or_else(|_| "main".to_string());
+                    match self
+                        .worktrees
+                        .iter()
+                        .find(|wt| !wt.is_bare && wt.branch.as_deref() == Some(&default_branch))
+                        .map(|wt| wt.path.clone())
+                    {
+                        Some(main_path) => {
+                            self.select_worktree_path(&main_path);
+                            Ok(())
+                        }
+                        None => Err(anyhow::anyhow!(
+                            "no worktree has {} checked out",
+                            default_branch
+                        )),
+                    }
+                }
+                ShipStep::Push | ShipStep::Pr => Ok(()),
             };
+            if let Err(e) = result {
+                return Err(ship::failure_message(
+                    &steps,
+                    step,
+                    &completed,
+                    &e.to_string(),
+                ));
+            }
+            completed.push(step);
         }
+        Ok(())
     }
 
-    match fs::copy(&src, &dst) {
-        Ok(_) => CopyFileOutcome::Copied {
-            file: file.to_string(),
-        },
-        Err(error) => CopyFileOutcome::Warning {
-            file: file.to_string(),
-            reason: format!("could not copy to {}: {}", dst.display(), error),
-        },
+    fn merge_upstream(&mut self) {
+        self.start_merge(None);
     }
-}
-
-#[cfg(test)]
-fn append_copy_warnings(message: String, outcomes: &[CopyFileOutcome]) -> String {
-    let warnings: Vec<String> = outcomes
-        .iter()
-        .filter_map(|outcome| match outcome {
-            CopyFileOutcome::Warning { file, reason } => Some(format!("{} ({})", file, reason)),
-            CopyFileOutcome::Copied { .. } => None,
-        })
-        .collect();
 
-    if warnings.is_empty() {
-        message
-    } else {
-        format!("{}\nCopy warnings:\n- {}", message, warnings.join("\n- "))
+    fn open_changed_files(&mut self) {
+        let Some(wt) = self.selected_worktree() else {
+            return;
+        };
+        if wt.is_bare {
+            self.message = Some(AppMessage::error("Bare repository has no files to view"));
+            return;
+        }
+        let worktree_path = wt.path.clone();
+        match git::list_changed_files(&worktree_path) {
+            Ok(files) if files.is_empty() => {
+                self.message = Some(AppMessage::info("No changed files"));
+            }
+            Ok(files) => {
+                self.state = AppState::ChangedFiles {
+                    worktree_path,
+                    files,
+                    selected: 0,
+                };
+            }
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!(
+                    "Failed to list changed files: {}",
+                    e
+                )));
+            }
+        }
     }
-}
 
-fn batch_display_name(worktrees: &[Worktree], singular: &str) -> String {
-    match worktrees {
-        [] => format!("0 {}s", singular),
-        [wt] => wt.display_name(),
-        _ => format!("{} {}s", worktrees.len(), singular),
+    fn handle_changed_files_input(
+        &mut self,
+        code: KeyCode,
+        worktree_path: PathBuf,
+        files: Vec<ChangedFile>,
+        selected: usize,
+    ) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = AppState::List;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.state = AppState::ChangedFiles {
+                    worktree_path,
+                    files,
+                    selected: selected.saturating_sub(1),
+                };
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let selected = (selected + 1).min(files.len().saturating_sub(1));
+                self.state = AppState::ChangedFiles {
+                    worktree_path,
+                    files,
+                    selected,
+                };
+            }
+            KeyCode::Enter => {
+                let Some(file) = files.get(selected) else {
+                    return;
+                };
+                if file.is_deleted() {
+                    self.message = Some(AppMessage::error(format!(
+                        "{} was deleted; nothing to preview",
+                        file.path
+                    )));
+                    return;
+                }
+                self.start_file_preview(&worktree_path, &file.path);
+                self.state = AppState::FilePreview {
+                    worktree_path,
+                    files,
+                    selected,
+                    scroll: 0,
+                };
+            }
+            _ => {}
+        }
     }
-}
 
-fn shell_quote(path: &std::path::Path) -> String {
-    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
-}
+    /// Load and highlight a changed file for the pager in the background, so
+    /// a large file does not freeze the list
+    fn start_file_preview(&mut self, worktree_path: &Path, relative: &str) {
+        let worktree_path = worktree_path.to_path_buf();
+        let relative = relative.to_string();
+        let light = self.theme.is_light;
+        self.file_preview = None;
 
-fn worktree_name_from_path(path: &Path) -> String {
-    path.file_name()
-        .map(|name| name.to_string_lossy().to_string())
-        .filter(|name| !name.is_empty())
-        .unwrap_or_else(|| "worktree".to_string())
-}
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = preview::load(&worktree_path, &relative, light)
+                .map_err(|error| format!("{:#}", error));
+            let _ = tx.send((worktree_path.join(relative), result));
+        });
+        self.file_preview_receiver = Some(rx);
+    }
+
+    /// Show the loaded file, unless the pager was closed in the meantime; a
+    /// file that cannot be previewed returns to the changed files list
+    fn poll_file_preview(&mut self) {
+        let Some(rx) = self.file_preview_receiver.as_ref() else {
+            return;
+        };
+        let (path, result) = match rx.try_recv() {
+            Ok(done) => done,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.file_preview_receiver = None;
+                return;
+            }
+        };
+        self.file_preview_receiver = None;
+        let AppState::FilePreview {
+            worktree_path,
+            files,
+            selected,
+            ..
+        } = &self.state
+        else {
+            return;
+        };
+        if files
+            .get(*selected)
+            .map(|file| worktree_path.join(&file.path))
+            != Some(path)
+        {
+            return;
+        }
+        match result {
+            Ok(loaded) => self.file_preview = Some(loaded),
+            Err(error) => {
+                self.message = Some(AppMessage::error(error));
+                self.state = AppState::ChangedFiles {
+                    worktree_path: worktree_path.clone(),
+                    files: files.clone(),
+                    selected: *selected,
+                };
+            }
+        }
+    }
 
-fn paths_refer_to_same_location(left: &Path, right: &Path) -> bool {
-    if left == right {
-        return true;
+    fn handle_file_preview_input(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        worktree_path: PathBuf,
+        files: Vec<ChangedFile>,
+        selected: usize,
+        scroll: u16,
+    ) {
+        let line_count = self
+            .file_preview
+            .as_ref()
+            .map_or(0, |preview| preview.lines.len());
+        let last_line = u16::try_from(line_count.saturating_sub(1)).unwrap_or(u16::MAX);
+        let page = self.viewport_height.get().max(1);
+        let scroll = match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.file_preview = None;
+                self.file_preview_receiver = None;
+                self.state = AppState::ChangedFiles {
+                    worktree_path,
+                    files,
+                    selected,
+                };
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('j') => scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => scroll.saturating_sub(1),
+            KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+                scroll.saturating_add(page / 2)
+            }
+            KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+                scroll.saturating_sub(page / 2)
+            }
+            KeyCode::PageDown | KeyCode::Char(' ') => scroll.saturating_add(page),
+            KeyCode::PageUp => scroll.saturating_sub(page),
+            KeyCode::Char('g') | KeyCode::Home => 0,
+            KeyCode::Char('G') | KeyCode::End => last_line,
+            _ => scroll,
+        };
+        self.state = AppState::FilePreview {
+            worktree_path,
+            files,
+            selected,
+            scroll: scroll.min(last_line),
+        };
     }
 
-    match (left.canonicalize(), right.canonicalize()) {
-        (Ok(left), Ok(right)) => left == right,
-        _ => false,
+    fn open_git_log(&mut self) {
+        let Some(wt) = self.selected_worktree() else {
+            return;
+        };
+        if wt.is_bare {
+            self.message = Some(AppMessage::error("Bare repository has no log to view"));
+            return;
+        }
+        let worktree_path = wt.path.clone();
+        match git::log_commits(&worktree_path, GIT_LOG_LIMIT) {
+            Ok(commits) if commits.is_empty() => {
+                self.message = Some(AppMessage::info("No commits yet"));
+            }
+            Ok(commits) => {
+                self.state = AppState::GitLog {
+                    worktree_path,
+                    commits,
+                    selected: 0,
+                };
+            }
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!("{:#}", e)));
+            }
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::sync::{Mutex, OnceLock};
-    use std::time::{Duration as StdDuration, Instant, SystemTime, UNIX_EPOCH};
+    fn handle_git_log_input(
+        &mut self,
+        code: KeyCode,
+        worktree_path: PathBuf,
+        commits: Vec<LogCommit>,
+        selected: usize,
+    ) {
+        let last = commits.len().saturating_sub(1);
+        let page = usize::from(self.viewport_height.get().max(1));
+        let selected = match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = AppState::List;
+                return;
+            }
+            KeyCode::Enter => {
+                let Some(commit) = commits.get(selected) else {
+                    return;
+                };
+                match git::show_commit(&worktree_path, &commit.hash) {
+                    Ok(lines) => {
+                        self.commit_detail = lines;
+                        self.state = AppState::CommitDetail {
+                            worktree_path,
+                            commits,
+                            selected,
+                            scroll: 0,
+                        };
+                    }
+                    Err(e) => {
+                        self.message = Some(AppMessage::error(format!("{:#}", e)));
+                    }
+                }
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('j') => selected + 1,
+            KeyCode::Up | KeyCode::Char('k') => selected.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => selected + page,
+            KeyCode::PageUp => selected.saturating_sub(page),
+            KeyCode::Char('g') | KeyCode::Home => 0,
+            KeyCode::Char('G') | KeyCode::End => last,
+            _ => selected,
+        };
+        self.state = AppState::GitLog {
+            worktree_path,
+            commits,
+            selected: selected.min(last),
+        };
+    }
+
+    fn handle_commit_detail_input(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        worktree_path: PathBuf,
+        commits: Vec<LogCommit>,
+        selected: usize,
+        scroll: u16,
+    ) {
+        let last_line =
+            u16::try_from(self.commit_detail.len().saturating_sub(1)).unwrap_or(u16::MAX);
+        let page = self.viewport_height.get().max(1);
+        let scroll = match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.commit_detail.clear();
+                self.state = AppState::GitLog {
+                    worktree_path,
+                    commits,
+                    selected,
+                };
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('j') => scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => scroll.saturating_sub(1),
+            KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+                scroll.saturating_add(page / 2)
+            }
+            KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+                scroll.saturating_sub(page / 2)
+            }
+            KeyCode::PageDown | KeyCode::Char(' ') => scroll.saturating_add(page),
+            KeyCode::PageUp => scroll.saturating_sub(page),
+            KeyCode::Char('g') | KeyCode::Home => 0,
+            KeyCode::Char('G') | KeyCode::End => last_line,
+            _ => scroll,
+        };
+        self.state = AppState::CommitDetail {
+            worktree_path,
+            commits,
+            selected,
+            scroll: scroll.min(last_line),
+        };
+    }
+
+    fn open_merge_branch_select(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+
+        let wt_info = self
+            .selected_worktree()
+            .map(|wt| (wt.is_bare, wt.status.clone()));
+
+        if let Some((is_bare, status)) = wt_info {
+            if is_bare {
+                self.message = Some(AppMessage::error("Cannot merge into bare repository"));
+                return;
+            }
+            if status != WorktreeStatus::Clean {
+                self.message = Some(AppMessage::error(
+                    "Cannot merge: worktree has uncommitted changes",
+                ));
+                return;
+            }
+
+            match git::list_local_branches(&self.bare_repo_path) {
+                Ok(branches) => {
+                    if branches.is_empty() {
+                        self.message = Some(AppMessage::error("No branches available to merge"));
+                        return;
+                    }
+                    self.state = AppState::MergeBranchSelect {
+                        branches,
+                        selected: 0,
+                    };
+                }
+                Err(e) => {
+                    self.message =
+                        Some(AppMessage::error(format!("Failed to list branches: {}", e)));
+                }
+            }
+        }
+    }
+
+    /// Offer every remote branch as the selected branch's upstream, starting
+    /// at the current upstream or at the same-named branch of its remote.
+    fn open_upstream_select(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+        let branch = match self.selected_worktree() {
+            Some(wt) if wt.is_bare => {
+                self.message = Some(AppMessage::error("Bare repository has no upstream"));
+                return;
+            }
+            Some(wt) => match wt.branch.clone() {
+                Some(branch) => branch,
+                None => {
+                    self.message =
+                        Some(AppMessage::error("Cannot set upstream on a detached HEAD"));
+                    return;
+                }
+            },
+            None => return,
+        };
+
+        let branches = match git::list_remote_branches(&self.bare_repo_path) {
+            Ok(branches) if branches.is_empty() => {
+                self.message = Some(AppMessage::error(
+                    "No remote branches; fetch with f or push with P first",
+                ));
+                return;
+            }
+            Ok(branches) => branches,
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!("{}", e)));
+                return;
+            }
+        };
+        let remote = self
+            .branch_remotes
+            .get(&branch)
+            .cloned()
+            .or_else(|| git::default_remote(&self.bare_repo_path))
+            .unwrap_or_else(|| "origin".to_string());
+        let selected = self
+            .branch_upstreams
+            .get(&branch)
+            .and_then(|upstream| branches.iter().position(|b| b == upstream))
+            .or_else(|| {
+                let same_name = format!("{}/{}", remote, branch);
+                branches.iter().position(|b| *b == same_name)
+            })
+            .unwrap_or(0);
+        self.state = AppState::UpstreamSelect { branches, selected };
+    }
+
+    /// Browse remote-tracking branches and create a tracking worktree for one
+    fn open_remote_branches(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+        match git::list_remote_branches(&self.bare_repo_path) {
+            Ok(branches) => self.remote_branches = branches,
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!("{}", e)));
+                return;
+            }
+        }
+        self.state = AppState::RemoteBranches {
+            filter: String::new(),
+            selected: 0,
+        };
+        if self.remote_branches.is_empty() {
+            self.start_remote_fetch();
+        }
+    }
+
+    /// Remote branches matching `filter`, best fuzzy match first
+    pub(crate) fn filtered_remote_branches(&self, filter: &str) -> Vec<&str> {
+        if filter.is_empty() {
+            return self.remote_branches.iter().map(String::as_str).collect();
+        }
+        let mut matches: Vec<(i64, &str)> = self
+            .remote_branches
+            .iter()
+            .filter_map(|branch| Some((fuzzy::score(filter, branch)?, branch.as_str())))
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        matches.into_iter().map(|(_, branch)| branch).collect()
+    }
+
+    fn start_remote_fetch(&mut self) {
+        if self.remote_fetch_receiver.is_some() {
+            return;
+        }
+        let bare_repo_path = self.bare_repo_path.clone();
+        let cancel = git::CancelToken::with_timeout(self.config.get_git_timeout());
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = git::fetch_all_remotes(&bare_repo_path, &cancel)
+                .and_then(|()| git::list_remote_branches(&bare_repo_path))
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+        self.remote_fetch_receiver = Some(rx);
+    }
+
+    fn poll_remote_fetch(&mut self) {
+        let Some(rx) = self.remote_fetch_receiver.as_ref() else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => Err("Fetch stopped unexpectedly".to_string()),
+        };
+        self.remote_fetch_receiver = None;
+        match result {
+            Ok(branches) => {
+                self.message = Some(AppMessage::info(format!(
+                    "Fetched {} remote branch(es)",
+                    branches.len()
+                )));
+                self.remote_branches = branches;
+                if let AppState::RemoteBranches { filter, selected } = &self.state {
+                    let last = self
+                        .filtered_remote_branches(filter)
+                        .len()
+                        .saturating_sub(1);
+                    self.state = AppState::RemoteBranches {
+                        filter: filter.clone(),
+                        selected: (*selected).min(last),
+                    };
+                }
+            }
+            Err(e) => self.message = Some(AppMessage::error(e)),
+        }
+    }
+
+    fn handle_remote_branches_input(
+        &mut self,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        mut filter: String,
+        selected: usize,
+    ) {
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        let selected = match code {
+            KeyCode::Esc => {
+                self.state = AppState::List;
+                return;
+            }
+            KeyCode::Enter => {
+                let Some(remote_branch) = self
+                    .filtered_remote_branches(&filter)
+                    .get(selected)
+                    .map(|branch| branch.to_string())
+                else {
+                    return;
+                };
+                let branch = remote_branch
+                    .split_once('/')
+                    .map_or(remote_branch.as_str(), |(_, branch)| branch);
+                self.state = AppState::List;
+                self.input_buffer = branch.to_string();
+                self.add_path_override = None;
+                self.queue_worktree_create_after_exit();
+                return;
+            }
+            KeyCode::Char('f') if ctrl => {
+                self.start_remote_fetch();
+                selected
+            }
+            KeyCode::Char('n') if ctrl => selected + 1,
+            KeyCode::Char('p') if ctrl => selected.saturating_sub(1),
+            KeyCode::Down => selected + 1,
+            KeyCode::Up => selected.saturating_sub(1),
+            KeyCode::Backspace => {
+                filter.pop();
+                0
+            }
+            KeyCode::Char(c) if !ctrl => {
+                filter.push(c);
+                0
+            }
+            _ => selected,
+        };
+        let last = self
+            .filtered_remote_branches(&filter)
+            .len()
+            .saturating_sub(1);
+        self.state = AppState::RemoteBranches {
+            filter,
+            selected: selected.min(last),
+        };
+    }
+
+    /// Local branches without a worktree, left behind by deleting worktrees
+    /// but not their branches
+    fn open_orphan_branches(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+        let branches = match self.orphan_branches() {
+            Ok(branches) if branches.is_empty() => {
+                self.message = Some(AppMessage::info("Every local branch has a worktree"));
+                return;
+            }
+            Ok(branches) => branches,
+            Err(e) => {
+                self.message = Some(AppMessage::error(format!("{}", e)));
+                return;
+            }
+        };
+        self.state = AppState::OrphanBranches {
+            branches,
+            selected: 0,
+        };
+    }
+
+    /// Ask how to duplicate the selected worktree at its current HEAD
+    fn open_duplicate_worktree(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+        let Some(wt) = self.selected_worktree().filter(|wt| !wt.is_bare) else {
+            return;
+        };
+        let Some(commit) = git::resolve_commit(&wt.path, "HEAD") else {
+            self.message = Some(AppMessage::error(format!(
+                "{} has no commit to duplicate",
+                wt.display_name()
+            )));
+            return;
+        };
+        self.state = AppState::DuplicateWorktree {
+            commit,
+            new_branch: false,
+        };
+    }
+
+    /// Branch (with `new_branch`) and path for a duplicate of `wt`: the
+    /// first free `<name>-copy`, `<name>-copy-2`, ...
+    pub(crate) fn duplicate_target(
+        &self,
+        wt: &Worktree,
+        new_branch: bool,
+    ) -> (Option<String>, PathBuf) {
+        let base = match wt.branch.as_deref() {
+            Some(branch) if new_branch => branch.to_string(),
+            _ => wt.display_name(),
+        };
+        for n in 1.. {
+            let name = match n {
+                1 => format!("{}-copy", base),
+                n => format!("{}-copy-{}", base, n),
+            };
+            let path = self.worktree_path_for_branch(&name);
+            let taken = path.exists()
+                || self.worktrees.iter().any(|other| other.path == path)
+                || (new_branch && self.branch_heads.contains_key(&name));
+            if !taken {
+                return (new_branch.then_some(name), path);
+            }
+        }
+        unreachable!("there is always a free -copy name")
+    }
+
+    fn handle_duplicate_input(&mut self, code: KeyCode, commit: String, new_branch: bool) {
+        let has_branch = self
+            .selected_worktree()
+            .is_some_and(|wt| wt.branch.is_some());
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('q') => {
+                self.state = AppState::List;
+            }
+            KeyCode::Char('b') if has_branch => {
+                self.state = AppState::DuplicateWorktree {
+                    commit,
+                    new_branch: !new_branch,
+                };
+            }
+            KeyCode::Enter | KeyCode::Char('y') => {
+                self.state = AppState::List;
+                self.duplicate_selected_worktree(commit, new_branch && has_branch);
+            }
+            _ => {}
+        }
+    }
+
+    /// Add the duplicate in the background, reported like any new worktree
+    fn duplicate_selected_worktree(&mut self, commit: String, new_branch: bool) {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return;
+        };
+        let (branch, target) = self.duplicate_target(&wt, new_branch);
+        let repo_path = self.bare_repo_path.clone();
+        let short = commit.chars().take(7).collect::<String>();
+        let cmd_detail = match &branch {
+            Some(branch) => format!(
+                "git -C {} worktree add -b {} {} {}",
+                repo_path.display(),
+                branch,
+                target.display(),
+                short
+            ),
+            None => format!(
+                "git -C {} worktree add --detach {} {}",
+                repo_path.display(),
+                target.display(),
+                short
+            ),
+        };
+        let display_name = worktree_name_from_path(&target);
+        self.message = Some(AppMessage::info(format!(
+            "Duplicating {} at {}...",
+            wt.display_name(),
+            short
+        )));
+
+        let source_name = wt.display_name();
+        let submodule_update = self.config.submodule_update.unwrap_or(false);
+        let lfs_cancel = self
+            .config
+            .lfs_pull
+            .unwrap_or(false)
+            .then(git::CancelToken::default);
+        let lfs_cancel_for_thread = lfs_cancel.clone();
+        let worktree_path = target.clone();
+        let display_name_for_thread = display_name.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = match &branch {
+                Some(branch) => {
+                    git::add_worktree(&repo_path, branch, &worktree_path, Some(&commit))
+                }
+                None => git::add_detached_worktree(&repo_path, &worktree_path, &commit),
+            };
+            let (success, message) = match result {
+                Ok(()) => (
+                    true,
+                    append_lfs_pull(
+                        append_submodule_update(
+                            format!(
+                                "Duplicated {} at {} into {} (uncommitted changes stay behind)",
+                                source_name,
+                                short,
+                                worktree_path.display()
+                            ),
+                            submodule_update,
+                            &worktree_path,
+                        ),
+                        lfs_cancel_for_thread.as_ref(),
+                        &worktree_path,
+                    ),
+                ),
+                Err(e) => (false, format!("{}", e)),
+            };
+            let _ = tx.send(OpResult {
+                kind: OpKind::Add,
+                success,
+                message,
+                cmd_detail,
+                worktree_path: worktree_path.clone(),
+                affected_paths: vec![worktree_path],
+                display_name: display_name_for_thread,
+            });
+        });
+
+        self.active_op = Some((OpKind::Add, rx));
+        self.op_cancel = lfs_cancel;
+        self.active_op_info = Some(ActiveOp {
+            kind: OpKind::Add,
+            worktree_path: wt.path.clone(),
+            worktree_paths: vec![wt.path],
+            display_name,
+        });
+    }
+
+    /// Remove the targeted worktrees but keep their branches, recording each
+    /// in the archive so `K` can add it back later.
+    fn archive_selected_worktrees(&mut self) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            return;
+        }
+        let mut archived = Vec::new();
+        let mut failures = Vec::new();
+        for worktree in self.action_worktrees() {
+            match archive::archive(&self.bare_repo_path, &self.project_root_path, &worktree) {
+                Ok(_) => archived.push(worktree.display_name()),
+                Err(e) => failures.push(format!("{}", e)),
+            }
+        }
+        if !archived.is_empty() {
+            self.selected_worktree_paths.clear();
+            self.refresh_worktrees();
+            self.metadata = Metadata::load(&self.project_root_path).unwrap_or_default();
+        }
+        self.message = Some(if failures.is_empty() {
+            AppMessage::info(format!("Archived {} (K restores)", archived.join(", ")))
+        } else if archived.is_empty() {
+            AppMessage::error(failures.join("; "))
+        } else {
+            AppMessage::error(format!(
+                "Archived {}; {}",
+                archived.join(", "),
+                failures.join("; ")
+            ))
+        });
+    }
+
+    fn open_archived_worktrees(&mut self) {
+        match archive::load(&self.project_root_path) {
+            Ok(entries) if entries.is_empty() => {
+                self.message = Some(AppMessage::info(
+                    "No archived worktrees (archive with a in the delete dialog)",
+                ));
+            }
+            Ok(mut entries) => {
+                entries.reverse();
+                self.state = AppState::ArchivedWorktrees {
+                    entries,
+                    selected: 0,
+                };
+            }
+            Err(e) => self.message = Some(AppMessage::error(format!("{}", e))),
+        }
+    }
+
+    fn handle_archived_worktrees_input(
+        &mut self,
+        code: KeyCode,
+        mut entries: Vec<ArchiveEntry>,
+        selected: usize,
+    ) {
+        let last = entries.len().saturating_sub(1);
+        let selected = match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = AppState::List;
+                return;
+            }
+            KeyCode::Enter => {
+                let Some(entry) = entries.get(selected).cloned() else {
+                    return;
+                };
+                if self.active_op.is_some() {
+                    self.message = Some(AppMessage::error("Another operation is in progress"));
+                    return;
+                }
+                match archive::restore(&self.bare_repo_path, &self.project_root_path, &entry) {
+                    Ok(()) => {
+                        self.state = AppState::List;
+                        self.metadata = Metadata::load(&self.project_root_path).unwrap_or_default();
+                        self.refresh_worktrees();
+                        self.select_worktree_path(&entry.path);
+                        self.message = Some(AppMessage::info(format!("Restored {}", entry.name())));
+                        return;
+                    }
+                    Err(e) => {
+                        self.message = Some(AppMessage::error(format!("{}", e)));
+                        selected
+                    }
+                }
+            }
+            KeyCode::Char('d') => {
+                let Some(entry) = entries.get(selected).cloned() else {
+                    return;
+                };
+                match archive::forget(&self.project_root_path, &entry) {
+                    Ok(()) => {
+                        self.message = Some(AppMessage::info(format!(
+                            "Removed {} from the archive; branch {} is kept",
+                            entry.name(),
+                            entry.branch
+                        )));
+                        entries.remove(selected);
+                    }
+                    Err(e) => self.message = Some(AppMessage::error(format!("{}", e))),
+                }
+                if entries.is_empty() {
+                    self.state = AppState::List;
+                    return;
+                }
+                selected
+            }
+            KeyCode::Down | KeyCode::Char('j') => selected + 1,
+            KeyCode::Up | KeyCode::Char('k') => selected.saturating_sub(1),
+            KeyCode::Char('g') | KeyCode::Home => 0,
+            KeyCode::Char('G') | KeyCode::End => last,
+            _ => selected,
+        };
+        let last = entries.len().saturating_sub(1);
+        self.state = AppState::ArchivedWorktrees {
+            entries,
+            selected: selected.min(last),
+        };
+    }
+
+    fn orphan_branches(&self) -> Result<Vec<String>> {
+        let checked_out: HashSet<&str> = self
+            .worktrees
+            .iter()
+            .filter_map(|wt| wt.branch.as_deref())
+            .collect();
+        Ok(git::list_local_branches(&self.bare_repo_path)?
+            .into_iter()
+            .filter(|branch| !checked_out.contains(branch.as_str()))
+            .collect())
+    }
+
+    fn handle_orphan_branches_input(
+        &mut self,
+        code: KeyCode,
+        mut branches: Vec<String>,
+        selected: usize,
+    ) {
+        let last = branches.len().saturating_sub(1);
+        let selected = match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = AppState::List;
+                return;
+            }
+            KeyCode::Enter | KeyCode::Char('a') => {
+                self.state = AppState::List;
+                let Some(branch) = branches.get(selected) else {
+                    return;
+                };
+                self.input_buffer = branch.clone();
+                self.add_path_override = None;
+                self.queue_worktree_create_after_exit();
+                return;
+            }
+            KeyCode::Char(c @ ('d' | 'D')) => {
+                let Some(branch) = branches.get(selected).cloned() else {
+                    return;
+                };
+                if self.config.is_protected_branch(&branch) {
+                    self.message = Some(AppMessage::error(format!(
+                        "{} is protected; delete it with git",
+                        branch
+                    )));
+                    return;
+                }
+                match git::delete_branch(&self.bare_repo_path, &branch, c == 'D') {
+                    Ok(()) => {
+                        self.message = Some(AppMessage::info(format!("Deleted branch {}", branch)));
+                        branches.remove(selected);
+                    }
+                    Err(e) => {
+                        let hint = if c == 'd' { " (D forces)" } else { "" };
+                        self.message = Some(AppMessage::error(format!("{}{}", e, hint)));
+                    }
+                }
+                if branches.is_empty() {
+                    self.state = AppState::List;
+                    return;
+                }
+                selected
+            }
+            KeyCode::Down | KeyCode::Char('j') => selected + 1,
+            KeyCode::Up | KeyCode::Char('k') => selected.saturating_sub(1),
+            KeyCode::Char('g') | KeyCode::Home => 0,
+            KeyCode::Char('G') | KeyCode::End => last,
+            _ => selected,
+        };
+        let last = branches.len().saturating_sub(1);
+        self.state = AppState::OrphanBranches {
+            branches,
+            selected: selected.min(last),
+        };
+    }
+
+    fn handle_upstream_select_input(
+        &mut self,
+        code: KeyCode,
+        branches: Vec<String>,
+        selected: usize,
+    ) {
+        let last = branches.len().saturating_sub(1);
+        let selected = match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = AppState::List;
+                return;
+            }
+            KeyCode::Enter => {
+                self.state = AppState::List;
+                let Some(upstream) = branches.get(selected) else {
+                    return;
+                };
+                let Some(wt) = self.selected_worktree().cloned() else {
+                    return;
+                };
+                let branch = wt.branch.clone().unwrap_or_default();
+                match git::set_upstream(&wt.path, upstream) {
+                    Ok(()) => {
+                        self.branch_upstreams
+                            .insert(branch.clone(), upstream.clone());
+                        if let Some(wt) = self.worktrees.iter_mut().find(|w| w.path == wt.path) {
+                            git::refresh_worktree_state(wt);
+                        }
+                        self.message = Some(AppMessage::info(format!(
+                            "Upstream of {} set to {}",
+                            branch, upstream
+                        )));
+                    }
+                    Err(e) => {
+                        self.message = Some(AppMessage::error(format!("{}", e)));
+                    }
+                }
+                return;
+            }
+            KeyCode::Down | KeyCode::Char('j') => selected + 1,
+            KeyCode::Up | KeyCode::Char('k') => selected.saturating_sub(1),
+            KeyCode::Char('g') | KeyCode::Home => 0,
+            KeyCode::Char('G') | KeyCode::End => last,
+            _ => selected,
+        };
+        self.state = AppState::UpstreamSelect {
+            branches,
+            selected: selected.min(last),
+        };
+    }
+
+    fn handle_merge_branch_select_input(
+        &mut self,
+        code: KeyCode,
+        branches: Vec<String>,
+        selected: usize,
+    ) {
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = AppState::List;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                let new_selected = if selected > 0 { selected - 1 } else { 0 };
+                self.state = AppState::MergeBranchSelect {
+                    branches,
+                    selected: new_selected,
+                };
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let new_selected = if selected < branches.len().saturating_sub(1) {
+                    selected + 1
+                } else {
+                    branches.len().saturating_sub(1)
+                };
+                self.state = AppState::MergeBranchSelect {
+                    branches,
+                    selected: new_selected,
+                };
+            }
+            KeyCode::Enter => {
+                if let Some(branch) = branches.get(selected) {
+                    self.start_merge(Some(branch.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn start_merge(&mut self, source_branch: Option<String>) {
+        if self.active_op.is_some() {
+            self.message = Some(AppMessage::error("Another operation is in progress"));
+            self.state = AppState::List;
+            return;
+        }
+
+        let wt = match self.selected_worktree().cloned() {
+            Some(wt) => wt,
+            Option::None => {
+                self.state = AppState::List;
+                return;
+            }
+        };
+
+        if wt.is_bare {
+            self.message = Some(AppMessage::error("Cannot merge into bare repository"));
+            self.state = AppState::List;
+            return;
+        }
+        if wt.status != WorktreeStatus::Clean {
+            self.message = Some(AppMessage::error(
+                "Cannot merge: worktree has uncommitted changes",
+            ));
+            self.state = AppState::List;
+            return;
+        }
+
+        let display_name = wt.display_name();
+        let display_name_for_thread = display_name.clone();
+        let display_name_for_state = display_name.clone();
+        let worktree_path = wt.path.clone();
+        let worktree_path_for_thread = worktree_path.clone();
+        let worktree_path_for_state = worktree_path.clone();
+        let source_branch_for_thread = source_branch.clone();
+
+        let cmd_detail = match source_branch.as_deref() {
+            Some(branch) => format!("git -C {} merge {}", worktree_path.display(), branch),
+            Option::None => format!("git -C {} merge @{{upstream}}", worktree_path.display()),
+        };
+        let cmd_detail_for_thread = cmd_detail.clone();
+
+        self.merge_source_branch = source_branch.clone();
+        self.state = AppState::List;
+        self.message = Some(AppMessage::info(match source_branch.as_deref() {
+            Some(branch) => format!("Merging {}...", branch),
+            Option::None => format!("Merging upstream into {}...", display_name),
+        }));
+
+        let cancel = git::CancelToken::with_timeout(self.config.get_git_timeout());
+        let cancel_for_thread = cancel.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = if let Some(source) = source_branch_for_thread {
+                git::merge_branch(&worktree_path_for_thread, &source, &cancel_for_thread)
+            } else {
+                git::merge_upstream(&worktree_path_for_thread, &cancel_for_thread)
+            };
+
+            let message = match &result {
+                Ok(msg) => {
+                    if msg.is_empty() {
+                        "Merge completed".to_string()
+                    } else {
+                        format!("Merge completed: {}", msg)
+                    }
+                }
+                Err(e) => format!("Merge failed: {}", e),
+            };
+
+            let _ = tx.send(OpResult {
+                kind: OpKind::Merge,
+                success: result.is_ok(),
+                message,
+                cmd_detail: cmd_detail_for_thread,
+                worktree_path: worktree_path_for_thread.clone(),
+                affected_paths: vec![worktree_path_for_thread.clone()],
+                display_name: display_name_for_thread,
+            });
+        });
+
+        self.active_op = Some((OpKind::Merge, rx));
+        self.op_cancel = Some(cancel);
+        self.active_op_info = Some(ActiveOp {
+            kind: OpKind::Merge,
+            worktree_path: worktree_path_for_state.clone(),
+            worktree_paths: vec![worktree_path_for_state.clone()],
+            display_name: display_name_for_state,
+        });
+    }
+}
+
+/// Nested repositories in `targets`, prefixed with the worktree name when
+/// more than one worktree is being deleted.
+fn nested_repo_labels(targets: &[Worktree]) -> Vec<String> {
+    targets
+        .iter()
+        .flat_map(|wt| {
+            let name = wt.display_name();
+            let prefix_name = targets.len() > 1;
+            nested_repos::find(&wt.path)
+                .into_iter()
+                .map(move |relative| {
+                    if prefix_name {
+                        format!("{}/{}", name, relative.display())
+                    } else {
+                        relative.display().to_string()
+                    }
+                })
+        })
+        .collect()
+}
+
+/// Complete the last component of a directory path as far as the existing
+/// directories agree, adding `/` once a single directory matches.
+fn complete_directory(input: &str) -> Option<String> {
+    let (dir_part, prefix) = match input.rfind('/') {
+        Some(index) => input.split_at(index + 1),
+        None => ("", input),
+    };
+    let dir = if dir_part.is_empty() {
+        PathBuf::from(".")
+    } else {
+        crate::config::expand_home_path(dir_part)
+    };
+    let mut matches: Vec<String> = fs::read_dir(&dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.'))
+        })
+        .collect();
+    matches.sort();
+
+    let completed = match matches.as_slice() {
+        [] => return None,
+        [only] => format!("{}/", only),
+        [first, rest @ ..] => {
+            let mut common = first.clone();
+            for name in rest {
+                while !name.starts_with(common.as_str()) {
+                    common.pop();
+                }
+            }
+            common
+        }
+    };
+    (completed.len() > prefix.len()).then(|| format!("{}{}", dir_part, completed))
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq, Eq)]
+enum CopyFileOutcome {
+    Copied { file: String },
+    Warning { file: String, reason: String },
+}
+
+#[cfg(test)]
+fn copy_configured_files(
+    source: &Path,
+    destination: &Path,
+    files: &[String],
+) -> Vec<CopyFileOutcome> {
+    files
+        .iter()
+        .map(|file| copy_configured_file(source, destination, file))
+        .collect()
+}
+
+#[cfg(test)]
+fn copy_configured_file(source: &Path, destination: &Path, file: &str) -> CopyFileOutcome {
+    let src = source.join(file);
+    let dst = destination.join(file);
+
+    if !src.exists() {
+        return CopyFileOutcome::Warning {
+            file: file.to_string(),
+            reason: format!("source file missing at {}", src.display()),
+        };
+    }
+
+    if !src.is_file() {
+        return CopyFileOutcome::Warning {
+            file: file.to_string(),
+            reason: format!("source is not a file at {}", src.display()),
+        };
+    }
+
+    if let Some(parent) = dst.parent() {
+        if let Err(error) = fs::create_dir_all(parent) {
+            return CopyFileOutcome::Warning {
+                file: file.to_string(),
+                reason: format!(
+                    "could not create destination parent {}: {}",
+                    parent.display(),
+                    error
+                ),
+            };
+        }
+    }
+
+    match fs::copy(&src, &dst) {
+        Ok(_) => CopyFileOutcome::Copied {
+            file: file.to_string(),
+        },
+        Err(error) => CopyFileOutcome::Warning {
+            file: file.to_string(),
+            reason: format!("could not copy to {}: {}", dst.display(), error),
+        },
+    }
+}
+
+#[cfg(test)]
+fn append_copy_warnings(message: String, outcomes: &[CopyFileOutcome]) -> String {
+    let warnings: Vec<String> = outcomes
+        .iter()
+        .filter_map(|outcome| match outcome {
+            CopyFileOutcome::Warning { file, reason } => Some(format!("{} ({})", file, reason)),
+            CopyFileOutcome::Copied { .. } => None,
+        })
+        .collect();
+
+    if warnings.is_empty() {
+        message
+    } else {
+        format!("{}\nCopy warnings:\n- {}", message, warnings.join("\n- "))
+    }
+}
+
+/// Check out the submodules of a new worktree when `submodule_update` is on.
+/// A failure is reported after `message`; the worktree is kept either way.
+fn append_submodule_update(message: String, enabled: bool, worktree_path: &Path) -> String {
+    if !enabled {
+        return message;
+    }
+    match git::update_submodules(worktree_path) {
+        Ok(true) => format!("{}\nSubmodules updated", message),
+        Ok(false) => message,
+        Err(error) => format!("{}\nSubmodule warning: {}", message, error),
+    }
+}
+
+/// Pull Git LFS objects into a new worktree when `lfs_pull` is on (`cancel`
+/// is set), reporting a failure after `message` like a submodule update.
+fn append_lfs_pull(
+    message: String,
+    cancel: Option<&git::CancelToken>,
+    worktree_path: &Path,
+) -> String {
+    let Some(cancel) = cancel else {
+        return message;
+    };
+    match git::lfs_pull(worktree_path, cancel) {
+        Ok(true) => format!("{}\nLFS objects pulled", message),
+        Ok(false) => message,
+        Err(error) => format!("{}\nLFS warning: {}", message, error),
+    }
+}
+
+/// Rename a branch, move its worktree folder, and optionally carry the rename
+/// to the remote. Returns (success, message, final worktree path); the local
+/// rename is kept even when a later step fails.
+fn rename_branch_and_propagate(
+    repo_path: &Path,
+    worktree_path: &Path,
+    old_branch: &str,
+    new_branch: &str,
+    new_path: Option<PathBuf>,
+    remote_target: Option<(String, String)>,
+    keep_old_remote: bool,
+) -> (bool, String, PathBuf) {
+    if let Err(e) = git::rename_branch(repo_path, old_branch, new_branch) {
+        return (false, e.to_string(), worktree_path.to_path_buf());
+    }
+
+    let mut notes = Vec::new();
+    let mut failures = Vec::new();
+    let mut final_path = worktree_path.to_path_buf();
+    if let Some(to) = new_path {
+        match git::move_worktree(repo_path, worktree_path, &to) {
+            Ok(()) => {
+                notes.push(format!("moved to {}", to.display()));
+                final_path = to;
+            }
+            Err(e) => failures.push(e.to_string()),
+        }
+    }
+
+    if let Some((remote, old_remote_branch)) = remote_target {
+        match git::push_worktree_set_upstream(
+            &final_path,
+            &remote,
+            new_branch,
+            &git::CancelToken::default(),
+        ) {
+            Ok(upstream) => {
+                notes.push(upstream);
+                match git::retarget_github_pr_bases(repo_path, &old_remote_branch, new_branch) {
+                    Ok(prs) if !prs.is_empty() => {
+                        notes.push(format!("retargeted PR #{}", prs.join(", #")))
+                    }
+                    Ok(_) => {}
+                    Err(e) => failures.push(e.to_string()),
+                }
+                if keep_old_remote {
+                    notes.push(format!("kept {}/{}", remote, old_remote_branch));
+                } else {
+                    match git::delete_remote_branch(repo_path, &remote, &old_remote_branch) {
+                        Ok(true) => notes.push(format!("deleted {}/{}", remote, old_remote_branch)),
+                        Ok(false) => {}
+                        Err(e) => failures.push(e.to_string()),
+                    }
+                }
+            }
+            Err(e) => failures.push(format!("Push failed: {}", e)),
+        }
+    }
+
+    let mut message = format!("Renamed branch: {} -> {}", old_branch, new_branch);
+    if !notes.is_empty() {
+        message.push_str(&format!(" ({})", notes.join("; ")));
+    }
+    if !failures.is_empty() {
+        message.push_str(&format!("\nWarnings: {}", failures.join("; ")));
+    }
+    (failures.is_empty(), message, final_path)
+}
+
+fn batch_display_name(worktrees: &[Worktree], singular: &str) -> String {
+    match worktrees {
+        [] => format!("0 {}s", singular),
+        [wt] => wt.display_name(),
+        _ => format!("{} {}s", worktrees.len(), singular),
+    }
+}
+
+fn shell_quote(path: &std::path::Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+fn worktree_name_from_path(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "worktree".to_string())
+}
+
+/// Whether `path` is `root` or inside it, comparing resolved paths when both exist
+fn path_is_within(path: &Path, root: &Path) -> bool {
+    match (path.canonicalize(), root.canonicalize()) {
+        (Ok(path), Ok(root)) => path.starts_with(root),
+        _ => path.starts_with(root),
+    }
+}
+
+/// Forward each line read from `source` as script output until it closes.
+fn stream_lines(
+    source: impl std::io::Read + Send + 'static,
+    tx: mpsc::Sender<ScriptEvent>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        use std::io::BufRead;
+        for line in std::io::BufReader::new(source).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if tx.send(ScriptEvent::Output(line)).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git_cmd, temp_dir};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::{Duration as StdDuration, Instant};
+
+    static ENV_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+    fn env_lock() -> &'static Mutex<()> {
+        ENV_LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    #[cfg(unix)]
+    fn make_executable(path: &Path) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut permissions = fs::metadata(path).unwrap().permissions();
+        permissions.set_mode(0o755);
+        fs::set_permissions(path, permissions).unwrap();
+    }
+
+    #[cfg(not(unix))]
+    fn make_executable(_path: &Path) {}
+
+    fn assert_git_success(output: std::process::Output, context: &str) {
+        assert!(
+            output.status.success(),
+            "{}: {}",
+            context,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn create_test_project(base: &Path) -> (PathBuf, PathBuf) {
+        let source_path = base.join("source");
+        let bare_path = base.join("repo").join(".bare");
+        let main_path = base.join("repo").join("main");
+
+        fs::create_dir_all(&source_path).unwrap();
+        assert_git_success(
+            git_cmd()
+                .current_dir(&source_path)
+                .args(["init", "-b", "main"])
+                .output()
+                .unwrap(),
+            "git init failed",
+        );
+        assert_git_success(
+            git_cmd()
+                .current_dir(&source_path)
+                .args(["config", "user.email", "test@test.com"])
+                .output()
+                .unwrap(),
+            "git config user.email failed",
+        );
+        assert_git_success(
+            git_cmd()
+                .current_dir(&source_path)
+                .args(["config", "user.name", "Test"])
+                .output()
+                .unwrap(),
+            "git config user.name failed",
+        );
+
+        fs::write(source_path.join("README.md"), "# Test\n").unwrap();
+        assert_git_success(
+            git_cmd()
+                .current_dir(&source_path)
+                .args(["add", "."])
+                .output()
+                .unwrap(),
+            "git add failed",
+        );
+        assert_git_success(
+            git_cmd()
+                .current_dir(&source_path)
+                .args(["commit", "-m", "initial"])
+                .output()
+                .unwrap(),
+            "git commit failed",
+        );
+        assert_git_success(
+            git_cmd()
+                .args([
+                    "clone",
+                    "--bare",
+                    &source_path.to_string_lossy(),
+                    &bare_path.to_string_lossy(),
+                ])
+                .output()
+                .unwrap(),
+            "git clone --bare failed",
+        );
+        assert_git_success(
+            git_cmd()
+                .args([
+                    "-C",
+                    &bare_path.to_string_lossy(),
+                    "worktree",
+                    "add",
+                    &main_path.to_string_lossy(),
+                    "main",
+                ])
+                .output()
+                .unwrap(),
+            "git worktree add main failed",
+        );
+
+        (bare_path, main_path)
+    }
+
+    fn wait_for_background_op(app: &mut App) {
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while app.active_op.is_some() {
+            app.poll_background_op();
+            if app.active_op.is_none() {
+                break;
+            }
+            assert!(Instant::now() < deadline, "background operation timed out");
+            std::thread::sleep(StdDuration::from_millis(20));
+        }
+    }
+
+    fn test_app(worktrees: Vec<Worktree>, selected_index: usize, bare_repo_path: &str) -> App {
+        App {
+            worktrees,
+            selected_index,
+            selected_worktree_paths: HashSet::new(),
+            state: AppState::List,
+            message: None,
+            bare_repo_path: PathBuf::from(bare_repo_path),
+            project_root_path: PathBuf::from("/repo"),
+            repo_is_bare: true,
+            input_buffer: String::new(),
+            should_quit: false,
+            config: Config::default(),
+            exit_action: ExitAction::Quit,
+            current_worktree_path: None,
+            merge_source_branch: None,
+            has_shell_integration: false,
+            filter_text: String::new(),
+            status_filter: StatusFilter::All,
+            hide_unmatched: false,
+            is_filtering: false,
+            last_key: None,
+            sort_mode: SortMode::default(),
+            sort_reversed: false,
+            verbose: false,
+            last_command_detail: None,
+            spinner_tick: 0,
+            theme: crate::ui::theme::detect_theme(),
+            spinner: Spinner::from_config(&Config::default()),
+            needs_clear: false,
+            viewport_height: Cell::new(0),
+            table_offset: Cell::new(0),
+            help_scroll_offset: 0,
+            script_status: ScriptStatus::Idle,
+            script_receiver: None,
+            script_output: Vec::new(),
+            bulk_run: HashMap::new(),
+            clone_receiver: None,
+            clone_progress: None,
+            issue_lookup_receiver: None,
+            pr_status_receiver: None,
+            active_op: None,
+            active_op_info: None,
+            op_cancel: None,
+            selected_details: None,
+            add_base_branch: "main".to_string(),
+            base_comparisons: HashMap::new(),
+            branch_suggestions: Vec::new(),
+            add_path_override: None,
+            add_editing_path: false,
+            delete_nested_repos: Vec::new(),
+            file_preview: None,
+            file_preview_receiver: None,
+            commit_detail: Vec::new(),
+            base_comparison_receiver: None,
+            base_update_receiver: None,
+            metadata: Metadata::default(),
+            usage_scores: HashMap::new(),
+            switcher_repositories: Vec::new(),
+            branch_remotes: HashMap::new(),
+            branch_upstreams: HashMap::new(),
+            remote_branches: Vec::new(),
+            remote_fetch_receiver: None,
+            fs_watcher: None,
+            fs_watch_paths: Vec::new(),
+            auto_fetch_receiver: None,
+            last_auto_fetch: None,
+            show_upstream: false,
+            branch_heads: HashMap::new(),
+            show_head_commit: false,
+            default_remote: None,
+            show_disk_usage: false,
+            disk_usage: HashMap::new(),
+            disk_usage_receiver: None,
+            merged_branches: HashSet::new(),
+            merged_receiver: None,
+            refresh_receiver: None,
+            last_auto_refresh: None,
+            ship_steps: Vec::new(),
+            ship_progress_receiver: None,
+            ascii_badges: false,
+            events: None,
+        }
+    }
+
+    fn test_worktree(name: &str, status: WorktreeStatus) -> Worktree {
+        Worktree {
+            path: PathBuf::from(format!("/repo/{}", name)),
+            branch: Some(name.to_string()),
+            is_bare: false,
+            status,
+            last_commit_time: None,
+            ahead_behind: None,
+            github_pr_status: None,
+            locked: false,
+        }
+    }
+
+    #[test]
+    fn preselect_selects_exact_branch_or_falls_back_to_filter() {
+        let mut app = test_app(
+            vec![
+                test_worktree("main", WorktreeStatus::Clean),
+                test_worktree("feature-login", WorktreeStatus::Clean),
+                test_worktree("feature-logout", WorktreeStatus::Clean),
+            ],
+            0,
+            "/repo/.bare",
+        );
+
+        app.preselect("feature-logout");
+        assert_eq!(app.selected_index, 2);
+        assert!(app.filter_text.is_empty());
+
+        app.preselect("/repo/main");
+        assert_eq!(app.selected_index, 0);
+
+        app.preselect("login");
+        assert_eq!(
+            app.selected_worktree().unwrap().display_name(),
+            "feature-login"
+        );
+        assert_eq!(app.filter_text, "login");
+    }
+
+    #[test]
+    fn config_modal_tmux_auto_run_is_read_only_project_modal() {
+        let mut app = test_app(vec![], 0, "/repo/.bare");
+
+        app.handle_config_modal_input(KeyCode::Enter, 5, false);
+
+        assert!(!app.config.run_post_add_script_in_tmux);
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Post-add auto-run can only be changed in global config")
+        );
+
+        app.config.run_post_add_script_in_tmux = true;
+        app.handle_config_modal_input(KeyCode::Enter, 5, false);
+
+        assert!(app.config.run_post_add_script_in_tmux);
+    }
+
+    #[test]
+    fn config_modal_toggles_tmux_worktree_mode() {
+        let mut app = test_app(vec![], 0, "/repo/.bare");
+
+        app.handle_config_modal_input(KeyCode::Enter, 4, false);
+
+        assert!(app.config.tmux_worktree_mode);
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Tmux worktree mode enabled (press 's' to save to file)")
+        );
+
+        app.handle_config_modal_input(KeyCode::Enter, 4, false);
+
+        assert!(!app.config.tmux_worktree_mode);
+    }
+
+    #[test]
+    fn enter_worktree_focuses_matching_tmux_pane_in_tmux_mode() {
+        let _guard = env_lock().lock().unwrap();
+        let base = temp_dir("enter_tmux_pane_focus");
+        let fake_bin = base.join("bin");
+        fs::create_dir_all(&fake_bin).unwrap();
+
+        let tmux_log = base.join("tmux-args.txt");
+        let fake_tmux = fake_bin.join("tmux");
+        fs::write(
+            &fake_tmux,
+            format!(
+                "#!/bin/sh\nprintf '%s\\n' \"$*\" >> '{}'\nif [ \"$1\" = \"list-panes\" ]; then printf '%%1\\tfeature\\t0:1.0\\n'; fi\n",
+                tmux_log.to_string_lossy().replace('\'', "'\\''")
+            ),
+        )
+        .unwrap();
+        make_executable(&fake_tmux);
+
+        let original_path = std::env::var_os("PATH");
+        let path = if let Some(existing) = original_path.as_ref() {
+            let mut paths = vec![fake_bin.clone()];
+            paths.extend(std::env::split_paths(existing));
+            std::env::join_paths(paths).unwrap()
+        } else {
+            fake_bin.clone().into_os_string()
+        };
+        std::env::set_var("PATH", path);
+
+        let mut app = test_app(
+            vec![test_worktree("feature", WorktreeStatus::Clean)],
+            0,
+            "/repo/.bare",
+        );
+        app.config.tmux_worktree_mode = true;
+
+        app.enter_worktree();
+
+        if let Some(path) = original_path {
+            std::env::set_var("PATH", path);
+        } else {
+            std::env::remove_var("PATH");
+        }
+
+        assert!(app.should_quit);
+        assert!(matches!(app.exit_action, ExitAction::Quit));
+        let tmux_args = fs::read_to_string(tmux_log).unwrap();
+        assert!(tmux_args.contains("list-panes -a -F"));
+        assert!(tmux_args.contains("switch-client -t 0:1.0"));
+        assert!(tmux_args.contains("select-pane -t %1"));
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn post_add_script_configured_path_editor_targets_configured_script() {
+        let base = temp_dir("post_add_script_configured_path_editor");
+        let project_root = base.join("project");
+        fs::create_dir_all(project_root.join(".owt")).unwrap();
+
+        let editor_log = base.join("editor-arg.txt");
+        let editor_path = base.join("fake-editor.sh");
+        fs::write(
+            &editor_path,
+            format!(
+                "#!/bin/sh\nprintf '%s' \"$1\" > '{}'\n",
+                editor_log.to_string_lossy().replace('\'', "'\\''")
+            ),
+        )
+        .unwrap();
+        make_executable(&editor_path);
+
+        let mut app = test_app(vec![], 0, "/repo/.bare");
+        app.project_root_path = project_root.clone();
+        app.config.editor = Some(editor_path.to_string_lossy().to_string());
+        app.config.post_add_script = Some("setup.sh".to_string());
+
+        app.open_post_add_script_editor();
+
+        let configured_script = project_root.join("setup.sh");
+        assert!(configured_script.exists());
+        assert!(!Config::post_add_script_path(&project_root).exists());
+        assert_eq!(
+            fs::read_to_string(editor_log).unwrap(),
+            configured_script.to_string_lossy()
+        );
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn post_add_script_configured_path_execution_ignores_default_script() {
+        let base = temp_dir("post_add_script_configured_path_default_ignored");
+        let project_root = base.join("project");
+        let worktree_path = base.join("worktree");
+        fs::create_dir_all(project_root.join(".owt")).unwrap();
+        fs::create_dir_all(&worktree_path).unwrap();
+        fs::write(Config::post_add_script_path(&project_root), "#!/bin/sh\n").unwrap();
+
+        let mut app = test_app(vec![], 0, "/repo/.bare");
+        app.project_root_path = project_root;
+        app.config.post_add_script = Some("setup.sh".to_string());
+        app.config.run_post_add_script_in_tmux = true;
+
+        app.run_post_add_script(&app.hook_env(&worktree_path));
+
+        assert!(app.script_receiver.is_none());
+        assert!(matches!(app.script_status, ScriptStatus::Idle));
+        assert!(app.message.is_none());
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn post_add_script_configured_path_execution_launches_configured_script_in_tmux() {
+        let _guard = env_lock().lock().unwrap();
+        let base = temp_dir("post_add_script_configured_path_tmux");
+        let project_root = base.join("project");
+        let worktree_path = base.join("worktree");
+        let fake_bin = base.join("bin");
+        fs::create_dir_all(&project_root).unwrap();
+        fs::create_dir_all(&worktree_path).unwrap();
+        fs::create_dir_all(&fake_bin).unwrap();
+
+        let configured_script = project_root.join("setup.sh");
+        fs::write(&configured_script, "#!/bin/sh\n").unwrap();
+        let tmux_log = base.join("tmux-args.txt");
+        let fake_tmux = fake_bin.join("tmux");
+        fs::write(
+            &fake_tmux,
+            format!(
+                "#!/bin/sh\nprintf '%s' \"$*\" > '{}'\nexit 0\n",
+                tmux_log.to_string_lossy().replace('\'', "'\\''")
+            ),
+        )
+        .unwrap();
+        make_executable(&fake_tmux);
+
+        let original_path = std::env::var_os("PATH");
+        let path = if let Some(existing) = original_path.as_ref() {
+            let mut paths = vec![fake_bin.clone()];
+            paths.extend(std::env::split_paths(existing));
+            std::env::join_paths(paths).unwrap()
+        } else {
+            fake_bin.clone().into_os_string()
+        };
+        std::env::set_var("PATH", path);
+
+        let mut app = test_app(vec![], 0, "/repo/.bare");
+        app.project_root_path = project_root.clone();
+        app.config.post_add_script = Some("setup.sh".to_string());
+        app.config.run_post_add_script_in_tmux = true;
+
+        app.run_post_add_script(&app.hook_env(&worktree_path));
+
+        if let Some(path) = original_path {
+            std::env::set_var("PATH", path);
+        } else {
+            std::env::remove_var("PATH");
+        }
+
+        let tmux_args = fs::read_to_string(tmux_log).unwrap();
+        assert!(tmux_args.contains("new-session -d -s owt-post-add-"));
+        assert!(tmux_args.contains(&format!("cd {}", shell_quote(&worktree_path))));
+        assert!(tmux_args.contains(&format!("sh {}", shell_quote(&configured_script))));
+        assert!(tmux_args.contains("export OWT_BRANCH='worktree' OWT_BASE_BRANCH='main'"));
+        assert!(tmux_args.contains("OWT_BARE_PATH='/repo/.bare' OWT_BRANCH_TYPE=''"));
+        assert!(app.script_receiver.is_some());
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn conflicting_worktree_for_branch_reports_existing_checkout_path() {
+        let app = test_app(
+            vec![Worktree {
+                path: PathBuf::from("/tmp/external/staging"),
+                branch: Some("staging".to_string()),
+                is_bare: false,
+                status: WorktreeStatus::Clean,
+                last_commit_time: None,
+                ahead_behind: None,
+                github_pr_status: None,
+                locked: false,
+            }],
+            0,
+            "/repo/.bare",
+        );
+
+        let desired_path = app.worktree_path_for_branch("staging");
+        let conflict = app
+            .conflicting_worktree_for_branch("staging", &desired_path)
+            .expect("staging checkout should be reported");
+
+        assert_eq!(desired_path, PathBuf::from("/repo/staging"));
+        assert_eq!(conflict.path, PathBuf::from("/tmp/external/staging"));
+    }
+
+    #[test]
+    fn add_modal_base_label_uses_session_default_branch() {
+        let app = test_app(
+            vec![Worktree {
+                path: PathBuf::from("/repo/staging"),
+                branch: Some("staging".to_string()),
+                is_bare: false,
+                status: WorktreeStatus::Clean,
+                last_commit_time: None,
+                ahead_behind: None,
+                github_pr_status: None,
+                locked: false,
+            }],
+            0,
+            "/repo/.bare",
+        );
+
+        assert_eq!(app.add_modal_base_label(), "Base branch: main");
+    }
+
+    #[test]
+    fn add_modal_enter_queues_post_tui_create_request() {
+        let mut app = test_app(
+            vec![test_worktree("main", WorktreeStatus::Clean)],
+            0,
+            "/repo/.bare",
+        );
+        app.current_worktree_path = Some(PathBuf::from("/repo/main"));
+        app.state = AppState::AddModal;
+        app.input_buffer = "feature/post tui".to_string();
+
+        app.handle_add_modal_input(KeyCode::Enter, KeyModifiers::NONE);
+
+        assert!(!app.should_quit);
+        assert!(matches!(app.state, AppState::AddModal));
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Invalid branch name: cannot contain spaces")
+        );
+
+        app.input_buffer = "feature/post-tui".to_string();
+        app.handle_add_modal_input(KeyCode::Enter, KeyModifiers::NONE);
+
+        assert!(app.should_quit);
+        assert!(app.active_op.is_none());
+        assert!(matches!(app.state, AppState::List));
+        assert!(app.input_buffer.is_empty());
+        match app.exit_action {
+            ExitAction::CreateWorktree(request) => {
+                assert_eq!(request.bare_repo_path, PathBuf::from("/repo/.bare"));
+                assert_eq!(request.project_root_path, PathBuf::from("/repo"));
+                assert_eq!(request.branch, "feature/post-tui");
+                assert_eq!(request.base_branch, "main");
+                assert_eq!(
+                    request.worktree_path,
+                    PathBuf::from("/repo/feature/post-tui")
+                );
+                assert_eq!(request.source_path, Some(PathBuf::from("/repo/main")));
+            }
+            other => panic!("expected post-TUI create request, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn add_modal_copies_from_copy_source_instead_of_the_current_worktree() {
+        let mut app = test_app(
+            vec![
+                test_worktree("main", WorktreeStatus::Clean),
+                test_worktree("stale", WorktreeStatus::Clean),
+            ],
+            1,
+            "/repo/.bare",
+        );
+        app.current_worktree_path = Some(PathBuf::from("/repo/stale"));
+        app.config.copy_source = Some("main".to_string());
+        app.state = AppState::AddModal;
+        app.input_buffer = "feature/env".to_string();
+        app.handle_add_modal_input(KeyCode::Enter, KeyModifiers::NONE);
+        match &app.exit_action {
+            ExitAction::CreateWorktree(request) => {
+                assert_eq!(request.source_path, Some(PathBuf::from("/repo/main")));
+            }
+            other => panic!("expected post-TUI create request, got {other:?}"),
+        }
+
+        // A copy_source that names no worktree copies nothing
+        app.config.copy_source = Some("develop".to_string());
+        assert_eq!(
+            app.config
+                .copy_source_path(&app.worktrees, Some(PathBuf::from("/repo/stale"))),
+            None
+        );
+    }
+
+    #[test]
+    fn add_modal_ctrl_t_builds_branch_name_from_issue_title() {
+        let mut app = test_app(vec![], 0, "/repo/.bare");
+        app.project_root_path = std::env::temp_dir();
+        app.state = AppState::AddModal;
+        app.input_buffer = "feature/ABC-123".to_string();
+
+        app.handle_add_modal_input(KeyCode::Char('t'), KeyModifiers::CONTROL);
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Set issue_title_command in config.toml to look up issue titles")
+        );
+
+        app.config.issue_title_command = Some("echo \"Fix login for {id}\"".to_string());
+        app.handle_add_modal_input(KeyCode::Char('t'), KeyModifiers::CONTROL);
+        while app.issue_lookup_receiver.is_some() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            app.poll_issue_lookup();
+        }
+        assert_eq!(app.input_buffer, "feature/ABC-123-fix-login-for-abc-123");
+        assert!(app.message.is_none());
+
+        app.handle_add_modal_input(KeyCode::Char('t'), KeyModifiers::CONTROL);
+        assert!(app.issue_lookup_receiver.is_none());
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Type a ticket ID first, e.g. feature/ABC-123 or #42")
+        );
+    }
+
+    #[test]
+    fn add_modal_path_override_completes_directories_and_marks_the_request() {
+        let base = temp_dir("add_path_override");
+        fs::create_dir_all(base.join("scratch-disk").join("owt")).unwrap();
+        fs::create_dir_all(base.join("scratch-other")).unwrap();
+        let mut app = test_app(
+            vec![test_worktree("main", WorktreeStatus::Clean)],
+            0,
+            "/repo/.bare",
+        );
+        app.input_buffer = "exp/huge".to_string();
+
+        app.handle_add_modal_input(KeyCode::Char('l'), KeyModifiers::CONTROL);
+        assert!(app.add_editing_path);
+        assert_eq!(app.add_path_override.as_deref(), Some("/repo/exp/huge"));
+
+        app.add_path_override = Some(format!("{}/scr", base.display()));
+        app.handle_add_modal_input(KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(
+            app.add_path_override,
+            Some(format!("{}/scratch-", base.display()))
+        );
+        app.handle_add_modal_input(KeyCode::Char('d'), KeyModifiers::NONE);
+        app.handle_add_modal_input(KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(
+            app.add_path_override,
+            Some(format!("{}/scratch-disk/", base.display()))
+        );
+        for c in "huge".chars() {
+            app.handle_add_modal_input(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        app.handle_add_modal_input(KeyCode::Enter, KeyModifiers::NONE);
+        assert!(!app.add_editing_path);
+        assert_eq!(app.input_buffer, "exp/huge");
+
+        app.handle_add_modal_input(KeyCode::Enter, KeyModifiers::NONE);
+
+        match app.exit_action {
+            ExitAction::CreateWorktree(request) => {
+                assert_eq!(
+                    request.worktree_path,
+                    base.join("scratch-disk").join("huge")
+                );
+                assert!(request.path_overridden);
+            }
+            other => panic!("expected post-TUI create request, got {other:?}"),
+        }
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn non_bare_worktree_path_uses_configurable_root_and_repo_namespace() {
+        let mut app = test_app(vec![], 0, "/repo");
+        app.repo_is_bare = false;
+        app.config.worktree_root = Some("/tmp/owt-worktrees".to_string());
+
+        assert_eq!(
+            app.worktree_path_for_branch("feature/login"),
+            PathBuf::from("/tmp/owt-worktrees/repo/feature/login")
+        );
+    }
+
+    #[test]
+    fn worktree_naming_maps_branch_to_folder_for_new_and_renamed_worktrees() {
+        let mut app = test_app(
+            vec![Worktree {
+                path: PathBuf::from("/repo/login"),
+                branch: Some("feature/login".to_string()),
+                ..test_worktree("login", WorktreeStatus::Clean)
+            }],
+            0,
+            "/repo/.bare",
+        );
+        app.config.worktree_naming = Some("strip-prefix".to_string());
+        assert_eq!(
+            app.worktree_path_for_branch("feature/login"),
+            PathBuf::from("/repo/login")
+        );
+        let wt = app.worktrees[0].clone();
+        assert_eq!(
+            app.renamed_worktree_path(&wt, "feature/sign-in"),
+            Some(PathBuf::from("/repo/sign-in"))
+        );
+
+        app.config.worktree_naming = Some("dash".to_string());
+        assert_eq!(
+            app.worktree_path_for_branch("feature/login"),
+            PathBuf::from("/repo/feature-login")
+        );
+        assert_eq!(app.renamed_worktree_path(&wt, "feature/sign-in"), None);
+    }
+
+    #[test]
+    fn bare_repo_ignores_worktree_root_and_keeps_sibling_layout() {
+        let mut app = test_app(vec![], 0, "/repo/.bare");
+        app.config.worktree_root = Some("/tmp/owt-worktrees".to_string());
+
+        assert_eq!(
+            app.worktree_path_for_branch("feature/login"),
+            PathBuf::from("/repo/feature/login")
+        );
+    }
+
+    #[test]
+    fn bare_repo_without_worktree_root_keeps_sibling_layout() {
+        let app = test_app(vec![], 0, "/repo/.bare");
+
+        assert_eq!(
+            app.worktree_path_for_branch("feature/login"),
+            PathBuf::from("/repo/feature/login")
+        );
+    }
+
+    #[test]
+    fn paths_match_after_canonicalization() {
+        let tmp = std::env::temp_dir();
+        let canonical = tmp.canonicalize().unwrap_or_else(|_| tmp.clone());
+
+        assert!(paths_refer_to_same_location(&tmp, &canonical));
+    }
+
+    #[test]
+    fn add_completion_selects_created_worktree_before_enter() {
+        let base = temp_dir("add_completion_enter");
+        let (bare_path, main_path) = create_test_project(&base);
+        let project_root = bare_path.parent().unwrap().to_path_buf();
+        let feature_path = project_root.join("feature").join("enter-after-create");
+
+        git::add_worktree(
+            &bare_path,
+            "feature/enter-after-create",
+            &feature_path,
+            Some("main"),
+        )
+        .unwrap();
+
+        let mut app =
+            App::new(bare_path.clone(), project_root, true, Some(main_path), true).unwrap();
+
+        app.handle_op_result(OpResult {
+            kind: OpKind::Add,
+            success: true,
+            message: "Created worktree: feature/enter-after-create".to_string(),
+            cmd_detail: String::new(),
+            worktree_path: feature_path.clone(),
+            affected_paths: vec![feature_path.clone()],
+            display_name: "feature/enter-after-create".to_string(),
+        });
+        app.enter_worktree();
+
+        match app.exit_action {
+            ExitAction::ChangeDirectory(path) => {
+                assert!(paths_refer_to_same_location(&path, &feature_path));
+            }
+            ExitAction::Quit => panic!("enter should request directory change"),
+            ExitAction::CreateWorktree(_) => panic!("enter should not create a worktree"),
+        }
+        assert!(app.should_quit);
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn copy_files_nested_branch() {
+        let base = temp_dir("copy_files_nested_branch");
+        let (bare_path, main_path) = create_test_project(&base);
+        let project_root = bare_path.parent().unwrap().to_path_buf();
+        let branch = "feature/copy-files-nested";
+        let worktree_path = project_root.join("feature").join("copy-files-nested");
+
+        fs::create_dir_all(main_path.join("config")).unwrap();
+        fs::write(main_path.join("config/local.env"), "TOKEN=secret\n").unwrap();
+
+        let mut app = App::new(
+            bare_path.clone(),
+            project_root.clone(),
+            true,
+            Some(main_path.clone()),
+            true,
+        )
+        .unwrap();
+        app.config.copy_files = vec!["config/local.env".to_string()];
+        app.input_buffer = branch.to_string();
+
+        app.add_worktree();
+        wait_for_background_op(&mut app);
+
+        assert!(worktree_path.exists());
+        assert_eq!(
+            fs::read_to_string(worktree_path.join("config/local.env")).unwrap(),
+            "TOKEN=secret\n"
+        );
+        let message = app.message.as_ref().unwrap();
+        assert!(!message.is_error);
+        assert!(message
+            .text
+            .contains("Created worktree: feature/copy-files-nested"));
+        assert!(!message.text.contains("Copy warnings:"));
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn copy_files_missing() {
+        let base = temp_dir("copy_files_missing");
+        let (bare_path, main_path) = create_test_project(&base);
+        let project_root = bare_path.parent().unwrap().to_path_buf();
+        let branch = "feature/copy-files-missing";
+        let worktree_path = project_root.join("feature").join("copy-files-missing");
+
+        let mut app = App::new(
+            bare_path.clone(),
+            project_root.clone(),
+            true,
+            Some(main_path),
+            true,
+        )
+        .unwrap();
+        app.config.copy_files = vec![".env.missing".to_string()];
+        app.input_buffer = branch.to_string();
+
+        app.add_worktree();
+        wait_for_background_op(&mut app);
+
+        assert!(worktree_path.exists());
+        assert!(!worktree_path.join(".env.missing").exists());
+        let message = app.message.as_ref().unwrap();
+        assert!(!message.is_error);
+        assert!(message
+            .text
+            .contains("Created worktree: feature/copy-files-missing"));
+        assert!(message.text.contains("Copy warnings:"));
+        assert!(message.text.contains(".env.missing"));
+        assert!(message.text.contains("source file missing"));
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn typing_a_branch_type_prefix_picks_its_base_and_shows_the_prefetched_comparison() {
+        let mut app = test_app(Vec::new(), 0, "/repo/.bare");
+        app.config.branch_types = vec![crate::config::BranchType {
+            name: "hotfix".to_string(),
+            prefix: "hotfix/".to_string(),
+            base: Some("release".to_string()),
+            ..Default::default()
+        }];
+        app.base_comparisons.insert(
+            "release".to_string(),
+            BaseComparison {
+                remote_branch: Some("origin/release".to_string()),
+                has_local: true,
+                local_vs_remote: Some(crate::types::AheadBehind {
+                    ahead: 0,
+                    behind: 2,
+                }),
+                fetched: true,
+            },
+        );
+        app.state = AppState::AddModal;
+
+        for c in "hotfix/".chars() {
+            app.handle_add_modal_input(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+
+        assert_eq!(app.add_base_branch, "release");
+        assert_eq!(
+            app.add_modal_base_label(),
+            "Base branch: release (local ↓2 origin/release)"
+        );
+    }
+
+    #[test]
+    fn branch_type_name_template_prompts_for_variables_and_is_enforced() {
+        let mut app = test_app(Vec::new(), 0, "/repo/.bare");
+        app.config.branch_types = vec![crate::config::BranchType {
+            name: "feature".to_string(),
+            prefix: "feature/".to_string(),
+            name_template: Some("{prefix}{ticket}-{slug}".to_string()),
+            ..Default::default()
+        }];
+        app.state = AppState::AddModal;
+
+        for c in "feature/".chars() {
+            app.handle_add_modal_input(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        assert!(app.input_buffer.is_empty());
+        assert_eq!(
+            app.state,
+            AppState::AddTemplateFields {
+                branch_type: 0,
+                values: Vec::new()
+            }
+        );
+
+        let type_value = |app: &mut App, text: &str| {
+            for c in text.chars() {
+                let AppState::AddTemplateFields {
+                    branch_type,
+                    values,
+                } = app.state.clone()
+                else {
+                    panic!("expected template fields");
+                };
+                app.handle_add_template_input(KeyCode::Char(c), branch_type, values);
+            }
+            let AppState::AddTemplateFields {
+                branch_type,
+                values,
+            } = app.state.clone()
+            else {
+                panic!("expected template fields");
+            };
+            app.handle_add_template_input(KeyCode::Enter, branch_type, values);
+        };
+        type_value(&mut app, "PAY-7");
+        type_value(&mut app, "Refund flow");
+
+        assert_eq!(app.state, AppState::AddModal);
+        assert_eq!(app.input_buffer, "feature/PAY-7-refund-flow");
+
+        app.input_buffer = "feature/refunds".to_string();
+        app.handle_add_modal_input(KeyCode::Enter, KeyModifiers::NONE);
+        assert!(!app.should_quit);
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("feature/ branches must look like {prefix}{ticket}-{slug}")
+        );
+
+        app.input_buffer = "feature".to_string();
+        app.handle_add_modal_input(KeyCode::Char('/'), KeyModifiers::NONE);
+        app.handle_add_template_input(KeyCode::Esc, 0, Vec::new());
+        assert_eq!(app.state, AppState::AddModal);
+        assert_eq!(app.input_buffer, "feature/");
+    }
+
+    #[test]
+    fn tab_accepts_the_branch_name_suggestion_before_cycling_base() {
+        let mut app = test_app(Vec::new(), 0, "/repo/.bare");
+        app.config.branch_types = vec![crate::config::BranchType {
+            name: "hotfix".to_string(),
+            prefix: "hotfix/".to_string(),
+            base: Some("release".to_string()),
+            ..Default::default()
+        }];
+        app.branch_suggestions = vec!["hotfix/".to_string()];
+        app.state = AppState::AddModal;
+
+        app.handle_add_modal_input(KeyCode::Char('h'), KeyModifiers::NONE);
+        assert_eq!(app.add_modal_suggestion(), Some("otfix/"));
+
+        app.handle_add_modal_input(KeyCode::Tab, KeyModifiers::NONE);
+
+        assert_eq!(app.input_buffer, "hotfix/");
+        assert_eq!(app.add_base_branch, "release");
+        assert_eq!(app.add_modal_suggestion(), None);
+    }
+
+    #[test]
+    fn clone_modal_clones_in_the_background_and_loads_the_new_repo() {
+        let base = temp_dir("clone_modal");
+        create_test_project(&base);
+        let target_dir = base.join("elsewhere");
+        fs::create_dir_all(&target_dir).unwrap();
+        let mut app = App::new_clone_prompt(target_dir.clone(), true);
+        app.input_buffer = format!("file://{}", base.join("source").display());
+
+        app.handle_clone_modal_input(KeyCode::Enter, KeyModifiers::NONE, &target_dir);
+        app.handle_clone_modal_input(KeyCode::Esc, KeyModifiers::NONE, &target_dir);
+        assert!(!app.should_quit, "Esc is ignored while cloning");
+
+        let deadline = Instant::now() + StdDuration::from_secs(10);
+        while app.clone_receiver.is_some() {
+            app.poll_clone();
+            assert!(Instant::now() < deadline, "clone timed out");
+            std::thread::sleep(StdDuration::from_millis(20));
+        }
+
+        assert!(matches!(app.state, AppState::List), "{:?}", app.message);
+        assert_eq!(app.bare_repo_path, target_dir.join("source").join(".bare"));
+        let main_path = target_dir.join("source").join("main");
+        assert!(app.worktrees.iter().any(|wt| wt.path == main_path));
+        assert_eq!(
+            app.current_worktree_path.as_deref(),
+            Some(main_path.as_path())
+        );
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn enter_is_blocked_while_background_operation_is_running() {
+        let (_tx, rx) = mpsc::channel();
+        let mut app = test_app(
+            vec![Worktree {
+                path: PathBuf::from("/repo/main"),
+                branch: Some("main".to_string()),
+                is_bare: false,
+                status: WorktreeStatus::Clean,
+                last_commit_time: None,
+                ahead_behind: None,
+                github_pr_status: None,
+                locked: false,
+            }],
+            0,
+            "/repo/.bare",
+        );
+        app.active_op = Some((OpKind::Add, rx));
+
+        app.handle_list_input(KeyCode::Enter, KeyModifiers::empty());
+
+        assert!(matches!(app.exit_action, ExitAction::Quit));
+        assert!(!app.should_quit);
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Operation still in progress")
+        );
+    }
+
+    #[test]
+    fn force_push_requires_confirmation_and_can_be_cancelled() {
+        let mut app = test_app(
+            vec![test_worktree("feature-rebase", WorktreeStatus::Clean)],
+            0,
+            "/repo/.bare",
+        );
+
+        app.handle_list_input(KeyCode::Char('F'), KeyModifiers::SHIFT);
+        assert_eq!(app.state, AppState::ConfirmForcePush);
+        assert!(app.active_op.is_none());
+
+        app.handle_confirm_force_push_input(KeyCode::Char('n'));
+        assert_eq!(app.state, AppState::List);
+        assert!(app.active_op.is_none());
+    }
+
+    #[test]
+    fn enter_rejects_bare_repository_selection() {
+        let mut app = test_app(
+            vec![Worktree {
+                path: PathBuf::from("/repo/.bare"),
+                branch: None,
+                is_bare: true,
+                status: WorktreeStatus::Clean,
+                last_commit_time: None,
+                ahead_behind: None,
+                github_pr_status: None,
+                locked: false,
+            }],
+            0,
+            "/repo/.bare",
+        );
+
+        app.enter_worktree();
+
+        assert!(matches!(app.exit_action, ExitAction::Quit));
+        assert!(!app.should_quit);
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Cannot enter bare repository")
+        );
+    }
+
+    #[test]
+    fn delete_confirmation_blocks_dirty_worktree_without_force() {
+        let mut app = test_app(
+            vec![Worktree {
+                path: PathBuf::from("/repo/dirty"),
+                branch: Some("dirty".to_string()),
+                is_bare: false,
+                status: WorktreeStatus::Unstaged,
+                last_commit_time: None,
+                ahead_behind: None,
+                github_pr_status: None,
+                locked: false,
+            }],
+            0,
+            "/repo/.bare",
+        );
+        app.state = AppState::ConfirmDelete {
+            delete_branch: false,
+            force: false,
+            delete_remote: false,
+            keep_nested: false,
+        };
+
+        app.handle_confirm_delete_input(KeyCode::Enter, false, false, false, false);
+
+        assert!(app.active_op.is_none());
+        assert!(matches!(
+            app.state,
+            AppState::ConfirmDelete {
+                delete_branch: false,
+                force: false,
+                delete_remote: false,
+                keep_nested: false
+            }
+        ));
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Worktree has uncommitted changes. Press 'f' to enable force delete.")
+        );
+    }
+
+    #[test]
+    fn forced_delete_of_dirty_worktree_requires_typing_its_name() {
+        let mut app = test_app(
+            vec![test_worktree("dirty", WorktreeStatus::Unstaged)],
+            0,
+            "/repo/.bare",
+        );
+        app.state = AppState::ConfirmDelete {
+            delete_branch: true,
+            force: true,
+            delete_remote: false,
+            keep_nested: false,
+        };
+
+        app.handle_confirm_delete_input(KeyCode::Char('y'), true, true, false, false);
+
+        let AppState::ConfirmDeleteTyped {
+            expected, at_risk, ..
+        } = &app.state
+        else {
+            panic!("expected typed confirmation, got {:?}", app.state.label());
+        };
+        assert_eq!(expected, "dirty");
+        assert_eq!(
+            at_risk,
+            &vec![
+                "dirty: uncommitted changes".to_string(),
+                "dirty: unmerged branch".to_string()
+            ]
+        );
+
+        for c in "dirt".chars() {
+            app.handle_confirm_delete_typed_input(
+                KeyCode::Char(c),
+                [true, true, false, false],
+                "dirty".to_string(),
+                at_risk_of(&app),
+            );
+        }
+        app.handle_confirm_delete_typed_input(
+            KeyCode::Enter,
+            [true, true, false, false],
+            "dirty".to_string(),
+            at_risk_of(&app),
+        );
+        assert!(app.active_op.is_none());
+        assert_eq!(app.input_buffer, "dirt");
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Type dirty to confirm, or Esc to go back")
+        );
+
+        app.handle_confirm_delete_typed_input(
+            KeyCode::Esc,
+            [true, true, false, false],
+            "dirty".to_string(),
+            at_risk_of(&app),
+        );
+        assert!(app.input_buffer.is_empty());
+        assert!(matches!(
+            app.state,
+            AppState::ConfirmDelete {
+                delete_branch: true,
+                force: true,
+                ..
+            }
+        ));
+        assert!(app.forced_delete_losses(true, false).is_empty());
+    }
+
+    fn at_risk_of(app: &App) -> Vec<String> {
+        match &app.state {
+            AppState::ConfirmDeleteTyped { at_risk, .. } => at_risk.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn delete_confirmation_toggles_remote_and_reports_protected_branches() {
+        let mut app = test_app(
+            vec![
+                test_worktree("main", WorktreeStatus::Clean),
+                test_worktree("feature", WorktreeStatus::Clean),
+            ],
+            0,
+            "/repo/.bare",
+        );
+        app.selected_worktree_paths
+            .insert(PathBuf::from("/repo/main"));
+        app.selected_worktree_paths
+            .insert(PathBuf::from("/repo/feature"));
+        app.state = AppState::ConfirmDelete {
+            delete_branch: true,
+            force: false,
+            delete_remote: false,
+            keep_nested: false,
+        };
+
+        app.handle_confirm_delete_input(KeyCode::Char('r'), true, false, false, false);
+
+        assert!(matches!(
+            app.state,
+            AppState::ConfirmDelete {
+                delete_branch: true,
+                force: false,
+                delete_remote: true,
+                keep_nested: false
+            }
+        ));
+        assert_eq!(app.protected_delete_branches(), vec!["main".to_string()]);
+    }
+
+    #[test]
+    fn remote_branch_is_kept_when_the_local_branch_delete_fails() {
+        let base = temp_dir("delete_keeps_remote");
+        let (bare_path, main_path) = create_test_project(&base);
+        let project_root = bare_path.parent().unwrap().to_path_buf();
+        let feature_path = project_root.join("feature");
+        let bare = bare_path.to_string_lossy().to_string();
+        let feature = feature_path.to_string_lossy().to_string();
+        assert_git_success(
+            git_cmd()
+                .args(["-C", &bare, "worktree", "add", "-b", "feature", &feature])
+                .output()
+                .unwrap(),
+            "git worktree add failed",
+        );
+        fs::write(feature_path.join("work.txt"), "unmerged\n").unwrap();
+        for args in [
+            vec!["add", "."],
+            vec!["commit", "-m", "unmerged work"],
+            vec!["push", "-u", "origin", "feature"],
+        ] {
+            assert_git_success(
+                git_cmd()
+                    .current_dir(&feature_path)
+                    .args(&args)
+                    .output()
+                    .unwrap(),
+                "git failed",
+            );
+        }
+
+        let mut app =
+            App::new(bare_path.clone(), project_root, true, Some(main_path), true).unwrap();
+        app.selected_index = app
+            .worktrees
+            .iter()
+            .position(|wt| wt.branch.as_deref() == Some("feature"))
+            .unwrap();
+        // `git branch -d` refuses the unmerged branch
+        app.delete_selected_worktree(true, false, true, false);
+        wait_for_background_op(&mut app);
+
+        assert!(!feature_path.exists());
+        let message = app.message.as_ref().unwrap();
+        assert!(message.is_error);
+        assert!(
+            message.text.contains("remote branch delete skipped"),
+            "{}",
+            message.text
+        );
+        assert_git_success(
+            git_cmd()
+                .current_dir(base.join("source"))
+                .args(["rev-parse", "--verify", "refs/heads/feature"])
+                .output()
+                .unwrap(),
+            "remote branch should still exist",
+        );
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn rename_modal_prefills_branch_and_plans_folder_move() {
+        let mut app = test_app(
+            vec![
+                test_worktree("main", WorktreeStatus::Clean),
+                Worktree {
+                    path: PathBuf::from("/repo/feature/login"),
+                    branch: Some("feature/login".to_string()),
+                    ..test_worktree("login", WorktreeStatus::Clean)
+                },
+            ],
+            1,
+            "/repo/.bare",
+        );
+
+        app.handle_list_input(KeyCode::Char('R'), KeyModifiers::SHIFT);
+
+        assert!(matches!(
+            app.state,
+            AppState::RenameModal {
+                propagate: false,
+                remote: None
+            }
+        ));
+        assert_eq!(app.input_buffer, "feature/login");
+        let wt = app.worktrees[1].clone();
+        assert_eq!(
+            app.renamed_worktree_path(&wt, "feature/sign-in"),
+            Some(PathBuf::from("/repo/feature/sign-in"))
+        );
+        assert_eq!(
+            app.renamed_worktree_path(&app.worktrees[0].clone(), "trunk"),
+            Some(PathBuf::from("/repo/trunk"))
+        );
+
+        app.current_worktree_path = Some(PathBuf::from("/repo/feature/login"));
+        assert_eq!(app.renamed_worktree_path(&wt, "feature/sign-in"), None);
+
+        app.handle_rename_modal_input(KeyCode::Esc, false, None);
+        assert!(matches!(app.state, AppState::List));
+        assert!(app.input_buffer.is_empty());
+    }
+
+    #[test]
+    fn space_toggles_worktree_selection_without_moving_cursor() {
+        let mut app = test_app(
+            vec![
+                test_worktree("main", WorktreeStatus::Clean),
+                test_worktree("feature", WorktreeStatus::Clean),
+            ],
+            1,
+            "/repo/.bare",
+        );
+
+        app.handle_list_input(KeyCode::Char(' '), KeyModifiers::empty());
+
+        assert_eq!(app.selected_index, 1);
+        assert!(app.is_worktree_marked(Path::new("/repo/feature")));
+        assert_eq!(app.selected_worktree_count(), 1);
+
+        app.handle_list_input(KeyCode::Char(' '), KeyModifiers::empty());
+
+        assert_eq!(app.selected_worktree_count(), 0);
+        assert!(!app.is_worktree_marked(Path::new("/repo/feature")));
+    }
+
+    #[test]
+    fn action_worktrees_uses_checked_worktrees_before_cursor() {
+        let mut app = test_app(
+            vec![
+                test_worktree("main", WorktreeStatus::Clean),
+                test_worktree("feature-a", WorktreeStatus::Clean),
+                test_worktree("feature-b", WorktreeStatus::Clean),
+            ],
+            0,
+            "/repo/.bare",
+        );
+        app.selected_worktree_paths
+            .insert(PathBuf::from("/repo/feature-a"));
+        app.selected_worktree_paths
+            .insert(PathBuf::from("/repo/feature-b"));
+
+        let action_paths: Vec<PathBuf> = app
+            .action_worktrees()
+            .into_iter()
+            .map(|wt| wt.path)
+            .collect();
+
+        assert_eq!(
+            action_paths,
+            vec![
+                PathBuf::from("/repo/feature-a"),
+                PathBuf::from("/repo/feature-b")
+            ]
+        );
+    }
+
+    #[test]
+    fn batch_pull_blocks_when_any_checked_worktree_is_dirty() {
+        let mut app = test_app(
+            vec![
+                test_worktree("main", WorktreeStatus::Clean),
+                test_worktree("dirty", WorktreeStatus::Unstaged),
+            ],
+            0,
+            "/repo/.bare",
+        );
+        app.selected_worktree_paths
+            .insert(PathBuf::from("/repo/main"));
+        app.selected_worktree_paths
+            .insert(PathBuf::from("/repo/dirty"));
+
+        app.pull_worktree();
+
+        assert!(app.active_op.is_none());
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Cannot pull: worktree has uncommitted changes: dirty")
+        );
+    }
+
+    #[test]
+    fn batch_delete_dirty_guard_checks_checked_worktrees_not_cursor() {
+        let mut app = test_app(
+            vec![
+                test_worktree("dirty-cursor", WorktreeStatus::Unstaged),
+                test_worktree("clean-target", WorktreeStatus::Clean),
+            ],
+            0,
+            "/repo/.bare",
+        );
+        app.selected_worktree_paths
+            .insert(PathBuf::from("/repo/clean-target"));
+        app.state = AppState::ConfirmDelete {
+            delete_branch: false,
+            force: false,
+            delete_remote: false,
+            keep_nested: false,
+        };
+
+        app.handle_confirm_delete_input(KeyCode::Enter, false, false, false, false);
+
+        assert!(app.active_op.is_some());
+        assert!(matches!(app.state, AppState::List));
+    }
+
+    #[test]
+    fn filter_enter_changes_to_first_matching_worktree() {
+        let mut app = test_app(
+            vec![
+                Worktree {
+                    path: PathBuf::from("/repo/main"),
+                    branch: Some("main".to_string()),
+                    is_bare: false,
+                    status: WorktreeStatus::Clean,
+                    last_commit_time: None,
+                    ahead_behind: None,
+                    github_pr_status: None,
+                    locked: false,
+                },
+                Worktree {
+                    path: PathBuf::from("/repo/feature-search"),
+                    branch: Some("feature/search".to_string()),
+                    is_bare: false,
+                    status: WorktreeStatus::Clean,
+                    last_commit_time: None,
+                    ahead_behind: None,
+                    github_pr_status: None,
+                    locked: false,
+                },
+            ],
+            0,
+            "/repo/.bare",
+        );
+        app.is_filtering = true;
+
+        app.handle_filter_input(KeyCode::Char('s'));
+        app.handle_filter_input(KeyCode::Enter);
+
+        match app.exit_action {
+            ExitAction::ChangeDirectory(path) => {
+                assert_eq!(path, PathBuf::from("/repo/feature-search"));
+            }
+            ExitAction::Quit => panic!("filter enter should request directory change"),
+            ExitAction::CreateWorktree(_) => panic!("filter enter should not create a worktree"),
+        }
+        assert!(!app.is_filtering);
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn filter_enter_is_blocked_while_background_operation_is_running() {
+        let (_tx, rx) = mpsc::channel();
+        let mut app = test_app(
+            vec![Worktree {
+                path: PathBuf::from("/repo/main"),
+                branch: Some("main".to_string()),
+                is_bare: false,
+                status: WorktreeStatus::Clean,
+                last_commit_time: None,
+                ahead_behind: None,
+                github_pr_status: None,
+                locked: false,
+            }],
+            0,
+            "/repo/.bare",
+        );
+        app.is_filtering = true;
+        app.active_op = Some((OpKind::Fetch, rx));
+
+        app.handle_filter_input(KeyCode::Enter);
+
+        assert!(matches!(app.exit_action, ExitAction::Quit));
+        assert!(!app.should_quit);
+        assert!(app.is_filtering);
+        assert_eq!(
+            app.message.as_ref().map(|message| message.text.as_str()),
+            Some("Operation still in progress")
+        );
+    }
+
+    #[test]
+    fn finished_base_update_opens_summary_modal() {
+        let mut app = test_app(Vec::new(), 0, "/repo/.bare");
+        let (updates_tx, updates_rx) = mpsc::channel();
+        let (op_tx, op_rx) = mpsc::channel();
+        app.base_update_receiver = Some(updates_rx);
+        app.active_op = Some((OpKind::UpdateBases, op_rx));
+        let updates = vec![BaseUpdate {
+            branch: "main".to_string(),
+            outcome: crate::types::BaseUpdateOutcome::UpToDate,
+        }];
+        updates_tx.send(updates.clone()).unwrap();
+        op_tx
+            .send(OpResult {
+                kind: OpKind::UpdateBases,
+                success: true,
+                message: "Updated 0 base branch(es)".to_string(),
+                cmd_detail: String::new(),
+                worktree_path: PathBuf::from("/repo/.bare"),
+                affected_paths: Vec::new(),
+                display_name: "main".to_string(),
+            })
+            .unwrap();
+
+        app.poll_background_op();
+
+        assert_eq!(app.state, AppState::BaseUpdateSummary { updates });
+        assert!(app.active_op.is_none());
+        assert!(app.base_update_receiver.is_none());
+    }
+
+    #[test]
+    fn poll_pr_status_updates_matching_worktree_without_active_op() {
+        let (tx, rx) = mpsc::channel();
+        let mut app = test_app(
+            vec![Worktree {
+                path: PathBuf::from("/repo/feature"),
+                branch: Some("feature".to_string()),
+                is_bare: false,
+                status: WorktreeStatus::Clean,
+                last_commit_time: None,
+                ahead_behind: None,
+                github_pr_status: None,
+                locked: false,
+            }],
+            0,
+            "/repo/.bare",
+        );
+        let (_op_tx, op_rx) = mpsc::channel();
+        app.active_op = Some((OpKind::Fetch, op_rx));
+        app.pr_status_receiver = Some(rx);
+
+        tx.send(vec![(
+            PathBuf::from("/repo/feature"),
+            Some(GithubPrStatus::Open),
+        )])
+        .unwrap();
+        app.poll_pr_status();
+
+        assert_eq!(
+            app.worktrees[0].github_pr_status,
+            Some(GithubPrStatus::Open)
+        );
+        assert!(app.pr_status_receiver.is_none());
+        assert!(app.active_op.is_some());
+    }
+
+    #[test]
+    fn changed_files_opens_read_only_preview_and_returns_to_list() {
+        let base = temp_dir("changed_files_preview");
+        let (bare_path, main_path) = create_test_project(&base);
+        fs::write(main_path.join("README.md"), "# Test\nchanged\n").unwrap();
+        let mut worktree = test_worktree("main", WorktreeStatus::Unstaged);
+        worktree.path = main_path.clone();
+        let mut app = test_app(vec![worktree], 0, &bare_path.to_string_lossy());
+
+        app.handle_list_input(KeyCode::Char('e'), KeyModifiers::NONE);
+        let files = vec![ChangedFile {
+            status: " M".to_string(),
+            path: "README.md".to_string(),
+        }];
+        assert_eq!(
+            app.state,
+            AppState::ChangedFiles {
+                worktree_path: main_path.clone(),
+                files: files.clone(),
+                selected: 0,
+            }
+        );
 
-    static ENV_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        app.handle_changed_files_input(KeyCode::Enter, main_path.clone(), files.clone(), 0);
+        assert!(matches!(app.state, AppState::FilePreview { .. }));
+        while app.file_preview_receiver.is_some() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            app.poll_file_preview();
+        }
+        let preview = app.file_preview.as_ref().expect("preview should load");
+        assert_eq!(preview.lines.len(), 2);
+        assert_eq!(preview.lines[1].to_string(), "changed");
+
+        app.handle_file_preview_input(
+            KeyCode::Char('G'),
+            KeyModifiers::NONE,
+            main_path.clone(),
+            files.clone(),
+            0,
+            0,
+        );
+        assert!(matches!(app.state, AppState::FilePreview { scroll: 1, .. }));
 
-    fn temp_dir(name: &str) -> PathBuf {
-        let id = std::process::id();
-        let ts = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!("owt_app_unit_{}_{}_{}", name, id, ts));
-        let _ = fs::remove_dir_all(&path);
-        fs::create_dir_all(&path).unwrap();
-        path
-    }
+        app.handle_file_preview_input(
+            KeyCode::Esc,
+            KeyModifiers::NONE,
+            main_path.clone(),
+            files.clone(),
+            0,
+            1,
+        );
+        assert!(app.file_preview.is_none());
+        assert!(matches!(app.state, AppState::ChangedFiles { .. }));
 
-    fn env_lock() -> &'static Mutex<()> {
-        ENV_LOCK.get_or_init(|| Mutex::new(()))
+        let _ = fs::remove_dir_all(&base);
     }
 
-    #[cfg(unix)]
-    fn make_executable(path: &Path) {
-        use std::os::unix::fs::PermissionsExt;
+    #[test]
+    fn git_log_lists_commits_and_opens_message_and_stat() {
+        let base = temp_dir("git_log_view");
+        let (bare_path, main_path) = create_test_project(&base);
+        let mut worktree = test_worktree("main", WorktreeStatus::Clean);
+        worktree.path = main_path.clone();
+        let mut app = test_app(vec![worktree], 0, &bare_path.to_string_lossy());
 
-        let mut permissions = fs::metadata(path).unwrap().permissions();
-        permissions.set_mode(0o755);
-        fs::set_permissions(path, permissions).unwrap();
-    }
+        app.handle_list_input(KeyCode::Char('V'), KeyModifiers::NONE);
+        let AppState::GitLog {
+            worktree_path,
+            commits,
+            selected: 0,
+        } = app.state.clone()
+        else {
+            panic!("expected the log view, got {:?}", app.state);
+        };
+        assert_eq!(worktree_path, main_path);
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].subject, "initial");
 
-    #[cfg(not(unix))]
-    fn make_executable(_path: &Path) {}
+        app.handle_git_log_input(KeyCode::Char('j'), main_path.clone(), commits.clone(), 0);
+        assert!(matches!(app.state, AppState::GitLog { selected: 0, .. }));
 
-    fn git_cmd() -> Command {
-        let mut cmd = Command::new("git");
-        cmd.env_remove("GIT_DIR")
-            .env_remove("GIT_WORK_TREE")
-            .env_remove("GIT_INDEX_FILE")
-            .env_remove("GIT_COMMON_DIR");
-        cmd
-    }
+        app.handle_git_log_input(KeyCode::Enter, main_path.clone(), commits.clone(), 0);
+        assert!(matches!(
+            app.state,
+            AppState::CommitDetail { scroll: 0, .. }
+        ));
+        assert!(app
+            .commit_detail
+            .iter()
+            .any(|line| line.contains("README.md")));
 
-    fn assert_git_success(output: std::process::Output, context: &str) {
-        assert!(
-            output.status.success(),
-            "{}: {}",
-            context,
-            String::from_utf8_lossy(&output.stderr)
+        app.handle_commit_detail_input(
+            KeyCode::Esc,
+            KeyModifiers::NONE,
+            main_path.clone(),
+            commits.clone(),
+            0,
+            0,
         );
-    }
+        assert!(app.commit_detail.is_empty());
+        assert!(matches!(app.state, AppState::GitLog { .. }));
+        app.handle_git_log_input(KeyCode::Esc, main_path, commits, 0);
+        assert_eq!(app.state, AppState::List);
 
-    fn create_test_project(base: &Path) -> (PathBuf, PathBuf) {
-        let source_path = base.join("source");
-        let bare_path = base.join("repo").join(".bare");
-        let main_path = base.join("repo").join("main");
+        let _ = fs::remove_dir_all(&base);
+    }
 
-        fs::create_dir_all(&source_path).unwrap();
-        assert_git_success(
-            git_cmd()
-                .current_dir(&source_path)
-                .args(["init", "-b", "main"])
-                .output()
-                .unwrap(),
-            "git init failed",
-        );
+    #[test]
+    fn amend_modal_folds_staged_files_into_head_and_optionally_rewords() {
+        let base = temp_dir("amend_head");
+        let (bare_path, main_path) = create_test_project(&base);
+        fs::write(main_path.join("forgot.txt"), "forgot\n").unwrap();
         assert_git_success(
             git_cmd()
-                .current_dir(&source_path)
-                .args(["config", "user.email", "test@test.com"])
+                .current_dir(&main_path)
+                .args(["add", "forgot.txt"])
                 .output()
                 .unwrap(),
-            "git config user.email failed",
+            "git add failed",
         );
-        assert_git_success(
-            git_cmd()
-                .current_dir(&source_path)
-                .args(["config", "user.name", "Test"])
+        let mut worktree = test_worktree("main", WorktreeStatus::Staged);
+        worktree.path = main_path.clone();
+        let mut app = test_app(vec![worktree], 0, &bare_path.to_string_lossy());
+        let commit_count = || {
+            let output = git_cmd()
+                .current_dir(&main_path)
+                .args(["rev-list", "--count", "HEAD"])
                 .output()
-                .unwrap(),
-            "git config user.name failed",
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+
+        app.handle_list_input(KeyCode::Char('A'), KeyModifiers::NONE);
+        assert_eq!(
+            app.state,
+            AppState::AmendModal {
+                worktree_path: main_path.clone(),
+                subject: "initial".to_string(),
+                published: false,
+            }
         );
+        app.handle_amend_modal_input(KeyCode::Enter, main_path.clone());
+        assert_eq!(app.state, AppState::List);
+        assert_eq!(commit_count(), "1");
+        assert_eq!(git::head_subject(&main_path).unwrap(), "initial");
+        assert_eq!(app.worktrees[0].status, WorktreeStatus::Clean);
+        let tracked = git_cmd()
+            .current_dir(&main_path)
+            .args(["ls-tree", "--name-only", "HEAD"])
+            .output()
+            .unwrap();
+        assert!(String::from_utf8_lossy(&tracked.stdout).contains("forgot.txt"));
+
+        app.handle_list_input(KeyCode::Char('A'), KeyModifiers::NONE);
+        for c in "reworded".chars() {
+            app.handle_amend_modal_input(KeyCode::Char(c), main_path.clone());
+        }
+        app.handle_amend_modal_input(KeyCode::Enter, main_path.clone());
+        assert_eq!(commit_count(), "1");
+        assert_eq!(git::head_subject(&main_path).unwrap(), "reworded");
 
-        fs::write(source_path.join("README.md"), "# Test\n").unwrap();
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn discard_modal_requires_typed_name_and_resets_the_worktree() {
+        let base = temp_dir("discard_changes");
+        let (bare_path, main_path) = create_test_project(&base);
+        fs::write(main_path.join("README.md"), "# Changed\n").unwrap();
+        fs::write(main_path.join("staged.txt"), "staged\n").unwrap();
         assert_git_success(
             git_cmd()
-                .current_dir(&source_path)
-                .args(["add", "."])
+                .current_dir(&main_path)
+                .args(["add", "staged.txt"])
                 .output()
                 .unwrap(),
             "git add failed",
         );
-        assert_git_success(
-            git_cmd()
-                .current_dir(&source_path)
-                .args(["commit", "-m", "initial"])
-                .output()
-                .unwrap(),
-            "git commit failed",
+        fs::write(main_path.join("scratch.txt"), "scratch\n").unwrap();
+        let mut worktree = test_worktree("main", WorktreeStatus::Staged);
+        worktree.path = main_path.clone();
+        let mut app = test_app(vec![worktree], 0, &bare_path.to_string_lossy());
+        app.config.discard_mode = Some("tracked".to_string());
+
+        app.handle_list_input(KeyCode::Char('X'), KeyModifiers::NONE);
+        assert_eq!(
+            app.state,
+            AppState::DiscardModal {
+                worktree_path: main_path.clone(),
+                name: "main".to_string(),
+                file_count: 3,
+            }
         );
-        assert_git_success(
-            git_cmd()
-                .args([
-                    "clone",
-                    "--bare",
-                    &source_path.to_string_lossy(),
-                    &bare_path.to_string_lossy(),
-                ])
-                .output()
-                .unwrap(),
-            "git clone --bare failed",
+        for c in "mai".chars() {
+            app.handle_discard_modal_input(KeyCode::Char(c), main_path.clone(), "main".into());
+        }
+        app.handle_discard_modal_input(KeyCode::Enter, main_path.clone(), "main".into());
+        assert!(matches!(app.state, AppState::DiscardModal { .. }));
+        assert!(main_path.join("staged.txt").exists());
+
+        app.handle_discard_modal_input(KeyCode::Char('n'), main_path.clone(), "main".into());
+        app.handle_discard_modal_input(KeyCode::Enter, main_path.clone(), "main".into());
+        assert_eq!(app.state, AppState::List);
+        assert_eq!(
+            fs::read_to_string(main_path.join("README.md")).unwrap(),
+            "# Test\n"
+        );
+        assert!(!main_path.join("staged.txt").exists());
+        assert!(main_path.join("scratch.txt").exists());
+
+        app.config.discard_mode = None;
+        app.handle_list_input(KeyCode::Char('X'), KeyModifiers::NONE);
+        for c in "main".chars() {
+            app.handle_discard_modal_input(KeyCode::Char(c), main_path.clone(), "main".into());
+        }
+        app.handle_discard_modal_input(KeyCode::Enter, main_path.clone(), "main".into());
+        assert!(!main_path.join("scratch.txt").exists());
+        assert!(git::list_changed_files(&main_path).unwrap().is_empty());
+
+        app.handle_list_input(KeyCode::Char('X'), KeyModifiers::NONE);
+        assert_eq!(app.state, AppState::List);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn upstream_select_preselects_same_named_remote_branch_and_sets_it() {
+        let base = temp_dir("upstream_select");
+        let (bare_path, main_path) = create_test_project(&base);
+        for args in [
+            vec![
+                "config",
+                "remote.origin.fetch",
+                "+refs/heads/*:refs/remotes/origin/*",
+            ],
+            vec!["fetch", "origin"],
+            vec!["update-ref", "refs/remotes/origin/release", "HEAD"],
+        ] {
+            assert_git_success(
+                git_cmd()
+                    .current_dir(&bare_path)
+                    .args(&args)
+                    .output()
+                    .unwrap(),
+                "git setup failed",
+            );
+        }
+        let mut worktree = test_worktree("main", WorktreeStatus::Clean);
+        worktree.path = main_path.clone();
+        worktree.branch = Some("main".to_string());
+        let mut app = test_app(vec![worktree], 0, &bare_path.to_string_lossy());
+
+        app.handle_list_input(KeyCode::Char('B'), KeyModifiers::NONE);
+        let branches = vec!["origin/main".to_string(), "origin/release".to_string()];
+        assert_eq!(
+            app.state,
+            AppState::UpstreamSelect {
+                branches: branches.clone(),
+                selected: 0,
+            }
+        );
+
+        app.handle_upstream_select_input(KeyCode::Char('j'), branches.clone(), 0);
+        assert!(matches!(
+            app.state,
+            AppState::UpstreamSelect { selected: 1, .. }
+        ));
+        app.handle_upstream_select_input(KeyCode::Enter, branches, 1);
+        assert_eq!(app.state, AppState::List);
+        assert_eq!(
+            app.branch_upstreams.get("main").map(String::as_str),
+            Some("origin/release")
+        );
+        assert_eq!(
+            git::branch_upstreams(&bare_path)
+                .get("main")
+                .map(String::as_str),
+            Some("origin/release")
         );
+
+        app.handle_list_input(KeyCode::Char('B'), KeyModifiers::NONE);
+        assert!(matches!(
+            app.state,
+            AppState::UpstreamSelect { selected: 1, .. }
+        ));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn ctrl_r_repairs_a_worktree_moved_on_disk() {
+        let base = temp_dir("repair_links");
+        let (bare_path, main_path) = create_test_project(&base);
+        let project_root = base.join("repo");
+        let feature_path = project_root.join("feature");
         assert_git_success(
             git_cmd()
-                .args([
-                    "-C",
-                    &bare_path.to_string_lossy(),
-                    "worktree",
-                    "add",
-                    &main_path.to_string_lossy(),
-                    "main",
-                ])
+                .current_dir(&main_path)
+                .args(["worktree", "add", "-b", "feature"])
+                .arg(&feature_path)
                 .output()
                 .unwrap(),
-            "git worktree add main failed",
+            "git worktree add feature failed",
+        );
+        let moved_path = project_root.join("feature-moved");
+        fs::rename(&feature_path, &moved_path).unwrap();
+        assert_eq!(
+            worktree_repair::broken_links(&bare_path, &project_root),
+            vec![moved_path.clone()]
         );
 
-        (bare_path, main_path)
+        let mut app = test_app(Vec::new(), 0, &bare_path.to_string_lossy());
+        app.project_root_path = project_root.clone();
+        app.handle_list_input(KeyCode::Char('r'), KeyModifiers::CONTROL);
+
+        assert!(worktree_repair::broken_links(&bare_path, &project_root).is_empty());
+        assert!(app
+            .worktrees
+            .iter()
+            .any(|worktree| worktree.path.ends_with("feature-moved")));
+        let status = git_cmd()
+            .current_dir(&moved_path)
+            .args(["status", "--short"])
+            .output()
+            .unwrap();
+        assert_git_success(status, "git status in moved worktree failed");
+
+        let _ = fs::remove_dir_all(&base);
     }
 
-    fn wait_for_background_op(app: &mut App) {
-        let deadline = Instant::now() + StdDuration::from_secs(5);
-        while app.active_op.is_some() {
-            app.poll_background_op();
-            if app.active_op.is_none() {
-                break;
+    #[test]
+    fn command_log_shows_git_commands_with_their_output() {
+        let base = temp_dir("command_log");
+        let (bare_path, _main_path) = create_test_project(&base);
+        let mut app = test_app(Vec::new(), 0, &bare_path.to_string_lossy());
+        app.handle_list_input(KeyCode::Char('x'), KeyModifiers::NONE);
+
+        app.handle_list_input(KeyCode::Char('@'), KeyModifiers::NONE);
+        assert_eq!(
+            app.state,
+            AppState::CommandLog {
+                scroll: 0,
+                follow: true
             }
-            assert!(Instant::now() < deadline, "background operation timed out");
-            std::thread::sleep(StdDuration::from_millis(20));
-        }
-    }
+        );
+        let prune = format!("git -C {} worktree prune", bare_path.to_string_lossy());
+        assert!(command_log::lines()
+            .iter()
+            .any(|line| line.contains("] exit 0 $ ") && line.contains(&prune)));
 
-    fn test_app(worktrees: Vec<Worktree>, selected_index: usize, bare_repo_path: &str) -> App {
-        App {
-            worktrees,
-            selected_index,
-            selected_worktree_paths: HashSet::new(),
-            state: AppState::List,
-            message: None,
-            bare_repo_path: PathBuf::from(bare_repo_path),
-            project_root_path: PathBuf::from("/repo"),
-            repo_is_bare: true,
-            input_buffer: String::new(),
-            should_quit: false,
-            config: Config::default(),
-            exit_action: ExitAction::Quit,
-            current_worktree_path: None,
-            merge_source_branch: None,
-            has_shell_integration: false,
-            filter_text: String::new(),
-            is_filtering: false,
-            last_key: None,
-            sort_mode: SortMode::default(),
-            verbose: false,
-            last_command_detail: None,
-            spinner_tick: 0,
-            theme: crate::ui::theme::detect_theme(),
-            viewport_height: Cell::new(0),
-            help_scroll_offset: 0,
-            script_status: ScriptStatus::Idle,
-            script_receiver: None,
-            pr_status_receiver: None,
-            active_op: None,
-            active_op_info: None,
-            selected_details: None,
-            add_base_branch: "main".to_string(),
-        }
-    }
+        app.handle_command_log_input(KeyCode::Char('g'), 0, true);
+        assert!(matches!(
+            app.state,
+            AppState::CommandLog {
+                scroll: 0,
+                follow: false
+            }
+        ));
+        app.handle_command_log_input(KeyCode::Esc, 0, false);
+        assert_eq!(app.state, AppState::List);
 
-    fn test_worktree(name: &str, status: WorktreeStatus) -> Worktree {
-        Worktree {
-            path: PathBuf::from(format!("/repo/{}", name)),
-            branch: Some(name.to_string()),
-            is_bare: false,
-            status,
-            last_commit_time: None,
-            ahead_behind: None,
-            github_pr_status: None,
-        }
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn config_modal_tmux_auto_run_is_read_only_project_modal() {
-        let mut app = test_app(vec![], 0, "/repo/.bare");
+    fn prune_lists_stale_entries_and_waits_for_confirmation() {
+        let base = temp_dir("prune_preview");
+        let (bare_path, main_path) = create_test_project(&base);
+        let mut app = test_app(Vec::new(), 0, &bare_path.to_string_lossy());
+        app.handle_list_input(KeyCode::Char('x'), KeyModifiers::NONE);
+        assert_eq!(app.state, AppState::List);
+        assert_eq!(app.message.as_ref().unwrap().text, "Nothing to prune");
 
-        app.handle_config_modal_input(KeyCode::Enter, 5, false);
+        let feature_path = base.join("repo").join("feature");
+        assert_git_success(
+            git_cmd()
+                .current_dir(&main_path)
+                .args(["worktree", "add", "-b", "feature"])
+                .arg(&feature_path)
+                .output()
+                .unwrap(),
+            "git worktree add feature failed",
+        );
+        fs::remove_dir_all(&feature_path).unwrap();
+        let listed = || {
+            let output = git_cmd()
+                .current_dir(&main_path)
+                .args(["worktree", "list", "--porcelain"])
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).contains("feature")
+        };
 
-        assert!(!app.config.run_post_add_script_in_tmux);
-        assert_eq!(
-            app.message.as_ref().map(|message| message.text.as_str()),
-            Some("Post-add auto-run can only be changed in global config")
+        app.handle_list_input(KeyCode::Char('x'), KeyModifiers::NONE);
+        let AppState::ConfirmPrune { entries } = app.state.clone() else {
+            panic!("expected prune confirmation, got {:?}", app.state);
+        };
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].contains("feature"));
+        app.handle_confirm_prune_input(KeyCode::Char('n'));
+        assert_eq!(app.state, AppState::List);
+        assert!(listed());
+
+        app.handle_list_input(KeyCode::Char('x'), KeyModifiers::NONE);
+        app.handle_confirm_prune_input(KeyCode::Char('y'));
+        assert_eq!(app.state, AppState::List);
+        assert!(!listed());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn archive_removes_the_worktree_keeps_the_branch_and_k_restores_it() {
+        let base = temp_dir("archive_restore");
+        let (bare_path, main_path) = create_test_project(&base);
+        let project_root = base.join("repo");
+        let feature_path = project_root.join("feature");
+        assert_git_success(
+            git_cmd()
+                .current_dir(&main_path)
+                .args(["worktree", "add", "-b", "feature"])
+                .arg(&feature_path)
+                .output()
+                .unwrap(),
+            "git worktree add feature failed",
         );
+        Metadata::update(&project_root, |metadata| {
+            metadata
+                .notes
+                .insert("feature".to_string(), "maybe later".to_string());
+        })
+        .unwrap();
+        let mut feature = test_worktree("feature", WorktreeStatus::Clean);
+        feature.path = feature_path.clone();
+        feature.branch = Some("feature".to_string());
+        let mut app = test_app(vec![feature], 0, &bare_path.to_string_lossy());
+        app.project_root_path = project_root.clone();
 
-        app.config.run_post_add_script_in_tmux = true;
-        app.handle_config_modal_input(KeyCode::Enter, 5, false);
+        app.handle_confirm_delete_input(KeyCode::Char('a'), false, false, false, false);
+        assert!(!app.message.as_ref().unwrap().is_error);
+        assert!(!feature_path.exists());
+        assert!(git::resolve_commit(&bare_path, "refs/heads/feature").is_some());
+        let entries = archive::load(&project_root).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, feature_path);
+        assert_eq!(entries[0].note.as_deref(), Some("maybe later"));
+        assert!(!app.metadata.notes.contains_key("feature"));
+
+        app.handle_list_input(KeyCode::Char('K'), KeyModifiers::SHIFT);
+        assert_eq!(
+            app.state,
+            AppState::ArchivedWorktrees {
+                entries: entries.clone(),
+                selected: 0
+            }
+        );
+        app.handle_archived_worktrees_input(KeyCode::Enter, entries, 0);
+        assert_eq!(app.state, AppState::List);
+        assert!(feature_path.join(".git").exists());
+        assert!(archive::load(&project_root).unwrap().is_empty());
+        assert_eq!(
+            app.metadata.notes.get("feature").map(String::as_str),
+            Some("maybe later")
+        );
 
-        assert!(app.config.run_post_add_script_in_tmux);
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn config_modal_toggles_tmux_worktree_mode() {
-        let mut app = test_app(vec![], 0, "/repo/.bare");
-
-        app.handle_config_modal_input(KeyCode::Enter, 4, false);
-
-        assert!(app.config.tmux_worktree_mode);
+    fn duplicate_adds_a_detached_snapshot_or_a_copy_branch_at_head() {
+        let base = temp_dir("duplicate_worktree");
+        let (bare_path, main_path) = create_test_project(&base);
+        let head = git::resolve_commit(&main_path, "HEAD").unwrap();
+        let mut main = test_worktree("main", WorktreeStatus::Clean);
+        main.path = main_path.clone();
+        main.branch = Some("main".to_string());
+        let mut app = test_app(vec![main], 0, &bare_path.to_string_lossy());
+        app.project_root_path = base.join("repo");
+
+        app.handle_list_input(KeyCode::Char('Y'), KeyModifiers::SHIFT);
         assert_eq!(
-            app.message.as_ref().map(|message| message.text.as_str()),
-            Some("Tmux worktree mode enabled (press 's' to save to file)")
+            app.state,
+            AppState::DuplicateWorktree {
+                commit: head.clone(),
+                new_branch: false
+            }
         );
+        app.handle_duplicate_input(KeyCode::Enter, head.clone(), false);
+        wait_for_background_op(&mut app);
+        let snapshot = base.join("repo").join("main-copy");
+        assert_eq!(git::resolve_commit(&snapshot, "HEAD"), Some(head.clone()));
+        assert_eq!(git::current_branch(&snapshot), None);
 
-        app.handle_config_modal_input(KeyCode::Enter, 4, false);
+        app.select_worktree_path(&main_path);
+        app.handle_duplicate_input(KeyCode::Char('b'), head.clone(), false);
+        assert_eq!(
+            app.state,
+            AppState::DuplicateWorktree {
+                commit: head.clone(),
+                new_branch: true
+            }
+        );
+        app.handle_duplicate_input(KeyCode::Char('y'), head.clone(), true);
+        wait_for_background_op(&mut app);
+        let copy = base.join("repo").join("main-copy-2");
+        assert_eq!(git::current_branch(&copy).as_deref(), Some("main-copy-2"));
+        assert_eq!(git::resolve_commit(&copy, "HEAD"), Some(head));
+        assert!(app.worktrees.iter().any(|wt| wt.path == copy));
 
-        assert!(!app.config.tmux_worktree_mode);
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn enter_worktree_focuses_matching_tmux_pane_in_tmux_mode() {
-        let _guard = env_lock().lock().unwrap();
-        let base = temp_dir("enter_tmux_pane_focus");
-        let fake_bin = base.join("bin");
-        fs::create_dir_all(&fake_bin).unwrap();
-
-        let tmux_log = base.join("tmux-args.txt");
-        let fake_tmux = fake_bin.join("tmux");
-        fs::write(
-            &fake_tmux,
-            format!(
-                "#!/bin/sh\nprintf '%s\\n' \"$*\" >> '{}'\nif [ \"$1\" = \"list-panes\" ]; then printf '%%1\\tfeature\\t0:1.0\\n'; fi\n",
-                tmux_log.to_string_lossy().replace('\'', "'\\''")
-            ),
-        )
-        .unwrap();
-        make_executable(&fake_tmux);
-
-        let original_path = std::env::var_os("PATH");
-        let path = if let Some(existing) = original_path.as_ref() {
-            let mut paths = vec![fake_bin.clone()];
-            paths.extend(std::env::split_paths(existing));
-            std::env::join_paths(paths).unwrap()
-        } else {
-            fake_bin.clone().into_os_string()
-        };
-        std::env::set_var("PATH", path);
+    fn orphan_branches_lists_branches_without_worktrees_and_acts_on_them() {
+        let base = temp_dir("orphan_branches");
+        let (bare_path, main_path) = create_test_project(&base);
+        fs::write(main_path.join("merged.txt"), "merged\n").unwrap();
+        for args in [
+            vec!["branch", "feature/done"],
+            vec!["branch", "feature/keep"],
+            vec!["checkout", "-b", "feature/wip"],
+            vec!["add", "merged.txt"],
+            vec!["commit", "-m", "wip"],
+            vec!["checkout", "main"],
+        ] {
+            assert_git_success(
+                git_cmd()
+                    .current_dir(&main_path)
+                    .args(&args)
+                    .output()
+                    .unwrap(),
+                "git setup failed",
+            );
+        }
+        let mut main = test_worktree("main", WorktreeStatus::Clean);
+        main.path = main_path.clone();
+        let mut app = test_app(vec![main], 0, &bare_path.to_string_lossy());
+        app.project_root_path = base.join("repo");
+
+        // Ctrl+O in the add modal opens the same list as O
+        app.state = AppState::AddModal;
+        app.input_buffer = "feat".to_string();
+        app.handle_add_modal_input(KeyCode::Char('o'), KeyModifiers::CONTROL);
+        assert!(app.input_buffer.is_empty());
+        assert_eq!(
+            app.state,
+            AppState::OrphanBranches {
+                branches: vec![
+                    "feature/done".to_string(),
+                    "feature/keep".to_string(),
+                    "feature/wip".to_string()
+                ],
+                selected: 0,
+            }
+        );
 
-        let mut app = test_app(
-            vec![test_worktree("feature", WorktreeStatus::Clean)],
+        app.handle_orphan_branches_input(
+            KeyCode::Char('d'),
+            vec!["feature/done".to_string(), "feature/wip".to_string()],
+            1,
+        );
+        assert!(app.message.as_ref().unwrap().is_error);
+        app.handle_orphan_branches_input(
+            KeyCode::Char('d'),
+            vec!["feature/done".to_string(), "feature/wip".to_string()],
             0,
-            "/repo/.bare",
         );
-        app.config.tmux_worktree_mode = true;
-
-        app.enter_worktree();
-
-        if let Some(path) = original_path {
-            std::env::set_var("PATH", path);
-        } else {
-            std::env::remove_var("PATH");
-        }
+        assert_eq!(
+            app.state,
+            AppState::OrphanBranches {
+                branches: vec!["feature/wip".to_string()],
+                selected: 0,
+            }
+        );
+        assert!(!git::list_local_branches(&bare_path)
+            .unwrap()
+            .contains(&"feature/done".to_string()));
+        app.handle_orphan_branches_input(KeyCode::Char('D'), vec!["feature/wip".to_string()], 0);
+        assert_eq!(app.state, AppState::List);
 
+        app.handle_list_input(KeyCode::Char('O'), KeyModifiers::NONE);
+        app.handle_orphan_branches_input(KeyCode::Enter, vec!["feature/keep".to_string()], 0);
         assert!(app.should_quit);
-        assert!(matches!(app.exit_action, ExitAction::Quit));
-        let tmux_args = fs::read_to_string(tmux_log).unwrap();
-        assert!(tmux_args.contains("list-panes -a -F"));
-        assert!(tmux_args.contains("switch-client -t 0:1.0"));
-        assert!(tmux_args.contains("select-pane -t %1"));
+        match &app.exit_action {
+            ExitAction::CreateWorktree(request) => assert_eq!(request.branch, "feature/keep"),
+            other => panic!("expected create request, got {other:?}"),
+        }
 
-        let _ = fs::remove_dir_all(base);
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn post_add_script_configured_path_editor_targets_configured_script() {
-        let base = temp_dir("post_add_script_configured_path_editor");
-        let project_root = base.join("project");
-        fs::create_dir_all(project_root.join(".owt")).unwrap();
-
-        let editor_log = base.join("editor-arg.txt");
-        let editor_path = base.join("fake-editor.sh");
-        fs::write(
-            &editor_path,
-            format!(
-                "#!/bin/sh\nprintf '%s' \"$1\" > '{}'\n",
-                editor_log.to_string_lossy().replace('\'', "'\\''")
-            ),
-        )
-        .unwrap();
-        make_executable(&editor_path);
-
-        let mut app = test_app(vec![], 0, "/repo/.bare");
-        app.project_root_path = project_root.clone();
-        app.config.editor = Some(editor_path.to_string_lossy().to_string());
-        app.config.post_add_script = Some("setup.sh".to_string());
+    fn remote_branch_browser_fetches_filters_and_creates_a_tracking_worktree() {
+        let base = temp_dir("remote_branches");
+        let (bare_path, _main_path) = create_test_project(&base);
+        for branch in ["feature/login", "fix/typo"] {
+            assert_git_success(
+                git_cmd()
+                    .current_dir(base.join("source"))
+                    .args(["branch", branch])
+                    .output()
+                    .unwrap(),
+                "git branch failed",
+            );
+        }
+        assert_git_success(
+            git_cmd()
+                .current_dir(&bare_path)
+                .args([
+                    "config",
+                    "remote.origin.fetch",
+                    "+refs/heads/*:refs/remotes/origin/*",
+                ])
+                .output()
+                .unwrap(),
+            "git config remote.origin.fetch failed",
+        );
+        let mut app = test_app(Vec::new(), 0, &bare_path.to_string_lossy());
 
-        app.open_post_add_script_editor();
+        app.handle_list_input(KeyCode::Char('E'), KeyModifiers::NONE);
+        assert!(app.remote_fetch_receiver.is_some());
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while app.remote_fetch_receiver.is_some() {
+            assert!(Instant::now() < deadline, "remote fetch timed out");
+            std::thread::sleep(StdDuration::from_millis(20));
+            app.poll_remote_fetch();
+        }
+        assert_eq!(
+            app.remote_branches,
+            vec!["origin/feature/login", "origin/fix/typo", "origin/main"]
+        );
 
-        let configured_script = project_root.join("setup.sh");
-        assert!(configured_script.exists());
-        assert!(!Config::post_add_script_path(&project_root).exists());
+        for c in "lgn".chars() {
+            let AppState::RemoteBranches { filter, selected } = app.state.clone() else {
+                panic!("expected remote branch browser, got {:?}", app.state);
+            };
+            app.handle_remote_branches_input(
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+                filter,
+                selected,
+            );
+        }
         assert_eq!(
-            fs::read_to_string(editor_log).unwrap(),
-            configured_script.to_string_lossy()
+            app.filtered_remote_branches("lgn"),
+            vec!["origin/feature/login"]
         );
+        app.handle_remote_branches_input(KeyCode::Enter, KeyModifiers::NONE, "lgn".to_string(), 0);
+        assert!(app.should_quit);
+        match &app.exit_action {
+            ExitAction::CreateWorktree(request) => assert_eq!(request.branch, "feature/login"),
+            other => panic!("expected create request, got {other:?}"),
+        }
 
-        let _ = fs::remove_dir_all(base);
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn post_add_script_configured_path_execution_ignores_default_script() {
-        let base = temp_dir("post_add_script_configured_path_default_ignored");
-        let project_root = base.join("project");
-        let worktree_path = base.join("worktree");
-        fs::create_dir_all(project_root.join(".owt")).unwrap();
+    fn new_worktree_offers_the_install_command_and_runs_it_in_the_background() {
+        let base = temp_dir("install_offer");
+        let worktree_path = base.join("feature-login");
         fs::create_dir_all(&worktree_path).unwrap();
-        fs::write(Config::post_add_script_path(&project_root), "#!/bin/sh\n").unwrap();
-
-        let mut app = test_app(vec![], 0, "/repo/.bare");
-        app.project_root_path = project_root;
-        app.config.post_add_script = Some("setup.sh".to_string());
-        app.config.run_post_add_script_in_tmux = true;
-
-        app.run_post_add_script(&worktree_path);
+        fs::write(worktree_path.join("package.json"), "{}").unwrap();
+        let mut worktree = test_worktree("feature-login", WorktreeStatus::Clean);
+        worktree.path = worktree_path.clone();
+        let mut app = test_app(vec![worktree], 0, "/repo/.bare");
+        app.project_root_path = base.clone();
+        app.config.install_command = Some("echo ok > installed".to_string());
+
+        let added = || OpResult {
+            kind: OpKind::Add,
+            success: true,
+            message: "Created worktree: feature-login".to_string(),
+            cmd_detail: String::new(),
+            worktree_path: worktree_path.clone(),
+            affected_paths: vec![worktree_path.clone()],
+            display_name: "feature-login".to_string(),
+        };
+        app.handle_op_result(added());
+        assert_eq!(
+            app.state,
+            AppState::ConfirmInstall {
+                worktree_path: worktree_path.clone(),
+                command: "echo ok > installed".to_string(),
+            }
+        );
+        app.handle_confirm_install_input(
+            KeyCode::Char('n'),
+            worktree_path.clone(),
+            "echo ok > installed".to_string(),
+        );
+        assert_eq!(app.state, AppState::List);
 
-        assert!(app.script_receiver.is_none());
-        assert!(matches!(app.script_status, ScriptStatus::Idle));
-        assert!(app.message.is_none());
+        app.handle_op_result(added());
+        app.handle_confirm_install_input(
+            KeyCode::Enter,
+            worktree_path.clone(),
+            "echo ok > installed".to_string(),
+        );
+        // Runs in the background: the list stays open and the row shows it
+        assert_eq!(app.state, AppState::List);
+        assert!(matches!(
+            &app.script_status,
+            ScriptStatus::Running { worktree_path: path, label, .. }
+                if *path == worktree_path && label == "Install"
+        ));
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while app.script_receiver.is_some() {
+            app.poll_script_status();
+            assert!(Instant::now() < deadline, "install did not finish");
+            std::thread::sleep(StdDuration::from_millis(10));
+        }
+        assert!(worktree_path.join("installed").is_file());
+        assert_eq!(
+            app.message.as_ref().unwrap().text,
+            "Install finished for feature-login"
+        );
 
-        let _ = fs::remove_dir_all(base);
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn post_add_script_configured_path_execution_launches_configured_script_in_tmux() {
-        let _guard = env_lock().lock().unwrap();
-        let base = temp_dir("post_add_script_configured_path_tmux");
+    fn setup_script_output_streams_into_panel() {
+        let base = temp_dir("setup_script_output");
         let project_root = base.join("project");
-        let worktree_path = base.join("worktree");
-        let fake_bin = base.join("bin");
-        fs::create_dir_all(&project_root).unwrap();
+        let worktree_path = base.join("feature-login");
+        fs::create_dir_all(project_root.join(".owt")).unwrap();
         fs::create_dir_all(&worktree_path).unwrap();
-        fs::create_dir_all(&fake_bin).unwrap();
-
-        let configured_script = project_root.join("setup.sh");
-        fs::write(&configured_script, "#!/bin/sh\n").unwrap();
-        let tmux_log = base.join("tmux-args.txt");
-        let fake_tmux = fake_bin.join("tmux");
         fs::write(
-            &fake_tmux,
-            format!(
-                "#!/bin/sh\nprintf '%s' \"$*\" > '{}'\nexit 0\n",
-                tmux_log.to_string_lossy().replace('\'', "'\\''")
-            ),
+            Config::post_add_script_path(&project_root),
+            "echo \"installing $OWT_BRANCH\"\necho 'missing lockfile' >&2\nexit 3\n",
         )
         .unwrap();
-        make_executable(&fake_tmux);
-
-        let original_path = std::env::var_os("PATH");
-        let path = if let Some(existing) = original_path.as_ref() {
-            let mut paths = vec![fake_bin.clone()];
-            paths.extend(std::env::split_paths(existing));
-            std::env::join_paths(paths).unwrap()
-        } else {
-            fake_bin.clone().into_os_string()
-        };
-        std::env::set_var("PATH", path);
-
-        let mut app = test_app(vec![], 0, "/repo/.bare");
-        app.project_root_path = project_root.clone();
-        app.config.post_add_script = Some("setup.sh".to_string());
-        app.config.run_post_add_script_in_tmux = true;
-
-        app.run_post_add_script(&worktree_path);
+        let mut worktree = test_worktree("feature-login", WorktreeStatus::Clean);
+        worktree.path = worktree_path.clone();
+        let mut app = test_app(vec![worktree], 0, "/repo/.bare");
+        app.project_root_path = project_root;
 
-        if let Some(path) = original_path {
-            std::env::set_var("PATH", path);
-        } else {
-            std::env::remove_var("PATH");
+        app.handle_list_input(KeyCode::Char('H'), KeyModifiers::NONE);
+        assert!(matches!(
+            app.state,
+            AppState::ScriptOutput { follow: true, .. }
+        ));
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while app.script_receiver.is_some() {
+            app.poll_script_status();
+            assert!(Instant::now() < deadline, "setup script did not finish");
+            std::thread::sleep(StdDuration::from_millis(10));
         }
 
-        let tmux_args = fs::read_to_string(tmux_log).unwrap();
-        assert!(tmux_args.contains("new-session -d -s owt-post-add-"));
-        assert!(tmux_args.contains(&format!("cd {}", shell_quote(&worktree_path))));
-        assert!(tmux_args.contains(&format!("sh {}", shell_quote(&configured_script))));
-        assert!(app.script_receiver.is_some());
-
-        let _ = fs::remove_dir_all(base);
-    }
-
-    #[test]
-    fn conflicting_worktree_for_branch_reports_existing_checkout_path() {
-        let app = test_app(
-            vec![Worktree {
-                path: PathBuf::from("/tmp/external/staging"),
-                branch: Some("staging".to_string()),
-                is_bare: false,
-                status: WorktreeStatus::Clean,
-                last_commit_time: None,
-                ahead_behind: None,
-                github_pr_status: None,
-            }],
-            0,
-            "/repo/.bare",
-        );
-
-        let desired_path = app.worktree_path_for_branch("staging");
-        let conflict = app
-            .conflicting_worktree_for_branch("staging", &desired_path)
-            .expect("staging checkout should be reported");
-
-        assert_eq!(desired_path, PathBuf::from("/repo/staging"));
-        assert_eq!(conflict.path, PathBuf::from("/tmp/external/staging"));
-    }
+        assert!(app
+            .script_output
+            .contains(&"installing feature-login".to_string()));
+        assert!(app.script_output.contains(&"missing lockfile".to_string()));
+        let message = app.message.as_ref().unwrap();
+        assert!(message.is_error);
+        assert_eq!(message.text, "Setup script failed: exit 3");
 
-    #[test]
-    fn add_modal_base_label_uses_session_default_branch() {
-        let app = test_app(
-            vec![Worktree {
-                path: PathBuf::from("/repo/staging"),
-                branch: Some("staging".to_string()),
-                is_bare: false,
-                status: WorktreeStatus::Clean,
-                last_commit_time: None,
-                ahead_behind: None,
-                github_pr_status: None,
-            }],
-            0,
-            "/repo/.bare",
+        app.handle_script_output_input(KeyCode::Char('k'), 0, true);
+        assert_eq!(
+            app.state,
+            AppState::ScriptOutput {
+                scroll: 1,
+                follow: false
+            }
         );
+        app.handle_script_output_input(KeyCode::Esc, 1, false);
+        assert_eq!(app.state, AppState::List);
 
-        assert_eq!(app.add_modal_base_label(), "Base branch: main");
+        let _ = fs::remove_dir_all(base);
     }
 
     #[test]
-    fn add_modal_enter_queues_post_tui_create_request() {
-        let mut app = test_app(
-            vec![test_worktree("main", WorktreeStatus::Clean)],
-            0,
-            "/repo/.bare",
-        );
-        app.current_worktree_path = Some(PathBuf::from("/repo/main"));
-        app.input_buffer = "feature/post-tui".to_string();
+    fn task_menu_runs_task_in_all_worktrees_and_groups_output() {
+        let base = temp_dir("task_all");
+        let mut worktrees = Vec::new();
+        for name in ["api", "login"] {
+            let mut worktree = test_worktree(name, WorktreeStatus::Clean);
+            worktree.path = base.join(name);
+            fs::create_dir_all(&worktree.path).unwrap();
+            worktrees.push(worktree);
+        }
+        fs::write(base.join("api").join("ok"), "").unwrap();
+        let mut app = test_app(worktrees, 0, "/repo/.bare");
+        app.config.tasks = vec![Task {
+            name: "check".to_string(),
+            command: "echo \"$OWT_BRANCH\"; echo done; test -f ok".to_string(),
+        }];
 
-        app.handle_add_modal_input(KeyCode::Enter);
+        app.handle_task_menu_input(KeyCode::Char('a'), 0);
+        assert!(matches!(app.state, AppState::ScriptOutput { .. }));
 
-        assert!(app.should_quit);
-        assert!(app.active_op.is_none());
-        assert!(matches!(app.state, AppState::List));
-        assert!(app.input_buffer.is_empty());
-        match app.exit_action {
-            ExitAction::CreateWorktree(request) => {
-                assert_eq!(request.bare_repo_path, PathBuf::from("/repo/.bare"));
-                assert_eq!(request.project_root_path, PathBuf::from("/repo"));
-                assert_eq!(request.branch, "feature/post-tui");
-                assert_eq!(request.base_branch, "main");
-                assert_eq!(
-                    request.worktree_path,
-                    PathBuf::from("/repo/feature/post-tui")
-                );
-                assert_eq!(request.source_path, Some(PathBuf::from("/repo/main")));
-            }
-            other => panic!("expected post-TUI create request, got {other:?}"),
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while app.script_receiver.is_some() {
+            app.poll_script_status();
+            assert!(Instant::now() < deadline, "task did not finish");
+            std::thread::sleep(StdDuration::from_millis(10));
         }
-    }
-
-    #[test]
-    fn non_bare_worktree_path_uses_configurable_root_and_repo_namespace() {
-        let mut app = test_app(vec![], 0, "/repo");
-        app.repo_is_bare = false;
-        app.config.worktree_root = Some("/tmp/owt-worktrees".to_string());
 
+        // Each worktree's lines stay together, whichever finished first
+        let output = &app.script_output;
+        assert_eq!(output.len(), 8);
+        let api = output.iter().position(|line| line == "==> api").unwrap();
+        let login = output.iter().position(|line| line == "==> login").unwrap();
+        assert_eq!(output[api + 1..api + 3], ["api", "done"]);
+        assert_eq!(output[login + 1..login + 4], ["login", "done", "exit 1"]);
         assert_eq!(
-            app.worktree_path_for_branch("feature/login"),
-            PathBuf::from("/tmp/owt-worktrees/repo/feature/login")
+            app.message.as_ref().unwrap().text,
+            "Task check failed: 1 of 2 worktrees (login)"
         );
+
+        let _ = fs::remove_dir_all(base);
     }
 
     #[test]
-    fn bare_repo_ignores_worktree_root_and_keeps_sibling_layout() {
-        let mut app = test_app(vec![], 0, "/repo/.bare");
-        app.config.worktree_root = Some("/tmp/owt-worktrees".to_string());
+    fn run_command_runs_in_checked_worktrees_with_row_status() {
+        let base = temp_dir("run_command");
+        let mut worktrees = Vec::new();
+        for name in ["api", "docs", "login"] {
+            let mut worktree = test_worktree(name, WorktreeStatus::Clean);
+            worktree.path = base.join(name);
+            fs::create_dir_all(&worktree.path).unwrap();
+            worktrees.push(worktree);
+        }
+        fs::write(base.join("api").join("ok"), "").unwrap();
+        let mut app = test_app(worktrees, 0, "/repo/.bare");
+        app.handle_list_input(KeyCode::Char(' '), KeyModifiers::NONE);
+        app.selected_index = 2;
+        app.handle_list_input(KeyCode::Char(' '), KeyModifiers::NONE);
+
+        app.handle_list_input(KeyCode::Char('!'), KeyModifiers::NONE);
+        assert_eq!(app.state, AppState::RunCommand);
+        for c in "test -f ok".chars() {
+            app.handle_run_command_input(KeyCode::Char(c));
+        }
+        app.handle_run_command_input(KeyCode::Enter);
+        assert!(matches!(app.state, AppState::ScriptOutput { .. }));
+        assert_eq!(app.bulk_run.len(), 2);
+
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while app.script_receiver.is_some() {
+            app.poll_script_status();
+            assert!(Instant::now() < deadline, "command did not finish");
+            std::thread::sleep(StdDuration::from_millis(10));
+        }
 
         assert_eq!(
-            app.worktree_path_for_branch("feature/login"),
-            PathBuf::from("/repo/feature/login")
+            app.bulk_run.get(&base.join("api")),
+            Some(&BulkRunStatus::Succeeded)
+        );
+        assert_eq!(
+            app.bulk_run.get(&base.join("login")),
+            Some(&BulkRunStatus::Failed("exit 1".to_string()))
         );
+        assert_eq!(app.bulk_run.get(&base.join("docs")), None);
+        assert_eq!(
+            app.message.as_ref().unwrap().text,
+            "Command failed: 1 of 2 worktrees (login)"
+        );
+
+        let _ = fs::remove_dir_all(base);
     }
 
     #[test]
-    fn bare_repo_without_worktree_root_keeps_sibling_layout() {
-        let app = test_app(vec![], 0, "/repo/.bare");
+    fn task_menu_runs_selected_task_in_worktree() {
+        let base = temp_dir("task_menu");
+        let worktree_path = base.join("feature-login");
+        fs::create_dir_all(&worktree_path).unwrap();
+        fs::write(worktree_path.join("marker.txt"), "here\n").unwrap();
+        let mut worktree = test_worktree("feature-login", WorktreeStatus::Clean);
+        worktree.path = worktree_path.clone();
+        let mut app = test_app(vec![worktree], 0, "/repo/.bare");
+        app.config.tasks = vec![
+            Task {
+                name: "install deps".to_string(),
+                command: "exit 1".to_string(),
+            },
+            Task {
+                name: "check".to_string(),
+                command: "cat marker.txt && echo \"$OWT_BRANCH\"".to_string(),
+            },
+        ];
+
+        app.handle_list_input(KeyCode::Char('T'), KeyModifiers::NONE);
+        assert_eq!(app.state, AppState::TaskMenu { selected: 0 });
+        app.handle_task_menu_input(KeyCode::Char('j'), 0);
+        app.handle_task_menu_input(KeyCode::Enter, 1);
+        assert!(matches!(app.state, AppState::ScriptOutput { .. }));
+
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while app.script_receiver.is_some() {
+            app.poll_script_status();
+            assert!(Instant::now() < deadline, "task did not finish");
+            std::thread::sleep(StdDuration::from_millis(10));
+        }
 
         assert_eq!(
-            app.worktree_path_for_branch("feature/login"),
-            PathBuf::from("/repo/feature/login")
+            app.script_output,
+            vec![
+                "$ cat marker.txt && echo \"$OWT_BRANCH\"".to_string(),
+                "here".to_string(),
+                "feature-login".to_string(),
+            ]
+        );
+        assert_eq!(
+            app.message.as_ref().unwrap().text,
+            "Task check finished for feature-login"
         );
+
+        let _ = fs::remove_dir_all(base);
     }
 
     #[test]
-    fn paths_match_after_canonicalization() {
-        let tmp = std::env::temp_dir();
-        let canonical = tmp.canonicalize().unwrap_or_else(|_| tmp.clone());
-
-        assert!(paths_refer_to_same_location(&tmp, &canonical));
+    fn remote_sort_groups_forks_after_the_default_remote() {
+        let mut app = test_app(
+            vec![
+                test_worktree("zeta", WorktreeStatus::Clean),
+                test_worktree("review", WorktreeStatus::Clean),
+                test_worktree("alpha", WorktreeStatus::Clean),
+                test_worktree("fix", WorktreeStatus::Clean),
+            ],
+            0,
+            "/repo/.bare",
+        );
+        app.default_remote = Some("origin".to_string());
+        app.branch_remotes = HashMap::from([
+            ("review".to_string(), "upstream".to_string()),
+            ("fix".to_string(), "alice".to_string()),
+            ("zeta".to_string(), "origin".to_string()),
+        ]);
+
+        app.sort_mode = SortMode::Remote;
+        app.apply_sort();
+
+        let names: Vec<String> = app.worktrees.iter().map(Worktree::display_name).collect();
+        assert_eq!(names, ["alpha", "zeta", "fix", "review"]);
+        assert_eq!(app.remote_badge(&app.worktrees[0]), None);
+        assert_eq!(app.remote_badge(&app.worktrees[2]), Some("alice"));
+        assert_eq!(app.worktree_remote(&app.worktrees[1]), Some("origin"));
     }
 
     #[test]
-    fn add_completion_selects_created_worktree_before_enter() {
-        let base = temp_dir("add_completion_enter");
-        let (bare_path, main_path) = create_test_project(&base);
-        let project_root = bare_path.parent().unwrap().to_path_buf();
-        let feature_path = project_root.join("feature").join("enter-after-create");
-
-        git::add_worktree(
-            &bare_path,
-            "feature/enter-after-create",
-            &feature_path,
-            Some("main"),
-        )
-        .unwrap();
+    fn reverse_sort_flips_the_order_but_keeps_bare_first() {
+        let mut bare = test_worktree(".bare", WorktreeStatus::Clean);
+        bare.is_bare = true;
+        let mut app = test_app(
+            vec![
+                bare,
+                test_worktree("beta", WorktreeStatus::Clean),
+                test_worktree("alpha", WorktreeStatus::Clean),
+                test_worktree("gamma", WorktreeStatus::Clean),
+            ],
+            1,
+            "/repo/.bare",
+        );
 
-        let mut app =
-            App::new(bare_path.clone(), project_root, true, Some(main_path), true).unwrap();
+        app.handle_list_input(KeyCode::Char('S'), KeyModifiers::SHIFT);
+        let names: Vec<String> = app.worktrees.iter().map(Worktree::display_name).collect();
+        assert!(app.worktrees[0].is_bare);
+        assert_eq!(names[1..], ["gamma", "beta", "alpha"]);
+        assert_eq!(app.selected_worktree().unwrap().display_name(), "beta");
+        assert_eq!(app.message.as_ref().unwrap().text, "Sort: name (reversed)");
 
-        app.handle_op_result(OpResult {
-            kind: OpKind::Add,
-            success: true,
-            message: "Created worktree: feature/enter-after-create".to_string(),
-            cmd_detail: String::new(),
-            worktree_path: feature_path.clone(),
-            affected_paths: vec![feature_path.clone()],
-            display_name: "feature/enter-after-create".to_string(),
-        });
-        app.enter_worktree();
+        app.handle_list_input(KeyCode::Char('S'), KeyModifiers::SHIFT);
+        let names: Vec<String> = app.worktrees.iter().map(Worktree::display_name).collect();
+        assert_eq!(names[1..], ["alpha", "beta", "gamma"]);
+    }
 
-        match app.exit_action {
-            ExitAction::ChangeDirectory(path) => {
-                assert!(paths_refer_to_same_location(&path, &feature_path));
+    #[test]
+    fn disk_usage_column_measures_worktrees_in_the_background() {
+        let base = temp_dir("disk_usage");
+        let worktree_path = base.join("feature");
+        fs::create_dir_all(&worktree_path).unwrap();
+        fs::write(worktree_path.join("bundle.js"), vec![b'x'; 3000]).unwrap();
+        let mut worktree = test_worktree("feature", WorktreeStatus::Clean);
+        worktree.path = worktree_path.clone();
+        let mut app = test_app(vec![worktree], 0, "/repo/.bare");
+
+        app.handle_list_input(KeyCode::Char('z'), KeyModifiers::empty());
+        assert!(app.show_disk_usage);
+        for _ in 0..100 {
+            app.poll_disk_usage();
+            if app.disk_usage_receiver.is_none() {
+                break;
             }
-            ExitAction::Quit => panic!("enter should request directory change"),
-            ExitAction::CreateWorktree(_) => panic!("enter should not create a worktree"),
+            std::thread::sleep(Duration::from_millis(10));
         }
-        assert!(app.should_quit);
+        assert_eq!(app.disk_usage.get(&worktree_path), Some(&3000));
 
-        let _ = fs::remove_dir_all(base);
+        app.handle_list_input(KeyCode::Char('z'), KeyModifiers::empty());
+        assert!(!app.show_disk_usage);
+        assert!(app.disk_usage.contains_key(&worktree_path));
+
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn copy_files_nested_branch() {
-        let base = temp_dir("copy_files_nested_branch");
+    fn resume_after_editor_updates_the_touched_worktree_and_rate_limits_full_refresh() {
+        let base = temp_dir("resume_after_external");
         let (bare_path, main_path) = create_test_project(&base);
         let project_root = bare_path.parent().unwrap().to_path_buf();
-        let branch = "feature/copy-files-nested";
-        let worktree_path = project_root.join("feature").join("copy-files-nested");
-
-        fs::create_dir_all(main_path.join("config")).unwrap();
-        fs::write(main_path.join("config/local.env"), "TOKEN=secret\n").unwrap();
+        let feature_path = project_root.join("feature");
+        git::add_worktree(&bare_path, "feature", &feature_path, Some("main")).unwrap();
 
         let mut app = App::new(
             bare_path.clone(),
             project_root.clone(),
             true,
-            Some(main_path.clone()),
+            Some(feature_path.clone()),
             true,
         )
         .unwrap();
-        app.config.copy_files = vec!["config/local.env".to_string()];
-        app.input_buffer = branch.to_string();
-
-        app.add_worktree();
-        wait_for_background_op(&mut app);
+        assert_eq!(app.selected_worktree().unwrap().path, feature_path);
 
-        assert!(worktree_path.exists());
+        fs::write(feature_path.join("README.md"), "# Edited\n").unwrap();
+        app.resume_after_external(&feature_path);
+        assert_eq!(app.selected_worktree().unwrap().path, feature_path);
         assert_eq!(
-            fs::read_to_string(worktree_path.join("config/local.env")).unwrap(),
-            "TOKEN=secret\n"
+            app.selected_worktree().unwrap().status,
+            WorktreeStatus::Unstaged
         );
-        let message = app.message.as_ref().unwrap();
-        assert!(!message.is_error);
-        assert!(message
-            .text
-            .contains("Created worktree: feature/copy-files-nested"));
-        assert!(!message.text.contains("Copy warnings:"));
+        assert!(app.refresh_receiver.is_some());
 
-        let _ = fs::remove_dir_all(base);
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while app.refresh_receiver.is_some() {
+            app.poll_background_refresh();
+            assert!(Instant::now() < deadline, "background refresh timed out");
+            std::thread::sleep(StdDuration::from_millis(20));
+        }
+        assert_eq!(app.selected_worktree().unwrap().path, feature_path);
+        assert!(app.worktrees.iter().any(|wt| wt.path == main_path));
+
+        // A second return within the interval only updates the touched worktree
+        app.resume_after_external(&feature_path);
+        assert!(app.refresh_receiver.is_none());
+
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn copy_files_missing() {
-        let base = temp_dir("copy_files_missing");
+    fn auto_refresh_reloads_the_list_once_the_interval_passes() {
+        let base = temp_dir("auto_refresh");
+        let (bare_path, _main_path) = create_test_project(&base);
+        let project_root = bare_path.parent().unwrap().to_path_buf();
+        let mut app = App::new(bare_path.clone(), project_root.clone(), true, None, true).unwrap();
+        let feature_path = project_root.join("feature");
+        git::add_worktree(&bare_path, "feature", &feature_path, Some("main")).unwrap();
+
+        app.poll_auto_refresh();
+        assert!(app.last_auto_refresh.is_none());
+
+        app.config.auto_refresh = Some(30);
+        app.poll_auto_refresh();
+        assert!(app.refresh_receiver.is_none());
+        assert!(app.last_auto_refresh.is_some());
+
+        let overdue = Instant::now() - StdDuration::from_secs(31);
+        app.last_auto_refresh = Some(overdue);
+        app.state = AppState::HelpModal;
+        app.poll_auto_refresh();
+        assert!(app.refresh_receiver.is_none());
+
+        app.state = AppState::List;
+        app.poll_auto_refresh();
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while app.refresh_receiver.is_some() {
+            app.poll_background_refresh();
+            assert!(Instant::now() < deadline, "background refresh timed out");
+            std::thread::sleep(StdDuration::from_millis(20));
+        }
+        assert!(app.worktrees.iter().any(|wt| wt.path == feature_path));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn auto_fetch_fetches_in_the_background_and_updates_behind_counts() {
+        let base = temp_dir("auto_fetch");
         let (bare_path, main_path) = create_test_project(&base);
+        let source_path = base.join("source");
+        for args in [
+            vec![
+                "config",
+                "remote.origin.fetch",
+                "+refs/heads/*:refs/remotes/origin/*",
+            ],
+            vec!["fetch", "origin"],
+            vec!["branch", "--set-upstream-to=origin/main", "main"],
+        ] {
+            assert_git_success(
+                git_cmd()
+                    .current_dir(&main_path)
+                    .args(&args)
+                    .output()
+                    .unwrap(),
+                "git setup failed",
+            );
+        }
+        assert_git_success(
+            git_cmd()
+                .current_dir(&source_path)
+                .args(["commit", "--allow-empty", "-m", "upstream work"])
+                .output()
+                .unwrap(),
+            "git commit failed",
+        );
         let project_root = bare_path.parent().unwrap().to_path_buf();
-        let branch = "feature/copy-files-missing";
-        let worktree_path = project_root.join("feature").join("copy-files-missing");
+        let mut app = App::new(bare_path.clone(), project_root, true, None, true).unwrap();
+        let behind = |app: &App| {
+            app.worktrees
+                .iter()
+                .find(|wt| wt.path == main_path)
+                .and_then(|wt| wt.ahead_behind.as_ref())
+                .map_or(0, |counts| counts.behind)
+        };
+        assert_eq!(behind(&app), 0);
+
+        app.poll_auto_fetch();
+        assert!(app.last_auto_fetch.is_none());
+        app.config.auto_fetch = Some(5);
+        app.poll_auto_fetch();
+        assert!(app.auto_fetch_receiver.is_none());
+
+        app.last_auto_fetch = Some(Instant::now() - StdDuration::from_secs(5 * 60));
+        app.poll_auto_fetch();
+        assert!(app.auto_fetch_receiver.is_some());
+        let deadline = Instant::now() + StdDuration::from_secs(10);
+        while app.auto_fetch_receiver.is_some() || app.refresh_receiver.is_some() {
+            assert!(Instant::now() < deadline, "background fetch timed out");
+            std::thread::sleep(StdDuration::from_millis(20));
+            app.poll_auto_fetch();
+            app.poll_background_refresh();
+        }
+        assert_eq!(behind(&app), 1);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn live_status_updates_the_row_of_a_worktree_edited_on_disk() {
+        let base = temp_dir("live_status");
+        let (bare_path, _main_path) = create_test_project(&base);
+        let project_root = bare_path.parent().unwrap().to_path_buf();
+        let feature_path = project_root.join("feature");
+        git::add_worktree(&bare_path, "feature", &feature_path, Some("main")).unwrap();
+        let mut app = App::new(bare_path.clone(), project_root.clone(), true, None, true).unwrap();
+        app.config.live_status = Some(true);
+        app.poll_fs_watch();
+        assert!(app.fs_watcher.is_some());
+
+        let status = |app: &App| {
+            app.worktrees
+                .iter()
+                .find(|wt| wt.path == feature_path)
+                .unwrap()
+                .status
+                .clone()
+        };
+        assert_eq!(status(&app), WorktreeStatus::Clean);
+        fs::write(feature_path.join("README.md"), "# Edited\n").unwrap();
+        let deadline = Instant::now() + StdDuration::from_secs(5);
+        while status(&app) == WorktreeStatus::Clean {
+            assert!(Instant::now() < deadline, "watcher never reported the edit");
+            std::thread::sleep(StdDuration::from_millis(50));
+            app.poll_fs_watch();
+        }
+        assert_eq!(status(&app), WorktreeStatus::Unstaged);
+
+        app.config.live_status = Some(false);
+        app.poll_fs_watch();
+        assert!(app.fs_watcher.is_none());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn ship_pushes_marks_review_and_jumps_to_main_or_reports_what_was_kept() {
+        let base = temp_dir("ship");
+        let (bare_path, main_path) = create_test_project(&base);
+        let project_root = bare_path.parent().unwrap().to_path_buf();
+        let feature_path = project_root.join("feature");
+        git::add_worktree(&bare_path, "feature", &feature_path, Some("main")).unwrap();
 
         let mut app = App::new(
             bare_path.clone(),
             project_root.clone(),
             true,
-            Some(main_path),
+            Some(feature_path.clone()),
             true,
         )
         .unwrap();
-        app.config.copy_files = vec![".env.missing".to_string()];
-        app.input_buffer = branch.to_string();
 
-        app.add_worktree();
+        // The remote is a local path, so the PR step fails after the push
+        app.handle_list_input(KeyCode::Char('Z'), KeyModifiers::SHIFT);
         wait_for_background_op(&mut app);
-
-        assert!(worktree_path.exists());
-        assert!(!worktree_path.join(".env.missing").exists());
-        let message = app.message.as_ref().unwrap();
-        assert!(!message.is_error);
+        let message = app.message.clone().unwrap();
+        assert!(message.is_error);
+        assert!(
+            message.text.contains("Ship stopped at create PR"),
+            "{}",
+            message.text
+        );
         assert!(message
             .text
-            .contains("Created worktree: feature/copy-files-missing"));
-        assert!(message.text.contains("Copy warnings:"));
-        assert!(message.text.contains(".env.missing"));
-        assert!(message.text.contains("source file missing"));
+            .contains("Already done (kept, not rolled back): push"));
+        assert!(message
+            .text
+            .contains("Not run: mark review-only, jump to main"));
+        assert!(app.metadata.review.is_empty());
+        assert_eq!(app.selected_worktree().unwrap().path, feature_path);
 
-        let _ = fs::remove_dir_all(base);
+        app.config.ship_steps = vec!["push".into(), "review".into(), "main".into()];
+        app.handle_list_input(KeyCode::Char('Z'), KeyModifiers::SHIFT);
+        wait_for_background_op(&mut app);
+        let message = app.message.clone().unwrap();
+        assert!(!message.is_error, "{}", message.text);
+        assert_eq!(message.text, "Shipped: feature");
+        assert!(app.metadata.review.contains("feature"));
+        assert_eq!(app.selected_worktree().unwrap().path, main_path);
+        assert!(Metadata::load(&project_root)
+            .unwrap()
+            .review
+            .contains("feature"));
+
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn enter_is_blocked_while_background_operation_is_running() {
-        let (_tx, rx) = mpsc::channel();
-        let mut app = test_app(
-            vec![Worktree {
-                path: PathBuf::from("/repo/main"),
-                branch: Some("main".to_string()),
-                is_bare: false,
-                status: WorktreeStatus::Clean,
-                last_commit_time: None,
-                ahead_behind: None,
-                github_pr_status: None,
-            }],
-            0,
-            "/repo/.bare",
+    fn clean_key_selects_only_merged_worktrees_for_deletion() {
+        let base = temp_dir("clean");
+        let (bare_path, main_path) = create_test_project(&base);
+        let project_root = bare_path.parent().unwrap().to_path_buf();
+        for name in ["done", "open"] {
+            git::add_worktree(&bare_path, name, &project_root.join(name), Some("main")).unwrap();
+        }
+        for name in ["done", "open"] {
+            let path = project_root.join(name);
+            fs::write(path.join(format!("{}.txt", name)), name).unwrap();
+            assert_git_success(
+                git_cmd()
+                    .current_dir(&path)
+                    .args(["add", "."])
+                    .output()
+                    .unwrap(),
+                "git add failed",
+            );
+            assert_git_success(
+                git_cmd()
+                    .current_dir(&path)
+                    .args(["commit", "-m", name])
+                    .output()
+                    .unwrap(),
+                "git commit failed",
+            );
+        }
+        assert_git_success(
+            git_cmd()
+                .current_dir(&main_path)
+                .args(["merge", "--no-ff", "-m", "merge done", "done"])
+                .output()
+                .unwrap(),
+            "git merge failed",
         );
-        app.active_op = Some((OpKind::Add, rx));
+        // Still at the main tip, so merged by git's definition but kept
+        git::add_worktree(
+            &bare_path,
+            "fresh",
+            &project_root.join("fresh"),
+            Some("main"),
+        )
+        .unwrap();
 
-        app.handle_list_input(KeyCode::Enter, KeyModifiers::empty());
+        let mut app = App::new(
+            bare_path.clone(),
+            project_root.clone(),
+            true,
+            Some(main_path.clone()),
+            true,
+        )
+        .unwrap();
+        app.handle_list_input(KeyCode::Char('C'), KeyModifiers::SHIFT);
 
-        assert!(matches!(app.exit_action, ExitAction::Quit));
-        assert!(!app.should_quit);
+        assert!(matches!(app.state, AppState::ConfirmDelete { .. }));
         assert_eq!(
-            app.message.as_ref().map(|message| message.text.as_str()),
-            Some("Operation still in progress")
+            app.selected_worktree_paths,
+            HashSet::from([project_root.join("done")])
         );
+        assert_eq!(
+            app.message.as_ref().unwrap().text,
+            "1 merged worktree(s) selected"
+        );
+
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn enter_rejects_bare_repository_selection() {
-        let mut app = test_app(
-            vec![Worktree {
-                path: PathBuf::from("/repo/.bare"),
-                branch: None,
-                is_bare: true,
-                status: WorktreeStatus::Clean,
-                last_commit_time: None,
-                ahead_behind: None,
-                github_pr_status: None,
-            }],
-            0,
-            "/repo/.bare",
-        );
+    fn view_state_is_saved_on_change_and_restored_on_launch() {
+        let base = temp_dir("view_state");
+        let (bare_path, _main_path) = create_test_project(&base);
+        let project_root = bare_path.parent().unwrap().to_path_buf();
+        let state_path = Metadata::state_path(&project_root);
 
-        app.enter_worktree();
+        let mut app = App::new(bare_path.clone(), project_root.clone(), true, None, true).unwrap();
+        app.save_view_state();
+        assert!(!state_path.exists(), "defaults should not write state");
 
-        assert!(matches!(app.exit_action, ExitAction::Quit));
-        assert!(!app.should_quit);
-        assert_eq!(
-            app.message.as_ref().map(|message| message.text.as_str()),
-            Some("Cannot enter bare repository")
-        );
+        app.handle_list_input(KeyCode::Char('s'), KeyModifiers::empty());
+        app.handle_list_input(KeyCode::Char('v'), KeyModifiers::empty());
+        app.filter_text = "mai".to_string();
+        app.save_view_state();
+
+        let restored = App::new(bare_path, project_root, true, None, true).unwrap();
+        assert_eq!(restored.sort_mode, SortMode::Recent);
+        assert_eq!(restored.filter_text, "mai");
+        assert!(restored.verbose);
+
+        let _ = fs::remove_dir_all(&base);
     }
 
     #[test]
-    fn delete_confirmation_blocks_dirty_worktree_without_force() {
+    fn status_filter_cycles_and_dims_rows_that_do_not_need_attention() {
+        let mut behind = test_worktree("behind", WorktreeStatus::Clean);
+        behind.ahead_behind = Some(crate::types::AheadBehind {
+            ahead: 0,
+            behind: 2,
+        });
         let mut app = test_app(
-            vec![Worktree {
-                path: PathBuf::from("/repo/dirty"),
-                branch: Some("dirty".to_string()),
-                is_bare: false,
-                status: WorktreeStatus::Unstaged,
-                last_commit_time: None,
-                ahead_behind: None,
-                github_pr_status: None,
-            }],
+            vec![
+                test_worktree("clean", WorktreeStatus::Clean),
+                behind,
+                test_worktree("dirty", WorktreeStatus::Unstaged),
+            ],
             0,
             "/repo/.bare",
         );
-        app.state = AppState::ConfirmDelete {
-            delete_branch: false,
-            force: false,
+        let matching = |app: &App| -> Vec<String> {
+            app.worktrees
+                .iter()
+                .filter(|wt| app.matches_filter(wt))
+                .map(Worktree::display_name)
+                .collect()
         };
 
-        app.handle_confirm_delete_input(KeyCode::Enter, false, false);
-
-        assert!(app.active_op.is_none());
-        assert!(matches!(
-            app.state,
-            AppState::ConfirmDelete {
-                delete_branch: false,
-                force: false
-            }
-        ));
+        app.handle_list_input(KeyCode::Tab, KeyModifiers::empty());
+        assert_eq!(app.status_filter, StatusFilter::Dirty);
+        assert_eq!(matching(&app), ["dirty"]);
+        assert_eq!(app.selected_worktree().unwrap().display_name(), "dirty");
         assert_eq!(
-            app.message.as_ref().map(|message| message.text.as_str()),
-            Some("Worktree has uncommitted changes. Press 'f' to enable force delete.")
+            app.message.as_ref().unwrap().text,
+            "Show: dirty (1 worktrees)"
         );
+
+        app.handle_list_input(KeyCode::Tab, KeyModifiers::empty());
+        assert_eq!(matching(&app), ["clean", "behind"]);
+        app.handle_list_input(KeyCode::Tab, KeyModifiers::empty());
+        assert_eq!(matching(&app), ["behind"]);
+
+        app.filter_text = "cl".to_string();
+        assert!(matching(&app).is_empty());
+        app.handle_list_input(KeyCode::Esc, KeyModifiers::empty());
+        assert_eq!(app.status_filter, StatusFilter::All);
+        assert!(!app.has_filter());
     }
 
     #[test]
-    fn space_toggles_worktree_selection_without_moving_cursor() {
+    fn fuzzy_filter_ranks_the_best_match_first_and_selects_it() {
         let mut app = test_app(
             vec![
+                test_worktree("fix-tooling-gen", WorktreeStatus::Clean),
                 test_worktree("main", WorktreeStatus::Clean),
-                test_worktree("feature", WorktreeStatus::Clean),
+                test_worktree("feature/login", WorktreeStatus::Clean),
             ],
             1,
             "/repo/.bare",
         );
 
-        app.handle_list_input(KeyCode::Char(' '), KeyModifiers::empty());
-
-        assert_eq!(app.selected_index, 1);
-        assert!(app.is_worktree_marked(Path::new("/repo/feature")));
-        assert_eq!(app.selected_worktree_count(), 1);
-
-        app.handle_list_input(KeyCode::Char(' '), KeyModifiers::empty());
-
-        assert_eq!(app.selected_worktree_count(), 0);
-        assert!(!app.is_worktree_marked(Path::new("/repo/feature")));
+        app.handle_list_input(KeyCode::Char('/'), KeyModifiers::empty());
+        for c in "ftlgn".chars() {
+            app.handle_filter_input(KeyCode::Char(c));
+        }
+        let branches: Vec<String> = app.worktrees.iter().map(Worktree::branch_display).collect();
+        assert_eq!(branches, ["feature/login", "fix-tooling-gen", "main"]);
+        assert_eq!(app.selected_index, 0);
+        assert!(!app.matches_filter(&app.worktrees[2]));
+
+        // Clearing the filter goes back to the name sort
+        app.handle_filter_input(KeyCode::Esc);
+        let branches: Vec<String> = app.worktrees.iter().map(Worktree::branch_display).collect();
+        assert_eq!(branches, ["fix-tooling-gen", "feature/login", "main"]);
+        assert!(app.matches_filter(&app.worktrees[2]));
     }
 
     #[test]
-    fn action_worktrees_uses_checked_worktrees_before_cursor() {
+    fn hiding_unmatched_rows_limits_navigation_to_matches() {
         let mut app = test_app(
             vec![
-                test_worktree("main", WorktreeStatus::Clean),
-                test_worktree("feature-a", WorktreeStatus::Clean),
-                test_worktree("feature-b", WorktreeStatus::Clean),
+                test_worktree("api", WorktreeStatus::Unstaged),
+                test_worktree("docs", WorktreeStatus::Clean),
+                test_worktree("web", WorktreeStatus::Unstaged),
             ],
-            0,
+            1,
             "/repo/.bare",
         );
-        app.selected_worktree_paths
-            .insert(PathBuf::from("/repo/feature-a"));
-        app.selected_worktree_paths
-            .insert(PathBuf::from("/repo/feature-b"));
+        assert_eq!(app.visible_indices(), [0, 1, 2]);
+
+        app.handle_list_input(KeyCode::Char('h'), KeyModifiers::empty());
+        app.handle_list_input(KeyCode::Tab, KeyModifiers::empty());
+        assert_eq!(app.visible_indices(), [0, 2]);
+        assert_eq!(app.selected_index, 0);
+
+        app.handle_list_input(KeyCode::Char('j'), KeyModifiers::empty());
+        assert_eq!(app.selected_index, 2);
+        app.handle_list_input(KeyCode::Char('j'), KeyModifiers::empty());
+        assert_eq!(app.selected_index, 2);
+        app.handle_list_input(KeyCode::Char('k'), KeyModifiers::empty());
+        assert_eq!(app.selected_index, 0);
+
+        // Dimming again makes every row reachable
+        app.handle_list_input(KeyCode::Char('h'), KeyModifiers::empty());
+        app.handle_list_input(KeyCode::Char('j'), KeyModifiers::empty());
+        assert_eq!(app.selected_index, 1);
+    }
 
-        let action_paths: Vec<PathBuf> = app
-            .action_worktrees()
-            .into_iter()
-            .map(|wt| wt.path)
-            .collect();
+    #[test]
+    fn number_keys_jump_to_rows_in_view_and_enter_on_second_press() {
+        let mut bare = test_worktree(".bare", WorktreeStatus::Clean);
+        bare.is_bare = true;
+        let mut worktrees = vec![bare];
+        worktrees
+            .extend((0..11).map(|n| test_worktree(&format!("wt{:02}", n), WorktreeStatus::Clean)));
+        let mut app = test_app(worktrees, 1, "/repo/.bare");
+
+        assert_eq!(app.quick_jump_targets(), (1..=9).collect::<Vec<_>>());
+        app.handle_list_input(KeyCode::Char('3'), KeyModifiers::empty());
+        assert_eq!(app.selected_worktree().unwrap().display_name(), "wt02");
+        assert!(!app.should_quit);
 
-        assert_eq!(
-            action_paths,
-            vec![
-                PathBuf::from("/repo/feature-a"),
-                PathBuf::from("/repo/feature-b")
-            ]
-        );
+        // Scrolled down, the numbers follow the rows in view
+        app.table_offset.set(4);
+        assert_eq!(app.quick_jump_targets(), (4..=11).collect::<Vec<_>>());
+        app.handle_list_input(KeyCode::Char('1'), KeyModifiers::empty());
+        assert_eq!(app.selected_worktree().unwrap().display_name(), "wt03");
+        app.handle_list_input(KeyCode::Char('9'), KeyModifiers::empty());
+        assert_eq!(app.selected_worktree().unwrap().display_name(), "wt03");
+
+        app.handle_list_input(KeyCode::Char('1'), KeyModifiers::empty());
+        assert!(app.should_quit);
+        assert!(matches!(
+            app.exit_action,
+            ExitAction::ChangeDirectory(ref path) if path.ends_with("wt03")
+        ));
     }
 
     #[test]
-    fn batch_pull_blocks_when_any_checked_worktree_is_dirty() {
+    fn frecency_sort_orders_worktrees_by_recorded_enters() {
+        let base = temp_dir("frecency_sort");
+        let mut bare = test_worktree(".bare", WorktreeStatus::Clean);
+        bare.is_bare = true;
         let mut app = test_app(
             vec![
-                test_worktree("main", WorktreeStatus::Clean),
-                test_worktree("dirty", WorktreeStatus::Unstaged),
+                bare,
+                test_worktree("alpha", WorktreeStatus::Clean),
+                test_worktree("beta", WorktreeStatus::Clean),
+                test_worktree("gamma", WorktreeStatus::Clean),
             ],
-            0,
+            2,
             "/repo/.bare",
         );
-        app.selected_worktree_paths
-            .insert(PathBuf::from("/repo/main"));
-        app.selected_worktree_paths
-            .insert(PathBuf::from("/repo/dirty"));
+        app.project_root_path = base.clone();
 
-        app.pull_worktree();
+        for index in [2, 3, 2] {
+            app.selected_index = index;
+            app.enter_worktree();
+        }
+        assert_eq!(history::load(&base).unwrap().len(), 3);
 
-        assert!(app.active_op.is_none());
-        assert_eq!(
-            app.message.as_ref().map(|message| message.text.as_str()),
-            Some("Cannot pull: worktree has uncommitted changes: dirty")
-        );
+        app.usage_scores = history::scores(&base);
+        app.handle_list_input(KeyCode::Char('s'), KeyModifiers::NONE);
+        app.handle_list_input(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(app.sort_mode, SortMode::Frecency);
+        let names: Vec<String> = app.worktrees.iter().map(Worktree::display_name).collect();
+        assert!(app.worktrees[0].is_bare);
+        assert_eq!(names[1..], ["beta", "gamma", "alpha"]);
+
+        let _ = fs::remove_dir_all(base);
     }
 
     #[test]
-    fn batch_delete_dirty_guard_checks_checked_worktrees_not_cursor() {
+    fn pinned_worktrees_stay_on_top_in_every_sort_and_persist() {
+        let base = temp_dir("pin_worktree");
+        let mut bare = test_worktree(".bare", WorktreeStatus::Clean);
+        bare.is_bare = true;
         let mut app = test_app(
             vec![
-                test_worktree("dirty-cursor", WorktreeStatus::Unstaged),
-                test_worktree("clean-target", WorktreeStatus::Clean),
+                bare,
+                test_worktree("alpha", WorktreeStatus::Clean),
+                test_worktree("develop", WorktreeStatus::Clean),
+                test_worktree("main", WorktreeStatus::Clean),
+                test_worktree("zeta", WorktreeStatus::Unstaged),
             ],
-            0,
+            3,
             "/repo/.bare",
         );
-        app.selected_worktree_paths
-            .insert(PathBuf::from("/repo/clean-target"));
-        app.state = AppState::ConfirmDelete {
-            delete_branch: false,
-            force: false,
+        app.project_root_path = base.clone();
+        let names = |app: &App| -> Vec<String> {
+            app.worktrees[1..]
+                .iter()
+                .map(Worktree::display_name)
+                .collect()
         };
 
-        app.handle_confirm_delete_input(KeyCode::Enter, false, false);
+        app.handle_list_input(KeyCode::Char('*'), KeyModifiers::NONE);
+        assert_eq!(app.message.as_ref().unwrap().text, "Pinned main");
+        app.selected_index = 3;
+        app.handle_list_input(KeyCode::Char('*'), KeyModifiers::NONE);
+        assert_eq!(names(&app), ["develop", "main", "alpha", "zeta"]);
+        assert!(app.worktrees[0].is_bare);
 
-        assert!(app.active_op.is_some());
-        assert!(matches!(app.state, AppState::List));
-    }
+        app.handle_list_input(KeyCode::Char('S'), KeyModifiers::SHIFT);
+        assert_eq!(names(&app), ["main", "develop", "zeta", "alpha"]);
+        app.sort_mode = SortMode::Status;
+        app.sort_reversed = false;
+        app.apply_sort();
+        assert_eq!(names(&app)[2..], ["zeta", "alpha"]);
 
-    #[test]
-    fn filter_enter_changes_to_first_matching_worktree() {
-        let mut app = test_app(
-            vec![
-                Worktree {
-                    path: PathBuf::from("/repo/main"),
-                    branch: Some("main".to_string()),
-                    is_bare: false,
-                    status: WorktreeStatus::Clean,
-                    last_commit_time: None,
-                    ahead_behind: None,
-                    github_pr_status: None,
-                },
-                Worktree {
-                    path: PathBuf::from("/repo/feature-search"),
-                    branch: Some("feature/search".to_string()),
-                    is_bare: false,
-                    status: WorktreeStatus::Clean,
-                    last_commit_time: None,
-                    ahead_behind: None,
-                    github_pr_status: None,
-                },
-            ],
-            0,
-            "/repo/.bare",
-        );
-        app.is_filtering = true;
+        let saved = Metadata::load(&base).unwrap();
+        assert!(saved.pinned.contains("main") && saved.pinned.contains("develop"));
 
-        app.handle_filter_input(KeyCode::Char('s'));
-        app.handle_filter_input(KeyCode::Enter);
+        app.select_worktree_path(Path::new("/repo/main"));
+        app.handle_list_input(KeyCode::Char('*'), KeyModifiers::NONE);
+        assert_eq!(app.message.as_ref().unwrap().text, "Unpinned main");
+        assert_eq!(names(&app), ["develop", "zeta", "main", "alpha"]);
 
-        match app.exit_action {
-            ExitAction::ChangeDirectory(path) => {
-                assert_eq!(path, PathBuf::from("/repo/feature-search"));
-            }
-            ExitAction::Quit => panic!("filter enter should request directory change"),
-            ExitAction::CreateWorktree(_) => panic!("filter enter should not create a worktree"),
-        }
-        assert!(!app.is_filtering);
-        assert!(app.should_quit);
+        let _ = fs::remove_dir_all(base);
     }
 
     #[test]
-    fn filter_enter_is_blocked_while_background_operation_is_running() {
-        let (_tx, rx) = mpsc::channel();
+    fn note_modal_saves_edits_and_clears_worktree_notes() {
+        let base = temp_dir("worktree_note");
         let mut app = test_app(
-            vec![Worktree {
-                path: PathBuf::from("/repo/main"),
-                branch: Some("main".to_string()),
-                is_bare: false,
-                status: WorktreeStatus::Clean,
-                last_commit_time: None,
-                ahead_behind: None,
-                github_pr_status: None,
-            }],
+            vec![test_worktree("feature", WorktreeStatus::Clean)],
             0,
             "/repo/.bare",
         );
-        app.is_filtering = true;
-        app.active_op = Some((OpKind::Fetch, rx));
+        app.project_root_path = base.clone();
 
-        app.handle_filter_input(KeyCode::Enter);
-
-        assert!(matches!(app.exit_action, ExitAction::Quit));
-        assert!(!app.should_quit);
-        assert!(app.is_filtering);
+        app.handle_list_input(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert!(matches!(&app.state, AppState::NoteModal { name } if name == "feature"));
+        for c in "waiting on API review ".chars() {
+            app.handle_note_modal_input(KeyCode::Char(c), "feature".to_string());
+        }
+        app.handle_note_modal_input(KeyCode::Enter, "feature".to_string());
+        assert!(matches!(app.state, AppState::List));
+        assert_eq!(app.message.as_ref().unwrap().text, "Note saved for feature");
         assert_eq!(
-            app.message.as_ref().map(|message| message.text.as_str()),
-            Some("Operation still in progress")
+            Metadata::load(&base).unwrap().notes["feature"],
+            "waiting on API review"
         );
+
+        app.handle_list_input(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(app.input_buffer, "waiting on API review");
+        app.input_buffer.clear();
+        app.handle_note_modal_input(KeyCode::Enter, "feature".to_string());
+        assert!(app.metadata.notes.is_empty());
+        assert!(Metadata::load(&base).unwrap().notes.is_empty());
+
+        let _ = fs::remove_dir_all(base);
     }
 
     #[test]
-    fn poll_pr_status_updates_matching_worktree_without_active_op() {
-        let (tx, rx) = mpsc::channel();
-        let mut app = test_app(
-            vec![Worktree {
-                path: PathBuf::from("/repo/feature"),
-                branch: Some("feature".to_string()),
-                is_bare: false,
-                status: WorktreeStatus::Clean,
-                last_commit_time: None,
-                ahead_behind: None,
-                github_pr_status: None,
-            }],
-            0,
-            "/repo/.bare",
-        );
-        let (_op_tx, op_rx) = mpsc::channel();
-        app.active_op = Some((OpKind::Fetch, op_rx));
-        app.pr_status_receiver = Some(rx);
+    fn repo_switcher_opens_another_configured_project_in_place() {
+        let base = temp_dir("repo_switcher");
+        let (first_bare, first_main) = create_test_project(&base.join("first"));
+        let (second_bare, second_main) = create_test_project(&base.join("second"));
+        let first_root = first_bare.parent().unwrap().to_path_buf();
+        let second_root = second_bare.parent().unwrap().to_path_buf();
 
-        tx.send(vec![(
-            PathBuf::from("/repo/feature"),
-            Some(GithubPrStatus::Open),
-        )])
+        let mut app = App::new(
+            first_bare,
+            first_root.clone(),
+            true,
+            Some(first_main),
+            false,
+        )
         .unwrap();
-        app.poll_pr_status();
+        app.config.repositories = Vec::new();
+        app.handle_list_input(KeyCode::Char('w'), KeyModifiers::NONE);
+        assert!(matches!(app.state, AppState::List));
 
-        assert_eq!(
-            app.worktrees[0].github_pr_status,
-            Some(GithubPrStatus::Open)
-        );
-        assert!(app.pr_status_receiver.is_none());
-        assert!(app.active_op.is_some());
+        app.config.repositories = vec![
+            first_root.display().to_string(),
+            second_main.display().to_string(),
+        ];
+        app.handle_list_input(KeyCode::Char('w'), KeyModifiers::NONE);
+        assert_eq!(app.state, AppState::RepoSwitcher { selected: 0 });
+        assert!(app.is_current_repository(&app.switcher_repositories[0]));
+        assert_eq!(app.switcher_repositories[1], second_main);
+
+        app.handle_repo_switcher_input(KeyCode::Char('j'), 0);
+        app.handle_repo_switcher_input(KeyCode::Enter, 1);
+        assert!(paths_refer_to_same_location(
+            &app.project_root_path,
+            &second_root
+        ));
+        assert!(paths_refer_to_same_location(
+            &app.bare_repo_path,
+            &second_bare
+        ));
+        assert!(app
+            .message
+            .as_ref()
+            .unwrap()
+            .text
+            .starts_with("Switched to "));
+
+        app.config.repositories = vec![base.join("missing").display().to_string()];
+        app.switch_repository(&base.join("missing"));
+        assert!(app
+            .message
+            .as_ref()
+            .unwrap()
+            .text
+            .starts_with("Cannot open "));
+        assert!(paths_refer_to_same_location(
+            &app.project_root_path,
+            &second_root
+        ));
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn terminal_handoff_goes_to_the_tty_not_stdout() {
+        let base = temp_dir("terminal_handoff");
+        fs::create_dir_all(&base).unwrap();
+        let tty_path = base.join("tty");
+        let tty = fs::File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tty_path)
+            .unwrap();
+
+        let mut app = test_app(Vec::new(), 0, "/tmp/bare");
+        let mut result = OpResult {
+            kind: OpKind::Fetch,
+            success: false,
+            message: "Fetch failed: terminal prompts disabled".to_string(),
+            cmd_detail: String::new(),
+            worktree_path: base.clone(),
+            affected_paths: Vec::new(),
+            display_name: "origin".to_string(),
+        };
+        let command = git::PromptedCommand {
+            args: vec!["--version".into()],
+            dir: None,
+        };
+        app.rerun_on_tty(&mut result, &command, tty);
+        // Hand the developer's terminal back if the test run has one
+        let _ = crossterm::terminal::disable_raw_mode();
+
+        // The notice, the screen switches, and git's own output all land on
+        // the terminal, so stdout stays free for the cd handoff
+        let written = fs::read_to_string(&tty_path).unwrap();
+        assert!(written.contains("owt: origin needs your input\n$ git --version"));
+        assert!(written.contains("git version"));
+        assert!(written.contains("\x1b[?1049l"));
+        assert!(written.contains("\x1b[?1049h"));
+        assert!(result.success);
+        assert!(app.needs_clear);
+
+        let source = include_str!("app.rs");
+        let start = source.find("fn rerun_in_terminal(").unwrap();
+        let end = start + source[start..].find("fn handle_op_result(").unwrap();
+        assert!(!source[start..end].contains("stdout()"));
+        assert!(!source[start..end].contains("println!"));
+
+        let _ = fs::remove_dir_all(base);
     }
 }
@@ -0,0 +1,178 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::git;
+use crate::journal::{self, JournalEntry, JournalOp};
+use crate::metadata::{self, Metadata};
+use crate::state_file::{write_atomic, StateLock};
+use crate::types::{Worktree, WorktreeStatus};
+
+/// A worktree removed with "archive" instead of delete: the branch stays, and
+/// this records where it was checked out so it can be added back.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct ArchiveEntry {
+    pub branch: String,
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Branch tip when archived, to recreate the branch if it was deleted since
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub archived_at: u64, // Unix seconds
+}
+
+impl ArchiveEntry {
+    /// Worktree name the note was stored under
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.branch.clone())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct ArchiveFile {
+    #[serde(default)]
+    entries: Vec<ArchiveEntry>,
+}
+
+/// Archived worktrees: .owt/archive.toml under the project root
+pub(crate) fn archive_path(project_root_path: &Path) -> PathBuf {
+    Config::owt_dir(project_root_path).join("archive.toml")
+}
+
+/// Every archived worktree, oldest first. A missing archive is empty.
+pub(crate) fn load(project_root_path: &Path) -> Result<Vec<ArchiveEntry>> {
+    let path = archive_path(project_root_path);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(error).with_context(|| format!("Failed to read {}", path.display()))
+        }
+    };
+    let file: ArchiveFile =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(file.entries)
+}
+
+/// Apply `change` to the on-disk archive while holding the state lock
+fn update(project_root_path: &Path, change: impl FnOnce(&mut Vec<ArchiveEntry>)) -> Result<()> {
+    let _lock = StateLock::acquire(project_root_path)?;
+    let mut entries = load(project_root_path)?;
+    change(&mut entries);
+    write_atomic(
+        &archive_path(project_root_path),
+        &toml::to_string(&ArchiveFile { entries })?,
+    )
+}
+
+/// Remove a clean worktree but keep its branch, recording the branch, path,
+/// and note in the archive. Dirty or detached worktrees are refused, since
+/// archiving them would lose work that restoring cannot bring back.
+pub(crate) fn archive(
+    repo_path: &Path,
+    project_root_path: &Path,
+    worktree: &Worktree,
+) -> Result<ArchiveEntry> {
+    if worktree.is_bare {
+        anyhow::bail!("Cannot archive the bare repository");
+    }
+    let Some(branch) = worktree.branch.clone() else {
+        anyhow::bail!(
+            "{} is detached; check out a branch first",
+            worktree.display_name()
+        );
+    };
+    if worktree.status != WorktreeStatus::Clean {
+        anyhow::bail!(
+            "{} has uncommitted changes; commit or stash them first",
+            worktree.display_name()
+        );
+    }
+
+    let name = worktree.display_name();
+    let entry = ArchiveEntry {
+        branch: branch.clone(),
+        path: worktree.path.clone(),
+        note: Metadata::load(project_root_path)
+            .ok()
+            .and_then(|metadata| metadata.notes.get(&name).cloned()),
+        commit: git::resolve_commit(repo_path, &format!("refs/heads/{}", branch)),
+        archived_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0),
+    };
+
+    let journal_id = journal::begin(
+        project_root_path,
+        JournalEntry::new(JournalOp::Archive, &worktree.path)
+            .branch(Some(&branch))
+            .previous_commit(entry.commit.clone())
+            .step(format!(
+                "git -C {} worktree remove {}",
+                repo_path.display(),
+                worktree.path.display()
+            )),
+    )?;
+    let result = update(project_root_path, |entries| entries.push(entry.clone())).and_then(|()| {
+        match git::remove_worktree(repo_path, &worktree.path, false) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let _ = update(project_root_path, |entries| {
+                    entries.retain(|archived| *archived != entry)
+                });
+                Err(e)
+            }
+        }
+    });
+    let _ = journal::finish(project_root_path, &journal_id);
+    result?;
+
+    let _ = metadata::cleanup_location(project_root_path, &worktree.path);
+    if entry.note.is_some() {
+        Metadata::update(project_root_path, |metadata| {
+            metadata.notes.remove(&name);
+        })?;
+    }
+    Ok(entry)
+}
+
+/// Add the archived worktree back at its old path, recreating the branch at
+/// the archived commit if it was deleted since, restore its note, and drop
+/// the entry from the archive.
+pub(crate) fn restore(
+    repo_path: &Path,
+    project_root_path: &Path,
+    entry: &ArchiveEntry,
+) -> Result<()> {
+    if entry.path.exists() {
+        anyhow::bail!("{} already exists", entry.path.display());
+    }
+    let branch_exists =
+        git::resolve_commit(repo_path, &format!("refs/heads/{}", entry.branch)).is_some();
+    let base = (!branch_exists)
+        .then_some(entry.commit.as_deref())
+        .flatten();
+    git::add_worktree(repo_path, &entry.branch, &entry.path, base)?;
+
+    if let Some(note) = &entry.note {
+        Metadata::update(project_root_path, |metadata| {
+            metadata.notes.insert(entry.name(), note.clone());
+        })?;
+    }
+    forget(project_root_path, entry)
+}
+
+/// Drop an entry from the archive. The branch is left alone.
+pub(crate) fn forget(project_root_path: &Path, entry: &ArchiveEntry) -> Result<()> {
+    update(project_root_path, |entries| {
+        entries.retain(|archived| archived != entry)
+    })
+}
@@ -0,0 +1,248 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::command_log;
+
+/// Longest issue-title slug kept in a generated branch name, in characters
+const MAX_TITLE_SLUG: usize = 40;
+
+/// Completion candidates learned from recent branch names, best first:
+/// `<prefix><ticket>-` for tickets still in progress, then bare prefixes
+/// (`feature/`, `alice/`) ordered by how often and how recently they were used.
+pub fn candidates(recent_branches: &[String]) -> Vec<String> {
+    // Score prefixes by use, weighting recent branches higher
+    let mut prefix_scores: HashMap<&str, usize> = HashMap::new();
+    let total = recent_branches.len();
+    for (rank, branch) in recent_branches.iter().enumerate() {
+        if let Some(prefix) = branch_prefix(branch) {
+            *prefix_scores.entry(prefix).or_default() += total - rank;
+        }
+    }
+    let mut prefixes: Vec<(&str, usize)> = prefix_scores.into_iter().collect();
+    prefixes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut candidates: Vec<String> = Vec::new();
+    for branch in recent_branches {
+        let Some(ticket) = ticket_id(branch) else {
+            continue;
+        };
+        let prefix = branch_prefix(branch).unwrap_or("");
+        let candidate = format!("{}{}-", prefix, ticket);
+        if !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+    candidates.extend(prefixes.into_iter().map(|(prefix, _)| prefix.to_string()));
+    candidates
+}
+
+/// The text to append to `input` for the best matching candidate.
+pub fn completion<'a>(candidates: &'a [String], input: &str) -> Option<&'a str> {
+    if input.is_empty() {
+        return None;
+    }
+    candidates
+        .iter()
+        .find(|candidate| candidate.len() > input.len() && candidate.starts_with(input))
+        .map(|candidate| &candidate[input.len()..])
+}
+
+/// The typed prefix and ticket of an add-modal input that is only a ticket:
+/// `feature/ABC-123` gives `("feature/", "ABC-123")`, and GitHub-style
+/// `#42` or `42` gives `("", "42")`.
+pub fn issue_ticket(input: &str) -> Option<(&str, &str)> {
+    let input = input.trim();
+    let prefix = branch_prefix(input).unwrap_or("");
+    let rest = &input[prefix.len()..];
+    if ticket_id(rest) == Some(rest) {
+        return Some((prefix, rest));
+    }
+    let number = rest.strip_prefix('#').unwrap_or(rest);
+    (!number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit()))
+        .then_some((prefix, number))
+}
+
+/// `<prefix><ticket>-<title slug>`, e.g. `feature/ABC-123-fix-login`
+pub fn issue_branch_name(prefix: &str, ticket: &str, title: &str) -> String {
+    let mut slug = String::new();
+    for word in title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let word = word.to_lowercase();
+        if !slug.is_empty() && slug.chars().count() + 1 + word.chars().count() > MAX_TITLE_SLUG {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word);
+    }
+    if slug.is_empty() {
+        format!("{}{}", prefix, ticket)
+    } else {
+        format!("{}{}-{}", prefix, ticket, slug)
+    }
+}
+
+/// Run the `issue_title_command` for `ticket` in `dir` and return the first
+/// line it prints. `{id}` in the command is replaced by the ticket, which is
+/// also passed as `OWT_ISSUE_ID`.
+pub fn fetch_issue_title(command_template: &str, ticket: &str, dir: &Path) -> Result<String> {
+    let mut command = Command::new("sh");
+    command
+        .args(["-c", &command_template.replace("{id}", ticket)])
+        .env("OWT_ISSUE_ID", ticket)
+        .current_dir(dir);
+    let result = command.output();
+    command_log::record(&command, &result);
+    let output = result?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        anyhow::bail!(if stderr.is_empty() {
+            format!("issue_title_command exited with {}", output.status)
+        } else {
+            stderr
+        });
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("issue_title_command printed no title for {}", ticket))
+}
+
+/// Everything up to and including the last `/`, e.g. `feature/` or `alice/fix/`.
+fn branch_prefix(branch: &str) -> Option<&str> {
+    branch.rfind('/').map(|index| &branch[..=index])
+}
+
+/// First Jira-style ticket ID (`ABC-123`) in a branch name.
+fn ticket_id(branch: &str) -> Option<&str> {
+    let bytes = branch.as_bytes();
+    let mut start = 0;
+    while start < bytes.len() {
+        let at_word_start = start == 0 || !bytes[start - 1].is_ascii_alphanumeric();
+        if at_word_start && bytes[start].is_ascii_uppercase() {
+            let mut end = start + 1;
+            while end < bytes.len()
+                && (bytes[end].is_ascii_uppercase() || bytes[end].is_ascii_digit())
+            {
+                end += 1;
+            }
+            if end < bytes.len() && bytes[end] == b'-' {
+                let digits_start = end + 1;
+                let mut digits_end = digits_start;
+                while digits_end < bytes.len() && bytes[digits_end].is_ascii_digit() {
+                    digits_end += 1;
+                }
+                let boundary =
+                    digits_end == bytes.len() || !bytes[digits_end].is_ascii_alphanumeric();
+                if digits_end > digits_start && end - start >= 2 && boundary {
+                    return Some(&branch[start..digits_end]);
+                }
+            }
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branches(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn ticket_ids_need_an_uppercase_key_and_a_number() {
+        assert_eq!(ticket_id("feature/PAY-142-refund-flow"), Some("PAY-142"));
+        assert_eq!(ticket_id("fix/OWT2-7"), Some("OWT2-7"));
+        assert_eq!(ticket_id("feature/login-2"), None);
+        assert_eq!(ticket_id("feature/A-1"), None);
+        assert_eq!(ticket_id("feature/PAY-14x"), None);
+    }
+
+    #[test]
+    fn candidates_put_tickets_before_prefixes_ranked_by_recent_use() {
+        let recent = branches(&[
+            "fix/PAY-150-rounding",
+            "feature/PAY-142-refund-flow",
+            "feature/search",
+            "main",
+            "chore/deps",
+        ]);
+
+        assert_eq!(
+            candidates(&recent),
+            vec![
+                "fix/PAY-150-",
+                "feature/PAY-142-",
+                "feature/",
+                "fix/",
+                "chore/"
+            ]
+        );
+    }
+
+    #[test]
+    fn issue_tickets_become_branch_names_from_their_titles() {
+        assert_eq!(
+            issue_ticket("feature/ABC-123"),
+            Some(("feature/", "ABC-123"))
+        );
+        assert_eq!(issue_ticket("#42"), Some(("", "42")));
+        assert_eq!(issue_ticket("fix/42"), Some(("fix/", "42")));
+        assert_eq!(issue_ticket("feature/ABC-123-login"), None);
+        assert_eq!(issue_ticket("feature/"), None);
+
+        assert_eq!(
+            issue_branch_name("feature/", "ABC-123", "Fix login: can't sign in!"),
+            "feature/ABC-123-fix-login-can-t-sign-in"
+        );
+        assert_eq!(
+            issue_branch_name(
+                "",
+                "42",
+                "A very long issue title that keeps going and going"
+            ),
+            "42-a-very-long-issue-title-that-keeps-going"
+        );
+        assert_eq!(issue_branch_name("fix/", "7", "!!!"), "fix/7");
+        assert_eq!(
+            issue_branch_name(
+                "",
+                "42",
+                "로그인 화면에서 비밀번호 재설정 메일이 오지 않는 문제 수정 필요 합니다 빨리"
+            ),
+            "42-로그인-화면에서-비밀번호-재설정-메일이-오지-않는-문제-수정-필요-합니다"
+        );
+
+        let dir = std::env::temp_dir();
+        assert_eq!(
+            fetch_issue_title("printf '\\nTitle of {id}\\n'", "ABC-1", &dir).unwrap(),
+            "Title of ABC-1"
+        );
+        assert!(fetch_issue_title("echo nope >&2; exit 1", "ABC-1", &dir)
+            .unwrap_err()
+            .to_string()
+            .contains("nope"));
+    }
+
+    #[test]
+    fn completion_returns_the_missing_suffix() {
+        let candidates = branches(&["feature/PAY-142-", "feature/", "fix/"]);
+
+        assert_eq!(completion(&candidates, "fe"), Some("ature/PAY-142-"));
+        assert_eq!(completion(&candidates, "fi"), Some("x/"));
+        assert_eq!(completion(&candidates, "fix/"), None);
+        assert_eq!(completion(&candidates, ""), None);
+    }
+}
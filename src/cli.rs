@@ -0,0 +1,774 @@
+use clap::{ArgAction, Args, Parser, Subcommand};
+use std::path::{Path, PathBuf};
+
+use crate::worktree_exec::ExecFilter;
+use crate::{Command, CommitCommand, ConfigCommand, PrCommand, SearchCommand, WorktreeCommand};
+
+const ROOT_AFTER_HELP: &str = r#"KEYBINDINGS (TUI):
+    Enter       Enter worktree (cd to directory)
+    j/k, ↑/↓    Navigate worktrees
+    a           Add new worktree
+    d           Delete selected worktree
+    o           Open in editor ($EDITOR)
+    t           Open in terminal ($TERMINAL)
+    f           Fetch all remotes
+    r           Refresh worktree list
+    c           View config settings
+    q           Quit
+
+ENVIRONMENT:
+    EDITOR      Editor to use (default: vim)
+    TERMINAL    Terminal app to use (default: Terminal.app on macOS)
+
+SHELL INTEGRATION:
+    Run `owt setup` to install the secure OWT_OUTPUT_FILE shell integration.
+    The TUI draws through /dev/tty so stdout can remain reserved for cd handoff.
+
+EXAMPLES:
+    owt clone https://github.com/user/repo.git
+    owt clone git@github.com:user/repo.git ~/projects
+    owt init
+    owt --path ~/repos/myproject.git
+    owt list
+    cd "$(owt pick)"
+    owt add feature/login --base main
+    owt remove feature/login --branch
+    owt clean --branch
+    owt pr status --branch feature/login
+    owt commit tree -n 12
+    owt search login
+    cd "$(owt path feature/login)"
+    owt config check
+    owt exec --dirty-only -- git status --short
+    owt doctor"#;
+
+const WORKTREE_RECORD_OUTPUT: &str = "OUTPUT:
+    kind<TAB>path<TAB>branch<TAB>status<TAB>last_commit<TAB>ahead<TAB>behind<TAB>pr<TAB>description
+    --json: an array of worktree objects with description
+    --porcelain=v1: `# owt porcelain v1`, then per worktree
+        kind status ahead behind locked pr branch path";
+
+/// owt - Git Worktree Manager
+///
+/// Starts the TUI when no subcommand is given.
+#[derive(Debug, Parser)]
+#[command(
+    name = "owt",
+    version = concat!("v", env!("CARGO_PKG_VERSION")),
+    disable_version_flag = true,
+    args_conflicts_with_subcommands = true,
+    after_help = ROOT_AFTER_HELP
+)]
+pub(crate) struct Cli {
+    #[command(flatten)]
+    tui: TuiArgs,
+
+    /// Print version information
+    #[arg(short = 'v', long, action = ArgAction::Version)]
+    version: Option<bool>,
+
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+#[derive(Debug, Args)]
+struct TuiArgs {
+    /// Path to a Git repository or worktree, or the name of a project listed
+    /// by `owt repos` (default: current directory)
+    #[arg(value_name = "PATH|REPO", value_parser = directory_or_registered_repo)]
+    target: Option<PathBuf>,
+
+    /// Path to a Git repository or worktree
+    #[arg(
+        short = 'p',
+        long = "path",
+        value_name = "PATH",
+        conflicts_with = "target"
+    )]
+    path: Option<PathBuf>,
+
+    /// Preselect a worktree by branch, name, or path; falls back to filtering
+    /// the list by NAME
+    #[arg(short, long, value_name = "NAME")]
+    select: Option<String>,
+
+    /// Write newline-delimited JSON state events (mode, selection,
+    /// op_started, op_finished) to an inherited file descriptor or a file/FIFO
+    #[arg(long, value_name = "FD|PATH")]
+    events: Option<String>,
+
+    /// Serve JSON requests on stdin instead of the TUI (methods: list, add,
+    /// remove, switch)
+    #[arg(long, conflicts_with_all = ["select", "events"])]
+    rpc: bool,
+}
+
+#[derive(Debug, Args)]
+struct PickArgs {
+    /// Path to a Git repository or worktree, or the name of a project listed
+    /// by `owt repos` (default: current directory)
+    #[arg(value_name = "PATH|REPO", value_parser = directory_or_registered_repo)]
+    target: Option<PathBuf>,
+
+    /// Path to a Git repository or worktree
+    #[arg(
+        short = 'p',
+        long = "path",
+        value_name = "PATH",
+        conflicts_with = "target"
+    )]
+    path: Option<PathBuf>,
+
+    /// Preselect a worktree by branch, name, or path; falls back to filtering
+    /// the list by NAME
+    #[arg(short, long, value_name = "NAME")]
+    select: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct RepoPathArgs {
+    /// Repository or worktree path (default: current directory)
+    #[arg(short = 'p', long = "path", value_name = "PATH")]
+    path: Option<PathBuf>,
+}
+
+/// `--porcelain` formats; a new version is added instead of changing one
+const PORCELAIN_VERSIONS: [&str; 1] = ["v1"];
+
+#[derive(Debug, Args)]
+struct JsonArgs {
+    /// Print one JSON document instead of tab-separated records
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum CliCommand {
+    /// Start the TUI (the default when no subcommand is given)
+    Tui(TuiArgs),
+    /// Pick a worktree in the TUI and print only its path
+    ///
+    /// Like fzf: the TUI draws on /dev/tty, and stdout gets nothing but the
+    /// path of the worktree chosen with Enter, or of one added from the
+    /// picker. Ignores OWT_OUTPUT_FILE and skips the shell integration tip.
+    /// Exits 1 with empty stdout when quit without picking.
+    #[command(after_help = "OUTPUT:
+    path
+
+EXAMPLES:
+    cd \"$(owt pick)\"
+    owt pick --select feature/ | xargs code")]
+    Pick(PickArgs),
+    /// Clone repository as bare and create first worktree
+    Clone {
+        /// Repository URL, or `user/repo` on the `clone_host` config (default: github.com)
+        url: String,
+        /// Parent directory for the clone (default: current directory)
+        path: Option<PathBuf>,
+        /// Check out this branch in the first worktree instead of the remote default
+        #[arg(short, long, value_name = "BRANCH")]
+        branch: Option<String>,
+        /// Shallow clone with this many commits of history
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        depth: Option<u32>,
+        /// Only create the .bare repository
+        #[arg(long)]
+        no_worktree: bool,
+    },
+    /// Show conversion guide for regular repositories
+    Init,
+    /// Convert a regular repository into the .bare layout
+    ///
+    /// Moves .git to .bare, sets core.bare, moves the checked-out files into
+    /// a worktree named after the current branch, and repairs linked
+    /// worktrees. Refuses to run with uncommitted or untracked changes, a
+    /// detached HEAD, or submodules.
+    #[command(after_help = "OUTPUT:
+    step<TAB>description        (--dry-run)
+    done<TAB>description
+    converted<TAB>worktree_path")]
+    Convert(ConvertArgs),
+    /// Install shell integration (adds function to .zshrc/.bashrc)
+    Setup,
+    /// Create a worktree for a branch (same as `worktree create`)
+    Add(CreateArgs),
+    /// Delete a worktree by branch, name, or path (same as `worktree delete`)
+    #[command(visible_alias = "rm")]
+    Remove(DeleteArgs),
+    /// List worktrees as tab-separated records (same as `worktree list`)
+    #[command(visible_alias = "ls", after_help = WORKTREE_RECORD_OUTPUT)]
+    List(ListArgs),
+    /// Remove worktrees whose branch is merged into the default branch
+    ///
+    /// Finds branches `git branch --merged` reports as merged into the
+    /// default branch, lists their worktrees, asks once, and removes them.
+    /// Keeps the default branch, the current worktree, branches still at the
+    /// default branch tip, and locked or dirty worktrees.
+    #[command(after_help = "OUTPUT:
+    clean<TAB>0
+    clean<TAB>kept<TAB>branch<TAB>path<TAB>reason
+    clean<TAB>would-remove<TAB>branch<TAB>path     (--dry-run)
+    clean<TAB>removed<TAB>branch<TAB>path
+    clean<TAB>failed<TAB>branch<TAB>path<TAB>error
+    --json: [{action, branch, path, detail}], action as above")]
+    Clean(CleanArgs),
+    /// Print the absolute path of a worktree by branch, name, or path
+    ///
+    /// For shell use without the TUI, as in `cd "$(owt path feature/login)"`.
+    /// Exits 1 with nothing on stdout when no worktree matches.
+    #[command(after_help = "OUTPUT:
+    path")]
+    Path(PathArgs),
+    /// Manage worktrees with plain CLI output
+    #[command(subcommand)]
+    Worktree(WorktreeCli),
+    /// Inspect GitHub PR status through gh
+    #[command(subcommand)]
+    Pr(PrCli),
+    /// Inspect commit history
+    #[command(subcommand)]
+    Commit(CommitCli),
+    /// Search worktrees by path, name, branch, status, or PR status
+    #[command(after_help = WORKTREE_RECORD_OUTPUT)]
+    Search(SearchArgs),
+    /// Inspect owt configuration
+    #[command(subcommand)]
+    Config(ConfigCli),
+    /// List projects registered by `owt clone` and the TUI
+    ///
+    /// owt records a project in ~/.config/owt/repos.toml when it clones it or
+    /// opens it in the TUI, named after the project directory. Open one from
+    /// anywhere with `owt <NAME>`. `missing` marks projects whose directory
+    /// no longer exists.
+    #[command(after_help = "OUTPUT:
+    name<TAB>path<TAB>ok|missing
+    --json: [{name, path, state}]")]
+    Repos(JsonArgs),
+    /// Diagnose git, shell integration, and repository problems
+    ///
+    /// Checks the git version, bare repository layout (remote fetch refspecs),
+    /// shell integration, /dev/tty access, clipboard support, stale worktree
+    /// administrative files, broken links between worktrees and the
+    /// repository, and operations left unfinished by an interrupted run. Each
+    /// problem is followed by a fix line. Exits non-zero when any error is
+    /// found. With --repair, runs `git worktree repair` first.
+    #[command(after_help = "OUTPUT:
+    repaired<TAB>git report line (--repair only)
+    ok<TAB>check<TAB>detail
+    warning<TAB>check<TAB>detail
+    error<TAB>check<TAB>detail
+    fix<TAB>check<TAB>command or hint")]
+    Doctor(DoctorArgs),
+    /// Run a command in every worktree
+    ///
+    /// Runs the command after `--` directly (no shell) in each worktree with
+    /// OWT_BRANCH and OWT_WORKTREE_PATH set, and reports each exit code. Runs
+    /// one worktree per CPU at a time; each worktree's output is printed in
+    /// one piece when it finishes, under a `==>` header on stderr. With
+    /// --jobs 1 the worktrees run in order and output streams live. Exits
+    /// non-zero, naming the failed worktrees, when the command failed in any
+    /// of them. Wrap it in `sh -c '...'` for pipes or shell syntax.
+    #[command(after_help = "OUTPUT:
+    exec<TAB>0                                   (no matching worktrees)
+    exec<TAB>exit_code<TAB>branch<TAB>path
+    exec<TAB>error<TAB>branch<TAB>path<TAB>message")]
+    Exec(ExecArgs),
+    /// Print the shell handoff path for debugging cd integration
+    #[command(hide = true)]
+    TestCd,
+}
+
+#[derive(Debug, Subcommand)]
+enum WorktreeCli {
+    /// List worktrees as tab-separated records
+    #[command(visible_alias = "ls", after_help = WORKTREE_RECORD_OUTPUT)]
+    List(ListArgs),
+    /// Create a worktree for a branch
+    Create(CreateArgs),
+    /// Delete a worktree by branch, name, or path
+    #[command(visible_aliases = ["remove", "rm"])]
+    Delete(DeleteArgs),
+    /// Prune missing metadata and completed PR worktrees
+    ///
+    /// Normal mode logs every worktree and removes non-current clean worktrees
+    /// whose GitHub PR status is merged or closed. Removal runs in parallel and
+    /// never deletes branches or the HEAD branch worktree. --dry-run does not
+    /// delete worktrees; it reviews removable candidates serially and logs
+    /// selected candidates as would-remove.
+    #[command(after_help = "OUTPUT:
+    pruned<TAB>0
+    pruned<TAB>worktree<TAB>branch<TAB>path
+    pruned<TAB>log<TAB>action<TAB>branch<TAB>path<TAB>reason
+    pruned<TAB>metadata<TAB>result")]
+    Prune(PruneArgs),
+}
+
+#[derive(Debug, Args)]
+struct ListArgs {
+    #[command(flatten)]
+    repo: RepoPathArgs,
+
+    #[command(flatten)]
+    output: JsonArgs,
+
+    /// Print the stable space-separated format, which never changes within
+    /// a version
+    #[arg(
+        long,
+        value_name = "VERSION",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "v1",
+        value_parser = PORCELAIN_VERSIONS,
+        conflicts_with = "json"
+    )]
+    porcelain: Option<String>,
+
+    /// Include GitHub PR status from gh
+    #[arg(long)]
+    pr: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "OUTPUT:
+    created<TAB>branch<TAB>path
+    --json: {branch, path, warnings}")]
+struct CreateArgs {
+    /// Branch to check out or create
+    branch: String,
+
+    #[command(flatten)]
+    repo: RepoPathArgs,
+
+    #[command(flatten)]
+    output: JsonArgs,
+
+    /// Base branch for a new branch
+    #[arg(short, long, value_name = "BRANCH")]
+    base: Option<String>,
+
+    /// Explicit destination path
+    #[arg(long, value_name = "PATH")]
+    worktree_path: Option<PathBuf>,
+
+    /// Override tmux worktree pane mode for this create
+    #[arg(
+        long,
+        value_name = "on|off",
+        num_args = 0..=1,
+        default_missing_value = "on",
+        value_parser = on_off
+    )]
+    tmux: Option<bool>,
+
+    /// Check out only DIR (repeatable); overrides sparse_paths
+    #[arg(long, value_name = "DIR")]
+    sparse: Vec<String>,
+
+    /// Full checkout even when sparse_paths is configured
+    #[arg(long, conflicts_with = "sparse")]
+    full: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(after_help = "OUTPUT:
+    kept<TAB>path                (one per nested repository moved out, with --keep-nested)
+    deleted<TAB>branch<TAB>path
+    --json: the deleted worktree object with kept")]
+struct DeleteArgs {
+    /// Branch, worktree name, or path
+    target: String,
+
+    #[command(flatten)]
+    repo: RepoPathArgs,
+
+    #[command(flatten)]
+    output: JsonArgs,
+
+    /// Delete even with uncommitted changes
+    #[arg(short, long)]
+    force: bool,
+
+    /// Delete the local branch after removing the worktree
+    #[arg(long = "branch")]
+    delete_branch: bool,
+
+    /// Move nested git repositories to .owt/kept/ instead of deleting them
+    #[arg(long)]
+    keep_nested: bool,
+}
+
+#[derive(Debug, Args)]
+struct PathArgs {
+    /// Branch, worktree name, or path
+    target: String,
+
+    #[command(flatten)]
+    repo: RepoPathArgs,
+}
+
+#[derive(Debug, Args)]
+struct CleanArgs {
+    #[command(flatten)]
+    repo: RepoPathArgs,
+
+    #[command(flatten)]
+    output: JsonArgs,
+
+    /// Also delete the merged local branches
+    #[arg(long = "branch")]
+    delete_branch: bool,
+
+    /// Remove without asking for confirmation
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Only list the worktrees that would be removed
+    #[arg(long, conflicts_with = "yes")]
+    dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+struct ExecArgs {
+    #[command(flatten)]
+    repo: RepoPathArgs,
+
+    /// Only worktrees with uncommitted or untracked changes
+    #[arg(long)]
+    dirty_only: bool,
+
+    /// Only worktrees whose branch matches PATTERN (`*` and `?` wildcards)
+    #[arg(long, value_name = "PATTERN")]
+    branch_glob: Option<String>,
+
+    /// Worktrees to run at once (default: number of CPUs)
+    #[arg(short, long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    jobs: Option<u32>,
+
+    /// Command and arguments to run
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    command: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct PruneArgs {
+    #[command(flatten)]
+    repo: RepoPathArgs,
+
+    /// Preview metadata pruning and review removable worktrees one at a time
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum PrCli {
+    /// Show PR status for a branch or worktree
+    #[command(after_help = "OUTPUT:
+    branch<TAB>path<TAB>status
+    --json: [{branch, path, pr}], pr null when unknown")]
+    Status {
+        #[command(flatten)]
+        repo: RepoPathArgs,
+
+        #[command(flatten)]
+        output: JsonArgs,
+
+        /// Branch to inspect
+        #[arg(short, long, value_name = "BRANCH")]
+        branch: Option<String>,
+
+        /// Inspect every non-bare worktree
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum CommitCli {
+    /// Print recent commits as a git graph
+    Tree {
+        #[command(flatten)]
+        repo: RepoPathArgs,
+
+        /// Number of commits to print
+        #[arg(short = 'n', long, value_name = "COUNT", default_value_t = 8)]
+        limit: usize,
+    },
+}
+
+#[derive(Debug, Args)]
+struct SearchArgs {
+    query: String,
+
+    #[command(flatten)]
+    repo: RepoPathArgs,
+
+    #[command(flatten)]
+    output: JsonArgs,
+
+    /// Print the stable space-separated format, which never changes within
+    /// a version
+    #[arg(
+        long,
+        value_name = "VERSION",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "v1",
+        value_parser = PORCELAIN_VERSIONS,
+        conflicts_with = "json"
+    )]
+    porcelain: Option<String>,
+
+    /// Include GitHub PR status before searching
+    #[arg(long)]
+    pr: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCli {
+    /// Validate global and project config files
+    ///
+    /// Reports syntax errors with line numbers, unknown keys, invalid branch
+    /// types (missing prefixes, duplicate shortcuts, missing base branches),
+    /// missing copy_files sources, and a non-executable post-add script.
+    /// Exits non-zero when any error is found.
+    #[command(after_help = "OUTPUT:
+    error<TAB>source<TAB>message
+    warning<TAB>source<TAB>message
+    ok<TAB>files_checked")]
+    Check(RepoPathArgs),
+    /// Bundle the global owt configuration into one portable file
+    ///
+    /// Includes ~/.config/owt/config.toml with its theme, branch types, and
+    /// other settings. The project registry (repos.toml) stays on this
+    /// machine. Writes to stdout when FILE is omitted.
+    #[command(after_help = "OUTPUT (with FILE):
+    exported<TAB>file
+    skipped<TAB>file<TAB>reason")]
+    Export {
+        /// Bundle file to write
+        file: Option<PathBuf>,
+    },
+    /// Install a bundle from `owt config export` into ~/.config/owt
+    ///
+    /// Refuses to replace files that differ from the bundle unless --force
+    /// is given. Use - to read the bundle from stdin.
+    #[command(after_help = "OUTPUT:
+    created<TAB>file
+    updated<TAB>file
+    unchanged<TAB>file")]
+    Import {
+        /// Bundle file to read, or - for stdin
+        file: PathBuf,
+        /// Overwrite files that differ from the bundle
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+struct ConvertArgs {
+    #[command(flatten)]
+    repo: RepoPathArgs,
+
+    /// Print the planned steps without changing anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+struct DoctorArgs {
+    #[command(flatten)]
+    repo: RepoPathArgs,
+
+    /// Forget unfinished operations after resolving them
+    #[arg(long)]
+    clear_journal: bool,
+
+    /// Rewrite broken gitdir links between the repository and worktrees that
+    /// moved on disk, in both directions, then run the checks
+    #[arg(long, conflicts_with = "clear_journal")]
+    repair: bool,
+}
+
+impl Cli {
+    /// Resolve defaults against `current_dir`, which is only called when a
+    /// command needs it.
+    pub(crate) fn into_command(self, current_dir: impl Fn() -> PathBuf) -> Command {
+        let current_dir = &current_dir;
+        let resolve = |repo: RepoPathArgs| repo.path.unwrap_or_else(current_dir);
+
+        match self.command {
+            None => self.tui.into_command(current_dir),
+            Some(CliCommand::Tui(tui)) => tui.into_command(current_dir),
+            Some(CliCommand::Clone {
+                url,
+                path,
+                branch,
+                depth,
+                no_worktree,
+            }) => Command::Clone {
+                url,
+                path,
+                branch,
+                depth,
+                no_worktree,
+            },
+            Some(CliCommand::Pick(args)) => Command::Pick {
+                path: args.target.or(args.path).unwrap_or_else(current_dir),
+                select: args.select,
+            },
+            Some(CliCommand::Init) => Command::Init,
+            Some(CliCommand::Convert(args)) => Command::Convert {
+                path: resolve(args.repo),
+                dry_run: args.dry_run,
+            },
+            Some(CliCommand::Setup) => Command::Setup,
+            Some(CliCommand::TestCd) => Command::TestCd,
+            Some(CliCommand::Add(args)) | Some(CliCommand::Worktree(WorktreeCli::Create(args))) => {
+                Command::Worktree(args.into_command(current_dir))
+            }
+            Some(CliCommand::Remove(args))
+            | Some(CliCommand::Worktree(WorktreeCli::Delete(args))) => {
+                Command::Worktree(WorktreeCommand::Delete {
+                    path: resolve(args.repo),
+                    target: args.target,
+                    force: args.force,
+                    delete_branch: args.delete_branch,
+                    keep_nested: args.keep_nested,
+                    json: args.output.json,
+                })
+            }
+            Some(CliCommand::List(args)) | Some(CliCommand::Worktree(WorktreeCli::List(args))) => {
+                Command::Worktree(WorktreeCommand::List {
+                    path: resolve(args.repo),
+                    include_pr: args.pr,
+                    json: args.output.json,
+                    porcelain: args.porcelain.is_some(),
+                })
+            }
+            Some(CliCommand::Path(args)) => Command::Path {
+                path: resolve(args.repo),
+                target: args.target,
+            },
+            Some(CliCommand::Clean(args)) => Command::Worktree(WorktreeCommand::Clean {
+                path: resolve(args.repo),
+                delete_branch: args.delete_branch,
+                yes: args.yes,
+                dry_run: args.dry_run,
+                json: args.output.json,
+            }),
+            Some(CliCommand::Worktree(WorktreeCli::Prune(args))) => {
+                Command::Worktree(WorktreeCommand::Prune {
+                    path: resolve(args.repo),
+                    dry_run: args.dry_run,
+                })
+            }
+            Some(CliCommand::Pr(PrCli::Status {
+                repo,
+                output,
+                branch,
+                all,
+            })) => Command::Pr(PrCommand::Status {
+                path: resolve(repo),
+                branch,
+                all,
+                json: output.json,
+            }),
+            Some(CliCommand::Commit(CommitCli::Tree { repo, limit })) => {
+                Command::Commit(CommitCommand::Tree {
+                    path: resolve(repo),
+                    limit,
+                })
+            }
+            Some(CliCommand::Search(args)) => Command::Search(SearchCommand::Query {
+                path: resolve(args.repo),
+                query: args.query,
+                include_pr: args.pr,
+                json: args.output.json,
+                porcelain: args.porcelain.is_some(),
+            }),
+            Some(CliCommand::Config(ConfigCli::Check(repo))) => {
+                Command::Config(ConfigCommand::Check {
+                    path: resolve(repo),
+                })
+            }
+            Some(CliCommand::Config(ConfigCli::Export { file })) => {
+                Command::Config(ConfigCommand::Export { file })
+            }
+            Some(CliCommand::Config(ConfigCli::Import { file, force })) => {
+                Command::Config(ConfigCommand::Import { file, force })
+            }
+            Some(CliCommand::Repos(output)) => Command::Repos { json: output.json },
+            Some(CliCommand::Doctor(args)) => Command::Doctor {
+                path: resolve(args.repo),
+                clear_journal: args.clear_journal,
+                repair: args.repair,
+            },
+            Some(CliCommand::Exec(args)) => Command::Exec {
+                path: resolve(args.repo),
+                filter: ExecFilter {
+                    dirty_only: args.dirty_only,
+                    branch_glob: args.branch_glob,
+                },
+                command: args.command,
+                jobs: args.jobs.map(|jobs| jobs as usize),
+            },
+        }
+    }
+}
+
+impl TuiArgs {
+    fn into_command(self, current_dir: &impl Fn() -> PathBuf) -> Command {
+        let path = self.target.or(self.path).unwrap_or_else(current_dir);
+        if self.rpc {
+            return Command::Rpc { path };
+        }
+        Command::Tui {
+            path,
+            select: self.select,
+            events: self.events,
+        }
+    }
+}
+
+impl CreateArgs {
+    fn into_command(self, current_dir: &impl Fn() -> PathBuf) -> WorktreeCommand {
+        let sparse = if self.full {
+            Some(Vec::new())
+        } else if self.sparse.is_empty() {
+            None
+        } else {
+            Some(self.sparse)
+        };
+        WorktreeCommand::Create {
+            path: self.repo.path.unwrap_or_else(current_dir),
+            branch: self.branch,
+            base: self.base,
+            worktree_path: self.worktree_path,
+            tmux: self.tmux,
+            sparse,
+            json: self.output.json,
+        }
+    }
+}
+
+/// The bare `owt <PATH>` form must name a directory, so a mistyped
+/// subcommand is reported instead of being opened as a path.
+/// An existing directory, else the project registered under that name
+fn directory_or_registered_repo(value: &str) -> Result<PathBuf, String> {
+    if Path::new(value).is_dir() {
+        return Ok(PathBuf::from(value));
+    }
+    crate::repo_registry::lookup(value).ok_or_else(|| {
+        "not a subcommand, an existing directory, or a project listed by `owt repos`".to_string()
+    })
+}
+
+fn on_off(value: &str) -> Result<bool, String> {
+    match value {
+        "on" | "true" | "1" | "yes" => Ok(true),
+        "off" | "false" | "0" | "no" => Ok(false),
+        _ => Err("expected on or off".to_string()),
+    }
+}
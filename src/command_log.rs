@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Oldest commands are dropped once the in-memory log holds this many
+const MAX_RECORDS: usize = 200;
+
+/// Output beyond this many characters is cut off, per command
+const MAX_OUTPUT_CHARS: usize = 8000;
+
+/// One git (or gh) command owt ran on the user's behalf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommandRecord {
+    pub at: u64, // Unix seconds
+    pub command: String,
+    pub dir: Option<PathBuf>,
+    /// Exit code, or `None` when the command could not start or was killed
+    pub exit_code: Option<i32>,
+    pub output: String,
+}
+
+fn records() -> &'static Mutex<VecDeque<CommandRecord>> {
+    static RECORDS: OnceLock<Mutex<VecDeque<CommandRecord>>> = OnceLock::new();
+    RECORDS.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+fn log_file() -> &'static Mutex<Option<PathBuf>> {
+    static LOG_FILE: OnceLock<Mutex<Option<PathBuf>>> = OnceLock::new();
+    LOG_FILE.get_or_init(|| Mutex::new(None))
+}
+
+/// Also append every record to `path` (the `command_log` config option), or
+/// stop writing to disk with `None`
+pub(crate) fn persist_to(path: Option<PathBuf>) {
+    if let Ok(mut log_file) = log_file().lock() {
+        *log_file = path;
+    }
+}
+
+/// Record a finished command with its combined stdout and stderr
+pub(crate) fn record(command: &Command, result: &std::io::Result<Output>) {
+    let (exit_code, output) = match result {
+        Ok(output) => (
+            output.status.code(),
+            [&output.stdout, &output.stderr]
+                .iter()
+                .map(|stream| String::from_utf8_lossy(stream).trim().to_string())
+                .filter(|stream| !stream.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        Err(error) => (None, error.to_string()),
+    };
+    push(CommandRecord {
+        at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0),
+        command: std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|part| part.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+        dir: command.get_current_dir().map(Path::to_path_buf),
+        exit_code,
+        output: truncate(output),
+    });
+}
+
+fn truncate(output: String) -> String {
+    match output.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((cut, _)) => format!("{}\n... (output truncated)", &output[..cut]),
+        None => output,
+    }
+}
+
+fn push(record: CommandRecord) {
+    if let Some(path) = log_file().lock().ok().and_then(|path| path.clone()) {
+        let _ = append(&path, &record);
+    }
+    if let Ok(mut records) = records().lock() {
+        records.push_back(record);
+        while records.len() > MAX_RECORDS {
+            records.pop_front();
+        }
+    }
+}
+
+fn append(path: &Path, record: &CommandRecord) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    write!(file, "{}", format_record(record))
+}
+
+/// A record as plain text: a `$ command` header line with the time and exit
+/// code, then the output
+pub(crate) fn format_record(record: &CommandRecord) -> String {
+    let exit = record
+        .exit_code
+        .map(|code| format!("exit {}", code))
+        .unwrap_or_else(|| "no exit code".to_string());
+    let mut text = format!(
+        "[{}] {} $ {}\n",
+        clock_time(record.at),
+        exit,
+        record.command
+    );
+    if let Some(dir) = &record.dir {
+        text.push_str(&format!("  in {}\n", dir.display()));
+    }
+    for line in record.output.lines() {
+        text.push_str(&format!("  {}\n", line));
+    }
+    text
+}
+
+/// `HH:MM:SS` of a Unix time in the local time zone (UTC off Unix)
+fn clock_time(at: u64) -> String {
+    #[cfg(unix)]
+    if let Some(tm) = local_time(at) {
+        return format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec);
+    }
+    let seconds = at % (24 * 60 * 60);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// A Unix time broken down in the local time zone
+#[cfg(unix)]
+pub(crate) fn local_time(at: u64) -> Option<libc::tm> {
+    let time = at as libc::time_t;
+    // SAFETY: localtime_r only writes into the tm we pass
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return None;
+    }
+    Some(tm)
+}
+
+/// Every command recorded this session, oldest first
+pub(crate) fn snapshot() -> Vec<CommandRecord> {
+    records()
+        .lock()
+        .map(|records| records.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// The whole log as display lines, oldest command first
+pub(crate) fn lines() -> Vec<String> {
+    snapshot()
+        .iter()
+        .flat_map(|record| {
+            format_record(record)
+                .lines()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_keeps_the_command_exit_code_and_output() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo owt-command-log-out; echo oops >&2; exit 3"]);
+        let result = command.output();
+        record(&command, &result);
+
+        let logged = snapshot()
+            .into_iter()
+            .rev()
+            .find(|record| record.command.contains("owt-command-log-out"))
+            .unwrap();
+        assert_eq!(logged.exit_code, Some(3));
+        assert_eq!(logged.output, "owt-command-log-out\noops");
+        let text = format_record(&logged);
+        assert!(text.contains("exit 3 $ sh -c echo owt-command-log-out"));
+        assert!(text.ends_with("  owt-command-log-out\n  oops\n"));
+
+        assert_eq!(truncate("short".to_string()), "short");
+        assert!(truncate("x".repeat(MAX_OUTPUT_CHARS + 1)).ends_with("(output truncated)"));
+    }
+}
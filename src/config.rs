@@ -1,24 +1,195 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::types::{DiscardMode, Worktree, WorktreeNaming};
+
 #[derive(Debug, Default)]
 pub struct Config {
     pub editor: Option<String>,
     pub terminal: Option<String>,
+    pub git_ui: Option<String>, // Git UI launched by l (default: lazygit)
     pub worktree_root: Option<String>,
-    pub copy_files: Vec<String>, // Files to copy when adding worktree
+    pub worktree_dir: Option<String>, // Path template for new worktrees, e.g. "../trees/{branch_slug}"
+    pub worktree_naming: Option<String>, // Folder for a branch: nested (default), dash, strip-prefix
+    pub copy_files: Vec<String>,         // Files to copy when adding worktree
+    pub copy_source: Option<String>,     // Worktree (branch or folder name) copy_files come from
+    pub sparse_paths: Vec<String>, // Directories checked out in new worktrees (empty: full checkout)
+    pub submodule_update: Option<bool>, // Run `git submodule update --init --recursive` in new worktrees
+    pub lfs_pull: Option<bool>, // Run `git lfs pull` in new worktrees whose .gitattributes use LFS
+    pub install_command: Option<String>, // Offered after adding a worktree; "auto" detects it
     pub post_add_script: Option<String>, // Script to run after adding worktree
     pub tmux_worktree_mode: bool,
     tmux_worktree_mode_configured: bool,
     pub run_post_add_script_in_tmux: bool,
-    run_post_add_script_in_tmux_configured: bool,
+    pub branch_types: Vec<BranchType>,
+    pub tasks: Vec<Task>, // Commands runnable in a worktree from the TUI task menu
+    pub issue_title_command: Option<String>, // Prints the title of issue {id}, for Ctrl+T in the add modal
+    pub ship_steps: Vec<String>, // Steps of the Z ship action (empty: push, pr, review, main)
+    pub protected_branches: Vec<String>, // Extra branch patterns never deleted on a remote
+    pub theme: Option<String>,   // "dark", "light", "auto", or "custom"
+    pub theme_colors: BTreeMap<String, String>, // [theme] overrides: color name -> "#rrggbb"
+    pub spinner: Option<String>, // Spinner frame set, or "none"
+    pub spinner_interval_ms: Option<u64>, // Time each spinner frame shows
+    pub show_disk_usage: Option<bool>, // Show the Size column in the worktree list
+    pub show_upstream: Option<bool>, // Show the Upstream column in the worktree list
+    pub show_head_commit: Option<bool>, // Show the HEAD column (short SHA and subject)
+    pub hide_unmatched: Option<bool>, // Filtering hides non-matching rows instead of dimming them
+    pub auto_refresh: Option<u64>, // Seconds between background list refreshes; 0 or unset disables
+    pub live_status: Option<bool>, // Watch worktrees and refs, re-reading status of changed rows
+    pub auto_fetch: Option<u64>, // Minutes between background fetches of the default remote; 0 or unset disables
+    pub git_timeout: Option<u64>, // Seconds before a fetch, pull, push, or merge is killed (default 120); 0 disables
+    pub command_log: Option<bool>, // Also append the @ command log to .owt/commands.log
+    pub repositories: Vec<String>, // Projects offered by the w switcher (global config only)
+    pub discard_mode: Option<String>, // What X removes: tracked, untracked, ignored (global config only)
+    pub clone_host: Option<String>,   // Host `owt clone user/repo` expands to (global config only)
+}
+
+/// A `[[branch_types]]` entry that classifies branches by name prefix.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BranchType {
+    pub name: String,
+    pub prefix: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>, // Palette name or "#rrggbb"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>, // Default base branch for new branches of this type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortcut: Option<String>, // Single key that picks this type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>, // Editor for worktrees of this type, instead of `editor`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_template: Option<String>, // Required branch name shape, e.g. "{prefix}{ticket}-{slug}"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sparse_paths: Option<Vec<String>>, // Replaces `sparse_paths` for this type; empty: full checkout
+}
+
+/// A piece of a `name_template`: literal text or a `{variable}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart<'a> {
+    Literal(&'a str),
+    Variable(&'a str),
+}
+
+fn template_parts(template: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + len];
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            parts.push(TemplatePart::Literal(&rest[..start + len + 1]));
+        } else {
+            if start > 0 {
+                parts.push(TemplatePart::Literal(&rest[..start]));
+            }
+            parts.push(TemplatePart::Variable(name));
+        }
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Literal(rest));
+    }
+    parts
+}
+
+impl BranchType {
+    /// Variables of the `name_template` the add modal asks for, in order.
+    /// `{prefix}` is filled in from the type itself.
+    pub fn template_fields(&self) -> Vec<String> {
+        let mut fields: Vec<String> = Vec::new();
+        for part in template_parts(self.name_template.as_deref().unwrap_or("")) {
+            if let TemplatePart::Variable(name) = part {
+                if name != "prefix" && !fields.iter().any(|field| field == name) {
+                    fields.push(name.to_string());
+                }
+            }
+        }
+        fields
+    }
+
+    /// The branch name from the template, with `value` giving each variable.
+    /// `{slug}` becomes lowercase words joined by `-`; other values only have
+    /// whitespace replaced by `-`. Variables without a value stay as written.
+    pub fn render_name(&self, value: impl Fn(&str) -> Option<String>) -> String {
+        template_parts(self.name_template.as_deref().unwrap_or(""))
+            .into_iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => text.to_string(),
+                TemplatePart::Variable("prefix") => self.prefix.clone(),
+                TemplatePart::Variable(name) => match value(name) {
+                    Some(text) if name == "slug" => text
+                        .split(|c: char| !c.is_alphanumeric())
+                        .filter(|word| !word.is_empty())
+                        .map(str::to_lowercase)
+                        .collect::<Vec<_>>()
+                        .join("-"),
+                    Some(text) => text.split_whitespace().collect::<Vec<_>>().join("-"),
+                    None => format!("{{{}}}", name),
+                },
+            })
+            .collect()
+    }
+
+    /// Whether `branch` has the shape of the `name_template`, each variable
+    /// standing for one or more characters other than `/`. Always true
+    /// without a template.
+    pub fn matches_template(&self, branch: &str) -> bool {
+        fn matches(parts: &[TemplatePart], prefix: &str, text: &str) -> bool {
+            let Some((part, rest)) = parts.split_first() else {
+                return text.is_empty();
+            };
+            let literal = match part {
+                TemplatePart::Literal(literal) => literal,
+                TemplatePart::Variable("prefix") => prefix,
+                TemplatePart::Variable(_) => {
+                    // Try every non-empty value up to the next `/`
+                    let end = text.find('/').unwrap_or(text.len());
+                    return text[..end]
+                        .char_indices()
+                        .map(|(index, c)| index + c.len_utf8())
+                        .any(|index| matches(rest, prefix, &text[index..]));
+                }
+            };
+            text.strip_prefix(literal)
+                .is_some_and(|text| matches(rest, prefix, text))
+        }
+        match self.name_template.as_deref() {
+            Some(template) => matches(&template_parts(template), &self.prefix, branch),
+            None => true,
+        }
+    }
 }
 
+/// A `[[tasks]]` entry: a named shell command run inside a worktree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Task {
+    pub name: String,
+    pub command: String,
+}
+
+/// Colors used when a branch prefix has no configured color.
+const DEFAULT_BRANCH_TYPE_COLORS: &[(&str, &str)] = &[
+    ("feature/", "green"),
+    ("feat/", "green"),
+    ("hotfix/", "red"),
+    ("bugfix/", "amber"),
+    ("fix/", "amber"),
+    ("release/", "violet"),
+];
+
+/// Branches that are always protected, in addition to `protected_branches`.
+pub const DEFAULT_PROTECTED_BRANCHES: &[&str] = &["main", "master", "develop"];
+
 impl Config {
     /// Load config with project-level override support
     /// Priority: project (.owt/config.toml) > global (~/.config/owt/config.toml)
-    #[allow(dead_code)]
     pub fn load() -> Result<Self> {
         Self::load_with_project(None)
     }
@@ -28,8 +199,7 @@ impl Config {
         // Start with global config
         let global_path = Self::global_config_path();
         let mut config = if global_path.exists() {
-            let content = fs::read_to_string(&global_path)?;
-            Self::parse(&content)?
+            Self::read_file(&global_path)?
         } else {
             Self::default()
         };
@@ -38,8 +208,7 @@ impl Config {
         if let Some(project_root) = project_root_path {
             let project_path = Self::project_config_path(project_root);
             if project_path.exists() {
-                let content = fs::read_to_string(&project_path)?;
-                let project_config = Self::parse(&content)?;
+                let project_config = Self::read_file(&project_path)?;
                 config.merge_from_project(project_config);
             }
         }
@@ -49,6 +218,9 @@ impl Config {
 
     /// Merge project config into self (project overrides global safe values).
     /// Script auto-run must stay globally trusted and cannot be enabled by a repo.
+    /// `repositories` is the user's own list across projects, so it is not merged.
+    /// Neither is `discard_mode`: a repository must not widen what `X` deletes.
+    /// `clone_host` is read before any repository exists, so it is global too.
     fn merge_from_project(&mut self, other: Config) {
         if other.editor.is_some() {
             self.editor = other.editor;
@@ -56,24 +228,109 @@ impl Config {
         if other.terminal.is_some() {
             self.terminal = other.terminal;
         }
+        if other.git_ui.is_some() {
+            self.git_ui = other.git_ui;
+        }
         if other.worktree_root.is_some() {
             self.worktree_root = other.worktree_root;
         }
+        if other.worktree_dir.is_some() {
+            self.worktree_dir = other.worktree_dir;
+        }
+        if other.worktree_naming.is_some() {
+            self.worktree_naming = other.worktree_naming;
+        }
         if !other.copy_files.is_empty() {
             self.copy_files = other.copy_files;
         }
+        if other.copy_source.is_some() {
+            self.copy_source = other.copy_source;
+        }
+        if !other.sparse_paths.is_empty() {
+            self.sparse_paths = other.sparse_paths;
+        }
+        if other.submodule_update.is_some() {
+            self.submodule_update = other.submodule_update;
+        }
+        if other.lfs_pull.is_some() {
+            self.lfs_pull = other.lfs_pull;
+        }
+        if other.install_command.is_some() {
+            self.install_command = other.install_command;
+        }
         if other.post_add_script.is_some() {
             self.post_add_script = other.post_add_script;
         }
         if other.tmux_worktree_mode_configured {
             self.tmux_worktree_mode = other.tmux_worktree_mode;
         }
+        if !other.branch_types.is_empty() {
+            self.branch_types = other.branch_types;
+        }
+        if !other.tasks.is_empty() {
+            self.tasks = other.tasks;
+        }
+        if other.issue_title_command.is_some() {
+            self.issue_title_command = other.issue_title_command;
+        }
+        if !other.ship_steps.is_empty() {
+            self.ship_steps = other.ship_steps;
+        }
+        if other.theme.is_some() {
+            self.theme = other.theme;
+        }
+        if !other.theme_colors.is_empty() {
+            self.theme_colors = other.theme_colors;
+        }
+        if other.spinner.is_some() {
+            self.spinner = other.spinner;
+        }
+        if other.spinner_interval_ms.is_some() {
+            self.spinner_interval_ms = other.spinner_interval_ms;
+        }
+        if other.show_disk_usage.is_some() {
+            self.show_disk_usage = other.show_disk_usage;
+        }
+        if other.show_upstream.is_some() {
+            self.show_upstream = other.show_upstream;
+        }
+        if other.show_head_commit.is_some() {
+            self.show_head_commit = other.show_head_commit;
+        }
+        if other.hide_unmatched.is_some() {
+            self.hide_unmatched = other.hide_unmatched;
+        }
+        if other.auto_refresh.is_some() {
+            self.auto_refresh = other.auto_refresh;
+        }
+        if other.live_status.is_some() {
+            self.live_status = other.live_status;
+        }
+        if other.auto_fetch.is_some() {
+            self.auto_fetch = other.auto_fetch;
+        }
+        if other.git_timeout.is_some() {
+            self.git_timeout = other.git_timeout;
+        }
+        if other.command_log.is_some() {
+            self.command_log = other.command_log;
+        }
+        // A repo can add protection but never lift what the global config protects
+        for pattern in other.protected_branches {
+            if !self.protected_branches.contains(&pattern) {
+                self.protected_branches.push(pattern);
+            }
+        }
+    }
+
+    /// Global config directory: ~/.config/owt
+    pub fn global_config_dir() -> PathBuf {
+        dirs_config_dir().join("owt")
     }
 
     /// Global config path: ~/.config/owt/config.toml
     pub fn global_config_path() -> PathBuf {
-        let config_dir = dirs_config_dir().join("owt");
-        config_dir.join("config.toml")
+        Self::global_config_dir().join("config.toml")
     }
 
     /// Project config path: .owt/config.toml under the project root
@@ -101,139 +358,317 @@ impl Config {
     /// Save config to specified path
     #[allow(dead_code)]
     fn save_to(&self, config_path: &PathBuf) -> Result<()> {
+        Self::write_file(config_path, &self.to_file(true))
+    }
+
+    fn save_to_project_path(&self, config_path: &PathBuf) -> Result<()> {
+        Self::write_file(config_path, &self.to_file(false))
+    }
+
+    fn write_file(config_path: &PathBuf, file: &ConfigFile) -> Result<()> {
         let config_dir = config_path.parent().unwrap();
 
         if !config_dir.exists() {
             fs::create_dir_all(config_dir)?;
         }
 
-        let mut content = String::new();
+        fs::write(config_path, toml::to_string(file)?)?;
+        Ok(())
+    }
 
-        if let Some(ref editor) = self.editor {
-            content.push_str(&format!("editor = \"{}\"\n", editor));
-        }
-        if let Some(ref terminal) = self.terminal {
-            content.push_str(&format!("terminal = \"{}\"\n", terminal));
-        }
-        if let Some(ref worktree_root) = self.worktree_root {
-            content.push_str(&format!("worktree_root = \"{}\"\n", worktree_root));
-        }
-        if !self.copy_files.is_empty() {
-            let files = self
-                .copy_files
-                .iter()
-                .map(|f| format!("\"{}\"", f))
-                .collect::<Vec<_>>()
-                .join(", ");
-            content.push_str(&format!("copy_files = [{}]\n", files));
-        }
-        if let Some(ref script) = self.post_add_script {
-            content.push_str(&format!("post_add_script = \"{}\"\n", script));
+    /// The on-disk form of this config. Script auto-run and the repository list
+    /// are only written to the global file, since project config cannot set them.
+    fn to_file(&self, global: bool) -> ConfigFile {
+        // `theme = "<mode>"` on its own, or a `[theme]` table (with `mode`) when
+        // there are color overrides, since TOML cannot hold both under one key.
+        let theme = if self.theme_colors.is_empty() {
+            self.theme.clone().map(ThemeSetting::Mode)
+        } else {
+            Some(ThemeSetting::Table {
+                mode: self.theme.clone(),
+                colors: self.theme_colors.clone(),
+            })
+        };
+
+        ConfigFile {
+            editor: self.editor.clone(),
+            terminal: self.terminal.clone(),
+            git_ui: self.git_ui.clone(),
+            worktree_root: self.worktree_root.clone(),
+            worktree_dir: self.worktree_dir.clone(),
+            worktree_naming: self.worktree_naming.clone(),
+            copy_files: self.copy_files.clone(),
+            copy_source: self.copy_source.clone(),
+            sparse_paths: self.sparse_paths.clone(),
+            submodule_update: self.submodule_update,
+            lfs_pull: self.lfs_pull,
+            install_command: self.install_command.clone(),
+            post_add_script: self.post_add_script.clone(),
+            tmux_worktree_mode: Some(self.tmux_worktree_mode),
+            run_post_add_script_in_tmux: global.then_some(self.run_post_add_script_in_tmux),
+            protected_branches: self.protected_branches.clone(),
+            theme,
+            spinner: self.spinner.clone(),
+            spinner_interval_ms: self.spinner_interval_ms,
+            show_disk_usage: self.show_disk_usage,
+            show_upstream: self.show_upstream,
+            show_head_commit: self.show_head_commit,
+            hide_unmatched: self.hide_unmatched,
+            auto_refresh: self.auto_refresh,
+            live_status: self.live_status,
+            auto_fetch: self.auto_fetch,
+            git_timeout: self.git_timeout,
+            command_log: self.command_log,
+            branch_types: self.branch_types.clone(),
+            tasks: self.tasks.clone(),
+            issue_title_command: self.issue_title_command.clone(),
+            ship_steps: self.ship_steps.clone(),
+            repositories: if global {
+                self.repositories.clone()
+            } else {
+                Vec::new()
+            },
+            discard_mode: self.discard_mode.clone().filter(|_| global),
+            clone_host: self.clone_host.clone().filter(|_| global),
+            core: None,
         }
-        content.push_str(&format!(
-            "tmux_worktree_mode = {}\n",
-            self.tmux_worktree_mode
-        ));
-        content.push_str(&format!(
-            "run_post_add_script_in_tmux = {}\n",
-            self.run_post_add_script_in_tmux
-        ));
+    }
 
-        fs::write(config_path, content)?;
-        Ok(())
+    fn read_file(config_path: &std::path::Path) -> Result<Self> {
+        let content = fs::read_to_string(config_path)?;
+        Self::parse(&content).map_err(|err| anyhow!("{}: {}", config_path.display(), err))
     }
 
-    fn save_to_project_path(&self, config_path: &PathBuf) -> Result<()> {
-        let config_dir = config_path.parent().unwrap();
+    pub(crate) fn parse(content: &str) -> Result<Self> {
+        let file: ConfigFile =
+            toml::from_str(content).map_err(|err| describe_toml_error(content, &err))?;
+        Ok(file.into())
+    }
+}
 
-        if !config_dir.exists() {
-            fs::create_dir_all(config_dir)?;
-        }
+/// On-disk shape of config.toml. Every key is optional so that a project file
+/// only overrides what it actually sets.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct ConfigFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    editor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    terminal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_ui: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worktree_root: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worktree_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worktree_naming: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    copy_files: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    copy_source: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sparse_paths: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submodule_update: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lfs_pull: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    install_command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    post_add_script: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tmux_worktree_mode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_post_add_script_in_tmux: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    protected_branches: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    theme: Option<ThemeSetting>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spinner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spinner_interval_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    show_disk_usage: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    show_upstream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    show_head_commit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hide_unmatched: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_refresh: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    live_status: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_fetch: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command_log: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issue_title_command: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    branch_types: Vec<BranchType>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tasks: Vec<Task>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ship_steps: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    repositories: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    discard_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clone_host: Option<String>,
+    /// Legacy `[core]` table; its keys apply unless also set at the top level.
+    #[serde(skip_serializing)]
+    core: Option<Box<ConfigFile>>,
+}
 
-        let mut content = String::new();
+/// `theme = "dark"` or a `[theme]` table with `mode` and color overrides.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum ThemeSetting {
+    Mode(String),
+    Table {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<String>,
+        #[serde(flatten)]
+        colors: BTreeMap<String, String>,
+    },
+}
 
-        if let Some(ref editor) = self.editor {
-            content.push_str(&format!("editor = \"{}\"\n", editor));
+impl ConfigFile {
+    fn fill_from(&mut self, other: ConfigFile) {
+        self.editor = self.editor.take().or(other.editor);
+        self.terminal = self.terminal.take().or(other.terminal);
+        self.git_ui = self.git_ui.take().or(other.git_ui);
+        self.worktree_root = self.worktree_root.take().or(other.worktree_root);
+        self.worktree_dir = self.worktree_dir.take().or(other.worktree_dir);
+        self.worktree_naming = self.worktree_naming.take().or(other.worktree_naming);
+        self.copy_source = self.copy_source.take().or(other.copy_source);
+        self.submodule_update = self.submodule_update.or(other.submodule_update);
+        self.lfs_pull = self.lfs_pull.or(other.lfs_pull);
+        self.install_command = self.install_command.take().or(other.install_command);
+        self.post_add_script = self.post_add_script.take().or(other.post_add_script);
+        self.tmux_worktree_mode = self.tmux_worktree_mode.or(other.tmux_worktree_mode);
+        self.run_post_add_script_in_tmux = self
+            .run_post_add_script_in_tmux
+            .or(other.run_post_add_script_in_tmux);
+        self.theme = self.theme.take().or(other.theme);
+        self.spinner = self.spinner.take().or(other.spinner);
+        self.spinner_interval_ms = self.spinner_interval_ms.or(other.spinner_interval_ms);
+        self.show_disk_usage = self.show_disk_usage.or(other.show_disk_usage);
+        self.show_upstream = self.show_upstream.or(other.show_upstream);
+        self.show_head_commit = self.show_head_commit.or(other.show_head_commit);
+        self.hide_unmatched = self.hide_unmatched.or(other.hide_unmatched);
+        self.auto_refresh = self.auto_refresh.or(other.auto_refresh);
+        self.live_status = self.live_status.or(other.live_status);
+        self.auto_fetch = self.auto_fetch.or(other.auto_fetch);
+        self.git_timeout = self.git_timeout.or(other.git_timeout);
+        self.command_log = self.command_log.or(other.command_log);
+        if self.copy_files.is_empty() {
+            self.copy_files = other.copy_files;
         }
-        if let Some(ref terminal) = self.terminal {
-            content.push_str(&format!("terminal = \"{}\"\n", terminal));
+        if self.sparse_paths.is_empty() {
+            self.sparse_paths = other.sparse_paths;
         }
-        if let Some(ref worktree_root) = self.worktree_root {
-            content.push_str(&format!("worktree_root = \"{}\"\n", worktree_root));
+        if self.protected_branches.is_empty() {
+            self.protected_branches = other.protected_branches;
         }
-        if !self.copy_files.is_empty() {
-            let files = self
-                .copy_files
-                .iter()
-                .map(|f| format!("\"{}\"", f))
-                .collect::<Vec<_>>()
-                .join(", ");
-            content.push_str(&format!("copy_files = [{}]\n", files));
+        if self.branch_types.is_empty() {
+            self.branch_types = other.branch_types;
         }
-        if let Some(ref script) = self.post_add_script {
-            content.push_str(&format!("post_add_script = \"{}\"\n", script));
+        if self.tasks.is_empty() {
+            self.tasks = other.tasks;
         }
-        content.push_str(&format!(
-            "tmux_worktree_mode = {}\n",
-            self.tmux_worktree_mode
-        ));
-
-        fs::write(config_path, content)?;
-        Ok(())
+        self.issue_title_command = self
+            .issue_title_command
+            .take()
+            .or(other.issue_title_command);
+        if self.ship_steps.is_empty() {
+            self.ship_steps = other.ship_steps;
+        }
+        if self.repositories.is_empty() {
+            self.repositories = other.repositories;
+        }
+        self.discard_mode = self.discard_mode.take().or(other.discard_mode);
+        self.clone_host = self.clone_host.take().or(other.clone_host);
     }
+}
 
-    fn parse(content: &str) -> Result<Self> {
-        let mut config = Config::default();
-
-        for line in content.lines() {
-            let line = line.trim();
+impl From<ConfigFile> for Config {
+    fn from(mut file: ConfigFile) -> Self {
+        if let Some(core) = file.core.take() {
+            file.fill_from(*core);
+        }
 
-            // Skip comments, empty lines, and section headers
-            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
-                continue;
-            }
+        let (theme, theme_colors) = match file.theme {
+            Some(ThemeSetting::Mode(mode)) => (Some(mode), BTreeMap::new()),
+            Some(ThemeSetting::Table { mode, colors }) => (mode, colors),
+            None => (None, BTreeMap::new()),
+        };
 
-            if let Some((key, value)) = line.split_once('=') {
-                let key = key.trim();
-                let value = value.trim().trim_matches('"').trim_matches('\'');
-
-                match key {
-                    "editor" => config.editor = Some(value.to_string()),
-                    "terminal" => config.terminal = Some(value.to_string()),
-                    "worktree_root" => config.worktree_root = Some(value.to_string()),
-                    "post_add_script" => config.post_add_script = Some(value.to_string()),
-                    "tmux_worktree_mode" => {
-                        config.tmux_worktree_mode = parse_bool(value);
-                        config.tmux_worktree_mode_configured = true;
-                    }
-                    "run_post_add_script_in_tmux" => {
-                        config.run_post_add_script_in_tmux = parse_bool(value);
-                        config.run_post_add_script_in_tmux_configured = true;
-                    }
-                    "copy_files" => {
-                        let files: Vec<String> = value
-                            .trim_matches('[')
-                            .trim_matches(']')
-                            .split(',')
-                            .map(|s| s.trim().trim_matches('"').trim_matches('\'').to_string())
-                            .filter(|s| !s.is_empty())
-                            .collect();
-                        config.copy_files = files;
-                    }
-                    _ => {}
-                }
-            }
+        let mut branch_types = file.branch_types;
+        branch_types.retain(|branch_type| !branch_type.prefix.is_empty());
+        let mut tasks = file.tasks;
+        tasks.retain(|task| !task.command.trim().is_empty());
+
+        Config {
+            editor: file.editor,
+            terminal: file.terminal,
+            git_ui: file.git_ui,
+            worktree_root: file.worktree_root,
+            worktree_dir: file.worktree_dir,
+            worktree_naming: file.worktree_naming,
+            copy_files: file.copy_files,
+            copy_source: file.copy_source,
+            sparse_paths: file.sparse_paths,
+            submodule_update: file.submodule_update,
+            lfs_pull: file.lfs_pull,
+            install_command: file.install_command,
+            post_add_script: file.post_add_script,
+            tmux_worktree_mode: file.tmux_worktree_mode.unwrap_or(false),
+            tmux_worktree_mode_configured: file.tmux_worktree_mode.is_some(),
+            run_post_add_script_in_tmux: file.run_post_add_script_in_tmux.unwrap_or(false),
+            branch_types,
+            tasks,
+            issue_title_command: file.issue_title_command,
+            ship_steps: file.ship_steps,
+            repositories: file.repositories,
+            discard_mode: file.discard_mode,
+            clone_host: file.clone_host,
+            protected_branches: file.protected_branches,
+            theme,
+            theme_colors,
+            spinner: file.spinner,
+            spinner_interval_ms: file.spinner_interval_ms,
+            show_disk_usage: file.show_disk_usage,
+            show_upstream: file.show_upstream,
+            show_head_commit: file.show_head_commit,
+            hide_unmatched: file.hide_unmatched,
+            auto_refresh: file.auto_refresh,
+            live_status: file.live_status,
+            auto_fetch: file.auto_fetch,
+            git_timeout: file.git_timeout,
+            command_log: file.command_log,
         }
-
-        Ok(config)
     }
 }
 
-fn parse_bool(value: &str) -> bool {
-    matches!(
-        value.trim().to_ascii_lowercase().as_str(),
-        "true" | "1" | "yes" | "on"
-    )
+/// `line L, column C: <message>`, short enough for the status bar.
+fn describe_toml_error(content: &str, err: &toml::de::Error) -> anyhow::Error {
+    let message = err.message().trim();
+    let Some(span) = err.span() else {
+        return anyhow!("{}", message);
+    };
+
+    let before = &content[..span.start.min(content.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    anyhow!("line {}, column {}: {}", line, column, message)
 }
 
 impl Config {
@@ -244,12 +679,160 @@ impl Config {
             .unwrap_or_else(|| "vim".to_string())
     }
 
+    /// `discard_mode`, falling back to `untracked` when unset or unknown
+    pub fn get_discard_mode(&self) -> DiscardMode {
+        self.discard_mode
+            .as_deref()
+            .and_then(DiscardMode::parse)
+            .unwrap_or_default()
+    }
+
+    /// `clone_host`, falling back to `github.com` when unset or blank
+    pub fn get_clone_host(&self) -> &str {
+        self.clone_host
+            .as_deref()
+            .map(|host| host.trim().trim_matches('/'))
+            .filter(|host| !host.is_empty())
+            .unwrap_or("github.com")
+    }
+
+    /// `worktree_naming`, falling back to `nested` when unset or unknown
+    pub fn get_worktree_naming(&self) -> WorktreeNaming {
+        self.worktree_naming
+            .as_deref()
+            .and_then(WorktreeNaming::parse)
+            .unwrap_or_default()
+    }
+
+    /// Folder, relative to the worktree root, for a worktree on `branch`.
+    /// `strip-prefix` drops the matching `[[branch_types]]` prefix, or else
+    /// the first path segment, and keeps the whole branch if nothing is left.
+    pub fn worktree_folder(&self, branch: &str) -> String {
+        match self.get_worktree_naming() {
+            WorktreeNaming::Nested => branch.to_string(),
+            WorktreeNaming::Dash => branch_slug(branch),
+            WorktreeNaming::StripPrefix => {
+                let stripped = self
+                    .branch_type_for(branch)
+                    .and_then(|branch_type| branch.strip_prefix(branch_type.prefix.as_str()))
+                    .or_else(|| branch.split_once('/').map(|(_, rest)| rest))
+                    .map(|rest| rest.trim_matches('/'))
+                    .filter(|rest| !rest.is_empty())
+                    .unwrap_or(branch);
+                branch_slug(stripped)
+            }
+        }
+    }
+
+    pub fn get_git_ui(&self) -> String {
+        self.git_ui.clone().unwrap_or_else(|| "lazygit".to_string())
+    }
+
+    /// How often the TUI reloads the worktree list on its own, if at all
+    pub fn get_auto_refresh(&self) -> Option<std::time::Duration> {
+        self.auto_refresh
+            .filter(|seconds| *seconds > 0)
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// How often the TUI fetches the default remote in the background, if at all
+    pub fn get_auto_fetch(&self) -> Option<std::time::Duration> {
+        self.auto_fetch
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| std::time::Duration::from_secs(minutes * 60))
+    }
+
+    /// How long a network git command may run before it is killed, or
+    /// `None` when `git_timeout = 0` turns the limit off
+    pub fn get_git_timeout(&self) -> Option<std::time::Duration> {
+        Some(self.git_timeout.unwrap_or(120))
+            .filter(|seconds| *seconds > 0)
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// Editor for a worktree on `branch`: the matching `[[branch_types]]`
+    /// entry's `editor`, then the usual `get_editor` fallback.
+    pub fn editor_for_branch(&self, branch: Option<&str>) -> String {
+        branch
+            .and_then(|branch| self.branch_type_for(branch))
+            .and_then(|branch_type| branch_type.editor.clone())
+            .unwrap_or_else(|| self.get_editor())
+    }
+
     pub fn get_terminal(&self) -> Option<String> {
         self.terminal
             .clone()
             .or_else(|| std::env::var("TERMINAL").ok())
     }
 
+    /// Worktree to copy `copy_files` from. With `copy_source` set, only the
+    /// worktree whose branch or folder has that name, so a missing one copies
+    /// nothing rather than stale files; otherwise `fallback`.
+    pub fn copy_source_path(
+        &self,
+        worktrees: &[Worktree],
+        fallback: Option<PathBuf>,
+    ) -> Option<PathBuf> {
+        let Some(name) = self.copy_source.as_deref() else {
+            return fallback;
+        };
+        worktrees
+            .iter()
+            .filter(|wt| !wt.is_bare)
+            .find(|wt| wt.branch.as_deref() == Some(name) || wt.display_name() == name)
+            .map(|wt| wt.path.clone())
+    }
+
+    /// Directories checked out in a new worktree on `branch`: the matching
+    /// `[[branch_types]]` entry's `sparse_paths`, then the global list.
+    pub fn sparse_paths_for_branch(&self, branch: &str) -> &[String] {
+        self.branch_type_for(branch)
+            .and_then(|branch_type| branch_type.sparse_paths.as_deref())
+            .unwrap_or(&self.sparse_paths)
+    }
+
+    /// Use `paths` for every new worktree, as `--sparse`/`--full` do, ignoring
+    /// per-type `sparse_paths`.
+    pub fn override_sparse_paths(&mut self, paths: Vec<String>) {
+        self.sparse_paths = paths;
+        for branch_type in &mut self.branch_types {
+            branch_type.sparse_paths = None;
+        }
+    }
+
+    /// The `[[branch_types]]` entry with the longest prefix matching `branch`.
+    pub fn branch_type_for(&self, branch: &str) -> Option<&BranchType> {
+        self.branch_types
+            .iter()
+            .filter(|branch_type| branch.starts_with(&branch_type.prefix))
+            .max_by_key(|branch_type| branch_type.prefix.len())
+    }
+
+    /// Color spec for a branch: the longest matching `[[branch_types]]` prefix
+    /// wins, then the built-in prefix defaults.
+    pub fn branch_type_color(&self, branch: &str) -> Option<&str> {
+        let configured = self.branch_type_for(branch);
+
+        if let Some(color) = configured.and_then(|branch_type| branch_type.color.as_deref()) {
+            return Some(color);
+        }
+
+        DEFAULT_BRANCH_TYPE_COLORS
+            .iter()
+            .find(|(prefix, _)| branch.starts_with(prefix))
+            .map(|(_, color)| *color)
+    }
+
+    /// Whether a branch matches the built-in or configured protected patterns,
+    /// which are globs like `owt exec --branch-glob` takes (e.g. `release/*-rc`).
+    pub fn is_protected_branch(&self, branch: &str) -> bool {
+        DEFAULT_PROTECTED_BRANCHES
+            .iter()
+            .copied()
+            .chain(self.protected_branches.iter().map(String::as_str))
+            .any(|pattern| crate::worktree_exec::glob_match(pattern, branch))
+    }
+
     pub fn default_worktree_root() -> PathBuf {
         home_dir()
             .map(|home| home.join(".owt").join("worktree"))
@@ -263,6 +846,45 @@ impl Config {
             .unwrap_or_else(Self::default_worktree_root)
     }
 
+    /// Path for a new worktree on `branch` from the `worktree_dir` template,
+    /// or `None` without one. Relative results are under the project root.
+    pub fn templated_worktree_path(
+        &self,
+        project_root_path: &std::path::Path,
+        branch: &str,
+    ) -> Option<PathBuf> {
+        let template = self.worktree_dir.as_deref().map(str::trim)?;
+        if template.is_empty() {
+            return None;
+        }
+        let repo = project_root_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "repo".to_string());
+        // A configured [[branch_types]] name, else the first path segment
+        let branch_type = self
+            .branch_type_for(branch)
+            .map(|branch_type| branch_type.name.clone())
+            .filter(|name| !name.is_empty())
+            .or_else(|| branch.split_once('/').map(|(head, _)| head.to_string()))
+            .unwrap_or_default();
+        let expanded = template
+            .replace("{branch_slug}", &branch_slug(branch))
+            .replace("{branch}", branch)
+            .replace("{type}", &branch_type)
+            .replace("{date}", &today())
+            .replace("{repo}", &repo);
+
+        let path = expand_home_path(&expanded);
+        let path = if path.is_absolute() {
+            path
+        } else {
+            project_root_path.join(path)
+        };
+        Some(normalize_lexically(&path))
+    }
+
     /// Get the .owt directory path under the project root
     pub fn owt_dir(project_root_path: &std::path::Path) -> PathBuf {
         project_root_path.join(".owt")
@@ -288,7 +910,7 @@ impl Config {
     }
 }
 
-fn expand_home_path(path: &str) -> PathBuf {
+pub fn expand_home_path(path: &str) -> PathBuf {
     if path == "~" {
         return home_dir().unwrap_or_else(|| PathBuf::from(path));
     }
@@ -302,6 +924,77 @@ fn expand_home_path(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
+/// Variables a `worktree_dir` template may use
+pub(crate) const WORKTREE_DIR_VARIABLES: &[&str] =
+    &["branch", "branch_slug", "type", "date", "repo"];
+
+/// `branch` as one path segment: `/` and anything but letters, digits, `-`,
+/// `_`, and `.` become `-`
+fn branch_slug(branch: &str) -> String {
+    branch
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Today as `YYYY-MM-DD` in the local time zone (UTC off Unix)
+fn today() -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    #[cfg(unix)]
+    if let Some(tm) = crate::command_log::local_time(now) {
+        return format!(
+            "{:04}-{:02}-{:02}",
+            tm.tm_year + 1900,
+            tm.tm_mon + 1,
+            tm.tm_mday
+        );
+    }
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let days = (now / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Resolve `.` and `..` without touching the filesystem, so a template like
+/// `../trees/{branch}` gives a clean sibling path
+fn normalize_lexically(path: &std::path::Path) -> PathBuf {
+    use std::path::Component;
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
 fn home_dir() -> Option<PathBuf> {
     std::env::var_os("HOME").map(PathBuf::from)
 }
@@ -322,6 +1015,7 @@ fn dirs_config_dir() -> PathBuf {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::temp_dir;
     use std::sync::{Mutex, OnceLock};
 
     fn test_env_lock() -> &'static Mutex<()> {
@@ -395,7 +1089,7 @@ run_post_add_script_in_tmux = true
     fn test_parse_with_comments() {
         let content = r#"
 # This is a comment
-editor = vim
+editor = "vim" # trailing comment
 # terminal = iTerm
 "#;
         let config = Config::parse(content).unwrap();
@@ -412,6 +1106,33 @@ copy_files = [".env", ".envrc", "config.json"]
         assert_eq!(config.copy_files, vec![".env", ".envrc", "config.json"]);
     }
 
+    #[test]
+    fn test_parse_core_section_yields_to_top_level_keys() {
+        let content = r#"
+editor = "code"
+
+[core]
+editor = "vim"
+terminal = "Ghostty"
+copy_files = [".env"]
+"#;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(config.editor, Some("code".to_string()));
+        assert_eq!(config.terminal, Some("Ghostty".to_string()));
+        assert_eq!(config.copy_files, vec![".env"]);
+    }
+
+    #[test]
+    fn test_parse_errors_report_line_and_column() {
+        let err = Config::parse("editor = \"vim\"\ntmux_worktree_mode = \"yes\"\n").unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("line 2, column 22:"), "{}", message);
+        assert!(message.contains("expected a boolean"), "{}", message);
+
+        let err = Config::parse("editor = vim\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 1, column 10:"), "{}", err);
+    }
+
     #[test]
     fn test_save_writes_tmux_post_add_flag() {
         let dir = std::env::temp_dir().join(format!(
@@ -480,7 +1201,7 @@ copy_files = [".env", ".envrc", "config.json"]
     }
 
     #[test]
-    fn test_project_config_does_not_enable_tmux_post_add() {
+    fn test_project_config_does_not_enable_tmux_post_add_or_set_global_only_keys() {
         let _env_lock = acquire_test_env_lock();
         let dir = std::env::temp_dir().join(format!(
             "owt_project_config_test_{}_{}",
@@ -498,7 +1219,7 @@ copy_files = [".env", ".envrc", "config.json"]
         fs::create_dir_all(project_dir.join(".owt")).unwrap();
         fs::write(
             project_dir.join(".owt").join("config.toml"),
-            "run_post_add_script_in_tmux = true\n",
+            "run_post_add_script_in_tmux = true\nrepositories = [\"~/src/other\"]\ndiscard_mode = \"ignored\"\nclone_host = \"git.example.com\"\n",
         )
         .unwrap();
 
@@ -508,6 +1229,9 @@ copy_files = [".env", ".envrc", "config.json"]
         let config = Config::load_with_project(Some(&project_dir)).unwrap();
 
         assert!(!config.run_post_add_script_in_tmux);
+        assert!(config.repositories.is_empty());
+        assert_eq!(config.get_discard_mode(), DiscardMode::Untracked);
+        assert_eq!(config.get_clone_host(), "github.com");
 
         let _ = fs::remove_dir_all(dir);
     }
@@ -605,7 +1329,7 @@ run_post_add_script_in_tmux = true
         let xdg_config_home = dir.join("xdg-config");
         let project_dir = dir.join("project");
         fs::create_dir_all(&home_dir).unwrap();
-        fs::create_dir_all(&xdg_config_home.join("owt")).unwrap();
+        fs::create_dir_all(xdg_config_home.join("owt")).unwrap();
         fs::create_dir_all(project_dir.join(".owt")).unwrap();
         fs::write(
             xdg_config_home.join("owt").join("config.toml"),
@@ -677,6 +1401,300 @@ shortcut = "f"
         assert_eq!(config.editor, Some("vim".to_string()));
     }
 
+    #[test]
+    fn test_parse_branch_types_with_colors() {
+        let content = r##"
+editor = "vim"
+
+[[branch_types]]
+name = "feature"
+prefix = "feature/"
+color = "#ff8800"
+
+[[branch_types]]
+name = "spike"
+prefix = "spike/"
+
+[core]
+terminal = "Ghostty"
+"##;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(config.editor, Some("vim".to_string()));
+        assert_eq!(config.terminal, Some("Ghostty".to_string()));
+        assert_eq!(
+            config.branch_types,
+            vec![
+                BranchType {
+                    name: "feature".to_string(),
+                    prefix: "feature/".to_string(),
+                    color: Some("#ff8800".to_string()),
+                    ..Default::default()
+                },
+                BranchType {
+                    name: "spike".to_string(),
+                    prefix: "spike/".to_string(),
+                    color: None,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tasks_skips_entries_without_command() {
+        let content = r##"
+[[tasks]]
+name = "install deps"
+command = "npm ci"
+
+[[tasks]]
+name = "broken"
+"##;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(
+            config.tasks,
+            vec![Task {
+                name: "install deps".to_string(),
+                command: "npm ci".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_branch_type_color_prefers_configured_then_defaults() {
+        let mut config = Config::default();
+        assert_eq!(config.branch_type_color("feature/login"), Some("green"));
+        assert_eq!(config.branch_type_color("hotfix/crash"), Some("red"));
+        assert_eq!(config.branch_type_color("release/1.0"), Some("violet"));
+        assert_eq!(config.branch_type_color("main"), None);
+
+        config.branch_types = vec![
+            BranchType {
+                name: "feature".to_string(),
+                prefix: "feature/".to_string(),
+                color: Some("cyan".to_string()),
+                ..Default::default()
+            },
+            BranchType {
+                name: "ui".to_string(),
+                prefix: "feature/ui-".to_string(),
+                color: Some("#ff00ff".to_string()),
+                ..Default::default()
+            },
+            BranchType {
+                name: "hotfix".to_string(),
+                prefix: "hotfix/".to_string(),
+                color: None,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(config.branch_type_color("feature/login"), Some("cyan"));
+        assert_eq!(config.branch_type_color("feature/ui-nav"), Some("#ff00ff"));
+        assert_eq!(config.branch_type_color("hotfix/crash"), Some("red"));
+    }
+
+    #[test]
+    fn test_protected_branches_match_defaults_patterns_and_only_grow_from_project() {
+        let mut config = Config::parse(r#"protected_branches = ["release/*", "staging"]"#).unwrap();
+        assert!(config.is_protected_branch("main"));
+        assert!(config.is_protected_branch("develop"));
+        assert!(config.is_protected_branch("release/1.0"));
+        assert!(config.is_protected_branch("staging"));
+        assert!(!config.is_protected_branch("feature/login"));
+        assert!(!config.is_protected_branch("staging-2"));
+
+        config.merge_from_project(Config::parse(r#"protected_branches = ["qa"]"#).unwrap());
+        assert!(config.is_protected_branch("qa"));
+        assert!(config.is_protected_branch("staging"));
+
+        let config = Config::parse(r#"protected_branches = ["release/*-rc", "hotfix-?"]"#).unwrap();
+        assert!(config.is_protected_branch("release/2.0-rc"));
+        assert!(config.is_protected_branch("hotfix-1"));
+        assert!(!config.is_protected_branch("release/2.0"));
+        assert!(!config.is_protected_branch("hotfix-12"));
+    }
+
+    #[test]
+    fn test_parse_and_save_theme_table() {
+        let config = Config::parse(
+            r##"
+[theme]
+mode = "custom"
+accent = "#ff8800"
+border = "#333333"
+"##,
+        )
+        .unwrap();
+        assert_eq!(config.theme.as_deref(), Some("custom"));
+        assert_eq!(
+            config.theme_colors.get("accent").map(String::as_str),
+            Some("#ff8800")
+        );
+
+        let dir = temp_dir("config_theme");
+        let config_path = dir.join("config.toml");
+        config.save_to_project_path(&config_path).unwrap();
+        let saved = Config::parse(&fs::read_to_string(&config_path).unwrap()).unwrap();
+
+        assert_eq!(saved.theme, config.theme);
+        assert_eq!(saved.theme_colors, config.theme_colors);
+        assert_eq!(
+            Config::parse(r#"theme = "light""#)
+                .unwrap()
+                .theme
+                .as_deref(),
+            Some("light")
+        );
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_save_preserves_branch_types() {
+        let dir = temp_dir("config_branch_types");
+        let config_path = dir.join("config.toml");
+        let config = Config {
+            branch_types: vec![BranchType {
+                name: "release".to_string(),
+                prefix: "release/".to_string(),
+                color: Some("amber".to_string()),
+                ..Default::default()
+            }],
+            ..Config::default()
+        };
+
+        config.save_to_project_path(&config_path).unwrap();
+        let saved = Config::parse(&fs::read_to_string(&config_path).unwrap()).unwrap();
+
+        assert_eq!(saved.branch_types, config.branch_types);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_branch_type_name_template_fields_render_and_match() {
+        let branch_type = BranchType {
+            name: "feature".to_string(),
+            prefix: "feature/".to_string(),
+            name_template: Some("{prefix}{ticket}-{slug}".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(branch_type.template_fields(), vec!["ticket", "slug"]);
+        assert_eq!(
+            branch_type.render_name(|name| match name {
+                "ticket" => Some("ABC-123".to_string()),
+                "slug" => Some("Fix the Login page".to_string()),
+                _ => None,
+            }),
+            "feature/ABC-123-fix-the-login-page"
+        );
+        assert_eq!(branch_type.render_name(|_| None), "feature/{ticket}-{slug}");
+
+        assert!(branch_type.matches_template("feature/ABC-123-fix-login"));
+        assert!(!branch_type.matches_template("feature/login"));
+        assert!(!branch_type.matches_template("feature/ABC-"));
+        assert!(!branch_type.matches_template("feature/a/b-c"));
+        assert!(BranchType::default().matches_template("anything"));
+    }
+
+    #[test]
+    fn test_sparse_paths_per_branch_type_replace_the_global_list() {
+        let mut config = Config::parse(
+            r#"
+sparse_paths = ["libs/common"]
+
+[[branch_types]]
+name = "web"
+prefix = "web/"
+sparse_paths = ["apps/web", "libs/ui"]
+
+[[branch_types]]
+name = "release"
+prefix = "release/"
+sparse_paths = []
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.sparse_paths_for_branch("web/login"),
+            ["apps/web", "libs/ui"]
+        );
+        assert!(config.sparse_paths_for_branch("release/1.0").is_empty());
+        assert_eq!(config.sparse_paths_for_branch("fix/typo"), ["libs/common"]);
+
+        config.override_sparse_paths(vec!["docs".to_string()]);
+        assert_eq!(config.sparse_paths_for_branch("web/login"), ["docs"]);
+    }
+
+    #[test]
+    fn test_worktree_naming_maps_branches_to_folders() {
+        let mut config = Config::parse(
+            r#"
+worktree_naming = "strip-prefix"
+
+[[branch_types]]
+name = "team"
+prefix = "team/api/"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.worktree_folder("feature/login"), "login");
+        assert_eq!(config.worktree_folder("team/api/auth/v2"), "auth-v2");
+        assert_eq!(config.worktree_folder("main"), "main");
+        assert_eq!(config.worktree_folder("feature/"), "feature-");
+
+        config.worktree_naming = Some("dash".to_string());
+        assert_eq!(config.worktree_folder("feature/login"), "feature-login");
+
+        config.worktree_naming = Some("flat".to_string());
+        assert_eq!(config.get_worktree_naming(), WorktreeNaming::Nested);
+        assert_eq!(config.worktree_folder("feature/login"), "feature/login");
+    }
+
+    #[test]
+    fn test_worktree_dir_template_expands_variables() {
+        let mut config = Config::parse(
+            r#"
+worktree_dir = "../trees/{type}/{branch_slug}"
+
+[[branch_types]]
+name = "feat"
+prefix = "feature/"
+"#,
+        )
+        .unwrap();
+        let root = std::path::Path::new("/work/api");
+
+        assert_eq!(
+            config.templated_worktree_path(root, "feature/login"),
+            Some(PathBuf::from("/work/trees/feat/feature-login"))
+        );
+        assert_eq!(
+            config.templated_worktree_path(root, "fix/crash"),
+            Some(PathBuf::from("/work/trees/fix/fix-crash"))
+        );
+        assert_eq!(
+            config.templated_worktree_path(root, "main"),
+            Some(PathBuf::from("/work/trees/main"))
+        );
+
+        config.worktree_dir = Some("/tmp/{repo}/{date}-{branch}".to_string());
+        let path = config
+            .templated_worktree_path(root, "feature/login")
+            .unwrap();
+        let date = path
+            .parent()
+            .unwrap()
+            .file_name()
+            .unwrap()
+            .to_string_lossy();
+        assert!(path.starts_with("/tmp/api"));
+        assert!(path.ends_with("login"));
+        assert_eq!(date.len(), "2026-01-01-feature".len());
+
+        config.worktree_dir = None;
+        assert_eq!(config.templated_worktree_path(root, "main"), None);
+    }
+
     #[test]
     fn test_resolved_worktree_root_uses_configured_absolute_path() {
         let config = Config {
@@ -776,6 +1794,20 @@ shortcut = "f"
             };
             assert_eq!(configured.get_editor(), "code");
             assert_eq!(configured.get_terminal(), Some("WezTerm".to_string()));
+
+            let typed = Config {
+                editor: Some("code".to_string()),
+                branch_types: vec![BranchType {
+                    name: "hotfix".to_string(),
+                    prefix: "hotfix/".to_string(),
+                    editor: Some("nvim".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            };
+            assert_eq!(typed.editor_for_branch(Some("hotfix/crash")), "nvim");
+            assert_eq!(typed.editor_for_branch(Some("feature/login")), "code");
+            assert_eq!(typed.editor_for_branch(None), "code");
         }
 
         {
@@ -783,6 +1815,11 @@ shortcut = "f"
             let _terminal_guard = EnvVarGuard::unset("TERMINAL");
 
             assert_eq!(Config::default().get_editor(), "vim");
+            assert_eq!(Config::default().get_git_ui(), "lazygit");
+            assert_eq!(
+                Config::parse("git_ui = \"gitui\"").unwrap().get_git_ui(),
+                "gitui"
+            );
             assert_eq!(Config::default().get_terminal(), None);
         }
     }
@@ -0,0 +1,157 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+const BUNDLE_VERSION: u32 = 1;
+
+/// Files a bundle carries, relative to the global config directory. Only
+/// settings travel: `repos.toml` lists this machine's project paths, which
+/// are private and mean nothing elsewhere.
+const BUNDLED_FILES: &[&str] = &["config.toml"];
+
+/// The bundled files under the global config directory (config.toml with its
+/// theme and branch types), keyed by relative path with `/` separators.
+#[derive(Debug, Serialize, Deserialize)]
+struct Bundle {
+    version: u32,
+    files: BTreeMap<String, String>,
+}
+
+pub(crate) struct Export {
+    pub bundle: String,
+    pub files: Vec<String>,
+    pub skipped: Vec<String>, // Not UTF-8, so not portable as text
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImportAction {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+impl ImportAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportAction::Created => "created",
+            ImportAction::Updated => "updated",
+            ImportAction::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// Bundle the files under `config_dir` (normally `~/.config/owt`).
+pub(crate) fn export(config_dir: &Path) -> Result<Export> {
+    let mut bundle = Bundle {
+        version: BUNDLE_VERSION,
+        files: BTreeMap::new(),
+    };
+    let mut skipped = Vec::new();
+
+    for name in BUNDLED_FILES {
+        let path = config_dir.join(name);
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                bundle.files.insert(name.to_string(), content);
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) if error.kind() == std::io::ErrorKind::InvalidData => {
+                skipped.push(name.to_string())
+            }
+            Err(error) => {
+                return Err(error).with_context(|| format!("Failed to read {}", path.display()))
+            }
+        }
+    }
+
+    if bundle.files.is_empty() {
+        anyhow::bail!("No owt configuration found in {}", config_dir.display());
+    }
+    skipped.sort();
+
+    Ok(Export {
+        bundle: toml::to_string(&bundle)?,
+        files: bundle.files.keys().cloned().collect(),
+        skipped,
+    })
+}
+
+/// Write the files in `content` under `config_dir`. Files that already exist
+/// with different content are only replaced with `force`, so an import never
+/// silently drops local settings.
+pub(crate) fn import(
+    config_dir: &Path,
+    content: &str,
+    force: bool,
+) -> Result<Vec<(String, ImportAction)>> {
+    let bundle: Bundle = toml::from_str(content).context("Not an owt config bundle")?;
+    if bundle.version > BUNDLE_VERSION {
+        anyhow::bail!(
+            "Bundle version {} is newer than this owt supports ({}); upgrade owt",
+            bundle.version,
+            BUNDLE_VERSION
+        );
+    }
+
+    let mut planned = Vec::new();
+    for (name, file_content) in &bundle.files {
+        let path = bundle_path(config_dir, name)?;
+        if name == "config.toml" {
+            Config::parse(file_content).with_context(|| format!("{} in the bundle", name))?;
+        }
+        let action = match fs::read_to_string(&path) {
+            Ok(existing) if existing == *file_content => ImportAction::Unchanged,
+            Ok(_) => ImportAction::Updated,
+            Err(_) if path.exists() => ImportAction::Updated,
+            Err(_) => ImportAction::Created,
+        };
+        planned.push((name.clone(), path, action));
+    }
+
+    let conflicts: Vec<&str> = planned
+        .iter()
+        .filter(|(_, _, action)| *action == ImportAction::Updated)
+        .map(|(name, _, _)| name.as_str())
+        .collect();
+    if !force && !conflicts.is_empty() {
+        anyhow::bail!(
+            "{} file(s) differ from the bundle: {}; rerun with --force to overwrite them",
+            conflicts.len(),
+            conflicts.join(", ")
+        );
+    }
+
+    let mut results = Vec::new();
+    for (name, path, action) in planned {
+        if action != ImportAction::Unchanged {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            fs::write(&path, &bundle.files[&name])
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        results.push((name, action));
+    }
+    Ok(results)
+}
+
+/// Resolve a bundle entry under `config_dir`, refusing anything but the
+/// files an export writes.
+fn bundle_path(config_dir: &Path, name: &str) -> Result<PathBuf> {
+    if !BUNDLED_FILES.contains(&name) {
+        anyhow::bail!(
+            "Bundle entry '{}' is not an owt config file (expected {})",
+            name,
+            BUNDLED_FILES.join(", ")
+        );
+    }
+    Ok(config_dir.join(name))
+}
+
+#[cfg(test)]
+mod tests;
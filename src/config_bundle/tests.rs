@@ -0,0 +1,123 @@
+use super::*;
+use crate::test_support::temp_dir;
+
+fn export_error(config_dir: &Path) -> String {
+    match export(config_dir) {
+        Ok(_) => panic!("export should fail"),
+        Err(error) => error.to_string(),
+    }
+}
+
+#[test]
+fn export_then_import_recreates_the_config() {
+    let base = temp_dir("config_bundle_round_trip");
+    let source = base.join("source");
+    fs::create_dir_all(&source).unwrap();
+    let config = "editor = \"nvim\"\n\n[theme]\nmode = \"custom\"\naccent = \"#ff8800\"\n";
+    fs::write(source.join("config.toml"), config).unwrap();
+
+    let export = export(&source).unwrap();
+    assert_eq!(export.files, vec!["config.toml"]);
+    assert!(export.skipped.is_empty());
+
+    let target = base.join("target");
+    let results = import(&target, &export.bundle, false).unwrap();
+    assert_eq!(
+        results,
+        vec![("config.toml".to_string(), ImportAction::Created)]
+    );
+    assert_eq!(
+        fs::read_to_string(target.join("config.toml")).unwrap(),
+        config
+    );
+
+    let again = import(&target, &export.bundle, false).unwrap();
+    assert!(again
+        .iter()
+        .all(|(_, action)| *action == ImportAction::Unchanged));
+
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn export_leaves_out_the_repo_registry_and_other_files() {
+    let base = temp_dir("config_bundle_registry");
+    fs::create_dir_all(base.join("themes")).unwrap();
+    fs::write(base.join("config.toml"), "editor = \"nvim\"\n").unwrap();
+    fs::write(
+        base.join("repos.toml"),
+        "[repos]\nproject = \"/home/me/secret-client/project\"\n",
+    )
+    .unwrap();
+    fs::write(
+        base.join("themes").join("night.toml"),
+        "cyan = \"#00ffff\"\n",
+    )
+    .unwrap();
+
+    let export = export(&base).unwrap();
+    assert_eq!(export.files, vec!["config.toml"]);
+    assert!(!export.bundle.contains("repos.toml"), "{}", export.bundle);
+    assert!(
+        !export.bundle.contains("secret-client"),
+        "{}",
+        export.bundle
+    );
+
+    fs::remove_file(base.join("config.toml")).unwrap();
+    let error = export_error(&base);
+    assert!(error.contains("No owt configuration"), "{}", error);
+
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn import_refuses_to_overwrite_local_changes_without_force() {
+    let base = temp_dir("config_bundle_conflict");
+    fs::write(base.join("config.toml"), "editor = \"vim\"\n").unwrap();
+    let bundle = "version = 1\n\n[files]\n\"config.toml\" = \"editor = \\\"nvim\\\"\\n\"\n";
+
+    let error = import(&base, bundle, false).unwrap_err().to_string();
+    assert!(error.contains("config.toml"), "{}", error);
+    assert!(error.contains("--force"), "{}", error);
+    assert_eq!(
+        fs::read_to_string(base.join("config.toml")).unwrap(),
+        "editor = \"vim\"\n"
+    );
+
+    let results = import(&base, bundle, true).unwrap();
+    assert_eq!(
+        results,
+        vec![("config.toml".to_string(), ImportAction::Updated)]
+    );
+    assert_eq!(
+        fs::read_to_string(base.join("config.toml")).unwrap(),
+        "editor = \"nvim\"\n"
+    );
+
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn import_rejects_unsafe_paths_and_invalid_config() {
+    let base = temp_dir("config_bundle_unsafe");
+
+    let escape = "version = 1\n\n[files]\n\"../outside.toml\" = \"x\"\n";
+    let error = import(&base, escape, true).unwrap_err().to_string();
+    assert!(error.contains("not an owt config file"), "{}", error);
+
+    let registry = "version = 1\n\n[files]\n\"repos.toml\" = \"[repos]\\nx = \\\"/tmp/x\\\"\\n\"\n";
+    let error = import(&base, registry, true).unwrap_err().to_string();
+    assert!(error.contains("'repos.toml'"), "{}", error);
+    assert!(!base.join("repos.toml").exists());
+
+    let invalid = "version = 1\n\n[files]\n\"config.toml\" = \"copy_files = 3\"\n";
+    assert!(import(&base, invalid, true).is_err());
+    assert!(!base.join("config.toml").exists());
+
+    let future = "version = 2\n\n[files]\n";
+    let error = import(&base, future, true).unwrap_err().to_string();
+    assert!(error.contains("upgrade owt"), "{}", error);
+
+    let _ = fs::remove_dir_all(base);
+}
@@ -0,0 +1,536 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, WORKTREE_DIR_VARIABLES};
+use crate::git;
+use crate::ship::{ShipStep, DEFAULT_SHIP_STEPS};
+use crate::types::{DiscardMode, WorktreeNaming, DISCARD_MODES, WORKTREE_NAMINGS};
+use crate::ui::spinner::SPINNER_STYLES;
+use crate::ui::theme::Theme;
+
+/// Keys read from the top level of config.toml (and from a legacy `[core]`).
+const CONFIG_KEYS: &[&str] = &[
+    "editor",
+    "terminal",
+    "git_ui",
+    "worktree_root",
+    "worktree_dir",
+    "worktree_naming",
+    "copy_files",
+    "copy_source",
+    "sparse_paths",
+    "submodule_update",
+    "lfs_pull",
+    "install_command",
+    "post_add_script",
+    "tmux_worktree_mode",
+    "run_post_add_script_in_tmux",
+    "protected_branches",
+    "theme",
+    "spinner",
+    "spinner_interval_ms",
+    "show_disk_usage",
+    "show_upstream",
+    "show_head_commit",
+    "hide_unmatched",
+    "auto_refresh",
+    "live_status",
+    "auto_fetch",
+    "git_timeout",
+    "command_log",
+    "branch_types",
+    "tasks",
+    "issue_title_command",
+    "ship_steps",
+    "discard_mode",
+    "repositories",
+    "clone_host",
+];
+
+const BRANCH_TYPE_KEYS: &[&str] = &[
+    "name",
+    "prefix",
+    "color",
+    "base",
+    "shortcut",
+    "editor",
+    "name_template",
+    "sparse_paths",
+];
+
+const TASK_KEYS: &[&str] = &["name", "command"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug)]
+pub(crate) struct Finding {
+    pub severity: Severity,
+    pub source: String,
+    pub message: String,
+}
+
+/// Where the effective config is checked against: the project whose
+/// `.owt/config.toml` applies, the repo for base branches, and the worktree
+/// `copy_files` are copied from.
+pub(crate) struct CheckContext<'a> {
+    pub project_root_path: Option<&'a Path>,
+    pub repo_path: Option<&'a Path>,
+    pub copy_source: Option<&'a Path>,
+}
+
+pub(crate) struct CheckReport {
+    pub files: Vec<PathBuf>,
+    pub findings: Vec<Finding>,
+}
+
+impl CheckReport {
+    pub fn error_count(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == Severity::Error)
+            .count()
+    }
+}
+
+pub(crate) fn check_configs(context: &CheckContext) -> CheckReport {
+    let mut files = vec![Config::global_config_path()];
+    if let Some(project_root) = context.project_root_path {
+        files.push(Config::project_config_path(project_root));
+    }
+    files.retain(|path| path.exists());
+
+    let mut findings = Vec::new();
+    for path in &files {
+        let source = path.display().to_string();
+        match fs::read_to_string(path) {
+            Ok(content) => findings.extend(check_file(&source, &content)),
+            Err(error) => findings.push(error_finding(&source, error.to_string())),
+        }
+    }
+
+    // Parse errors are already reported per file above
+    if let Ok(config) = Config::load_with_project(context.project_root_path) {
+        findings.extend(check_effective(&config, context));
+    }
+
+    CheckReport { files, findings }
+}
+
+pub(crate) fn print_check_report(report: &CheckReport) {
+    for finding in &report.findings {
+        let severity = match finding.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        println!(
+            "{}\t{}\t{}",
+            severity,
+            plain_field(&finding.source),
+            plain_field(&finding.message)
+        );
+    }
+
+    if report.findings.is_empty() {
+        println!("ok\t{}", report.files.len());
+    }
+}
+
+/// Syntax, unknown keys, and branch type problems within a single file.
+fn check_file(source: &str, content: &str) -> Vec<Finding> {
+    let config = match Config::parse(content) {
+        Ok(config) => config,
+        Err(error) => return vec![error_finding(source, error.to_string())],
+    };
+    let mut findings = Vec::new();
+
+    if let Ok(table) = content.parse::<toml::Table>() {
+        check_unknown_keys(source, &table, "", &mut findings);
+        if let Some(toml::Value::Table(core)) = table.get("core") {
+            check_unknown_keys(source, core, "core.", &mut findings);
+        }
+        if let Some(toml::Value::Table(theme)) = table.get("theme") {
+            check_theme_colors(source, theme, &mut findings);
+        }
+        check_spinner(source, &config, &mut findings);
+        check_ship_steps(source, &config, &mut findings);
+        check_discard_mode(source, &config, &mut findings);
+        check_worktree_dir(source, &config, &mut findings);
+        check_worktree_naming(source, &config, &mut findings);
+        if let Some(toml::Value::Array(entries)) = table.get("branch_types") {
+            check_branch_type_entries(source, entries, &mut findings);
+        }
+        if let Some(toml::Value::Array(entries)) = table.get("tasks") {
+            check_task_entries(source, entries, &mut findings);
+        }
+    }
+
+    check_branch_types(source, &config, &mut findings);
+    findings
+}
+
+fn check_unknown_keys(
+    source: &str,
+    table: &toml::Table,
+    prefix: &str,
+    findings: &mut Vec<Finding>,
+) {
+    for key in table.keys() {
+        let known = CONFIG_KEYS.contains(&key.as_str()) || (prefix.is_empty() && key == "core");
+        if !known {
+            findings.push(warning_finding(
+                source,
+                format!("unknown key `{}{}`", prefix, key),
+            ));
+        }
+    }
+}
+
+fn check_theme_colors(source: &str, theme: &toml::Table, findings: &mut Vec<Finding>) {
+    let mut probe = Theme::dark();
+    for (name, value) in theme {
+        if name == "mode" {
+            continue;
+        }
+        let valid = value
+            .as_str()
+            .is_some_and(|value| probe.set_color(name, value));
+        if !valid {
+            findings.push(warning_finding(
+                source,
+                format!(
+                    "[theme] `{}` is not a known color name with a #rrggbb value",
+                    name
+                ),
+            ));
+        }
+    }
+}
+
+fn check_spinner(source: &str, config: &Config, findings: &mut Vec<Finding>) {
+    if let Some(name) = config.spinner.as_deref() {
+        let known = SPINNER_STYLES
+            .iter()
+            .any(|(style, _)| style.eq_ignore_ascii_case(name));
+        if !known {
+            let styles: Vec<&str> = SPINNER_STYLES.iter().map(|(style, _)| *style).collect();
+            findings.push(warning_finding(
+                source,
+                format!(
+                    "spinner `{}` is not one of {}; using dots",
+                    name,
+                    styles.join(", ")
+                ),
+            ));
+        }
+    }
+    if config.spinner_interval_ms == Some(0) {
+        findings.push(warning_finding(
+            source,
+            "spinner_interval_ms must be at least 1".to_string(),
+        ));
+    }
+}
+
+fn check_discard_mode(source: &str, config: &Config, findings: &mut Vec<Finding>) {
+    if let Some(name) = config.discard_mode.as_deref() {
+        if DiscardMode::parse(name).is_none() {
+            findings.push(warning_finding(
+                source,
+                format!(
+                    "discard_mode `{}` is not one of {}; using untracked",
+                    name,
+                    DISCARD_MODES.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+fn check_worktree_naming(source: &str, config: &Config, findings: &mut Vec<Finding>) {
+    if let Some(name) = config.worktree_naming.as_deref() {
+        if WorktreeNaming::parse(name).is_none() {
+            findings.push(warning_finding(
+                source,
+                format!(
+                    "worktree_naming `{}` is not one of {}; using nested",
+                    name,
+                    WORKTREE_NAMINGS.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+fn check_worktree_dir(source: &str, config: &Config, findings: &mut Vec<Finding>) {
+    let Some(template) = config.worktree_dir.as_deref() else {
+        return;
+    };
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + end];
+        if !WORKTREE_DIR_VARIABLES.contains(&name) {
+            findings.push(warning_finding(
+                source,
+                format!(
+                    "worktree_dir variable `{{{}}}` is not one of {}; it is kept as written",
+                    name,
+                    WORKTREE_DIR_VARIABLES.join(", ")
+                ),
+            ));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    if !template.contains("{branch}") && !template.contains("{branch_slug}") {
+        findings.push(warning_finding(
+            source,
+            "worktree_dir has no {branch} or {branch_slug}; every worktree would get the same path"
+                .to_string(),
+        ));
+    }
+}
+
+fn check_ship_steps(source: &str, config: &Config, findings: &mut Vec<Finding>) {
+    for name in &config.ship_steps {
+        if ShipStep::parse(name).is_none() {
+            findings.push(warning_finding(
+                source,
+                format!(
+                    "ship step `{}` is not one of {}; it is skipped",
+                    name,
+                    DEFAULT_SHIP_STEPS.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+fn check_branch_type_entries(source: &str, entries: &[toml::Value], findings: &mut Vec<Finding>) {
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(entry) = entry.as_table() else {
+            continue;
+        };
+        for key in entry.keys() {
+            if !BRANCH_TYPE_KEYS.contains(&key.as_str()) {
+                findings.push(warning_finding(
+                    source,
+                    format!("unknown key `branch_types[{}].{}`", index, key),
+                ));
+            }
+        }
+        let has_prefix = entry
+            .get("prefix")
+            .and_then(toml::Value::as_str)
+            .is_some_and(|prefix| !prefix.is_empty());
+        if !has_prefix {
+            findings.push(error_finding(
+                source,
+                format!("branch_types[{}] has no prefix and is ignored", index),
+            ));
+        }
+    }
+}
+
+fn check_task_entries(source: &str, entries: &[toml::Value], findings: &mut Vec<Finding>) {
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(entry) = entry.as_table() else {
+            continue;
+        };
+        for key in entry.keys() {
+            if !TASK_KEYS.contains(&key.as_str()) {
+                findings.push(warning_finding(
+                    source,
+                    format!("unknown key `tasks[{}].{}`", index, key),
+                ));
+            }
+        }
+        let has_command = entry
+            .get("command")
+            .and_then(toml::Value::as_str)
+            .is_some_and(|command| !command.trim().is_empty());
+        if !has_command {
+            findings.push(error_finding(
+                source,
+                format!("tasks[{}] has no command and is ignored", index),
+            ));
+        }
+    }
+}
+
+fn check_branch_types(source: &str, config: &Config, findings: &mut Vec<Finding>) {
+    let probe = Theme::dark();
+    let mut prefixes: HashMap<&str, &str> = HashMap::new();
+    let mut shortcuts: HashMap<&str, &str> = HashMap::new();
+
+    for branch_type in &config.branch_types {
+        let name = if branch_type.name.is_empty() {
+            branch_type.prefix.as_str()
+        } else {
+            branch_type.name.as_str()
+        };
+
+        if let Some(other) = prefixes.insert(&branch_type.prefix, name) {
+            findings.push(warning_finding(
+                source,
+                format!(
+                    "branch types `{}` and `{}` share prefix `{}`",
+                    other, name, branch_type.prefix
+                ),
+            ));
+        }
+
+        if let Some(ref shortcut) = branch_type.shortcut {
+            if shortcut.chars().count() != 1 {
+                findings.push(error_finding(
+                    source,
+                    format!(
+                        "branch type `{}` shortcut `{}` must be a single character",
+                        name, shortcut
+                    ),
+                ));
+            } else if let Some(other) = shortcuts.insert(shortcut, name) {
+                findings.push(error_finding(
+                    source,
+                    format!(
+                        "branch types `{}` and `{}` share shortcut `{}`",
+                        other, name, shortcut
+                    ),
+                ));
+            }
+        }
+
+        if let Some(ref color) = branch_type.color {
+            if probe.color_from_spec(color).is_none() {
+                findings.push(warning_finding(
+                    source,
+                    format!(
+                        "branch type `{}` color `{}` is not a palette name or #rrggbb",
+                        name, color
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Checks against the filesystem and repository, on the merged config.
+fn check_effective(config: &Config, context: &CheckContext) -> Vec<Finding> {
+    let source = "effective";
+    let mut findings = Vec::new();
+
+    if let Some(repo_path) = context.repo_path {
+        for branch_type in &config.branch_types {
+            let Some(ref base) = branch_type.base else {
+                continue;
+            };
+            if !git::branch_exists_anywhere(repo_path, base) {
+                findings.push(warning_finding(
+                    source,
+                    format!(
+                        "branch type `{}` base `{}` does not exist locally or on any remote",
+                        branch_type.name, base
+                    ),
+                ));
+            }
+        }
+    }
+
+    if let Some(copy_source) = context.copy_source {
+        for file in &config.copy_files {
+            let path = copy_source.join(file);
+            if !path.is_file() {
+                findings.push(warning_finding(
+                    source,
+                    format!("copy_files source {} is missing", path.display()),
+                ));
+            }
+        }
+    }
+
+    if let Some(project_root) = context.project_root_path {
+        findings.extend(check_post_add_script(config, project_root));
+    }
+
+    findings
+}
+
+fn check_post_add_script(config: &Config, project_root: &Path) -> Vec<Finding> {
+    let source = "effective";
+    let script = config.resolved_post_add_script_path(project_root);
+
+    if !script.exists() {
+        let expected = config.post_add_script.is_some() || config.run_post_add_script_in_tmux;
+        return if expected {
+            vec![warning_finding(
+                source,
+                format!("post-add script {} does not exist", script.display()),
+            )]
+        } else {
+            Vec::new()
+        };
+    }
+
+    if !script.is_file() {
+        return vec![error_finding(
+            source,
+            format!("post-add script {} is not a file", script.display()),
+        )];
+    }
+
+    if !is_executable(&script) {
+        return vec![warning_finding(
+            source,
+            format!(
+                "post-add script {} is not executable; run chmod +x {}",
+                script.display(),
+                script.display()
+            ),
+        )];
+    }
+
+    Vec::new()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path)
+        .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+fn error_finding(source: &str, message: String) -> Finding {
+    Finding {
+        severity: Severity::Error,
+        source: source.to_string(),
+        message,
+    }
+}
+
+fn warning_finding(source: &str, message: String) -> Finding {
+    Finding {
+        severity: Severity::Warning,
+        source: source.to_string(),
+        message,
+    }
+}
+
+fn plain_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,148 @@
+use super::*;
+use crate::test_support::temp_dir;
+
+fn messages(findings: &[Finding], severity: Severity) -> Vec<&str> {
+    findings
+        .iter()
+        .filter(|finding| finding.severity == severity)
+        .map(|finding| finding.message.as_str())
+        .collect()
+}
+
+#[test]
+fn check_file_reports_parse_errors_with_line_numbers() {
+    let findings = check_file("config.toml", "editor = \"vim\"\ncopy_files = \".env\"\n");
+
+    let errors = messages(&findings, Severity::Error);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("line 2, column 14:"), "{}", errors[0]);
+}
+
+#[test]
+fn check_file_warns_about_unknown_keys_and_theme_colors() {
+    let content = r##"
+edtor = "vim"
+spinner = "spiral"
+worktree_dir = "../trees/{repo}/{kind}"
+ship_steps = ["push", "deploy"]
+
+[core]
+terminl = "Ghostty"
+
+[theme]
+mode = "custom"
+accent = "#ff8800"
+acent = "#ff8800"
+border = "grey"
+"##;
+    let findings = check_file("config.toml", content);
+
+    let warnings = messages(&findings, Severity::Warning);
+    assert!(warnings.contains(&"unknown key `edtor`"));
+    assert!(warnings.contains(&"unknown key `core.terminl`"));
+    assert!(warnings.iter().any(|message| message.contains("`acent`")));
+    assert!(warnings.iter().any(|message| message.contains("`border`")));
+    assert!(!warnings.iter().any(|message| message.contains("`accent`")));
+    assert!(warnings
+        .iter()
+        .any(|message| message.starts_with("spinner `spiral`")));
+    assert!(warnings
+        .iter()
+        .any(|message| message.starts_with("ship step `deploy`")));
+    assert!(!warnings.iter().any(|message| message.contains("`push`")));
+    assert!(warnings
+        .iter()
+        .any(|message| message.starts_with("worktree_dir variable `{kind}`")));
+    assert!(!warnings.iter().any(|message| message.contains("`{repo}`")));
+    assert!(warnings
+        .iter()
+        .any(|message| message.starts_with("worktree_dir has no {branch}")));
+    assert!(messages(&findings, Severity::Error).is_empty());
+}
+
+#[test]
+fn check_file_flags_invalid_branch_types() {
+    let content = r#"
+[[branch_types]]
+name = "feature"
+prefix = "feature/"
+shortcut = "f"
+color = "chartreuse"
+
+[[branch_types]]
+name = "fix"
+prefix = "fix/"
+shortcut = "f"
+owner = "me"
+
+[[branch_types]]
+name = "broken"
+
+[[tasks]]
+name = "test"
+cmd = "cargo test"
+"#;
+    let findings = check_file("config.toml", content);
+
+    let errors = messages(&findings, Severity::Error);
+    assert!(errors.contains(&"branch types `feature` and `fix` share shortcut `f`"));
+    assert!(errors.contains(&"branch_types[2] has no prefix and is ignored"));
+    assert!(errors.contains(&"tasks[0] has no command and is ignored"));
+    let warnings = messages(&findings, Severity::Warning);
+    assert!(warnings.contains(&"unknown key `branch_types[1].owner`"));
+    assert!(warnings.contains(&"unknown key `tasks[0].cmd`"));
+    assert!(warnings
+        .iter()
+        .any(|message| message.contains("color `chartreuse`")));
+}
+
+#[test]
+fn check_effective_reports_missing_copy_files_and_script_problems() {
+    let dir = temp_dir("config_check_effective");
+    fs::write(dir.join(".env"), "KEY=1\n").unwrap();
+    let script = dir.join("setup.sh");
+    fs::write(&script, "#!/bin/sh\n").unwrap();
+    let mut config = Config::default();
+    config.copy_files = vec![".env".to_string(), ".env.local".to_string()];
+    config.post_add_script = Some("setup.sh".to_string());
+    let context = CheckContext {
+        project_root_path: Some(&dir),
+        repo_path: None,
+        copy_source: Some(&dir),
+    };
+
+    let findings = check_effective(&config, &context);
+    let warnings = messages(&findings, Severity::Warning);
+
+    assert!(warnings[0].contains(".env.local"), "{:?}", warnings);
+    #[cfg(unix)]
+    assert_eq!(
+        &warnings[1..],
+        [format!(
+            "post-add script {} is not executable; run chmod +x {}",
+            script.display(),
+            script.display()
+        )]
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(check_post_add_script(&config, &dir).is_empty());
+    }
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn check_post_add_script_ignores_missing_default_helper() {
+    let dir = temp_dir("config_check_default_script");
+
+    assert!(check_post_add_script(&Config::default(), &dir).is_empty());
+    let mut configured = Config::default();
+    configured.post_add_script = Some("missing.sh".to_string());
+    assert_eq!(check_post_add_script(&configured, &dir).len(), 1);
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git;
+
+/// What `owt convert` will do to turn a regular repository into the `.bare`
+/// layout, worked out up front so `--dry-run` and the safety checks see the
+/// same plan.
+#[derive(Debug)]
+pub(crate) struct ConvertPlan {
+    pub root: PathBuf,
+    pub git_dir: PathBuf,
+    pub bare_path: PathBuf,
+    pub branch: String,
+    pub worktree_path: PathBuf,
+    pub entries: Vec<PathBuf>, // Everything in the root except .git, moved into the worktree
+    pub linked_worktrees: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step {
+    MoveGitDir,
+    SetBare,
+    AddWorktree,
+    MoveEntries,
+    RepairWorktrees,
+}
+
+impl ConvertPlan {
+    pub fn steps(&self) -> Vec<Step> {
+        let mut steps = vec![
+            Step::MoveGitDir,
+            Step::SetBare,
+            Step::AddWorktree,
+            Step::MoveEntries,
+        ];
+        if !self.linked_worktrees.is_empty() {
+            steps.push(Step::RepairWorktrees);
+        }
+        steps
+    }
+
+    pub fn describe(&self, step: Step) -> String {
+        match step {
+            Step::MoveGitDir => format!(
+                "move {} to {}",
+                self.git_dir.display(),
+                self.bare_path.display()
+            ),
+            Step::SetBare => format!("git config core.bare true in {}", self.bare_path.display()),
+            Step::AddWorktree => format!(
+                "register worktree {} for {}",
+                self.worktree_path.display(),
+                self.branch
+            ),
+            Step::MoveEntries => format!(
+                "move {} file(s) and folder(s) from {} into {}",
+                self.entries.len(),
+                self.root.display(),
+                self.worktree_path.display()
+            ),
+            Step::RepairWorktrees => format!(
+                "repair gitdir pointers of {} linked worktree(s)",
+                self.linked_worktrees.len()
+            ),
+        }
+    }
+
+    fn run_step(&self, step: Step) -> Result<()> {
+        match step {
+            Step::MoveGitDir => fs::rename(&self.git_dir, &self.bare_path)
+                .with_context(|| format!("Failed to move {}", self.git_dir.display())),
+            Step::SetBare => git::set_core_bare(&self.bare_path),
+            Step::AddWorktree => git::add_worktree_without_checkout(
+                &self.bare_path,
+                &self.branch,
+                &self.worktree_path,
+            ),
+            Step::MoveEntries => {
+                for entry in &self.entries {
+                    let name = entry.file_name().context("Unexpected path in root")?;
+                    fs::rename(entry, self.worktree_path.join(name))
+                        .with_context(|| format!("Failed to move {}", entry.display()))?;
+                }
+                Ok(())
+            }
+            Step::RepairWorktrees => {
+                git::repair_worktrees(&self.bare_path, &self.linked_worktrees).map(|_| ())
+            }
+        }
+    }
+}
+
+/// Check that the repository at `path` can be converted safely and plan it.
+pub(crate) fn plan(path: &Path) -> Result<ConvertPlan> {
+    if !git::is_git_repo(path) {
+        anyhow::bail!("Not a git repository");
+    }
+    let common_dir = git::get_git_common_dir(path)?;
+    if git::is_bare_repo(&common_dir)? {
+        anyhow::bail!(
+            "{} is already a bare repository; nothing to convert",
+            common_dir.display()
+        );
+    }
+
+    let root = git::get_worktree_root(path)?;
+    let git_dir = root.join(".git");
+    let is_main_worktree = git_dir.is_dir()
+        && git_dir
+            .canonicalize()
+            .is_ok_and(|git_dir| git_dir == common_dir);
+    if !is_main_worktree {
+        anyhow::bail!(
+            "{} is not the main working tree of {}; run owt convert from the repository itself",
+            root.display(),
+            common_dir.display()
+        );
+    }
+
+    let Some(branch) = git::current_branch(&root) else {
+        anyhow::bail!("HEAD is detached; check out the branch the first worktree should use");
+    };
+    let summary = git::get_status_summary(&root)?;
+    if !summary.ends_with("· clean") {
+        anyhow::bail!(
+            "{}; commit or stash changes (including untracked files) before converting",
+            summary
+        );
+    }
+    if git_dir.join("modules").exists() {
+        anyhow::bail!(
+            "Repositories with submodules cannot be converted automatically; \
+             see owt init for the manual steps"
+        );
+    }
+
+    let bare_path = root.join(".bare");
+    if bare_path.exists() {
+        anyhow::bail!("{} already exists", bare_path.display());
+    }
+    let worktree_path = root.join(&branch);
+    let first_component = branch.split('/').next().unwrap_or(&branch);
+    if root.join(first_component).exists() {
+        anyhow::bail!(
+            "{} already exists in the repository, so the {} worktree cannot go there",
+            root.join(first_component).display(),
+            branch
+        );
+    }
+
+    let mut entries = Vec::new();
+    for entry in
+        fs::read_dir(&root).with_context(|| format!("Failed to read {}", root.display()))?
+    {
+        let entry = entry?;
+        if entry.file_name() != ".git" {
+            entries.push(entry.path());
+        }
+    }
+    entries.sort();
+
+    let linked_worktrees = git::list_worktrees(&root)?
+        .into_iter()
+        .filter(|wt| !wt.is_bare && wt.path.canonicalize().ok().as_deref() != Some(&root))
+        .map(|wt| wt.path)
+        .collect();
+
+    Ok(ConvertPlan {
+        root,
+        git_dir,
+        bare_path,
+        branch,
+        worktree_path,
+        entries,
+        linked_worktrees,
+    })
+}
+
+/// Run every step of `plan`, reporting each as it completes. A failure names
+/// the steps that are left so the conversion can be finished by hand.
+pub(crate) fn convert(plan: &ConvertPlan, mut on_step: impl FnMut(&str)) -> Result<()> {
+    let steps = plan.steps();
+    for (index, step) in steps.iter().enumerate() {
+        if let Err(error) = plan.run_step(*step) {
+            let remaining: Vec<String> = steps[index..]
+                .iter()
+                .map(|step| plan.describe(*step))
+                .collect();
+            return Err(error.context(format!(
+                "Conversion stopped; remaining steps: {}",
+                remaining.join("; ")
+            )));
+        }
+        on_step(&plan.describe(*step));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
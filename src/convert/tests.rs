@@ -0,0 +1,94 @@
+use super::*;
+use crate::test_support::{create_test_regular_repo, git_in, temp_dir};
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = git_in(dir, args);
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// A one-commit repository with a tracked `src` folder and an ignored `.env`;
+/// returns its branch
+fn create_regular_repo(root: &Path) -> String {
+    let branch = create_test_regular_repo(root);
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src").join("lib.rs"), "\n").unwrap();
+    fs::write(root.join(".gitignore"), ".env\n").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-m", "src"]);
+    branch
+}
+
+#[test]
+fn convert_moves_files_into_a_worktree_and_repairs_linked_ones() {
+    let base = temp_dir("convert_layout").canonicalize().unwrap();
+    let root = base.join("project");
+    let branch = create_regular_repo(&root);
+    fs::write(root.join(".env"), "SECRET=1\n").unwrap();
+    let linked = base.join("linked");
+    git(
+        &root,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            &linked.to_string_lossy(),
+        ],
+    );
+
+    let plan = plan(&root).unwrap();
+    assert_eq!(plan.branch, branch);
+    assert_eq!(plan.linked_worktrees, vec![linked.clone()]);
+    assert_eq!(plan.steps().last(), Some(&Step::RepairWorktrees));
+
+    let mut done = Vec::new();
+    convert(&plan, |step| done.push(step.to_string())).unwrap();
+    assert_eq!(done.len(), 5);
+
+    let main = root.join(&branch);
+    assert!(git::is_bare_repo(&root.join(".bare")).unwrap());
+    assert!(!root.join(".git").exists());
+    assert!(!root.join("README.md").exists());
+    assert_eq!(
+        fs::read_to_string(main.join(".env")).unwrap(),
+        "SECRET=1\n",
+        "ignored files move with the worktree"
+    );
+    assert_eq!(git(&main, &["status", "--porcelain"]), "");
+    assert_eq!(git(&main, &["rev-parse", "--abbrev-ref", "HEAD"]), branch);
+    assert_eq!(git(&linked, &["status", "--porcelain"]), "");
+    assert_eq!(
+        git::get_git_common_dir(&linked).unwrap(),
+        root.join(".bare")
+    );
+
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn plan_refuses_unsafe_repositories_without_changing_them() {
+    let base = temp_dir("convert_unsafe").canonicalize().unwrap();
+    let root = base.join("project");
+    let branch = create_regular_repo(&root);
+
+    fs::write(root.join("notes.txt"), "draft\n").unwrap();
+    let error = plan(&root).unwrap_err().to_string();
+    assert!(error.contains("commit or stash"), "{}", error);
+    fs::remove_file(root.join("notes.txt")).unwrap();
+
+    fs::create_dir_all(root.join(&branch)).unwrap();
+    fs::write(root.join(&branch).join("x"), "x\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "main folder"]);
+    let error = plan(&root).unwrap_err().to_string();
+    assert!(error.contains("already exists"), "{}", error);
+    assert!(root.join(".git").is_dir());
+
+    let _ = fs::remove_dir_all(base);
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Total size of the files under `path` in bytes. Symlinks are counted as
+/// links rather than followed, and unreadable entries are skipped, so the
+/// result is a lower bound rather than an error.
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending: Vec<PathBuf> = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                total += metadata.len();
+            }
+        }
+    }
+    total
+}
+
+/// Compact size for a table cell: `512B`, `9.5K`, `120M`, `4.2G`
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    if value < 10.0 {
+        format!("{:.1}{}", value, unit)
+    } else {
+        format!("{:.0}{}", value, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn dir_size_sums_nested_files_and_format_size_picks_a_unit() {
+        let base = temp_dir("disk_usage");
+        fs::create_dir_all(base.join("node_modules/pkg")).unwrap();
+        fs::write(base.join("README.md"), vec![b'x'; 100]).unwrap();
+        fs::write(base.join("node_modules/pkg/index.js"), vec![b'y'; 2000]).unwrap();
+
+        assert_eq!(dir_size(&base), 2100);
+        assert_eq!(dir_size(&base.join("missing")), 0);
+
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(2100), "2.1K");
+        assert_eq!(format_size(120 * 1024 * 1024), "120M");
+        assert_eq!(format_size(4_500_000_000), "4.2G");
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
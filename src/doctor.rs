@@ -0,0 +1,368 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::journal::{self, JournalEntry, JournalOp};
+use crate::{git, worktree_repair, RepositoryContext};
+
+/// Oldest git with `git worktree move`/`remove`, which owt relies on.
+const MIN_GIT_VERSION: (u32, u32) = (2, 17);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug)]
+pub(crate) struct Check {
+    pub status: Status,
+    pub name: &'static str,
+    pub detail: String,
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status: Status::Ok,
+            name,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            status: Status::Warning,
+            name,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn error(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            status: Status::Error,
+            name,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+pub(crate) fn run_checks(path: &Path) -> Vec<Check> {
+    let mut checks = vec![check_git_version(git::git_version().as_deref())];
+
+    match crate::resolve_repository_context(path) {
+        Ok(context) => {
+            checks.extend(check_layout(&context));
+            checks.push(check_stale_worktrees(&context.repo_path));
+            checks.push(check_worktree_links(&context));
+            checks.extend(check_journal(&context.project_root_path));
+        }
+        Err(_) => checks.push(Check::warning(
+            "repository",
+            format!("{} is not inside a git repository", path.display()),
+            "run owt doctor from a repository to check its layout and worktrees",
+        )),
+    }
+
+    checks.push(check_shell_integration());
+    checks.push(check_tty());
+    checks.push(check_clipboard());
+    checks
+}
+
+pub(crate) fn print_checks(checks: &[Check]) {
+    for check in checks {
+        let status = match check.status {
+            Status::Ok => "ok",
+            Status::Warning => "warning",
+            Status::Error => "error",
+        };
+        println!("{}\t{}\t{}", status, check.name, plain_field(&check.detail));
+        if let Some(ref fix) = check.fix {
+            println!("fix\t{}\t{}", check.name, plain_field(fix));
+        }
+    }
+}
+
+fn check_git_version(version_output: Option<&str>) -> Check {
+    let Some(output) = version_output else {
+        return Check::error(
+            "git",
+            "git was not found on PATH",
+            "install git 2.17 or newer",
+        );
+    };
+
+    match parse_git_version(output) {
+        Some(version) if version >= MIN_GIT_VERSION => Check::ok("git", output),
+        Some(_) => Check::error(
+            "git",
+            format!("{} is too old for worktree move/remove", output),
+            "upgrade git to 2.17 or newer",
+        ),
+        None => Check::warning(
+            "git",
+            format!("could not read the git version from '{}'", output),
+            "make sure `git --version` works",
+        ),
+    }
+}
+
+/// `git version 2.39.5 (Apple Git-154)` -> (2, 39)
+fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+    let version = output
+        .strip_prefix("git version ")?
+        .split_whitespace()
+        .next()?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+fn check_layout(context: &RepositoryContext) -> Vec<Check> {
+    if !context.repo_is_bare {
+        return vec![Check::ok(
+            "layout",
+            format!(
+                "regular repository at {}",
+                context.project_root_path.display()
+            ),
+        )];
+    }
+
+    let mut checks = vec![Check::ok(
+        "layout",
+        format!("bare repository at {}", context.repo_path.display()),
+    )];
+    // `git clone --bare` sets no fetch refspec, so fetch never updates remote branches
+    for remote in git::list_remotes(&context.repo_path) {
+        if git::remote_fetch_refspec(&context.repo_path, &remote).is_none() {
+            checks.push(Check::warning(
+                "layout",
+                format!("remote '{}' has no fetch refspec", remote),
+                format!(
+                    "git -C {} config remote.{}.fetch '+refs/heads/*:refs/remotes/{}/*'",
+                    shell_arg(&context.repo_path),
+                    remote,
+                    remote
+                ),
+            ));
+        }
+    }
+    checks
+}
+
+fn check_stale_worktrees(repo_path: &Path) -> Check {
+    match git::preview_prune_worktrees(repo_path) {
+        Ok(output) if output.is_empty() => {
+            Check::ok("worktrees", "no stale worktree administrative files")
+        }
+        Ok(output) => Check::warning(
+            "worktrees",
+            format!(
+                "stale administrative files: {}",
+                output.lines().collect::<Vec<_>>().join("; ")
+            ),
+            "owt worktree prune",
+        ),
+        Err(error) => Check::warning(
+            "worktrees",
+            error.to_string(),
+            format!("git -C {} worktree prune -v", shell_arg(repo_path)),
+        ),
+    }
+}
+
+fn check_worktree_links(context: &RepositoryContext) -> Check {
+    let broken = worktree_repair::broken_links(&context.repo_path, &context.project_root_path);
+    if broken.is_empty() {
+        return Check::ok(
+            "links",
+            "every worktree and the repository point at each other",
+        );
+    }
+    Check::warning(
+        "links",
+        format!(
+            "broken gitdir links (moved on disk?): {}",
+            broken
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join("; ")
+        ),
+        "owt doctor --repair",
+    )
+}
+
+fn check_journal(project_root_path: &Path) -> Vec<Check> {
+    let entries = match journal::pending(project_root_path) {
+        Ok(entries) => entries,
+        Err(error) => {
+            return vec![Check::warning(
+                "journal",
+                error.to_string(),
+                format!(
+                    "inspect {}",
+                    journal::journal_path(project_root_path).display()
+                ),
+            )]
+        }
+    };
+
+    if entries.is_empty() {
+        return vec![Check::ok("journal", "no unfinished operations")];
+    }
+
+    let mut checks: Vec<Check> = entries.iter().map(journal_check).collect();
+    checks.push(Check::warning(
+        "journal",
+        format!(
+            "{} unfinished operation(s) from an interrupted run",
+            entries.len()
+        ),
+        "after finishing or undoing them, run owt doctor --clear-journal",
+    ));
+    checks
+}
+
+/// What is left of an interrupted operation, and how to undo it.
+fn journal_check(entry: &JournalEntry) -> Check {
+    let target = entry
+        .branch
+        .clone()
+        .unwrap_or_else(|| entry.worktree_path.display().to_string());
+    let detail = format!(
+        "{} of {} was interrupted; planned: {}",
+        entry.op.label(),
+        target,
+        entry.steps.join("; ")
+    );
+
+    let fix = match (entry.op, entry.branch.as_deref(), &entry.previous_commit) {
+        (JournalOp::Delete, _, _) if entry.worktree_path.exists() => format!(
+            "finish with: {}",
+            entry.steps.first().cloned().unwrap_or_default()
+        ),
+        (JournalOp::Delete, Some(branch), Some(commit)) => format!(
+            "worktree is gone; to restore it: git worktree add {} {} (after git branch {} {} if the branch was deleted)",
+            shell_arg(&entry.worktree_path),
+            branch,
+            branch,
+            commit
+        ),
+        (JournalOp::Archive, _, _) if entry.worktree_path.exists() => format!(
+            "finish with: {}, or drop the matching entry from .owt/archive.toml",
+            entry.steps.first().cloned().unwrap_or_default()
+        ),
+        (JournalOp::Archive, Some(_), _) => {
+            "worktree is gone and its branch is kept; restore it from the archive with K".to_string()
+        }
+        (JournalOp::ForcePush, Some(branch), Some(commit)) => format!(
+            "check the remote branch; to restore the overwritten commit: git -C {} push --force-with-lease <remote> {}:{}",
+            shell_arg(&entry.worktree_path),
+            commit,
+            branch
+        ),
+        _ => "check the state of the worktree and branch by hand".to_string(),
+    };
+    Check::warning("journal", detail, fix)
+}
+
+fn check_shell_integration() -> Check {
+    if env::var_os("OWT_OUTPUT_FILE").is_some() {
+        return Check::ok("shell", "shell integration is active");
+    }
+
+    let (shell_name, config_file) = crate::shell_config_file();
+    let installed = config_file
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .is_some_and(|content| crate::has_shell_function(&content));
+
+    match config_file {
+        Some(path) if installed => Check::warning(
+            "shell",
+            format!("shell integration is in {} but not loaded", path.display()),
+            format!("source {}", path.display()),
+        ),
+        _ => Check::warning(
+            "shell",
+            format!(
+                "shell integration is not installed ({}); Enter cannot change directory",
+                shell_name
+            ),
+            "owt setup",
+        ),
+    }
+}
+
+#[cfg(unix)]
+fn check_tty() -> Check {
+    match std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+    {
+        Ok(_) => Check::ok("tty", "/dev/tty is readable and writable"),
+        Err(error) => Check::error(
+            "tty",
+            format!("cannot open /dev/tty: {}", error),
+            "run owt from an interactive terminal; scripts can use the plain CLI commands",
+        ),
+    }
+}
+
+#[cfg(not(unix))]
+fn check_tty() -> Check {
+    Check::ok("tty", "not checked on this platform")
+}
+
+fn check_clipboard() -> Check {
+    let candidates: &[&str] = if cfg!(target_os = "macos") {
+        &["pbcopy"]
+    } else if cfg!(target_os = "linux") {
+        &["xclip", "xsel"]
+    } else {
+        &[]
+    };
+
+    match candidates.iter().find(|tool| find_on_path(tool).is_some()) {
+        Some(tool) => Check::ok("clipboard", format!("{} is available", tool)),
+        None if candidates.is_empty() => Check::warning(
+            "clipboard",
+            "copying paths is not supported on this platform",
+            "copy paths from `owt worktree list` instead",
+        ),
+        None => Check::warning(
+            "clipboard",
+            format!(
+                "none of {} is on PATH; y cannot copy paths",
+                candidates.join(", ")
+            ),
+            format!("install {}", candidates.join(" or ")),
+        ),
+    }
+}
+
+fn find_on_path(program: &str) -> Option<PathBuf> {
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+fn shell_arg(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+fn plain_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests;
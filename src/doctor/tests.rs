@@ -0,0 +1,68 @@
+use super::*;
+
+#[test]
+fn git_version_parses_vendor_suffixes() {
+    assert_eq!(
+        parse_git_version("git version 2.39.5 (Apple Git-154)"),
+        Some((2, 39))
+    );
+    assert_eq!(
+        parse_git_version("git version 2.45.1.windows.1"),
+        Some((2, 45))
+    );
+    assert_eq!(parse_git_version("version 2"), None);
+}
+
+#[test]
+fn old_or_missing_git_is_an_error() {
+    assert_eq!(
+        check_git_version(Some("git version 2.43.0")).status,
+        Status::Ok
+    );
+    assert_eq!(
+        check_git_version(Some("git version 2.17.0")).status,
+        Status::Ok
+    );
+
+    let old = check_git_version(Some("git version 2.16.6"));
+    assert_eq!(old.status, Status::Error);
+    assert_eq!(old.fix.as_deref(), Some("upgrade git to 2.17 or newer"));
+
+    assert_eq!(check_git_version(None).status, Status::Error);
+}
+
+#[test]
+fn interrupted_delete_suggests_restoring_the_branch() {
+    let entry = JournalEntry::new(JournalOp::Delete, Path::new("/nonexistent/owt/feature"))
+        .branch(Some("feature/login"))
+        .previous_commit(Some("abc123".to_string()))
+        .step("git worktree remove /nonexistent/owt/feature")
+        .step("git branch -d feature/login");
+
+    let check = journal_check(&entry);
+
+    assert_eq!(check.status, Status::Warning);
+    assert!(check
+        .detail
+        .starts_with("delete of feature/login was interrupted"));
+    let fix = check.fix.unwrap();
+    assert!(fix.contains("git branch feature/login abc123"), "{}", fix);
+    assert!(
+        fix.contains("git worktree add '/nonexistent/owt/feature' feature/login"),
+        "{}",
+        fix
+    );
+}
+
+#[test]
+fn interrupted_delete_with_worktree_left_suggests_finishing() {
+    let dir = std::env::temp_dir();
+    let entry = JournalEntry::new(JournalOp::Delete, &dir).step("git worktree remove x");
+
+    let check = journal_check(&entry);
+
+    assert_eq!(
+        check.fix.as_deref(),
+        Some("finish with: git worktree remove x")
+    );
+}
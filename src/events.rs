@@ -0,0 +1,225 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::types::{ActiveOp, OpKind, Worktree};
+
+/// Newline-delimited JSON state events for `--events` (statusbars, dashboards).
+/// Write failures disable the sink instead of interrupting the TUI.
+pub struct EventSink {
+    writer: Option<Box<dyn Write>>,
+    last_mode: Option<&'static str>,
+    last_selection: Option<PathBuf>,
+    op_running: bool,
+}
+
+impl EventSink {
+    /// `target` is an inherited file descriptor number (Unix) or a file/FIFO path.
+    pub fn open(target: &str) -> Result<Self> {
+        Ok(Self::new(Box::new(open_target(target)?)))
+    }
+
+    fn new(writer: Box<dyn Write>) -> Self {
+        Self {
+            writer: Some(writer),
+            last_mode: None,
+            last_selection: None,
+            op_running: false,
+        }
+    }
+
+    pub fn emit(&mut self, event: &str, fields: Value) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+
+        let mut record = json!({ "event": event });
+        if let (Some(record), Value::Object(fields)) = (record.as_object_mut(), fields) {
+            record.extend(fields);
+        }
+
+        if writeln!(writer, "{}", record)
+            .and_then(|_| writer.flush())
+            .is_err()
+        {
+            self.writer = None;
+        }
+    }
+
+    pub fn observe_mode(&mut self, mode: &'static str) {
+        if self.last_mode != Some(mode) {
+            self.last_mode = Some(mode);
+            self.emit("mode", json!({ "mode": mode }));
+        }
+    }
+
+    pub fn observe_selection(&mut self, worktree: Option<&Worktree>) {
+        let path = worktree.map(|wt| wt.path.clone());
+        if self.last_selection == path {
+            return;
+        }
+        self.last_selection = path;
+        self.emit(
+            "selection",
+            json!({
+                "name": worktree.map(Worktree::display_name),
+                "branch": worktree.and_then(|wt| wt.branch.clone()),
+                "path": worktree.map(|wt| wt.path.display().to_string()),
+            }),
+        );
+    }
+
+    pub fn observe_op(&mut self, op: Option<&ActiveOp>) {
+        match op {
+            Some(op) if !self.op_running => {
+                self.op_running = true;
+                self.emit(
+                    "op_started",
+                    json!({
+                        "kind": op.kind.label(),
+                        "name": op.display_name,
+                        "paths": op
+                            .worktree_paths
+                            .iter()
+                            .map(|path| path.display().to_string())
+                            .collect::<Vec<_>>(),
+                    }),
+                );
+            }
+            Some(_) => {}
+            None => self.op_running = false,
+        }
+    }
+
+    pub fn op_finished(&mut self, kind: &OpKind, success: bool, message: &str) {
+        self.op_running = false;
+        self.emit(
+            "op_finished",
+            json!({ "kind": kind.label(), "success": success, "message": message }),
+        );
+    }
+}
+
+#[cfg(unix)]
+fn open_target(target: &str) -> Result<File> {
+    use std::os::unix::io::FromRawFd;
+
+    if let Ok(fd) = target.parse::<i32>() {
+        if fd <= 2 {
+            anyhow::bail!("--events needs a descriptor other than stdin/stdout/stderr");
+        }
+        // SAFETY: fcntl only checks that the descriptor is open before we take ownership.
+        if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+            anyhow::bail!("File descriptor {} is not open", fd);
+        }
+        return Ok(unsafe { File::from_raw_fd(fd) });
+    }
+    open_path(target)
+}
+
+#[cfg(not(unix))]
+fn open_target(target: &str) -> Result<File> {
+    open_path(target)
+}
+
+fn open_path(target: &str) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(target)
+        .with_context(|| format!("Failed to open event stream {}", target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::WorktreeStatus;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn events(&self) -> Vec<Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    fn events_test_worktree(name: &str) -> Worktree {
+        Worktree {
+            path: PathBuf::from(format!("/repo/{}", name)),
+            branch: Some(name.to_string()),
+            is_bare: false,
+            status: WorktreeStatus::Clean,
+            last_commit_time: None,
+            ahead_behind: None,
+            github_pr_status: None,
+            locked: false,
+        }
+    }
+
+    #[test]
+    fn sink_emits_only_on_change() {
+        let buffer = SharedBuffer::default();
+        let mut sink = EventSink::new(Box::new(buffer.clone()));
+        let main = events_test_worktree("main");
+        let feature = events_test_worktree("feature");
+
+        sink.observe_mode("list");
+        sink.observe_mode("list");
+        sink.observe_selection(Some(&main));
+        sink.observe_selection(Some(&main));
+        sink.observe_selection(Some(&feature));
+
+        let events = buffer.events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0], json!({ "event": "mode", "mode": "list" }));
+        assert_eq!(events[1]["event"], "selection");
+        assert_eq!(events[1]["branch"], "main");
+        assert_eq!(events[2]["path"], "/repo/feature");
+    }
+
+    #[test]
+    fn sink_reports_op_start_once_and_finish() {
+        let buffer = SharedBuffer::default();
+        let mut sink = EventSink::new(Box::new(buffer.clone()));
+        let op = ActiveOp {
+            kind: OpKind::Pull,
+            worktree_path: PathBuf::from("/repo/main"),
+            worktree_paths: vec![PathBuf::from("/repo/main")],
+            display_name: "main".to_string(),
+        };
+
+        sink.observe_op(Some(&op));
+        sink.observe_op(Some(&op));
+        sink.op_finished(&OpKind::Pull, false, "rejected");
+        sink.observe_op(None);
+
+        let events = buffer.events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["event"], "op_started");
+        assert_eq!(events[0]["kind"], "pull");
+        assert_eq!(events[0]["paths"], json!(["/repo/main"]));
+        assert_eq!(
+            events[1],
+            json!({ "event": "op_finished", "kind": "pull", "success": false, "message": "rejected" })
+        );
+    }
+}
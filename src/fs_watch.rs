@@ -0,0 +1,217 @@
+use anyhow::{Context, Result};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::worktree_repair;
+
+/// Quiet time after the last event before the changed worktrees are
+/// reported, so a save or a checkout touching many files costs one refresh
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// What an event path means for the worktree list
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Change {
+    Worktree(PathBuf),
+    All, // A ref moved, so ahead/behind may change everywhere
+}
+
+/// Where everything lives, to map event paths back to worktrees
+struct Targets {
+    common_dir: PathBuf,
+    worktrees: Vec<PathBuf>,
+    admin_dirs: HashMap<PathBuf, PathBuf>, // `.git` dir of a worktree -> worktree
+}
+
+/// Watches every worktree and the repository's refs for `live_status`, and
+/// reports which worktrees need their status re-read.
+pub(crate) struct WorktreeWatcher {
+    _watcher: RecommendedWatcher,
+    events: mpsc::Receiver<notify::Result<Event>>,
+    targets: Targets,
+    originals: HashMap<PathBuf, PathBuf>, // Watched (canonical) path -> path as listed
+    pending: HashSet<PathBuf>,
+    last_event: Option<Instant>,
+}
+
+impl WorktreeWatcher {
+    /// Watch `worktree_paths` recursively, plus the refs and per-worktree
+    /// administrative files (HEAD, index) under `common_dir`
+    pub(crate) fn start(common_dir: &Path, worktree_paths: &[PathBuf]) -> Result<Self> {
+        let common_dir = normalize(common_dir);
+        let originals: HashMap<PathBuf, PathBuf> = worktree_paths
+            .iter()
+            .map(|path| (normalize(path), path.clone()))
+            .collect();
+        let (tx, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .context("Failed to start file watcher")?;
+
+        for path in originals.keys() {
+            watcher
+                .watch(path, RecursiveMode::Recursive)
+                .with_context(|| format!("Failed to watch {}", path.display()))?;
+        }
+        watcher
+            .watch(&common_dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", common_dir.display()))?;
+        for dir in ["refs", "worktrees"] {
+            let path = common_dir.join(dir);
+            if path.is_dir() {
+                watcher
+                    .watch(&path, RecursiveMode::Recursive)
+                    .with_context(|| format!("Failed to watch {}", path.display()))?;
+            }
+        }
+
+        let admin_dirs = originals
+            .keys()
+            .filter_map(|path| {
+                let git = path.join(".git");
+                let admin_dir = if git.is_dir() {
+                    git
+                } else {
+                    worktree_repair::gitdir_of(path)?
+                };
+                Some((normalize(&admin_dir), path.clone()))
+            })
+            .collect();
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            targets: Targets {
+                common_dir,
+                worktrees: originals.keys().cloned().collect(),
+                admin_dirs,
+            },
+            originals,
+            pending: HashSet::new(),
+            last_event: None,
+        })
+    }
+
+    /// Worktrees touched since the last call, once events have been quiet
+    /// for `DEBOUNCE`. Empty while nothing changed or events keep coming.
+    pub(crate) fn settled_changes(&mut self) -> Vec<PathBuf> {
+        while let Ok(event) = self.events.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+            for path in &event.paths {
+                match self.targets.classify(path) {
+                    Some(Change::Worktree(worktree)) => {
+                        self.pending.insert(worktree);
+                    }
+                    Some(Change::All) => self.pending.extend(self.targets.worktrees.clone()),
+                    None => continue,
+                }
+                self.last_event = Some(Instant::now());
+            }
+        }
+
+        match self.last_event {
+            Some(last) if last.elapsed() >= DEBOUNCE => {
+                self.last_event = None;
+                let mut changed: Vec<PathBuf> = self
+                    .pending
+                    .drain()
+                    .filter_map(|path| self.originals.get(&path).cloned())
+                    .collect();
+                changed.sort();
+                changed
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Targets {
+    fn classify(&self, path: &Path) -> Option<Change> {
+        let name = path.file_name()?.to_string_lossy();
+        if name.ends_with(".lock") {
+            return None;
+        }
+
+        if let Ok(rel) = path.strip_prefix(&self.common_dir) {
+            let mut parts = rel.components().map(Component::as_os_str);
+            return match parts.next()?.to_str()? {
+                "refs" | "packed-refs" => Some(Change::All),
+                "worktrees" => {
+                    let admin_dir = self.common_dir.join("worktrees").join(parts.next()?);
+                    match parts.next()?.to_str()? {
+                        "HEAD" | "index" => self.admin_change(&admin_dir),
+                        _ => None,
+                    }
+                }
+                "HEAD" | "index" => self.admin_change(&self.common_dir),
+                _ => None,
+            };
+        }
+
+        self.worktrees
+            .iter()
+            .filter(|worktree| path.starts_with(worktree))
+            .max_by_key(|worktree| worktree.components().count())
+            .map(|worktree| Change::Worktree(worktree.clone()))
+    }
+
+    fn admin_change(&self, admin_dir: &Path) -> Option<Change> {
+        self.admin_dirs
+            .get(admin_dir)
+            .map(|worktree| Change::Worktree(worktree.clone()))
+    }
+}
+
+/// Resolve `..` and symlinks where possible so `.git` file paths compare
+/// equal to the paths events arrive with
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_maps_worktree_files_and_git_state_to_rows() {
+        let main = PathBuf::from("/repo/main");
+        let feature = PathBuf::from("/repo/feature");
+        let nested = PathBuf::from("/repo/feature/nested");
+        let targets = Targets {
+            common_dir: PathBuf::from("/repo/.bare"),
+            worktrees: vec![main.clone(), feature.clone(), nested.clone()],
+            admin_dirs: HashMap::from([
+                (PathBuf::from("/repo/.bare/worktrees/main"), main.clone()),
+                (
+                    PathBuf::from("/repo/.bare/worktrees/feature"),
+                    feature.clone(),
+                ),
+            ]),
+        };
+        let classify = |path: &str| targets.classify(Path::new(path));
+
+        assert_eq!(
+            classify("/repo/main/src/lib.rs"),
+            Some(Change::Worktree(main.clone()))
+        );
+        assert_eq!(
+            classify("/repo/feature/nested/a.txt"),
+            Some(Change::Worktree(nested))
+        );
+        assert_eq!(
+            classify("/repo/.bare/worktrees/feature/index"),
+            Some(Change::Worktree(feature))
+        );
+        assert_eq!(classify("/repo/.bare/refs/heads/main"), Some(Change::All));
+        assert_eq!(classify("/repo/.bare/packed-refs"), Some(Change::All));
+        assert_eq!(classify("/repo/.bare/worktrees/main/index.lock"), None);
+        assert_eq!(classify("/repo/.bare/worktrees/main/logs/HEAD"), None);
+        assert_eq!(classify("/repo/.bare/objects/ab/cdef"), None);
+        assert_eq!(classify("/elsewhere/file"), None);
+    }
+}
@@ -0,0 +1,84 @@
+/// fzf-style subsequence score of `pattern` in `text`, ignoring case, or
+/// `None` when the characters do not all appear in order. Consecutive
+/// characters and characters at the start of a word (after `/`, `-`, `_`,
+/// `.` or a space) score higher, and gaps between matches cost a little, so
+/// `ftlgn` prefers `feature/login` over `fix-tooling-gen`.
+pub(crate) fn score(pattern: &str, text: &str) -> Option<i64> {
+    fuzzy_match(pattern, text).map(|(score, _)| score)
+}
+
+/// Indices of the characters of `text` that `pattern` matched, for
+/// highlighting, or `None` when it does not match.
+pub(crate) fn positions(pattern: &str, text: &str) -> Option<Vec<usize>> {
+    fuzzy_match(pattern, text).map(|(_, positions)| positions)
+}
+
+fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    const MATCH: i64 = 16;
+    const CONSECUTIVE: i64 = 16;
+    const WORD_START: i64 = 24;
+    const GAP: i64 = 1;
+
+    let mut pattern = pattern.chars().map(fold_case).peekable();
+    pattern.peek()?;
+
+    let mut total = 0;
+    let mut positions = Vec::new();
+    let mut previous: Option<char> = None;
+    for (index, c) in text.chars().enumerate() {
+        let Some(&wanted) = pattern.peek() else {
+            break;
+        };
+        if fold_case(c) == wanted {
+            total += MATCH;
+            if previous.is_none_or(|p| matches!(p, '/' | '-' | '_' | '.' | ' ')) {
+                total += WORD_START;
+            }
+            match positions.last() {
+                Some(&last) if last + 1 == index => total += CONSECUTIVE,
+                Some(&last) => total -= GAP * (index - last - 1) as i64,
+                None => {}
+            }
+            positions.push(index);
+            pattern.next();
+        }
+        previous = Some(c);
+    }
+
+    pattern.peek().is_none().then_some((total, positions))
+}
+
+/// Compare one character at a time so match positions line up with `text`
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_matches_subsequences_and_prefers_word_starts_and_runs() {
+        assert!(score("ftlgn", "feature/login").is_some());
+        assert!(score("FtLgn", "feature/login").is_some());
+        assert_eq!(score("xyz", "feature/login"), None);
+        assert_eq!(score("nigol", "feature/login"), None);
+        assert_eq!(score("", "feature/login"), None);
+
+        let login = score("login", "feature/login").unwrap();
+        assert!(login > score("login", "fix/long-option-ignore").unwrap());
+        assert!(
+            score("ftlgn", "feature/login").unwrap() > score("ftlgn", "fix-tooling-gen").unwrap()
+        );
+    }
+
+    #[test]
+    fn positions_point_at_the_matched_characters() {
+        assert_eq!(
+            positions("ftlgn", "feature/login"),
+            Some(vec![0, 3, 8, 10, 12])
+        );
+        assert_eq!(positions("LOG", "Ünï/login"), Some(vec![4, 5, 6]));
+        assert_eq!(positions("xyz", "feature/login"), None);
+    }
+}
@@ -1,9 +1,18 @@
 use anyhow::{Context, Result};
 use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use crate::types::{AheadBehind, GithubPrStatus, Worktree, WorktreeDetails, WorktreeStatus};
+use crate::command_log;
+use crate::types::{
+    AheadBehind, BaseComparison, BaseUpdate, BaseUpdateOutcome, ChangedFile, CloneProgress,
+    DiscardMode, GithubPrStatus, LogCommit, Worktree, WorktreeDetails, WorktreeStatus,
+};
 
 fn git_command() -> Command {
     let mut command = Command::new("git");
@@ -15,14 +24,309 @@ fn git_command() -> Command {
     command
 }
 
+/// `Command::output` for commands that change something, recorded in the
+/// command log (`@` in the TUI)
+trait LoggedOutput {
+    fn logged_output(&mut self) -> std::io::Result<Output>;
+}
+
+impl LoggedOutput for Command {
+    fn logged_output(&mut self) -> std::io::Result<Output> {
+        let result = self.output();
+        command_log::record(self, &result);
+        result
+    }
+}
+
+/// Kill switch for git commands run on a background thread. Canceling kills
+/// the running git child, and commands started afterwards fail right away.
+/// With a timeout, each command is also killed once it runs that long. A
+/// command that failed because it needed a password or passphrase is kept
+/// so it can be run again in the terminal.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    canceled: Arc<AtomicBool>,
+    timeout: Option<Duration>,
+    prompted: Arc<Mutex<Option<PromptedCommand>>>,
+    progress: Arc<Mutex<Option<String>>>,
+}
+
+/// A git command that has to ask the user something before it can run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptedCommand {
+    pub args: Vec<OsString>,
+    pub dir: Option<PathBuf>,
+}
+
+impl PromptedCommand {
+    /// Run the command again with `tty` as its stdin, stdout, and stderr, so
+    /// git and ssh can prompt without touching our stdout, which may be the
+    /// cd handoff pipe. The TUI must have released the terminal.
+    pub fn run_interactive(&self, tty: &std::fs::File) -> Result<std::process::ExitStatus> {
+        let mut command = git_command();
+        command
+            .args(&self.args)
+            .stdin(tty.try_clone()?)
+            .stdout(tty.try_clone()?)
+            .stderr(tty.try_clone()?);
+        if let Some(dir) = &self.dir {
+            command.current_dir(dir);
+        }
+        command.status().context("Failed to run git")
+    }
+
+    /// `git <args>` for messages
+    pub fn display(&self) -> String {
+        std::iter::once("git".into())
+            .chain(self.args.iter().map(|arg| arg.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl CancelToken {
+    pub fn with_timeout(timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            ..Self::default()
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.canceled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::SeqCst)
+    }
+
+    /// Latest `--progress` meter of the running command, e.g.
+    /// `Receiving 45% (450/1000)`
+    pub fn progress(&self) -> Option<String> {
+        self.progress.lock().ok()?.clone()
+    }
+
+    /// The first command that failed for lack of a terminal prompt, if any
+    pub fn take_prompted(&self) -> Option<PromptedCommand> {
+        self.prompted.lock().ok()?.take()
+    }
+
+    fn record_prompted(&self, command: &Command) {
+        if let Ok(mut prompted) = self.prompted.lock() {
+            prompted.get_or_insert_with(|| PromptedCommand {
+                args: command.get_args().map(OsString::from).collect(),
+                dir: command.get_current_dir().map(Path::to_path_buf),
+            });
+        }
+    }
+}
+
+/// Collect a child's stderr, keeping git's `--progress` meter out of it:
+/// every meter update goes to `progress` instead, so a running fetch can show
+/// how far it got and the final message does not repeat the meter. Anything
+/// else is kept, whether it ends in `\r` or `\n`.
+fn read_stderr_with_progress(mut pipe: impl Read, progress: &Mutex<Option<String>>) -> Vec<u8> {
+    let mut kept = Vec::new();
+    let mut segment = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = match pipe.read(&mut chunk) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => read,
+        };
+        for &byte in &chunk[..read] {
+            segment.push(byte);
+            if byte != b'\r' && byte != b'\n' {
+                continue;
+            }
+            match parse_progress(&String::from_utf8_lossy(&segment)) {
+                Some(meter) => {
+                    if let Ok(mut progress) = progress.lock() {
+                        *progress = Some(meter);
+                    }
+                }
+                None => kept.extend_from_slice(&segment),
+            }
+            segment.clear();
+        }
+    }
+    kept.extend_from_slice(&segment);
+    kept
+}
+
+/// `Receiving 45% (450/1000)` from a git progress line such as
+/// `remote: Counting objects:  45% (450/1000), 1.2 MiB | 2 MiB/s`
+pub(crate) fn parse_progress(line: &str) -> Option<String> {
+    let line = line.trim();
+    let line = line.strip_prefix("remote: ").unwrap_or(line);
+    let (phase, rest) = line.split_once(':')?;
+    let rest = rest.trim_start();
+    let (percent, rest) = rest.split_once('%')?;
+    let percent: u8 = percent.trim().parse().ok()?;
+    let counts = rest
+        .trim_start()
+        .strip_prefix('(')
+        .and_then(|rest| rest.split_once(')'))
+        .map(|(counts, _)| counts)?;
+    let phase = phase.split_whitespace().next()?;
+    Some(format!("{} {}% ({})", phase, percent, counts))
+}
+
+/// Whether git's stderr says it stopped because it could not ask for a
+/// username, password, SSH passphrase, or host key confirmation
+pub(crate) fn needs_terminal_prompt(stderr: &str) -> bool {
+    [
+        "terminal prompts disabled",
+        "could not read Username",
+        "could not read Password",
+        "read_passphrase: can't open /dev/tty",
+        "Host key verification failed",
+        "Permission denied (publickey",
+    ]
+    .iter()
+    .any(|needle| stderr.contains(needle))
+}
+
+/// Kill the child and, since it leads its own session, whatever it started
+/// (ssh, credential helpers)
+fn kill_child(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// `command.output()`, killing the child once `cancel` is canceled. The
+/// child runs in its own session without prompts, so git and ssh fail
+/// instead of reading a password from the terminal the TUI is drawing on.
+fn output_cancelable(command: &mut Command, cancel: &CancelToken, what: &str) -> Result<Output> {
+    output_cancelable_then(command, cancel, what, || {})
+}
+
+/// [`output_cancelable`] that runs `after_kill` once the child was killed,
+/// before the cancel or timeout is reported
+fn output_cancelable_then(
+    command: &mut Command,
+    cancel: &CancelToken,
+    what: &str,
+    after_kill: impl FnOnce(),
+) -> Result<Output> {
+    if cancel.is_canceled() {
+        anyhow::bail!("Canceled");
+    }
+    command.env("GIT_TERMINAL_PROMPT", "0");
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // SAFETY: setsid is async-signal-safe
+        unsafe {
+            command.pre_exec(|| {
+                libc::setsid();
+                Ok(())
+            });
+        }
+    }
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| what.to_string())?;
+
+    let stdout = child.stdout.take().map(|mut pipe| {
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = pipe.read_to_end(&mut buffer);
+            buffer
+        })
+    });
+    let stderr = child.stderr.take().map(|pipe| {
+        let progress = Arc::clone(&cancel.progress);
+        std::thread::spawn(move || read_stderr_with_progress(pipe, &progress))
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().with_context(|| what.to_string())? {
+            break status;
+        }
+        if cancel.is_canceled() {
+            kill_child(&mut child);
+            after_kill();
+            command_log::record(command, &Err(std::io::Error::other("Canceled")));
+            anyhow::bail!("Canceled");
+        }
+        if let Some(timeout) = cancel
+            .timeout
+            .filter(|timeout| started.elapsed() >= *timeout)
+        {
+            kill_child(&mut child);
+            after_kill();
+            let message = format!("timed out after {}s", timeout.as_secs());
+            command_log::record(command, &Err(std::io::Error::other(message.clone())));
+            anyhow::bail!("{}: {}", what, message);
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let join = |reader: Option<std::thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    };
+    let result = Ok(Output {
+        status,
+        stdout: join(stdout),
+        stderr: join(stderr),
+    });
+    command_log::record(command, &result);
+    let output = result?;
+    if !output.status.success() && needs_terminal_prompt(&String::from_utf8_lossy(&output.stderr)) {
+        cancel.record_prompted(command);
+    }
+    Ok(output)
+}
+
+/// Undo what a killed merge or pull left in `worktree_path`: remove the
+/// `index.lock` the dead git held and abort the merge or rebase it started,
+/// so later git commands in the worktree work
+fn recover_interrupted_merge(worktree_path: &Path) {
+    let dir = worktree_path.to_string_lossy();
+    let git_path = |name: &str| {
+        let output = git_command()
+            .args(["-C", &dir, "rev-parse", "--git-path", name])
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| worktree_path.join(String::from_utf8_lossy(&output.stdout).trim()))
+    };
+    let exists = |name: &str| git_path(name).is_some_and(|path| path.exists());
+
+    if let Some(lock) = git_path("index.lock") {
+        let _ = std::fs::remove_file(lock);
+    }
+    if exists("MERGE_HEAD") {
+        let _ = git_command()
+            .args(["-C", &dir, "merge", "--abort"])
+            .logged_output();
+    }
+    if exists("rebase-merge") || exists("rebase-apply") {
+        let _ = git_command()
+            .args(["-C", &dir, "rebase", "--abort"])
+            .logged_output();
+    }
+}
+
 /// Check for .bare folder pattern (common worktree layout)
 /// Returns the path to .bare if found
 pub fn find_bare_in_parent(path: &Path) -> Option<PathBuf> {
     let bare_path = path.join(".bare");
-    if bare_path.exists() && bare_path.is_dir() {
-        if is_bare_repo(&bare_path).unwrap_or(false) {
-            return Some(bare_path);
-        }
+    if bare_path.exists() && bare_path.is_dir() && is_bare_repo(&bare_path).unwrap_or(false) {
+        return Some(bare_path);
     }
     None
 }
@@ -121,6 +425,7 @@ fn parse_worktree_list(output: &str, _bare_repo_path: &Path) -> Result<Vec<Workt
     let mut current_path: Option<PathBuf> = None;
     let mut current_branch: Option<String> = None;
     let mut is_bare = false;
+    let mut locked = false;
 
     for line in output.lines() {
         if line.starts_with("worktree ") {
@@ -128,11 +433,7 @@ fn parse_worktree_list(output: &str, _bare_repo_path: &Path) -> Result<Vec<Workt
                 let (status, last_commit_time, ahead_behind) = if is_bare {
                     (WorktreeStatus::Clean, None, None)
                 } else {
-                    (
-                        get_status(&path).unwrap_or(WorktreeStatus::Clean),
-                        get_last_commit_time(&path).ok(),
-                        get_ahead_behind(&path),
-                    )
+                    worktree_state(&path)
                 };
                 worktrees.push(Worktree {
                     path,
@@ -142,10 +443,12 @@ fn parse_worktree_list(output: &str, _bare_repo_path: &Path) -> Result<Vec<Workt
                     last_commit_time,
                     ahead_behind,
                     github_pr_status: None,
+                    locked,
                 });
             }
             current_path = Some(PathBuf::from(line.strip_prefix("worktree ").unwrap()));
             is_bare = false;
+            locked = false;
         } else if line.starts_with("branch ") {
             let branch = line
                 .strip_prefix("branch refs/heads/")
@@ -153,6 +456,8 @@ fn parse_worktree_list(output: &str, _bare_repo_path: &Path) -> Result<Vec<Workt
             current_branch = Some(branch.to_string());
         } else if line == "bare" {
             is_bare = true;
+        } else if line == "locked" || line.starts_with("locked ") {
+            locked = true;
         } else if line.starts_with("HEAD ") {
             // Detached HEAD, no branch
         }
@@ -163,11 +468,7 @@ fn parse_worktree_list(output: &str, _bare_repo_path: &Path) -> Result<Vec<Workt
         let (status, last_commit_time, ahead_behind) = if is_bare {
             (WorktreeStatus::Clean, None, None)
         } else {
-            (
-                get_status(&path).unwrap_or(WorktreeStatus::Clean),
-                get_last_commit_time(&path).ok(),
-                get_ahead_behind(&path),
-            )
+            worktree_state(&path)
         };
         worktrees.push(Worktree {
             path,
@@ -177,12 +478,34 @@ fn parse_worktree_list(output: &str, _bare_repo_path: &Path) -> Result<Vec<Workt
             last_commit_time,
             ahead_behind,
             github_pr_status: None,
+            locked,
         });
     }
 
     Ok(worktrees)
 }
 
+fn worktree_state(path: &Path) -> (WorktreeStatus, Option<String>, Option<AheadBehind>) {
+    (
+        get_status(path).unwrap_or(WorktreeStatus::Clean),
+        get_last_commit_time(path).ok(),
+        get_ahead_behind(path),
+    )
+}
+
+/// Re-read one worktree's branch, status, last commit, and ahead/behind
+/// without listing every worktree again
+pub fn refresh_worktree_state(worktree: &mut Worktree) {
+    if worktree.is_bare {
+        return;
+    }
+    let (status, last_commit_time, ahead_behind) = worktree_state(&worktree.path);
+    worktree.branch = current_branch(&worktree.path);
+    worktree.status = status;
+    worktree.last_commit_time = last_commit_time;
+    worktree.ahead_behind = ahead_behind;
+}
+
 pub fn get_status(path: &Path) -> Result<WorktreeStatus> {
     ensure_worktree_is_usable(path)?;
 
@@ -249,6 +572,19 @@ pub fn add_worktree(
     branch: &str,
     worktree_path: &Path,
     base_branch: Option<&str>,
+) -> Result<()> {
+    add_worktree_sparse(bare_repo_path, branch, worktree_path, base_branch, &[])
+}
+
+/// Add a worktree that only checks out `sparse_paths` (cone-mode directories).
+/// The worktree is created with `--no-checkout` so files outside the cone are
+/// never written; with no paths this is a regular full checkout.
+pub fn add_worktree_sparse(
+    bare_repo_path: &Path,
+    branch: &str,
+    worktree_path: &Path,
+    base_branch: Option<&str>,
+    sparse_paths: &[String],
 ) -> Result<()> {
     if let Some(parent) = worktree_path.parent() {
         std::fs::create_dir_all(parent).with_context(|| {
@@ -265,23 +601,25 @@ pub fn add_worktree(
         "worktree".to_string(),
         "add".to_string(),
     ];
+    if !sparse_paths.is_empty() {
+        args.push("--no-checkout".to_string());
+    }
 
     let branch_exists = ref_exists(bare_repo_path, &format!("refs/heads/{}", branch));
-    let remote_branch_exists =
-        ref_exists(bare_repo_path, &format!("refs/remotes/origin/{}", branch));
+    let remote_branch = find_remote_branch(bare_repo_path, branch);
     let base_ref = resolve_base_ref(bare_repo_path, base_branch);
 
     if branch_exists {
         // Branch exists locally, just add worktree
         args.push(worktree_path.to_string_lossy().to_string());
         args.push(branch.to_string());
-    } else if remote_branch_exists {
+    } else if let Some(remote_branch) = remote_branch {
         // Remote branch exists, track it
         args.push("--track".to_string());
         args.push("-b".to_string());
         args.push(branch.to_string());
         args.push(worktree_path.to_string_lossy().to_string());
-        args.push(format!("origin/{}", branch));
+        args.push(remote_branch);
     } else {
         // Create new branch
         args.push("-b".to_string());
@@ -294,7 +632,7 @@ pub fn add_worktree(
 
     let output = git_command()
         .args(&args)
-        .output()
+        .logged_output()
         .context("Failed to add worktree")?;
 
     if !output.status.success() {
@@ -306,6 +644,144 @@ pub fn add_worktree(
 
     ensure_worktree_is_usable(worktree_path)?;
 
+    if !sparse_paths.is_empty() {
+        populate_sparse_worktree(worktree_path, sparse_paths)?;
+    }
+
+    Ok(())
+}
+
+/// Add a worktree with a detached HEAD at `commit`, such as a snapshot of
+/// another worktree to run tests on while that one keeps changing
+pub fn add_detached_worktree(
+    bare_repo_path: &Path,
+    worktree_path: &Path,
+    commit: &str,
+) -> Result<()> {
+    if let Some(parent) = worktree_path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "Failed to create worktree parent directory {}",
+                parent.display()
+            )
+        })?;
+    }
+    let output = git_command()
+        .args([
+            "-C",
+            &bare_repo_path.to_string_lossy(),
+            "worktree",
+            "add",
+            "--detach",
+            &worktree_path.to_string_lossy(),
+            commit,
+        ])
+        .logged_output()
+        .context("Failed to add worktree")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to add worktree: {}",
+            command_failure_detail(&output)
+        );
+    }
+    ensure_worktree_is_usable(worktree_path)
+}
+
+/// Run `git submodule update --init --recursive` in a new worktree, which
+/// otherwise has empty submodule directories. Returns false without running
+/// anything when the worktree has no `.gitmodules`.
+pub fn update_submodules(worktree_path: &Path) -> Result<bool> {
+    if !worktree_path.join(".gitmodules").is_file() {
+        return Ok(false);
+    }
+    let output = git_command()
+        .args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "submodule",
+            "update",
+            "--init",
+            "--recursive",
+        ])
+        .logged_output()
+        .context("Failed to update submodules")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to update submodules: {}",
+            command_failure_detail(&output)
+        );
+    }
+    Ok(true)
+}
+
+/// Whether the worktree's top-level `.gitattributes` sends files through the
+/// Git LFS filter
+pub fn uses_lfs(worktree_path: &Path) -> bool {
+    std::fs::read_to_string(worktree_path.join(".gitattributes")).is_ok_and(|attributes| {
+        attributes
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .any(|line| line.split_whitespace().any(|attr| attr == "filter=lfs"))
+    })
+}
+
+/// Run `git lfs pull` in a new worktree so LFS files are real content rather
+/// than pointer files. Returns false without running anything when the
+/// worktree does not use LFS. The download meter shows through `cancel`.
+pub fn lfs_pull(worktree_path: &Path, cancel: &CancelToken) -> Result<bool> {
+    if !uses_lfs(worktree_path) {
+        return Ok(false);
+    }
+    let mut command = git_command();
+    command
+        .args(["-C", &worktree_path.to_string_lossy(), "lfs", "pull"])
+        // git-lfs only prints its meter to a terminal otherwise
+        .env("GIT_LFS_FORCE_PROGRESS", "1");
+    let output = output_cancelable(&mut command, cancel, "Failed to pull LFS objects")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to pull LFS objects: {}",
+            command_failure_detail(&output)
+        );
+    }
+    Ok(true)
+}
+
+fn populate_sparse_worktree(worktree_path: &Path, sparse_paths: &[String]) -> Result<()> {
+    let worktree_str = worktree_path.to_string_lossy();
+
+    let mut set_args = vec![
+        "-C",
+        &*worktree_str,
+        "sparse-checkout",
+        "set",
+        "--cone",
+        "--",
+    ];
+    set_args.extend(sparse_paths.iter().map(String::as_str));
+    let output = git_command()
+        .args(&set_args)
+        .logged_output()
+        .context("Failed to configure sparse checkout")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to configure sparse checkout: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    // Fill the index from HEAD and write only the files inside the cone
+    let output = git_command()
+        .args(["-C", &worktree_str, "read-tree", "-mu", "HEAD"])
+        .logged_output()
+        .context("Failed to populate sparse worktree")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to populate sparse worktree: {}",
+            command_failure_detail(&output)
+        );
+    }
+
     Ok(())
 }
 
@@ -324,33 +800,112 @@ fn ref_exists(bare_repo_path: &Path, reference: &str) -> bool {
         .unwrap_or(false)
 }
 
-fn has_origin_remote(bare_repo_path: &Path) -> bool {
-    git_command()
+/// List configured remotes in `git remote` order
+pub fn list_remotes(repo_path: &Path) -> Vec<String> {
+    let output = git_command()
+        .args(["-C", &repo_path.to_string_lossy(), "remote"])
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn git_config_value(repo_path: &Path, key: &str) -> Option<String> {
+    let output = git_command()
+        .args(["-C", &repo_path.to_string_lossy(), "config", "--get", key])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Remote used for new branches and base refs.
+/// Prefers git's `checkout.defaultRemote`, then `origin`, then the first remote.
+pub fn default_remote(repo_path: &Path) -> Option<String> {
+    let remotes = list_remotes(repo_path);
+    if let Some(configured) = git_config_value(repo_path, "checkout.defaultRemote") {
+        if remotes.contains(&configured) {
+            return Some(configured);
+        }
+    }
+    if remotes.iter().any(|remote| remote == "origin") {
+        return Some("origin".to_string());
+    }
+    remotes.into_iter().next()
+}
+
+/// Remote a branch tracks (`branch.<name>.remote`), falling back to the default remote
+pub fn branch_remote(repo_path: &Path, branch: &str) -> Option<String> {
+    git_config_value(repo_path, &format!("branch.{}.remote", branch))
+        .filter(|remote| remote != ".")
+        .or_else(|| default_remote(repo_path))
+}
+
+/// Current branch of a worktree, or None when HEAD is detached
+pub fn current_branch(worktree_path: &Path) -> Option<String> {
+    let output = git_command()
         .args([
             "-C",
-            &bare_repo_path.to_string_lossy(),
-            "remote",
-            "get-url",
-            "origin",
+            &worktree_path.to_string_lossy(),
+            "symbolic-ref",
+            "--quiet",
+            "--short",
+            "HEAD",
         ])
         .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if branch.is_empty() {
+        None
+    } else {
+        Some(branch)
+    }
 }
 
-fn remote_branch_exists_on_origin(bare_repo_path: &Path, branch: &str) -> Result<bool> {
-    if !has_origin_remote(bare_repo_path) {
-        return Ok(false);
+/// Find `<remote>/<branch>` among remote-tracking refs, checking the default remote first
+fn find_remote_branch(repo_path: &Path, branch: &str) -> Option<String> {
+    let mut remotes = list_remotes(repo_path);
+    if let Some(default) = default_remote(repo_path) {
+        remotes.retain(|remote| remote != &default);
+        remotes.insert(0, default);
     }
 
+    remotes
+        .into_iter()
+        .find(|remote| ref_exists(repo_path, &format!("refs/remotes/{}/{}", remote, branch)))
+        .map(|remote| format!("{}/{}", remote, branch))
+}
+
+fn remote_branch_exists(repo_path: &Path, remote: &str, branch: &str) -> Result<bool> {
     let output = git_command()
         .args([
             "-C",
-            &bare_repo_path.to_string_lossy(),
+            &repo_path.to_string_lossy(),
             "ls-remote",
             "--exit-code",
             "--heads",
-            "origin",
+            remote,
             branch,
         ])
         .output()
@@ -362,44 +917,64 @@ fn remote_branch_exists_on_origin(bare_repo_path: &Path, branch: &str) -> Result
         Ok(false)
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to inspect origin/{}: {}", branch, stderr.trim());
+        anyhow::bail!("Failed to inspect {}/{}: {}", remote, branch, stderr.trim());
     }
 }
 
+/// Fetch a single branch from the default remote into its remote-tracking ref.
+/// Returns false when there is no remote or the branch does not exist there.
 pub fn fetch_remote_branch(bare_repo_path: &Path, branch: &str) -> Result<bool> {
-    if !remote_branch_exists_on_origin(bare_repo_path, branch)? {
+    let Some(remote) = default_remote(bare_repo_path) else {
+        return Ok(false);
+    };
+    if !remote_branch_exists(bare_repo_path, &remote, branch)? {
         return Ok(false);
     }
 
-    let refspec = format!("refs/heads/{}:refs/remotes/origin/{}", branch, branch);
+    let refspec = format!("refs/heads/{}:refs/remotes/{}/{}", branch, remote, branch);
     let output = git_command()
         .args([
             "-C",
             &bare_repo_path.to_string_lossy(),
             "fetch",
-            "origin",
+            &remote,
             &refspec,
         ])
-        .output()
+        .logged_output()
         .context("Failed to fetch remote branch")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to fetch origin/{}: {}", branch, stderr.trim());
+        anyhow::bail!("Failed to fetch {}/{}: {}", remote, branch, stderr.trim());
     }
 
     Ok(true)
 }
 
-fn resolve_base_ref(bare_repo_path: &Path, base_branch: Option<&str>) -> Option<String> {
-    base_branch.map(|base| {
-        let remote_ref = format!("refs/remotes/origin/{}", base);
-        if ref_exists(bare_repo_path, &remote_ref) {
-            format!("origin/{}", base)
-        } else {
-            base.to_string()
+/// Fetch `base` from the default remote and compare the local branch with it.
+pub fn compare_base_branch(repo_path: &Path, base: &str) -> BaseComparison {
+    let fetched = fetch_remote_branch(repo_path, base).unwrap_or(false);
+    let remote_branch = find_remote_branch(repo_path, base);
+    let local_ref = format!("refs/heads/{}", base);
+    let has_local = ref_exists(repo_path, &local_ref);
+    let local_vs_remote = match (&remote_branch, has_local) {
+        (Some(remote), true) => {
+            count_ahead_behind_of(repo_path, &format!("refs/remotes/{}", remote), &local_ref)
         }
-    })
+        _ => None,
+    };
+
+    BaseComparison {
+        remote_branch,
+        has_local,
+        local_vs_remote,
+        fetched,
+    }
+}
+
+fn resolve_base_ref(bare_repo_path: &Path, base_branch: Option<&str>) -> Option<String> {
+    base_branch
+        .map(|base| find_remote_branch(bare_repo_path, base).unwrap_or_else(|| base.to_string()))
 }
 
 fn ensure_worktree_is_usable(worktree_path: &Path) -> Result<()> {
@@ -460,7 +1035,7 @@ fn ensure_worktree_is_usable(worktree_path: &Path) -> Result<()> {
         .arg(format!("--git-dir={}", resolved_git_dir.display()))
         .arg(format!("--work-tree={}", worktree_path.display()))
         .args(["config", "--worktree", "core.bare", "false"])
-        .output()
+        .logged_output()
         .context("Failed to write worktree-specific config")?;
 
     if !fix_output.status.success() {
@@ -506,28 +1081,43 @@ pub fn build_add_worktree_command_detail(
     branch: &str,
     worktree_path: &Path,
     base_branch: Option<&str>,
+    sparse_paths: &[String],
 ) -> String {
     let branch_exists = ref_exists(bare_repo_path, &format!("refs/heads/{}", branch));
-    let remote_branch_exists =
-        ref_exists(bare_repo_path, &format!("refs/remotes/origin/{}", branch));
+    let remote_branch = find_remote_branch(bare_repo_path, branch);
     let base_ref = resolve_base_ref(bare_repo_path, base_branch);
 
     let bare = bare_repo_path.display();
     let wt = worktree_path.display();
+    let add = if sparse_paths.is_empty() {
+        "worktree add"
+    } else {
+        "worktree add --no-checkout"
+    };
 
-    if branch_exists {
-        format!("git -C {} worktree add {} {}", bare, wt, branch)
-    } else if remote_branch_exists {
+    let mut detail = if branch_exists {
+        format!("git -C {} {} {} {}", bare, add, wt, branch)
+    } else if let Some(remote_branch) = remote_branch {
         format!(
-            "git -C {} worktree add --track -b {} {} origin/{}",
-            bare, branch, wt, branch
+            "git -C {} {} --track -b {} {} {}",
+            bare, add, branch, wt, remote_branch
         )
     } else {
         match base_ref {
-            Some(base) => format!("git -C {} worktree add -b {} {} {}", bare, branch, wt, base),
-            _ => format!("git -C {} worktree add -b {} {}", bare, branch, wt),
+            Some(base) => format!("git -C {} {} -b {} {} {}", bare, add, branch, wt, base),
+            _ => format!("git -C {} {} -b {} {}", bare, add, branch, wt),
         }
+    };
+
+    if !sparse_paths.is_empty() {
+        detail.push_str(&format!(
+            "\n$ git -C {} sparse-checkout set --cone -- {}\n$ git -C {} read-tree -mu HEAD",
+            wt,
+            sparse_paths.join(" "),
+            wt
+        ));
     }
+    detail
 }
 
 pub fn remove_worktree(bare_repo_path: &Path, worktree_path: &Path, force: bool) -> Result<()> {
@@ -544,7 +1134,7 @@ pub fn remove_worktree(bare_repo_path: &Path, worktree_path: &Path, force: bool)
 
     let output = git_command()
         .args(&args)
-        .output()
+        .logged_output()
         .context("Failed to remove worktree")?;
 
     if !output.status.success() {
@@ -600,7 +1190,7 @@ fn run_worktree_prune(bare_repo_path: &Path, dry_run: bool) -> Result<String> {
 
     let output = git_command()
         .args(&args)
-        .output()
+        .logged_output()
         .context("Failed to prune worktrees")?;
 
     if !output.status.success() {
@@ -608,8 +1198,14 @@ fn run_worktree_prune(bare_repo_path: &Path, dry_run: bool) -> Result<String> {
         anyhow::bail!("Failed to prune worktrees: {}", stderr.trim());
     }
 
+    // `-v` reports each entry on stderr
     let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(stdout.trim().to_string())
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok([stdout.trim(), stderr.trim()]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n"))
 }
 
 pub fn delete_branch(bare_repo_path: &Path, branch: &str, force: bool) -> Result<()> {
@@ -623,7 +1219,7 @@ pub fn delete_branch(bare_repo_path: &Path, branch: &str, force: bool) -> Result
             flag,
             branch,
         ])
-        .output()
+        .logged_output()
         .context("Failed to delete branch")?;
 
     if !output.status.success() {
@@ -634,28 +1230,592 @@ pub fn delete_branch(bare_repo_path: &Path, branch: &str, force: bool) -> Result
     Ok(())
 }
 
-/// Fetch only the remote tracking branch for a specific worktree
-pub fn fetch_worktree(worktree_path: &Path) -> Result<()> {
-    let output = git_command()
-        .args(["-C", &worktree_path.to_string_lossy(), "fetch", "origin"])
-        .output()
-        .context("Failed to fetch")?;
+/// Remote and branch name a local branch publishes to: `branch.<name>.merge`
+/// when set, otherwise the same name on the branch remote.
+pub fn remote_branch_for(repo_path: &Path, branch: &str) -> Option<(String, String)> {
+    let remote = branch_remote(repo_path, branch)?;
+    let remote_branch = git_config_value(repo_path, &format!("branch.{}.merge", branch))
+        .and_then(|merge| merge.strip_prefix("refs/heads/").map(str::to_string))
+        .unwrap_or_else(|| branch.to_string());
+    Some((remote, remote_branch))
+}
 
+/// Output of `git --version`, e.g. `git version 2.39.5`
+pub fn git_version() -> Option<String> {
+    let output = git_command().arg("--version").output().ok()?;
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to fetch: {}", stderr.trim());
+        return None;
     }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
 
-    Ok(())
+/// `remote.<name>.fetch`, which `git clone --bare` leaves unset
+pub fn remote_fetch_refspec(repo_path: &Path, remote: &str) -> Option<String> {
+    git_config_value(repo_path, &format!("remote.{}.fetch", remote))
 }
 
-pub fn get_last_commit_time(path: &Path) -> Result<String> {
+/// Full commit hash `rev` points at, if it resolves
+pub fn resolve_commit(path: &Path, rev: &str) -> Option<String> {
     let output = git_command()
-        .args(["-C", &path.to_string_lossy(), "log", "-1", "--format=%ar"])
-        .output()
-        .context("Failed to get last commit time")?;
-
-    if !output.status.success() {
+        .args([
+            "-C",
+            &path.to_string_lossy(),
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", rev),
+        ])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    let hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!hash.is_empty()).then_some(hash)
+}
+
+/// Why git would reject `name` as a new branch name, checked in-process with
+/// the rules of `git check-ref-format --branch`, or `None` when it is valid
+pub fn branch_name_problem(name: &str) -> Option<&'static str> {
+    if name.is_empty() {
+        return Some("branch name is empty");
+    }
+    if name.starts_with('-') {
+        return Some("cannot start with '-'");
+    }
+    if name == "HEAD" {
+        return Some("is reserved by git");
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| c.is_control() || matches!(c, ' ' | '~' | '^' | ':' | '?' | '*' | '[' | '\\'))
+    {
+        return Some(match c {
+            ' ' => "cannot contain spaces",
+            c if c.is_control() => "cannot contain control characters",
+            _ => "cannot contain ~ ^ : ? * [ or \\",
+        });
+    }
+    if name.contains("..") {
+        return Some("cannot contain '..'");
+    }
+    if name.contains("@{") {
+        return Some("cannot contain '@{'");
+    }
+    if name.starts_with('/') || name.ends_with('/') || name.contains("//") {
+        return Some("cannot start or end with '/' or contain '//'");
+    }
+    if name.ends_with('.') {
+        return Some("cannot end with '.'");
+    }
+    for component in name.split('/') {
+        if component.starts_with('.') {
+            return Some("no part may start with '.'");
+        }
+        if component.ends_with(".lock") {
+            return Some("no part may end with '.lock'");
+        }
+    }
+    None
+}
+
+/// Whether `branch` exists as a local branch or on any remote
+pub fn branch_exists_anywhere(repo_path: &Path, branch: &str) -> bool {
+    git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "for-each-ref",
+            "--count=1",
+            "--format=%(refname)",
+            &format!("refs/heads/{}", branch),
+            &format!("refs/remotes/*/{}", branch),
+        ])
+        .output()
+        .map(|output| output.status.success() && !output.stdout.trim_ascii().is_empty())
+        .unwrap_or(false)
+}
+
+/// Delete a branch on a remote (`git push <remote> --delete <branch>`).
+/// Returns false when the remote no longer has the branch.
+pub fn delete_remote_branch(repo_path: &Path, remote: &str, branch: &str) -> Result<bool> {
+    if !remote_branch_exists(repo_path, remote, branch)? {
+        return Ok(false);
+    }
+
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "push",
+            remote,
+            "--delete",
+            branch,
+        ])
+        .logged_output()
+        .context("Failed to delete remote branch")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to delete remote branch: {}", stderr.trim());
+    }
+
+    Ok(true)
+}
+
+/// Rename a local branch (`git branch -m <old> <new>`)
+pub fn rename_branch(repo_path: &Path, old_branch: &str, new_branch: &str) -> Result<()> {
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "branch",
+            "-m",
+            old_branch,
+            new_branch,
+        ])
+        .logged_output()
+        .context("Failed to rename branch")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to rename branch: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// `branch.<name>.description`, as set by `git branch --edit-description`
+pub fn branch_description(repo_path: &Path, branch: &str) -> Option<String> {
+    git_config_value(repo_path, &format!("branch.{}.description", branch))
+}
+
+/// Descriptions of every branch that has one, keyed by branch name
+pub fn branch_descriptions(repo_path: &Path) -> HashMap<String, String> {
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "config",
+            "-z",
+            "--get-regexp",
+            r"^branch\..*\.description$",
+        ])
+        .output();
+    let Ok(output) = output else {
+        return HashMap::new();
+    };
+
+    // -z: "<key>\n<value>\0" per entry, so multi-line values stay intact
+    String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter_map(|entry| {
+            let (key, value) = entry.split_once('\n')?;
+            let branch = key.strip_prefix("branch.")?.strip_suffix(".description")?;
+            let value = value.trim();
+            (!value.is_empty()).then(|| (branch.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// `url.<base>.insteadOf` rules from the user's and system git config, as
+/// (prefix, base) pairs
+pub fn url_rewrites() -> Vec<(String, String)> {
+    // Read outside any repository so a project's own config does not apply,
+    // matching what `git clone` sees
+    let output = git_command()
+        .current_dir(std::env::temp_dir())
+        .args(["config", "-z", "--get-regexp", r"^url\..*\.insteadof$"])
+        .output();
+    let Ok(output) = output else {
+        return Vec::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter_map(|entry| {
+            let (key, prefix) = entry.split_once('\n')?;
+            let base = key.strip_prefix("url.")?.strip_suffix(".insteadof")?;
+            (!prefix.is_empty()).then(|| (prefix.to_string(), base.to_string()))
+        })
+        .collect()
+}
+
+/// `url` with the longest matching `insteadOf` prefix replaced by its base,
+/// as git rewrites it; `None` when no rule matches
+pub fn rewrite_url(url: &str, rewrites: &[(String, String)]) -> Option<String> {
+    rewrites
+        .iter()
+        .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(prefix, base)| format!("{}{}", base, &url[prefix.len()..]))
+}
+
+/// Set or, when `description` is blank, clear a branch description
+pub fn set_branch_description(repo_path: &Path, branch: &str, description: &str) -> Result<()> {
+    let key = format!("branch.{}.description", branch);
+    let description = description.trim();
+    let mut command = git_command();
+    command.args(["-C", &repo_path.to_string_lossy(), "config"]);
+    if description.is_empty() {
+        command.args(["--unset", &key]);
+    } else {
+        command.args([key.as_str(), description]);
+    }
+    let output = command
+        .logged_output()
+        .context("Failed to set branch description")?;
+
+    // Exit 5: unsetting a key that was never set
+    let already_unset = description.is_empty() && output.status.code() == Some(5);
+    if !output.status.success() && !already_unset {
+        anyhow::bail!(
+            "Failed to set branch description: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    Ok(())
+}
+
+/// Move a linked worktree (`git worktree move <from> <to>`)
+pub fn move_worktree(repo_path: &Path, from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "worktree",
+            "move",
+            &from.to_string_lossy(),
+            &to.to_string_lossy(),
+        ])
+        .logged_output()
+        .context("Failed to move worktree")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to move worktree: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// Point a branch that is not checked out at `target` (`git branch -f`).
+/// Callers check that this is a fast-forward first.
+pub fn force_update_local_branch(repo_path: &Path, branch: &str, target: &str) -> Result<()> {
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "branch",
+            "-f",
+            branch,
+            target,
+        ])
+        .logged_output()
+        .context("Failed to update branch")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to update {}: {}",
+            branch,
+            command_failure_detail(&output)
+        );
+    }
+
+    Ok(())
+}
+
+/// Fast-forward a local base branch to its remote-tracking branch: moving the
+/// ref when the branch is not checked out, or fast-forwarding `worktree` when
+/// it is. Never merges; diverged branches and dirty worktrees are skipped.
+pub fn update_base_branch(repo_path: &Path, base: &str, worktree: Option<&Path>) -> BaseUpdate {
+    let comparison = compare_base_branch(repo_path, base);
+    let outcome = match (comparison.remote_branch, comparison.local_vs_remote) {
+        (None, _) => BaseUpdateOutcome::Skipped("no remote branch".to_string()),
+        (Some(_), None) => BaseUpdateOutcome::Skipped("no local branch".to_string()),
+        (Some(_), Some(counts)) if counts.behind == 0 => BaseUpdateOutcome::UpToDate,
+        (Some(_), Some(counts)) if counts.ahead > 0 => BaseUpdateOutcome::Skipped(format!(
+            "diverged ({} local, {} remote commit(s))",
+            counts.ahead, counts.behind
+        )),
+        (Some(remote_branch), Some(counts)) => match worktree {
+            Some(worktree) => match get_status(worktree) {
+                Ok(WorktreeStatus::Clean) => {
+                    match fast_forward_worktree(worktree, &remote_branch) {
+                        Ok(()) => BaseUpdateOutcome::Pulled {
+                            worktree: worktree.to_path_buf(),
+                            commits: counts.behind,
+                        },
+                        Err(error) => BaseUpdateOutcome::Skipped(error.to_string()),
+                    }
+                }
+                Ok(_) => BaseUpdateOutcome::Skipped("worktree has uncommitted changes".to_string()),
+                Err(error) => BaseUpdateOutcome::Skipped(error.to_string()),
+            },
+            None => match force_update_local_branch(repo_path, base, &remote_branch) {
+                Ok(()) => BaseUpdateOutcome::FastForwarded {
+                    remote_branch,
+                    commits: counts.behind,
+                },
+                Err(error) => BaseUpdateOutcome::Skipped(error.to_string()),
+            },
+        },
+    };
+
+    BaseUpdate {
+        branch: base.to_string(),
+        outcome,
+    }
+}
+
+fn fast_forward_worktree(worktree_path: &Path, target: &str) -> Result<()> {
+    let output = git_command()
+        .args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "merge",
+            "--ff-only",
+            target,
+        ])
+        .logged_output()
+        .context("Failed to fast-forward worktree")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to fast-forward: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    Ok(())
+}
+
+/// Mark a repository directory as bare (`core.bare = true`)
+pub fn set_core_bare(repo_path: &Path) -> Result<()> {
+    let output = git_command()
+        .arg(format!("--git-dir={}", repo_path.display()))
+        .args(["config", "--bool", "core.bare", "true"])
+        .logged_output()
+        .context("Failed to configure bare repository")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to set core.bare: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    Ok(())
+}
+
+/// Register a worktree for `branch` without writing any files, with its index
+/// filled from the branch. Files already on disk can then be moved in and
+/// show up as unchanged.
+pub fn add_worktree_without_checkout(
+    bare_repo_path: &Path,
+    branch: &str,
+    worktree_path: &Path,
+) -> Result<()> {
+    let output = git_command()
+        .args([
+            "-C",
+            &bare_repo_path.to_string_lossy(),
+            "worktree",
+            "add",
+            "--no-checkout",
+            &worktree_path.to_string_lossy(),
+            branch,
+        ])
+        .logged_output()
+        .context("Failed to add worktree")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to add worktree: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    let output = git_command()
+        .args(["-C", &worktree_path.to_string_lossy(), "read-tree", "HEAD"])
+        .logged_output()
+        .context("Failed to fill worktree index")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to fill worktree index: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    Ok(())
+}
+
+/// Point linked worktrees back at the repository after its git directory
+/// moved, and the repository at worktrees that moved. Returns git's report of
+/// the links it rewrote, empty when nothing needed fixing.
+pub fn repair_worktrees(repo_path: &Path, worktree_paths: &[PathBuf]) -> Result<String> {
+    let mut args = vec![
+        "-C".to_string(),
+        repo_path.to_string_lossy().to_string(),
+        "worktree".to_string(),
+        "repair".to_string(),
+    ];
+    args.extend(
+        worktree_paths
+            .iter()
+            .map(|path| path.to_string_lossy().to_string()),
+    );
+
+    let output = git_command()
+        .args(&args)
+        .logged_output()
+        .context("Failed to repair worktrees")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to repair worktrees: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    // git reports each rewritten link on stderr
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok([stdout.trim(), stderr.trim()]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Whether the branch has `branch.<name>.remote` configured
+pub fn branch_has_remote(repo_path: &Path, branch: &str) -> bool {
+    git_config_value(repo_path, &format!("branch.{}.remote", branch))
+        .is_some_and(|remote| remote != ".")
+}
+
+/// `branch.<name>.remote` for every branch that tracks a remote
+pub fn branch_remotes(repo_path: &Path) -> HashMap<String, String> {
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "config",
+            "-z",
+            "--get-regexp",
+            r"^branch\..*\.remote$",
+        ])
+        .output();
+    let Ok(output) = output else {
+        return HashMap::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter_map(|entry| {
+            let (key, remote) = entry.split_once('\n')?;
+            let branch = key.strip_prefix("branch.")?.strip_suffix(".remote")?;
+            (remote != "." && !remote.is_empty()).then(|| (branch.to_string(), remote.to_string()))
+        })
+        .collect()
+}
+
+/// Upstream of every local branch that has one (`branch.<name>.remote/merge`),
+/// shortened the way git prints it, e.g. `origin/main`
+pub fn branch_upstreams(repo_path: &Path) -> HashMap<String, String> {
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "for-each-ref",
+            "--format=%(refname:short)%00%(upstream:short)",
+            "refs/heads/",
+        ])
+        .output();
+    let Ok(output) = output else {
+        return HashMap::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (branch, upstream) = line.split_once('\0')?;
+            (!upstream.is_empty()).then(|| (branch.to_string(), upstream.to_string()))
+        })
+        .collect()
+}
+
+/// Short tip hash and subject of every local branch, e.g. `1a2b3c4 Fix login`,
+/// read with one `for-each-ref`
+pub fn branch_heads(repo_path: &Path) -> HashMap<String, String> {
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "for-each-ref",
+            "--format=%(refname:short)%00%(objectname:short) %(contents:subject)",
+            "refs/heads/",
+        ])
+        .output();
+    let Ok(output) = output else {
+        return HashMap::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (branch, head) = line.split_once('\0')?;
+            Some((branch.to_string(), head.trim_end().to_string()))
+        })
+        .collect()
+}
+
+/// Remote `git fetch` uses for a worktree: its branch remote, else the default remote
+pub fn worktree_remote(worktree_path: &Path) -> Option<String> {
+    current_branch(worktree_path)
+        .and_then(|branch| branch_remote(worktree_path, &branch))
+        .or_else(|| default_remote(worktree_path))
+}
+
+/// Fetch the remote the worktree's branch tracks (or the default remote)
+pub fn fetch_worktree(worktree_path: &Path, cancel: &CancelToken) -> Result<()> {
+    let Some(remote) = worktree_remote(worktree_path) else {
+        anyhow::bail!("Failed to fetch: no remote configured");
+    };
+
+    let output = output_cancelable(
+        git_command().args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "fetch",
+            "--progress",
+            &remote,
+        ]),
+        cancel,
+        "Failed to fetch",
+    )?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to fetch: {}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+pub fn get_last_commit_time(path: &Path) -> Result<String> {
+    let output = git_command()
+        .args(["-C", &path.to_string_lossy(), "log", "-1", "--format=%ar"])
+        .output()
+        .context("Failed to get last commit time")?;
+
+    if !output.status.success() {
         anyhow::bail!("Failed to get last commit time");
     }
 
@@ -668,74 +1828,182 @@ pub fn get_worktree_details(path: &Path) -> Result<WorktreeDetails> {
     Ok(WorktreeDetails {
         status_summary: get_status_summary(path)?,
         recent_commits: get_recent_commit_graph(path, 8)?,
+        description: current_branch(path).and_then(|branch| branch_description(path, &branch)),
     })
 }
 
-pub fn get_status_summary(path: &Path) -> Result<String> {
+pub fn get_status_summary(path: &Path) -> Result<String> {
+    let output = git_command()
+        .args([
+            "-C",
+            &path.to_string_lossy(),
+            "status",
+            "--short",
+            "--branch",
+        ])
+        .output()
+        .context("Failed to get status summary")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to get status summary: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let branch = lines
+        .next()
+        .map(|line| line.trim_start_matches("## ").to_string())
+        .unwrap_or_else(|| "unknown branch".to_string());
+    let changed = lines.count();
+
+    if changed == 0 {
+        Ok(format!("{} · clean", branch))
+    } else {
+        Ok(format!(
+            "{} · {} changed file{}",
+            branch,
+            changed,
+            if changed == 1 { "" } else { "s" }
+        ))
+    }
+}
+
+pub fn get_recent_commit_graph(path: &Path, limit: usize) -> Result<Vec<String>> {
+    let output = git_command()
+        .args([
+            "-C",
+            &path.to_string_lossy(),
+            "log",
+            "--graph",
+            "--decorate",
+            "--date=short",
+            "--pretty=format:%h %ad%d %s",
+            &format!("-n{}", limit),
+        ])
+        .output()
+        .context("Failed to get recent commits")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to get recent commits: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// The newest `limit` commits reachable from the worktree's HEAD, for the
+/// log view.
+pub fn log_commits(path: &Path, limit: usize) -> Result<Vec<LogCommit>> {
+    let output = git_command()
+        .args([
+            "-C",
+            &path.to_string_lossy(),
+            "log",
+            "--date=short",
+            "--format=%h%x1f%an%x1f%ad%x1f%s",
+            &format!("-n{}", limit),
+        ])
+        .output()
+        .context("Failed to read git log")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to read git log: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\x1f');
+            Some(LogCommit {
+                hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Full message and `--stat` of one commit, as `git show` prints them.
+pub fn show_commit(path: &Path, hash: &str) -> Result<Vec<String>> {
     let output = git_command()
         .args([
             "-C",
             &path.to_string_lossy(),
-            "status",
-            "--short",
-            "--branch",
+            "show",
+            "--no-color",
+            "--stat",
+            "--format=fuller",
+            hash,
         ])
         .output()
-        .context("Failed to get status summary")?;
+        .context("Failed to show commit")?;
 
     if !output.status.success() {
         anyhow::bail!(
-            "Failed to get status summary: {}",
+            "Failed to show commit {}: {}",
+            hash,
             command_failure_detail(&output)
         );
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut lines = stdout.lines();
-    let branch = lines
-        .next()
-        .map(|line| line.trim_start_matches("## ").to_string())
-        .unwrap_or_else(|| "unknown branch".to_string());
-    let changed = lines.count();
-
-    if changed == 0 {
-        Ok(format!("{} · clean", branch))
-    } else {
-        Ok(format!(
-            "{} · {} changed file{}",
-            branch,
-            changed,
-            if changed == 1 { "" } else { "s" }
-        ))
-    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
 }
 
-pub fn get_recent_commit_graph(path: &Path, limit: usize) -> Result<Vec<String>> {
+/// Files reported by `git status`, with untracked directories expanded so
+/// every entry can be opened.
+pub fn list_changed_files(path: &Path) -> Result<Vec<ChangedFile>> {
     let output = git_command()
         .args([
             "-C",
             &path.to_string_lossy(),
-            "log",
-            "--graph",
-            "--decorate",
-            "--date=short",
-            "--pretty=format:%h %ad%d %s",
-            &format!("-n{}", limit),
+            "status",
+            "--porcelain",
+            "-z",
+            "--untracked-files=all",
         ])
         .output()
-        .context("Failed to get recent commits")?;
+        .context("Failed to list changed files")?;
 
     if !output.status.success() {
         anyhow::bail!(
-            "Failed to get recent commits: {}",
+            "Failed to list changed files: {}",
             command_failure_detail(&output)
         );
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|line| line.to_string())
-        .collect())
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut entries = stdout.split('\0').filter(|entry| !entry.is_empty());
+    let mut files = Vec::new();
+    while let Some(entry) = entries.next() {
+        if entry.len() < 4 {
+            continue;
+        }
+        let status = entry[..2].to_string();
+        if status.contains('R') || status.contains('C') {
+            // Renames and copies are followed by the original path
+            entries.next();
+        }
+        files.push(ChangedFile {
+            status,
+            path: entry[3..].to_string(),
+        });
+    }
+    Ok(files)
 }
 
 fn command_failure_detail(output: &std::process::Output) -> String {
@@ -775,13 +2043,14 @@ pub fn github_pr_statuses_for_worktrees(
 }
 
 fn github_repo_slug(repo_path: &Path) -> Option<String> {
+    let remote = default_remote(repo_path)?;
     let output = git_command()
         .args([
             "-C",
             &repo_path.to_string_lossy(),
             "remote",
             "get-url",
-            "origin",
+            &remote,
         ])
         .output()
         .ok()?;
@@ -814,6 +2083,84 @@ fn github_repo_slug_from_remote_url(url: &str) -> Option<String> {
     Some(format!("{}/{}", owner, repo))
 }
 
+/// Open a GitHub PR for the worktree's pushed branch with `gh pr create --fill`,
+/// which takes the title and body from its commits. Returns the PR URL.
+pub fn create_github_pr(worktree_path: &Path) -> Result<String> {
+    if github_repo_slug(worktree_path).is_none() {
+        anyhow::bail!("Failed to create PR: remote is not on GitHub");
+    }
+
+    let output = Command::new("gh")
+        .current_dir(worktree_path)
+        .args(["pr", "create", "--fill"])
+        .stdin(Stdio::null())
+        .logged_output()
+        .context("Failed to run gh")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to create PR: {}", stderr.trim());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().last().unwrap_or("").trim().to_string())
+}
+
+/// Point open GitHub PRs based on `old_base` at `new_base` via `gh pr edit`.
+/// Returns the retargeted PR numbers; non-GitHub remotes retarget nothing.
+pub fn retarget_github_pr_bases(
+    repo_path: &Path,
+    old_base: &str,
+    new_base: &str,
+) -> Result<Vec<String>> {
+    let Some(repo) = github_repo_slug(repo_path) else {
+        return Ok(Vec::new());
+    };
+
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "list",
+            "--repo",
+            &repo,
+            "--state",
+            "open",
+            "--base",
+            old_base,
+            "--json",
+            "number",
+            "--template",
+            r#"{{range .}}{{.number}}{{"\n"}}{{end}}"#,
+        ])
+        .logged_output()
+        .context("Failed to run gh")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to list PRs: {}", stderr.trim());
+    }
+
+    let numbers: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    for number in &numbers {
+        let output = Command::new("gh")
+            .args(["pr", "edit", number, "--repo", &repo, "--base", new_base])
+            .logged_output()
+            .context("Failed to run gh")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Failed to retarget PR #{}: {}", number, stderr.trim());
+        }
+    }
+
+    Ok(numbers)
+}
+
 fn github_pr_statuses_for_branches(
     repo: &str,
     branches: &[String],
@@ -883,7 +2230,40 @@ fn github_pr_status_from_label(label: &str) -> Option<GithubPrStatus> {
 }
 
 pub fn get_ahead_behind(path: &Path) -> Option<AheadBehind> {
-    // Get the upstream tracking branch
+    // Prefer the upstream tracking branch, then the same branch on its remote
+    if let Some(ahead_behind) = count_ahead_behind(path, "@{upstream}") {
+        return Some(ahead_behind);
+    }
+
+    let branch = current_branch(path)?;
+    let remote = branch_remote(path, &branch)?;
+    let remote_ref = format!("refs/remotes/{}/{}", remote, branch);
+    if !ref_exists(path, &remote_ref) {
+        return None;
+    }
+    count_ahead_behind(path, &remote_ref)
+}
+
+fn has_upstream(path: &Path) -> bool {
+    git_command()
+        .args([
+            "-C",
+            &path.to_string_lossy(),
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{upstream}",
+        ])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+fn count_ahead_behind(path: &Path, base: &str) -> Option<AheadBehind> {
+    count_ahead_behind_of(path, base, "HEAD")
+}
+
+fn count_ahead_behind_of(path: &Path, base: &str, head: &str) -> Option<AheadBehind> {
     let output = git_command()
         .args([
             "-C",
@@ -891,7 +2271,7 @@ pub fn get_ahead_behind(path: &Path) -> Option<AheadBehind> {
             "rev-list",
             "--left-right",
             "--count",
-            "@{upstream}...HEAD",
+            &format!("{}...{}", base, head),
         ])
         .output()
         .ok()?;
@@ -912,104 +2292,369 @@ pub fn get_ahead_behind(path: &Path) -> Option<AheadBehind> {
     }
 }
 
-pub fn clone_bare(url: &str, path: &Path) -> Result<()> {
+/// Clone `url` as a bare repository. `branch` becomes HEAD instead of the
+/// remote default; `depth` makes a shallow clone of that branch only.
+/// `on_progress` is called as git reports each progress update.
+pub fn clone_bare(
+    url: &str,
+    path: &Path,
+    branch: Option<&str>,
+    depth: Option<u32>,
+    mut on_progress: impl FnMut(CloneProgress),
+) -> Result<()> {
+    let mut args = vec![
+        "clone".to_string(),
+        "--bare".to_string(),
+        "--progress".to_string(),
+    ];
+    if let Some(branch) = branch {
+        args.push("--branch".to_string());
+        args.push(branch.to_string());
+    }
+    if let Some(depth) = depth {
+        args.push("--depth".to_string());
+        args.push(depth.to_string());
+    }
+    args.push(url.to_string());
+    args.push(path.to_string_lossy().to_string());
+
+    let mut child = git_command()
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to clone repository")?;
+
+    // Progress updates end in '\r' and finished phases in '\n'; read the
+    // stream as it arrives and keep everything else for the error message
+    let mut stderr = child.stderr.take().context("Failed to read git output")?;
+    let mut messages: Vec<String> = Vec::new();
+    let mut segment: Vec<u8> = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let read = match stderr.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error).context("Failed to read git output"),
+        };
+        for &byte in &buffer[..read] {
+            if byte != b'\r' && byte != b'\n' {
+                segment.push(byte);
+                continue;
+            }
+            let line = String::from_utf8_lossy(&segment).trim().to_string();
+            segment.clear();
+            match CloneProgress::parse(&line) {
+                Some(progress) => on_progress(progress),
+                None if !line.is_empty() => messages.push(line),
+                None => {}
+            }
+        }
+    }
+    let trailing = String::from_utf8_lossy(&segment).trim().to_string();
+    if !trailing.is_empty() {
+        messages.push(trailing);
+    }
+
+    let status = child.wait().context("Failed to clone repository")?;
+    if !status.success() {
+        anyhow::bail!("Failed to clone: {}", messages.join("\n"));
+    }
+
+    Ok(())
+}
+
+pub fn get_default_branch(bare_repo_path: &Path) -> Result<String> {
+    // Try to get the default branch from HEAD
     let output = git_command()
-        .args(["clone", "--bare", url, &path.to_string_lossy()])
+        .args([
+            "-C",
+            &bare_repo_path.to_string_lossy(),
+            "symbolic-ref",
+            "HEAD",
+        ])
         .output()
-        .context("Failed to clone repository")?;
+        .context("Failed to get default branch")?;
+
+    if output.status.success() {
+        let head = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        // refs/heads/main -> main
+        if let Some(branch) = head.strip_prefix("refs/heads/") {
+            return Ok(branch.to_string());
+        }
+    }
+
+    // Fallback: try common branch names
+    for branch in &["main", "master"] {
+        let check = git_command()
+            .args([
+                "-C",
+                &bare_repo_path.to_string_lossy(),
+                "show-ref",
+                "--verify",
+                "--quiet",
+                &format!("refs/heads/{}", branch),
+            ])
+            .status();
+
+        if check.map(|s| s.success()).unwrap_or(false) {
+            return Ok(branch.to_string());
+        }
+    }
+
+    // Default to main
+    Ok("main".to_string())
+}
+
+/// Explicit `<remote> <branch>` to pull from when the worktree's branch has no
+/// upstream but its remote (`branch.<name>.remote` or the default) has the branch
+pub fn pull_target(worktree_path: &Path) -> Option<(String, String)> {
+    if has_upstream(worktree_path) {
+        return None;
+    }
+    let branch = current_branch(worktree_path)?;
+    let (remote, remote_branch) = remote_branch_for(worktree_path, &branch)?;
+    ref_exists(
+        worktree_path,
+        &format!("refs/remotes/{}/{}", remote, remote_branch),
+    )
+    .then_some((remote, remote_branch))
+}
+
+/// Pull changes from remote for a worktree. Uses the upstream when set,
+/// otherwise the branch's own remote (see [`pull_target`]). A canceled pull
+/// is cleaned up like a canceled merge.
+pub fn pull_worktree(worktree_path: &Path, cancel: &CancelToken) -> Result<String> {
+    let mut command = git_command();
+    command.args(["-C", &worktree_path.to_string_lossy(), "pull", "--progress"]);
+    if let Some((remote, branch)) = pull_target(worktree_path) {
+        command.args([remote, branch]);
+    }
+    let output = output_cancelable_then(&mut command, cancel, "Failed to pull", || {
+        recover_interrupted_merge(worktree_path)
+    })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to pull: {}", stderr.trim());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.trim().to_string())
+}
+
+/// Push changes to remote for a worktree
+pub fn push_worktree(worktree_path: &Path, cancel: &CancelToken) -> Result<String> {
+    let output = output_cancelable(
+        git_command().args(["-C", &worktree_path.to_string_lossy(), "push", "--progress"]),
+        cancel,
+        "Failed to push",
+    )?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to push: {}", stderr.trim());
+    }
+
+    // Git push often outputs to stderr even on success
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = if stdout.trim().is_empty() {
+        stderr.trim().to_string()
+    } else {
+        stdout.trim().to_string()
+    };
+    Ok(message)
+}
+
+/// Force push a worktree's branch to its upstream with `--force-with-lease`,
+/// which refuses to overwrite remote commits that were not fetched yet
+pub fn force_push_worktree(worktree_path: &Path, cancel: &CancelToken) -> Result<String> {
+    if !has_upstream(worktree_path) {
+        anyhow::bail!("No upstream branch configured; push with P first");
+    }
+
+    let output = output_cancelable(
+        git_command().args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "push",
+            "--progress",
+            "--force-with-lease",
+        ]),
+        cancel,
+        "Failed to force push",
+    )?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to force push: {}", stderr.trim());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = if stdout.trim().is_empty() {
+        stderr.trim().to_string()
+    } else {
+        stdout.trim().to_string()
+    };
+    Ok(message)
+}
+
+/// Subject line of the worktree's HEAD commit
+pub fn head_subject(worktree_path: &Path) -> Result<String> {
+    let output = git_command()
+        .args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "log",
+            "-1",
+            "--format=%s",
+        ])
+        .output()
+        .context("Failed to read HEAD commit")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to read HEAD commit: {}",
+            command_failure_detail(&output)
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether the upstream already contains HEAD, so rewriting it changes
+/// pushed history
+pub fn head_is_published(worktree_path: &Path) -> bool {
+    git_command()
+        .args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "merge-base",
+            "--is-ancestor",
+            "HEAD",
+            "@{upstream}",
+        ])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Fold the staged changes into HEAD with `git commit --amend`, keeping the
+/// message (`--no-edit`) unless a new one is given
+pub fn amend_head_commit(worktree_path: &Path, message: Option<&str>) -> Result<()> {
+    let mut command = git_command();
+    command.args(["-C", &worktree_path.to_string_lossy(), "commit", "--amend"]);
+    match message.map(str::trim).filter(|message| !message.is_empty()) {
+        Some(message) => command.args(["-m", message]),
+        None => command.arg("--no-edit"),
+    };
+    let output = command.logged_output().context("Failed to amend commit")?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to clone: {}", stderr.trim());
+        anyhow::bail!(
+            "Failed to amend commit: {}",
+            command_failure_detail(&output)
+        );
     }
 
     Ok(())
 }
 
-pub fn get_default_branch(bare_repo_path: &Path) -> Result<String> {
-    // Try to get the default branch from HEAD
+/// Throw away every change in a worktree: staged and unstaged edits go back
+/// to HEAD, then `git clean` removes untracked (and, for
+/// `DiscardMode::Ignored`, ignored) files.
+pub fn discard_changes(worktree_path: &Path, mode: DiscardMode) -> Result<()> {
+    let path = worktree_path.to_string_lossy();
     let output = git_command()
         .args([
             "-C",
-            &bare_repo_path.to_string_lossy(),
-            "symbolic-ref",
-            "HEAD",
+            &path,
+            "restore",
+            "--source=HEAD",
+            "--staged",
+            "--worktree",
+            "--",
+            ".",
         ])
-        .output()
-        .context("Failed to get default branch")?;
-
-    if output.status.success() {
-        let head = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        // refs/heads/main -> main
-        if let Some(branch) = head.strip_prefix("refs/heads/") {
-            return Ok(branch.to_string());
-        }
+        .logged_output()
+        .context("Failed to restore tracked files")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to restore tracked files: {}",
+            command_failure_detail(&output)
+        );
     }
 
-    // Fallback: try common branch names
-    for branch in &["main", "master"] {
-        let check = git_command()
-            .args([
-                "-C",
-                &bare_repo_path.to_string_lossy(),
-                "show-ref",
-                "--verify",
-                "--quiet",
-                &format!("refs/heads/{}", branch),
-            ])
-            .status();
-
-        if check.map(|s| s.success()).unwrap_or(false) {
-            return Ok(branch.to_string());
-        }
+    let clean_flags = match mode {
+        DiscardMode::Tracked => return Ok(()),
+        DiscardMode::Untracked => "-fd",
+        DiscardMode::Ignored => "-fdx",
+    };
+    let output = git_command()
+        .args(["-C", &path, "clean", clean_flags])
+        .logged_output()
+        .context("Failed to remove untracked files")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to remove untracked files: {}",
+            command_failure_detail(&output)
+        );
     }
 
-    // Default to main
-    Ok("main".to_string())
+    Ok(())
 }
 
-/// Pull changes from remote for a worktree
-pub fn pull_worktree(worktree_path: &Path) -> Result<String> {
-    let output = git_command()
-        .args(["-C", &worktree_path.to_string_lossy(), "pull"])
-        .output()
-        .context("Failed to pull")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to pull: {}", stderr.trim());
+/// Remote and branch for a first push when the current branch has no upstream.
+/// Uses `remote.pushDefault` when set, otherwise the branch's remote.
+pub fn missing_upstream_push_target(worktree_path: &Path) -> Option<(String, String)> {
+    if has_upstream(worktree_path) {
+        return None;
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(stdout.trim().to_string())
+    let branch = current_branch(worktree_path)?;
+    let remotes = list_remotes(worktree_path);
+    let remote = git_config_value(worktree_path, "remote.pushDefault")
+        .filter(|remote| remotes.contains(remote))
+        .or_else(|| branch_remote(worktree_path, &branch))?;
+    Some((remote, branch))
 }
 
-/// Push changes to remote for a worktree
-pub fn push_worktree(worktree_path: &Path) -> Result<String> {
-    let output = git_command()
-        .args(["-C", &worktree_path.to_string_lossy(), "push"])
-        .output()
-        .context("Failed to push")?;
+/// Push a branch and record it as the upstream (`git push -u <remote> <branch>`)
+pub fn push_worktree_set_upstream(
+    worktree_path: &Path,
+    remote: &str,
+    branch: &str,
+    cancel: &CancelToken,
+) -> Result<String> {
+    let output = output_cancelable(
+        git_command().args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "push",
+            "--progress",
+            "-u",
+            remote,
+            branch,
+        ]),
+        cancel,
+        "Failed to push",
+    )?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         anyhow::bail!("Failed to push: {}", stderr.trim());
     }
 
-    // Git push often outputs to stderr even on success
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let message = if stdout.trim().is_empty() {
-        stderr.trim().to_string()
-    } else {
-        stdout.trim().to_string()
-    };
-    Ok(message)
+    Ok(format!("upstream set to {}/{}", remote, branch))
 }
 
 /// Merge upstream branch into a worktree
 /// Finds the configured upstream and merges it
-pub fn merge_upstream(worktree_path: &Path) -> Result<String> {
+pub fn merge_upstream(worktree_path: &Path, cancel: &CancelToken) -> Result<String> {
     // First, get the upstream branch
     let upstream_output = git_command()
         .args([
@@ -1023,7 +2668,7 @@ pub fn merge_upstream(worktree_path: &Path) -> Result<String> {
         .context("Failed to get upstream")?;
 
     if !upstream_output.status.success() {
-        anyhow::bail!("No upstream branch configured");
+        anyhow::bail!("No upstream branch configured; pick one with B");
     }
 
     let upstream = String::from_utf8_lossy(&upstream_output.stdout)
@@ -1031,10 +2676,12 @@ pub fn merge_upstream(worktree_path: &Path) -> Result<String> {
         .to_string();
 
     // Merge the upstream
-    let output = git_command()
-        .args(["-C", &worktree_path.to_string_lossy(), "merge", &upstream])
-        .output()
-        .context("Failed to merge upstream")?;
+    let output = output_cancelable_then(
+        git_command().args(["-C", &worktree_path.to_string_lossy(), "merge", &upstream]),
+        cancel,
+        "Failed to merge upstream",
+        || recover_interrupted_merge(worktree_path),
+    )?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1045,17 +2692,24 @@ pub fn merge_upstream(worktree_path: &Path) -> Result<String> {
     Ok(format!("Merged {} - {}", upstream, stdout.trim()))
 }
 
-/// Merge a specific branch into a worktree
-pub fn merge_branch(worktree_path: &Path, source_branch: &str) -> Result<String> {
-    let output = git_command()
-        .args([
+/// Merge a specific branch into a worktree. A canceled merge is aborted so
+/// the worktree is left as it was.
+pub fn merge_branch(
+    worktree_path: &Path,
+    source_branch: &str,
+    cancel: &CancelToken,
+) -> Result<String> {
+    let output = output_cancelable_then(
+        git_command().args([
             "-C",
             &worktree_path.to_string_lossy(),
             "merge",
             source_branch,
-        ])
-        .output()
-        .context("Failed to merge")?;
+        ]),
+        cancel,
+        "Failed to merge",
+        || recover_interrupted_merge(worktree_path),
+    )?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1066,6 +2720,52 @@ pub fn merge_branch(worktree_path: &Path, source_branch: &str) -> Result<String>
     Ok(stdout.trim().to_string())
 }
 
+/// Local branches whose tips are reachable from the ref `into` (`git branch --merged`)
+pub fn merged_branches(repo_path: &Path, into: &str) -> Result<HashSet<String>> {
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "for-each-ref",
+            "--format=%(refname:short)",
+            &format!("--merged={}", into),
+            "refs/heads/",
+        ])
+        .output()
+        .context("Failed to list merged branches")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to list merged branches: {}", stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|branch| !branch.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Local branches already merged into `<remote>/<default>` (the local default
+/// branch when there is no remote copy). The default branch and branches still
+/// at that tip are left out: they have nothing of their own to be merged.
+pub fn merged_into_default(repo_path: &Path) -> Result<HashSet<String>> {
+    let default_branch = get_default_branch(repo_path)?;
+    let target = default_remote(repo_path)
+        .map(|remote| format!("refs/remotes/{}/{}", remote, default_branch))
+        .filter(|target| resolve_commit(repo_path, target).is_some())
+        .unwrap_or_else(|| format!("refs/heads/{}", default_branch));
+    let target_tip = resolve_commit(repo_path, &target);
+
+    let mut merged = merged_branches(repo_path, &target)?;
+    merged.remove(&default_branch);
+    merged.retain(|branch| {
+        resolve_commit(repo_path, &format!("refs/heads/{}", branch)) != target_tip
+    });
+    Ok(merged)
+}
+
 /// List local branches for merge selection
 pub fn list_local_branches(bare_repo_path: &Path) -> Result<Vec<String>> {
     let output = git_command()
@@ -1094,144 +2794,155 @@ pub fn list_local_branches(bare_repo_path: &Path) -> Result<Vec<String>> {
     Ok(branches)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{
-        add_worktree, fetch_remote_branch, get_worktree_details, get_worktree_root,
-        github_pr_statuses_for_worktrees, github_pr_statuses_from_gh_template,
-        github_repo_slug_from_remote_url, list_worktrees, remove_completed_pr_worktree,
-        remove_worktree,
-    };
-    use std::fs;
-    use std::io::Write;
-    use std::os::fd::AsRawFd;
-    use std::path::{Path, PathBuf};
-    use std::process::{Command, Output};
-    use std::sync::{Mutex, OnceLock};
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    use crate::types::GithubPrStatus;
-
-    fn temp_dir(name: &str) -> PathBuf {
-        let id = std::process::id();
-        let ts = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!("owt_git_unit_{}_{}_{}", name, id, ts));
-        let _ = fs::remove_dir_all(&path);
-        fs::create_dir_all(&path).unwrap();
-        path
-    }
+/// Remote-tracking branches such as `origin/main`, without `<remote>/HEAD`
+pub fn list_remote_branches(repo_path: &Path) -> Result<Vec<String>> {
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "for-each-ref",
+            "--format=%(refname:short)%00%(symref)",
+            "refs/remotes/",
+        ])
+        .output()
+        .context("Failed to list remote branches")?;
 
-    fn git_cmd() -> Command {
-        let mut cmd = Command::new("git");
-        cmd.env_remove("GIT_DIR")
-            .env_remove("GIT_WORK_TREE")
-            .env_remove("GIT_INDEX_FILE")
-            .env_remove("GIT_COMMON_DIR");
-        cmd
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to list remote branches: {}",
+            command_failure_detail(&output)
+        );
     }
 
-    fn create_test_bare_repo(path: &PathBuf) -> String {
-        let temp = path.parent().unwrap().join("temp_init");
-        fs::create_dir_all(&temp).unwrap();
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (branch, symref) = line.split_once('\0')?;
+            symref.is_empty().then(|| branch.to_string())
+        })
+        .collect())
+}
 
-        let init_output = git_cmd()
-            .current_dir(&temp)
-            .args(["init"])
-            .output()
-            .unwrap();
-        assert!(
-            init_output.status.success(),
-            "git init failed: {}",
-            String::from_utf8_lossy(&init_output.stderr)
-        );
+/// `git fetch <default remote>` from the repository, for `auto_fetch`
+pub fn fetch_default_remote(repo_path: &Path, cancel: &CancelToken) -> Result<()> {
+    let Some(remote) = default_remote(repo_path) else {
+        anyhow::bail!("Failed to fetch: no remote configured");
+    };
 
-        let config_email = git_cmd()
-            .current_dir(&temp)
-            .args(["config", "user.email", "test@test.com"])
-            .output()
-            .unwrap();
-        assert!(
-            config_email.status.success(),
-            "git config email failed: {}",
-            String::from_utf8_lossy(&config_email.stderr)
-        );
+    let output = output_cancelable(
+        git_command().args(["-C", &repo_path.to_string_lossy(), "fetch", &remote]),
+        cancel,
+        "Failed to fetch",
+    )?;
 
-        let config_name = git_cmd()
-            .current_dir(&temp)
-            .args(["config", "user.name", "Test"])
-            .output()
-            .unwrap();
-        assert!(
-            config_name.status.success(),
-            "git config name failed: {}",
-            String::from_utf8_lossy(&config_name.stderr)
-        );
+    if !output.status.success() {
+        anyhow::bail!("Failed to fetch: {}", command_failure_detail(&output));
+    }
+    Ok(())
+}
 
-        fs::write(temp.join("README.md"), "# Test").unwrap();
+/// `git fetch --all --prune` from the repository, for the remote branch browser
+pub fn fetch_all_remotes(repo_path: &Path, cancel: &CancelToken) -> Result<()> {
+    let output = output_cancelable(
+        git_command().args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "fetch",
+            "--all",
+            "--prune",
+        ]),
+        cancel,
+        "Failed to fetch",
+    )?;
 
-        let add_output = git_cmd()
-            .current_dir(&temp)
-            .args(["add", "."])
-            .output()
-            .unwrap();
-        assert!(
-            add_output.status.success(),
-            "git add failed: {}",
-            String::from_utf8_lossy(&add_output.stderr)
-        );
+    if !output.status.success() {
+        anyhow::bail!("Failed to fetch: {}", command_failure_detail(&output));
+    }
+    Ok(())
+}
 
-        let commit_output = git_cmd()
-            .current_dir(&temp)
-            .args(["commit", "-m", "Initial commit"])
-            .output()
-            .unwrap();
-        assert!(
-            commit_output.status.success(),
-            "git commit failed: {}",
-            String::from_utf8_lossy(&commit_output.stderr)
-        );
+/// Point the worktree's current branch at `upstream` (`git branch
+/// --set-upstream-to`)
+pub fn set_upstream(worktree_path: &Path, upstream: &str) -> Result<()> {
+    let output = git_command()
+        .args([
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "branch",
+            &format!("--set-upstream-to={}", upstream),
+        ])
+        .logged_output()
+        .context("Failed to set upstream")?;
 
-        let branch_output = git_cmd()
-            .current_dir(&temp)
-            .args(["branch", "--show-current"])
-            .output()
-            .unwrap();
-        assert!(
-            branch_output.status.success(),
-            "git branch --show-current failed: {}",
-            String::from_utf8_lossy(&branch_output.stderr)
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to set upstream: {}",
+            command_failure_detail(&output)
         );
-        let branch = String::from_utf8_lossy(&branch_output.stdout)
-            .trim()
-            .to_string();
-        assert!(!branch.is_empty(), "current branch should not be empty");
+    }
 
-        let clone_output = git_cmd()
-            .args([
-                "clone",
-                "--bare",
-                &temp.to_string_lossy(),
-                &path.to_string_lossy(),
-            ])
-            .output()
-            .unwrap();
-        assert!(
-            clone_output.status.success(),
-            "git clone --bare failed: {}",
-            String::from_utf8_lossy(&clone_output.stderr)
-        );
+    Ok(())
+}
 
-        let _ = fs::remove_dir_all(&temp);
-        branch
+/// Local branch names, most recently committed first.
+pub fn recent_local_branches(repo_path: &Path, limit: usize) -> Vec<String> {
+    let output = git_command()
+        .args([
+            "-C",
+            &repo_path.to_string_lossy(),
+            "for-each-ref",
+            "--sort=-committerdate",
+            &format!("--count={}", limit),
+            "--format=%(refname:short)",
+            "refs/heads/",
+        ])
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+        _ => Vec::new(),
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_worktree, add_worktree_sparse, branch_description, branch_descriptions,
+        branch_has_remote, branch_heads, branch_name_problem, branch_remote, branch_remotes,
+        branch_upstreams, clone_bare, compare_base_branch, current_branch, default_remote,
+        delete_remote_branch, fetch_remote_branch, force_push_worktree, get_ahead_behind,
+        get_worktree_details, get_worktree_root, github_pr_statuses_for_worktrees,
+        github_pr_statuses_from_gh_template, github_repo_slug_from_remote_url, lfs_pull,
+        list_changed_files, list_worktrees, log_commits, merge_branch, merged_into_default,
+        missing_upstream_push_target, move_worktree, needs_terminal_prompt, output_cancelable,
+        parse_progress, pull_target, pull_worktree, push_worktree_set_upstream,
+        read_stderr_with_progress, remote_branch_for, remove_completed_pr_worktree,
+        remove_worktree, rename_branch, resolve_commit, rewrite_url, set_branch_description,
+        show_commit, update_base_branch, update_submodules, uses_lfs, worktree_remote, CancelToken,
+    };
+    use std::fs;
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Output};
+    use std::sync::{Mutex, OnceLock};
+
+    use crate::test_support::{create_test_regular_repo, git_cmd, git_in, temp_dir};
+    use crate::types::{BaseUpdateOutcome, GithubPrStatus};
 
-    fn create_test_regular_repo(path: &Path) -> String {
-        fs::create_dir_all(path).unwrap();
+    fn create_test_bare_repo(path: &Path) -> String {
+        let temp = path.parent().unwrap().join("temp_init");
+        fs::create_dir_all(&temp).unwrap();
 
-        let init_output = git_cmd().current_dir(path).args(["init"]).output().unwrap();
+        let init_output = git_cmd()
+            .current_dir(&temp)
+            .args(["init"])
+            .output()
+            .unwrap();
         assert!(
             init_output.status.success(),
             "git init failed: {}",
@@ -1239,7 +2950,7 @@ mod tests {
         );
 
         let config_email = git_cmd()
-            .current_dir(path)
+            .current_dir(&temp)
             .args(["config", "user.email", "test@test.com"])
             .output()
             .unwrap();
@@ -1250,7 +2961,7 @@ mod tests {
         );
 
         let config_name = git_cmd()
-            .current_dir(path)
+            .current_dir(&temp)
             .args(["config", "user.name", "Test"])
             .output()
             .unwrap();
@@ -1260,9 +2971,10 @@ mod tests {
             String::from_utf8_lossy(&config_name.stderr)
         );
 
-        fs::write(path.join("README.md"), "# Test").unwrap();
+        fs::write(temp.join("README.md"), "# Test").unwrap();
+
         let add_output = git_cmd()
-            .current_dir(path)
+            .current_dir(&temp)
             .args(["add", "."])
             .output()
             .unwrap();
@@ -1273,7 +2985,7 @@ mod tests {
         );
 
         let commit_output = git_cmd()
-            .current_dir(path)
+            .current_dir(&temp)
             .args(["commit", "-m", "Initial commit"])
             .output()
             .unwrap();
@@ -1284,7 +2996,7 @@ mod tests {
         );
 
         let branch_output = git_cmd()
-            .current_dir(path)
+            .current_dir(&temp)
             .args(["branch", "--show-current"])
             .output()
             .unwrap();
@@ -1293,10 +3005,28 @@ mod tests {
             "git branch --show-current failed: {}",
             String::from_utf8_lossy(&branch_output.stderr)
         );
-
-        String::from_utf8_lossy(&branch_output.stdout)
+        let branch = String::from_utf8_lossy(&branch_output.stdout)
             .trim()
-            .to_string()
+            .to_string();
+        assert!(!branch.is_empty(), "current branch should not be empty");
+
+        let clone_output = git_cmd()
+            .args([
+                "clone",
+                "--bare",
+                &temp.to_string_lossy(),
+                &path.to_string_lossy(),
+            ])
+            .output()
+            .unwrap();
+        assert!(
+            clone_output.status.success(),
+            "git clone --bare failed: {}",
+            String::from_utf8_lossy(&clone_output.stderr)
+        );
+
+        let _ = fs::remove_dir_all(&temp);
+        branch
     }
 
     #[cfg(unix)]
@@ -1352,8 +3082,53 @@ mod tests {
         (stdout, stderr)
     }
 
-    fn git_in(path: &Path, args: &[&str]) -> Output {
-        git_cmd().current_dir(path).args(args).output().unwrap()
+    #[test]
+    fn branch_name_problem_agrees_with_check_ref_format() {
+        let names = [
+            "feature/login",
+            "hotfix/bug-123",
+            "v1.2",
+            "feature login",
+            "-dash",
+            "a..b",
+            "a@{b",
+            "/lead",
+            "trail/",
+            "a//b",
+            "dot.",
+            "x/.hidden",
+            "x.lock",
+            "x.lock/y",
+            "what?",
+            "star*",
+            "tilde~1",
+            "caret^",
+            "col:on",
+            "br[acket",
+            "back\\slash",
+            "tab\tname",
+            "HEAD",
+            "",
+        ];
+        for name in names {
+            let valid = git_cmd()
+                .args(["check-ref-format", "--branch", name])
+                .output()
+                .unwrap()
+                .status
+                .success();
+            assert_eq!(
+                branch_name_problem(name).is_none(),
+                valid,
+                "{:?}: {:?}",
+                name,
+                branch_name_problem(name)
+            );
+        }
+        assert_eq!(
+            branch_name_problem("feature login"),
+            Some("cannot contain spaces")
+        );
     }
 
     fn assert_git_success(output: &Output, context: &str) {
@@ -1545,6 +3320,218 @@ mod tests {
         path.canonicalize().unwrap()
     }
 
+    #[test]
+    fn output_cancelable_kills_the_child_when_canceled() {
+        let cancel = CancelToken::default();
+        let canceler = cancel.clone();
+        let started = std::time::Instant::now();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            canceler.cancel();
+        });
+
+        let error = output_cancelable(Command::new("sleep").arg("30"), &cancel, "sleep")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "Canceled");
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        assert!(output_cancelable(Command::new("true").arg(""), &cancel, "true").is_err());
+
+        let timed = CancelToken::with_timeout(Some(std::time::Duration::from_secs(1)));
+        let started = std::time::Instant::now();
+        let error = output_cancelable(Command::new("sleep").arg("30"), &timed, "sleep")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "sleep: timed out after 1s");
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn canceled_merge_leaves_the_worktree_usable() {
+        let base = temp_dir("canceled_merge");
+        let repo = base.join("repo");
+        let branch = create_test_regular_repo(&repo);
+        // A merge driver that hangs, so the merge is killed holding index.lock
+        assert_git_success(
+            &git_in(&repo, &["config", "merge.slow.driver", "sleep 30; false"]),
+            "git config failed",
+        );
+        write_and_commit(
+            &repo,
+            ".gitattributes",
+            "README.md merge=slow\n",
+            "attributes",
+        );
+        assert_git_success(
+            &git_in(&repo, &["checkout", "-b", "side"]),
+            "checkout failed",
+        );
+        write_and_commit(&repo, "README.md", "side\n", "side");
+        assert_git_success(&git_in(&repo, &["checkout", &branch]), "checkout failed");
+        let head = write_and_commit(&repo, "README.md", "main\n", "main");
+
+        let cancel = CancelToken::default();
+        let canceler = cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            canceler.cancel();
+        });
+        let started = std::time::Instant::now();
+        let error = merge_branch(&repo, "side", &cancel)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "Canceled");
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+        assert!(!repo.join(resolve_git_path(&repo, "index.lock")).exists());
+        assert!(!repo.join(resolve_git_path(&repo, "MERGE_HEAD")).exists());
+        assert_git_success(&git_in(&repo, &["status"]), "git status failed");
+        assert_git_success(&git_in(&repo, &["add", "README.md"]), "git add failed");
+        assert_eq!(
+            String::from_utf8_lossy(&git_in(&repo, &["rev-parse", "HEAD"]).stdout).trim(),
+            head
+        );
+        assert_eq!(
+            fs::read_to_string(repo.join("README.md")).unwrap(),
+            "main\n"
+        );
+
+        let _ = fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn output_cancelable_kills_the_child_at_the_timeout_and_logs_no_exit_code() {
+        let marker = format!("owt-timeout-{}", std::process::id());
+        let mut command = Command::new("sh");
+        command.args(["-c", &format!("sleep 5 # {}", marker)]);
+        let timed = CancelToken::with_timeout(Some(std::time::Duration::from_millis(200)));
+
+        let started = std::time::Instant::now();
+        let error = output_cancelable(&mut command, &timed, "sleep")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("timed out after"), "{}", error);
+        assert!(
+            started.elapsed() < std::time::Duration::from_secs(2),
+            "took {:?}",
+            started.elapsed()
+        );
+
+        let record = crate::command_log::snapshot()
+            .into_iter()
+            .rfind(|record| record.command.contains(&marker))
+            .expect("the timed out command should be logged");
+        assert_eq!(record.exit_code, None);
+        assert!(
+            record.output.contains("timed out after"),
+            "{}",
+            record.output
+        );
+    }
+
+    #[test]
+    fn lfs_is_detected_from_gitattributes_and_skipped_without_it() {
+        let base = temp_dir("uses_lfs");
+        fs::create_dir_all(&base).unwrap();
+        assert!(!uses_lfs(&base));
+        assert!(lfs_pull(&base, &CancelToken::default()).is_ok_and(|ran| !ran));
+
+        fs::write(
+            base.join(".gitattributes"),
+            "# *.bin filter=lfs\n*.txt text eol=lf\n",
+        )
+        .unwrap();
+        assert!(!uses_lfs(&base));
+        fs::write(
+            base.join(".gitattributes"),
+            "*.psd filter=lfs diff=lfs merge=lfs -text\n",
+        )
+        .unwrap();
+        assert!(uses_lfs(&base));
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn progress_meter_is_parsed_and_kept_out_of_stderr() {
+        assert_eq!(
+            parse_progress("remote: Counting objects:  45% (450/1000)\r").as_deref(),
+            Some("Counting 45% (450/1000)")
+        );
+        assert_eq!(
+            parse_progress("Receiving objects: 100% (1000/1000), 1.20 MiB | 2.00 MiB/s, done.")
+                .as_deref(),
+            Some("Receiving 100% (1000/1000)")
+        );
+        assert_eq!(
+            parse_progress("Downloading LFS objects:  50% (1/2), 1.0 MB | 2.0 MB/s\r").as_deref(),
+            Some("Downloading 50% (1/2)")
+        );
+        assert_eq!(parse_progress("From github.com:owner/repo"), None);
+        assert_eq!(parse_progress("error: 50% of nothing"), None);
+
+        let progress = Mutex::new(None);
+        let stderr = "Receiving objects:  10% (1/10)\rReceiving objects: 100% (10/10), done.\n\
+                      From example.com:repo\n   abc..def  main -> origin/main\n";
+        let kept = read_stderr_with_progress(stderr.as_bytes(), &progress);
+        assert_eq!(
+            String::from_utf8(kept).unwrap(),
+            "From example.com:repo\n   abc..def  main -> origin/main\n"
+        );
+        assert_eq!(
+            progress.into_inner().unwrap().as_deref(),
+            Some("Receiving 100% (10/10)")
+        );
+
+        let progress = Mutex::new(None);
+        let stderr = "Counting objects:  50% (1/2)\rerror: RPC failed\rfatal: early EOF\r\n";
+        let kept = read_stderr_with_progress(stderr.as_bytes(), &progress);
+        assert_eq!(
+            String::from_utf8(kept).unwrap(),
+            "error: RPC failed\rfatal: early EOF\r\n"
+        );
+    }
+
+    #[test]
+    fn output_cancelable_keeps_commands_that_needed_a_prompt() {
+        let cancel = CancelToken::default();
+        let output = output_cancelable(
+            Command::new("sh").args(["-c", "echo ok; exit 1"]),
+            &cancel,
+            "sh",
+        )
+        .unwrap();
+        assert!(!output.status.success());
+        assert_eq!(cancel.take_prompted(), None);
+
+        let mut command = Command::new("sh");
+        command.current_dir(std::env::temp_dir()).args([
+            "-c",
+            "echo \"fatal: could not read Username for 'https://example.com': terminal prompts disabled\" >&2; exit 128",
+        ]);
+        let output = output_cancelable(&mut command, &cancel, "sh").unwrap();
+        assert_eq!(output.status.code(), Some(128));
+        let prompted = cancel.take_prompted().unwrap();
+        assert_eq!(prompted.dir, Some(std::env::temp_dir()));
+        assert_eq!(prompted.args[0], "-c");
+        assert_eq!(cancel.take_prompted(), None);
+
+        assert!(needs_terminal_prompt(
+            "git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository."
+        ));
+        assert!(!needs_terminal_prompt(
+            "fatal: couldn't find remote ref nope"
+        ));
+
+        let output = output_cancelable(
+            Command::new("echo").arg("done"),
+            &CancelToken::default(),
+            "echo",
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "done\n");
+    }
+
     #[test]
     fn add_worktree_creates_usable_worktree_with_worktree_config_extension_enabled() {
         let base = temp_dir("add_worktree_worktree_config");
@@ -1567,6 +3554,35 @@ mod tests {
         let _ = fs::remove_dir_all(&base);
     }
 
+    #[test]
+    fn add_worktree_sparse_checks_out_only_configured_directories() {
+        let base = temp_dir("add_worktree_sparse");
+        let repo_path = base.join("repo");
+        let default_branch = create_test_regular_repo(&repo_path);
+        fs::create_dir_all(repo_path.join("docs")).unwrap();
+        fs::create_dir_all(repo_path.join("src")).unwrap();
+        fs::write(repo_path.join("src").join("lib.rs"), "").unwrap();
+        write_and_commit(&repo_path, "docs/index.md", "docs", "add docs and src");
+        let worktree_path = base.join("docs-only");
+
+        add_worktree_sparse(
+            &repo_path,
+            "docs-only",
+            &worktree_path,
+            Some(&default_branch),
+            &["docs".to_string()],
+        )
+        .unwrap();
+
+        assert!(worktree_path.join("docs").join("index.md").is_file());
+        assert!(!worktree_path.join("src").exists());
+        let status = git_in(&worktree_path, &["status", "--porcelain"]);
+        assert_git_success(&status, "git status failed");
+        assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
     #[test]
     fn add_worktree_works_from_non_bare_repository() {
         let base = temp_dir("add_worktree_non_bare");
@@ -1596,6 +3612,49 @@ mod tests {
         let _ = fs::remove_dir_all(&base);
     }
 
+    #[test]
+    fn list_worktrees_reports_locked_worktrees() {
+        let base = temp_dir("list_worktrees_locked");
+        let repo_path = base.join("repo");
+        let default_branch = create_test_regular_repo(&repo_path);
+        let locked_path = base.join("locked");
+        let unlocked_path = base.join("unlocked");
+
+        add_worktree(&repo_path, "locked", &locked_path, Some(&default_branch)).unwrap();
+        add_worktree(
+            &repo_path,
+            "unlocked",
+            &unlocked_path,
+            Some(&default_branch),
+        )
+        .unwrap();
+        assert_git_success(
+            &git_in(
+                &repo_path,
+                &[
+                    "worktree",
+                    "lock",
+                    "--reason",
+                    "on usb drive",
+                    &locked_path.to_string_lossy(),
+                ],
+            ),
+            "git worktree lock",
+        );
+
+        let worktrees = list_worktrees(&repo_path).unwrap();
+        let locked_of = |branch: &str| {
+            worktrees
+                .iter()
+                .find(|wt| wt.branch.as_deref() == Some(branch))
+                .map(|wt| wt.locked)
+        };
+        assert_eq!(locked_of("locked"), Some(true));
+        assert_eq!(locked_of("unlocked"), Some(false));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
     #[test]
     fn get_worktree_root_resolves_repo_root_from_subdirectory() {
         let base = temp_dir("get_worktree_root");
@@ -1629,9 +3688,7 @@ mod tests {
             .find(|line| line.contains("Initial commit"))
             .expect("recent commits should include the commit subject");
         assert!(
-            initial_commit
-                .split_whitespace()
-                .any(|token| is_short_commit_date(token)),
+            initial_commit.split_whitespace().any(is_short_commit_date),
             "recent commit should include a short date: {}",
             initial_commit
         );
@@ -1639,6 +3696,39 @@ mod tests {
         let _ = fs::remove_dir_all(&base);
     }
 
+    #[test]
+    fn branch_descriptions_round_trip_and_show_in_details() {
+        let base = temp_dir("branch_descriptions");
+        let bare_path = base.join("test.bare");
+        let branch = create_test_bare_repo(&bare_path);
+        let worktree_path = base.join("main");
+        add_worktree(&bare_path, &branch, &worktree_path, None).unwrap();
+
+        set_branch_description(&bare_path, &branch, "  Trunk\nreleased weekly ").unwrap();
+        set_branch_description(&bare_path, "release/1.2", "Hotfix line").unwrap();
+
+        assert_eq!(
+            branch_description(&bare_path, &branch).as_deref(),
+            Some("Trunk\nreleased weekly")
+        );
+        let descriptions = branch_descriptions(&bare_path);
+        assert_eq!(descriptions.len(), 2);
+        assert_eq!(descriptions["release/1.2"], "Hotfix line");
+        assert_eq!(
+            get_worktree_details(&worktree_path)
+                .unwrap()
+                .description
+                .as_deref(),
+            Some("Trunk\nreleased weekly")
+        );
+
+        set_branch_description(&bare_path, "release/1.2", " ").unwrap();
+        set_branch_description(&bare_path, "release/1.2", "").unwrap();
+        assert_eq!(branch_description(&bare_path, "release/1.2"), None);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
     fn is_short_commit_date(token: &str) -> bool {
         let bytes = token.as_bytes();
         bytes.len() == 10
@@ -1760,31 +3850,223 @@ mod tests {
             add_result
         );
 
-        enable_worktree_config(&bare_path);
+        enable_worktree_config(&bare_path);
+
+        let broken_status = git_output(&[
+            "-C",
+            &worktree_path.to_string_lossy(),
+            "status",
+            "--porcelain",
+        ]);
+        assert!(
+            !broken_status.status.success(),
+            "legacy nested worktree should fail before self-heal once extension is enabled"
+        );
+
+        let worktrees =
+            list_worktrees(&bare_path).expect("list_worktrees should succeed after self-heal");
+        let expected_path = canonicalize_existing(&worktree_path);
+        assert!(
+            worktrees
+                .iter()
+                .any(|wt| canonicalize_existing(&wt.path) == expected_path),
+            "list_worktrees should include the repaired legacy worktree"
+        );
+
+        assert_worktree_usable(&worktree_path);
+        assert_worktree_config_marks_non_bare(&worktree_path);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn compare_base_branch_fetches_and_counts_local_against_remote() {
+        let base = temp_dir("compare_base_branch");
+        let (source_path, bare_path) = create_source_and_bare_repo(&base);
+        assert_git_success(
+            &git_in(&source_path, &["checkout", "staging"]),
+            "git checkout staging failed",
+        );
+        write_and_commit(&source_path, "staging.txt", "v2\n", "staging v2");
+
+        let comparison = compare_base_branch(&bare_path, "staging");
+
+        assert!(comparison.fetched);
+        assert!(comparison.has_local);
+        assert_eq!(comparison.remote_branch.as_deref(), Some("origin/staging"));
+        let counts = comparison.local_vs_remote.unwrap();
+        assert_eq!((counts.ahead, counts.behind), (0, 1));
+
+        let missing = compare_base_branch(&bare_path, "does-not-exist");
+        assert!(!missing.fetched);
+        assert_eq!(missing.summary(), "not found");
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn list_changed_files_expands_untracked_dirs_and_follows_renames() {
+        let base = temp_dir("list_changed_files");
+        let repo_path = base.join("repo");
+        create_test_regular_repo(&repo_path);
+        write_and_commit(&repo_path, "old name.txt", "old", "add file");
+        assert_git_success(
+            &git_in(&repo_path, &["mv", "old name.txt", "new name.txt"]),
+            "git mv failed",
+        );
+        fs::write(repo_path.join("README.md"), "changed").unwrap();
+        fs::create_dir_all(repo_path.join("notes")).unwrap();
+        fs::write(repo_path.join("notes").join("todo.md"), "todo").unwrap();
+
+        let files = list_changed_files(&repo_path).unwrap();
+        let entries: Vec<(&str, &str)> = files
+            .iter()
+            .map(|file| (file.status.as_str(), file.path.as_str()))
+            .collect();
+
+        assert!(entries.contains(&(" M", "README.md")), "{:?}", entries);
+        assert!(entries.contains(&("R ", "new name.txt")), "{:?}", entries);
+        assert!(entries.contains(&("??", "notes/todo.md")), "{:?}", entries);
+        assert_eq!(entries.len(), 3, "{:?}", entries);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn log_commits_lists_newest_first_and_show_commit_includes_message_and_stat() {
+        let base = temp_dir("log_commits");
+        let repo_path = base.join("repo");
+        create_test_regular_repo(&repo_path);
+        write_and_commit(&repo_path, "a.txt", "a", "add a");
+        write_and_commit(
+            &repo_path,
+            "b.txt",
+            "b",
+            "add b\n\nLonger body explaining b",
+        );
+
+        let commits = log_commits(&repo_path, 2).unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].subject, "add b");
+        assert_eq!(commits[1].subject, "add a");
+        assert!(!commits[0].author.is_empty());
+        assert_eq!(commits[0].date.len(), "2024-01-01".len());
+
+        let detail = show_commit(&repo_path, &commits[0].hash).unwrap();
+        assert!(detail
+            .iter()
+            .any(|line| line.contains("Longer body explaining b")));
+        assert!(detail.iter().any(|line| line.contains("b.txt")));
+        assert!(!detail.iter().any(|line| line.starts_with("+b")));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn update_base_branch_moves_refs_and_fast_forwards_checked_out_worktrees() {
+        let base = temp_dir("update_base_branch");
+        let (source_path, bare_path) = create_source_and_bare_repo(&base);
+        let main_worktree = base.join("main");
+        add_worktree(&bare_path, "main", &main_worktree, None).unwrap();
+        write_and_commit(&source_path, "main.txt", "v2\n", "main v2");
+        assert_git_success(
+            &git_in(&source_path, &["checkout", "staging"]),
+            "git checkout staging failed",
+        );
+        write_and_commit(&source_path, "staging.txt", "v2\n", "staging v2");
+
+        let staging = update_base_branch(&bare_path, "staging", None);
+        assert_eq!(
+            staging.outcome,
+            BaseUpdateOutcome::FastForwarded {
+                remote_branch: "origin/staging".to_string(),
+                commits: 1
+            }
+        );
+        assert_eq!(
+            update_base_branch(&bare_path, "staging", None).outcome,
+            BaseUpdateOutcome::UpToDate
+        );
+
+        fs::write(main_worktree.join("README.md"), "local edit\n").unwrap();
+        let dirty = update_base_branch(&bare_path, "main", Some(&main_worktree));
+        assert!(matches!(dirty.outcome, BaseUpdateOutcome::Skipped(_)));
+        fs::write(main_worktree.join("README.md"), "# Test\n").unwrap();
+
+        let main = update_base_branch(&bare_path, "main", Some(&main_worktree));
+        assert_eq!(
+            main.outcome,
+            BaseUpdateOutcome::Pulled {
+                worktree: main_worktree.clone(),
+                commits: 1
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(main_worktree.join("main.txt")).unwrap(),
+            "v2\n"
+        );
+
+        let _ = fs::remove_dir_all(&base);
+    }
 
-        let broken_status = git_output(&[
-            "-C",
-            &worktree_path.to_string_lossy(),
-            "status",
-            "--porcelain",
-        ]);
-        assert!(
-            !broken_status.status.success(),
-            "legacy nested worktree should fail before self-heal once extension is enabled"
+    #[test]
+    fn rewrite_url_uses_the_longest_matching_insteadof() {
+        let rewrites = [
+            ("gh:".to_string(), "https://github.com/".to_string()),
+            (
+                "https://github.com/".to_string(),
+                "git@github.com:".to_string(),
+            ),
+            (
+                "https://github.com/acme/".to_string(),
+                "git@acme.example:".to_string(),
+            ),
+        ];
+
+        assert_eq!(
+            rewrite_url("gh:user/repo", &rewrites).as_deref(),
+            Some("https://github.com/user/repo")
+        );
+        assert_eq!(
+            rewrite_url("https://github.com/acme/api.git", &rewrites).as_deref(),
+            Some("git@acme.example:api.git")
+        );
+        assert_eq!(
+            rewrite_url("https://github.com/user/repo.git", &rewrites).as_deref(),
+            Some("git@github.com:user/repo.git")
         );
+        assert_eq!(rewrite_url("https://gitlab.com/user/repo", &rewrites), None);
+    }
+
+    #[test]
+    fn clone_bare_streams_progress_and_reports_failures() {
+        let base = temp_dir("clone_bare_progress");
+        let (source_path, _) = create_source_and_bare_repo(&base);
+        let url = format!("file://{}", source_path.display());
+
+        let mut phases: Vec<(String, u8)> = Vec::new();
+        clone_bare(&url, &base.join("progress.bare"), None, None, |progress| {
+            phases.push((progress.phase, progress.percent))
+        })
+        .unwrap();
 
-        let worktrees =
-            list_worktrees(&bare_path).expect("list_worktrees should succeed after self-heal");
-        let expected_path = canonicalize_existing(&worktree_path);
         assert!(
-            worktrees
-                .iter()
-                .any(|wt| canonicalize_existing(&wt.path) == expected_path),
-            "list_worktrees should include the repaired legacy worktree"
+            phases.contains(&("Receiving objects".to_string(), 100)),
+            "{:?}",
+            phases
         );
 
-        assert_worktree_usable(&worktree_path);
-        assert_worktree_config_marks_non_bare(&worktree_path);
+        let error = clone_bare(
+            &format!("file://{}", base.join("missing").display()),
+            &base.join("missing.bare"),
+            None,
+            None,
+            |_| {},
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.starts_with("Failed to clone:"), "{}", error);
+        assert!(error.contains("missing"), "{}", error);
 
         let _ = fs::remove_dir_all(&base);
     }
@@ -1833,7 +4115,7 @@ mod tests {
                 "missing remote branch should be a non-fatal lookup result: {:?}",
                 result
             );
-            assert_eq!(result.unwrap(), false);
+            assert!(!result.unwrap());
         });
 
         assert!(
@@ -1905,6 +4187,367 @@ mod tests {
         let _ = fs::remove_dir_all(&base);
     }
 
+    #[test]
+    fn fork_remote_without_origin_is_used_for_fetch_track_and_ahead_behind() {
+        let base = temp_dir("fork_remote_without_origin");
+        let (source_path, bare_path) = create_source_and_bare_repo(&base);
+        let review_path = base.join("review");
+        assert_git_success(
+            &git_in(&bare_path, &["remote", "rename", "origin", "upstream"]),
+            "git remote rename failed",
+        );
+        assert_git_success(
+            &git_in(
+                &bare_path,
+                &[
+                    "config",
+                    "remote.upstream.fetch",
+                    "+refs/heads/*:refs/remotes/upstream/*",
+                ],
+            ),
+            "git config fetch refspec failed",
+        );
+        assert_git_success(
+            &git_in(&source_path, &["checkout", "-b", "review"]),
+            "git checkout review failed",
+        );
+        write_and_commit(&source_path, "review.txt", "v1\n", "review v1");
+
+        assert_eq!(default_remote(&bare_path).as_deref(), Some("upstream"));
+        assert!(fetch_remote_branch(&bare_path, "review").unwrap());
+        add_worktree(&bare_path, "review", &review_path, Some("main")).unwrap();
+
+        assert_eq!(
+            branch_remote(&bare_path, "review").as_deref(),
+            Some("upstream")
+        );
+        assert_git_success(
+            &git_in(&review_path, &["config", "user.email", "test@test.com"]),
+            "git config user.email failed",
+        );
+        assert_git_success(
+            &git_in(&review_path, &["config", "user.name", "Test"]),
+            "git config user.name failed",
+        );
+        write_and_commit(&review_path, "local.txt", "local\n", "local work");
+        let ahead_behind = get_ahead_behind(&review_path).unwrap();
+        assert_eq!((ahead_behind.ahead, ahead_behind.behind), (1, 0));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn pull_without_upstream_uses_the_branch_remote() {
+        let base = temp_dir("pull_branch_remote");
+        let (source_path, bare_path) = create_source_and_bare_repo(&base);
+        let review_path = base.join("review");
+        add_worktree(&bare_path, "review", &review_path, Some("main")).unwrap();
+        assert_eq!(pull_target(&review_path), None);
+
+        assert_git_success(
+            &git_in(&source_path, &["checkout", "-b", "review"]),
+            "git checkout review failed",
+        );
+        write_and_commit(&source_path, "review.txt", "v1\n", "review v1");
+        assert_git_success(
+            &git_in(
+                &bare_path,
+                &["remote", "add", "colleague", &source_path.to_string_lossy()],
+            ),
+            "git remote add failed",
+        );
+        assert_git_success(
+            &git_in(&bare_path, &["fetch", "colleague"]),
+            "git fetch colleague failed",
+        );
+        assert_git_success(
+            &git_in(&bare_path, &["config", "branch.review.remote", "colleague"]),
+            "git config branch remote failed",
+        );
+
+        let remotes = branch_remotes(&bare_path);
+        assert_eq!(remotes.get("review").map(String::as_str), Some("colleague"));
+        assert_eq!(worktree_remote(&review_path).as_deref(), Some("colleague"));
+        assert_eq!(
+            pull_target(&review_path),
+            Some(("colleague".to_string(), "review".to_string()))
+        );
+
+        pull_worktree(&review_path, &CancelToken::default()).unwrap();
+        assert!(review_path.join("review.txt").exists());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn merged_into_default_follows_the_fetched_remote_default_branch() {
+        let base = temp_dir("merged_into_default");
+        let (source_path, bare_path) = create_source_and_bare_repo(&base);
+        assert_git_success(
+            &git_in(
+                &bare_path,
+                &[
+                    "config",
+                    "remote.origin.fetch",
+                    "+refs/heads/*:refs/remotes/origin/*",
+                ],
+            ),
+            "git config fetch refspec failed",
+        );
+        assert_git_success(
+            &git_in(&bare_path, &["fetch", "origin"]),
+            "git fetch origin failed",
+        );
+        let done_path = base.join("done");
+        add_worktree(&bare_path, "done", &done_path, Some("main")).unwrap();
+        add_worktree(&bare_path, "fresh", &base.join("fresh"), Some("main")).unwrap();
+        write_and_commit(&done_path, "done.txt", "done\n", "done");
+        assert!(merged_into_default(&bare_path).unwrap().is_empty());
+
+        // Merge `done` upstream; the badge appears once it is fetched
+        assert_git_success(
+            &git_in(
+                &source_path,
+                &["fetch", &done_path.to_string_lossy(), "done"],
+            ),
+            "git fetch done failed",
+        );
+        assert_git_success(
+            &git_in(
+                &source_path,
+                &["merge", "--no-ff", "-m", "merge done", "FETCH_HEAD"],
+            ),
+            "git merge done failed",
+        );
+        assert!(merged_into_default(&bare_path).unwrap().is_empty());
+        assert_git_success(
+            &git_in(&bare_path, &["fetch", "origin"]),
+            "git fetch origin failed",
+        );
+        let merged = merged_into_default(&bare_path).unwrap();
+        assert!(merged.contains("done"), "{:?}", merged);
+        assert!(!merged.contains("main"));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn first_push_sets_upstream_on_branch_remote() {
+        let base = temp_dir("first_push_sets_upstream");
+        let (source_path, bare_path) = create_source_and_bare_repo(&base);
+        let feature_path = base.join("feature");
+        assert_git_success(
+            &git_in(
+                &bare_path,
+                &[
+                    "config",
+                    "remote.origin.fetch",
+                    "+refs/heads/*:refs/remotes/origin/*",
+                ],
+            ),
+            "git config fetch refspec failed",
+        );
+        add_worktree(
+            &bare_path,
+            "feature/first-push",
+            &feature_path,
+            Some("main"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            missing_upstream_push_target(&feature_path),
+            Some(("origin".to_string(), "feature/first-push".to_string()))
+        );
+        let message = push_worktree_set_upstream(
+            &feature_path,
+            "origin",
+            "feature/first-push",
+            &CancelToken::default(),
+        )
+        .unwrap();
+        assert_eq!(message, "upstream set to origin/feature/first-push");
+
+        assert_eq!(missing_upstream_push_target(&feature_path), None);
+        let upstreams = branch_upstreams(&bare_path);
+        assert_eq!(
+            upstreams.get("feature/first-push").map(String::as_str),
+            Some("origin/feature/first-push")
+        );
+        assert_git_success(
+            &git_in(&feature_path, &["config", "user.email", "test@test.com"]),
+            "git config user.email failed",
+        );
+        assert_git_success(
+            &git_in(&feature_path, &["config", "user.name", "Test"]),
+            "git config user.name failed",
+        );
+        write_and_commit(&feature_path, "first.txt", "v1\n", "first");
+        assert!(force_push_worktree(&feature_path, &CancelToken::default()).is_ok());
+        assert_git_success(
+            &git_in(&feature_path, &["reset", "--hard", "HEAD~1"]),
+            "git reset failed",
+        );
+        write_and_commit(&feature_path, "rewritten.txt", "v2\n", "rewritten");
+        let head = branch_heads(&bare_path)
+            .remove("feature/first-push")
+            .unwrap();
+        let (hash, subject) = head.split_once(' ').unwrap();
+        assert!(resolve_commit(&feature_path, "HEAD")
+            .unwrap()
+            .starts_with(hash));
+        assert_eq!(subject, "rewritten");
+        assert!(
+            force_push_worktree(&feature_path, &CancelToken::default()).is_ok(),
+            "force-with-lease should replace rewritten history"
+        );
+        assert_git_success(
+            &git_in(
+                &source_path,
+                &["show-ref", "--verify", "refs/heads/feature/first-push"],
+            ),
+            "pushed branch should exist on remote",
+        );
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn delete_remote_branch_uses_tracking_config_and_tolerates_missing_branch() {
+        let base = temp_dir("delete_remote_branch");
+        let (source_path, bare_path) = create_source_and_bare_repo(&base);
+        assert_git_success(
+            &git_in(&bare_path, &["config", "branch.staging.remote", "origin"]),
+            "git config branch remote failed",
+        );
+        assert_git_success(
+            &git_in(
+                &bare_path,
+                &["config", "branch.staging.merge", "refs/heads/staging"],
+            ),
+            "git config branch merge failed",
+        );
+
+        let (remote, remote_branch) = remote_branch_for(&bare_path, "staging").unwrap();
+        assert_eq!(
+            (remote.as_str(), remote_branch.as_str()),
+            ("origin", "staging")
+        );
+        assert!(delete_remote_branch(&bare_path, &remote, &remote_branch).unwrap());
+        assert!(!git_in(
+            &source_path,
+            &["show-ref", "--verify", "--quiet", "refs/heads/staging"]
+        )
+        .status
+        .success());
+        assert!(!delete_remote_branch(&bare_path, &remote, &remote_branch).unwrap());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn rename_branch_moves_worktree_and_pushes_new_name() {
+        let base = temp_dir("rename_branch");
+        let (source_path, bare_path) = create_source_and_bare_repo(&base);
+        assert_git_success(
+            &git_in(
+                &bare_path,
+                &[
+                    "config",
+                    "remote.origin.fetch",
+                    "+refs/heads/*:refs/remotes/origin/*",
+                ],
+            ),
+            "git config fetch refspec failed",
+        );
+        let old_path = base.join("feature").join("old-name");
+        let new_path = base.join("feature").join("new-name");
+        add_worktree(&bare_path, "feature/old-name", &old_path, Some("main")).unwrap();
+        push_worktree_set_upstream(
+            &old_path,
+            "origin",
+            "feature/old-name",
+            &CancelToken::default(),
+        )
+        .unwrap();
+        assert!(branch_has_remote(&bare_path, "feature/old-name"));
+
+        rename_branch(&bare_path, "feature/old-name", "feature/new-name").unwrap();
+        move_worktree(&bare_path, &old_path, &new_path).unwrap();
+        push_worktree_set_upstream(
+            &new_path,
+            "origin",
+            "feature/new-name",
+            &CancelToken::default(),
+        )
+        .unwrap();
+        assert!(delete_remote_branch(&bare_path, "origin", "feature/old-name").unwrap());
+
+        assert!(!old_path.exists());
+        assert_eq!(
+            current_branch(&new_path).as_deref(),
+            Some("feature/new-name")
+        );
+        assert_eq!(
+            remote_branch_for(&bare_path, "feature/new-name"),
+            Some(("origin".to_string(), "feature/new-name".to_string()))
+        );
+        assert!(git_in(
+            &source_path,
+            &[
+                "show-ref",
+                "--verify",
+                "--quiet",
+                "refs/heads/feature/new-name"
+            ]
+        )
+        .status
+        .success());
+        assert!(!git_in(
+            &source_path,
+            &[
+                "show-ref",
+                "--verify",
+                "--quiet",
+                "refs/heads/feature/old-name"
+            ]
+        )
+        .status
+        .success());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn default_remote_prefers_checkout_default_remote_then_origin() {
+        let base = temp_dir("default_remote_preference");
+        let (source_path, bare_path) = create_source_and_bare_repo(&base);
+        assert_git_success(
+            &git_in(
+                &bare_path,
+                &["remote", "add", "upstream", &source_path.to_string_lossy()],
+            ),
+            "git remote add failed",
+        );
+
+        assert_eq!(default_remote(&bare_path).as_deref(), Some("origin"));
+        assert_git_success(
+            &git_in(
+                &bare_path,
+                &["config", "checkout.defaultRemote", "upstream"],
+            ),
+            "git config failed",
+        );
+        assert_eq!(default_remote(&bare_path).as_deref(), Some("upstream"));
+        assert_git_success(
+            &git_in(&bare_path, &["config", "checkout.defaultRemote", "missing"]),
+            "git config failed",
+        );
+        assert_eq!(default_remote(&bare_path).as_deref(), Some("origin"));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
     #[test]
     fn github_repo_slug_parses_only_github_remotes() {
         assert_eq!(
@@ -2057,4 +4700,46 @@ mod tests {
             .contains(&worktree_path.to_string_lossy().to_string()));
         let _ = fs::remove_dir_all(&base);
     }
+
+    #[test]
+    fn update_submodules_skips_worktrees_without_submodules_and_reports_failures() {
+        let base = temp_dir("update_submodules");
+        let submodule_repo = base.join("submodule");
+        let source_repo = base.join("source");
+        let worktree_path = base.join("feature-submodule");
+
+        create_test_regular_repo(&submodule_repo);
+        create_test_regular_repo(&source_repo);
+        assert!(!update_submodules(&source_repo).unwrap());
+
+        assert_git_success(
+            &git_cmd()
+                .current_dir(&source_repo)
+                .args([
+                    "-c",
+                    "protocol.file.allow=always",
+                    "submodule",
+                    "add",
+                    &submodule_repo.to_string_lossy(),
+                    "vendor/submodule",
+                ])
+                .output()
+                .unwrap(),
+            "git submodule add failed",
+        );
+        assert_git_success(&git_in(&source_repo, &["add", "."]), "git add failed");
+        assert_git_success(
+            &git_in(&source_repo, &["commit", "-m", "add submodule"]),
+            "git commit failed",
+        );
+        add_worktree(&source_repo, "feature/submodule", &worktree_path, None).unwrap();
+        assert!(worktree_path.join(".gitmodules").is_file());
+        assert!(!worktree_path.join("vendor/submodule/README.md").exists());
+
+        // The submodule can no longer be cloned, so the update must report it
+        fs::remove_dir_all(&submodule_repo).unwrap();
+        let error = update_submodules(&worktree_path).unwrap_err();
+        assert!(error.to_string().contains("Failed to update submodules"));
+        let _ = fs::remove_dir_all(&base);
+    }
 }
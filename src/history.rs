@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::state_file::{write_atomic, StateLock};
+
+/// Oldest visits are dropped once the history grows past this many lines
+const MAX_VISITS: usize = 1000;
+
+/// One "enter worktree" event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Visit {
+    pub at: u64, // Unix seconds
+    pub path: PathBuf,
+}
+
+/// Usage history: .owt/history under the project root, one
+/// `<unix seconds>\t<worktree path>` line per visit, oldest first
+pub(crate) fn history_path(project_root_path: &Path) -> PathBuf {
+    Config::owt_dir(project_root_path).join("history")
+}
+
+/// Append a visit to `worktree_path` now.
+pub(crate) fn record(project_root_path: &Path, worktree_path: &Path) -> Result<()> {
+    record_at(project_root_path, worktree_path, unix_now())
+}
+
+/// Frecency of every worktree in the project's history as of now. An
+/// unreadable history counts as empty.
+pub(crate) fn scores(project_root_path: &Path) -> HashMap<PathBuf, u64> {
+    frecency(&load(project_root_path).unwrap_or_default(), unix_now())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+fn record_at(project_root_path: &Path, worktree_path: &Path, at: u64) -> Result<()> {
+    let _lock = StateLock::acquire(project_root_path)?;
+    let mut visits = load(project_root_path)?;
+    visits.push(Visit {
+        at,
+        path: worktree_path.to_path_buf(),
+    });
+    let skip = visits.len().saturating_sub(MAX_VISITS);
+
+    let mut content = String::new();
+    for visit in &visits[skip..] {
+        content.push_str(&format!("{}\t{}\n", visit.at, visit.path.display()));
+    }
+    write_atomic(&history_path(project_root_path), &content)
+}
+
+/// Every recorded visit, oldest first. Lines that do not parse are skipped.
+pub(crate) fn load(project_root_path: &Path) -> Result<Vec<Visit>> {
+    let path = history_path(project_root_path);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(error).with_context(|| format!("Failed to read {}", path.display()))
+        }
+    };
+
+    Ok(content
+        .lines()
+        .filter_map(|line| {
+            let (at, path) = line.split_once('\t')?;
+            Some(Visit {
+                at: at.parse().ok()?,
+                path: PathBuf::from(path),
+            })
+        })
+        .collect())
+}
+
+/// Frecency score per worktree path: every visit counts, and recent visits
+/// count more (16 within the hour, 8 within the day, 4 within the week, 2
+/// within the month, 1 after that), so a worktree used often last month
+/// still ranks below one used a few times today.
+pub(crate) fn frecency(visits: &[Visit], now: u64) -> HashMap<PathBuf, u64> {
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+
+    let mut scores: HashMap<PathBuf, u64> = HashMap::new();
+    for visit in visits {
+        let weight = match now.saturating_sub(visit.at) {
+            age if age < HOUR => 16,
+            age if age < DAY => 8,
+            age if age < 7 * DAY => 4,
+            age if age < 30 * DAY => 2,
+            _ => 1,
+        };
+        *scores.entry(visit.path.clone()).or_default() += weight;
+    }
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn record_appends_visits_and_frecency_prefers_recent_use() {
+        let root = temp_dir("history");
+        assert!(load(&root).unwrap().is_empty());
+
+        let now = 100 * 24 * 60 * 60;
+        let old = Path::new("/repo/old");
+        let today = Path::new("/repo/today");
+        for days_ago in [40, 35, 33, 31] {
+            record_at(&root, old, now - days_ago * 24 * 60 * 60).unwrap();
+        }
+        record_at(&root, today, now - 60).unwrap();
+        fs::write(
+            history_path(&root),
+            fs::read_to_string(history_path(&root)).unwrap() + "torn line\n",
+        )
+        .unwrap();
+
+        let visits = load(&root).unwrap();
+        assert_eq!(visits.len(), 5);
+        assert_eq!(visits[4].path, today);
+
+        let scores = frecency(&visits, now);
+        assert_eq!(scores[old], 4);
+        assert_eq!(scores[today], 16);
+        assert!(!scores.contains_key(Path::new("/repo/never")));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use crate::config::Config;
+
+/// Project file at the worktree root and the command that installs what it
+/// lists. The first one found wins, so lockfiles come before their manifest.
+const INSTALLERS: &[(&str, &str)] = &[
+    ("pnpm-lock.yaml", "pnpm install"),
+    ("yarn.lock", "yarn install"),
+    ("bun.lock", "bun install"),
+    ("bun.lockb", "bun install"),
+    ("package-lock.json", "npm install"),
+    ("package.json", "npm install"),
+    ("Cargo.toml", "cargo fetch"),
+    ("uv.lock", "uv sync"),
+    ("poetry.lock", "poetry install"),
+    ("pyproject.toml", "pip install -e ."),
+];
+
+/// Install command for the project files found in `worktree_path`
+pub(crate) fn detect(worktree_path: &Path) -> Option<&'static str> {
+    INSTALLERS
+        .iter()
+        .find(|(file, _)| worktree_path.join(file).is_file())
+        .map(|(_, command)| *command)
+}
+
+/// Command to offer after adding `worktree_path`: `install_command = "auto"`
+/// picks one from the project files, any other value is run as written, and
+/// unset offers nothing. Worktrees without a known project file get nothing.
+pub(crate) fn command_for(config: &Config, worktree_path: &Path) -> Option<String> {
+    let configured = config.install_command.as_deref()?.trim();
+    let detected = detect(worktree_path)?;
+    match configured {
+        "" => None,
+        "auto" => Some(detected.to_string()),
+        command => Some(command.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn lockfiles_pick_the_package_manager_and_config_decides_the_command() {
+        let dir = temp_dir("install_detect");
+        let mut config = Config::default();
+        assert_eq!(detect(&dir), None);
+
+        fs::write(dir.join("package.json"), "{}").unwrap();
+        assert_eq!(detect(&dir), Some("npm install"));
+        fs::write(dir.join("pnpm-lock.yaml"), "").unwrap();
+        assert_eq!(detect(&dir), Some("pnpm install"));
+
+        assert_eq!(command_for(&config, &dir), None);
+        config.install_command = Some("auto".to_string());
+        assert_eq!(command_for(&config, &dir).as_deref(), Some("pnpm install"));
+        config.install_command = Some("make deps".to_string());
+        assert_eq!(command_for(&config, &dir).as_deref(), Some("make deps"));
+        config.install_command = Some(" ".to_string());
+        assert_eq!(command_for(&config, &dir), None);
+
+        let empty = temp_dir("install_empty");
+        config.install_command = Some("auto".to_string());
+        assert_eq!(command_for(&config, &empty), None);
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&empty);
+    }
+}
@@ -0,0 +1,275 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::git;
+use crate::state_file::StateLock;
+use crate::types::Worktree;
+
+/// Destructive operations recorded before they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum JournalOp {
+    Delete,
+    ForcePush,
+    Archive,
+}
+
+impl JournalOp {
+    pub fn label(self) -> &'static str {
+        match self {
+            JournalOp::Delete => "delete",
+            JournalOp::ForcePush => "force-push",
+            JournalOp::Archive => "archive",
+        }
+    }
+}
+
+/// What an operation was about to do, with enough state to finish or undo it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: String,
+    pub op: JournalOp,
+    pub started_at: u64, // Unix seconds
+    pub worktree_path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Commit the branch pointed at before the operation (delete: local
+    /// branch, force-push: the remote-tracking ref being overwritten).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_commit: Option<String>,
+    /// Planned commands, in order
+    #[serde(default)]
+    pub steps: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+enum JournalRecord {
+    Begin(JournalEntry),
+    Done { id: String },
+}
+
+impl JournalEntry {
+    pub fn new(op: JournalOp, worktree_path: &Path) -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        Self {
+            id: format!("{}-{}", std::process::id(), now.as_nanos()),
+            op,
+            started_at: now.as_secs(),
+            worktree_path: worktree_path.to_path_buf(),
+            branch: None,
+            previous_commit: None,
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn branch(mut self, branch: Option<&str>) -> Self {
+        self.branch = branch.map(str::to_string);
+        self
+    }
+
+    pub fn previous_commit(mut self, commit: Option<String>) -> Self {
+        self.previous_commit = commit;
+        self
+    }
+
+    pub fn step(mut self, step: impl Into<String>) -> Self {
+        self.steps.push(step.into());
+        self
+    }
+}
+
+/// Entry for removing `worktree`, recording the branch tip so a lost branch
+/// can be recreated.
+pub fn delete_entry(
+    repo_path: &Path,
+    worktree: &Worktree,
+    force: bool,
+    delete_branch: bool,
+    remote_target: Option<&(String, String)>,
+) -> JournalEntry {
+    let force_flag = if force { " --force" } else { "" };
+    let mut entry = JournalEntry::new(JournalOp::Delete, &worktree.path)
+        .branch(worktree.branch.as_deref())
+        .previous_commit(git::resolve_commit(&worktree.path, "HEAD"))
+        .step(format!(
+            "git -C {} worktree remove{} {}",
+            repo_path.display(),
+            force_flag,
+            worktree.path.display()
+        ));
+    if let (true, Some(branch)) = (delete_branch, worktree.branch.as_deref()) {
+        let flag = if force { "-D" } else { "-d" };
+        entry = entry.step(format!(
+            "git -C {} branch {} {}",
+            repo_path.display(),
+            flag,
+            branch
+        ));
+    }
+    if let Some((remote, remote_branch)) = remote_target {
+        entry = entry.step(format!(
+            "git -C {} push {} --delete {}",
+            repo_path.display(),
+            remote,
+            remote_branch
+        ));
+    }
+    entry
+}
+
+/// Operation journal: .owt/journal.jsonl under the project root
+pub fn journal_path(project_root_path: &Path) -> PathBuf {
+    Config::owt_dir(project_root_path).join("journal.jsonl")
+}
+
+/// Record `entry` before running it. The returned id is passed to [`finish`].
+pub fn begin(project_root_path: &Path, entry: JournalEntry) -> Result<String> {
+    let id = entry.id.clone();
+    let _lock = StateLock::acquire(project_root_path)?;
+    append(project_root_path, &JournalRecord::Begin(entry))?;
+    Ok(id)
+}
+
+/// Mark an operation complete. The journal file is removed once nothing is
+/// left pending, so it only exists while work is in flight or after a crash.
+pub fn finish(project_root_path: &Path, id: &str) -> Result<()> {
+    let _lock = StateLock::acquire(project_root_path)?;
+    let remaining = read_pending(project_root_path)?
+        .into_iter()
+        .filter(|entry| entry.id != id)
+        .count();
+
+    if remaining > 0 {
+        return append(
+            project_root_path,
+            &JournalRecord::Done { id: id.to_string() },
+        );
+    }
+
+    let path = journal_path(project_root_path);
+    match fs::remove_file(&path) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+            Err(error).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Operations that started but never finished, oldest first.
+pub fn pending(project_root_path: &Path) -> Result<Vec<JournalEntry>> {
+    let _lock = StateLock::acquire(project_root_path)?;
+    read_pending(project_root_path)
+}
+
+/// Forget every pending entry once the user has resolved them by hand.
+/// Returns how many entries were dropped.
+pub fn clear(project_root_path: &Path) -> Result<usize> {
+    let _lock = StateLock::acquire(project_root_path)?;
+    let count = read_pending(project_root_path)?.len();
+    let path = journal_path(project_root_path);
+    if path.exists() {
+        fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(count)
+}
+
+fn read_pending(project_root_path: &Path) -> Result<Vec<JournalEntry>> {
+    let path = journal_path(project_root_path);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(error).with_context(|| format!("Failed to read {}", path.display()))
+        }
+    };
+
+    let mut entries: Vec<JournalEntry> = Vec::new();
+    // A crash can leave a torn last line; skip anything that does not parse
+    for record in content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<JournalRecord>(line).ok())
+    {
+        match record {
+            JournalRecord::Begin(entry) => entries.push(entry),
+            JournalRecord::Done { id } => entries.retain(|entry| entry.id != id),
+        }
+    }
+    Ok(entries)
+}
+
+fn append(project_root_path: &Path, record: &JournalRecord) -> Result<()> {
+    let path = journal_path(project_root_path);
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+
+    // Start on a fresh line if a crash left the previous record torn
+    let torn = fs::read(&path)
+        .map(|content| content.last().is_some_and(|byte| *byte != b'\n'))
+        .unwrap_or(false);
+    if torn {
+        line.insert(0, '\n');
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .and_then(|_| file.sync_all())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn unfinished_operations_stay_pending_until_finished() {
+        let dir = temp_dir("journal_pending");
+        let delete = JournalEntry::new(JournalOp::Delete, Path::new("/repo/feature"))
+            .branch(Some("feature/login"))
+            .previous_commit(Some("abc123".to_string()))
+            .step("git worktree remove /repo/feature");
+        let push = JournalEntry::new(JournalOp::ForcePush, Path::new("/repo/main"));
+
+        let delete_id = begin(&dir, delete.clone()).unwrap();
+        let push_id = begin(&dir, push.clone()).unwrap();
+        assert_eq!(pending(&dir).unwrap(), vec![delete.clone(), push]);
+
+        finish(&dir, &push_id).unwrap();
+        assert_eq!(pending(&dir).unwrap(), vec![delete]);
+
+        finish(&dir, &delete_id).unwrap();
+        assert!(pending(&dir).unwrap().is_empty());
+        assert!(!journal_path(&dir).exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn torn_trailing_line_is_ignored() {
+        let dir = temp_dir("journal_torn");
+        let entry = JournalEntry::new(JournalOp::Delete, Path::new("/repo/feature"));
+        begin(&dir, entry.clone()).unwrap();
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(journal_path(&dir))
+            .unwrap();
+        file.write_all(b"{\"event\":\"done\",\"id\":").unwrap();
+        assert_eq!(pending(&dir).unwrap(), vec![entry.clone()]);
+
+        let next = JournalEntry::new(JournalOp::ForcePush, Path::new("/repo/main"));
+        begin(&dir, next.clone()).unwrap();
+        assert_eq!(pending(&dir).unwrap(), vec![entry, next]);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
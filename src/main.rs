@@ -1,49 +1,110 @@
 mod app;
+mod archive;
+mod branch_suggest;
+mod cli;
+mod command_log;
 mod config;
+mod config_bundle;
+mod config_check;
+mod convert;
+mod disk_usage;
+mod doctor;
+mod events;
+mod fs_watch;
+mod fuzzy;
 mod git;
+mod history;
+mod install;
+mod journal;
+mod metadata;
+mod nested_repos;
+mod plain_picker;
+mod preview;
+mod repo_registry;
+mod rpc;
+mod ship;
+mod state_file;
+mod template;
+#[cfg(test)]
+mod test_support;
 mod tmux;
 mod types;
 mod ui;
+mod worktree_clean;
+mod worktree_exec;
 mod worktree_prune;
+mod worktree_repair;
+mod zellij;
 
 use anyhow::{Context, Result};
+use clap::Parser;
 use config::Config;
+use std::collections::HashMap;
 use std::env;
+use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command as ProcessCommand;
+use worktree_clean::CleanDecision;
+use worktree_prune::paths_refer_to_same_location;
 
 enum Command {
-    Tui { path: PathBuf },
-    Clone { url: String, path: Option<PathBuf> },
+    Tui {
+        path: PathBuf,
+        select: Option<String>,
+        events: Option<String>,
+    },
+    Rpc {
+        path: PathBuf,
+    },
+    Pick {
+        path: PathBuf,
+        select: Option<String>,
+    },
+    Clone {
+        url: String,
+        path: Option<PathBuf>,
+        branch: Option<String>,
+        depth: Option<u32>,
+        no_worktree: bool,
+    },
     Init,
+    Convert {
+        path: PathBuf,
+        dry_run: bool,
+    },
     Setup,
-    Help(HelpTopic),
-    Version,
     TestCd, // Test command for debugging cd functionality
     Worktree(WorktreeCommand),
     Pr(PrCommand),
     Commit(CommitCommand),
     Search(SearchCommand),
-}
-
-enum HelpTopic {
-    Root,
-    Worktree,
-    WorktreeList,
-    WorktreeCreate,
-    WorktreeDelete,
-    WorktreePrune,
-    Pr,
-    PrStatus,
-    Commit,
-    CommitTree,
-    Search,
+    Config(ConfigCommand),
+    Repos {
+        json: bool,
+    },
+    Path {
+        path: PathBuf,
+        target: String,
+    },
+    Doctor {
+        path: PathBuf,
+        clear_journal: bool,
+        repair: bool,
+    },
+    Exec {
+        path: PathBuf,
+        filter: worktree_exec::ExecFilter,
+        command: Vec<String>,
+        jobs: Option<usize>,
+    },
 }
 
 enum WorktreeCommand {
     List {
         path: PathBuf,
         include_pr: bool,
+        json: bool,
+        porcelain: bool, // Only v1 exists
     },
     Create {
         path: PathBuf,
@@ -51,17 +112,28 @@ enum WorktreeCommand {
         base: Option<String>,
         worktree_path: Option<PathBuf>,
         tmux: Option<bool>,
+        sparse: Option<Vec<String>>, // Overrides sparse_paths; empty means full checkout
+        json: bool,
     },
     Delete {
         path: PathBuf,
         target: String,
         force: bool,
         delete_branch: bool,
+        keep_nested: bool,
+        json: bool,
     },
     Prune {
         path: PathBuf,
         dry_run: bool,
     },
+    Clean {
+        path: PathBuf,
+        delete_branch: bool,
+        yes: bool,
+        dry_run: bool,
+        json: bool,
+    },
 }
 
 enum PrCommand {
@@ -69,6 +141,7 @@ enum PrCommand {
         path: PathBuf,
         branch: Option<String>,
         all: bool,
+        json: bool,
     },
 }
 
@@ -81,9 +154,17 @@ enum SearchCommand {
         path: PathBuf,
         query: String,
         include_pr: bool,
+        json: bool,
+        porcelain: bool,
     },
 }
 
+enum ConfigCommand {
+    Check { path: PathBuf },
+    Export { file: Option<PathBuf> },
+    Import { file: PathBuf, force: bool },
+}
+
 struct RepositoryContext {
     repo_path: PathBuf,
     project_root_path: PathBuf,
@@ -94,7 +175,14 @@ struct RepositoryContext {
 impl Command {
     fn tui_path(&self) -> Option<&std::path::Path> {
         match self {
-            Command::Tui { path } => Some(path.as_path()),
+            Command::Tui { path, .. } => Some(path.as_path()),
+            _ => None,
+        }
+    }
+
+    fn tui_select(&self) -> Option<&str> {
+        match self {
+            Command::Tui { select, .. } => select.as_deref(),
             _ => None,
         }
     }
@@ -125,43 +213,92 @@ owt() {
 
 fn main() -> Result<()> {
     match parse_args() {
-        Command::Help(topic) => {
-            print_help(topic);
-            Ok(())
-        }
-        Command::Version => {
-            println!("owt v{}", env!("CARGO_PKG_VERSION"));
-            Ok(())
-        }
-        Command::Clone { url, path } => run_clone(&url, path),
+        Command::Clone {
+            url,
+            path,
+            branch,
+            depth,
+            no_worktree,
+        } => run_clone(&url, path, branch.as_deref(), depth, no_worktree)
+            .map(|project_dir| register_cloned_project(&project_dir)),
         Command::Init => run_init(),
+        Command::Convert { path, dry_run } => run_convert(&path, dry_run),
         Command::Setup => run_setup(),
-        Command::Tui { path } => run_tui(path),
+        Command::Tui {
+            path,
+            select,
+            events,
+        } => run_tui(path, select, events, false),
+        Command::Pick { path, select } => run_tui(path, select, None, true),
+        Command::Rpc { path } => rpc::run(&path),
         Command::TestCd => run_test_cd(),
         Command::Worktree(command) => run_worktree_command(command),
         Command::Pr(command) => run_pr_command(command),
         Command::Commit(command) => run_commit_command(command),
         Command::Search(command) => run_search_command(command),
+        Command::Config(command) => run_config_command(command),
+        Command::Repos { json } => run_repos(json),
+        Command::Path { path, target } => run_path(&path, &target),
+        Command::Doctor {
+            path,
+            clear_journal,
+            repair,
+        } => run_doctor(&path, clear_journal, repair),
+        Command::Exec {
+            path,
+            filter,
+            command,
+            jobs,
+        } => run_exec(&path, &filter, &command, jobs),
     }
 }
 
-fn run_tui(path: PathBuf) -> Result<()> {
+/// Run the TUI. As a picker (`owt pick`) only the chosen worktree's path
+/// reaches stdout, whatever OWT_OUTPUT_FILE says, and quitting without one
+/// exits 1.
+fn run_tui(
+    path: PathBuf,
+    select: Option<String>,
+    events: Option<String>,
+    pick: bool,
+) -> Result<()> {
     use std::fs::File;
-    use std::io::Write;
 
     // Check if we should write result to a file (for shell integration)
-    let output_file = env::var("OWT_OUTPUT_FILE").ok();
+    let output_file = env::var("OWT_OUTPUT_FILE").ok().filter(|_| !pick);
+
+    // Outside a repository the TUI opens on a clone prompt instead
+    let repo_context = if pick {
+        Some(resolve_repository_context(&path)?)
+    } else {
+        resolve_repository_context(&path).ok()
+    };
 
-    let repo_context = match resolve_repository_context(&path) {
-        Ok(context) => context,
-        Err(_) => {
+    // Open the event stream before the TUI takes over the terminal so errors stay visible
+    let event_sink = events.as_deref().map(events::EventSink::open).transpose()?;
+
+    // Always use /dev/tty for TUI to support shell integration. Stdout is
+    // often a pipe on purpose (`cd "$(owt)"`), so only a missing terminal
+    // falls back to the plain menu.
+    let tty = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()
+        .filter(|tty| tty.is_terminal());
+    let Some(tty) = tty else {
+        let Some(repo_context) = repo_context else {
             print_not_git_repo_error();
             std::process::exit(1);
-        }
+        };
+        return run_plain_picker(
+            &repo_context,
+            &path,
+            select.as_deref(),
+            output_file.as_deref(),
+            pick,
+        );
     };
-
-    // Always use /dev/tty for TUI to support shell integration
-    let tty = File::options().read(true).write(true).open("/dev/tty")?;
     let mut tty_for_control = tty.try_clone()?;
 
     crossterm::terminal::enable_raw_mode()?;
@@ -170,14 +307,25 @@ fn run_tui(path: PathBuf) -> Result<()> {
     let backend = ratatui::backend::CrosstermBackend::new(tty);
     let mut terminal = ratatui::Terminal::new(backend)?;
 
-    let has_shell_integration = output_file.is_some();
-    let mut app = app::App::new(
-        repo_context.repo_path,
-        repo_context.project_root_path,
-        repo_context.repo_is_bare,
-        Some(path),
-        has_shell_integration,
-    )?;
+    // The picker hands the path over itself, so the setup tip does not apply
+    let has_shell_integration = output_file.is_some() || pick;
+    let mut app = match repo_context {
+        Some(repo_context) => {
+            let mut app = app::App::new(
+                repo_context.repo_path,
+                repo_context.project_root_path,
+                repo_context.repo_is_bare,
+                Some(path),
+                has_shell_integration,
+            )?;
+            if let Some(target) = select.as_deref() {
+                app.preselect(target);
+            }
+            app
+        }
+        None => app::App::new_clone_prompt(path, has_shell_integration),
+    };
+    app.events = event_sink;
     let result = app.run(&mut terminal);
 
     // Restore terminal
@@ -186,31 +334,78 @@ fn run_tui(path: PathBuf) -> Result<()> {
 
     let exit_action = app.exit_action.clone();
 
+    // Remember the project for `owt <name>` and the repository switcher
+    if !app.worktrees.is_empty() {
+        let _ = repo_registry::register(&app.project_root_path);
+    }
+
     // Handle exit action - write path for shell integration
     match &exit_action {
         types::ExitAction::ChangeDirectory(worktree_path) => {
-            if let Some(ref output_path) = output_file {
-                let mut file = open_shell_output_file(output_path)?;
-                writeln!(file, "{}", worktree_path.display())?;
-                // Log for debugging
-                eprintln!("→ {}", worktree_path.display());
-            } else {
-                // No shell integration - print only the path to keep stdout
-                // machine-readable for shell wrappers and scripts.
-                println!("{}", worktree_path.display());
-            }
+            hand_off_directory(worktree_path, output_file.as_deref())?;
+        }
+        types::ExitAction::Quit if pick => {
+            result?;
+            std::process::exit(1);
         }
         types::ExitAction::Quit => {
             // Normal quit, no directory change
         }
         types::ExitAction::CreateWorktree(request) => {
-            run_post_tui_create_worktree(request, &app.config, output_file.as_deref())?;
+            run_post_tui_create_worktree(request, &app.config, output_file.as_deref(), pick)?;
         }
     }
 
     result
 }
 
+/// Stand-in for the TUI when there is no terminal: a numbered menu on stderr
+/// read from stdin, with the choice handed off like the TUI's.
+fn run_plain_picker(
+    context: &RepositoryContext,
+    launch_path: &Path,
+    select: Option<&str>,
+    output_file: Option<&str>,
+    pick: bool,
+) -> Result<()> {
+    let worktrees = git::list_worktrees(&context.repo_path)?;
+    let default = select
+        .and_then(|target| find_worktree_target(&worktrees, target).ok())
+        .map(|worktree| worktree.path)
+        .or_else(|| current_worktree_path(&worktrees, launch_path));
+
+    eprintln!("No terminal for the TUI; choose a worktree:");
+    let chosen = plain_picker::choose(
+        &worktrees,
+        default.as_deref(),
+        io::stdin().lock(),
+        io::stderr(),
+    )?;
+    let _ = repo_registry::register(&context.project_root_path);
+
+    match chosen {
+        Some(worktree_path) => hand_off_directory(&worktree_path, output_file),
+        None if pick => std::process::exit(1),
+        None => Ok(()),
+    }
+}
+
+/// Pass the chosen worktree to the shell wrapper through OWT_OUTPUT_FILE, or
+/// print it as the only line on stdout.
+fn hand_off_directory(worktree_path: &Path, output_file: Option<&str>) -> Result<()> {
+    if let Some(output_path) = output_file {
+        let mut file = open_shell_output_file(output_path)?;
+        writeln!(file, "{}", worktree_path.display())?;
+        // Log for debugging
+        eprintln!("→ {}", worktree_path.display());
+    } else {
+        // No shell integration - print only the path to keep stdout
+        // machine-readable for shell wrappers and scripts.
+        println!("{}", worktree_path.display());
+    }
+    Ok(())
+}
+
 fn run_test_cd() -> Result<()> {
     use std::io::Write;
 
@@ -262,48 +457,149 @@ fn run_post_tui_create_worktree(
     request: &types::WorktreeCreateRequest,
     config: &Config,
     output_file: Option<&str>,
+    quiet: bool,
 ) -> Result<()> {
-    eprintln!(
+    // Progress lines; warnings are printed either way
+    let progress = |line: String| {
+        if !quiet {
+            eprintln!("{}", line);
+        }
+    };
+    progress(format!(
         "Creating worktree: {} (base: {})",
         request.branch, request.base_branch
-    );
+    ));
 
     let base_branch = Some(request.base_branch.as_str());
-    let _ = git::fetch_remote_branch(&request.bare_repo_path, &request.base_branch);
-    git::add_worktree(
+    if !request.base_fetched {
+        let _ = git::fetch_remote_branch(&request.bare_repo_path, &request.base_branch);
+    }
+    let created_root = metadata::created_root(&request.worktree_path);
+    git::add_worktree_sparse(
         &request.bare_repo_path,
         &request.branch,
         &request.worktree_path,
         base_branch,
+        config.sparse_paths_for_branch(&request.branch),
     )?;
+    if request.path_overridden {
+        if let Err(error) = metadata::record_location(
+            &request.project_root_path,
+            &request.worktree_path,
+            &created_root,
+        ) {
+            eprintln!("warning\tlocation\t{}", plain_field(&error.to_string()));
+        }
+    }
 
     if !config.copy_files.is_empty() {
-        if let Some(source) = request.source_path.as_deref() {
-            for warning in copy_configured_files(source, &request.worktree_path, &config.copy_files)
-            {
-                eprintln!("warning\t{}", plain_field(&warning));
+        match (
+            request.source_path.as_deref(),
+            config.copy_source.as_deref(),
+        ) {
+            (Some(source), _) => {
+                for warning in
+                    copy_configured_files(source, &request.worktree_path, &config.copy_files)
+                {
+                    eprintln!("warning\t{}", plain_field(&warning));
+                }
             }
+            (None, Some(name)) => eprintln!(
+                "warning\tcopy_source\t{}",
+                plain_field(&copy_source_missing(name))
+            ),
+            (None, None) => {}
+        }
+    }
+    for warning in template::apply(
+        &request.project_root_path,
+        &request.worktree_path,
+        &request.branch,
+        &request.base_branch,
+    ) {
+        eprintln!("warning\t{}", plain_field(&warning));
+    }
+
+    if config.submodule_update.unwrap_or(false) {
+        match git::update_submodules(&request.worktree_path) {
+            Ok(true) => progress("submodules\tupdated".to_string()),
+            Ok(false) => {}
+            Err(error) => eprintln!("warning\tsubmodules\t{}", plain_field(&error.to_string())),
+        }
+    }
+    if config.lfs_pull.unwrap_or(false) && git::uses_lfs(&request.worktree_path) {
+        progress("lfs\tpulling".to_string());
+        match git::lfs_pull(&request.worktree_path, &git::CancelToken::default()) {
+            Ok(_) => progress("lfs\tpulled".to_string()),
+            Err(error) => eprintln!("warning\tlfs\t{}", plain_field(&error.to_string())),
         }
     }
 
     if config.tmux_worktree_mode {
         let worktree_name = worktree_name_from_path(&request.worktree_path);
         match tmux::open_worktree_pane(&request.worktree_path, &worktree_name) {
-            Ok(()) => eprintln!("tmux\topened\t{}", plain_field(&worktree_name)),
+            Ok(()) => progress(format!("tmux\topened\t{}", plain_field(&worktree_name))),
             Err(error) => eprintln!("warning\ttmux\t{}", plain_field(&error.to_string())),
         }
     }
 
-    if let Err(error) =
-        launch_post_add_script(config, &request.project_root_path, &request.worktree_path)
-    {
+    let hook_env = types::HookEnv {
+        branch: request.branch.clone(),
+        base_branch: request.base_branch.clone(),
+        worktree_path: request.worktree_path.clone(),
+        bare_path: request.bare_repo_path.clone(),
+        branch_type: config
+            .branch_type_for(&request.branch)
+            .map(|branch_type| branch_type.name.clone()),
+    };
+    if let Err(error) = launch_post_add_script(config, &request.project_root_path, &hook_env) {
         eprintln!("warning\tpost_add\t{}", plain_field(&error.to_string()));
     }
+    if let Some(command) = install::command_for(config, &request.worktree_path) {
+        if !quiet && io::stdin().is_terminal() {
+            if let Err(error) = offer_install(&command, &hook_env) {
+                eprintln!("warning\tinstall\t{}", plain_field(&error.to_string()));
+            }
+        }
+    }
 
     write_shell_handoff(output_file, &request.worktree_path)?;
     Ok(())
 }
 
+/// Ask whether to run `install_command` in the new worktree, then run it
+/// there with its output on stderr, keeping stdout for the shell handoff
+fn offer_install(command: &str, hook_env: &types::HookEnv) -> Result<()> {
+    eprint!(
+        "Run `{}` in {}? [y/N] ",
+        command,
+        worktree_name_from_path(&hook_env.worktree_path)
+    );
+    io::stderr()
+        .flush()
+        .context("Failed to flush install prompt")?;
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .context("Failed to read install answer")?;
+    if !matches!(answer.trim(), "y" | "Y" | "yes" | "YES") {
+        return Ok(());
+    }
+
+    let status = ProcessCommand::new("sh")
+        .args(["-c", command])
+        .current_dir(&hook_env.worktree_path)
+        .envs(hook_env.vars())
+        .stdout(io::stderr())
+        .status()
+        .with_context(|| format!("Failed to run {}", command))?;
+    if !status.success() {
+        anyhow::bail!("{} exited with {}", command, status);
+    }
+    eprintln!("install\tfinished\t{}", plain_field(command));
+    Ok(())
+}
+
 fn write_shell_handoff(output_file: Option<&str>, worktree_path: &Path) -> Result<()> {
     if let Some(output_path) = output_file {
         let mut file = open_shell_output_file(output_path)?;
@@ -319,18 +615,19 @@ fn write_shell_handoff(output_file: Option<&str>, worktree_path: &Path) -> Resul
 fn launch_post_add_script(
     config: &Config,
     project_root_path: &Path,
-    worktree_path: &Path,
+    hook_env: &types::HookEnv,
 ) -> Result<()> {
     let script_path = config.resolved_post_add_script_path(project_root_path);
     if !script_path.exists() || !config.run_post_add_script_in_tmux {
         return Ok(());
     }
 
-    let worktree_name = worktree_name_from_path(worktree_path);
+    let worktree_name = worktree_name_from_path(&hook_env.worktree_path);
     let session_name = format!("owt-post-add-{}", std::process::id());
     let command = format!(
-        "cd {} && sh {}; status=$?; tmux kill-session -t {}; exit $status",
-        shell_quote(worktree_path),
+        "cd {} && {} && sh {}; status=$?; tmux kill-session -t {}; exit $status",
+        shell_quote(&hook_env.worktree_path),
+        hook_env.shell_exports(),
         shell_quote(&script_path),
         session_name
     );
@@ -352,15 +649,23 @@ fn launch_post_add_script(
 
 fn run_worktree_command(command: WorktreeCommand) -> Result<()> {
     match command {
-        WorktreeCommand::List { path, include_pr } => {
+        WorktreeCommand::List {
+            path,
+            include_pr,
+            json,
+            porcelain,
+        } => {
             let context = resolve_repository_context(&path)?;
             let mut worktrees = git::list_worktrees(&context.repo_path)?;
             if include_pr {
                 refresh_pr_statuses(&context.repo_path, &mut worktrees);
             }
-            for worktree in &worktrees {
-                print_worktree_record(worktree);
+            if porcelain {
+                print_porcelain_v1(&worktrees);
+                return Ok(());
             }
+            let descriptions = git::branch_descriptions(&context.repo_path);
+            print_worktree_records(&worktrees, &descriptions, json);
             Ok(())
         }
         WorktreeCommand::Create {
@@ -369,52 +674,50 @@ fn run_worktree_command(command: WorktreeCommand) -> Result<()> {
             base,
             worktree_path,
             tmux,
+            sparse,
+            json,
         } => {
             let context = resolve_repository_context(&path)?;
-            let config =
-                Config::load_with_project(Some(&context.project_root_path)).unwrap_or_default();
-            let worktrees = git::list_worktrees(&context.repo_path)?;
-            let target_path = worktree_path
-                .unwrap_or_else(|| worktree_path_for_branch(&context, &config, &branch));
-
-            if let Some(existing) =
-                conflicting_worktree_for_branch(&worktrees, &branch, &target_path)
-            {
-                anyhow::bail!(
-                    "Branch '{}' is already checked out at {}",
-                    branch,
-                    existing.path.display()
-                );
-            }
-
-            if let Some(base_branch) = base.as_deref() {
-                let _ = git::fetch_remote_branch(&context.repo_path, base_branch);
+            let mut config = Config::load_with_project(Some(&context.project_root_path))
+                .unwrap_or_else(|error| {
+                    eprintln!("warning\tconfig\t{}", plain_field(&error.to_string()));
+                    Config::default()
+                });
+            if let Some(sparse) = sparse {
+                config.override_sparse_paths(sparse);
             }
-
-            git::add_worktree(&context.repo_path, &branch, &target_path, base.as_deref())?;
+            let (target_path, mut warnings) = create_worktree(
+                &context,
+                &config,
+                &path,
+                &branch,
+                base.as_deref(),
+                worktree_path,
+            )?;
 
             let tmux_enabled = tmux.unwrap_or(config.tmux_worktree_mode);
             if tmux_enabled {
                 let worktree_name = worktree_name_from_path(&target_path);
                 match tmux::open_worktree_pane(&target_path, &worktree_name) {
                     Ok(()) => eprintln!("tmux\topened\t{}", plain_field(&worktree_name)),
+                    Err(error) if json => warnings.push(format!("tmux: {}", error)),
                     Err(error) => eprintln!("warning\ttmux\t{}", plain_field(&error.to_string())),
                 }
             }
 
-            if !config.copy_files.is_empty() {
-                let source = current_worktree_path(&worktrees, &path).or_else(|| {
-                    worktrees
-                        .iter()
-                        .find(|wt| !wt.is_bare)
-                        .map(|wt| wt.path.clone())
-                });
-                if let Some(source) = source {
-                    for warning in copy_configured_files(&source, &target_path, &config.copy_files)
-                    {
-                        eprintln!("warning\t{}", plain_field(&warning));
-                    }
-                }
+            if json {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "branch": branch,
+                        "path": target_path.display().to_string(),
+                        "warnings": warnings,
+                    })
+                );
+                return Ok(());
+            }
+            for warning in warnings {
+                eprintln!("warning\t{}", plain_field(&warning));
             }
 
             println!(
@@ -429,27 +732,21 @@ fn run_worktree_command(command: WorktreeCommand) -> Result<()> {
             target,
             force,
             delete_branch,
+            keep_nested,
+            json,
         } => {
             let context = resolve_repository_context(&path)?;
-            let worktrees = git::list_worktrees(&context.repo_path)?;
-            let worktree = find_worktree_target(&worktrees, &target)?;
+            let (worktree, kept) =
+                delete_worktree(&context, &target, force, delete_branch, keep_nested)?;
 
-            if worktree.is_bare {
-                anyhow::bail!("Cannot delete bare repository");
-            }
-            if worktree.status != types::WorktreeStatus::Clean && !force {
-                anyhow::bail!(
-                    "Worktree has uncommitted changes. Re-run with --force to delete it."
-                );
+            if json {
+                println!("{}", rpc::deleted_worktree_json(&worktree, &kept));
+                return Ok(());
             }
 
-            git::remove_worktree(&context.repo_path, &worktree.path, force)?;
-            if delete_branch {
-                if let Some(branch) = worktree.branch.as_deref() {
-                    git::delete_branch(&context.repo_path, branch, force)?;
-                }
+            for path in kept {
+                println!("kept\t{}", plain_field(&path.display().to_string()));
             }
-
             println!(
                 "deleted\t{}\t{}",
                 plain_field(worktree.branch.as_deref().unwrap_or("-")),
@@ -469,20 +766,351 @@ fn run_worktree_command(command: WorktreeCommand) -> Result<()> {
             worktree_prune::print_prune_output(&metadata_output, &logs);
             Ok(())
         }
+        WorktreeCommand::Clean {
+            path,
+            delete_branch,
+            yes,
+            dry_run,
+            json,
+        } => {
+            let mut report = CleanReport::new(json);
+            let context = resolve_repository_context(&path)?;
+            let worktrees = git::list_worktrees(&context.repo_path)?;
+            let current_path = current_worktree_path(&worktrees, &path);
+            let decisions = worktree_clean::merged_worktrees(
+                &context.repo_path,
+                &worktrees,
+                current_path.as_deref(),
+            )?;
+
+            let mut candidates = Vec::new();
+            for (worktree, decision) in decisions {
+                if worktree.is_bare {
+                    continue;
+                }
+                match decision {
+                    CleanDecision::Merged => candidates.push(worktree),
+                    CleanDecision::Kept(reason) => report.record("kept", &worktree, &reason),
+                }
+            }
+            if candidates.is_empty() {
+                report.finish_empty();
+                return Ok(());
+            }
+            if dry_run {
+                for worktree in &candidates {
+                    report.record("would-remove", worktree, "");
+                }
+                report.finish();
+                return Ok(());
+            }
+            if !yes && !confirm_clean(&candidates, delete_branch)? {
+                for worktree in &candidates {
+                    report.record("kept", worktree, "declined");
+                }
+                report.finish();
+                return Ok(());
+            }
+
+            let mut failures = 0;
+            for worktree in &candidates {
+                let target = worktree.path.display().to_string();
+                match delete_worktree(&context, &target, false, delete_branch, false) {
+                    Ok(_) => report.record("removed", worktree, ""),
+                    Err(error) => {
+                        failures += 1;
+                        report.record("failed", worktree, &error.to_string());
+                    }
+                }
+            }
+            report.finish();
+            if failures > 0 {
+                anyhow::bail!("{} merged worktree(s) could not be removed", failures);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// `owt clean` output: a tab-separated record per decision as it is made, or
+/// with `--json` one array of them printed by `finish`.
+struct CleanReport {
+    json: Option<Vec<serde_json::Value>>,
+}
+
+impl CleanReport {
+    fn new(json: bool) -> Self {
+        Self {
+            json: json.then(Vec::new),
+        }
+    }
+
+    /// `clean<TAB>action<TAB>branch<TAB>path`, plus a reason or error when given
+    fn record(&mut self, action: &str, worktree: &types::Worktree, detail: &str) {
+        if let Some(records) = self.json.as_mut() {
+            records.push(serde_json::json!({
+                "action": action,
+                "branch": worktree.branch,
+                "path": worktree.path.display().to_string(),
+                "detail": (!detail.is_empty()).then_some(detail),
+            }));
+            return;
+        }
+        let mut record = format!(
+            "clean\t{}\t{}\t{}",
+            action,
+            plain_field(worktree.branch.as_deref().unwrap_or("-")),
+            plain_field(&worktree.path.display().to_string())
+        );
+        if !detail.is_empty() {
+            record.push('\t');
+            record.push_str(&plain_field(detail));
+        }
+        println!("{}", record);
+    }
+
+    /// Nothing merged: `clean<TAB>0` after any kept records
+    fn finish_empty(self) {
+        if self.json.is_none() {
+            println!("clean\t0");
+        }
+        self.finish();
+    }
+
+    fn finish(self) {
+        if let Some(records) = self.json {
+            println!("{}", serde_json::Value::Array(records));
+        }
+    }
+}
+
+/// List the merged worktrees on stderr and ask once before removing them all
+fn confirm_clean(candidates: &[types::Worktree], delete_branch: bool) -> Result<bool> {
+    for worktree in candidates {
+        eprintln!(
+            "merged\t{}\t{}",
+            plain_field(worktree.branch.as_deref().unwrap_or("-")),
+            plain_field(&worktree.path.display().to_string())
+        );
+    }
+    eprint!(
+        "Remove {} merged worktree(s){}? [y/N] ",
+        candidates.len(),
+        if delete_branch {
+            " and their branches"
+        } else {
+            ""
+        }
+    );
+    io::stderr()
+        .flush()
+        .context("Failed to flush clean prompt")?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .context("Failed to read clean answer")?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "YES"))
+}
+
+/// Create a worktree for `branch`, copy the configured files into it, and
+/// apply the project template. Returns the new worktree path and any copy
+/// warnings.
+fn create_worktree(
+    context: &RepositoryContext,
+    config: &Config,
+    launch_path: &Path,
+    branch: &str,
+    base: Option<&str>,
+    worktree_path: Option<PathBuf>,
+) -> Result<(PathBuf, Vec<String>)> {
+    let worktrees = git::list_worktrees(&context.repo_path)?;
+    let path_overridden = worktree_path.is_some();
+    let target_path =
+        worktree_path.unwrap_or_else(|| worktree_path_for_branch(context, config, branch));
+
+    if let Some(existing) = conflicting_worktree_for_branch(&worktrees, branch, &target_path) {
+        anyhow::bail!(
+            "Branch '{}' is already checked out at {}",
+            branch,
+            existing.path.display()
+        );
+    }
+
+    if let Some(base_branch) = base {
+        let _ = git::fetch_remote_branch(&context.repo_path, base_branch);
+    }
+
+    let created_root = metadata::created_root(&target_path);
+    git::add_worktree_sparse(
+        &context.repo_path,
+        branch,
+        &target_path,
+        base,
+        config.sparse_paths_for_branch(branch),
+    )?;
+
+    let mut warnings = Vec::new();
+    if path_overridden {
+        if let Err(error) =
+            metadata::record_location(&context.project_root_path, &target_path, &created_root)
+        {
+            warnings.push(format!("location not recorded: {}", error));
+        }
+    }
+    if !config.copy_files.is_empty() {
+        let source = config.copy_source_path(
+            &worktrees,
+            current_worktree_path(&worktrees, launch_path).or_else(|| {
+                worktrees
+                    .iter()
+                    .find(|wt| !wt.is_bare)
+                    .map(|wt| wt.path.clone())
+            }),
+        );
+        match (source, config.copy_source.as_deref()) {
+            (Some(source), _) => {
+                warnings.extend(copy_configured_files(
+                    &source,
+                    &target_path,
+                    &config.copy_files,
+                ));
+            }
+            (None, Some(name)) => warnings.push(copy_source_missing(name)),
+            (None, None) => {}
+        }
+    }
+    let template_base = match base {
+        Some(base) => base.to_string(),
+        None => git::get_default_branch(&context.repo_path).unwrap_or_default(),
+    };
+    warnings.extend(template::apply(
+        &context.project_root_path,
+        &target_path,
+        branch,
+        &template_base,
+    ));
+    if config.submodule_update.unwrap_or(false) {
+        if let Err(error) = git::update_submodules(&target_path) {
+            warnings.push(error.to_string());
+        }
+    }
+    if config.lfs_pull.unwrap_or(false) {
+        if let Err(error) = git::lfs_pull(&target_path, &git::CancelToken::default()) {
+            warnings.push(error.to_string());
+        }
+    }
+
+    Ok((target_path, warnings))
+}
+
+/// Remove the worktree matching `target`, optionally deleting its branch.
+/// Nested git repositories are deleted only with `force`; `keep_nested`
+/// moves them out first and returns their new locations.
+fn delete_worktree(
+    context: &RepositoryContext,
+    target: &str,
+    force: bool,
+    delete_branch: bool,
+    keep_nested: bool,
+) -> Result<(types::Worktree, Vec<PathBuf>)> {
+    let worktrees = git::list_worktrees(&context.repo_path)?;
+    let worktree = find_worktree_target(&worktrees, target)?;
+
+    if worktree.is_bare {
+        anyhow::bail!("Cannot delete bare repository");
+    }
+    if worktree.status != types::WorktreeStatus::Clean && !force {
+        anyhow::bail!("Worktree has uncommitted changes. Re-run with --force to delete it.");
+    }
+    let nested = nested_repos::find(&worktree.path);
+    if !nested.is_empty() && !keep_nested {
+        let names: Vec<String> = nested
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        if !force {
+            anyhow::bail!(
+                "Worktree contains nested git repositories that would be deleted too: {}. \
+                 Re-run with --keep-nested to move them to {}, or --force to delete them.",
+                names.join(", "),
+                nested_repos::kept_dir(&context.project_root_path, &worktree.display_name())
+                    .display()
+            );
+        }
+        for name in &names {
+            eprintln!("warning\tnested_repo\t{}", plain_field(name));
+        }
+    }
+
+    let journal_id = journal::begin(
+        &context.project_root_path,
+        journal::delete_entry(&context.repo_path, &worktree, force, delete_branch, None),
+    )?;
+    let kept = if keep_nested {
+        nested_repos::keep(
+            &context.project_root_path,
+            &worktree.path,
+            &worktree.display_name(),
+            &nested,
+        )
+    } else {
+        Ok(Vec::new())
+    };
+    let result = kept.and_then(|kept| {
+        remove_worktree_and_branch(context, &worktree, force, delete_branch).map(|()| kept)
+    });
+    let _ = journal::finish(&context.project_root_path, &journal_id);
+    let kept = result?;
+    let _ = metadata::cleanup_location(&context.project_root_path, &worktree.path);
+
+    Ok((worktree, kept))
+}
+
+fn remove_worktree_and_branch(
+    context: &RepositoryContext,
+    worktree: &types::Worktree,
+    force: bool,
+    delete_branch: bool,
+) -> Result<()> {
+    git::remove_worktree(&context.repo_path, &worktree.path, force)?;
+    if delete_branch {
+        if let Some(branch) = worktree.branch.as_deref() {
+            git::delete_branch(&context.repo_path, branch, force)?;
+        }
     }
+    Ok(())
 }
 
 fn run_pr_command(command: PrCommand) -> Result<()> {
     match command {
-        PrCommand::Status { path, branch, all } => {
+        PrCommand::Status {
+            path,
+            branch,
+            all,
+            json,
+        } => {
             let context = resolve_repository_context(&path)?;
             let worktrees = git::list_worktrees(&context.repo_path)?;
             let targets = pr_status_targets(&worktrees, &path, branch, all);
             let statuses = git::github_pr_statuses_for_worktrees(&context.repo_path, &targets);
 
-            for ((target_path, status), (_, branch)) in
-                statuses.into_iter().zip(targets.into_iter())
-            {
+            if json {
+                let records: Vec<serde_json::Value> = statuses
+                    .into_iter()
+                    .zip(targets)
+                    .map(|((target_path, status), (_, branch))| {
+                        serde_json::json!({
+                            "branch": branch,
+                            "path": target_path.display().to_string(),
+                            "pr": status.map(|status| status.label()),
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::Value::Array(records));
+                return Ok(());
+            }
+            for ((target_path, status), (_, branch)) in statuses.into_iter().zip(targets) {
                 println!(
                     "{}\t{}\t{}",
                     plain_field(&branch),
@@ -514,6 +1142,8 @@ fn run_search_command(command: SearchCommand) -> Result<()> {
             path,
             query,
             include_pr,
+            json,
+            porcelain,
         } => {
             let context = resolve_repository_context(&path)?;
             let mut worktrees = git::list_worktrees(&context.repo_path)?;
@@ -521,36 +1151,160 @@ fn run_search_command(command: SearchCommand) -> Result<()> {
                 refresh_pr_statuses(&context.repo_path, &mut worktrees);
             }
             let needle = query.to_lowercase();
-            for worktree in &worktrees {
-                if worktree_matches(worktree, &needle) {
-                    print_worktree_record(worktree);
-                }
+            let descriptions = git::branch_descriptions(&context.repo_path);
+            worktrees.retain(|worktree| worktree_matches(worktree, &descriptions, &needle));
+            if porcelain {
+                print_porcelain_v1(&worktrees);
+                return Ok(());
             }
+            print_worktree_records(&worktrees, &descriptions, json);
             Ok(())
         }
     }
 }
 
-fn resolve_repository_context(path: &Path) -> Result<RepositoryContext> {
-    if let Some(bare_path) = git::find_bare_in_parent(path) {
-        let project_root = bare_path
-            .parent()
-            .map(PathBuf::from)
-            .unwrap_or_else(|| path.to_path_buf());
-        return Ok(RepositoryContext {
-            repo_path: bare_path,
-            project_root_path: project_root,
-            repo_is_bare: true,
-        });
+fn run_convert(path: &Path, dry_run: bool) -> Result<()> {
+    let plan = convert::plan(path)?;
+    if dry_run {
+        for step in plan.steps() {
+            println!("step\t{}", plain_field(&plan.describe(step)));
+        }
+        return Ok(());
     }
 
-    if !git::is_git_repo(path) {
-        anyhow::bail!("Not a git repository");
-    }
+    convert::convert(&plan, |step| println!("done\t{}", plain_field(step)))?;
+    println!(
+        "converted\t{}",
+        plain_field(&plan.worktree_path.to_string_lossy())
+    );
+    Ok(())
+}
 
-    let common_dir = git::get_git_common_dir(path)?;
-    if git::is_bare_repo(&common_dir)? {
-        let project_root = common_dir
+fn run_doctor(path: &Path, clear_journal: bool, repair: bool) -> Result<()> {
+    if clear_journal {
+        let context = resolve_repository_context(path)?;
+        let cleared = journal::clear(&context.project_root_path)?;
+        println!("cleared\tjournal\t{}", cleared);
+        return Ok(());
+    }
+    if repair {
+        let context = resolve_repository_context(path)?;
+        let report = worktree_repair::repair(&context.repo_path, &context.project_root_path)?;
+        if report.is_empty() {
+            println!("repaired\tnothing to repair");
+        }
+        for line in report {
+            println!("repaired\t{}", plain_field(&line));
+        }
+    }
+
+    let checks = doctor::run_checks(path);
+    doctor::print_checks(&checks);
+
+    let errors = checks
+        .iter()
+        .filter(|check| check.status == doctor::Status::Error)
+        .count();
+    if errors > 0 {
+        anyhow::bail!("doctor found {} problem(s) to fix", errors);
+    }
+    Ok(())
+}
+
+fn run_config_command(command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Check { path } => {
+            // Outside a repository only the global config is checked
+            let context = resolve_repository_context(&path).ok();
+            let copy_source = match context {
+                Some(ref context) => {
+                    let config = Config::load_with_project(Some(&context.project_root_path))
+                        .unwrap_or_default();
+                    git::list_worktrees(&context.repo_path)
+                        .ok()
+                        .and_then(|worktrees| {
+                            let fallback = current_worktree_path(&worktrees, &path).or_else(|| {
+                                worktrees
+                                    .iter()
+                                    .find(|wt| !wt.is_bare)
+                                    .map(|wt| wt.path.clone())
+                            });
+                            config.copy_source_path(&worktrees, fallback)
+                        })
+                }
+                None => None,
+            };
+
+            let report = config_check::check_configs(&config_check::CheckContext {
+                project_root_path: context.as_ref().map(|c| c.project_root_path.as_path()),
+                repo_path: context.as_ref().map(|c| c.repo_path.as_path()),
+                copy_source: copy_source.as_deref(),
+            });
+            config_check::print_check_report(&report);
+
+            let errors = report.error_count();
+            if errors > 0 {
+                anyhow::bail!("config check found {} error(s)", errors);
+            }
+            Ok(())
+        }
+        ConfigCommand::Export { file } => {
+            let export = config_bundle::export(&Config::global_config_dir())?;
+            let Some(file) = file else {
+                print!("{}", export.bundle);
+                for name in &export.skipped {
+                    eprintln!("skipped\t{}\tnot a text file", plain_field(name));
+                }
+                return Ok(());
+            };
+
+            std::fs::write(&file, &export.bundle)
+                .with_context(|| format!("Failed to write {}", file.display()))?;
+            for name in &export.files {
+                println!("exported\t{}", plain_field(name));
+            }
+            for name in &export.skipped {
+                println!("skipped\t{}\tnot a text file", plain_field(name));
+            }
+            Ok(())
+        }
+        ConfigCommand::Import { file, force } => {
+            let content = if file == Path::new("-") {
+                io::read_to_string(io::stdin()).context("Failed to read the bundle from stdin")?
+            } else {
+                std::fs::read_to_string(&file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?
+            };
+
+            let results = config_bundle::import(&Config::global_config_dir(), &content, force)?;
+            for (name, action) in results {
+                println!("{}\t{}", action.label(), plain_field(&name));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn resolve_repository_context(path: &Path) -> Result<RepositoryContext> {
+    if let Some(bare_path) = git::find_bare_in_parent(path) {
+        let project_root = bare_path
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| path.to_path_buf());
+        return Ok(RepositoryContext {
+            repo_path: bare_path,
+            project_root_path: project_root,
+            repo_is_bare: true,
+        });
+    }
+
+    if !git::is_git_repo(path) {
+        anyhow::bail!("Not a git repository");
+    }
+
+    let common_dir = git::get_git_common_dir(path)?;
+    if git::is_bare_repo(&common_dir)? {
+        let project_root = common_dir
             .parent()
             .map(PathBuf::from)
             .unwrap_or_else(|| path.to_path_buf());
@@ -588,18 +1342,22 @@ fn refresh_pr_statuses(repo_path: &Path, worktrees: &mut [types::Worktree]) {
 }
 
 fn worktree_path_for_branch(context: &RepositoryContext, config: &Config, branch: &str) -> PathBuf {
+    if let Some(path) = config.templated_worktree_path(&context.project_root_path, branch) {
+        return path;
+    }
+    let folder = config.worktree_folder(branch);
     if context.repo_is_bare {
         return context
             .repo_path
             .parent()
-            .map(|parent| parent.join(branch))
-            .unwrap_or_else(|| PathBuf::from(branch));
+            .map(|parent| parent.join(&folder))
+            .unwrap_or_else(|| PathBuf::from(&folder));
     }
 
     config
         .resolved_worktree_root()
         .join(repo_namespace(&context.project_root_path))
-        .join(branch)
+        .join(folder)
 }
 
 fn worktree_name_from_path(path: &Path) -> String {
@@ -705,7 +1463,11 @@ fn pr_status_targets(
         .collect()
 }
 
-fn worktree_matches(worktree: &types::Worktree, needle: &str) -> bool {
+fn worktree_matches(
+    worktree: &types::Worktree,
+    descriptions: &HashMap<String, String>,
+    needle: &str,
+) -> bool {
     let pr_status = worktree.github_pr_status.map(|status| status.label());
     [
         worktree.path.display().to_string(),
@@ -713,12 +1475,76 @@ fn worktree_matches(worktree: &types::Worktree, needle: &str) -> bool {
         worktree.branch_display(),
         worktree.status.label().to_string(),
         pr_status.unwrap_or("-").to_string(),
+        branch_description_field(worktree, descriptions).to_string(),
     ]
     .iter()
     .any(|value| value.to_lowercase().contains(needle))
 }
 
-fn print_worktree_record(worktree: &types::Worktree) {
+/// Description of the worktree's branch, or `-`
+fn branch_description_field<'a>(
+    worktree: &types::Worktree,
+    descriptions: &'a HashMap<String, String>,
+) -> &'a str {
+    worktree
+        .branch
+        .as_ref()
+        .and_then(|branch| descriptions.get(branch))
+        .map_or("-", String::as_str)
+}
+
+/// `list` and `search` output: a record per worktree, or one JSON array of
+/// worktree objects
+fn print_worktree_records(
+    worktrees: &[types::Worktree],
+    descriptions: &HashMap<String, String>,
+    json: bool,
+) {
+    if json {
+        let listed = worktrees
+            .iter()
+            .map(|worktree| rpc::listed_worktree_json(worktree, descriptions))
+            .collect();
+        println!("{}", serde_json::Value::Array(listed));
+        return;
+    }
+    for worktree in worktrees {
+        print_worktree_record(worktree, descriptions);
+    }
+}
+
+/// `--porcelain=v1`: a version header, then one line per worktree. Never
+/// change this format; add a `v2` next to it instead.
+fn print_porcelain_v1(worktrees: &[types::Worktree]) {
+    println!("# owt porcelain v1");
+    for worktree in worktrees {
+        println!("{}", porcelain_v1_line(worktree));
+    }
+}
+
+/// `kind status ahead behind locked pr branch path`, separated by single
+/// spaces. Only the path, last, can contain spaces; unknown values are `-`.
+fn porcelain_v1_line(worktree: &types::Worktree) -> String {
+    let count = |count: Option<u32>| count.map_or("-".to_string(), |count| count.to_string());
+    let ahead_behind = worktree.ahead_behind.as_ref();
+    format!(
+        "{} {} {} {} {} {} {} {}",
+        if worktree.is_bare { "bare" } else { "worktree" },
+        worktree.status.label(),
+        count(ahead_behind.map(|ahead_behind| ahead_behind.ahead)),
+        count(ahead_behind.map(|ahead_behind| ahead_behind.behind)),
+        if worktree.locked { "locked" } else { "-" },
+        worktree.github_pr_display(),
+        worktree.branch_display(),
+        worktree
+            .path
+            .display()
+            .to_string()
+            .replace(['\n', '\r'], " ")
+    )
+}
+
+fn print_worktree_record(worktree: &types::Worktree, descriptions: &HashMap<String, String>) {
     let (ahead, behind) = worktree
         .ahead_behind
         .as_ref()
@@ -731,7 +1557,7 @@ fn print_worktree_record(worktree: &types::Worktree) {
         .unwrap_or_else(|| ("-".to_string(), "-".to_string()));
 
     println!(
-        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
         if worktree.is_bare { "bare" } else { "worktree" },
         plain_field(&worktree.path.display().to_string()),
         plain_field(&worktree.branch_display()),
@@ -739,10 +1565,15 @@ fn print_worktree_record(worktree: &types::Worktree) {
         plain_field(worktree.last_commit_time.as_deref().unwrap_or("-")),
         ahead,
         behind,
-        worktree.github_pr_display()
+        worktree.github_pr_display(),
+        plain_field(branch_description_field(worktree, descriptions))
     );
 }
 
+fn copy_source_missing(name: &str) -> String {
+    format!("copy_source '{}' matches no worktree; nothing copied", name)
+}
+
 fn copy_configured_files(source: &Path, destination: &Path, files: &[String]) -> Vec<String> {
     files
         .iter()
@@ -773,17 +1604,6 @@ fn copy_configured_file(source: &Path, destination: &Path, file: &str) -> Result
     Ok(())
 }
 
-fn paths_refer_to_same_location(left: &Path, right: &Path) -> bool {
-    if left == right {
-        return true;
-    }
-
-    match (left.canonicalize(), right.canonicalize()) {
-        (Ok(left), Ok(right)) => left == right,
-        _ => false,
-    }
-}
-
 fn plain_field(value: &str) -> String {
     value.replace(['\t', '\n', '\r'], " ")
 }
@@ -802,39 +1622,241 @@ fn process_failure_detail(output: &std::process::Output) -> String {
     }
 }
 
-fn run_clone(url: &str, target_path: Option<PathBuf>) -> Result<()> {
+fn run_clone(
+    url: &str,
+    target_path: Option<PathBuf>,
+    branch: Option<&str>,
+    depth: Option<u32>,
+    no_worktree: bool,
+) -> Result<PathBuf> {
+    let config = Config::load().unwrap_or_default();
+    let (source, resolved) = resolve_clone_url(url, &config);
+
     // Extract repo name from URL
-    let repo_name = extract_repo_name(url);
+    let repo_name = extract_repo_name(&resolved);
 
     // Determine paths
     let base_dir =
         target_path.unwrap_or_else(|| env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
     let project_dir = base_dir.join(&repo_name);
     let bare_repo_path = project_dir.join(".bare");
-    let worktree_path = project_dir.join("main");
-
-    println!("Cloning {} as bare repository...", url);
 
-    // Clone as bare
-    git::clone_bare(url, &bare_repo_path)?;
+    match depth {
+        Some(depth) => println!(
+            "Cloning {} as bare repository (depth {})...",
+            resolved, depth
+        ),
+        None => println!("Cloning {} as bare repository...", resolved),
+    }
+
+    // Clone as bare, redrawing one progress line on a terminal and printing
+    // each finished phase otherwise
+    let live = io::stdout().is_terminal();
+    let mut drawn = false;
+    let mut finished_phase = String::new();
+    let cloned = git::clone_bare(&source, &bare_repo_path, branch, depth, |progress| {
+        let line = format!("  {}: {:>3}%", progress.phase, progress.percent);
+        if live {
+            print!("\r\x1b[2K{}", line);
+            let _ = io::stdout().flush();
+            drawn = true;
+        } else if progress.percent == 100 && progress.phase != finished_phase {
+            println!("{}", line);
+            finished_phase = progress.phase;
+        }
+    });
+    if drawn {
+        println!();
+    }
+    cloned?;
     println!("  Created bare repo: {}", bare_repo_path.display());
 
-    // Get default branch
-    let default_branch =
-        git::get_default_branch(&bare_repo_path).unwrap_or_else(|_| "main".to_string());
+    if no_worktree {
+        println!("\nDone! Create a worktree with:");
+        println!("  owt add <BRANCH> --path {}", bare_repo_path.display());
+        return Ok(project_dir);
+    }
+
+    let (first_branch, worktree_path) =
+        first_clone_worktree(&config, &bare_repo_path, &project_dir, branch);
 
     // Create first worktree
-    println!("Creating worktree for '{}'...", default_branch);
-    git::add_worktree(&bare_repo_path, &default_branch, &worktree_path, None)?;
+    println!("Creating worktree for '{}'...", first_branch);
+    git::add_worktree(&bare_repo_path, &first_branch, &worktree_path, None)?;
     println!("  Created worktree: {}", worktree_path.display());
 
     println!("\nDone! To start using owt:");
     println!("  cd {}", project_dir.display());
     println!("  owt");
 
+    Ok(project_dir)
+}
+
+/// Record a fresh clone in the project registry. The clone already
+/// succeeded, so a registry problem is only a warning.
+fn register_cloned_project(project_dir: &Path) {
+    match repo_registry::register(project_dir) {
+        Ok(name) => println!(
+            "  Registered as '{}'; open it from anywhere with: owt {}",
+            name, name
+        ),
+        Err(error) => eprintln!("warning	registry	{}", plain_field(&error.to_string())),
+    }
+}
+
+fn run_repos(json: bool) -> Result<()> {
+    let repos = repo_registry::load()?;
+    if json {
+        let records: Vec<serde_json::Value> = repos
+            .into_iter()
+            .map(|(name, path)| {
+                serde_json::json!({
+                    "name": name,
+                    "state": if path.is_dir() { "ok" } else { "missing" },
+                    "path": path.display().to_string(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::Value::Array(records));
+        return Ok(());
+    }
+    for (name, path) in repos {
+        let state = if path.is_dir() { "ok" } else { "missing" };
+        println!(
+            "{}\t{}\t{}",
+            plain_field(&name),
+            plain_field(&path.display().to_string()),
+            state
+        );
+    }
+    Ok(())
+}
+
+/// Run `command` in each worktree `filter` selects, `jobs` at a time, and
+/// report every exit code. One job streams output as it comes; more collect
+/// each worktree's output and print it in one piece when it finishes. Fails
+/// with the list of worktrees whose command failed.
+fn run_exec(
+    path: &Path,
+    filter: &worktree_exec::ExecFilter,
+    command: &[String],
+    jobs: Option<usize>,
+) -> Result<()> {
+    let context = resolve_repository_context(path)?;
+    let worktrees = git::list_worktrees(&context.repo_path)?;
+    let targets = filter.select(&worktrees);
+    if targets.is_empty() {
+        println!("exec\t0");
+        return Ok(());
+    }
+
+    let mut failed = Vec::new();
+    let mut finish = |worktree: &types::Worktree, status: Result<std::process::ExitStatus>| {
+        if !print_exec_record(worktree, status) {
+            failed.push(worktree.display_name());
+        }
+    };
+    let jobs = jobs.unwrap_or_else(worktree_exec::default_jobs);
+    if jobs <= 1 {
+        for worktree in &targets {
+            eprintln!("{}", exec_header(worktree));
+            finish(worktree, worktree_exec::run_in(worktree, command));
+        }
+    } else {
+        worktree_exec::run_parallel(&targets, command, jobs, |index, result| {
+            let worktree = targets[index];
+            eprintln!("{}", exec_header(worktree));
+            let status = result.map(|output| {
+                let _ = io::stdout().write_all(&output.stdout);
+                let _ = io::stderr().write_all(&output.stderr);
+                output.status
+            });
+            finish(worktree, status);
+        });
+    }
+    if !failed.is_empty() {
+        anyhow::bail!(
+            "{} of {} worktree(s) failed: {}",
+            failed.len(),
+            targets.len(),
+            failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn exec_header(worktree: &types::Worktree) -> String {
+    format!(
+        "==> {} ({})",
+        worktree.display_name(),
+        worktree.branch.as_deref().unwrap_or("-")
+    )
+}
+
+/// Print the `exec` record for one worktree; true when the command succeeded
+fn print_exec_record(worktree: &types::Worktree, status: Result<std::process::ExitStatus>) -> bool {
+    let location = format!(
+        "{}\t{}",
+        plain_field(worktree.branch.as_deref().unwrap_or("-")),
+        plain_field(&worktree.path.display().to_string())
+    );
+    match status {
+        Ok(status) if status.success() => {
+            println!("exec\t0\t{}", location);
+            true
+        }
+        Ok(status) => {
+            match status.code() {
+                Some(code) => println!("exec\t{}\t{}", code, location),
+                None => println!("exec\terror\t{}\tkilled by signal", location),
+            }
+            false
+        }
+        Err(error) => {
+            println!(
+                "exec\terror\t{}\t{}",
+                location,
+                plain_field(&format!("{:#}", error))
+            );
+            false
+        }
+    }
+}
+
+fn run_path(path: &Path, target: &str) -> Result<()> {
+    let context = resolve_repository_context(path)?;
+    let worktrees = git::list_worktrees(&context.repo_path)?;
+    let worktree = find_worktree_target(&worktrees, target)?;
+    if worktree.is_bare {
+        anyhow::bail!("'{}' is the bare repository, not a worktree", target);
+    }
+    let absolute = worktree.path.canonicalize().unwrap_or(worktree.path);
+    println!("{}", absolute.display());
     Ok(())
 }
 
+/// Branch and path of the first worktree in a fresh clone: the requested
+/// branch, or the remote default checked out in `main/`. A `worktree_dir`
+/// template places it like any other new worktree.
+fn first_clone_worktree(
+    config: &Config,
+    bare_repo_path: &Path,
+    project_dir: &Path,
+    branch: Option<&str>,
+) -> (String, PathBuf) {
+    let (branch, folder) = match branch {
+        Some(branch) => (branch.to_string(), config.worktree_folder(branch)),
+        None => (
+            git::get_default_branch(bare_repo_path).unwrap_or_else(|_| "main".to_string()),
+            "main".to_string(),
+        ),
+    };
+    let path = config
+        .templated_worktree_path(project_dir, &branch)
+        .unwrap_or_else(|| project_dir.join(folder));
+    (branch, path)
+}
+
 fn run_init() -> Result<()> {
     let current_dir = env::current_dir()?;
 
@@ -866,7 +1888,8 @@ fn run_init() -> Result<()> {
         .unwrap_or_else(|| "myproject".to_string());
 
     println!("This is a regular git repository.");
-    println!("\nTo convert to bare repository + worktree setup:\n");
+    println!("\nRun 'owt convert' to convert it automatically ('owt convert --dry-run' shows the steps),");
+    println!("or convert to bare repository + worktree setup by hand:\n");
     println!("  # 1. Go to parent directory");
     println!("  cd ..\n");
     println!("  # 2. Move .git to new bare repo");
@@ -887,16 +1910,7 @@ fn run_setup() -> Result<()> {
     use std::fs;
     use std::io::{self, Write};
 
-    // Detect shell from SHELL environment variable
-    let shell = env::var("SHELL").unwrap_or_default();
-    let home = env::var("HOME").ok().map(PathBuf::from);
-    let (shell_name, config_file) = if shell.contains("zsh") {
-        ("zsh", home.map(|h| h.join(".zshrc")))
-    } else if shell.contains("bash") {
-        ("bash", home.map(|h| h.join(".bashrc")))
-    } else {
-        ("unknown", None)
-    };
+    let (shell_name, config_file) = shell_config_file();
 
     let config_path = match config_file {
         Some(path) => path,
@@ -914,7 +1928,7 @@ fn run_setup() -> Result<()> {
     // Check if function already exists
     if config_path.exists() {
         let content = fs::read_to_string(&config_path)?;
-        if content.contains("owt()") || content.contains("owt ()") {
+        if has_shell_function(&content) {
             println!("\n✓ Shell integration already installed!");
             println!(
                 "  If it's not working, try: source {}",
@@ -979,639 +1993,84 @@ fn run_setup() -> Result<()> {
     Ok(())
 }
 
+/// Shell name and rc file detected from `SHELL`
+fn shell_config_file() -> (&'static str, Option<PathBuf>) {
+    let shell = env::var("SHELL").unwrap_or_default();
+    let home = env::var("HOME").ok().map(PathBuf::from);
+    if shell.contains("zsh") {
+        ("zsh", home.map(|h| h.join(".zshrc")))
+    } else if shell.contains("bash") {
+        ("bash", home.map(|h| h.join(".bashrc")))
+    } else {
+        ("unknown", None)
+    }
+}
+
+fn has_shell_function(content: &str) -> bool {
+    content.contains("owt()") || content.contains("owt ()")
+}
+
 fn extract_repo_name(url: &str) -> String {
     // Handle various URL formats:
     // https://github.com/user/repo.git
     // git@github.com:user/repo.git
+    // git@host:repo.git
     // /path/to/repo.git
     // repo.git
 
     let url = url.trim_end_matches('/');
-    let name = url
-        .rsplit('/')
-        .next()
-        .or_else(|| url.rsplit(':').next())
-        .unwrap_or(url);
+    let name = url.rsplit(['/', ':']).next().unwrap_or(url);
 
     name.trim_end_matches(".git").to_string()
 }
 
+/// The URL to hand to `git clone` for what the user typed, and what git will
+/// fetch from once `url.<base>.insteadOf` rules apply. The latter names the
+/// project directory and is what gets printed.
+pub(crate) fn resolve_clone_url(url: &str, config: &Config) -> (String, String) {
+    let source =
+        expand_clone_shorthand(url, config.get_clone_host()).unwrap_or_else(|| url.to_string());
+    let resolved =
+        git::rewrite_url(&source, &git::url_rewrites()).unwrap_or_else(|| source.clone());
+    (source, resolved)
+}
+
+/// `user/repo` as an https URL on `host`. Anything with a scheme or an
+/// scp-style `host:`, or that exists on disk, is not shorthand.
+fn expand_clone_shorthand(url: &str, host: &str) -> Option<String> {
+    let plain = |part: &str| {
+        !part.is_empty()
+            && part != "."
+            && part != ".."
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    let (owner, repo) = url.split_once('/')?;
+    if !plain(owner) || !plain(repo) || Path::new(url).exists() {
+        return None;
+    }
+    Some(format!(
+        "https://{}/{}/{}.git",
+        host,
+        owner,
+        repo.trim_end_matches(".git")
+    ))
+}
+
 fn parse_args() -> Command {
     let args: Vec<String> = env::args().collect();
     parse_args_from(args, || {
         env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
     })
+    .unwrap_or_else(|error| error.exit())
 }
 
-fn parse_args_from(args: Vec<String>, current_dir: impl Fn() -> PathBuf) -> Command {
-    let current_dir = &current_dir;
-
-    if args.len() < 2 {
-        return Command::Tui {
-            path: current_dir(),
-        };
-    }
-
-    match args[1].as_str() {
-        "--help" | "-h" | "help" => Command::Help(HelpTopic::Root),
-        "--version" | "-v" => Command::Version,
-        "clone" => {
-            if args.len() < 3 {
-                eprintln!("Error: clone requires a URL argument");
-                eprintln!("Usage: owt clone <url> [path]");
-                std::process::exit(1);
-            }
-            let url = args[2].clone();
-            let path = args.get(3).map(PathBuf::from);
-            Command::Clone { url, path }
-        }
-        "init" => Command::Init,
-        "setup" => Command::Setup,
-        "worktree" => parse_worktree_command(&args[2..], current_dir()),
-        "pr" => parse_pr_command(&args[2..], current_dir()),
-        "commit" => parse_commit_command(&args[2..], current_dir()),
-        "search" => parse_search_command(&args[2..], current_dir()),
-        "test-cd" | "--test-cd" => Command::TestCd,
-        arg if arg.starts_with('-') => {
-            // Handle flags for TUI mode
-            let mut path = current_dir();
-            let mut i = 1;
-            while i < args.len() {
-                match args[i].as_str() {
-                    "--path" | "-p" => {
-                        if i + 1 < args.len() {
-                            path = PathBuf::from(&args[i + 1]);
-                            i += 2;
-                        } else {
-                            eprintln!("Error: --path requires an argument");
-                            std::process::exit(1);
-                        }
-                    }
-                    _ => i += 1,
-                }
-            }
-            Command::Tui { path }
-        }
-        _ => {
-            // Treat as path for TUI mode
-            Command::Tui {
-                path: PathBuf::from(&args[1]),
-            }
-        }
-    }
-}
-
-fn parse_worktree_command(args: &[String], default_path: PathBuf) -> Command {
-    if args.is_empty() || is_help_arg(&args[0]) {
-        return Command::Help(HelpTopic::Worktree);
-    }
-
-    match args[0].as_str() {
-        "list" | "ls" => {
-            if has_help_arg(&args[1..]) {
-                return Command::Help(HelpTopic::WorktreeList);
-            }
-            let mut path = default_path;
-            let mut include_pr = false;
-            let mut i = 1;
-            while i < args.len() {
-                match args[i].as_str() {
-                    "--path" | "-p" => {
-                        path = PathBuf::from(option_value(args, i, "--path"));
-                        i += 2;
-                    }
-                    "--pr" => {
-                        include_pr = true;
-                        i += 1;
-                    }
-                    arg => unknown_arg("owt worktree list", arg),
-                }
-            }
-            Command::Worktree(WorktreeCommand::List { path, include_pr })
-        }
-        "create" => {
-            if has_help_arg(&args[1..]) {
-                return Command::Help(HelpTopic::WorktreeCreate);
-            }
-            let mut path = default_path;
-            let mut base = None;
-            let mut worktree_path = None;
-            let mut tmux = None;
-            let mut branch = None;
-            let mut i = 1;
-            while i < args.len() {
-                match args[i].as_str() {
-                    "--path" | "-p" => {
-                        path = PathBuf::from(option_value(args, i, "--path"));
-                        i += 2;
-                    }
-                    "--base" | "-b" => {
-                        base = Some(option_value(args, i, "--base").to_string());
-                        i += 2;
-                    }
-                    "--worktree-path" => {
-                        worktree_path =
-                            Some(PathBuf::from(option_value(args, i, "--worktree-path")));
-                        i += 2;
-                    }
-                    arg if arg.starts_with("--tmux=") => {
-                        tmux = Some(parse_on_off_value("--tmux", &arg["--tmux=".len()..]));
-                        i += 1;
-                    }
-                    "--tmux" => {
-                        if i + 1 < args.len() && !args[i + 1].starts_with('-') {
-                            tmux = Some(parse_on_off_value("--tmux", &args[i + 1]));
-                            i += 2;
-                        } else {
-                            tmux = Some(true);
-                            i += 1;
-                        }
-                    }
-                    arg if arg.starts_with('-') => unknown_arg("owt worktree create", arg),
-                    arg => {
-                        if branch.replace(arg.to_string()).is_some() {
-                            unknown_arg("owt worktree create", arg);
-                        }
-                        i += 1;
-                    }
-                }
-            }
-            let branch = branch.unwrap_or_else(|| missing_arg("owt worktree create", "<branch>"));
-            Command::Worktree(WorktreeCommand::Create {
-                path,
-                branch,
-                base,
-                worktree_path,
-                tmux,
-            })
-        }
-        "delete" | "remove" | "rm" => {
-            if has_help_arg(&args[1..]) {
-                return Command::Help(HelpTopic::WorktreeDelete);
-            }
-            let mut path = default_path;
-            let mut force = false;
-            let mut delete_branch = false;
-            let mut target = None;
-            let mut i = 1;
-            while i < args.len() {
-                match args[i].as_str() {
-                    "--path" | "-p" => {
-                        path = PathBuf::from(option_value(args, i, "--path"));
-                        i += 2;
-                    }
-                    "--force" | "-f" => {
-                        force = true;
-                        i += 1;
-                    }
-                    "--branch" => {
-                        delete_branch = true;
-                        i += 1;
-                    }
-                    arg if arg.starts_with('-') => unknown_arg("owt worktree delete", arg),
-                    arg => {
-                        if target.replace(arg.to_string()).is_some() {
-                            unknown_arg("owt worktree delete", arg);
-                        }
-                        i += 1;
-                    }
-                }
-            }
-            let target = target.unwrap_or_else(|| missing_arg("owt worktree delete", "<target>"));
-            Command::Worktree(WorktreeCommand::Delete {
-                path,
-                target,
-                force,
-                delete_branch,
-            })
-        }
-        "prune" => {
-            if has_help_arg(&args[1..]) {
-                return Command::Help(HelpTopic::WorktreePrune);
-            }
-            let mut path = default_path;
-            let mut dry_run = false;
-            let mut i = 1;
-            while i < args.len() {
-                match args[i].as_str() {
-                    "--path" | "-p" => {
-                        path = PathBuf::from(option_value(args, i, "--path"));
-                        i += 2;
-                    }
-                    "--dry-run" => {
-                        dry_run = true;
-                        i += 1;
-                    }
-                    arg => unknown_arg("owt worktree prune", arg),
-                }
-            }
-            Command::Worktree(WorktreeCommand::Prune { path, dry_run })
-        }
-        _ => Command::Help(HelpTopic::Worktree),
-    }
-}
-
-fn parse_pr_command(args: &[String], default_path: PathBuf) -> Command {
-    if args.is_empty() || is_help_arg(&args[0]) {
-        return Command::Help(HelpTopic::Pr);
-    }
-
-    match args[0].as_str() {
-        "status" => {
-            if has_help_arg(&args[1..]) {
-                return Command::Help(HelpTopic::PrStatus);
-            }
-            let mut path = default_path;
-            let mut branch = None;
-            let mut all = false;
-            let mut i = 1;
-            while i < args.len() {
-                match args[i].as_str() {
-                    "--path" | "-p" => {
-                        path = PathBuf::from(option_value(args, i, "--path"));
-                        i += 2;
-                    }
-                    "--branch" | "-b" => {
-                        branch = Some(option_value(args, i, "--branch").to_string());
-                        i += 2;
-                    }
-                    "--all" => {
-                        all = true;
-                        i += 1;
-                    }
-                    arg => unknown_arg("owt pr status", arg),
-                }
-            }
-            Command::Pr(PrCommand::Status { path, branch, all })
-        }
-        _ => Command::Help(HelpTopic::Pr),
-    }
-}
-
-fn parse_commit_command(args: &[String], default_path: PathBuf) -> Command {
-    if args.is_empty() || is_help_arg(&args[0]) {
-        return Command::Help(HelpTopic::Commit);
-    }
-
-    match args[0].as_str() {
-        "tree" => {
-            if has_help_arg(&args[1..]) {
-                return Command::Help(HelpTopic::CommitTree);
-            }
-            let mut path = default_path;
-            let mut limit = 8;
-            let mut i = 1;
-            while i < args.len() {
-                match args[i].as_str() {
-                    "--path" | "-p" => {
-                        path = PathBuf::from(option_value(args, i, "--path"));
-                        i += 2;
-                    }
-                    "--limit" | "-n" => {
-                        limit = option_value(args, i, "--limit")
-                            .parse()
-                            .unwrap_or_else(|_| {
-                                eprintln!("Error: --limit requires a positive integer");
-                                std::process::exit(1);
-                            });
-                        i += 2;
-                    }
-                    arg => unknown_arg("owt commit tree", arg),
-                }
-            }
-            Command::Commit(CommitCommand::Tree { path, limit })
-        }
-        _ => Command::Help(HelpTopic::Commit),
-    }
-}
-
-fn parse_search_command(args: &[String], default_path: PathBuf) -> Command {
-    if args.is_empty() || is_help_arg(&args[0]) {
-        return Command::Help(HelpTopic::Search);
-    }
-
-    let mut path = default_path;
-    let mut include_pr = false;
-    let mut query = None;
-    let mut i = 0;
-    while i < args.len() {
-        match args[i].as_str() {
-            "--path" | "-p" => {
-                path = PathBuf::from(option_value(args, i, "--path"));
-                i += 2;
-            }
-            "--pr" => {
-                include_pr = true;
-                i += 1;
-            }
-            arg if arg.starts_with('-') => unknown_arg("owt search", arg),
-            arg => {
-                if query.replace(arg.to_string()).is_some() {
-                    unknown_arg("owt search", arg);
-                }
-                i += 1;
-            }
-        }
-    }
-    let query = query.unwrap_or_else(|| missing_arg("owt search", "<query>"));
-    Command::Search(SearchCommand::Query {
-        path,
-        query,
-        include_pr,
-    })
-}
-
-fn option_value<'a>(args: &'a [String], index: usize, flag: &str) -> &'a str {
-    args.get(index + 1)
-        .map(String::as_str)
-        .unwrap_or_else(|| missing_arg(flag, "<value>"))
-}
-
-fn parse_on_off_value(flag: &str, value: &str) -> bool {
-    match value {
-        "on" | "true" | "1" | "yes" => true,
-        "off" | "false" | "0" | "no" => false,
-        _ => {
-            eprintln!("Error: {} requires on or off", flag);
-            std::process::exit(1);
-        }
-    }
-}
-
-fn is_help_arg(arg: &str) -> bool {
-    matches!(arg, "--help" | "-h" | "help")
-}
-
-fn has_help_arg(args: &[String]) -> bool {
-    args.iter().any(|arg| is_help_arg(arg))
-}
-
-fn unknown_arg(command: &str, arg: &str) -> ! {
-    eprintln!("Error: unexpected argument '{}' for {}", arg, command);
-    eprintln!("Run '{} --help' for usage.", command);
-    std::process::exit(1);
-}
-
-fn missing_arg(command: &str, arg: &str) -> ! {
-    eprintln!("Error: {} requires {}", command, arg);
-    std::process::exit(1);
-}
-
-fn print_help(topic: HelpTopic) {
-    match topic {
-        HelpTopic::Root => print_root_help(),
-        HelpTopic::Worktree => print_worktree_help(),
-        HelpTopic::WorktreeList => print_worktree_list_help(),
-        HelpTopic::WorktreeCreate => print_worktree_create_help(),
-        HelpTopic::WorktreeDelete => print_worktree_delete_help(),
-        HelpTopic::WorktreePrune => print_worktree_prune_help(),
-        HelpTopic::Pr => print_pr_help(),
-        HelpTopic::PrStatus => print_pr_status_help(),
-        HelpTopic::Commit => print_commit_help(),
-        HelpTopic::CommitTree => print_commit_tree_help(),
-        HelpTopic::Search => print_search_help(),
-    }
-}
-
-fn print_root_help() {
-    println!(
-        r#"owt - Git Worktree Manager
-
-USAGE:
-    owt [OPTIONS] [PATH]         Start TUI (default)
-    owt clone <URL> [PATH]       Clone as bare repo + create main worktree
-    owt init                     Show guide to convert regular repo to bare
-    owt setup                    Install shell integration for directory changing
-
-ARGS:
-    [PATH]    Path to a Git repository or worktree (default: current directory)
-
-OPTIONS:
-    -p, --path <PATH>    Path to a Git repository or worktree
-    -h, --help           Print help information
-    -v, --version        Print version information
-
-SUBCOMMANDS:
-    clone <URL> [PATH]   Clone repository as bare and create first worktree
-    init                 Show conversion guide for regular repositories
-    setup                Install shell integration (adds function to .zshrc/.bashrc)
-    worktree             Manage worktrees with plain CLI output
-    pr                   Inspect GitHub PR merge status
-    commit               Inspect commit history
-    search               Search worktrees
-
-KEYBINDINGS (TUI):
-    Enter       Enter worktree (cd to directory)
-    j/k, ↑/↓    Navigate worktrees
-    a           Add new worktree
-    d           Delete selected worktree
-    o           Open in editor ($EDITOR)
-    t           Open in terminal ($TERMINAL)
-    f           Fetch all remotes
-    r           Refresh worktree list
-    c           View config settings
-    q           Quit
-
-ENVIRONMENT:
-    EDITOR      Editor to use (default: vim)
-    TERMINAL    Terminal app to use (default: Terminal.app on macOS)
-
-SHELL INTEGRATION:
-    Run `owt setup` to install the secure OWT_OUTPUT_FILE shell integration.
-    The TUI draws through /dev/tty so stdout can remain reserved for cd handoff.
-
-EXAMPLES:
-    owt clone https://github.com/user/repo.git
-    owt clone git@github.com:user/repo.git ~/projects
-    owt init
-    owt --path ~/repos/myproject.git
-    owt worktree list
-    owt worktree create feature/login --base main
-    owt pr status --branch feature/login
-    owt commit tree -n 12
-    owt search login"#
-    );
-}
-
-fn print_worktree_help() {
-    println!(
-        r#"Manage worktrees with plain CLI output.
-
-USAGE:
-    owt worktree <COMMAND>
-
-COMMANDS:
-    list      List worktrees as tab-separated records
-    create    Create a worktree for a branch
-    delete    Delete a worktree by branch, name, or path
-    prune     Prune missing metadata and completed PR worktrees
-
-EXAMPLES:
-    owt worktree list
-    owt worktree list --pr
-    owt worktree create feature/login --base main
-    owt worktree delete feature/login --branch --force"#
-    );
-}
-
-fn print_worktree_list_help() {
-    println!(
-        r#"List worktrees as tab-separated records.
-
-USAGE:
-    owt worktree list [OPTIONS]
-
-OPTIONS:
-    -p, --path <PATH>    Repository or worktree path (default: current directory)
-        --pr             Include GitHub PR status from gh
-    -h, --help           Print help information
-
-OUTPUT:
-    kind<TAB>path<TAB>branch<TAB>status<TAB>last_commit<TAB>ahead<TAB>behind<TAB>pr"#
-    );
-}
-
-fn print_worktree_create_help() {
-    println!(
-        r#"Create a worktree for a branch.
-
-USAGE:
-    owt worktree create <BRANCH> [OPTIONS]
-
-OPTIONS:
-    -p, --path <PATH>             Repository or worktree path (default: current directory)
-    -b, --base <BRANCH>           Base branch for a new branch
-        --worktree-path <PATH>    Explicit destination path
-        --tmux=on|off             Override tmux worktree pane mode for this create
-    -h, --help                    Print help information
-
-OUTPUT:
-    created<TAB>branch<TAB>path"#
-    );
-}
-
-fn print_worktree_delete_help() {
-    println!(
-        r#"Delete a worktree by branch, name, or path.
-
-USAGE:
-    owt worktree delete <TARGET> [OPTIONS]
-
-OPTIONS:
-    -p, --path <PATH>    Repository or worktree path (default: current directory)
-    -f, --force          Delete even with uncommitted changes
-        --branch         Delete the local branch after removing the worktree
-    -h, --help           Print help information
-
-OUTPUT:
-    deleted<TAB>branch<TAB>path"#
-    );
-}
-
-fn print_worktree_prune_help() {
-    println!(
-        r#"Prune missing metadata and completed PR worktrees.
-
-USAGE:
-    owt worktree prune [OPTIONS]
-
-OPTIONS:
-    -p, --path <PATH>    Repository or worktree path (default: current directory)
-        --dry-run        Preview metadata pruning and review removable worktrees one at a time
-    -h, --help           Print help information
-
-OUTPUT:
-    pruned<TAB>0
-    pruned<TAB>worktree<TAB>branch<TAB>path
-    pruned<TAB>log<TAB>action<TAB>branch<TAB>path<TAB>reason
-    pruned<TAB>metadata<TAB>result
-
-NOTES:
-    Normal mode logs every worktree and removes non-current clean worktrees whose GitHub PR status is merged or closed. Removal runs in parallel and never deletes branches or the HEAD branch worktree.
-    --dry-run does not delete worktrees; it reviews removable candidates serially and logs selected candidates as would-remove."#
-    );
-}
-
-fn print_pr_help() {
-    println!(
-        r#"Inspect GitHub PR status through gh.
-
-USAGE:
-    owt pr <COMMAND>
-
-COMMANDS:
-    status    Show PR status for a branch or worktree
-
-EXAMPLES:
-    owt pr status
-    owt pr status --branch feature/login
-    owt pr status --all"#
-    );
-}
-
-fn print_pr_status_help() {
-    println!(
-        r#"Show GitHub PR status for a branch or worktree.
-
-USAGE:
-    owt pr status [OPTIONS]
-
-OPTIONS:
-    -p, --path <PATH>       Repository or worktree path (default: current directory)
-    -b, --branch <BRANCH>   Branch to inspect
-        --all               Inspect every non-bare worktree
-    -h, --help              Print help information
-
-OUTPUT:
-    branch<TAB>path<TAB>status"#
-    );
-}
-
-fn print_commit_help() {
-    println!(
-        r#"Inspect commit history.
-
-USAGE:
-    owt commit <COMMAND>
-
-COMMANDS:
-    tree    Print recent commits as a git graph
-
-EXAMPLES:
-    owt commit tree
-    owt commit tree -n 20"#
-    );
-}
-
-fn print_commit_tree_help() {
-    println!(
-        r#"Print recent commits as a git graph.
-
-USAGE:
-    owt commit tree [OPTIONS]
-
-OPTIONS:
-    -p, --path <PATH>     Worktree path (default: current directory)
-    -n, --limit <COUNT>   Number of commits to print (default: 8)
-    -h, --help            Print help information"#
-    );
-}
-
-fn print_search_help() {
-    println!(
-        r#"Search worktrees by path, name, branch, status, or PR status.
-
-USAGE:
-    owt search <QUERY> [OPTIONS]
-
-OPTIONS:
-    -p, --path <PATH>    Repository or worktree path (default: current directory)
-        --pr             Include GitHub PR status before searching
-    -h, --help           Print help information
-
-OUTPUT:
-    kind<TAB>path<TAB>branch<TAB>status<TAB>last_commit<TAB>ahead<TAB>behind<TAB>pr"#
-    );
+fn parse_args_from(
+    args: Vec<String>,
+    current_dir: impl Fn() -> PathBuf,
+) -> Result<Command, clap::Error> {
+    Ok(cli::Cli::try_parse_from(args)?.into_command(current_dir))
 }
 
 fn print_not_git_repo_error() {
@@ -1626,32 +2085,11 @@ Please navigate to a git repository or specify the path with --path."#
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{git_cmd, temp_dir};
     use std::fs;
     use std::io::Write;
     use std::path::{Path, PathBuf};
-    use std::process::{Command as ProcessCommand, Output};
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    fn temp_dir(name: &str) -> PathBuf {
-        let id = std::process::id();
-        let ts = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!("owt_main_unit_{}_{}_{}", name, id, ts));
-        let _ = fs::remove_dir_all(&path);
-        fs::create_dir_all(&path).unwrap();
-        path
-    }
-
-    fn git_cmd() -> ProcessCommand {
-        let mut cmd = ProcessCommand::new("git");
-        cmd.env_remove("GIT_DIR")
-            .env_remove("GIT_WORK_TREE")
-            .env_remove("GIT_INDEX_FILE")
-            .env_remove("GIT_COMMON_DIR");
-        cmd
-    }
+    use std::process::Output;
 
     fn assert_git_success(output: Output, context: &str) {
         assert!(
@@ -1758,55 +2196,125 @@ mod tests {
 
     #[test]
     fn parse_args_defaults_to_current_directory_tui() {
-        let command = parse_args_from(vec!["owt".to_string()], || PathBuf::from("/repo/main"));
+        let command =
+            parse_args_from(vec!["owt".to_string()], || PathBuf::from("/repo/main")).unwrap();
+
+        assert_eq!(command.tui_path(), Some(Path::new("/repo/main")));
+    }
+
+    #[test]
+    fn parse_args_accepts_path_flag_and_positional_path() {
+        let flag_command = parse_args_from(
+            vec![
+                "owt".to_string(),
+                "--path".to_string(),
+                "/tmp/repo".to_string(),
+            ],
+            || PathBuf::from("/repo/main"),
+        )
+        .unwrap();
+        let positional_command = parse_args_from(vec!["owt".to_string(), "/".to_string()], || {
+            PathBuf::from("/repo/main")
+        })
+        .unwrap();
+
+        assert_eq!(flag_command.tui_path(), Some(Path::new("/tmp/repo")));
+        assert_eq!(positional_command.tui_path(), Some(Path::new("/")));
+    }
+
+    #[test]
+    fn parse_args_accepts_select_with_path() {
+        let command = parse_args_from(
+            vec![
+                "owt".to_string(),
+                "/".to_string(),
+                "--select".to_string(),
+                "feature/login".to_string(),
+            ],
+            || PathBuf::from("/repo/main"),
+        )
+        .unwrap();
+        let short_command = parse_args_from(
+            vec!["owt".to_string(), "-s".to_string(), "login".to_string()],
+            || PathBuf::from("/repo/main"),
+        )
+        .unwrap();
+
+        assert_eq!(command.tui_path(), Some(Path::new("/")));
+        assert_eq!(command.tui_select(), Some("feature/login"));
+        assert_eq!(short_command.tui_path(), Some(Path::new("/repo/main")));
+        assert_eq!(short_command.tui_select(), Some("login"));
+    }
+
+    #[test]
+    fn parse_args_accepts_events_target() {
+        let command = parse_args_from(
+            vec![
+                "owt".to_string(),
+                "--events".to_string(),
+                "3".to_string(),
+                "/".to_string(),
+            ],
+            || PathBuf::from("/repo/main"),
+        )
+        .unwrap();
 
-        assert_eq!(command.tui_path(), Some(Path::new("/repo/main")));
+        assert_eq!(command.tui_path(), Some(Path::new("/")));
+        assert!(matches!(
+            command,
+            Command::Tui { events: Some(ref target), .. } if target == "3"
+        ));
     }
 
     #[test]
-    fn parse_args_accepts_path_flag_and_positional_path() {
-        let flag_command = parse_args_from(
+    fn parse_args_accepts_rpc_with_path() {
+        let command = parse_args_from(
             vec![
                 "owt".to_string(),
+                "--rpc".to_string(),
                 "--path".to_string(),
-                "/tmp/repo".to_string(),
+                "/tmp/other".to_string(),
             ],
             || PathBuf::from("/repo/main"),
-        );
-        let positional_command =
-            parse_args_from(vec!["owt".to_string(), "/tmp/other".to_string()], || {
-                PathBuf::from("/repo/main")
-            });
+        )
+        .unwrap();
 
-        assert_eq!(flag_command.tui_path(), Some(Path::new("/tmp/repo")));
-        assert_eq!(positional_command.tui_path(), Some(Path::new("/tmp/other")));
+        assert!(matches!(
+            command,
+            Command::Rpc { ref path } if path == Path::new("/tmp/other")
+        ));
     }
 
     #[test]
     fn parse_args_recognizes_documented_subcommands() {
         assert!(matches!(
-            parse_args_from(vec!["owt".to_string(), "init".to_string()], PathBuf::new),
+            parse_args_from(vec!["owt".to_string(), "init".to_string()], PathBuf::new).unwrap(),
             Command::Init
         ));
         assert!(matches!(
-            parse_args_from(vec!["owt".to_string(), "setup".to_string()], PathBuf::new),
+            parse_args_from(vec!["owt".to_string(), "setup".to_string()], PathBuf::new).unwrap(),
             Command::Setup
         ));
         assert!(matches!(
-            parse_args_from(vec!["owt".to_string(), "test-cd".to_string()], PathBuf::new),
+            parse_args_from(vec!["owt".to_string(), "test-cd".to_string()], PathBuf::new).unwrap(),
             Command::TestCd
         ));
         assert!(matches!(
-            parse_args_from(
-                vec!["owt".to_string(), "--version".to_string()],
-                PathBuf::new
-            ),
-            Command::Version
-        ));
-        assert!(matches!(
-            parse_args_from(vec!["owt".to_string(), "help".to_string()], PathBuf::new),
-            Command::Help(HelpTopic::Root)
+            parse_args_from(vec!["owt".to_string(), "repos".to_string()], PathBuf::new).unwrap(),
+            Command::Repos { json: false }
         ));
+        assert_eq!(
+            parse_args_from(vec!["owt".to_string(), "-v".to_string()], PathBuf::new)
+                .err()
+                .map(|error| error.kind()),
+            Some(clap::error::ErrorKind::DisplayVersion)
+        );
+        assert_eq!(
+            parse_args_from(vec!["owt".to_string(), "help".to_string()], PathBuf::new)
+                .err()
+                .map(|error| error.kind()),
+            Some(clap::error::ErrorKind::DisplayHelp)
+        );
         assert!(matches!(
             parse_args_from(
                 vec![
@@ -1816,10 +2324,38 @@ mod tests {
                     "/tmp/projects".to_string()
                 ],
                 PathBuf::new
-            ),
-            Command::Clone { url, path }
+            ).unwrap(),
+            Command::Clone { url, path, branch: None, depth: None, no_worktree: false }
                 if url == "https://example.com/repo.git" && path == Some(PathBuf::from("/tmp/projects"))
         ));
+        assert!(matches!(
+            parse_args_from(
+                vec![
+                    "owt".to_string(),
+                    "clone".to_string(),
+                    "https://example.com/repo.git".to_string(),
+                    "--branch".to_string(),
+                    "develop".to_string(),
+                    "--depth".to_string(),
+                    "1".to_string(),
+                    "--no-worktree".to_string(),
+                ],
+                PathBuf::new
+            ).unwrap(),
+            Command::Clone { branch: Some(branch), depth: Some(1), no_worktree: true, .. }
+                if branch == "develop"
+        ));
+        assert!(parse_args_from(
+            vec![
+                "owt".to_string(),
+                "clone".to_string(),
+                "https://example.com/repo.git".to_string(),
+                "--depth".to_string(),
+                "0".to_string(),
+            ],
+            PathBuf::new
+        )
+        .is_err());
     }
 
     #[test]
@@ -1835,9 +2371,9 @@ mod tests {
                     "--pr".to_string(),
                 ],
                 || PathBuf::from("/cwd")
-            ),
-            Command::Worktree(WorktreeCommand::List { path, include_pr })
-                if path == PathBuf::from("/repo") && include_pr
+            ).unwrap(),
+            Command::Worktree(WorktreeCommand::List { path, include_pr, json: false, porcelain: false })
+                if path == Path::new("/repo") && include_pr
         ));
         assert!(matches!(
             parse_args_from(
@@ -1853,19 +2389,40 @@ mod tests {
                     "--tmux=on".to_string(),
                 ],
                 || PathBuf::from("/cwd")
-            ),
+            ).unwrap(),
             Command::Worktree(WorktreeCommand::Create {
                 path,
                 branch,
                 base,
                 worktree_path,
-                tmux
-            }) if path == PathBuf::from("/cwd")
+                tmux,
+                sparse: None,
+                json: false,
+            }) if path == Path::new("/cwd")
                 && branch == "feature/login"
                 && base == Some("main".to_string())
                 && worktree_path == Some(PathBuf::from("/tmp/login"))
                 && tmux == Some(true)
         ));
+        assert!(matches!(
+            parse_args_from(
+                vec![
+                    "owt".to_string(),
+                    "worktree".to_string(),
+                    "create".to_string(),
+                    "feature/docs".to_string(),
+                    "--sparse".to_string(),
+                    "docs".to_string(),
+                    "--sparse".to_string(),
+                    "src/web".to_string(),
+                ],
+                PathBuf::new
+            ).unwrap(),
+            Command::Worktree(WorktreeCommand::Create {
+                sparse: Some(sparse),
+                ..
+            }) if sparse == ["docs", "src/web"]
+        ));
         assert!(matches!(
             parse_args_from(
                 vec![
@@ -1877,7 +2434,7 @@ mod tests {
                     "off".to_string(),
                 ],
                 PathBuf::new
-            ),
+            ).unwrap(),
             Command::Worktree(WorktreeCommand::Create {
                 branch,
                 tmux: Some(false),
@@ -1895,7 +2452,7 @@ mod tests {
                     "--force".to_string(),
                 ],
                 PathBuf::new
-            ),
+            ).unwrap(),
             Command::Worktree(WorktreeCommand::Delete {
                 target,
                 force: true,
@@ -1913,7 +2470,7 @@ mod tests {
                     "/repo".to_string(),
                 ],
                 PathBuf::new
-            ),
+            ).unwrap(),
             Command::Worktree(WorktreeCommand::Prune { path, dry_run })
                 if path == Path::new("/repo") && !dry_run
         ));
@@ -1928,61 +2485,330 @@ mod tests {
                     "/repo".to_string(),
                 ],
                 PathBuf::new
-            ),
+            ).unwrap(),
             Command::Worktree(WorktreeCommand::Prune { path, dry_run })
                 if path == Path::new("/repo") && dry_run
         ));
     }
 
     #[test]
-    fn parse_args_recognizes_plain_cli_help_topics() {
+    fn parse_args_shows_help_for_every_cli_level() {
+        for args in [
+            vec!["worktree", "--help"],
+            vec!["worktree", "create", "--help"],
+            vec!["worktree", "prune", "--help"],
+            vec!["pr", "status", "--help"],
+            vec!["add", "-h"],
+        ] {
+            let args = std::iter::once("owt")
+                .chain(args.iter().copied())
+                .map(String::from)
+                .collect();
+            assert_eq!(
+                parse_args_from(args, PathBuf::new)
+                    .err()
+                    .map(|error| error.kind()),
+                Some(clap::error::ErrorKind::DisplayHelp)
+            );
+        }
+    }
+
+    #[test]
+    fn parse_args_rejects_unknown_flags_and_mistyped_subcommands() {
+        let parse = |args: &[&str]| {
+            let args = std::iter::once("owt")
+                .chain(args.iter().copied())
+                .map(String::from)
+                .collect();
+            parse_args_from(args, PathBuf::new)
+                .err()
+                .map(|error| error.kind())
+        };
+
+        assert_eq!(
+            parse(&["--verbos"]),
+            Some(clap::error::ErrorKind::UnknownArgument)
+        );
+        assert_eq!(
+            parse(&["wroktree"]),
+            Some(clap::error::ErrorKind::ValueValidation)
+        );
+        assert_eq!(
+            parse(&["worktree", "create", "feature/x", "--tmux=maybe"]),
+            Some(clap::error::ErrorKind::ValueValidation)
+        );
+        assert_eq!(
+            parse(&["worktree", "delete"]),
+            Some(clap::error::ErrorKind::MissingRequiredArgument)
+        );
+    }
+
+    #[test]
+    fn parse_args_passes_exec_command_through_untouched() {
+        let command = parse_args_from(
+            [
+                "owt",
+                "exec",
+                "--branch-glob",
+                "feature/*",
+                "--",
+                "cargo",
+                "test",
+                "--dirty-only",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            || PathBuf::from("/cwd"),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            command,
+            Command::Exec { ref path, ref filter, ref command, jobs: None }
+                if path == Path::new("/cwd")
+                    && !filter.dirty_only
+                    && filter.branch_glob.as_deref() == Some("feature/*")
+                    && command == &["cargo", "test", "--dirty-only"]
+        ));
+        assert!(parse_args_from(
+            vec![
+                "owt".to_string(),
+                "exec".to_string(),
+                "--dirty-only".to_string()
+            ],
+            PathBuf::new
+        )
+        .is_err());
         assert!(matches!(
             parse_args_from(
-                vec![
-                    "owt".to_string(),
-                    "worktree".to_string(),
-                    "--help".to_string(),
-                ],
+                ["owt", "exec", "-j", "4", "--", "make"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
                 PathBuf::new
-            ),
-            Command::Help(HelpTopic::Worktree)
+            )
+            .unwrap(),
+            Command::Exec { jobs: Some(4), .. }
         ));
+        assert!(parse_args_from(
+            ["owt", "exec", "--jobs", "0", "--", "make"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            PathBuf::new
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parse_args_maps_top_level_add_remove_and_list() {
         assert!(matches!(
             parse_args_from(
                 vec![
                     "owt".to_string(),
-                    "worktree".to_string(),
-                    "create".to_string(),
-                    "--help".to_string(),
+                    "add".to_string(),
+                    "feature/login".to_string(),
+                    "-b".to_string(),
+                    "main".to_string(),
+                    "--full".to_string(),
                 ],
+                || PathBuf::from("/cwd")
+            )
+            .unwrap(),
+            Command::Worktree(WorktreeCommand::Create { path, branch, base, sparse, .. })
+                if path == Path::new("/cwd")
+                    && branch == "feature/login"
+                    && base.as_deref() == Some("main")
+                    && sparse == Some(Vec::new())
+        ));
+        assert!(matches!(
+            parse_args_from(
+                vec!["owt".to_string(), "rm".to_string(), "feature/login".to_string()],
                 PathBuf::new
-            ),
-            Command::Help(HelpTopic::WorktreeCreate)
+            )
+            .unwrap(),
+            Command::Worktree(WorktreeCommand::Delete { target, force: false, .. })
+                if target == "feature/login"
         ));
         assert!(matches!(
             parse_args_from(
-                vec![
-                    "owt".to_string(),
-                    "worktree".to_string(),
-                    "prune".to_string(),
-                    "--help".to_string(),
-                ],
+                vec!["owt".to_string(), "ls".to_string(), "--pr".to_string()],
                 PathBuf::new
-            ),
-            Command::Help(HelpTopic::WorktreePrune)
+            )
+            .unwrap(),
+            Command::Worktree(WorktreeCommand::List {
+                include_pr: true,
+                ..
+            })
         ));
         assert!(matches!(
             parse_args_from(
                 vec![
                     "owt".to_string(),
-                    "pr".to_string(),
-                    "status".to_string(),
-                    "--help".to_string(),
+                    "tui".to_string(),
+                    "-s".to_string(),
+                    "login".to_string(),
                 ],
+                || PathBuf::from("/cwd")
+            )
+            .unwrap(),
+            Command::Tui { path, select: Some(select), .. }
+                if path == Path::new("/cwd") && select == "login"
+        ));
+    }
+
+    #[test]
+    fn parse_args_accepts_json_on_record_commands() {
+        let parse = |args: &[&str]| {
+            let args = std::iter::once("owt")
+                .chain(args.iter().copied())
+                .map(String::from)
+                .collect();
+            parse_args_from(args, PathBuf::new)
+        };
+
+        assert!(matches!(
+            parse(&["ls", "--json"]).unwrap(),
+            Command::Worktree(WorktreeCommand::List { json: true, .. })
+        ));
+        assert!(matches!(
+            parse(&["add", "feature/x", "--json"]).unwrap(),
+            Command::Worktree(WorktreeCommand::Create { json: true, .. })
+        ));
+        assert!(matches!(
+            parse(&["rm", "feature/x", "--json"]).unwrap(),
+            Command::Worktree(WorktreeCommand::Delete { json: true, .. })
+        ));
+        assert!(matches!(
+            parse(&["clean", "--dry-run", "--json"]).unwrap(),
+            Command::Worktree(WorktreeCommand::Clean { json: true, .. })
+        ));
+        assert!(matches!(
+            parse(&["search", "login", "--json"]).unwrap(),
+            Command::Search(SearchCommand::Query { json: true, .. })
+        ));
+        assert!(matches!(
+            parse(&["repos", "--json"]).unwrap(),
+            Command::Repos { json: true }
+        ));
+        assert!(parse(&["doctor", "--json"]).is_err());
+    }
+
+    #[test]
+    fn parse_args_maps_path_subcommand() {
+        assert!(matches!(
+            parse_args_from(
+                ["owt", "path", "feature/login", "-p", "/repo"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
                 PathBuf::new
-            ),
-            Command::Help(HelpTopic::PrStatus)
+            )
+            .unwrap(),
+            Command::Path { ref path, ref target }
+                if path == Path::new("/repo") && target == "feature/login"
+        ));
+        assert_eq!(
+            parse_args_from(vec!["owt".to_string(), "path".to_string()], PathBuf::new)
+                .err()
+                .map(|error| error.kind()),
+            Some(clap::error::ErrorKind::MissingRequiredArgument)
+        );
+    }
+
+    #[test]
+    fn parse_args_maps_pick_subcommand() {
+        assert!(matches!(
+            parse_args_from(
+                ["owt", "pick", "-p", "/repo", "--select", "feature/login"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                PathBuf::new
+            )
+            .unwrap(),
+            Command::Pick { ref path, select: Some(ref select) }
+                if path == Path::new("/repo") && select == "feature/login"
+        ));
+        assert!(matches!(
+            parse_args_from(
+                vec!["owt".to_string(), "pick".to_string()],
+                || PathBuf::from("/cwd")
+            )
+            .unwrap(),
+            Command::Pick { ref path, select: None } if path == Path::new("/cwd")
+        ));
+    }
+
+    #[test]
+    fn parse_args_accepts_only_known_porcelain_versions() {
+        let parse = |args: &[&str]| {
+            let args = std::iter::once("owt")
+                .chain(args.iter().copied())
+                .map(String::from)
+                .collect();
+            parse_args_from(args, PathBuf::new)
+        };
+
+        assert!(matches!(
+            parse(&["list", "--porcelain"]).unwrap(),
+            Command::Worktree(WorktreeCommand::List {
+                porcelain: true,
+                json: false,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse(&["search", "login", "--porcelain=v1"]).unwrap(),
+            Command::Search(SearchCommand::Query {
+                porcelain: true,
+                ..
+            })
         ));
+        assert_eq!(
+            parse(&["list", "--porcelain=v9"])
+                .err()
+                .map(|error| error.kind()),
+            Some(clap::error::ErrorKind::InvalidValue)
+        );
+        assert_eq!(
+            parse(&["list", "--porcelain", "--json"])
+                .err()
+                .map(|error| error.kind()),
+            Some(clap::error::ErrorKind::ArgumentConflict)
+        );
+    }
+
+    #[test]
+    fn porcelain_v1_line_keeps_field_order_and_path_last() {
+        let mut worktree = types::Worktree {
+            path: PathBuf::from("/repo/my login"),
+            branch: Some("feature/login".to_string()),
+            is_bare: false,
+            status: types::WorktreeStatus::Unstaged,
+            last_commit_time: Some("2 hours ago".to_string()),
+            ahead_behind: Some(types::AheadBehind {
+                ahead: 2,
+                behind: 0,
+            }),
+            github_pr_status: Some(types::GithubPrStatus::Open),
+            locked: true,
+        };
+        assert_eq!(
+            porcelain_v1_line(&worktree),
+            "worktree unstaged 2 0 locked open feature/login /repo/my login"
+        );
+
+        worktree.branch = None;
+        worktree.ahead_behind = None;
+        worktree.github_pr_status = None;
+        worktree.locked = false;
+        worktree.status = types::WorktreeStatus::Clean;
+        assert_eq!(
+            porcelain_v1_line(&worktree),
+            "worktree clean - - - - - /repo/my login"
+        );
     }
 
     #[test]
@@ -1996,11 +2822,12 @@ mod tests {
                     "--branch".to_string(),
                     "feature/login".to_string(),
                     "--all".to_string(),
+                    "--json".to_string(),
                 ],
                 || PathBuf::from("/repo")
-            ),
-            Command::Pr(PrCommand::Status { path, branch, all: true })
-                if path == PathBuf::from("/repo") && branch == Some("feature/login".to_string())
+            ).unwrap(),
+            Command::Pr(PrCommand::Status { path, branch, all: true, json: true })
+                if path == Path::new("/repo") && branch == Some("feature/login".to_string())
         ));
         assert!(matches!(
             parse_args_from(
@@ -2012,9 +2839,9 @@ mod tests {
                     "12".to_string(),
                 ],
                 || PathBuf::from("/repo")
-            ),
+            ).unwrap(),
             Command::Commit(CommitCommand::Tree { path, limit: 12 })
-                if path == PathBuf::from("/repo")
+                if path == Path::new("/repo")
         ));
         assert!(matches!(
             parse_args_from(
@@ -2025,13 +2852,54 @@ mod tests {
                     "--pr".to_string(),
                 ],
                 || PathBuf::from("/repo")
-            ),
+            ).unwrap(),
             Command::Search(SearchCommand::Query {
                 path,
                 query,
-                include_pr: true
-            }) if path == PathBuf::from("/repo") && query == "login"
+                include_pr: true,
+                json: false,
+                porcelain: false,
+            }) if path == Path::new("/repo") && query == "login"
+        ));
+    }
+
+    #[test]
+    fn parse_args_recognizes_config_export_and_import() {
+        assert!(matches!(
+            parse_args_from(
+                vec![
+                    "owt".to_string(),
+                    "config".to_string(),
+                    "export".to_string()
+                ],
+                PathBuf::new
+            )
+            .unwrap(),
+            Command::Config(ConfigCommand::Export { file: None })
+        ));
+        assert!(matches!(
+            parse_args_from(
+                vec![
+                    "owt".to_string(),
+                    "config".to_string(),
+                    "import".to_string(),
+                    "owt-bundle.toml".to_string(),
+                    "--force".to_string(),
+                ],
+                PathBuf::new
+            ).unwrap(),
+            Command::Config(ConfigCommand::Import { file, force: true })
+                if file == Path::new("owt-bundle.toml")
         ));
+        assert!(parse_args_from(
+            vec![
+                "owt".to_string(),
+                "config".to_string(),
+                "import".to_string()
+            ],
+            PathBuf::new
+        )
+        .is_err());
     }
 
     #[test]
@@ -2043,6 +2911,7 @@ mod tests {
             ("repo.git", "repo"),
             ("/path/to/repo.git", "repo"),
             ("https://github.com/user/repo.git/", "repo"),
+            ("git@host:repo.git", "repo"),
         ];
 
         for (url, expected) in cases {
@@ -2050,6 +2919,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn clone_shorthand_expands_only_owner_and_repo() {
+        assert_eq!(
+            expand_clone_shorthand("user/repo", "github.com").as_deref(),
+            Some("https://github.com/user/repo.git")
+        );
+        assert_eq!(
+            expand_clone_shorthand("team/api.git", "gitlab.example.com").as_deref(),
+            Some("https://gitlab.example.com/team/api.git")
+        );
+        for url in [
+            "https://github.com/user/repo",
+            "git@github.com:user/repo.git",
+            "gh:user/repo",
+            "/path/to/repo",
+            "../repo",
+            "user/repo/extra",
+            "repo",
+        ] {
+            assert_eq!(expand_clone_shorthand(url, "github.com"), None, "{url}");
+        }
+    }
+
     #[test]
     fn run_clone_creates_dot_bare_layout_and_first_worktree() {
         let base = temp_dir("run_clone_layout");
@@ -2057,7 +2949,14 @@ mod tests {
         let target_parent = base.join("projects");
         create_source_repo(&source);
 
-        run_clone(&source.to_string_lossy(), Some(target_parent.clone())).unwrap();
+        run_clone(
+            &source.to_string_lossy(),
+            Some(target_parent.clone()),
+            None,
+            None,
+            false,
+        )
+        .unwrap();
 
         let project_dir = target_parent.join("source-repo");
         assert!(project_dir.join(".bare").is_dir());
@@ -2068,6 +2967,49 @@ mod tests {
         let _ = fs::remove_dir_all(base);
     }
 
+    #[test]
+    fn run_clone_honors_branch_depth_and_no_worktree() {
+        let base = temp_dir("run_clone_options");
+        let source = base.join("source-repo");
+        let target_parent = base.join("projects");
+        create_source_repo(&source);
+        commit_file(&source, "second.txt", "second\n", "second commit");
+        assert_git_success(
+            git_cmd()
+                .current_dir(&source)
+                .args(["branch", "develop"])
+                .output()
+                .unwrap(),
+            "git branch failed",
+        );
+        // --depth is ignored for plain local paths
+        let url = format!("file://{}", source.display());
+
+        run_clone(
+            &url,
+            Some(target_parent.clone()),
+            Some("develop"),
+            Some(1),
+            false,
+        )
+        .unwrap();
+
+        let project_dir = target_parent.join("source-repo");
+        let bare = project_dir.join(".bare");
+        assert!(project_dir.join("develop").is_dir());
+        assert!(!project_dir.join("main").exists());
+        assert_eq!(git::get_default_branch(&bare).unwrap(), "develop");
+        assert!(bare.join("shallow").exists());
+        let _ = fs::remove_dir_all(&project_dir);
+
+        run_clone(&url, Some(target_parent.clone()), None, None, true).unwrap();
+
+        assert!(project_dir.join(".bare").is_dir());
+        assert!(!project_dir.join("main").exists());
+
+        let _ = fs::remove_dir_all(base);
+    }
+
     #[test]
     fn worktree_prune_keeps_locally_merged_worktrees_without_pr_status() {
         let base = temp_dir("prune_without_pr_status");
@@ -2205,6 +3147,33 @@ mod tests {
         let _ = fs::remove_dir_all(base);
     }
 
+    #[test]
+    fn delete_worktree_refuses_nested_repos_unless_kept_or_forced() {
+        let base = temp_dir("delete_nested");
+        let source = base.join("source-repo");
+        let worktree_path = base.join("nested");
+        create_source_repo(&source);
+        git::add_worktree(&source, "nested", &worktree_path, None).unwrap();
+        fs::create_dir_all(worktree_path.join("vendor/lib/.git")).unwrap();
+        let context = resolve_repository_context(&source).unwrap();
+
+        let error = delete_worktree(&context, "nested", false, false, false)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("vendor/lib"), "{}", error);
+        assert!(worktree_path.exists());
+
+        let (_, kept) = delete_worktree(&context, "nested", false, false, true).unwrap();
+        assert_eq!(
+            kept,
+            vec![nested_repos::kept_dir(&context.project_root_path, "nested").join("vendor/lib")]
+        );
+        assert!(kept[0].join(".git").exists());
+        assert!(!worktree_path.exists());
+
+        let _ = fs::remove_dir_all(base);
+    }
+
     #[test]
     fn post_tui_create_worktree_creates_copies_and_writes_handoff_path() {
         let base = temp_dir("post_tui_create");
@@ -2229,12 +3198,19 @@ mod tests {
             base_branch: "main".to_string(),
             worktree_path: worktree_path.clone(),
             source_path: Some(source.clone()),
+            base_fetched: false,
+            path_overridden: false,
         };
         let mut config = Config::default();
         config.copy_files = vec!["config/local.env".to_string()];
 
-        run_post_tui_create_worktree(&request, &config, Some(output_path.to_str().unwrap()))
-            .unwrap();
+        run_post_tui_create_worktree(
+            &request,
+            &config,
+            Some(output_path.to_str().unwrap()),
+            false,
+        )
+        .unwrap();
 
         assert!(worktree_path.exists());
         assert_eq!(
@@ -2265,8 +3241,8 @@ mod tests {
             .find("No shell integration - print only the path")
             .expect("path-only fallback comment should exist");
         let fallback_end = source[fallback_start..]
-            .find("types::ExitAction::Quit")
-            .expect("quit match arm should follow fallback")
+            .find("Ok(())")
+            .expect("hand-off should return after the fallback")
             + fallback_start;
         let fallback_block = &source[fallback_start..fallback_end];
 
@@ -0,0 +1,453 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::state_file::{write_atomic, StateLock};
+use crate::types::Worktree;
+
+/// Per-project worktree metadata kept under `.owt/`.
+/// Entries are keyed by worktree name (the worktree directory name).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    pub pinned: BTreeSet<String>,
+    pub review: BTreeSet<String>,
+    pub notes: BTreeMap<String, String>,
+    /// Worktrees created at an overridden path, mapped to the topmost
+    /// directory owt created for them, so deletes can clean it up.
+    /// Keyed by worktree path rather than name.
+    pub locations: BTreeMap<String, String>,
+    /// List view restored on the next launch: sort mode label and direction,
+    /// filter text, status filter label, and verbose mode. Stored in state.toml.
+    pub view: ViewState,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ViewState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(skip_serializing_if = "is_false")]
+    pub sort_reversed: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub filter: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_filter: Option<String>,
+    #[serde(skip_serializing_if = "is_false")]
+    pub verbose: bool,
+}
+
+/// state.toml: pins, review flags, and the list view
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct StateFile {
+    pinned: BTreeSet<String>,
+    review: BTreeSet<String>,
+    #[serde(flatten)]
+    view: ViewState,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Badge {
+    Pinned,
+    Locked,
+    Note,
+    Review,
+    Merged,
+}
+
+pub const ALL_BADGES: [Badge; 5] = [
+    Badge::Pinned,
+    Badge::Locked,
+    Badge::Note,
+    Badge::Review,
+    Badge::Merged,
+];
+
+impl Badge {
+    pub fn symbol(self, ascii: bool) -> &'static str {
+        match (self, ascii) {
+            (Badge::Pinned, false) => "📌",
+            (Badge::Locked, false) => "🔒",
+            (Badge::Note, false) => "📝",
+            (Badge::Review, false) => "👀",
+            (Badge::Merged, false) => "✅",
+            (Badge::Pinned, true) => "[P]",
+            (Badge::Locked, true) => "[L]",
+            (Badge::Note, true) => "[N]",
+            (Badge::Review, true) => "[R]",
+            (Badge::Merged, true) => "[M]",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Badge::Pinned => "Pinned",
+            Badge::Locked => "Locked (git worktree lock)",
+            Badge::Note => "Has a note",
+            Badge::Review => "Review-only worktree",
+            Badge::Merged => "Merged into the default branch, safe to delete",
+        }
+    }
+}
+
+impl Metadata {
+    /// Pins and flags: .owt/state.toml under the project root
+    pub fn state_path(project_root_path: &Path) -> PathBuf {
+        Config::owt_dir(project_root_path).join("state.toml")
+    }
+
+    /// Notes: .owt/notes.toml under the project root
+    pub fn notes_path(project_root_path: &Path) -> PathBuf {
+        Config::owt_dir(project_root_path).join("notes.toml")
+    }
+
+    /// Overridden worktree locations: .owt/locations.toml under the project root
+    pub fn locations_path(project_root_path: &Path) -> PathBuf {
+        Config::owt_dir(project_root_path).join("locations.toml")
+    }
+
+    pub fn load(project_root_path: &Path) -> Result<Self> {
+        let state: StateFile = read_toml(&Self::state_path(project_root_path))?;
+        let mut notes: BTreeMap<String, String> = read_toml(&Self::notes_path(project_root_path))?;
+        notes.retain(|name, note| !name.is_empty() && !note.trim().is_empty());
+        Ok(Self {
+            pinned: state.pinned,
+            review: state.review,
+            notes,
+            locations: read_toml(&Self::locations_path(project_root_path))?,
+            view: state.view,
+        })
+    }
+
+    /// Apply `change` to the latest on-disk metadata while holding the state
+    /// lock, so edits made by another owt process since our load are kept.
+    pub fn update(project_root_path: &Path, change: impl FnOnce(&mut Metadata)) -> Result<Self> {
+        let _lock = StateLock::acquire(project_root_path)?;
+        let mut metadata = Self::load(project_root_path)?;
+        change(&mut metadata);
+        metadata.write_files(project_root_path)?;
+        Ok(metadata)
+    }
+
+    fn write_files(&self, project_root_path: &Path) -> Result<()> {
+        let state = StateFile {
+            pinned: self.pinned.clone(),
+            review: self.review.clone(),
+            view: self.view.clone(),
+        };
+        write_atomic(
+            &Self::state_path(project_root_path),
+            &toml::to_string(&state)?,
+        )?;
+        write_atomic(
+            &Self::notes_path(project_root_path),
+            &toml::to_string(&self.notes)?,
+        )?;
+        if !self.locations.is_empty() || Self::locations_path(project_root_path).exists() {
+            write_atomic(
+                &Self::locations_path(project_root_path),
+                &toml::to_string(&self.locations)?,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Badges shown after the worktree name, in display order. `Merged` comes
+    /// from git rather than metadata and is added by the table.
+    pub fn badges(&self, worktree: &Worktree) -> Vec<Badge> {
+        if worktree.is_bare {
+            return Vec::new();
+        }
+
+        let name = worktree.display_name();
+        let mut badges = Vec::new();
+        if self.pinned.contains(&name) {
+            badges.push(Badge::Pinned);
+        }
+        if worktree.locked {
+            badges.push(Badge::Locked);
+        }
+        if self.notes.contains_key(&name) {
+            badges.push(Badge::Note);
+        }
+        if self.review.contains(&name) {
+            badges.push(Badge::Review);
+        }
+        badges
+    }
+}
+
+/// Parse a TOML state file; a missing file is the default.
+fn read_toml<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(error) => {
+            return Err(error).with_context(|| format!("Failed to read {}", path.display()))
+        }
+    };
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// The topmost directory that creating `worktree_path` will add, i.e. its
+/// highest ancestor that does not exist yet. Call before creating it.
+pub fn created_root(worktree_path: &Path) -> PathBuf {
+    let mut root = worktree_path.to_path_buf();
+    while let Some(parent) = root.parent() {
+        if parent.as_os_str().is_empty() || parent.exists() {
+            break;
+        }
+        root = parent.to_path_buf();
+    }
+    root
+}
+
+/// Remember that `worktree_path` was created at an overridden location.
+pub fn record_location(
+    project_root_path: &Path,
+    worktree_path: &Path,
+    created_root: &Path,
+) -> Result<()> {
+    Metadata::update(project_root_path, |metadata| {
+        metadata.locations.insert(
+            worktree_path.display().to_string(),
+            created_root.display().to_string(),
+        );
+    })?;
+    Ok(())
+}
+
+/// After `worktree_path` was removed, delete the now-empty directories owt
+/// created for its overridden location and forget it. Directories that still
+/// hold anything are kept.
+pub fn cleanup_location(project_root_path: &Path, worktree_path: &Path) -> Result<()> {
+    let key = worktree_path.display().to_string();
+    let Some(created_root) = Metadata::load(project_root_path)?.locations.remove(&key) else {
+        return Ok(());
+    };
+    let created_root = PathBuf::from(created_root);
+    let mut dir = worktree_path.to_path_buf();
+    while dir.starts_with(&created_root) {
+        if dir.exists() && fs::remove_dir(&dir).is_err() {
+            break;
+        }
+        match dir.parent() {
+            Some(parent) => dir = parent.to_path_buf(),
+            None => break,
+        }
+    }
+    Metadata::update(project_root_path, |metadata| {
+        metadata.locations.remove(&key);
+    })?;
+    Ok(())
+}
+
+/// Badges render as emoji unless the locale does not advertise UTF-8.
+pub fn ascii_badges() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|value| !value.is_empty()));
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            !(locale.contains("utf-8") || locale.contains("utf8"))
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use crate::types::WorktreeStatus;
+
+    fn metadata_test_worktree(name: &str, locked: bool) -> Worktree {
+        Worktree {
+            path: PathBuf::from(format!("/repo/{}", name)),
+            branch: Some(name.to_string()),
+            is_bare: false,
+            status: WorktreeStatus::Clean,
+            last_commit_time: None,
+            ahead_behind: None,
+            github_pr_status: None,
+            locked,
+        }
+    }
+
+    #[test]
+    fn badges_follow_metadata_and_lock_state_in_display_order() {
+        let mut metadata = Metadata::default();
+        metadata.pinned.insert("main".to_string());
+        metadata.review.insert("main".to_string());
+        metadata
+            .notes
+            .insert("main".to_string(), "long-lived".to_string());
+
+        assert_eq!(
+            metadata.badges(&metadata_test_worktree("main", true)),
+            vec![Badge::Pinned, Badge::Locked, Badge::Note, Badge::Review]
+        );
+        assert!(metadata
+            .badges(&metadata_test_worktree("feature", false))
+            .is_empty());
+    }
+
+    #[test]
+    fn badge_symbols_have_ascii_fallback() {
+        for badge in ALL_BADGES {
+            assert!(badge.symbol(true).is_ascii());
+            assert!(!badge.symbol(false).is_ascii());
+        }
+    }
+
+    #[test]
+    fn save_and_load_round_trip_pins_review_notes_and_view() {
+        let dir = temp_dir("metadata_round_trip");
+        let mut metadata = Metadata::default();
+        metadata.pinned.insert("main".to_string());
+        metadata.pinned.insert("develop".to_string());
+        metadata.review.insert("pr-812".to_string());
+        metadata.notes.insert(
+            "feature-login".to_string(),
+            "waiting on \"API\" review = soon".to_string(),
+        );
+        metadata.notes.insert(
+            "feature-crlf".to_string(),
+            "line one\r\n\tline two \\ \u{1b}[0m".to_string(),
+        );
+        metadata.view = ViewState {
+            sort: Some("recent".to_string()),
+            sort_reversed: true,
+            filter: "feat \"x\"\r\t\u{7}".to_string(),
+            status_filter: Some("dirty".to_string()),
+            verbose: true,
+        };
+
+        Metadata::update(&dir, |saved| *saved = metadata.clone()).unwrap();
+        let loaded = Metadata::load(&dir).unwrap();
+
+        assert_eq!(loaded, metadata);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn update_keeps_changes_written_by_another_process() {
+        let dir = temp_dir("metadata_update_merge");
+        let stale = Metadata::load(&dir).unwrap();
+
+        Metadata::update(&dir, |other| {
+            other.pinned.insert("main".to_string());
+        })
+        .unwrap();
+
+        let updated = Metadata::update(&dir, |metadata| {
+            metadata.review.insert("pr-812".to_string());
+        })
+        .unwrap();
+
+        assert_eq!(stale, Metadata::default());
+        assert!(updated.pinned.contains("main"));
+        assert!(updated.review.contains("pr-812"));
+        assert_eq!(Metadata::load(&dir).unwrap(), updated);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn concurrent_updates_are_serialized_and_leave_no_temp_files() {
+        let dir = temp_dir("metadata_update_concurrent");
+        let handles = (0..8)
+            .map(|index| {
+                let dir = dir.clone();
+                std::thread::spawn(move || {
+                    Metadata::update(&dir, |metadata| {
+                        metadata.pinned.insert(format!("wt-{}", index));
+                    })
+                    .unwrap();
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(Metadata::load(&dir).unwrap().pinned.len(), 8);
+        let leftovers = fs::read_dir(Config::owt_dir(&dir))
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn cleanup_location_removes_only_the_empty_directories_owt_created() {
+        let dir = temp_dir("metadata_locations");
+        let disk = dir.join("disk");
+        fs::create_dir_all(&disk).unwrap();
+        let worktree = disk.join("owt").join("exp").join("huge");
+        let created = created_root(&worktree);
+        assert_eq!(created, disk.join("owt"));
+        fs::create_dir_all(&worktree).unwrap();
+        fs::create_dir_all(disk.join("owt").join("keep")).unwrap();
+
+        record_location(&dir, &worktree, &created).unwrap();
+        assert_eq!(
+            Metadata::load(&dir)
+                .unwrap()
+                .locations
+                .get(&worktree.display().to_string()),
+            Some(&created.display().to_string())
+        );
+        fs::remove_dir(&worktree).unwrap();
+        cleanup_location(&dir, &worktree).unwrap();
+
+        assert!(!disk.join("owt").join("exp").exists());
+        assert!(disk.join("owt").join("keep").exists());
+        assert!(Metadata::load(&dir).unwrap().locations.is_empty());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn files_from_the_line_based_writer_still_load() {
+        let dir = temp_dir("metadata_line_based");
+        fs::create_dir_all(Config::owt_dir(&dir)).unwrap();
+        fs::write(
+            Metadata::state_path(&dir),
+            "pinned = [\"main\"]\nreview = []\nsort = \"recent\"\nverbose = true\n",
+        )
+        .unwrap();
+        fs::write(
+            Metadata::notes_path(&dir),
+            "\"main\" = \"say \\\"hi\\\"\\nbye\"\n\"empty\" = \" \"\n",
+        )
+        .unwrap();
+
+        let loaded = Metadata::load(&dir).unwrap();
+        assert!(loaded.pinned.contains("main"));
+        assert_eq!(loaded.view.sort.as_deref(), Some("recent"));
+        assert!(loaded.view.verbose);
+        assert_eq!(loaded.notes.len(), 1);
+        assert_eq!(loaded.notes["main"], "say \"hi\"\nbye");
+
+        Metadata::update(&dir, |_| {}).unwrap();
+        assert_eq!(Metadata::load(&dir).unwrap(), loaded);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn load_without_files_is_empty() {
+        let dir = temp_dir("metadata_missing");
+        assert_eq!(Metadata::load(&dir).unwrap(), Metadata::default());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Git repositories inside `worktree_path` (vendored checkouts, tools cloned
+/// into the tree), which deleting the worktree removes along with it. Paths
+/// are relative to the worktree; repositories inside a nested repository are
+/// not listed separately.
+pub(crate) fn find(worktree_path: &Path) -> Vec<PathBuf> {
+    let mut nested = Vec::new();
+    let mut pending = vec![worktree_path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if !file_type.is_dir() || entry.file_name() == ".git" {
+                continue;
+            }
+            let path = entry.path();
+            if path.join(".git").exists() {
+                if let Ok(relative) = path.strip_prefix(worktree_path) {
+                    nested.push(relative.to_path_buf());
+                }
+            } else {
+                pending.push(path);
+            }
+        }
+    }
+    nested.sort();
+    nested
+}
+
+/// Where nested repositories of the worktree named `worktree_name` are kept
+/// when they are excluded from a delete: `.owt/kept/<name>/`.
+pub(crate) fn kept_dir(project_root_path: &Path, worktree_name: &str) -> PathBuf {
+    Config::owt_dir(project_root_path)
+        .join("kept")
+        .join(worktree_name)
+}
+
+/// Move `nested` repositories out of `worktree_path` so deleting the worktree
+/// leaves them alone. Returns their new locations.
+pub(crate) fn keep(
+    project_root_path: &Path,
+    worktree_path: &Path,
+    worktree_name: &str,
+    nested: &[PathBuf],
+) -> Result<Vec<PathBuf>> {
+    let kept_dir = kept_dir(project_root_path, worktree_name);
+    let mut kept = Vec::new();
+    for relative in nested {
+        let source = worktree_path.join(relative);
+        let destination = kept_dir.join(relative);
+        if destination.exists() {
+            anyhow::bail!(
+                "Cannot keep {}: {} already exists",
+                relative.display(),
+                destination.display()
+            );
+        }
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::rename(&source, &destination).with_context(|| {
+            format!(
+                "Failed to move nested repository {} to {}",
+                source.display(),
+                destination.display()
+            )
+        })?;
+        kept.push(destination);
+    }
+    Ok(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn find_lists_outermost_nested_repositories_and_keep_moves_them() {
+        let base = temp_dir("nested_find_keep");
+        let worktree = base.join("feature");
+        fs::create_dir_all(worktree.join(".git")).unwrap();
+        fs::create_dir_all(worktree.join("src")).unwrap();
+        fs::create_dir_all(worktree.join("vendor/lib/.git")).unwrap();
+        fs::create_dir_all(worktree.join("vendor/lib/inner/.git")).unwrap();
+        fs::create_dir_all(worktree.join("tools/gen")).unwrap();
+        fs::write(worktree.join("tools/gen/.git"), "gitdir: elsewhere\n").unwrap();
+
+        let nested = find(&worktree);
+        assert_eq!(
+            nested,
+            vec![PathBuf::from("tools/gen"), PathBuf::from("vendor/lib")]
+        );
+
+        let kept = keep(&base, &worktree, "feature", &nested).unwrap();
+        assert_eq!(kept[1], kept_dir(&base, "feature").join("vendor/lib"));
+        assert!(kept[1].join("inner/.git").exists());
+        assert!(!worktree.join("vendor/lib").exists());
+        assert!(find(&worktree).is_empty());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::types::Worktree;
+
+/// Numbered worktree menu for when there is no terminal to draw the TUI on,
+/// such as CI or some IDE terminals. Lists the non-bare worktrees on `menu`
+/// and reads a number from `input`; Enter alone takes `default`. Returns
+/// `None` on `q` or end of input.
+pub(crate) fn choose(
+    worktrees: &[Worktree],
+    default: Option<&Path>,
+    mut input: impl BufRead,
+    mut menu: impl Write,
+) -> Result<Option<PathBuf>> {
+    let choices: Vec<&Worktree> = worktrees.iter().filter(|wt| !wt.is_bare).collect();
+    if choices.is_empty() {
+        anyhow::bail!("No worktrees to choose from");
+    }
+
+    let width = choices
+        .iter()
+        .map(|wt| wt.branch_display().chars().count())
+        .max()
+        .unwrap_or(0);
+    for (number, worktree) in (1..).zip(&choices) {
+        writeln!(
+            menu,
+            "{:>3}) {:<width$}  {}",
+            number,
+            worktree.branch_display(),
+            worktree.path.display(),
+        )?;
+    }
+
+    let default = default.and_then(|path| choices.iter().position(|wt| wt.path == path));
+    loop {
+        match default {
+            Some(index) => write!(menu, "Worktree [{}] (q to quit): ", index + 1)?,
+            None => write!(menu, "Worktree (q to quit): ")?,
+        }
+        menu.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(menu)?;
+            return Ok(None);
+        }
+        let answer = line.trim();
+        let index = match answer {
+            "q" => return Ok(None),
+            "" => default,
+            _ => answer
+                .parse::<usize>()
+                .ok()
+                .filter(|number| (1..=choices.len()).contains(number))
+                .map(|number| number - 1),
+        };
+        match index {
+            Some(index) => return Ok(Some(choices[index].path.clone())),
+            // Enter without a default just asks again
+            None if answer.is_empty() => {}
+            None => writeln!(menu, "Enter a number from 1 to {}", choices.len())?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::WorktreeStatus;
+    use std::io::Cursor;
+
+    fn worktrees() -> Vec<Worktree> {
+        let worktree = |name: &str, branch: Option<&str>, is_bare: bool| Worktree {
+            path: PathBuf::from(format!("/repo/{}", name)),
+            branch: branch.map(str::to_string),
+            is_bare,
+            status: WorktreeStatus::Clean,
+            last_commit_time: None,
+            ahead_behind: None,
+            github_pr_status: None,
+            locked: false,
+        };
+        vec![
+            worktree(".bare", None, true),
+            worktree("main", Some("main"), false),
+            worktree("login", Some("feature/login"), false),
+        ]
+    }
+
+    fn run(answers: &str, default: Option<&Path>) -> (Option<PathBuf>, String) {
+        let mut menu = Vec::new();
+        let chosen = choose(&worktrees(), default, Cursor::new(answers), &mut menu).unwrap();
+        (chosen, String::from_utf8(menu).unwrap())
+    }
+
+    #[test]
+    fn numbered_menu_skips_bare_and_retries_bad_answers() {
+        let (chosen, menu) = run("0\nlogin\n\n2\n", None);
+
+        assert_eq!(chosen, Some(PathBuf::from("/repo/login")));
+        assert!(
+            menu.starts_with("  1) main           /repo/main\n  2) feature/login  /repo/login\n")
+        );
+        assert_eq!(menu.matches("Enter a number from 1 to 2").count(), 2);
+        assert_eq!(menu.matches("Worktree (q to quit): ").count(), 4);
+    }
+
+    #[test]
+    fn enter_takes_the_default_and_quit_or_eof_choose_nothing() {
+        let (chosen, menu) = run("\n", Some(Path::new("/repo/login")));
+        assert_eq!(chosen, Some(PathBuf::from("/repo/login")));
+        assert!(menu.ends_with("Worktree [2] (q to quit): "));
+
+        assert_eq!(run("q\n", None).0, None);
+        assert_eq!(run("", Some(Path::new("/repo/main"))).0, None);
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::{Context, Result};
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Files larger than this are not loaded into the pager
+const MAX_PREVIEW_BYTES: u64 = 1024 * 1024;
+
+/// Above either limit a file is shown as plain text: highlighting time grows
+/// with file size and line length, and minified files take seconds
+const MAX_HIGHLIGHT_BYTES: usize = 128 * 1024;
+const MAX_HIGHLIGHT_LINE: usize = 1000;
+
+/// A file loaded for the read-only pager, highlighted line by line.
+#[derive(Debug, Clone)]
+pub(crate) struct FilePreview {
+    pub path: PathBuf,
+    pub lines: Vec<Line<'static>>,
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn highlight_theme(light: bool) -> &'static Theme {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    let themes = THEMES.get_or_init(ThemeSet::load_defaults);
+    let name = if light {
+        "InspiredGitHub"
+    } else {
+        "base16-ocean.dark"
+    };
+    &themes.themes[name]
+}
+
+/// Read `relative` inside `worktree_path` for previewing. Binary and very
+/// large files are refused rather than shown garbled.
+pub(crate) fn load(worktree_path: &Path, relative: &str, light: bool) -> Result<FilePreview> {
+    let path = worktree_path.join(relative);
+    let metadata =
+        fs::metadata(&path).with_context(|| format!("Cannot open {}", path.display()))?;
+    if metadata.is_dir() {
+        anyhow::bail!("{} is a directory", relative);
+    }
+    if metadata.len() > MAX_PREVIEW_BYTES {
+        anyhow::bail!(
+            "{} is too large to preview ({} KiB)",
+            relative,
+            metadata.len() / 1024
+        );
+    }
+    let bytes = fs::read(&path).with_context(|| format!("Cannot read {}", path.display()))?;
+    if bytes.contains(&0) {
+        anyhow::bail!("{} is a binary file", relative);
+    }
+    let Ok(text) = String::from_utf8(bytes) else {
+        anyhow::bail!("{} is not valid UTF-8", relative);
+    };
+
+    Ok(FilePreview {
+        lines: highlight(&path, &text, light),
+        path,
+    })
+}
+
+fn highlight(path: &Path, text: &str, light: bool) -> Vec<Line<'static>> {
+    if text.len() > MAX_HIGHLIGHT_BYTES || text.lines().any(|line| line.len() > MAX_HIGHLIGHT_LINE)
+    {
+        return plain_lines(text);
+    }
+
+    let syntaxes = syntax_set();
+    let syntax = path
+        .extension()
+        .and_then(|ext| syntaxes.find_syntax_by_extension(&ext.to_string_lossy()))
+        .or_else(|| syntaxes.find_syntax_by_first_line(text.lines().next().unwrap_or("")));
+    let Some(syntax) = syntax else {
+        return plain_lines(text);
+    };
+
+    let mut highlighter = HighlightLines::new(syntax, highlight_theme(light));
+    LinesWithEndings::from(text)
+        .map(|line| match highlighter.highlight_line(line, syntaxes) {
+            Ok(ranges) => Line::from(
+                ranges
+                    .into_iter()
+                    .map(|(style, piece)| {
+                        let fg = style.foreground;
+                        Span::styled(
+                            piece.trim_end_matches(['\n', '\r']).to_string(),
+                            Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b)),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            Err(_) => Line::raw(line.trim_end_matches(['\n', '\r']).to_string()),
+        })
+        .collect()
+}
+
+fn plain_lines(text: &str) -> Vec<Line<'static>> {
+    text.lines()
+        .map(|line| Line::raw(line.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn load_highlights_known_syntaxes_and_refuses_binary_files() {
+        let base = temp_dir("preview_load");
+        fs::create_dir_all(base.join("src")).unwrap();
+        fs::write(base.join("src/main.rs"), "fn main() {\n    let x = 1;\n}\n").unwrap();
+        fs::write(base.join("notes.unknownext"), "first\nsecond\n").unwrap();
+        fs::write(base.join("image.bin"), [0x89, b'P', 0, 0]).unwrap();
+
+        let rust = load(&base, "src/main.rs", false).unwrap();
+        assert_eq!(rust.lines.len(), 3);
+        assert_eq!(rust.lines[1].to_string(), "    let x = 1;");
+        assert!(rust.lines[0].spans.len() > 1);
+
+        let plain = load(&base, "notes.unknownext", true).unwrap();
+        assert_eq!(plain.lines.len(), 2);
+        assert_eq!(plain.lines[0].to_string(), "first");
+
+        let minified = format!("var a = {};\n", "[1, 2, 3], ".repeat(200));
+        fs::write(base.join("app.min.js"), &minified).unwrap();
+        let long_line = load(&base, "app.min.js", false).unwrap();
+        assert_eq!(long_line.lines.len(), 1);
+        assert_eq!(long_line.lines[0].spans.len(), 1);
+        assert_eq!(long_line.lines[0].to_string(), minified.trim_end());
+
+        let binary = load(&base, "image.bin", false).unwrap_err();
+        assert!(binary.to_string().contains("binary"), "{}", binary);
+        assert!(load(&base, "src", false).is_err());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::state_file::write_atomic;
+
+/// Projects owt has cloned or opened, by short name, so `owt <name>` and the
+/// `w` switcher can find them from anywhere.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct RegistryFile {
+    #[serde(default)]
+    repos: BTreeMap<String, PathBuf>,
+}
+
+/// Registry path: ~/.config/owt/repos.toml
+pub(crate) fn registry_path() -> PathBuf {
+    Config::global_config_dir().join("repos.toml")
+}
+
+/// Registered projects, name to project root. A missing registry is empty.
+pub(crate) fn load() -> Result<BTreeMap<String, PathBuf>> {
+    load_from(&registry_path())
+}
+
+/// Project root registered under `name`
+pub(crate) fn lookup(name: &str) -> Option<PathBuf> {
+    load().ok()?.remove(name)
+}
+
+/// Add the project at `project_root` under its directory name, or under
+/// `<name>-2`, `<name>-3`, ... when another project already has that name.
+/// Returns the name it is registered under; registering again is a no-op.
+pub(crate) fn register(project_root: &Path) -> Result<String> {
+    register_in(&registry_path(), project_root)
+}
+
+fn load_from(path: &Path) -> Result<BTreeMap<String, PathBuf>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(error) => {
+            return Err(error).with_context(|| format!("Failed to read {}", path.display()))
+        }
+    };
+    let file: RegistryFile =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(file.repos)
+}
+
+fn register_in(path: &Path, project_root: &Path) -> Result<String> {
+    let project_root = project_root
+        .canonicalize()
+        .unwrap_or_else(|_| project_root.to_path_buf());
+    let mut repos = load_from(path)?;
+    if let Some((name, _)) = repos.iter().find(|(_, root)| **root == project_root) {
+        return Ok(name.clone());
+    }
+
+    let base = project_root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repo".to_string());
+    let name = std::iter::once(base.clone())
+        .chain((2..).map(|n| format!("{}-{}", base, n)))
+        .find(|name| !repos.contains_key(name))
+        .unwrap_or(base);
+    repos.insert(name.clone(), project_root);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    write_atomic(path, &toml::to_string(&RegistryFile { repos })?)?;
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn register_names_projects_by_directory_and_dedupes() {
+        let base = temp_dir("repo_registry");
+        let api = base.join("work").join("api");
+        let other_api = base.join("oss").join("api");
+        fs::create_dir_all(&api).unwrap();
+        fs::create_dir_all(&other_api).unwrap();
+        let registry = base.join("config").join("repos.toml");
+        assert!(load_from(&registry).unwrap().is_empty());
+
+        assert_eq!(register_in(&registry, &api).unwrap(), "api");
+        assert_eq!(register_in(&registry, &api).unwrap(), "api");
+        assert_eq!(register_in(&registry, &other_api).unwrap(), "api-2");
+
+        let repos = load_from(&registry).unwrap();
+        assert_eq!(repos.len(), 2);
+        assert_eq!(repos["api"], api.canonicalize().unwrap());
+        assert_eq!(repos["api-2"], other_api.canonicalize().unwrap());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
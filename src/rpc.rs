@@ -0,0 +1,258 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::git;
+use crate::types::Worktree;
+use crate::RepositoryContext;
+
+/// `owt --rpc`: newline-delimited JSON requests on stdin, one JSON response
+/// per line on stdout. Used by editor extensions instead of the TUI.
+pub fn run(path: &Path) -> Result<()> {
+    let context = crate::resolve_repository_context(path)?;
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+
+    for line in stdin.lock().lines() {
+        let line = line.context("Failed to read RPC request")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = handle_line(&context, path, &line);
+        writeln!(stdout, "{}", response)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+fn handle_line(context: &RepositoryContext, launch_path: &Path, line: &str) -> Value {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(error) => return error_response(Value::Null, &format!("Invalid JSON: {}", error)),
+    };
+
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return error_response(id, "Missing method");
+    };
+    let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    match handle_request(context, launch_path, method, &params) {
+        Ok(result) => json!({ "id": id, "result": result }),
+        Err(error) => error_response(id, &format!("{:#}", error)),
+    }
+}
+
+fn handle_request(
+    context: &RepositoryContext,
+    launch_path: &Path,
+    method: &str,
+    params: &Value,
+) -> Result<Value> {
+    match method {
+        "list" => {
+            let mut worktrees = git::list_worktrees(&context.repo_path)?;
+            if bool_param(params, "pr") {
+                crate::refresh_pr_statuses(&context.repo_path, &mut worktrees);
+            }
+            let descriptions = git::branch_descriptions(&context.repo_path);
+            let listed = worktrees
+                .iter()
+                .map(|worktree| listed_worktree_json(worktree, &descriptions))
+                .collect();
+            Ok(Value::Array(listed))
+        }
+        "add" => {
+            let branch = string_param(params, "branch")?;
+            let base = optional_string_param(params, "base");
+            let worktree_path = optional_string_param(params, "path").map(PathBuf::from);
+            let config =
+                Config::load_with_project(Some(&context.project_root_path)).unwrap_or_default();
+            let (target_path, warnings) = crate::create_worktree(
+                context,
+                &config,
+                launch_path,
+                &branch,
+                base.as_deref(),
+                worktree_path,
+            )?;
+            Ok(json!({
+                "branch": branch,
+                "path": target_path.display().to_string(),
+                "warnings": warnings,
+            }))
+        }
+        "remove" => {
+            let target = string_param(params, "target")?;
+            let (worktree, kept) = crate::delete_worktree(
+                context,
+                &target,
+                bool_param(params, "force"),
+                bool_param(params, "delete_branch"),
+                bool_param(params, "keep_nested"),
+            )?;
+            Ok(deleted_worktree_json(&worktree, &kept))
+        }
+        "switch" => {
+            let target = string_param(params, "target")?;
+            let worktrees = git::list_worktrees(&context.repo_path)?;
+            let worktree = crate::find_worktree_target(&worktrees, &target)?;
+            if worktree.is_bare {
+                anyhow::bail!("Cannot switch to bare repository");
+            }
+            Ok(worktree_json(&worktree))
+        }
+        _ => anyhow::bail!("Unknown method '{}'", method),
+    }
+}
+
+/// The worktree object shared by RPC responses and `--json` output
+pub(crate) fn worktree_json(worktree: &Worktree) -> Value {
+    json!({
+        "name": worktree.display_name(),
+        "path": worktree.path.display().to_string(),
+        "branch": worktree.branch,
+        "bare": worktree.is_bare,
+        "locked": worktree.locked,
+        "status": worktree.status.label(),
+        "last_commit": worktree.last_commit_time,
+        "ahead": worktree.ahead_behind.as_ref().map(|ahead_behind| ahead_behind.ahead),
+        "behind": worktree.ahead_behind.as_ref().map(|ahead_behind| ahead_behind.behind),
+        "pr": worktree.github_pr_status.map(|status| status.label()),
+    })
+}
+
+/// `worktree_json` with the branch description, as `list` returns it
+pub(crate) fn listed_worktree_json(
+    worktree: &Worktree,
+    descriptions: &HashMap<String, String>,
+) -> Value {
+    let description = worktree
+        .branch
+        .as_ref()
+        .and_then(|branch| descriptions.get(branch));
+    with_field(worktree_json(worktree), "description", json!(description))
+}
+
+/// `worktree_json` with the nested repositories moved to `.owt/kept/`
+pub(crate) fn deleted_worktree_json(worktree: &Worktree, kept: &[PathBuf]) -> Value {
+    let kept: Vec<String> = kept.iter().map(|path| path.display().to_string()).collect();
+    with_field(worktree_json(worktree), "kept", json!(kept))
+}
+
+fn with_field(mut object: Value, key: &str, value: Value) -> Value {
+    if let Value::Object(ref mut fields) = object {
+        fields.insert(key.to_string(), value);
+    }
+    object
+}
+
+fn error_response(id: Value, message: &str) -> Value {
+    json!({ "id": id, "error": { "message": message } })
+}
+
+fn string_param(params: &Value, key: &str) -> Result<String> {
+    optional_string_param(params, key)
+        .with_context(|| format!("Missing string parameter '{}'", key))
+}
+
+fn optional_string_param(params: &Value, key: &str) -> Option<String> {
+    params
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn bool_param(params: &Value, key: &str) -> bool {
+    params.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_test_regular_repo, temp_dir};
+    use std::fs;
+
+    #[test]
+    fn rpc_add_list_switch_and_remove_worktree() {
+        let root = temp_dir("rpc_round_trip");
+        let repo = root.join("app");
+        let main_branch = create_test_regular_repo(&repo);
+        let context = crate::resolve_repository_context(&repo).unwrap();
+        let feature_path = root.join("feature-rpc");
+
+        let added = handle_line(
+            &context,
+            &repo,
+            &json!({
+                "id": 1,
+                "method": "add",
+                "params": { "branch": "feature/rpc", "path": feature_path.display().to_string() }
+            })
+            .to_string(),
+        );
+        assert_eq!(added["id"], 1);
+        assert_eq!(added["result"]["branch"], "feature/rpc");
+        assert!(feature_path.exists());
+
+        let listed = handle_line(&context, &repo, r#"{"id":2,"method":"list"}"#);
+        let branches: Vec<&str> = listed["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|worktree| worktree["branch"].as_str())
+            .collect();
+        assert_eq!(branches, vec![main_branch.as_str(), "feature/rpc"]);
+
+        let switched = handle_line(
+            &context,
+            &repo,
+            r#"{"id":3,"method":"switch","params":{"target":"feature/rpc"}}"#,
+        );
+        assert_eq!(switched["result"]["name"], "feature-rpc");
+        assert_eq!(switched["result"]["status"], "clean");
+
+        let removed = handle_line(
+            &context,
+            &repo,
+            r#"{"id":"4","method":"remove","params":{"target":"feature/rpc","delete_branch":true}}"#,
+        );
+        assert_eq!(removed["id"], "4");
+        assert_eq!(removed["result"]["branch"], "feature/rpc");
+        assert!(!feature_path.exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn rpc_errors_keep_request_id() {
+        let root = temp_dir("rpc_errors");
+        let repo = root.join("app");
+        create_test_regular_repo(&repo);
+        let context = crate::resolve_repository_context(&repo).unwrap();
+
+        let unknown = handle_line(&context, &repo, r#"{"id":7,"method":"rename"}"#);
+        assert_eq!(unknown["id"], 7);
+        assert_eq!(unknown["error"]["message"], "Unknown method 'rename'");
+
+        let missing = handle_line(&context, &repo, r#"{"id":8,"method":"switch"}"#);
+        assert_eq!(
+            missing["error"]["message"],
+            "Missing string parameter 'target'"
+        );
+
+        let invalid = handle_line(&context, &repo, "not json");
+        assert!(invalid["id"].is_null());
+        assert!(invalid["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid JSON"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
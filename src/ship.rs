@@ -0,0 +1,120 @@
+use crate::config::Config;
+
+/// One step of the `Z` ship action, named in `ship_steps`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ShipStep {
+    Push,   // Push the branch, setting its upstream on the first push
+    Pr,     // Open a GitHub PR with `gh pr create --fill`
+    Review, // Mark the worktree review-only
+    Main,   // Select the default branch worktree
+}
+
+/// Steps used when `ship_steps` is not set
+pub(crate) const DEFAULT_SHIP_STEPS: &[&str] = &["push", "pr", "review", "main"];
+
+impl ShipStep {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "push" => Some(ShipStep::Push),
+            "pr" => Some(ShipStep::Pr),
+            "review" => Some(ShipStep::Review),
+            "main" => Some(ShipStep::Main),
+            _ => None,
+        }
+    }
+
+    /// Progress and result wording, e.g. "Ship 2/4: create PR"
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            ShipStep::Push => "push",
+            ShipStep::Pr => "create PR",
+            ShipStep::Review => "mark review-only",
+            ShipStep::Main => "jump to main",
+        }
+    }
+
+    /// Push and PR talk to the remote and run in the background; the others
+    /// only change owt state and run once those succeed.
+    pub(crate) fn is_remote(self) -> bool {
+        matches!(self, ShipStep::Push | ShipStep::Pr)
+    }
+}
+
+/// Configured ship steps in order, ignoring unknown names and repeats
+pub(crate) fn steps(config: &Config) -> Vec<ShipStep> {
+    let mut steps = Vec::new();
+    let names: Vec<&str> = if config.ship_steps.is_empty() {
+        DEFAULT_SHIP_STEPS.to_vec()
+    } else {
+        config.ship_steps.iter().map(String::as_str).collect()
+    };
+    for step in names.into_iter().filter_map(ShipStep::parse) {
+        if !steps.contains(&step) {
+            steps.push(step);
+        }
+    }
+    steps
+}
+
+/// Status message when step `failed` stops the sequence: what already ran,
+/// and what was skipped. Nothing is undone, so the message says so.
+pub(crate) fn failure_message(
+    steps: &[ShipStep],
+    failed: ShipStep,
+    completed: &[ShipStep],
+    error: &str,
+) -> String {
+    let skipped: Vec<&str> = steps
+        .iter()
+        .filter(|step| **step != failed && !completed.contains(step))
+        .map(|step| step.label())
+        .collect();
+    let mut message = format!("Ship stopped at {}: {}", failed.label(), error);
+    if !completed.is_empty() {
+        let done: Vec<&str> = completed.iter().map(|step| step.label()).collect();
+        message.push_str(&format!(
+            "\nAlready done (kept, not rolled back): {}",
+            done.join(", ")
+        ));
+    }
+    if !skipped.is_empty() {
+        message.push_str(&format!("\nNot run: {}", skipped.join(", ")));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_default_dedupe_and_failure_message_lists_done_and_skipped() {
+        let mut config = Config::default();
+        assert_eq!(
+            steps(&config),
+            [
+                ShipStep::Push,
+                ShipStep::Pr,
+                ShipStep::Review,
+                ShipStep::Main
+            ]
+        );
+
+        config.ship_steps = vec![
+            "push".to_string(),
+            "deploy".to_string(),
+            "push".to_string(),
+            "main".to_string(),
+        ];
+        assert_eq!(steps(&config), [ShipStep::Push, ShipStep::Main]);
+
+        let all = steps(&Config::default());
+        let message = failure_message(&all, ShipStep::Pr, &[ShipStep::Push], "gh: not logged in");
+        assert_eq!(
+            message,
+            "Ship stopped at create PR: gh: not logged in\n\
+             Already done (kept, not rolled back): push\n\
+             Not run: mark review-only, jump to main"
+        );
+    }
+}
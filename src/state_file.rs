@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::config::Config;
+
+/// Exclusive advisory lock on `.owt/state.lock`, released when dropped.
+pub(crate) struct StateLock {
+    _file: fs::File,
+}
+
+impl StateLock {
+    pub(crate) fn acquire(project_root_path: &Path) -> Result<Self> {
+        let owt_dir = Config::owt_dir(project_root_path);
+        fs::create_dir_all(&owt_dir)
+            .with_context(|| format!("Failed to create {}", owt_dir.display()))?;
+
+        let lock_path = owt_dir.join("state.lock");
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+        lock_exclusive(&file).with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+        Ok(Self { _file: file })
+    }
+}
+
+#[cfg(unix)]
+fn lock_exclusive(file: &fs::File) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    loop {
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+            return Ok(());
+        }
+        let error = std::io::Error::last_os_error();
+        if error.kind() != std::io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn lock_exclusive(_file: &fs::File) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Write through a sibling temp file and rename it into place, so a crash or a
+/// concurrent reader never sees a half-written file.
+pub(crate) fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    let result = (|| -> std::io::Result<()> {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result.with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Per-project files copied into every new worktree: `.owt/template/`.
+pub(crate) fn template_dir(project_root_path: &Path) -> PathBuf {
+    Config::owt_dir(project_root_path).join("template")
+}
+
+/// Copy the project template into `worktree_path`, replacing `{{branch}}` and
+/// `{{base}}` in text files. Binary files are copied as-is. Problems become
+/// warnings so the new worktree is kept.
+pub(crate) fn apply(
+    project_root_path: &Path,
+    worktree_path: &Path,
+    branch: &str,
+    base: &str,
+) -> Vec<String> {
+    let template_dir = template_dir(project_root_path);
+    if !template_dir.is_dir() {
+        return Vec::new();
+    }
+
+    let mut warnings = Vec::new();
+    let mut pending = vec![template_dir.clone()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(error) => {
+                warnings.push(format!("template {} unreadable: {}", dir.display(), error));
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let relative = path.strip_prefix(&template_dir).unwrap_or(&path);
+            if let Err(error) =
+                copy_template_file(&path, &worktree_path.join(relative), branch, base)
+            {
+                warnings.push(format!("template {}: {:#}", relative.display(), error));
+            }
+        }
+    }
+    warnings.sort();
+    warnings
+}
+
+fn copy_template_file(source: &Path, destination: &Path, branch: &str, base: &str) -> Result<()> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("could not create {}", parent.display()))?;
+    }
+    let bytes = fs::read(source).with_context(|| format!("could not read {}", source.display()))?;
+    let content = match String::from_utf8(bytes) {
+        Ok(text) => substitute(&text, branch, base).into_bytes(),
+        Err(error) => error.into_bytes(),
+    };
+    fs::write(destination, content)
+        .with_context(|| format!("could not write {}", destination.display()))?;
+    // Keep template scripts executable
+    if let Ok(metadata) = fs::metadata(source) {
+        let _ = fs::set_permissions(destination, metadata.permissions());
+    }
+    Ok(())
+}
+
+fn substitute(text: &str, branch: &str, base: &str) -> String {
+    text.replace("{{branch}}", branch).replace("{{base}}", base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn apply_copies_nested_files_and_substitutes_placeholders() {
+        let base = temp_dir("template_apply");
+        let template = template_dir(&base);
+        fs::create_dir_all(template.join(".vscode")).unwrap();
+        fs::write(
+            template.join(".env"),
+            "BRANCH={{branch}}\nBASE={{base}}\nDB=app_{{branch}}\n",
+        )
+        .unwrap();
+        fs::write(
+            template.join(".vscode").join("settings.json"),
+            "{\"title\": \"{{branch}}\"}\n",
+        )
+        .unwrap();
+        fs::write(template.join("logo.bin"), [0xff, 0xfe, b'{', b'{']).unwrap();
+        let worktree = base.join("feature").join("login");
+        fs::create_dir_all(&worktree).unwrap();
+
+        let warnings = apply(&base, &worktree, "feature/login", "develop");
+
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(
+            fs::read_to_string(worktree.join(".env")).unwrap(),
+            "BRANCH=feature/login\nBASE=develop\nDB=app_feature/login\n"
+        );
+        assert_eq!(
+            fs::read_to_string(worktree.join(".vscode").join("settings.json")).unwrap(),
+            "{\"title\": \"feature/login\"}\n"
+        );
+        assert_eq!(
+            fs::read(worktree.join("logo.bin")).unwrap(),
+            vec![0xff, 0xfe, b'{', b'{']
+        );
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn apply_without_template_does_nothing() {
+        let base = temp_dir("template_missing");
+
+        assert!(apply(&base, &base.join("main"), "main", "main").is_empty());
+        assert!(!base.join("main").exists());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,103 @@
+//! Temp directories and git repositories for unit tests
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    let id = std::process::id();
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("owt_test_{}_{}_{}", name, id, ts));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+pub(crate) fn git_cmd() -> Command {
+    let mut cmd = Command::new("git");
+    cmd.env_remove("GIT_DIR")
+        .env_remove("GIT_WORK_TREE")
+        .env_remove("GIT_INDEX_FILE")
+        .env_remove("GIT_COMMON_DIR");
+    cmd
+}
+
+pub(crate) fn git_in(path: &Path, args: &[&str]) -> Output {
+    git_cmd().current_dir(path).args(args).output().unwrap()
+}
+
+/// A non-bare repository with one commit; returns its branch
+pub(crate) fn create_test_regular_repo(path: &Path) -> String {
+    fs::create_dir_all(path).unwrap();
+
+    let init_output = git_cmd().current_dir(path).args(["init"]).output().unwrap();
+    assert!(
+        init_output.status.success(),
+        "git init failed: {}",
+        String::from_utf8_lossy(&init_output.stderr)
+    );
+
+    let config_email = git_cmd()
+        .current_dir(path)
+        .args(["config", "user.email", "test@test.com"])
+        .output()
+        .unwrap();
+    assert!(
+        config_email.status.success(),
+        "git config email failed: {}",
+        String::from_utf8_lossy(&config_email.stderr)
+    );
+
+    let config_name = git_cmd()
+        .current_dir(path)
+        .args(["config", "user.name", "Test"])
+        .output()
+        .unwrap();
+    assert!(
+        config_name.status.success(),
+        "git config name failed: {}",
+        String::from_utf8_lossy(&config_name.stderr)
+    );
+
+    fs::write(path.join("README.md"), "# Test").unwrap();
+    let add_output = git_cmd()
+        .current_dir(path)
+        .args(["add", "."])
+        .output()
+        .unwrap();
+    assert!(
+        add_output.status.success(),
+        "git add failed: {}",
+        String::from_utf8_lossy(&add_output.stderr)
+    );
+
+    let commit_output = git_cmd()
+        .current_dir(path)
+        .args(["commit", "-m", "Initial commit"])
+        .output()
+        .unwrap();
+    assert!(
+        commit_output.status.success(),
+        "git commit failed: {}",
+        String::from_utf8_lossy(&commit_output.stderr)
+    );
+
+    let branch_output = git_cmd()
+        .current_dir(path)
+        .args(["branch", "--show-current"])
+        .output()
+        .unwrap();
+    assert!(
+        branch_output.status.success(),
+        "git branch --show-current failed: {}",
+        String::from_utf8_lossy(&branch_output.stderr)
+    );
+
+    String::from_utf8_lossy(&branch_output.stdout)
+        .trim()
+        .to_string()
+}
@@ -45,6 +45,64 @@ pub fn open_worktree_pane(worktree_path: &Path, worktree_name: &str) -> Result<(
     Ok(())
 }
 
+/// Where [`open_worktree_window`] put the worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TmuxTarget {
+    Window(String),  // Window in the tmux session owt runs in
+    Session(String), // Detached session, since owt is not inside tmux
+}
+
+/// Open `worktree_path` in tmux under `name` (the branch). Inside tmux this
+/// selects the current session's window with that name, or opens a new one
+/// with `tmux new-window -c <path>`. Outside tmux it starts a detached session
+/// of that name, reusing one that already exists, for the user to attach to.
+pub fn open_worktree_window(worktree_path: &Path, name: &str) -> Result<TmuxTarget> {
+    let path = worktree_path.display().to_string();
+    if std::env::var_os("TMUX").is_some_and(|value| !value.is_empty()) {
+        let name = tmux_name(name);
+        let windows = tmux_output(&[
+            "list-windows".to_string(),
+            "-F".to_string(),
+            "#{window_id}\t#{window_name}".to_string(),
+        ])?;
+        let existing = windows
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .find(|(_, window_name)| *window_name == name)
+            .map(|(window_id, _)| window_id.to_string());
+        match existing {
+            Some(window_id) => {
+                tmux_status(&["select-window".to_string(), "-t".to_string(), window_id])?
+            }
+            None => tmux_status(&[
+                "new-window".to_string(),
+                "-c".to_string(),
+                path,
+                "-n".to_string(),
+                name.clone(),
+            ])?,
+        }
+        return Ok(TmuxTarget::Window(name));
+    }
+
+    let session = session_name(name);
+    let exists = Command::new("tmux")
+        .args(["has-session", "-t", &format!("={}", session)])
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if !exists {
+        tmux_status(&[
+            "new-session".to_string(),
+            "-d".to_string(),
+            "-s".to_string(),
+            session.clone(),
+            "-c".to_string(),
+            path,
+        ])?;
+    }
+    Ok(TmuxTarget::Session(session))
+}
+
 pub fn focus_pane_named(worktree_name: &str) -> Result<bool> {
     let target_name = tmux_name(worktree_name);
     let panes = tmux_output(&[
@@ -128,6 +186,12 @@ fn command_detail(output: &std::process::Output) -> String {
     }
 }
 
+/// tmux reads `.` and `:` in a target as window and pane separators, so they
+/// cannot appear in session names.
+fn session_name(name: &str) -> String {
+    tmux_name(name).replace(['.', ':'], "-")
+}
+
 fn tmux_name(name: &str) -> String {
     let name = name.replace(['\t', '\n', '\r'], " ").trim().to_string();
     if name.is_empty() {
@@ -136,3 +200,15 @@ fn tmux_name(name: &str) -> String {
         name
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_name_replaces_target_separators_and_blank_names() {
+        assert_eq!(session_name("feature/login"), "feature/login");
+        assert_eq!(session_name("release/1.2:rc"), "release/1-2-rc");
+        assert_eq!(session_name(" \t"), "worktree");
+    }
+}
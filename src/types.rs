@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use crate::archive::ArchiveEntry;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WorktreeStatus {
     Clean,
@@ -70,6 +72,100 @@ impl AheadBehind {
     }
 }
 
+/// What updating one local base branch from its remote did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BaseUpdateOutcome {
+    /// Branch was not checked out, so its ref moved to the remote commit
+    FastForwarded {
+        remote_branch: String,
+        commits: u32,
+    },
+    /// Branch was checked out, so its worktree was fast-forwarded
+    Pulled {
+        worktree: PathBuf,
+        commits: u32,
+    },
+    UpToDate,
+    Skipped(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseUpdate {
+    pub branch: String,
+    pub outcome: BaseUpdateOutcome,
+}
+
+impl BaseUpdate {
+    pub fn moved(&self) -> bool {
+        matches!(
+            self.outcome,
+            BaseUpdateOutcome::FastForwarded { .. } | BaseUpdateOutcome::Pulled { .. }
+        )
+    }
+
+    pub fn summary(&self) -> String {
+        match &self.outcome {
+            BaseUpdateOutcome::FastForwarded {
+                remote_branch,
+                commits,
+            } => format!("fast-forwarded {} commit(s) to {}", commits, remote_branch),
+            BaseUpdateOutcome::Pulled { worktree, commits } => {
+                format!("pulled {} commit(s) in {}", commits, worktree.display())
+            }
+            BaseUpdateOutcome::UpToDate => "up to date".to_string(),
+            BaseUpdateOutcome::Skipped(reason) => format!("skipped: {}", reason),
+        }
+    }
+}
+
+/// One step of git's clone progress, e.g. `Receiving objects:  45% (450/1000)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloneProgress {
+    pub phase: String, // "Receiving objects", without the "remote: " prefix
+    pub percent: u8,
+}
+
+impl CloneProgress {
+    /// Parse one `\r`- or `\n`-terminated segment of `git clone --progress`
+    /// stderr. Lines without a percentage ("Cloning into...") are not progress.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let line = line.strip_prefix("remote: ").unwrap_or(line);
+        let (phase, rest) = line.split_once(": ")?;
+        let (percent, _) = rest.trim_start().split_once('%')?;
+        Some(Self {
+            phase: phase.to_string(),
+            percent: percent.parse().ok().filter(|percent| *percent <= 100)?,
+        })
+    }
+}
+
+/// How a local base branch compares with its remote-tracking branch, after
+/// fetching it.
+#[derive(Debug, Clone, Default)]
+pub struct BaseComparison {
+    pub remote_branch: Option<String>, // e.g. "origin/main"
+    pub has_local: bool,
+    pub local_vs_remote: Option<AheadBehind>,
+    pub fetched: bool,
+}
+
+impl BaseComparison {
+    pub fn summary(&self) -> String {
+        match (&self.remote_branch, self.has_local) {
+            (None, true) => "local only".to_string(),
+            (None, false) => "not found".to_string(),
+            (Some(remote), false) => format!("from {}", remote),
+            (Some(remote), true) => match self.local_vs_remote.as_ref() {
+                Some(counts) if counts.ahead > 0 || counts.behind > 0 => {
+                    format!("local {} {}", counts.display().unwrap_or_default(), remote)
+                }
+                _ => format!("up to date with {}", remote),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Worktree {
     pub path: PathBuf,
@@ -79,12 +175,92 @@ pub struct Worktree {
     pub last_commit_time: Option<String>,
     pub ahead_behind: Option<AheadBehind>,
     pub github_pr_status: Option<GithubPrStatus>,
+    pub locked: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct WorktreeDetails {
     pub status_summary: String,
     pub recent_commits: Vec<String>,
+    pub description: Option<String>, // branch.<name>.description
+}
+
+/// One entry of `git status` in a worktree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    pub status: String, // Two-letter porcelain code, e.g. " M", "??"
+    pub path: String,   // Relative to the worktree; the new name for renames
+}
+
+/// What `X` removes besides changes to tracked files (`discard_mode`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiscardMode {
+    Tracked, // git restore only; untracked files stay
+    #[default]
+    Untracked, // Also git clean -fd
+    Ignored, // Also git clean -fdx, removing ignored files like build output
+}
+
+/// Accepted `discard_mode` values
+pub const DISCARD_MODES: &[&str] = &["tracked", "untracked", "ignored"];
+
+impl DiscardMode {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "tracked" => Some(DiscardMode::Tracked),
+            "untracked" => Some(DiscardMode::Untracked),
+            "ignored" => Some(DiscardMode::Ignored),
+            _ => None,
+        }
+    }
+
+    /// What the confirmation says will go
+    pub fn description(self) -> &'static str {
+        match self {
+            DiscardMode::Tracked => "changes to tracked files",
+            DiscardMode::Untracked => "changes and untracked files",
+            DiscardMode::Ignored => "changes, untracked and ignored files",
+        }
+    }
+}
+
+/// How a branch name becomes its worktree folder (`worktree_naming`). Git
+/// always gets the branch name itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WorktreeNaming {
+    #[default]
+    Nested, // feature/login -> feature/login
+    Dash,        // feature/login -> feature-login
+    StripPrefix, // feature/login -> login
+}
+
+/// Accepted `worktree_naming` values
+pub const WORKTREE_NAMINGS: &[&str] = &["nested", "dash", "strip-prefix"];
+
+impl WorktreeNaming {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "nested" => Some(WorktreeNaming::Nested),
+            "dash" => Some(WorktreeNaming::Dash),
+            "strip-prefix" => Some(WorktreeNaming::StripPrefix),
+            _ => None,
+        }
+    }
+}
+
+/// One commit in the log view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogCommit {
+    pub hash: String, // Abbreviated
+    pub author: String,
+    pub date: String, // YYYY-MM-DD
+    pub subject: String,
+}
+
+impl ChangedFile {
+    pub fn is_deleted(&self) -> bool {
+        self.status.contains('D')
+    }
 }
 
 impl Worktree {
@@ -114,9 +290,17 @@ impl Worktree {
 pub enum AppState {
     List,
     AddModal,
+    /// Asking for the `name_template` variables of `branch_types[branch_type]`
+    /// one at a time; `values` holds the ones already answered
+    AddTemplateFields {
+        branch_type: usize,
+        values: Vec<String>,
+    },
     ConfirmDelete {
         delete_branch: bool,
         force: bool,
+        delete_remote: bool,
+        keep_nested: bool, // Move nested git repositories out before deleting
     },
     ConfigModal {
         selected_index: usize,
@@ -128,6 +312,220 @@ pub enum AppState {
         branches: Vec<String>,
         selected: usize,
     },
+    /// Remote branch to set as the selected branch's upstream
+    UpstreamSelect {
+        branches: Vec<String>,
+        selected: usize,
+    },
+    /// Remote-tracking branches (`App::remote_branches`) narrowed by a fuzzy filter
+    RemoteBranches {
+        filter: String,
+        selected: usize,
+    },
+    /// Second worktree at the selected worktree's HEAD `commit`: detached,
+    /// or with `new_branch` on a `<branch>-copy` branch
+    DuplicateWorktree {
+        commit: String,
+        new_branch: bool,
+    },
+    /// Offer to run `command` (`install_command`) in the new worktree at
+    /// `worktree_path`
+    ConfirmInstall {
+        worktree_path: PathBuf,
+        command: String,
+    },
+    /// Local branches no worktree has checked out
+    OrphanBranches {
+        branches: Vec<String>,
+        selected: usize,
+    },
+    /// Worktrees removed with archive (`.owt/archive.toml`), newest first
+    ArchivedWorktrees {
+        entries: Vec<ArchiveEntry>,
+        selected: usize,
+    },
+    /// Confirmation before `git push --force-with-lease`
+    ConfirmForcePush,
+    /// Entries `git worktree prune --dry-run -v` would remove, before pruning
+    ConfirmPrune {
+        entries: Vec<String>,
+    },
+    /// New branch name input; `remote` is where the old branch was published
+    RenameModal {
+        propagate: bool,
+        remote: Option<String>,
+    },
+    /// One-line input for `branch.<branch>.description`; empty clears it
+    DescribeModal {
+        branch: String,
+    },
+    /// One-line input for the worktree's note in `.owt/notes.toml`; empty clears it
+    NoteModal {
+        name: String,
+    },
+    /// Guarded `git commit --amend` of HEAD in `worktree_path`; the input is
+    /// a new message, empty keeps the current one. `published` warns that the
+    /// upstream already has HEAD.
+    AmendModal {
+        worktree_path: PathBuf,
+        subject: String,
+        published: bool,
+    },
+    /// Typed confirmation before a forced delete that loses uncommitted
+    /// changes or unmerged commits; the input must equal `expected`
+    ConfirmDeleteTyped {
+        delete_branch: bool,
+        force: bool,
+        delete_remote: bool,
+        keep_nested: bool,
+        expected: String,
+        at_risk: Vec<String>,
+    },
+    /// Typed confirmation before discarding every change in `worktree_path`;
+    /// the input must equal `name`
+    DiscardModal {
+        worktree_path: PathBuf,
+        name: String,
+        file_count: usize,
+    },
+    /// Result of updating local base branches from their remotes
+    BaseUpdateSummary {
+        updates: Vec<BaseUpdate>,
+    },
+    /// URL input shown when owt starts outside a repository; the clone goes
+    /// into `target_dir`
+    CloneModal {
+        target_dir: PathBuf,
+    },
+    /// Changed files of the worktree at `worktree_path`
+    ChangedFiles {
+        worktree_path: PathBuf,
+        files: Vec<ChangedFile>,
+        selected: usize,
+    },
+    /// Live output of the setup script; `follow` keeps the newest line in view
+    ScriptOutput {
+        scroll: u16,
+        follow: bool,
+    },
+    /// Git commands owt ran this session with their output; `follow` keeps
+    /// the newest line in view
+    CommandLog {
+        scroll: u16,
+        follow: bool,
+    },
+    /// `[[tasks]]` to run in the selected worktree
+    TaskMenu {
+        selected: usize,
+    },
+    /// One-line shell command to run in the checked worktrees
+    RunCommand,
+    /// Configured `repositories` to open in place of the current project
+    RepoSwitcher {
+        selected: usize,
+    },
+    /// Commits of the worktree at `worktree_path`, newest first
+    GitLog {
+        worktree_path: PathBuf,
+        commits: Vec<LogCommit>,
+        selected: usize,
+    },
+    /// Message and stat of `commits[selected]`; Esc returns to the log
+    CommitDetail {
+        worktree_path: PathBuf,
+        commits: Vec<LogCommit>,
+        selected: usize,
+        scroll: u16,
+    },
+    /// Read-only view of the selected changed file; Esc returns to the list
+    FilePreview {
+        worktree_path: PathBuf,
+        files: Vec<ChangedFile>,
+        selected: usize,
+        scroll: u16,
+    },
+}
+
+impl AppState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AppState::List => "list",
+            AppState::AddModal => "add",
+            AppState::AddTemplateFields { .. } => "add_template",
+            AppState::ConfirmDelete { .. } => "confirm_delete",
+            AppState::ConfigModal { .. } => "config",
+            AppState::HelpModal => "help",
+            AppState::MergeBranchSelect { .. } => "merge_branch_select",
+            AppState::UpstreamSelect { .. } => "upstream_select",
+            AppState::RemoteBranches { .. } => "remote_branches",
+            AppState::OrphanBranches { .. } => "orphan_branches",
+            AppState::DuplicateWorktree { .. } => "duplicate",
+            AppState::ConfirmInstall { .. } => "install",
+            AppState::ArchivedWorktrees { .. } => "archived_worktrees",
+            AppState::ConfirmForcePush => "confirm_force_push",
+            AppState::ConfirmPrune { .. } => "confirm_prune",
+            AppState::RenameModal { .. } => "rename",
+            AppState::DescribeModal { .. } => "describe",
+            AppState::NoteModal { .. } => "note",
+            AppState::AmendModal { .. } => "amend",
+            AppState::ConfirmDeleteTyped { .. } => "confirm_delete_typed",
+            AppState::DiscardModal { .. } => "discard",
+            AppState::BaseUpdateSummary { .. } => "base_update_summary",
+            AppState::CloneModal { .. } => "clone",
+            AppState::ChangedFiles { .. } => "changed_files",
+            AppState::FilePreview { .. } => "file_preview",
+            AppState::ScriptOutput { .. } => "script_output",
+            AppState::CommandLog { .. } => "command_log",
+            AppState::TaskMenu { .. } => "task_menu",
+            AppState::RunCommand => "run_command",
+            AppState::RepoSwitcher { .. } => "repo_switcher",
+            AppState::GitLog { .. } => "git_log",
+            AppState::CommitDetail { .. } => "commit_detail",
+        }
+    }
+}
+
+/// Context passed to hook scripts as `OWT_*` environment variables, so they
+/// do not have to work it out from the working directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookEnv {
+    pub branch: String,
+    pub base_branch: String,
+    pub worktree_path: PathBuf,
+    pub bare_path: PathBuf,
+    pub branch_type: Option<String>, // Name of the matching [[branch_types]] entry
+}
+
+impl HookEnv {
+    pub fn vars(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("OWT_BRANCH", self.branch.clone()),
+            ("OWT_BASE_BRANCH", self.base_branch.clone()),
+            (
+                "OWT_WORKTREE_PATH",
+                self.worktree_path.to_string_lossy().to_string(),
+            ),
+            (
+                "OWT_BARE_PATH",
+                self.bare_path.to_string_lossy().to_string(),
+            ),
+            (
+                "OWT_BRANCH_TYPE",
+                self.branch_type.clone().unwrap_or_default(),
+            ),
+        ]
+    }
+
+    /// `export` statement for hooks started through a shell command line,
+    /// such as a tmux session that does not inherit owt's environment.
+    pub fn shell_exports(&self) -> String {
+        let assignments: Vec<String> = self
+            .vars()
+            .into_iter()
+            .map(|(name, value)| format!("{}='{}'", name, value.replace('\'', "'\\''")))
+            .collect();
+        format!("export {}", assignments.join(" "))
+    }
 }
 
 /// Exit reason when quitting the app
@@ -146,6 +544,8 @@ pub struct WorktreeCreateRequest {
     pub base_branch: String,
     pub worktree_path: PathBuf,
     pub source_path: Option<PathBuf>,
+    pub base_fetched: bool, // Base was already fetched while the add modal was open
+    pub path_overridden: bool, // worktree_path was typed in the add modal, not derived
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -153,15 +553,30 @@ pub enum SortMode {
     #[default]
     Name,
     Recent,
+    Frecency, // Most entered lately, from .owt/history
     Status,
+    Remote,
 }
 
 impl SortMode {
     pub fn next(self) -> Self {
         match self {
             SortMode::Name => SortMode::Recent,
-            SortMode::Recent => SortMode::Status,
-            SortMode::Status => SortMode::Name,
+            SortMode::Recent => SortMode::Frecency,
+            SortMode::Frecency => SortMode::Status,
+            SortMode::Status => SortMode::Remote,
+            SortMode::Remote => SortMode::Name,
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "name" => Some(SortMode::Name),
+            "recent" => Some(SortMode::Recent),
+            "frecency" => Some(SortMode::Frecency),
+            "status" => Some(SortMode::Status),
+            "remote" => Some(SortMode::Remote),
+            _ => None,
         }
     }
 
@@ -169,7 +584,63 @@ impl SortMode {
         match self {
             SortMode::Name => "name",
             SortMode::Recent => "recent",
+            SortMode::Frecency => "frecency",
             SortMode::Status => "status",
+            SortMode::Remote => "remote",
+        }
+    }
+}
+
+/// Quick filter cycled with Tab, applied on top of the `/` text filter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Dirty,    // Any uncommitted change or conflict
+    Clean,    // Nothing to commit
+    Diverged, // Ahead of or behind its upstream
+}
+
+impl StatusFilter {
+    pub fn next(self) -> Self {
+        match self {
+            StatusFilter::All => StatusFilter::Dirty,
+            StatusFilter::Dirty => StatusFilter::Clean,
+            StatusFilter::Clean => StatusFilter::Diverged,
+            StatusFilter::Diverged => StatusFilter::All,
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "all" => Some(StatusFilter::All),
+            "dirty" => Some(StatusFilter::Dirty),
+            "clean" => Some(StatusFilter::Clean),
+            "ahead-behind" => Some(StatusFilter::Diverged),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatusFilter::All => "all",
+            StatusFilter::Dirty => "dirty",
+            StatusFilter::Clean => "clean",
+            StatusFilter::Diverged => "ahead-behind",
+        }
+    }
+
+    /// The bare repository only shows under `All`
+    pub fn matches(&self, worktree: &Worktree) -> bool {
+        match self {
+            StatusFilter::All => true,
+            _ if worktree.is_bare => false,
+            StatusFilter::Dirty => worktree.status != WorktreeStatus::Clean,
+            StatusFilter::Clean => worktree.status == WorktreeStatus::Clean,
+            StatusFilter::Diverged => worktree
+                .ahead_behind
+                .as_ref()
+                .is_some_and(|ab| ab.ahead > 0 || ab.behind > 0),
         }
     }
 }
@@ -200,7 +671,19 @@ impl AppMessage {
 #[derive(Debug, Clone)]
 pub enum ScriptStatus {
     Idle,
-    Running { worktree_name: String },
+    Running {
+        worktree_name: String,
+        worktree_path: PathBuf,
+        label: String, // "Setup script", "Task <name>", or "Install"
+    },
+}
+
+/// Where a command run across several worktrees stands in one of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkRunStatus {
+    Running,
+    Succeeded,
+    Failed(String), // "exit 1", or why it could not start
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -208,10 +691,28 @@ pub enum OpKind {
     Fetch,
     Pull,
     Push,
-    #[allow(dead_code)]
     Add,
     Delete,
     Merge,
+    Rename,
+    UpdateBases,
+    Ship,
+}
+
+impl OpKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OpKind::Fetch => "fetch",
+            OpKind::Pull => "pull",
+            OpKind::Push => "push",
+            OpKind::Add => "add",
+            OpKind::Delete => "delete",
+            OpKind::Merge => "merge",
+            OpKind::Rename => "rename",
+            OpKind::UpdateBases => "update_bases",
+            OpKind::Ship => "ship",
+        }
+    }
 }
 
 pub struct OpResult {
@@ -236,6 +737,54 @@ pub struct ActiveOp {
 mod tests {
     use super::*;
 
+    #[test]
+    fn hook_env_exports_quote_values_for_the_shell() {
+        let hook_env = HookEnv {
+            branch: "feature/it's-done".to_string(),
+            base_branch: "develop".to_string(),
+            worktree_path: PathBuf::from("/repo/feature/it's-done"),
+            bare_path: PathBuf::from("/repo/.bare"),
+            branch_type: Some("feature".to_string()),
+        };
+
+        assert_eq!(
+            hook_env.shell_exports(),
+            "export OWT_BRANCH='feature/it'\\''s-done' OWT_BASE_BRANCH='develop' \
+             OWT_WORKTREE_PATH='/repo/feature/it'\\''s-done' OWT_BARE_PATH='/repo/.bare' \
+             OWT_BRANCH_TYPE='feature'"
+        );
+    }
+
+    #[test]
+    fn clone_progress_parses_local_and_remote_phases() {
+        assert_eq!(
+            CloneProgress::parse("Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s"),
+            Some(CloneProgress {
+                phase: "Receiving objects".to_string(),
+                percent: 45
+            })
+        );
+        assert_eq!(
+            CloneProgress::parse("remote: Compressing objects: 100% (8/8), done."),
+            Some(CloneProgress {
+                phase: "Compressing objects".to_string(),
+                percent: 100
+            })
+        );
+        assert_eq!(
+            CloneProgress::parse("Cloning into bare repository 'repo/.bare'..."),
+            None
+        );
+        assert_eq!(
+            CloneProgress::parse("remote: Enumerating objects: 10, done."),
+            None
+        );
+        assert_eq!(
+            CloneProgress::parse("fatal: repository 'x' not found"),
+            None
+        );
+    }
+
     #[test]
     fn worktree_status_symbols_and_labels_match_contract() {
         let cases = [
@@ -298,6 +847,7 @@ mod tests {
             last_commit_time: None,
             ahead_behind: None,
             github_pr_status: None,
+            locked: false,
         };
 
         assert_eq!(worktree.github_pr_display(), "-");
@@ -8,11 +8,12 @@ use ratatui::{
 
 use super::theme::centered_rect_with_min;
 use crate::app::App;
+use crate::git;
 
 pub fn render(frame: &mut Frame, app: &App) {
     let t = &app.theme;
-    // min: 6 inner rows + 2 border = 8
-    let area = centered_rect_with_min(64, 32, 8, frame.area());
+    // min: 7 inner rows + 2 border = 9
+    let area = centered_rect_with_min(64, 32, 9, frame.area());
 
     // Clear the background
     frame.render_widget(Clear, area);
@@ -30,26 +31,42 @@ pub fn render(frame: &mut Frame, app: &App) {
         Constraint::Length(1), // Label + Input
         Constraint::Length(1), // Hint
         Constraint::Length(1), // Base branch
+        Constraint::Length(1), // Worktree path
         Constraint::Min(1),    // Spacing
         Constraint::Length(1), // Help
     ])
     .split(inner);
 
     // Branch name label + input (inline like config_modal)
-    let input_display = format!("[{}█]", app.input_buffer);
+    // Suggested completion trails the cursor as ghost text
     let label_input = Paragraph::new(Line::from(vec![
         Span::styled("Branch name: ", Style::default().fg(t.text_primary)),
-        Span::styled(input_display, Style::default().fg(t.amber)),
+        Span::styled(
+            format!("[{}█", app.input_buffer),
+            Style::default().fg(t.amber),
+        ),
+        Span::styled(
+            app.add_modal_suggestion().unwrap_or("").to_string(),
+            Style::default().fg(t.text_muted),
+        ),
+        Span::styled("]", Style::default().fg(t.amber)),
     ]));
     frame.render_widget(label_input, chunks[1]);
 
-    // Hint for name format
-    let hint = Paragraph::new(Line::from(vec![Span::styled(
-        "  e.g. feature/login, hotfix/bug-123",
-        Style::default()
-            .fg(t.text_muted)
-            .add_modifier(Modifier::ITALIC),
-    )]));
+    // Hint for name format, replaced by the problem while the name is invalid
+    let branch = app.input_buffer.trim();
+    let hint = match git::branch_name_problem(branch).filter(|_| !branch.is_empty()) {
+        Some(problem) => Paragraph::new(Line::from(vec![Span::styled(
+            format!("  Invalid: {}", problem),
+            Style::default().fg(t.red),
+        )])),
+        None => Paragraph::new(Line::from(vec![Span::styled(
+            "  e.g. feature/login, hotfix/bug-123",
+            Style::default()
+                .fg(t.text_muted)
+                .add_modifier(Modifier::ITALIC),
+        )])),
+    };
     frame.render_widget(hint, chunks[2]);
 
     let base_branch = Paragraph::new(Line::from(vec![Span::styled(
@@ -58,15 +75,55 @@ pub fn render(frame: &mut Frame, app: &App) {
     )]));
     frame.render_widget(base_branch, chunks[3]);
 
+    let path_line = match app.add_path_override.as_deref() {
+        Some(path) if app.add_editing_path => Line::from(vec![
+            Span::styled("  Path: ", Style::default().fg(t.text_primary)),
+            Span::styled(format!("[{}█]", path), Style::default().fg(t.amber)),
+        ]),
+        Some(path) => Line::from(vec![Span::styled(
+            format!("  Path: {}  (override, Ctrl+L to edit)", path),
+            Style::default().fg(t.amber),
+        )]),
+        None => Line::from(vec![Span::styled(
+            format!(
+                "  Path: {}  (Ctrl+L to change)",
+                app.add_modal_worktree_path(app.input_buffer.trim())
+                    .display()
+            ),
+            Style::default().fg(t.text_muted),
+        )]),
+    };
+    frame.render_widget(Paragraph::new(path_line), chunks[4]);
+
     // Help text
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("Enter", Style::default().fg(t.cyan)),
-        Span::raw(" confirm  "),
-        Span::styled("Tab", Style::default().fg(t.cyan)),
-        Span::raw(" base  "),
-        Span::styled("Esc", Style::default().fg(t.cyan)),
-        Span::raw(" cancel"),
-    ]))
-    .style(Style::default().fg(t.text_muted));
-    frame.render_widget(help, chunks[5]);
+    let help_spans = if app.add_editing_path {
+        vec![
+            Span::styled("Enter", Style::default().fg(t.cyan)),
+            Span::raw(" keep path  "),
+            Span::styled("Tab", Style::default().fg(t.cyan)),
+            Span::raw(" complete  "),
+            Span::styled("Esc", Style::default().fg(t.cyan)),
+            Span::raw(" default path"),
+        ]
+    } else {
+        let mut spans = vec![
+            Span::styled("Enter", Style::default().fg(t.cyan)),
+            Span::raw(" confirm  "),
+            Span::styled("Tab", Style::default().fg(t.cyan)),
+            Span::raw(" complete/base  "),
+            Span::styled("^L", Style::default().fg(t.cyan)),
+            Span::raw(" path  "),
+            Span::styled("^O", Style::default().fg(t.cyan)),
+            Span::raw(" local branch  "),
+        ];
+        if app.config.issue_title_command.is_some() {
+            spans.push(Span::styled("^T", Style::default().fg(t.cyan)));
+            spans.push(Span::raw(" issue title  "));
+        }
+        spans.push(Span::styled("Esc", Style::default().fg(t.cyan)));
+        spans.push(Span::raw(" cancel"));
+        spans
+    };
+    let help = Paragraph::new(Line::from(help_spans)).style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[6]);
 }
@@ -0,0 +1,80 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::AmendModal {
+        subject, published, ..
+    } = &app.state
+    else {
+        return;
+    };
+
+    // min: 6 inner rows + 2 border = 8
+    let area = centered_rect_with_min(64, 30, 8, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Amend Last Commit ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if *published { t.red } else { t.cyan }));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // HEAD subject
+        Constraint::Length(1), // Spacing
+        Constraint::Length(1), // Label + Input
+        Constraint::Length(1), // Published warning
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let head = Paragraph::new(Line::from(vec![
+        Span::styled("HEAD: ", Style::default().fg(t.text_muted)),
+        Span::styled(subject.as_str(), Style::default().fg(t.text_primary)),
+    ]));
+    frame.render_widget(head, chunks[0]);
+
+    let input_display = format!("[{}█]", app.input_buffer);
+    let label_input = Paragraph::new(Line::from(vec![
+        Span::styled("New message: ", Style::default().fg(t.text_primary)),
+        Span::styled(input_display, Style::default().fg(t.amber)),
+    ]));
+    frame.render_widget(label_input, chunks[2]);
+
+    let note = if *published {
+        Span::styled(
+            "  Already pushed: amending rewrites history (then force push with F)",
+            Style::default().fg(t.red),
+        )
+    } else {
+        Span::styled(
+            "  Staged changes are folded into HEAD",
+            Style::default()
+                .fg(t.text_muted)
+                .add_modifier(Modifier::ITALIC),
+        )
+    };
+    frame.render_widget(Paragraph::new(Line::from(note)), chunks[3]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" amend (empty keeps message)  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[5]);
+}
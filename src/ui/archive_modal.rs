@@ -0,0 +1,87 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::ArchivedWorktrees { entries, selected } = &app.state else {
+        return;
+    };
+    let selected = *selected;
+
+    let area = centered_rect(60, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Archived Worktrees ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Count
+        Constraint::Length(1), // Spacing
+        Constraint::Min(1),    // Entries
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let count = Paragraph::new(Line::from(Span::styled(
+        format!("{} archived worktree(s), newest first", entries.len()),
+        Style::default().fg(t.text_muted),
+    )));
+    frame.render_widget(count, chunks[0]);
+
+    let items: Vec<ListItem> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let (name_style, detail_style) = if i == selected {
+                let style = Style::default()
+                    .fg(t.selection_bg)
+                    .bg(t.cyan)
+                    .add_modifier(Modifier::BOLD);
+                (style, style)
+            } else {
+                (
+                    Style::default().fg(t.text_primary),
+                    Style::default().fg(t.text_muted),
+                )
+            };
+            let mut spans = vec![
+                Span::styled(format!("  {}", entry.name()), name_style),
+                Span::styled(format!("  {}", entry.branch), detail_style),
+            ];
+            if let Some(note) = &entry.note {
+                spans.push(Span::styled(format!("  {}", note), detail_style));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let mut list_state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(List::new(items), chunks[2], &mut list_state);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" navigate  "),
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" restore  "),
+        Span::styled("d", Style::default().fg(t.red)),
+        Span::raw(" drop entry (keeps branch)  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" close"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[3]);
+}
@@ -0,0 +1,72 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::{AppState, BaseUpdateOutcome};
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let updates = match &app.state {
+        AppState::BaseUpdateSummary { updates } => updates.as_slice(),
+        _ => &[],
+    };
+
+    // min: one row per base + spacing + help + 2 border
+    let min_height = updates.len().max(1) as u16 + 5;
+    let area = centered_rect_with_min(64, 30, min_height, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Update Base Branches ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1),                           // Spacing
+        Constraint::Length(updates.len().max(1) as u16), // One row per base
+        Constraint::Min(1),                              // Spacing
+        Constraint::Length(1),                           // Help
+    ])
+    .split(inner);
+
+    let lines: Vec<Line> = if updates.is_empty() {
+        vec![Line::from(Span::styled(
+            "No base branches to update",
+            Style::default().fg(t.text_muted),
+        ))]
+    } else {
+        updates
+            .iter()
+            .map(|update| {
+                let color = match update.outcome {
+                    BaseUpdateOutcome::FastForwarded { .. } | BaseUpdateOutcome::Pulled { .. } => {
+                        t.accent
+                    }
+                    BaseUpdateOutcome::UpToDate => t.text_muted,
+                    BaseUpdateOutcome::Skipped(_) => t.amber,
+                };
+                Line::from(vec![
+                    Span::styled(format!(" {}: ", update.branch), Style::default().fg(t.cyan)),
+                    Span::styled(update.summary(), Style::default().fg(color)),
+                ])
+            })
+            .collect()
+    };
+    frame.render_widget(Paragraph::new(lines), chunks[1]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("any key", Style::default().fg(t.cyan)),
+        Span::raw(" close"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[3]);
+}
@@ -0,0 +1,103 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::AddTemplateFields {
+        branch_type,
+        values,
+    } = &app.state
+    else {
+        return;
+    };
+    let Some(branch_type) = app.config.branch_types.get(*branch_type) else {
+        return;
+    };
+    let fields = branch_type.template_fields();
+    let Some(field) = fields.get(values.len()) else {
+        return;
+    };
+
+    // min: 6 inner rows + 2 border = 8
+    let area = centered_rect_with_min(64, 30, 8, frame.area());
+    frame.render_widget(Clear, area);
+
+    let title = if branch_type.name.is_empty() {
+        format!(" New {} Branch ", branch_type.prefix)
+    } else {
+        format!(" New {} Branch ", branch_type.name)
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Template
+        Constraint::Length(1), // Name so far
+        Constraint::Length(1), // Spacing
+        Constraint::Length(1), // Label + Input
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let template = Paragraph::new(Line::from(Span::styled(
+        format!(
+            "  Template: {}",
+            branch_type.name_template.as_deref().unwrap_or_default()
+        ),
+        Style::default()
+            .fg(t.text_muted)
+            .add_modifier(Modifier::ITALIC),
+    )));
+    frame.render_widget(template, chunks[0]);
+
+    // Answered values plus what is being typed, the rest left as {variable}
+    let preview = branch_type.render_name(|name| {
+        let index = fields.iter().position(|field| field == name)?;
+        match index.cmp(&values.len()) {
+            std::cmp::Ordering::Less => Some(values[index].clone()),
+            std::cmp::Ordering::Equal if !app.input_buffer.trim().is_empty() => {
+                Some(app.input_buffer.clone())
+            }
+            _ => None,
+        }
+    });
+    let name = Paragraph::new(Line::from(vec![
+        Span::styled("  Name: ", Style::default().fg(t.text_muted)),
+        Span::styled(preview, Style::default().fg(t.text_primary)),
+    ]));
+    frame.render_widget(name, chunks[1]);
+
+    let input_display = format!("[{}█]", app.input_buffer);
+    let label_input = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!("{} ({}/{}): ", field, values.len() + 1, fields.len()),
+            Style::default().fg(t.text_primary),
+        ),
+        Span::styled(input_display, Style::default().fg(t.amber)),
+    ]));
+    frame.render_widget(label_input, chunks[3]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" next  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" type the name yourself"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[5]);
+}
@@ -0,0 +1,95 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let (worktree_path, files, selected) = match &app.state {
+        AppState::ChangedFiles {
+            worktree_path,
+            files,
+            selected,
+        } => (worktree_path, files, *selected),
+        _ => return,
+    };
+
+    let area = centered_rect(60, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Changed Files ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Worktree
+        Constraint::Length(1), // Spacing
+        Constraint::Min(1),    // File list
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let info = Paragraph::new(Line::from(vec![
+        Span::styled("Worktree: ", Style::default().fg(t.text_muted)),
+        Span::styled(
+            worktree_path.display().to_string(),
+            Style::default().fg(t.amber),
+        ),
+    ]));
+    frame.render_widget(info, chunks[0]);
+
+    let items: Vec<ListItem> = files
+        .iter()
+        .enumerate()
+        .map(|(i, file)| {
+            let status_color = if file.status == "??" {
+                t.text_muted
+            } else if file.is_deleted() {
+                t.red
+            } else {
+                t.accent
+            };
+            let (status_style, path_style) = if i == selected {
+                let style = Style::default()
+                    .fg(t.selection_bg)
+                    .bg(t.cyan)
+                    .add_modifier(Modifier::BOLD);
+                (style, style)
+            } else {
+                (
+                    Style::default().fg(status_color),
+                    Style::default().fg(t.text_primary),
+                )
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {} ", file.status), status_style),
+                Span::styled(format!(" {}", file.path), path_style),
+            ]))
+        })
+        .collect();
+
+    let mut list_state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(List::new(items), chunks[2], &mut list_state);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" navigate  "),
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" view  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" close"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[3]);
+}
@@ -0,0 +1,92 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let target_dir = match &app.state {
+        AppState::CloneModal { target_dir } => target_dir.display().to_string(),
+        _ => String::new(),
+    };
+
+    // min: 7 inner rows + 2 border = 9
+    let area = centered_rect_with_min(64, 34, 9, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Clone Repository ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Not a repository
+        Constraint::Length(1), // Spacing
+        Constraint::Length(1), // Label + Input
+        Constraint::Length(1), // Destination
+        Constraint::Length(1), // Progress or message
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let intro = Paragraph::new(Line::from(vec![Span::styled(
+        format!("{} is not a git repository", target_dir),
+        Style::default().fg(t.text_primary),
+    )]));
+    frame.render_widget(intro, chunks[0]);
+
+    let input_display = format!("[{}█]", app.input_buffer);
+    let label_input = Paragraph::new(Line::from(vec![
+        Span::styled("Clone URL: ", Style::default().fg(t.text_primary)),
+        Span::styled(input_display, Style::default().fg(t.amber)),
+    ]));
+    frame.render_widget(label_input, chunks[2]);
+
+    let destination = Paragraph::new(Line::from(vec![Span::styled(
+        "  creates <name>/.bare and <name>/main here, like owt clone",
+        Style::default()
+            .fg(t.text_muted)
+            .add_modifier(Modifier::ITALIC),
+    )]));
+    frame.render_widget(destination, chunks[3]);
+
+    let status_line = if app.clone_receiver.is_some() {
+        let spinner = app.spinner.frame();
+        let progress = match app.clone_progress {
+            Some(ref progress) => format!(" {}: {}%", progress.phase, progress.percent),
+            None => " Cloning...".to_string(),
+        };
+        Some(Line::from(vec![
+            Span::styled(spinner, Style::default().fg(t.amber)),
+            Span::styled(progress, Style::default().fg(t.amber)),
+        ]))
+    } else {
+        app.message.as_ref().map(|msg| {
+            let color = if msg.is_error { t.red } else { t.accent };
+            Line::from(Span::styled(&msg.text, Style::default().fg(color)))
+        })
+    };
+    if let Some(line) = status_line {
+        frame.render_widget(Paragraph::new(line), chunks[4]);
+    }
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" clone  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" quit"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[6]);
+}
@@ -0,0 +1,78 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+use crate::command_log;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::CommandLog { scroll, follow } = app.state else {
+        return;
+    };
+
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Command Log ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(1),    // Commands and output
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    // Following keeps the newest command in view
+    let log = command_log::lines();
+    let height = chunks[0].height as usize;
+    let start = if follow {
+        log.len().saturating_sub(height)
+    } else {
+        (scroll as usize).min(log.len().saturating_sub(1))
+    };
+    let lines: Vec<Line> = log
+        .iter()
+        .skip(start)
+        .take(height)
+        .map(|line| {
+            let style = if !line.starts_with('[') {
+                Style::default().fg(t.text_primary)
+            } else if line.contains("] exit 0 $ ") {
+                Style::default().fg(t.green)
+            } else {
+                Style::default().fg(t.red)
+            };
+            Line::from(Span::styled(line.as_str(), style))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" scroll  "),
+        Span::styled("d/u", Style::default().fg(t.cyan)),
+        Span::raw(" page  "),
+        Span::styled("g/G", Style::default().fg(t.cyan)),
+        Span::raw(if follow {
+            " top/following  "
+        } else {
+            " top/follow  "
+        }),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" close"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[1]);
+}
@@ -177,6 +177,7 @@ pub fn render(frame: &mut Frame, app: &App) {
     frame.render_widget(help, chunks[13]);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_config_item(
     frame: &mut Frame,
     area: Rect,
@@ -12,15 +12,17 @@ use crate::types::{AppState, WorktreeStatus};
 
 pub fn render(frame: &mut Frame, app: &App) {
     let t = &app.theme;
-    let (delete_branch, force) = match app.state {
+    let (delete_branch, force, delete_remote, keep_nested) = match app.state {
         AppState::ConfirmDelete {
             delete_branch,
             force,
-        } => (delete_branch, force),
-        _ => (false, false),
+            delete_remote,
+            keep_nested,
+        } => (delete_branch, force, delete_remote, keep_nested),
+        _ => (false, false, false, false),
     };
 
-    let area = centered_rect(55, 40, frame.area());
+    let area = centered_rect(55, 45, frame.area());
 
     // Clear the background
     frame.render_widget(Clear, area);
@@ -41,6 +43,8 @@ pub fn render(frame: &mut Frame, app: &App) {
         Constraint::Length(1), // Branch
         Constraint::Length(1), // Delete branch option
         Constraint::Length(1), // Force delete option
+        Constraint::Length(1), // Delete remote branch option
+        Constraint::Length(1), // Keep nested repositories option
         Constraint::Length(1), // Status warning
         Constraint::Min(1),    // Spacing
         Constraint::Length(1), // Help
@@ -122,6 +126,42 @@ pub fn render(frame: &mut Frame, app: &App) {
         ]));
         frame.render_widget(force_opt, chunks[6]);
 
+        // Delete remote branch option (protected branches are always kept)
+        let protected = app.protected_delete_branches();
+        let remote_checkbox = if delete_remote { "[x]" } else { "[ ]" };
+        let remote_color = if delete_remote { t.red } else { t.text_muted };
+        let mut remote_spans = vec![
+            Span::styled(remote_checkbox, Style::default().fg(remote_color)),
+            Span::raw(" Also delete remote branch"),
+        ];
+        if !protected.is_empty() {
+            remote_spans.push(Span::styled(
+                format!(" (keeps protected {})", protected.join(", ")),
+                Style::default().fg(t.amber),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(remote_spans)), chunks[7]);
+
+        // Nested git repositories are deleted with the worktree unless kept
+        if !app.delete_nested_repos.is_empty() {
+            let nested = app.delete_nested_repos.join(", ");
+            let nested_line = if keep_nested {
+                Line::from(vec![
+                    Span::styled("[x]", Style::default().fg(t.accent)),
+                    Span::raw(format!(" Keep nested repos in .owt/kept/: {}", nested)),
+                ])
+            } else {
+                Line::from(vec![
+                    Span::styled("[ ]", Style::default().fg(t.text_muted)),
+                    Span::styled(
+                        format!(" Keep nested repos (deleted too: {})", nested),
+                        Style::default().fg(t.amber),
+                    ),
+                ])
+            };
+            frame.render_widget(Paragraph::new(nested_line), chunks[8]);
+        }
+
         // Status warning
         if is_dirty {
             let warning_text = if force {
@@ -133,12 +173,12 @@ pub fn render(frame: &mut Frame, app: &App) {
                 warning_text,
                 Style::default().fg(if force { t.red } else { t.amber }),
             )]));
-            frame.render_widget(warning, chunks[7]);
+            frame.render_widget(warning, chunks[9]);
         }
     }
 
     // Help text
-    let help = Paragraph::new(Line::from(vec![
+    let mut help_spans = vec![
         Span::styled("y", Style::default().fg(t.red)),
         Span::raw(" yes  "),
         Span::styled("n", Style::default().fg(t.cyan)),
@@ -147,9 +187,17 @@ pub fn render(frame: &mut Frame, app: &App) {
         Span::raw(" branch  "),
         Span::styled("f", Style::default().fg(t.red)),
         Span::raw(" force  "),
-        Span::styled("Esc", Style::default().fg(t.cyan)),
-        Span::raw(" cancel"),
-    ]))
-    .style(Style::default().fg(t.text_muted));
-    frame.render_widget(help, chunks[9]);
+        Span::styled("r", Style::default().fg(t.red)),
+        Span::raw(" remote  "),
+        Span::styled("a", Style::default().fg(t.cyan)),
+        Span::raw(" archive  "),
+    ];
+    if !app.delete_nested_repos.is_empty() {
+        help_spans.push(Span::styled("k", Style::default().fg(t.amber)));
+        help_spans.push(Span::raw(" keep nested  "));
+    }
+    help_spans.push(Span::styled("Esc", Style::default().fg(t.cyan)));
+    help_spans.push(Span::raw(" cancel"));
+    let help = Paragraph::new(Line::from(help_spans)).style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[11]);
 }
@@ -0,0 +1,74 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    // Branch descriptions live in git config, worktree notes in .owt/notes.toml
+    let (title, label, target) = match &app.state {
+        AppState::DescribeModal { branch } => (
+            " Branch Description ",
+            "Description: ",
+            format!("  stored as branch.{}.description", branch),
+        ),
+        AppState::NoteModal { name } => (
+            " Worktree Note ",
+            "Note: ",
+            format!("  stored for {} in .owt/notes.toml", name),
+        ),
+        _ => return,
+    };
+
+    // min: 5 inner rows + 2 border = 7
+    let area = centered_rect_with_min(64, 25, 7, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Spacing
+        Constraint::Length(1), // Label + Input
+        Constraint::Length(1), // Branch
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let input_display = format!("[{}█]", app.input_buffer);
+    let label_input = Paragraph::new(Line::from(vec![
+        Span::styled(label, Style::default().fg(t.text_primary)),
+        Span::styled(input_display, Style::default().fg(t.amber)),
+    ]));
+    frame.render_widget(label_input, chunks[1]);
+
+    let target = Paragraph::new(Line::from(vec![Span::styled(
+        target,
+        Style::default()
+            .fg(t.text_muted)
+            .add_modifier(Modifier::ITALIC),
+    )]));
+    frame.render_widget(target, chunks[2]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" save (empty clears)  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[4]);
+}
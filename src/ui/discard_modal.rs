@@ -0,0 +1,81 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::DiscardModal {
+        name, file_count, ..
+    } = &app.state
+    else {
+        return;
+    };
+
+    // min: 6 inner rows + 2 border = 8
+    let area = centered_rect_with_min(64, 30, 8, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Discard Changes ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.red));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // What goes
+        Constraint::Length(1), // Cannot be undone
+        Constraint::Length(1), // Spacing
+        Constraint::Length(1), // Label + Input
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let mode = app.config.get_discard_mode();
+    let summary = Paragraph::new(Line::from(vec![
+        Span::styled("Removes ", Style::default().fg(t.text_primary)),
+        Span::styled(mode.description(), Style::default().fg(t.red)),
+        Span::styled(
+            format!(" in {} ({} changed files)", name, file_count),
+            Style::default().fg(t.text_primary),
+        ),
+    ]));
+    frame.render_widget(summary, chunks[0]);
+
+    let warning = Paragraph::new(Line::from(Span::styled(
+        "This cannot be undone.",
+        Style::default()
+            .fg(t.text_muted)
+            .add_modifier(Modifier::ITALIC),
+    )));
+    frame.render_widget(warning, chunks[1]);
+
+    let input_display = format!("[{}█]", app.input_buffer);
+    let label_input = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!("Type {} to confirm: ", name),
+            Style::default().fg(t.text_primary),
+        ),
+        Span::styled(input_display, Style::default().fg(t.amber)),
+    ]));
+    frame.render_widget(label_input, chunks[3]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" discard  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[5]);
+}
@@ -0,0 +1,99 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::{AppState, WorktreeStatus};
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::DuplicateWorktree { commit, new_branch } = &app.state else {
+        return;
+    };
+    let Some(wt) = app.selected_worktree() else {
+        return;
+    };
+    let new_branch = *new_branch && wt.branch.is_some();
+    let (branch, target) = app.duplicate_target(wt, new_branch);
+
+    // min: 6 inner rows + 2 border = 8
+    let area = centered_rect_with_min(64, 30, 8, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Duplicate Worktree ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Source
+        Constraint::Length(1), // Checkout
+        Constraint::Length(1), // Path
+        Constraint::Length(1), // Uncommitted changes
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let short: String = commit.chars().take(7).collect();
+    let source = Paragraph::new(Line::from(vec![
+        Span::styled("Copy ", Style::default().fg(t.text_primary)),
+        Span::styled(wt.display_name(), Style::default().fg(t.cyan)),
+        Span::styled(
+            format!(" at {}", short),
+            Style::default().fg(t.text_primary),
+        ),
+    ]));
+    frame.render_widget(source, chunks[0]);
+
+    let checkout = match &branch {
+        Some(branch) => format!("  On new branch {}", branch),
+        None => "  Detached HEAD (snapshot)".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(Span::styled(checkout, Style::default().fg(t.text_muted))),
+        chunks[1],
+    );
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            format!("  Path: {}", target.display()),
+            Style::default().fg(t.text_muted),
+        )),
+        chunks[2],
+    );
+
+    if wt.status != WorktreeStatus::Clean {
+        let note = Paragraph::new(Span::styled(
+            "  Uncommitted changes are not copied",
+            Style::default().fg(t.amber).add_modifier(Modifier::ITALIC),
+        ));
+        frame.render_widget(note, chunks[3]);
+    }
+
+    let mut help = vec![
+        Span::styled("y/Enter", Style::default().fg(t.cyan)),
+        Span::raw(" create  "),
+    ];
+    if wt.branch.is_some() {
+        help.push(Span::styled("b", Style::default().fg(t.cyan)));
+        help.push(Span::raw(if new_branch {
+            " detach instead  "
+        } else {
+            " new branch  "
+        }));
+    }
+    help.push(Span::styled("Esc", Style::default().fg(t.cyan)));
+    help.push(Span::raw(" cancel"));
+    frame.render_widget(
+        Paragraph::new(Line::from(help)).style(Style::default().fg(t.text_muted)),
+        chunks[5],
+    );
+}
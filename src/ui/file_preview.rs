@@ -0,0 +1,97 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::FilePreview {
+        worktree_path,
+        files,
+        selected,
+        scroll,
+    } = &app.state
+    else {
+        return;
+    };
+
+    let area = centered_rect(90, 90, frame.area());
+    frame.render_widget(Clear, area);
+
+    let Some(preview) = &app.file_preview else {
+        let path = files
+            .get(*selected)
+            .map(|file| worktree_path.join(&file.path))
+            .unwrap_or_else(|| worktree_path.clone());
+        let block = Block::default()
+            .title(format!(" {} (read-only) ", path.display()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.cyan));
+        let loading = Paragraph::new("Loading...")
+            .style(Style::default().fg(t.text_muted))
+            .block(block);
+        frame.render_widget(loading, area);
+        return;
+    };
+
+    let block = Block::default()
+        .title(format!(" {} (read-only) ", preview.path.display()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(1),    // File content
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    // Line numbers sized for the longest one
+    let number_width = preview.lines.len().to_string().len();
+    let lines: Vec<Line> = preview
+        .lines
+        .iter()
+        .enumerate()
+        .skip(*scroll as usize)
+        .take(chunks[0].height as usize)
+        .map(|(i, line)| {
+            let mut spans = vec![Span::styled(
+                format!("{:>width$} ", i + 1, width = number_width),
+                Style::default().fg(t.text_muted),
+            )];
+            spans.extend(line.spans.iter().cloned());
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" scroll  "),
+        Span::styled("^d/^u", Style::default().fg(t.cyan)),
+        Span::raw(" half page  "),
+        Span::styled("g/G", Style::default().fg(t.cyan)),
+        Span::raw(" top/bottom  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" back"),
+        Span::styled(
+            format!(
+                "  {}/{}",
+                (*scroll as usize + 1).min(preview.lines.len()),
+                preview.lines.len()
+            ),
+            Style::default().fg(t.text_muted),
+        ),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[1]);
+}
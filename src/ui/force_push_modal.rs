@@ -0,0 +1,76 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let area = centered_rect(55, 30, frame.area());
+
+    // Clear the background
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Force Push ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.red));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Spacing
+        Constraint::Length(1), // Question
+        Constraint::Length(1), // Spacing
+        Constraint::Length(1), // Worktree name
+        Constraint::Length(1), // Branch
+        Constraint::Length(1), // Lease note
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let question = Paragraph::new(Line::from(vec![Span::styled(
+        "Force push this branch with --force-with-lease?",
+        Style::default().fg(t.text_primary),
+    )]));
+    frame.render_widget(question, chunks[1]);
+
+    if let Some(wt) = app.selected_worktree() {
+        let name = Paragraph::new(Line::from(vec![
+            Span::styled("Name: ", Style::default().fg(t.text_muted)),
+            Span::styled(wt.display_name(), Style::default().fg(t.text_primary)),
+        ]));
+        frame.render_widget(name, chunks[3]);
+
+        let branch = Paragraph::new(Line::from(vec![
+            Span::styled("Branch: ", Style::default().fg(t.text_muted)),
+            Span::styled(wt.branch_display(), Style::default().fg(t.cyan)),
+        ]));
+        frame.render_widget(branch, chunks[4]);
+    }
+
+    let note = Paragraph::new(Line::from(vec![Span::styled(
+        "Rewrites the remote branch unless it moved since your last fetch.",
+        Style::default().fg(t.amber),
+    )]));
+    frame.render_widget(note, chunks[5]);
+
+    // Help text
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("y", Style::default().fg(t.red)),
+        Span::raw(" force push  "),
+        Span::styled("n", Style::default().fg(t.cyan)),
+        Span::raw(" no  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[7]);
+}
@@ -0,0 +1,168 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::types::{AppState, LogCommit};
+
+/// Full-screen log of the selected worktree, or the commit opened from it
+pub fn render(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    frame.render_widget(Clear, area);
+
+    match &app.state {
+        AppState::GitLog {
+            worktree_path,
+            commits,
+            selected,
+        } => {
+            let title = format!(" Log: {} ", worktree_path.display());
+            render_log(frame, app, area, &title, commits, *selected);
+        }
+        AppState::CommitDetail {
+            commits,
+            selected,
+            scroll,
+            ..
+        } => {
+            let hash = commits.get(*selected).map_or("", |commit| &commit.hash);
+            render_detail(frame, app, area, hash, *scroll);
+        }
+        _ => {}
+    }
+}
+
+fn render_log(
+    frame: &mut Frame,
+    app: &App,
+    area: Rect,
+    title: &str,
+    commits: &[LogCommit],
+    selected: usize,
+) {
+    let t = &app.theme;
+    let block = Block::default()
+        .title(title.to_string())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(1),    // Commits
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    // Authors padded to the longest one so subjects line up
+    let author_width = commits
+        .iter()
+        .map(|commit| commit.author.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(20);
+    let items: Vec<ListItem> = commits
+        .iter()
+        .enumerate()
+        .map(|(i, commit)| {
+            let author: String = commit.author.chars().take(author_width).collect();
+            let styles = if i == selected {
+                let style = Style::default()
+                    .fg(t.selection_bg)
+                    .bg(t.cyan)
+                    .add_modifier(Modifier::BOLD);
+                [style; 4]
+            } else {
+                [
+                    Style::default().fg(t.amber),
+                    Style::default().fg(t.text_muted),
+                    Style::default().fg(t.cyan),
+                    Style::default().fg(t.text_primary),
+                ]
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", commit.hash), styles[0]),
+                Span::styled(format!("{} ", commit.date), styles[1]),
+                Span::styled(
+                    format!("{:<width$} ", author, width = author_width),
+                    styles[2],
+                ),
+                Span::styled(commit.subject.clone(), styles[3]),
+            ]))
+        })
+        .collect();
+
+    let mut list_state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(List::new(items), chunks[0], &mut list_state);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" navigate  "),
+        Span::styled("g/G", Style::default().fg(t.cyan)),
+        Span::raw(" top/bottom  "),
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" show commit  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" close"),
+        Span::styled(
+            format!("  {}/{}", selected + 1, commits.len()),
+            Style::default().fg(t.text_muted),
+        ),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[1]);
+}
+
+fn render_detail(frame: &mut Frame, app: &App, area: Rect, hash: &str, scroll: u16) {
+    let t = &app.theme;
+    let block = Block::default()
+        .title(format!(" Commit {} ", hash))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(1),    // Message and stat
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let lines: Vec<Line> = app
+        .commit_detail
+        .iter()
+        .skip(scroll as usize)
+        .take(chunks[0].height as usize)
+        .map(|line| {
+            let color = if line.starts_with("commit ") {
+                t.amber
+            } else if line.starts_with("Author")
+                || line.starts_with("Commit")
+                || line.contains(" changed, ")
+            {
+                t.text_muted
+            } else {
+                t.text_primary
+            };
+            Line::styled(line.clone(), Style::default().fg(color))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" scroll  "),
+        Span::styled("^d/^u", Style::default().fg(t.cyan)),
+        Span::raw(" half page  "),
+        Span::styled("g/G", Style::default().fg(t.cyan)),
+        Span::raw(" top/bottom  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" back to log"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[1]);
+}
@@ -7,6 +7,7 @@ use ratatui::{
 
 use super::theme::centered_rect;
 use crate::app::App;
+use crate::metadata::ALL_BADGES;
 
 pub fn render(frame: &mut Frame, app: &App) {
     let t = &app.theme;
@@ -34,8 +35,11 @@ pub fn render(frame: &mut Frame, app: &App) {
                 ("Ctrl+d/u", "Half page down/up"),
                 ("g", "Jump to current worktree"),
                 ("/", "Search worktrees"),
+                ("Tab", "Show all/dirty/clean/ahead-behind"),
+                ("h", "Hide or dim rows a filter excludes"),
                 ("Space", "Select/unselect worktree"),
                 ("Enter", "Enter worktree (cd)"),
+                ("1-9", "Jump to numbered row (again: enter)"),
             ],
         ),
         (
@@ -43,9 +47,22 @@ pub fn render(frame: &mut Frame, app: &App) {
             vec![
                 ("a", "Add new worktree"),
                 ("d", "Delete selected worktree(s)"),
-                ("x", "Prune stale worktrees"),
+                ("R", "Rename branch"),
+                ("D", "Edit branch description"),
+                ("n", "Edit worktree note"),
+                ("x", "Prune stale worktrees (confirm)"),
+                ("Ctrl+r", "Repair moved worktree links"),
+                ("Esc", "Cancel running fetch/pull/push/merge"),
+                ("@", "Command log (git commands owt ran)"),
+                ("C", "Clean worktrees merged into main"),
+                ("O", "Branches without a worktree"),
+                ("Y", "Duplicate worktree at HEAD"),
+                ("K", "Archived worktrees (Enter restores)"),
+                ("E", "Browse remote branches (Enter adds)"),
                 ("r", "Refresh list"),
-                ("s", "Sort (name/recent/status)"),
+                ("s", "Sort (name/recent/frecency/status/remote)"),
+                ("S", "Reverse sort direction"),
+                ("*", "Pin/unpin worktree to the top"),
             ],
         ),
         (
@@ -54,19 +71,40 @@ pub fn render(frame: &mut Frame, app: &App) {
                 ("f", "Fetch remotes"),
                 ("p", "Pull selected worktree(s)"),
                 ("P", "Push to remote"),
+                ("F", "Force push with lease"),
+                ("A", "Amend last commit"),
+                ("X", "Discard all changes (type name to confirm)"),
                 ("m", "Merge upstream"),
                 ("M", "Merge branch (select)"),
+                ("B", "Set upstream branch (select)"),
+                ("U", "Update base branches"),
+                ("Z", "Ship: push, open PR, mark review, go to main"),
             ],
         ),
         (
             "External Apps",
-            vec![("o", "Open in editor"), ("t", "Open in terminal")],
+            vec![
+                ("o", "Open in editor"),
+                ("t", "Open in terminal"),
+                ("l", "Open git UI (lazygit)"),
+                ("W", "Open in tmux window"),
+                ("e", "View changed files (read-only)"),
+                ("V", "Browse git log"),
+                ("H", "Run setup script on selected"),
+                ("T", "Run a task on selected"),
+                ("!", "Run a shell command on selected"),
+                ("L", "Show script output"),
+            ],
         ),
         (
             "Other",
             vec![
                 ("y", "Copy path to clipboard"),
                 ("v", "Toggle verbose mode"),
+                ("z", "Toggle disk usage column"),
+                ("b", "Toggle upstream column"),
+                ("i", "Toggle HEAD commit column"),
+                ("w", "Switch to another repository"),
                 ("c", "View config"),
                 ("?", "Show this help"),
                 ("q", "Quit"),
@@ -96,6 +134,24 @@ pub fn render(frame: &mut Frame, app: &App) {
         lines.push(Line::from(""));
     }
 
+    // Badge legend
+    lines.push(Line::from(Span::styled(
+        "  Badges",
+        Style::default().fg(t.amber).add_modifier(Modifier::BOLD),
+    )));
+    lines.push(Line::from(""));
+    for badge in ALL_BADGES {
+        lines.push(Line::from(vec![
+            Span::raw("    "),
+            Span::styled(
+                format!("{:12}", badge.symbol(app.ascii_badges)),
+                Style::default().fg(t.cyan),
+            ),
+            Span::styled(badge.label(), Style::default().fg(t.text_primary)),
+        ]));
+    }
+    lines.push(Line::from(""));
+
     // Help text
     lines.push(Line::from(vec![
         Span::raw("  "),
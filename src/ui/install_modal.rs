@@ -0,0 +1,72 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::ConfirmInstall {
+        worktree_path,
+        command,
+    } = &app.state
+    else {
+        return;
+    };
+    let name = worktree_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    // min: 4 inner rows + 2 border = 6
+    let area = centered_rect_with_min(56, 20, 6, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Install Dependencies ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Question
+        Constraint::Length(1), // Command
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let question = Paragraph::new(Line::from(vec![
+        Span::styled(
+            "Install dependencies in ",
+            Style::default().fg(t.text_primary),
+        ),
+        Span::styled(name, Style::default().fg(t.cyan)),
+        Span::styled("?", Style::default().fg(t.text_primary)),
+    ]));
+    frame.render_widget(question, chunks[0]);
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            format!("  $ {}", command),
+            Style::default().fg(t.amber),
+        )),
+        chunks[1],
+    );
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("y/Enter", Style::default().fg(t.cyan)),
+        Span::raw(" run in background  "),
+        Span::styled("n/Esc", Style::default().fg(t.cyan)),
+        Span::raw(" skip"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[3]);
+}
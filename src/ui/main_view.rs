@@ -1,6 +1,6 @@
 use ratatui::{
     layout::{Constraint, Layout, Margin, Rect},
-    style::{Modifier, Style, Stylize},
+    style::{Color, Modifier, Style, Stylize},
     symbols::border,
     text::{Line, Span},
     widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
@@ -8,11 +8,13 @@ use ratatui::{
 };
 
 use crate::app::App;
-use crate::types::{OpKind, ScriptStatus, SortMode, WorktreeStatus};
+use crate::metadata::Badge;
+use crate::types::{
+    BulkRunStatus, OpKind, ScriptStatus, SortMode, StatusFilter, Worktree, WorktreeStatus,
+};
 use crate::ui::theme::Theme;
 
 // Spinner frames for loading animation
-const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
 pub fn render(frame: &mut Frame, app: &App) {
     let area = frame.area();
@@ -58,7 +60,7 @@ fn render_header(frame: &mut Frame, area: Rect, app: &App) {
     let t = &app.theme;
     let worktree_count = app.worktrees.iter().filter(|w| !w.is_bare).count();
 
-    let header_text = vec![Line::from(vec![
+    let mut header_text = vec![Line::from(vec![
         Span::styled("Worktrees", Style::default().fg(t.text_primary).bold()),
         Span::raw("  "),
         Span::styled(
@@ -71,6 +73,23 @@ fn render_header(frame: &mut Frame, area: Rect, app: &App) {
             Style::default().fg(t.amber),
         ),
     ])];
+    if app.has_filter() {
+        let matching = app
+            .worktrees
+            .iter()
+            .filter(|wt| !wt.is_bare && app.matches_filter(wt))
+            .count();
+        let label = if app.hide_unmatched {
+            "shown"
+        } else {
+            "matching"
+        };
+        header_text[0].spans.push(Span::raw("  "));
+        header_text[0].spans.push(Span::styled(
+            format!("{} {}", matching, label),
+            Style::default().fg(t.accent),
+        ));
+    }
 
     let header = Paragraph::new(header_text);
     frame.render_widget(header, area);
@@ -82,27 +101,35 @@ fn render_table(frame: &mut Frame, area: Rect, app: &App) {
     // Store viewport height for half-page navigation (subtract 1 for header row)
     app.viewport_height.set(area.height.saturating_sub(1));
 
-    let header = Row::new(vec![
+    let mut header_cells = vec![
         Cell::from(""),
         Cell::from("Name").style(Style::default().fg(t.text_muted)),
         Cell::from("Branch").style(Style::default().fg(t.text_muted)),
         Cell::from("Status").style(Style::default().fg(t.text_muted)),
-        Cell::from("PR").style(Style::default().fg(t.text_muted)),
-        Cell::from("Commit").style(Style::default().fg(t.text_muted)),
-    ])
-    .height(1);
+    ];
+    if app.show_upstream {
+        header_cells.push(Cell::from("Upstream").style(Style::default().fg(t.text_muted)));
+    }
+    header_cells.push(Cell::from("PR").style(Style::default().fg(t.text_muted)));
+    if app.show_head_commit {
+        header_cells.push(Cell::from("HEAD").style(Style::default().fg(t.text_muted)));
+    }
+    if app.show_disk_usage {
+        header_cells.push(Cell::from("Size").style(Style::default().fg(t.text_muted)));
+    }
+    header_cells.push(Cell::from("Commit").style(Style::default().fg(t.text_muted)));
+    let header = Row::new(header_cells).height(1);
 
-    // Check if filter matches a worktree
-    let filter_lower = app.filter_text.to_lowercase();
-    let has_filter = !app.filter_text.is_empty();
+    let has_filter = app.has_filter();
 
     // Get current spinner frame
-    let spinner = SPINNER_FRAMES[app.spinner_tick % SPINNER_FRAMES.len()];
+    let spinner = app.spinner.frame();
 
-    let rows: Vec<Row> = app
-        .worktrees
+    let visible = app.visible_indices();
+    let quick_jump = app.quick_jump_targets();
+    let rows: Vec<Row> = visible
         .iter()
-        .enumerate()
+        .map(|&i| (i, &app.worktrees[i]))
         .map(|(i, wt)| {
             let is_selected = i == app.selected_index;
             let is_current = app
@@ -112,13 +139,7 @@ fn render_table(frame: &mut Frame, area: Rect, app: &App) {
                 .unwrap_or(false);
             let is_marked = app.is_worktree_marked(&wt.path);
 
-            // Check if this row matches filter
-            let matches_filter = if has_filter {
-                wt.display_name().to_lowercase().contains(&filter_lower)
-                    || wt.branch_display().to_lowercase().contains(&filter_lower)
-            } else {
-                true
-            };
+            let matches_filter = app.matches_filter(wt);
 
             // Modern indicator: dot for selection, filled dot for current
             let cursor = if is_marked {
@@ -138,6 +159,12 @@ fn render_table(frame: &mut Frame, area: Rect, app: &App) {
             } else {
                 t.text_muted
             };
+            // 1-9 shortcut of the first rows in view
+            let number = quick_jump
+                .iter()
+                .position(|&target| target == i)
+                .map(|position| (position + 1).to_string())
+                .unwrap_or_default();
 
             let status_color = match wt.status {
                 WorktreeStatus::Clean => t.accent,
@@ -176,6 +203,16 @@ fn render_table(frame: &mut Frame, area: Rect, app: &App) {
                 })
                 .unwrap_or(false);
 
+            // A setup script, task, or install running in this worktree
+            let running_script = match &app.script_status {
+                ScriptStatus::Running {
+                    worktree_path,
+                    label,
+                    ..
+                } if *worktree_path == wt.path => Some(label),
+                _ => None,
+            };
+
             let (last_commit, last_commit_style) = if is_op_target {
                 let op = app.active_op_info.as_ref().unwrap();
                 let label = match &op.kind {
@@ -185,13 +222,44 @@ fn render_table(frame: &mut Frame, area: Rect, app: &App) {
                     OpKind::Add => "Adding...",
                     OpKind::Delete => "Deleting...",
                     OpKind::Merge => "Merging...",
+                    OpKind::Rename => "Renaming...",
+                    OpKind::UpdateBases => "Updating...",
+                    OpKind::Ship => "Shipping...",
                 };
                 let color = if op.kind == OpKind::Delete {
                     t.red
                 } else {
                     t.amber
                 };
-                (format!("{} {}", spinner, label), Style::default().fg(color))
+                // A single fetch, pull, or push shows git's progress meter;
+                // with several targets the meter could belong to any of them
+                let progress = app
+                    .op_cancel
+                    .as_ref()
+                    .filter(|_| op.worktree_paths.len() <= 1)
+                    .and_then(|cancel| cancel.progress());
+                match progress {
+                    Some(progress) => (progress, Style::default().fg(color)),
+                    None => (format!("{} {}", spinner, label), Style::default().fg(color)),
+                }
+            } else if let Some(label) = running_script {
+                (
+                    format!("{} {}...", spinner, label),
+                    Style::default().fg(t.amber),
+                )
+            } else if let Some(status) = app.bulk_run.get(&wt.path) {
+                match status {
+                    BulkRunStatus::Running => (
+                        format!("{} Running...", spinner),
+                        Style::default().fg(t.amber),
+                    ),
+                    BulkRunStatus::Succeeded => {
+                        ("✓ done".to_string(), Style::default().fg(t.accent))
+                    }
+                    BulkRunStatus::Failed(reason) => {
+                        (format!("✗ {}", reason), Style::default().fg(t.red))
+                    }
+                }
             } else {
                 (
                     wt.last_commit_time
@@ -214,35 +282,69 @@ fn render_table(frame: &mut Frame, area: Rect, app: &App) {
             let branch_style = if has_filter && !matches_filter {
                 Style::default().fg(t.text_muted)
             } else {
-                Style::default().fg(t.cyan)
+                Style::default().fg(branch_color(app, wt.branch.as_deref()))
             };
 
+            // Characters the `/` filter matched stand out in name and branch
+            let match_style = Style::default()
+                .fg(t.accent)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
             let status_style = if has_filter && !matches_filter {
                 Style::default().fg(t.text_muted)
             } else {
                 Style::default().fg(status_color)
             };
 
-            Row::new(vec![
-                Cell::from(cursor).style(Style::default().fg(cursor_color)),
-                Cell::from(wt.display_name()).style(name_style),
-                Cell::from(wt.branch_display()).style(branch_style),
+            let mut cells = vec![
+                Cell::from(Line::from(vec![
+                    Span::styled(format!("{:>1} ", number), Style::default().fg(t.text_muted)),
+                    Span::styled(cursor, Style::default().fg(cursor_color)),
+                ])),
+                Cell::from(name_with_badges(app, wt, match_style)).style(name_style),
+                Cell::from(branch_with_remote(app, wt, match_style)).style(branch_style),
                 Cell::from(status_text).style(status_style),
-                Cell::from(wt.github_pr_display()).style(Style::default().fg(t.text_muted)),
-                Cell::from(last_commit).style(last_commit_style),
-            ])
-            .style(row_style)
+            ];
+            if app.show_upstream {
+                cells.push(
+                    Cell::from(app.upstream_display(wt)).style(Style::default().fg(t.text_muted)),
+                );
+            }
+            cells.push(Cell::from(wt.github_pr_display()).style(Style::default().fg(t.text_muted)));
+            if app.show_head_commit {
+                cells.push(
+                    Cell::from(app.head_commit_display(wt))
+                        .style(Style::default().fg(t.text_muted)),
+                );
+            }
+            if app.show_disk_usage {
+                cells.push(
+                    Cell::from(disk_usage_display(app, wt))
+                        .style(Style::default().fg(t.text_muted)),
+                );
+            }
+            cells.push(Cell::from(last_commit).style(last_commit_style));
+            Row::new(cells).style(row_style)
         })
         .collect();
 
-    let widths = [
-        Constraint::Length(2),
+    let mut widths = vec![
+        Constraint::Length(4),
         Constraint::Percentage(20),
         Constraint::Percentage(26),
         Constraint::Percentage(20),
-        Constraint::Length(8),
-        Constraint::Percentage(26),
     ];
+    if app.show_upstream {
+        widths.push(Constraint::Percentage(16));
+    }
+    widths.push(Constraint::Length(8));
+    if app.show_head_commit {
+        widths.push(Constraint::Percentage(24));
+    }
+    if app.show_disk_usage {
+        widths.push(Constraint::Length(7));
+    }
+    widths.push(Constraint::Percentage(26));
 
     let table = Table::new(rows, widths)
         .header(header)
@@ -254,9 +356,87 @@ fn render_table(frame: &mut Frame, area: Rect, app: &App) {
         );
 
     // Use StatefulWidget so ratatui handles scroll offset automatically
-    let selected = Some(app.selected_index);
+    let selected = visible.iter().position(|&i| i == app.selected_index);
     let mut table_state = TableState::new().with_selected(selected);
     frame.render_stateful_widget(table, area, &mut table_state);
+    app.table_offset.set(table_state.offset());
+}
+
+fn name_with_badges(app: &App, wt: &Worktree, match_style: Style) -> Line<'static> {
+    let mut spans = highlight_matches(app, &wt.display_name(), match_style);
+    let mut badges = app.metadata.badges(wt);
+    if app.is_merged(wt) {
+        badges.push(Badge::Merged);
+    }
+    for badge in badges {
+        spans.push(Span::raw(format!(" {}", badge.symbol(app.ascii_badges))));
+    }
+    Line::from(spans)
+}
+
+/// `text` split into spans with the characters the `/` filter matched styled
+/// by `match_style`; the rest keeps the cell style.
+fn highlight_matches(app: &App, text: &str, match_style: Style) -> Vec<Span<'static>> {
+    let positions = app.filter_positions(text);
+    if positions.is_empty() {
+        return vec![Span::raw(text.to_string())];
+    }
+
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (index, c) in text.chars().enumerate() {
+        let matched = positions.contains(&index);
+        if matched != run_matched && !run.is_empty() {
+            spans.push(match_span(
+                std::mem::take(&mut run),
+                run_matched,
+                match_style,
+            ));
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(match_span(run, run_matched, match_style));
+    }
+    spans
+}
+
+fn match_span(text: String, matched: bool, match_style: Style) -> Span<'static> {
+    if matched {
+        Span::styled(text, match_style)
+    } else {
+        Span::raw(text)
+    }
+}
+
+/// Cached worktree size, or `...` while it is still being measured
+fn disk_usage_display(app: &App, wt: &Worktree) -> String {
+    if wt.is_bare {
+        return "-".to_string();
+    }
+    match app.disk_usage.get(&wt.path) {
+        Some(bytes) => crate::disk_usage::format_size(*bytes),
+        None => "...".to_string(),
+    }
+}
+
+/// Branch name, suffixed with `@remote` when it tracks a non-default remote
+fn branch_with_remote(app: &App, wt: &Worktree, match_style: Style) -> Line<'static> {
+    let mut spans = highlight_matches(app, &wt.branch_display(), match_style);
+    if let Some(remote) = app.remote_badge(wt) {
+        spans.push(Span::raw(format!(" @{}", remote)));
+    }
+    Line::from(spans)
+}
+
+/// Branch color from its `[[branch_types]]` prefix, falling back to cyan.
+fn branch_color(app: &App, branch: Option<&str>) -> Color {
+    branch
+        .and_then(|branch| app.config.branch_type_color(branch))
+        .and_then(|spec| app.theme.color_from_spec(spec))
+        .unwrap_or(app.theme.cyan)
 }
 
 fn render_details(frame: &mut Frame, area: Rect, app: &App) {
@@ -277,7 +457,10 @@ fn render_details(frame: &mut Frame, area: Rect, app: &App) {
                     Style::default().fg(t.text_primary).bold(),
                 ),
                 Span::styled("  Branch ", Style::default().fg(t.text_muted)),
-                Span::styled(wt.branch_display(), Style::default().fg(t.cyan)),
+                Span::styled(
+                    wt.branch_display(),
+                    Style::default().fg(branch_color(app, wt.branch.as_deref())),
+                ),
             ]));
             lines.push(Line::from(vec![
                 Span::styled("Status ", Style::default().fg(t.text_muted).bold()),
@@ -289,6 +472,25 @@ fn render_details(frame: &mut Frame, area: Rect, app: &App) {
                     Style::default().fg(t.text_primary).bold(),
                 ),
             ]));
+            if let Some(note) = app.metadata.notes.get(&wt.display_name()) {
+                lines.push(Line::from(vec![
+                    Span::styled("Note  ", Style::default().fg(t.text_muted)),
+                    Span::styled(note.clone(), Style::default().fg(t.amber)),
+                ]));
+            }
+            if let Some(description) = app
+                .selected_details
+                .as_ref()
+                .and_then(|details| details.description.as_deref())
+            {
+                for (index, text) in description.lines().take(2).enumerate() {
+                    let label = if index == 0 { "About " } else { "      " };
+                    lines.push(Line::from(vec![
+                        Span::styled(label, Style::default().fg(t.text_muted)),
+                        Span::styled(text.to_string(), Style::default().fg(t.text_secondary)),
+                    ]));
+                }
+            }
             lines.push(Line::from(vec![Span::styled(
                 "Recent commits",
                 Style::default().fg(t.text_muted),
@@ -446,11 +648,17 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
         })
         .collect();
 
-    // Show current sort mode if not default
-    if app.sort_mode != SortMode::Name {
+    // Show current sort mode and direction if not default
+    let show_sort = app.sort_mode != SortMode::Name || app.sort_reversed;
+    if show_sort {
         binding_spans.push(Span::styled("│ ", Style::default().fg(t.border)));
+        binding_spans.push(Span::styled(app.sort_label(), Style::default().fg(t.amber)));
+    }
+    if app.status_filter != StatusFilter::All {
+        let separator = if show_sort { " │ " } else { "│ " };
+        binding_spans.push(Span::styled(separator, Style::default().fg(t.border)));
         binding_spans.push(Span::styled(
-            app.sort_mode.label(),
+            format!("{} only", app.status_filter.label()),
             Style::default().fg(t.amber),
         ));
     }
@@ -466,7 +674,7 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
     };
 
     let footer_content = if let Some(ref op) = app.active_op_info {
-        let spinner = SPINNER_FRAMES[app.spinner_tick % SPINNER_FRAMES.len()];
+        let spinner = app.spinner.frame();
         let label = match &op.kind {
             OpKind::Fetch => "Fetching",
             OpKind::Pull => "Pulling",
@@ -474,6 +682,9 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
             OpKind::Add => "Creating",
             OpKind::Delete => "Deleting",
             OpKind::Merge => "Merging",
+            OpKind::Rename => "Renaming",
+            OpKind::UpdateBases => "Updating",
+            OpKind::Ship => "Shipping",
         };
         vec![
             Line::from(binding_spans),
@@ -483,16 +694,39 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
                     format!(" {} {}...", label, op.display_name),
                     Style::default().fg(t.amber),
                 ),
+                // A new worktree has no row yet to show its LFS download meter
+                Span::styled(
+                    app.op_cancel
+                        .as_ref()
+                        .filter(|_| op.kind == OpKind::Add)
+                        .and_then(|cancel| cancel.progress())
+                        .map(|progress| format!(" {}", progress))
+                        .unwrap_or_default(),
+                    Style::default().fg(t.amber),
+                ),
+                Span::styled(
+                    if app.op_cancel.is_some() {
+                        "  Esc cancel"
+                    } else {
+                        ""
+                    },
+                    Style::default().fg(t.text_muted),
+                ),
             ]),
         ]
-    } else if let ScriptStatus::Running { ref worktree_name } = app.script_status {
-        let spinner = SPINNER_FRAMES[app.spinner_tick % SPINNER_FRAMES.len()];
+    } else if let ScriptStatus::Running {
+        ref worktree_name,
+        ref label,
+        ..
+    } = app.script_status
+    {
+        let spinner = app.spinner.frame();
         let script_line = if let Some(ref msg) = app.message {
             // Show both message and script status
             Line::from(vec![
                 Span::styled(&msg.text, Style::default().fg(t.accent)),
                 Span::styled(
-                    format!("  {} running setup...", spinner),
+                    format!("  {} {} running...", spinner, label),
                     Style::default().fg(t.amber),
                 ),
             ])
@@ -500,7 +734,7 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
             Line::from(vec![
                 Span::styled(spinner, Style::default().fg(t.amber)),
                 Span::styled(
-                    format!(" Running setup script for {}...", worktree_name),
+                    format!(" {} running for {}... (L: output)", label, worktree_name),
                     Style::default().fg(t.amber),
                 ),
             ])
@@ -526,26 +760,6 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
                 Span::styled(" (Esc to clear)", Style::default().fg(t.text_muted)),
             ]),
         ]
-    } else if let Some(ref op) = app.active_op_info {
-        let spinner = SPINNER_FRAMES[app.spinner_tick % SPINNER_FRAMES.len()];
-        let label = match &op.kind {
-            OpKind::Fetch => "Fetching",
-            OpKind::Pull => "Pulling",
-            OpKind::Push => "Pushing",
-            OpKind::Add => "Adding",
-            OpKind::Delete => "Deleting",
-            OpKind::Merge => "Merging",
-        };
-        vec![
-            Line::from(binding_spans),
-            Line::from(vec![
-                Span::styled(spinner, Style::default().fg(t.amber)),
-                Span::styled(
-                    format!(" {} {}...", label, op.display_name),
-                    Style::default().fg(t.amber),
-                ),
-            ]),
-        ]
     } else if let Some(warning) = integration_warning {
         vec![Line::from(binding_spans), Line::from(warning)]
     } else {
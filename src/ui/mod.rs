@@ -1,7 +1,32 @@
 pub mod add_modal;
+pub mod amend_modal;
+pub mod archive_modal;
+pub mod base_update_modal;
+pub mod branch_template_modal;
+pub mod changed_files_modal;
+pub mod clone_modal;
+pub mod command_log_modal;
 pub mod config_modal;
 pub mod confirm_modal;
+pub mod describe_modal;
+pub mod discard_modal;
+pub mod duplicate_modal;
+pub mod file_preview;
+pub mod force_push_modal;
+pub mod git_log_modal;
 pub mod help_modal;
+pub mod install_modal;
 pub mod main_view;
 pub mod merge_modal;
+pub mod orphan_branches_modal;
+pub mod prune_modal;
+pub mod remote_branches_modal;
+pub mod rename_modal;
+pub mod repo_switcher_modal;
+pub mod run_command_modal;
+pub mod script_output_modal;
+pub mod spinner;
+pub mod task_menu_modal;
 pub mod theme;
+pub mod typed_delete_modal;
+pub mod upstream_modal;
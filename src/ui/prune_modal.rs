@@ -0,0 +1,70 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let entries = match &app.state {
+        AppState::ConfirmPrune { entries } => entries.as_slice(),
+        _ => &[],
+    };
+
+    // min: question + one row per entry + spacing + help + 2 border
+    let min_height = entries.len() as u16 + 7;
+    let area = centered_rect_with_min(64, 30, min_height, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Prune Worktrees ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.red));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1),                    // Spacing
+        Constraint::Length(1),                    // Question
+        Constraint::Length(1),                    // Spacing
+        Constraint::Length(entries.len() as u16), // One row per entry
+        Constraint::Min(1),                       // Spacing
+        Constraint::Length(1),                    // Help
+    ])
+    .split(inner);
+
+    let question = Paragraph::new(Line::from(vec![Span::styled(
+        "Remove these stale worktree entries?",
+        Style::default().fg(t.text_primary),
+    )]));
+    frame.render_widget(question, chunks[1]);
+
+    let lines: Vec<Line> = entries
+        .iter()
+        .map(|entry| {
+            Line::from(Span::styled(
+                format!(" {}", entry),
+                Style::default().fg(t.amber),
+            ))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), chunks[3]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("y", Style::default().fg(t.red)),
+        Span::raw(" prune  "),
+        Span::styled("n", Style::default().fg(t.cyan)),
+        Span::raw(" no  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[5]);
+}
@@ -0,0 +1,87 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::RemoteBranches { filter, selected } = &app.state else {
+        return;
+    };
+    let selected = *selected;
+    let branches = app.filtered_remote_branches(filter);
+
+    let area = centered_rect(55, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let title = if app.remote_fetch_receiver.is_some() {
+        " Remote Branches (fetching...) "
+    } else {
+        " Remote Branches "
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Filter
+        Constraint::Length(1), // Spacing
+        Constraint::Min(1),    // Branch list
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let input = Paragraph::new(Line::from(vec![
+        Span::styled("Filter: ", Style::default().fg(t.text_muted)),
+        Span::styled(filter.as_str(), Style::default().fg(t.text_primary)),
+        Span::styled("█", Style::default().fg(t.cyan)),
+        Span::styled(
+            format!("  {}/{}", branches.len(), app.remote_branches.len()),
+            Style::default().fg(t.text_muted),
+        ),
+    ]));
+    frame.render_widget(input, chunks[0]);
+
+    let items: Vec<ListItem> = branches
+        .iter()
+        .enumerate()
+        .map(|(i, branch)| {
+            let style = if i == selected {
+                Style::default()
+                    .fg(t.selection_bg)
+                    .bg(t.cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(t.text_primary)
+            };
+            ListItem::new(Line::from(Span::styled(format!("  {}", branch), style)))
+        })
+        .collect();
+
+    let mut list_state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(List::new(items), chunks[2], &mut list_state);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("↑/↓", Style::default().fg(t.cyan)),
+        Span::raw(" navigate  "),
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" create worktree  "),
+        Span::styled("Ctrl+f", Style::default().fg(t.cyan)),
+        Span::raw(" fetch  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[3]);
+}
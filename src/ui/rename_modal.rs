@@ -0,0 +1,103 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let (propagate, remote) = match &app.state {
+        AppState::RenameModal { propagate, remote } => (*propagate, remote.as_deref()),
+        _ => (false, None),
+    };
+
+    // min: 7 inner rows + 2 border = 9
+    let area = centered_rect_with_min(64, 34, 9, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Rename Branch ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Spacing
+        Constraint::Length(1), // Label + Input
+        Constraint::Length(1), // Current branch
+        Constraint::Length(1), // Worktree move
+        Constraint::Length(1), // Remote option
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let input_display = format!("[{}█]", app.input_buffer);
+    let label_input = Paragraph::new(Line::from(vec![
+        Span::styled("New branch name: ", Style::default().fg(t.text_primary)),
+        Span::styled(input_display, Style::default().fg(t.amber)),
+    ]));
+    frame.render_widget(label_input, chunks[1]);
+
+    if let Some(wt) = app.selected_worktree() {
+        let current = Paragraph::new(Line::from(vec![Span::styled(
+            format!("  from {}", wt.branch_display()),
+            Style::default()
+                .fg(t.text_muted)
+                .add_modifier(Modifier::ITALIC),
+        )]));
+        frame.render_widget(current, chunks[2]);
+
+        let new_branch = app.input_buffer.trim();
+        if let Some(to) = app
+            .renamed_worktree_path(wt, new_branch)
+            .filter(|_| !new_branch.is_empty())
+        {
+            let moved = Paragraph::new(Line::from(vec![Span::styled(
+                format!("  folder moves to {}", to.display()),
+                Style::default().fg(t.text_muted),
+            )]));
+            frame.render_widget(moved, chunks[3]);
+        }
+    }
+
+    let remote_line = match remote {
+        Some(remote) => {
+            let checkbox = if propagate { "[x]" } else { "[ ]" };
+            let color = if propagate { t.amber } else { t.text_muted };
+            Line::from(vec![
+                Span::styled(checkbox, Style::default().fg(color)),
+                Span::raw(format!(
+                    " Push rename to {} (retarget upstream and PR bases, delete old name)",
+                    remote
+                )),
+            ])
+        }
+        None => Line::from(Span::styled(
+            "  Branch is not published; only the local name changes",
+            Style::default().fg(t.text_muted),
+        )),
+    };
+    frame.render_widget(Paragraph::new(remote_line), chunks[4]);
+
+    let mut help_spans = vec![
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" rename  "),
+    ];
+    if remote.is_some() {
+        help_spans.push(Span::styled("Tab", Style::default().fg(t.cyan)));
+        help_spans.push(Span::raw(" remote  "));
+    }
+    help_spans.push(Span::styled("Esc", Style::default().fg(t.cyan)));
+    help_spans.push(Span::raw(" cancel"));
+    let help = Paragraph::new(Line::from(help_spans)).style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[6]);
+}
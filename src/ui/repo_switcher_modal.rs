@@ -0,0 +1,83 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::RepoSwitcher { selected } = app.state else {
+        return;
+    };
+
+    let area = centered_rect(60, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Switch Repository ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Spacing
+        Constraint::Min(1),    // Repository list
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let items: Vec<ListItem> = app
+        .switcher_repositories
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string());
+            let marker = if app.is_current_repository(path) {
+                "● "
+            } else {
+                "  "
+            };
+            let (name_style, path_style) = if i == selected {
+                let style = Style::default()
+                    .fg(t.selection_bg)
+                    .bg(t.cyan)
+                    .add_modifier(Modifier::BOLD);
+                (style, style)
+            } else {
+                (
+                    Style::default().fg(t.text_primary),
+                    Style::default().fg(t.text_muted),
+                )
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{}{}", marker, name), name_style),
+                Span::styled(format!("  {}", path.display()), path_style),
+            ]))
+        })
+        .collect();
+
+    let mut list_state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(List::new(items), chunks[1], &mut list_state);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" navigate  "),
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" open  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[2]);
+}
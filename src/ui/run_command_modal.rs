@@ -0,0 +1,67 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    if app.state != AppState::RunCommand {
+        return;
+    }
+    let targets: Vec<String> = app
+        .action_worktrees()
+        .iter()
+        .filter(|wt| !wt.is_bare)
+        .map(|wt| wt.display_name())
+        .collect();
+
+    // min: 5 inner rows + 2 border = 7
+    let area = centered_rect_with_min(64, 25, 7, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Run Command ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Targets
+        Constraint::Length(1), // Spacing
+        Constraint::Length(1), // Label + Input
+        Constraint::Min(1),    // Spacing
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    let run_in = Paragraph::new(Line::from(vec![
+        Span::styled("Run in: ", Style::default().fg(t.text_muted)),
+        Span::styled(targets.join(", "), Style::default().fg(t.amber)),
+    ]));
+    frame.render_widget(run_in, chunks[0]);
+
+    let input_display = format!("[{}█]", app.input_buffer);
+    let label_input = Paragraph::new(Line::from(vec![
+        Span::styled("$ ", Style::default().fg(t.text_primary)),
+        Span::styled(input_display, Style::default().fg(t.amber)),
+    ]));
+    frame.render_widget(label_input, chunks[2]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" run through sh -c  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[4]);
+}
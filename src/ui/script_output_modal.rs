@@ -0,0 +1,80 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+use crate::types::{AppState, ScriptStatus};
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::ScriptOutput { scroll, follow } = app.state else {
+        return;
+    };
+
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let title = match &app.script_status {
+        ScriptStatus::Running {
+            worktree_name,
+            label,
+            ..
+        } => format!(" {} {}: {} ", app.spinner.frame(), label, worktree_name),
+        ScriptStatus::Idle => " Script Output ".to_string(),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Min(1),    // Output
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    // Following keeps the last page in view as lines arrive
+    let height = chunks[0].height as usize;
+    let total = app.script_output.len();
+    let start = if follow {
+        total.saturating_sub(height)
+    } else {
+        (scroll as usize).min(total.saturating_sub(1))
+    };
+    let lines: Vec<Line> = app
+        .script_output
+        .iter()
+        .skip(start)
+        .take(height)
+        .map(|line| {
+            Line::from(Span::styled(
+                line.as_str(),
+                Style::default().fg(t.text_primary),
+            ))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" scroll  "),
+        Span::styled("g/G", Style::default().fg(t.cyan)),
+        Span::raw(if follow {
+            " top/following  "
+        } else {
+            " top/follow  "
+        }),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" hide (L reopens)"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[1]);
+}
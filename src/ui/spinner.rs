@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// How long each frame shows when `spinner_interval_ms` is not set
+const DEFAULT_INTERVAL_MS: u64 = 100;
+
+/// Frame sets selectable with `spinner = "<name>"`. `none` draws nothing, for
+/// screen readers and terminal recordings.
+pub const SPINNER_STYLES: &[(&str, &[&str])] = &[
+    ("dots", &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]),
+    ("line", &["-", "\\", "|", "/"]),
+    ("arc", &["◜", "◠", "◝", "◞", "◡", "◟"]),
+    ("bounce", &["⠁", "⠂", "⠄", "⠂"]),
+    ("none", &[]),
+];
+
+/// Loading indicator shared by every in-progress state. Frames follow the
+/// wall clock, so the speed does not depend on how often the UI redraws.
+#[derive(Debug, Clone)]
+pub struct Spinner {
+    frames: &'static [&'static str],
+    interval: Duration,
+    started: Instant,
+}
+
+impl Spinner {
+    pub fn from_config(config: &Config) -> Self {
+        let frames = config
+            .spinner
+            .as_deref()
+            .and_then(|name| {
+                SPINNER_STYLES
+                    .iter()
+                    .find(|(style, _)| style.eq_ignore_ascii_case(name))
+            })
+            .map_or(SPINNER_STYLES[0].1, |(_, frames)| *frames);
+        let interval_ms = config
+            .spinner_interval_ms
+            .unwrap_or(DEFAULT_INTERVAL_MS)
+            .max(1);
+        Self {
+            frames,
+            interval: Duration::from_millis(interval_ms),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// The frame to draw now; empty for the `none` style.
+    pub fn frame(&self) -> &'static str {
+        self.frame_at(self.started.elapsed())
+    }
+
+    fn frame_at(&self, elapsed: Duration) -> &'static str {
+        if self.frames.is_empty() {
+            return "";
+        }
+        let step = elapsed.as_millis() / self.interval.as_millis();
+        self.frames[(step % self.frames.len() as u128) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spinner_uses_configured_style_and_interval() {
+        let mut config = Config::default();
+        let dots = Spinner::from_config(&config);
+        assert_eq!(dots.frame_at(Duration::ZERO), "⠋");
+        assert_eq!(dots.frame_at(Duration::from_millis(250)), "⠹");
+
+        config.spinner = Some("Line".to_string());
+        config.spinner_interval_ms = Some(50);
+        let line = Spinner::from_config(&config);
+        assert_eq!(line.frame_at(Duration::from_millis(120)), "|");
+        assert_eq!(line.frame_at(Duration::from_millis(200)), "-");
+
+        config.spinner = Some("none".to_string());
+        assert_eq!(
+            Spinner::from_config(&config).frame_at(Duration::from_secs(3)),
+            ""
+        );
+
+        config.spinner = Some("unknown".to_string());
+        assert_eq!(Spinner::from_config(&config).frames, SPINNER_STYLES[0].1);
+    }
+}
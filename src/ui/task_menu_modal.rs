@@ -0,0 +1,86 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::TaskMenu { selected } = app.state else {
+        return;
+    };
+
+    let area = centered_rect(60, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Run Task ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Target worktree
+        Constraint::Length(1), // Spacing
+        Constraint::Min(1),    // Task list
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    if let Some(wt) = app.selected_worktree() {
+        let info = Paragraph::new(Line::from(vec![
+            Span::styled("Run in: ", Style::default().fg(t.text_muted)),
+            Span::styled(wt.display_name(), Style::default().fg(t.amber)),
+        ]));
+        frame.render_widget(info, chunks[0]);
+    }
+
+    let items: Vec<ListItem> = app
+        .config
+        .tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let (name_style, command_style) = if i == selected {
+                let style = Style::default()
+                    .fg(t.selection_bg)
+                    .bg(t.cyan)
+                    .add_modifier(Modifier::BOLD);
+                (style, style)
+            } else {
+                (
+                    Style::default().fg(t.text_primary),
+                    Style::default().fg(t.text_muted),
+                )
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("  {}", task.name), name_style),
+                Span::styled(format!("  {}", task.command), command_style),
+            ]))
+        })
+        .collect();
+
+    let mut list_state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(List::new(items), chunks[2], &mut list_state);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" navigate  "),
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" run  "),
+        Span::styled("a", Style::default().fg(t.cyan)),
+        Span::raw(" run in all worktrees  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[3]);
+}
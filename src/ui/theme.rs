@@ -1,5 +1,7 @@
 use ratatui::{layout::Rect, style::Color};
 
+use crate::config::Config;
+
 /// All UI colors used throughout the application.
 #[derive(Debug, Clone)]
 pub struct Theme {
@@ -9,12 +11,13 @@ pub struct Theme {
     pub red: Color,
     pub cyan: Color,
     pub green: Color,
+    pub violet: Color,
     pub text_primary: Color,
     pub text_secondary: Color,
     pub text_muted: Color,
-    pub bg_elevated: Color,
     pub border: Color,
     pub selection_bg: Color,
+    pub is_light: bool,
 }
 
 impl Theme {
@@ -26,12 +29,13 @@ impl Theme {
             red: Color::Rgb(239, 68, 68),      // Red
             cyan: Color::Rgb(34, 211, 238),    // Cyan
             green: Color::Rgb(16, 185, 129),   // Same as accent
+            violet: Color::Rgb(192, 132, 252), // Violet
             text_primary: Color::Rgb(250, 250, 250),
             text_secondary: Color::Rgb(161, 161, 170),
             text_muted: Color::Rgb(113, 113, 122),
-            bg_elevated: Color::Rgb(39, 39, 42),
             border: Color::Rgb(63, 63, 70),
             selection_bg: Color::Rgb(6, 95, 70), // Same as accent_dim
+            is_light: false,
         }
     }
 
@@ -43,14 +47,105 @@ impl Theme {
             red: Color::Rgb(185, 28, 28),          // Darker red
             cyan: Color::Rgb(14, 116, 144),        // Darker cyan
             green: Color::Rgb(5, 150, 105),        // Same as accent
+            violet: Color::Rgb(126, 34, 206),      // Darker violet
             text_primary: Color::Rgb(24, 24, 27),  // Near black
             text_secondary: Color::Rgb(82, 82, 91),
             text_muted: Color::Rgb(161, 161, 170),
-            bg_elevated: Color::Rgb(244, 244, 245),
             border: Color::Rgb(212, 212, 216),
             selection_bg: Color::Rgb(209, 250, 229), // Same as accent_dim
+            is_light: true,
         }
     }
+
+    /// Override one named color with a `#rrggbb` value.
+    /// Returns false for unknown names or malformed hex.
+    pub fn set_color(&mut self, name: &str, hex: &str) -> bool {
+        let Some((r, g, b)) = parse_hex_color(hex.trim()) else {
+            return false;
+        };
+        let slot = match name {
+            "accent" => &mut self.accent,
+            "accent_dim" => &mut self.accent_dim,
+            "amber" => &mut self.amber,
+            "red" => &mut self.red,
+            "cyan" => &mut self.cyan,
+            "green" => &mut self.green,
+            "violet" => &mut self.violet,
+            "text_primary" => &mut self.text_primary,
+            "text_secondary" => &mut self.text_secondary,
+            "text_muted" => &mut self.text_muted,
+            "border" => &mut self.border,
+            "selection_bg" => &mut self.selection_bg,
+            _ => return false,
+        };
+        *slot = Color::Rgb(r, g, b);
+        true
+    }
+
+    /// Resolve a user color spec (palette name or `#rrggbb`) against this theme.
+    /// Palette names map to the theme's own shades; hex colors are nudged toward
+    /// the foreground until they stay readable on the terminal background.
+    pub fn color_from_spec(&self, spec: &str) -> Option<Color> {
+        let spec = spec.trim();
+        let named = match spec.to_ascii_lowercase().as_str() {
+            "green" => Some(self.green),
+            "red" => Some(self.red),
+            "amber" | "yellow" | "orange" => Some(self.amber),
+            "cyan" | "blue" => Some(self.cyan),
+            "violet" | "purple" | "magenta" => Some(self.violet),
+            "gray" | "grey" => Some(self.text_secondary),
+            "muted" => Some(self.text_muted),
+            "default" | "white" | "black" => Some(self.text_primary),
+            _ => None,
+        };
+        if named.is_some() {
+            return named;
+        }
+
+        let (r, g, b) = parse_hex_color(spec)?;
+        Some(self.readable_rgb(r, g, b))
+    }
+
+    fn readable_rgb(&self, r: u8, g: u8, b: u8) -> Color {
+        let target: u8 = if self.is_light { 0 } else { 255 };
+        let mut rgb = (r, g, b);
+        // Blend toward the foreground in small steps; a handful is enough to
+        // lift near-background colors without washing out saturated ones.
+        for _ in 0..8 {
+            let luminance = relative_luminance(rgb);
+            let readable = if self.is_light {
+                luminance <= 0.45
+            } else {
+                luminance >= 0.2
+            };
+            if readable {
+                break;
+            }
+            rgb = (
+                blend_channel(rgb.0, target),
+                blend_channel(rgb.1, target),
+                blend_channel(rgb.2, target),
+            );
+        }
+        Color::Rgb(rgb.0, rgb.1, rgb.2)
+    }
+}
+
+fn parse_hex_color(spec: &str) -> Option<(u8, u8, u8)> {
+    let hex = spec.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+    Some((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+fn blend_channel(value: u8, target: u8) -> u8 {
+    ((value as u16 * 3 + target as u16) / 4) as u8
+}
+
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f32 {
+    (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0
 }
 
 /// Detect terminal theme from environment.
@@ -69,6 +164,29 @@ pub fn detect_theme() -> Theme {
     Theme::dark()
 }
 
+/// Theme selected by the `theme` config key.
+/// `custom` starts from the detected palette and applies the `[theme]` overrides;
+/// unknown modes fall back to detection.
+pub fn resolve_theme(config: &Config) -> Theme {
+    match config
+        .theme
+        .as_deref()
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("dark") => Theme::dark(),
+        Some("light") => Theme::light(),
+        Some("custom") => {
+            let mut theme = detect_theme();
+            for (name, hex) in &config.theme_colors {
+                theme.set_color(name, hex);
+            }
+            theme
+        }
+        _ => detect_theme(),
+    }
+}
+
 /// Centered rectangle helper used by all modals.
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     centered_rect_with_min(percent_x, percent_y, 0, r)
@@ -86,3 +204,63 @@ pub fn centered_rect_with_min(percent_x: u16, percent_y: u16, min_height: u16, r
 
     Rect::new(x, y, width, height)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_spec_names_follow_theme_palette() {
+        let dark = Theme::dark();
+        let light = Theme::light();
+
+        assert_eq!(dark.color_from_spec("green"), Some(dark.green));
+        assert_eq!(light.color_from_spec("Violet"), Some(light.violet));
+        assert_eq!(dark.color_from_spec("yellow"), Some(dark.amber));
+        assert_eq!(dark.color_from_spec("chartreuse"), None);
+        assert_eq!(dark.color_from_spec("#12345"), None);
+    }
+
+    #[test]
+    fn resolve_theme_honors_mode_and_custom_overrides() {
+        let mut config = Config::default();
+        config.theme = Some("light".to_string());
+        config
+            .theme_colors
+            .insert("accent".to_string(), "#ff0000".to_string());
+        assert!(resolve_theme(&config).is_light);
+        assert_eq!(resolve_theme(&config).accent, Theme::light().accent);
+
+        config.theme = Some("custom".to_string());
+        config
+            .theme_colors
+            .insert("bogus".to_string(), "#00ff00".to_string());
+        config
+            .theme_colors
+            .insert("border".to_string(), "not-a-color".to_string());
+        let custom = resolve_theme(&config);
+        let base = detect_theme();
+        assert_eq!(custom.accent, Color::Rgb(255, 0, 0));
+        assert_eq!(custom.border, base.border);
+        assert_eq!(custom.is_light, base.is_light);
+    }
+
+    #[test]
+    fn hex_color_spec_is_lifted_for_contrast() {
+        let dark = Theme::dark();
+        let light = Theme::light();
+
+        assert_eq!(
+            dark.color_from_spec("#ff8800"),
+            Some(Color::Rgb(255, 136, 0))
+        );
+        let Some(Color::Rgb(r, g, b)) = dark.color_from_spec("#101010") else {
+            panic!("hex color should resolve");
+        };
+        assert!(relative_luminance((r, g, b)) >= 0.2);
+        let Some(Color::Rgb(r, g, b)) = light.color_from_spec("#fafafa") else {
+            panic!("hex color should resolve");
+        };
+        assert!(relative_luminance((r, g, b)) <= 0.45);
+    }
+}
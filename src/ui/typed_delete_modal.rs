@@ -0,0 +1,94 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect_with_min;
+use crate::app::App;
+use crate::types::AppState;
+
+/// Most losses listed before collapsing the rest into "and N more"
+const MAX_LISTED: usize = 5;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::ConfirmDeleteTyped {
+        expected, at_risk, ..
+    } = &app.state
+    else {
+        return;
+    };
+
+    let listed = at_risk.len().min(MAX_LISTED);
+    let more = at_risk.len() - listed;
+    let list_rows = (listed + usize::from(more > 0)) as u16;
+    // min: list + 5 inner rows + 2 border
+    let area = centered_rect_with_min(64, 30, list_rows + 7, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Force Delete ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.red));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1),         // Header
+        Constraint::Length(list_rows), // What is lost
+        Constraint::Length(1),         // Spacing
+        Constraint::Length(1),         // Label + Input
+        Constraint::Min(1),            // Spacing
+        Constraint::Length(1),         // Help
+    ])
+    .split(inner);
+
+    let header = Paragraph::new(Line::from(Span::styled(
+        "Force delete throws away work that cannot be recovered:",
+        Style::default().fg(t.text_primary),
+    )));
+    frame.render_widget(header, chunks[0]);
+
+    let mut lines: Vec<Line> = at_risk
+        .iter()
+        .take(listed)
+        .map(|loss| {
+            Line::from(Span::styled(
+                format!("  {}", loss),
+                Style::default().fg(t.red),
+            ))
+        })
+        .collect();
+    if more > 0 {
+        lines.push(Line::from(Span::styled(
+            format!("  and {} more", more),
+            Style::default()
+                .fg(t.text_muted)
+                .add_modifier(Modifier::ITALIC),
+        )));
+    }
+    frame.render_widget(Paragraph::new(lines), chunks[1]);
+
+    let input_display = format!("[{}█]", app.input_buffer);
+    let label_input = Paragraph::new(Line::from(vec![
+        Span::styled(
+            format!("Type {} to confirm: ", expected),
+            Style::default().fg(t.text_primary),
+        ),
+        Span::styled(input_display, Style::default().fg(t.amber)),
+    ]));
+    frame.render_widget(label_input, chunks[3]);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" delete  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" back"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[5]);
+}
@@ -0,0 +1,85 @@
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::theme::centered_rect;
+use crate::app::App;
+use crate::types::AppState;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let AppState::UpstreamSelect { branches, selected } = &app.state else {
+        return;
+    };
+    let selected = *selected;
+
+    let area = centered_rect(50, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Set Upstream ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.cyan));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1), // Branch and current upstream
+        Constraint::Length(1), // Spacing
+        Constraint::Min(1),    // Remote branch list
+        Constraint::Length(1), // Help
+    ])
+    .split(inner);
+
+    if let Some(wt) = app.selected_worktree() {
+        let current = wt
+            .branch
+            .as_ref()
+            .and_then(|branch| app.branch_upstreams.get(branch));
+        let info = Paragraph::new(Line::from(vec![
+            Span::styled("Branch: ", Style::default().fg(t.text_muted)),
+            Span::styled(wt.branch_display(), Style::default().fg(t.amber)),
+            Span::styled("  upstream: ", Style::default().fg(t.text_muted)),
+            Span::styled(
+                current.map_or("(none)", String::as_str),
+                Style::default().fg(t.text_primary),
+            ),
+        ]));
+        frame.render_widget(info, chunks[0]);
+    }
+
+    let items: Vec<ListItem> = branches
+        .iter()
+        .enumerate()
+        .map(|(i, branch)| {
+            let style = if i == selected {
+                Style::default()
+                    .fg(t.selection_bg)
+                    .bg(t.cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(t.text_primary)
+            };
+            ListItem::new(Line::from(Span::styled(format!("  {}", branch), style)))
+        })
+        .collect();
+
+    let mut list_state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(List::new(items), chunks[2], &mut list_state);
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("j/k", Style::default().fg(t.cyan)),
+        Span::raw(" navigate  "),
+        Span::styled("Enter", Style::default().fg(t.cyan)),
+        Span::raw(" set upstream  "),
+        Span::styled("Esc", Style::default().fg(t.cyan)),
+        Span::raw(" cancel"),
+    ]))
+    .style(Style::default().fg(t.text_muted));
+    frame.render_widget(help, chunks[3]);
+}
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::worktree_prune::paths_refer_to_same_location;
+use crate::{git, types};
+
+/// Whether `owt clean` (and the TUI `C` action) removes a worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CleanDecision {
+    Merged,
+    Kept(String),
+}
+
+struct CleanContext<'a> {
+    current_path: Option<&'a Path>,
+    default_branch: Option<&'a str>,
+    default_tip: Option<&'a str>,
+    merged: &'a HashSet<String>,
+}
+
+/// Classify every worktree against the default branch. Worktrees whose
+/// branch `git branch --merged` lists are removable, except branches still
+/// at the default branch tip, which have no work of their own yet.
+pub(crate) fn merged_worktrees(
+    repo_path: &Path,
+    worktrees: &[types::Worktree],
+    current_path: Option<&Path>,
+) -> Result<Vec<(types::Worktree, CleanDecision)>> {
+    let default_branch = git::get_default_branch(repo_path)?;
+    let merged = git::merged_branches(repo_path, &format!("refs/heads/{}", default_branch))?;
+    let default_tip = git::resolve_commit(repo_path, &format!("refs/heads/{}", default_branch));
+    let context = CleanContext {
+        current_path,
+        default_branch: Some(&default_branch),
+        default_tip: default_tip.as_deref(),
+        merged: &merged,
+    };
+
+    Ok(worktrees
+        .iter()
+        .map(|worktree| {
+            let tip = worktree.branch.as_ref().and_then(|branch| {
+                git::resolve_commit(repo_path, &format!("refs/heads/{}", branch))
+            });
+            let decision = clean_decision(worktree, tip.as_deref(), &context);
+            (worktree.clone(), decision)
+        })
+        .collect())
+}
+
+fn clean_decision(
+    worktree: &types::Worktree,
+    tip: Option<&str>,
+    context: &CleanContext<'_>,
+) -> CleanDecision {
+    if worktree.is_bare {
+        return CleanDecision::Kept("bare".to_string());
+    }
+    let Some(branch) = worktree.branch.as_deref() else {
+        return CleanDecision::Kept("detached".to_string());
+    };
+    if context.default_branch == Some(branch) {
+        return CleanDecision::Kept("head".to_string());
+    }
+    if context
+        .current_path
+        .is_some_and(|path| paths_refer_to_same_location(path, &worktree.path))
+    {
+        return CleanDecision::Kept("current".to_string());
+    }
+    if !context.merged.contains(branch) {
+        return CleanDecision::Kept("unmerged".to_string());
+    }
+    if tip.is_some() && tip == context.default_tip {
+        return CleanDecision::Kept("no-commits".to_string());
+    }
+    if worktree.locked {
+        return CleanDecision::Kept("locked".to_string());
+    }
+    if worktree.status != types::WorktreeStatus::Clean {
+        return CleanDecision::Kept(format!("status-{}", worktree.status.label()));
+    }
+    CleanDecision::Merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn clean_test_worktree(branch: &str, status: types::WorktreeStatus) -> types::Worktree {
+        types::Worktree {
+            path: PathBuf::from(format!("/worktrees/{}", branch)),
+            branch: Some(branch.to_string()),
+            is_bare: false,
+            status,
+            last_commit_time: None,
+            ahead_behind: None,
+            github_pr_status: None,
+            locked: false,
+        }
+    }
+
+    #[test]
+    fn clean_decision_removes_only_clean_merged_branches_with_their_own_commits() {
+        let merged: HashSet<String> = ["main", "feature/done", "feature/fresh", "feature/dirty"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let current = PathBuf::from("/worktrees/feature/current");
+        let context = CleanContext {
+            current_path: Some(&current),
+            default_branch: Some("main"),
+            default_tip: Some("aaa"),
+            merged: &merged,
+        };
+        let decide = |branch: &str, status, tip| {
+            clean_decision(&clean_test_worktree(branch, status), Some(tip), &context)
+        };
+        let kept = |reason: &str| CleanDecision::Kept(reason.to_string());
+
+        use types::WorktreeStatus::{Clean, Unstaged};
+        assert_eq!(decide("feature/done", Clean, "bbb"), CleanDecision::Merged);
+        assert_eq!(decide("main", Clean, "aaa"), kept("head"));
+        assert_eq!(decide("feature/fresh", Clean, "aaa"), kept("no-commits"));
+        assert_eq!(decide("feature/open", Clean, "ccc"), kept("unmerged"));
+        assert_eq!(
+            decide("feature/dirty", Unstaged, "ddd"),
+            kept("status-unstaged")
+        );
+
+        let mut locked = clean_test_worktree("feature/done", Clean);
+        locked.locked = true;
+        assert_eq!(
+            clean_decision(&locked, Some("bbb"), &context),
+            kept("locked")
+        );
+        let mut in_use = clean_test_worktree("feature/done", Clean);
+        in_use.path = current.clone();
+        assert_eq!(
+            clean_decision(&in_use, Some("bbb"), &context),
+            kept("current")
+        );
+    }
+}
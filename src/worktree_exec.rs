@@ -0,0 +1,246 @@
+use anyhow::{Context, Result};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+use crate::types::{Worktree, WorktreeStatus};
+
+/// Which worktrees `owt exec` runs in
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ExecFilter {
+    pub dirty_only: bool,
+    pub branch_glob: Option<String>,
+}
+
+impl ExecFilter {
+    /// Non-bare worktrees that pass the filter. With a branch glob, detached
+    /// worktrees never match.
+    pub fn select<'a>(&self, worktrees: &'a [Worktree]) -> Vec<&'a Worktree> {
+        worktrees
+            .iter()
+            .filter(|wt| !wt.is_bare)
+            .filter(|wt| !self.dirty_only || wt.status != WorktreeStatus::Clean)
+            .filter(|wt| match self.branch_glob.as_deref() {
+                Some(pattern) => wt
+                    .branch
+                    .as_deref()
+                    .is_some_and(|branch| glob_match(pattern, branch)),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+/// Shell-style match of the whole `text`: `*` matches any run of characters,
+/// including `/`, and `?` matches exactly one.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Last `*` seen and the text position it currently stands in for
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Run `command` (program and arguments, no shell) in `worktree` with its
+/// stdio attached to ours. `OWT_BRANCH` and `OWT_WORKTREE_PATH` name the
+/// worktree, like the hook variables.
+pub(crate) fn run_in(worktree: &Worktree, command: &[String]) -> Result<ExitStatus> {
+    process(worktree, command)?
+        .status()
+        .with_context(|| format!("Failed to run {}", command[0]))
+}
+
+/// Like `run_in`, but collects stdout and stderr instead of passing them
+/// through, so runs in parallel do not interleave.
+pub(crate) fn run_captured(worktree: &Worktree, command: &[String]) -> Result<Output> {
+    process(worktree, command)?
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to run {}", command[0]))
+}
+
+/// Run `command` in every worktree with at most `jobs` at a time. `report`
+/// gets each worktree's index and result as soon as it finishes, on the
+/// calling thread, so it can print without locking.
+pub(crate) fn run_parallel(
+    worktrees: &[&Worktree],
+    command: &[String],
+    jobs: usize,
+    mut report: impl FnMut(usize, Result<Output>),
+) {
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, worktrees.len().max(1)) {
+            let tx = tx.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(worktree) = worktrees.get(index) else {
+                    break;
+                };
+                if tx.send((index, run_captured(worktree, command))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+        for (index, result) in rx {
+            report(index, result);
+        }
+    });
+}
+
+/// Worker count when none is given: one per CPU.
+pub(crate) fn default_jobs() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+fn process(worktree: &Worktree, command: &[String]) -> Result<Command> {
+    let (program, args) = command
+        .split_first()
+        .context("No command given; pass it after --")?;
+    let mut process = Command::new(program);
+    process
+        .args(args)
+        .current_dir(&worktree.path)
+        .env("OWT_BRANCH", worktree.branch.as_deref().unwrap_or_default())
+        .env("OWT_WORKTREE_PATH", &worktree.path);
+    Ok(process)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn worktree(name: &str, branch: Option<&str>, status: WorktreeStatus) -> Worktree {
+        Worktree {
+            path: PathBuf::from(format!("/repo/{}", name)),
+            branch: branch.map(str::to_string),
+            is_bare: false,
+            status,
+            last_commit_time: None,
+            ahead_behind: None,
+            github_pr_status: None,
+            locked: false,
+        }
+    }
+
+    #[test]
+    fn glob_matches_whole_branch_names() {
+        assert!(glob_match("feature/*", "feature/login"));
+        assert!(glob_match("feature/*", "feature/auth/v2"));
+        assert!(glob_match("*/login", "feature/login"));
+        assert!(glob_match("fix-?", "fix-1"));
+        assert!(glob_match("*a*b*", "xxaxxbxx"));
+        assert!(glob_match("main", "main"));
+        assert!(!glob_match("main", "main2"));
+        assert!(!glob_match("feature/*", "hotfix/login"));
+        assert!(!glob_match("fix-?", "fix-10"));
+        assert!(!glob_match("*a*b", "xxaxxbxx"));
+    }
+
+    #[test]
+    fn parallel_run_reports_every_worktree_once_with_its_own_output() {
+        let base = std::env::temp_dir().join(format!("owt-exec-{}", std::process::id()));
+        let worktrees: Vec<Worktree> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| {
+                let mut wt = worktree(name, Some(name), WorktreeStatus::Clean);
+                wt.path = base.join(name);
+                std::fs::create_dir_all(&wt.path).unwrap();
+                wt
+            })
+            .collect();
+        let targets: Vec<&Worktree> = worktrees.iter().collect();
+        let command = [
+            "sh",
+            "-c",
+            "echo \"$OWT_BRANCH\"; test \"$OWT_BRANCH\" != b",
+        ]
+        .map(String::from);
+
+        let mut seen = Vec::new();
+        run_parallel(&targets, &command, 2, |index, result| {
+            let output = result.unwrap();
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                format!("{}\n", targets[index].display_name())
+            );
+            assert_eq!(output.status.success(), index != 1);
+            seen.push(index);
+        });
+        seen.sort();
+        assert_eq!(seen, [0, 1, 2]);
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn filter_skips_bare_clean_and_unmatched_worktrees() {
+        let mut bare = worktree(".bare", None, WorktreeStatus::Clean);
+        bare.is_bare = true;
+        let worktrees = vec![
+            bare,
+            worktree("main", Some("main"), WorktreeStatus::Clean),
+            worktree("login", Some("feature/login"), WorktreeStatus::Unstaged),
+            worktree("api", Some("feature/api"), WorktreeStatus::Clean),
+            worktree("snapshot", None, WorktreeStatus::Staged),
+        ];
+        let names = |filter: ExecFilter| -> Vec<String> {
+            filter
+                .select(&worktrees)
+                .iter()
+                .map(|wt| wt.display_name())
+                .collect()
+        };
+
+        assert_eq!(
+            names(ExecFilter::default()),
+            ["main", "login", "api", "snapshot"]
+        );
+        assert_eq!(
+            names(ExecFilter {
+                dirty_only: true,
+                branch_glob: None,
+            }),
+            ["login", "snapshot"]
+        );
+        assert_eq!(
+            names(ExecFilter {
+                dirty_only: false,
+                branch_glob: Some("feature/*".to_string()),
+            }),
+            ["login", "api"]
+        );
+        assert_eq!(
+            names(ExecFilter {
+                dirty_only: true,
+                branch_glob: Some("feature/*".to_string()),
+            }),
+            ["login"]
+        );
+    }
+}
@@ -243,7 +243,8 @@ fn current_worktree_path(worktrees: &[types::Worktree], launch_path: &Path) -> O
         .map(|worktree| worktree.path.clone())
 }
 
-fn paths_refer_to_same_location(left: &Path, right: &Path) -> bool {
+/// Whether two paths name the same directory, through symlinks and `..`
+pub(crate) fn paths_refer_to_same_location(left: &Path, right: &Path) -> bool {
     if left == right {
         return true;
     }
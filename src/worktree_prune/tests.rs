@@ -9,6 +9,7 @@ fn prune_test_worktree(branch: Option<&str>, status: types::WorktreeStatus) -> t
         last_commit_time: None,
         ahead_behind: None,
         github_pr_status: None,
+        locked: false,
     }
 }
 
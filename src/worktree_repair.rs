@@ -0,0 +1,77 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git;
+
+/// Worktrees to hand to `git worktree repair`: every listed worktree that is
+/// still where git expects it, plus any directory next to the repository
+/// (the bare layout's project root) whose `.git` file names a worktree. The
+/// second set finds worktrees moved on disk, and passing both lets git fix
+/// the links in both directions when the repository itself moved too.
+pub(crate) fn candidates(repo_path: &Path, project_root_path: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = git::list_worktrees(repo_path)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|worktree| !worktree.is_bare && worktree.path.join(".git").is_file())
+        .map(|worktree| worktree.path)
+        .collect();
+
+    if let Ok(entries) = fs::read_dir(project_root_path) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if gitdir_of(&path).is_some() && !paths.iter().any(|known| same_path(known, &path)) {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    paths
+}
+
+/// Candidates whose `.git` file and the repository's administrative
+/// `gitdir` file no longer point at each other
+pub(crate) fn broken_links(repo_path: &Path, project_root_path: &Path) -> Vec<PathBuf> {
+    candidates(repo_path, project_root_path)
+        .into_iter()
+        .filter(|path| !link_is_intact(path))
+        .collect()
+}
+
+/// Run `git worktree repair` over every candidate. Returns git's report,
+/// one line per link it rewrote.
+pub(crate) fn repair(repo_path: &Path, project_root_path: &Path) -> Result<Vec<String>> {
+    let paths = candidates(repo_path, project_root_path);
+    let output = git::repair_worktrees(repo_path, &paths)?;
+    Ok(output.lines().map(str::to_string).collect())
+}
+
+fn link_is_intact(worktree_path: &Path) -> bool {
+    let Some(admin_dir) = gitdir_of(worktree_path) else {
+        return false;
+    };
+    let Ok(back) = fs::read_to_string(admin_dir.join("gitdir")) else {
+        return false;
+    };
+    same_path(Path::new(back.trim()), &worktree_path.join(".git"))
+}
+
+/// Administrative directory named by `<path>/.git` when it is a file
+pub(crate) fn gitdir_of(path: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(path.join(".git")).ok()?;
+    let gitdir = Path::new(content.trim().strip_prefix("gitdir:")?.trim());
+    Some(if gitdir.is_absolute() {
+        gitdir.to_path_buf()
+    } else {
+        path.join(gitdir)
+    })
+}
+
+fn same_path(left: &Path, right: &Path) -> bool {
+    if left == right {
+        return true;
+    }
+    match (left.canonicalize(), right.canonicalize()) {
+        (Ok(left), Ok(right)) => left == right,
+        _ => false,
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Open `worktree_path` in a new tab of the zellij session owt runs in,
+/// named `name` (the branch): `zellij action new-tab --cwd <path> --name <name>`.
+pub fn open_worktree_tab(worktree_path: &Path, name: &str) -> Result<()> {
+    if std::env::var_os("ZELLIJ").is_none() {
+        anyhow::bail!("not running inside a zellij session");
+    }
+    let args = new_tab_args(worktree_path, name);
+    let output = Command::new("zellij")
+        .args(&args)
+        .output()
+        .with_context(|| format!("failed to run zellij {}", args.join(" ")))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "zellij {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn new_tab_args(worktree_path: &Path, name: &str) -> Vec<String> {
+    let name = name.replace(['\t', '\n', '\r'], " ").trim().to_string();
+    vec![
+        "action".to_string(),
+        "new-tab".to_string(),
+        "--cwd".to_string(),
+        worktree_path.display().to_string(),
+        "--name".to_string(),
+        if name.is_empty() {
+            "worktree".to_string()
+        } else {
+            name
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_tab_args_name_the_tab_after_the_branch() {
+        assert_eq!(
+            new_tab_args(Path::new("/repo/login"), "feature/login"),
+            [
+                "action",
+                "new-tab",
+                "--cwd",
+                "/repo/login",
+                "--name",
+                "feature/login"
+            ]
+        );
+        assert_eq!(new_tab_args(Path::new("/repo/x"), "\n")[5], "worktree");
+    }
+}
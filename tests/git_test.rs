@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Helper to create a temporary directory with unique ID
@@ -31,7 +31,7 @@ fn git_cmd() -> Command {
 }
 
 /// Create a bare repo with initial commit
-fn create_test_bare_repo(path: &PathBuf) {
+fn create_test_bare_repo(path: &Path) {
     // Create a temp regular repo first
     let temp = path.parent().unwrap().join("temp_init");
     fs::create_dir_all(&temp).unwrap();